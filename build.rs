@@ -1,11 +1,18 @@
+#[cfg(feature = "renderer")]
 use std::env;
+#[cfg(feature = "renderer")]
 use std::fs::{self, File};
+#[cfg(feature = "renderer")]
 use std::io::Write;
+#[cfg(feature = "renderer")]
 use std::ops::Deref;
+#[cfg(feature = "renderer")]
 use std::path::{Path, PathBuf};
 
+#[cfg(feature = "renderer")]
 use shaderc;
 
+#[cfg(feature = "renderer")]
 macro_rules! warn {
     ($msg:expr) => ({
         std::println!(concat!("cargo:warning=Build script warning: ", $msg))
@@ -16,6 +23,14 @@ macro_rules! warn {
 }
 
 fn main() {
+    // Shaders are only needed by the renderer, headless builds skip
+    // them along with the shader compiler
+    #[cfg(feature = "renderer")]
+    compile_shaders();
+}
+
+#[cfg(feature = "renderer")]
+fn compile_shaders() {
     // Tell cargo to only rerun this script if it detects changes in `src/shaders`
     println!("cargo:rerun-if-changed=src/shaders");
 
@@ -42,6 +57,7 @@ fn main() {
     compile_shader_directory(&mut compiler, &compile_options, &src_dir, &dst_dir);
 }
 
+#[cfg(feature = "renderer")]
 fn compile_shader_directory(
     compiler: &mut shaderc::Compiler,
     compile_options: &shaderc::CompileOptions,
@@ -116,6 +132,7 @@ fn compile_shader_directory(
     }
 }
 
+#[cfg(feature = "renderer")]
 fn compile_shader_source(
     compiler: &mut shaderc::Compiler,
     compile_options: &shaderc::CompileOptions,
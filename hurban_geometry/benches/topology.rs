@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nalgebra::{Point3, Rotation3, Vector3};
+
+use hurban_geometry::mesh::{primitive, tools, topology, Mesh, NormalStrategy};
+
+/// A sphere with roughly half a million faces and two poles of high
+/// valency, which used to make the face to face topology slow.
+fn large_sphere() -> Mesh {
+    primitive::create_uv_sphere(
+        Point3::origin(),
+        Rotation3::identity(),
+        Vector3::repeat(1.0),
+        500,
+        500,
+        NormalStrategy::Sharp,
+    )
+}
+
+fn bench_topology(c: &mut Criterion) {
+    let mesh = large_sphere();
+    let e2f = topology::compute_edge_to_face_topology(&mesh);
+    let f2f = topology::compute_face_to_face_topology(&mesh, &e2f);
+
+    let mut group = c.benchmark_group("topology");
+    group.sample_size(10);
+    group.bench_function("compute_edge_to_face_topology", |b| {
+        b.iter(|| topology::compute_edge_to_face_topology(black_box(&mesh)))
+    });
+    group.bench_function("compute_face_to_face_topology", |b| {
+        b.iter(|| topology::compute_face_to_face_topology(black_box(&mesh), black_box(&e2f)))
+    });
+    group.bench_function("synchronize_mesh_winding", |b| {
+        b.iter(|| tools::synchronize_mesh_winding(black_box(&mesh), black_box(&f2f)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_topology);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Hash maps and sets with a fixed hasher.
+//!
+//! The standard collections seed their hasher randomly for each
+//! process, so iterating them visits the items in a different order on
+//! each run. Mesh operations often walk their hash maps, e.g. to chain
+//! edges into loops, so the kernel uses these instead and produces the
+//! same meshes for the same inputs on every run.
+//!
+//! The hasher is only stable for a given Rust release, which the
+//! toolchain file pins.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap as StdHashMap, HashSet as StdHashSet};
+use std::hash::BuildHasherDefault;
+
+/// Builds the unseeded `DefaultHasher`, which hashes the same value to
+/// the same hash in every process.
+pub type StableState = BuildHasherDefault<DefaultHasher>;
+
+pub type HashMap<K, V> = StdHashMap<K, V, StableState>;
+
+pub type HashSet<T> = StdHashSet<T, StableState>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_set_iterates_in_same_order_for_same_insertions() {
+        let set_a: HashSet<u32> = (0..1000).collect();
+        let set_b: HashSet<u32> = (0..1000).collect();
+
+        let items_a: Vec<u32> = set_a.into_iter().collect();
+        let items_b: Vec<u32> = set_b.into_iter().collect();
+
+        assert_eq!(items_a, items_b);
+    }
+}
@@ -0,0 +1,148 @@
+use nalgebra::Point3;
+
+/// A polyline through its vertices, in order.
+///
+/// The last vertex of a closed polyline is connected back to its first
+/// vertex, so the first vertex is not repeated at the end.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Polyline {
+    vertices: Vec<Point3<f32>>,
+    closed: bool,
+}
+
+impl Polyline {
+    /// Creates a polyline through the `vertices`. Polylines with less
+    /// than 3 vertices can not enclose anything, so they are always
+    /// open.
+    pub fn new(vertices: Vec<Point3<f32>>, closed: bool) -> Self {
+        let closed = closed && vertices.len() >= 3;
+        Self { vertices, closed }
+    }
+
+    pub fn vertices(&self) -> &[Point3<f32>] {
+        &self.vertices
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the line segments between the consecutive vertices,
+    /// including the segment closing a closed polyline.
+    pub fn segments_iter<'a>(&'a self) -> impl Iterator<Item = (Point3<f32>, Point3<f32>)> + 'a {
+        let closing_segment = match (self.closed, self.vertices.first(), self.vertices.last()) {
+            (true, Some(first), Some(last)) => Some((*last, *first)),
+            _ => None,
+        };
+
+        self.vertices
+            .windows(2)
+            .map(|segment| (segment[0], segment[1]))
+            .chain(closing_segment)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.segments_iter()
+            .map(|(start, end)| nalgebra::distance(&start, &end))
+            .sum()
+    }
+}
+
+/// A curve made of one or more polylines.
+///
+/// Just like a mesh can consist of several disconnected parts, a curve
+/// can consist of several disconnected polylines, e.g. all the border
+/// loops of a mesh.
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Curve {
+    polylines: Vec<Polyline>,
+}
+
+impl Curve {
+    /// Creates a curve from the polylines. Polylines without any
+    /// segments are left out.
+    pub fn new(polylines: Vec<Polyline>) -> Self {
+        let polylines = polylines
+            .into_iter()
+            .filter(|polyline| polyline.vertices.len() >= 2)
+            .collect();
+
+        Self { polylines }
+    }
+
+    pub fn polylines(&self) -> &[Polyline] {
+        &self.polylines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.polylines.is_empty()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.polylines
+            .iter()
+            .map(|polyline| polyline.vertices.len())
+            .sum()
+    }
+
+    /// Returns the line segments of all the polylines.
+    pub fn segments_iter<'a>(&'a self) -> impl Iterator<Item = (Point3<f32>, Point3<f32>)> + 'a {
+        self.polylines.iter().flat_map(Polyline::segments_iter)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.polylines.iter().map(Polyline::length).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Point3<f32>> {
+        vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn test_polyline_segments_iter_closes_closed_polylines() {
+        let open = Polyline::new(square(), false);
+        let closed = Polyline::new(square(), true);
+
+        assert_eq!(open.segments_iter().count(), 3);
+        assert_eq!(closed.segments_iter().count(), 4);
+        assert_eq!(
+            closed.segments_iter().last(),
+            Some((Point3::new(0.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0))),
+        );
+        assert!(approx::relative_eq!(open.length(), 3.0));
+        assert!(approx::relative_eq!(closed.length(), 4.0));
+    }
+
+    #[test]
+    fn test_polyline_new_opens_polylines_with_less_than_3_vertices() {
+        let polyline = Polyline::new(square()[0..2].to_vec(), true);
+
+        assert!(!polyline.is_closed());
+        assert_eq!(polyline.segments_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_curve_new_leaves_out_polylines_without_segments() {
+        let curve = Curve::new(vec![
+            Polyline::new(square(), true),
+            Polyline::new(vec![Point3::new(2.0, 0.0, 0.0)], false),
+            Polyline::new(Vec::new(), false),
+            Polyline::new(square()[1..3].to_vec(), false),
+        ]);
+
+        assert_eq!(curve.polylines().len(), 2);
+        assert_eq!(curve.vertex_count(), 6);
+        assert_eq!(curve.segments_iter().count(), 5);
+        assert!(approx::relative_eq!(curve.length(), 5.0));
+    }
+}
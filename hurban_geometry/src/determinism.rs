@@ -0,0 +1,28 @@
+//! Deterministic evaluation of the mesh operations.
+//!
+//! The kernel iterates its hash maps in a fixed order (see
+//! `collections`) and the parallel operations combine their partial
+//! results independently of the order the threads finish in. The
+//! remaining variable is the thread pool, which is sized by the number
+//! of cores of the machine. Deterministic mode runs the parallel
+//! operations on a fixed number of threads instead, so that the same
+//! inputs produce bit-identical meshes across runs and machines, at
+//! the cost of speed.
+//!
+//! The kernel is built without fast-math optimizations and doesn't
+//! depend on the CPU features of the machine, so floating point
+//! arithmetic needs no special handling.
+
+/// How many threads run the parallel operations in deterministic mode.
+pub const DETERMINISTIC_THREAD_COUNT: usize = 1;
+
+/// Runs all subsequent parallel operations on a fixed number of
+/// threads.
+///
+/// Fails if the global thread pool was already initialized, e.g. if a
+/// parallel operation already ran, so this must be called at startup.
+pub fn enable_deterministic_mode() -> Result<(), rayon::ThreadPoolBuildError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(DETERMINISTIC_THREAD_COUNT)
+        .build_global()
+}
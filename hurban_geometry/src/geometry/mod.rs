@@ -0,0 +1,397 @@
+use std::f32;
+
+use nalgebra::{Point2, Point3, Vector3};
+
+pub mod predicates;
+
+/// Computes a (normalized) normal vector for a triangle.
+pub fn compute_triangle_normal(
+    p1: &Point3<f32>,
+    p2: &Point3<f32>,
+    p3: &Point3<f32>,
+) -> Vector3<f32> {
+    let u = p2 - p1;
+    let v = p3 - p1;
+
+    Vector3::cross(&u, &v).normalize()
+}
+
+/// Computes barycentric coordinates of point P in triangle A, B,
+/// C. Returns `None` for degenerate triangles.
+pub fn compute_barycentric_coords(
+    a: Point2<f32>,
+    b: Point2<f32>,
+    c: Point2<f32>,
+    p: Point2<f32>,
+) -> Option<Point3<f32>> {
+    let ab = b - a;
+    let ac = c - a;
+    let pa = a - p;
+    let xs = Vector3::new(ac.x, ab.x, pa.x);
+    let ys = Vector3::new(ac.y, ab.y, pa.y);
+    let ortho = xs.cross(&ys);
+
+    if approx::relative_eq!(f32::abs(ortho.z), 0.0) {
+        None
+    } else {
+        Some(Point3::new(
+            1.0 - (ortho.x + ortho.y) / ortho.z,
+            ortho.y / ortho.z,
+            ortho.x / ortho.z,
+        ))
+    }
+}
+
+/// Converts barycentric coordinates into cartesian.
+pub fn barycentric_to_cartesian(
+    barycentric_coords: &Point3<f32>,
+    a: &Point3<f32>,
+    b: &Point3<f32>,
+    c: &Point3<f32>,
+) -> Point3<f32> {
+    Point3::from(
+        barycentric_coords.x * a.coords
+            + barycentric_coords.y * b.coords
+            + barycentric_coords.z * c.coords,
+    )
+}
+
+/// Finds the point on triangle A, B, C closest to point P and returns
+/// its barycentric coordinates within the triangle. The cartesian
+/// position can be obtained with `barycentric_to_cartesian`.
+///
+/// Real-Time Collision Detection, Christer Ericson, 5.1.5
+pub fn compute_closest_point_on_triangle_barycentric_coords(
+    p: &Point3<f32>,
+    a: &Point3<f32>,
+    b: &Point3<f32>,
+    c: &Point3<f32>,
+) -> Point3<f32> {
+    let ab = b - a;
+    let ac = c - a;
+
+    // Check if P in vertex region outside A
+    let ap = p - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return Point3::new(1.0, 0.0, 0.0);
+    }
+
+    // Check if P in vertex region outside B
+    let bp = p - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return Point3::new(0.0, 1.0, 0.0);
+    }
+
+    // Check if P in edge region of AB
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let ab_t = d1 / (d1 - d3);
+        return Point3::new(1.0 - ab_t, ab_t, 0.0);
+    }
+
+    // Check if P in vertex region outside C
+    let cp = p - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return Point3::new(0.0, 0.0, 1.0);
+    }
+
+    // Check if P in edge region of AC
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let ac_t = d2 / (d2 - d6);
+        return Point3::new(1.0 - ac_t, 0.0, ac_t);
+    }
+
+    // Check if P in edge region of BC
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let bc_t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return Point3::new(0.0, 1.0 - bc_t, bc_t);
+    }
+
+    // FIXME: @Correctness Degenerate (collinear) triangles can fall
+    // through all the region tests above. Snap to the closest vertex
+    // for them instead of dividing by zero.
+    let area = va + vb + vc;
+    if area <= 0.0 {
+        let da = ap.norm_squared();
+        let db = bp.norm_squared();
+        let dc = cp.norm_squared();
+        return if da <= db && da <= dc {
+            Point3::new(1.0, 0.0, 0.0)
+        } else if db <= dc {
+            Point3::new(0.0, 1.0, 0.0)
+        } else {
+            Point3::new(0.0, 0.0, 1.0)
+        };
+    }
+
+    // P inside face region
+    let denom = 1.0 / area;
+    let coord_b = vb * denom;
+    let coord_c = vc * denom;
+
+    Point3::new(1.0 - coord_b - coord_c, coord_b, coord_c)
+}
+
+/// Checks if all three points lay on the same line.
+///
+/// http://www.ambrsoft.com/TrigoCalc/Line3D/LineColinear.htm
+pub fn are_points_collinear(v0: &Point3<f32>, v1: &Point3<f32>, v2: &Point3<f32>) -> bool {
+    if approx::relative_eq!(v0, v1) || approx::relative_eq!(v0, v2) {
+        return true;
+    }
+    let n1 = v1 - v0;
+    let n2 = v2 - v0;
+    let cross = n1.cross(&n2);
+    approx::relative_eq!(cross, Vector3::zeros())
+}
+
+/// Computes the distance along the ray from its origin to its
+/// intersection with triangle V0, V1, V2, regardless of the triangle
+/// winding. Returns `None` if the ray misses the triangle, the
+/// triangle is behind the ray origin or the triangle is degenerate.
+///
+/// The distance is in the units of the direction vector, so it is
+/// the world space distance only for normalized directions.
+///
+/// https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+pub fn compute_ray_triangle_intersection(
+    ray_origin: &Point3<f32>,
+    ray_direction: &Vector3<f32>,
+    v0: &Point3<f32>,
+    v1: &Point3<f32>,
+    v2: &Point3<f32>,
+) -> Option<f32> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+
+    let direction_cross_edge2 = ray_direction.cross(&edge2);
+    let determinant = edge1.dot(&direction_cross_edge2);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let inverse_determinant = 1.0 / determinant;
+    let origin_from_v0 = ray_origin - v0;
+
+    let barycentric_u = origin_from_v0.dot(&direction_cross_edge2) * inverse_determinant;
+    if barycentric_u < 0.0 || barycentric_u > 1.0 {
+        return None;
+    }
+
+    let origin_cross_edge1 = origin_from_v0.cross(&edge1);
+    let barycentric_v = ray_direction.dot(&origin_cross_edge1) * inverse_determinant;
+    if barycentric_v < 0.0 || barycentric_u + barycentric_v > 1.0 {
+        return None;
+    }
+
+    let distance = edge2.dot(&origin_cross_edge1) * inverse_determinant;
+    if distance > 0.0 {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_triangle_normal_returns_z_vector_for_horizontal_triangle() {
+        let normal_correct = Vector3::new(0.0, 0.0, 1.0);
+
+        let normal_computed = compute_triangle_normal(
+            &Point3::new(0.0, 1.0, 0.0),
+            &Point3::new(-0.866025, -0.5, 0.0),
+            &Point3::new(0.866025, -0.5, 0.0),
+        );
+
+        assert_eq!(normal_correct, normal_computed);
+    }
+
+    #[test]
+    fn test_compute_triangle_normal_returns_x_vector_for_vertical_triangle() {
+        let normal_correct = Vector3::new(1.0, 0.0, 0.0);
+
+        let normal_computed = compute_triangle_normal(
+            &Point3::new(0.0, 1.0, 0.0),
+            &Point3::new(0.0, -0.5, 0.866025),
+            &Point3::new(0.0, -0.5, -0.866025),
+        );
+
+        assert_eq!(normal_correct, normal_computed);
+    }
+
+    #[test]
+    fn test_compute_triangle_normal_returns_vector_for_arbitrary_triangle() {
+        let normal_correct = Vector3::new(0.62270945, -0.614937, 0.48382375);
+
+        let normal_computed = compute_triangle_normal(
+            &Point3::new(0.268023, 0.8302, 0.392469),
+            &Point3::new(-0.870844, -0.462665, 0.215034),
+            &Point3::new(0.334798, -0.197734, -0.999972),
+        );
+
+        assert_eq!(normal_correct, normal_computed);
+    }
+
+    #[test]
+    fn test_compute_barycentric_coords_for_point_inside() {
+        let triangle_points = (
+            Point2::new(0.0, 1.0),
+            Point2::new(-0.866025, -0.5),
+            Point2::new(0.866025, -0.5),
+        );
+
+        let test_point = Point2::new(0.0, 0.0);
+        let barycentric_computed = compute_barycentric_coords(
+            triangle_points.0,
+            triangle_points.1,
+            triangle_points.2,
+            test_point,
+        )
+        .expect("Failed to compute barycentric coords");
+
+        let barycentric_correct = Point3::new(0.333333, 0.333333, 0.333333);
+
+        assert!(barycentric_computed
+            .coords
+            .relative_eq(&barycentric_correct.coords, 0.001, 0.001));
+    }
+
+    #[test]
+    fn test_compute_barycentric_coords_for_point_outside() {
+        let triangle_points = (
+            Point2::new(0.0, 1.0),
+            Point2::new(-0.866025, -0.5),
+            Point2::new(0.866025, -0.5),
+        );
+
+        let test_point = Point2::new(0.0, 2.0);
+        let barycentric_computed = compute_barycentric_coords(
+            triangle_points.0,
+            triangle_points.1,
+            triangle_points.2,
+            test_point,
+        )
+        .expect("Failed to compute barycentric coords");
+
+        let barycentric_correct = Point3::new(1.6666667, -0.33333334, -0.33333334);
+
+        assert!(barycentric_computed
+            .coords
+            .relative_eq(&barycentric_correct.coords, 0.001, 0.001));
+    }
+
+    #[test]
+    fn test_compute_closest_point_on_triangle_barycentric_coords_for_point_above_face() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 1.0, 0.0);
+
+        let barycentric = compute_closest_point_on_triangle_barycentric_coords(
+            &Point3::new(0.25, 0.25, 1.0),
+            &a,
+            &b,
+            &c,
+        );
+        let closest = barycentric_to_cartesian(&barycentric, &a, &b, &c);
+
+        assert!(closest
+            .coords
+            .relative_eq(&Point3::new(0.25, 0.25, 0.0).coords, 0.0001, 0.0001));
+    }
+
+    #[test]
+    fn test_compute_closest_point_on_triangle_barycentric_coords_for_point_near_vertex() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 1.0, 0.0);
+
+        let barycentric = compute_closest_point_on_triangle_barycentric_coords(
+            &Point3::new(2.0, -1.0, 0.5),
+            &a,
+            &b,
+            &c,
+        );
+
+        assert_eq!(barycentric, Point3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_compute_closest_point_on_triangle_barycentric_coords_for_point_near_edge() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 1.0, 0.0);
+
+        let barycentric = compute_closest_point_on_triangle_barycentric_coords(
+            &Point3::new(1.0, 1.0, 0.0),
+            &a,
+            &b,
+            &c,
+        );
+        let closest = barycentric_to_cartesian(&barycentric, &a, &b, &c);
+
+        assert!(closest
+            .coords
+            .relative_eq(&Point3::new(0.5, 0.5, 0.0).coords, 0.0001, 0.0001));
+    }
+
+    #[test]
+    fn test_compute_ray_triangle_intersection_hits_both_sides() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 1.0, 0.0);
+
+        let from_above = compute_ray_triangle_intersection(
+            &Point3::new(0.25, 0.25, 2.0),
+            &Vector3::new(0.0, 0.0, -1.0),
+            &a,
+            &b,
+            &c,
+        );
+        let from_below = compute_ray_triangle_intersection(
+            &Point3::new(0.25, 0.25, -3.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+            &a,
+            &b,
+            &c,
+        );
+
+        assert_eq!(from_above, Some(2.0));
+        assert_eq!(from_below, Some(3.0));
+    }
+
+    #[test]
+    fn test_compute_ray_triangle_intersection_misses() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 1.0, 0.0);
+
+        let outside = compute_ray_triangle_intersection(
+            &Point3::new(0.75, 0.75, 2.0),
+            &Vector3::new(0.0, 0.0, -1.0),
+            &a,
+            &b,
+            &c,
+        );
+        let behind = compute_ray_triangle_intersection(
+            &Point3::new(0.25, 0.25, 2.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+            &a,
+            &b,
+            &c,
+        );
+
+        assert_eq!(outside, None);
+        assert_eq!(behind, None);
+    }
+}
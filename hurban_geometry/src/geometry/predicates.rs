@@ -0,0 +1,337 @@
+use nalgebra::{Point2, Point3};
+
+// Robust geometric predicates after Jonathan Richard Shewchuk,
+// Adaptive Precision Floating-Point Arithmetic and Fast Robust
+// Geometric Predicates.
+//
+// https://www.cs.cmu.edu/~quake/robust.html
+//
+// Each predicate first evaluates its determinant in plain floating
+// point. If the result is too close to zero for its sign to be
+// trusted, the determinant is evaluated exactly with floating point
+// expansions: sums of non-overlapping components ordered by increasing
+// magnitude, whose sign is the sign of the largest component.
+//
+// The inputs are `f32`, but the arithmetic is done in `f64`, so the
+// error bounds are those for `f64`.
+
+/// Half of the machine epsilon, the largest relative error of a
+/// rounded `f64` operation.
+const EPSILON: f64 = std::f64::EPSILON / 2.0;
+const ORIENT2D_ERROR_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const ORIENT3D_ERROR_BOUND: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const INCIRCLE_ERROR_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// Returns a positive value if the points `a`, `b` and `c` are in
+/// counterclockwise order, a negative value if they are in clockwise
+/// order and zero if they are collinear. The value approximates twice
+/// the signed area of the triangle, its sign is always exact.
+pub fn orient2d(a: Point2<f32>, b: Point2<f32>, c: Point2<f32>) -> f64 {
+    let (ax, ay) = (f64::from(a.x), f64::from(a.y));
+    let (bx, by) = (f64::from(b.x), f64::from(b.y));
+    let (cx, cy) = (f64::from(c.x), f64::from(c.y));
+
+    let det_left = (ax - cx) * (by - cy);
+    let det_right = (ay - cy) * (bx - cx);
+    let det = det_left - det_right;
+
+    let permanent = det_left.abs() + det_right.abs();
+    if det.abs() > ORIENT2D_ERROR_BOUND * permanent {
+        return det;
+    }
+
+    let acx = difference(a.x, c.x);
+    let bcy = difference(b.y, c.y);
+    let acy = difference(a.y, c.y);
+    let bcx = difference(b.x, c.x);
+
+    estimate(&expansion_diff(
+        &expansion_product(&acx, &bcy),
+        &expansion_product(&acy, &bcx),
+    ))
+}
+
+/// Returns a positive value if the point `d` lies below the plane
+/// through the points `a`, `b` and `c`, a negative value if it lies
+/// above it and zero if the points are coplanar. "Above" is the side
+/// from which `a`, `b` and `c` appear in counterclockwise order,
+/// i.e. the side the normal `(b - a) x (c - a)` points to. The value
+/// approximates six times the signed volume of the tetrahedron, its
+/// sign is always exact.
+pub fn orient3d(a: &Point3<f32>, b: &Point3<f32>, c: &Point3<f32>, d: &Point3<f32>) -> f64 {
+    let (adx, ady, adz) = relative_to(a, d);
+    let (bdx, bdy, bdz) = relative_to(b, d);
+    let (cdx, cdy, cdz) = relative_to(c, d);
+
+    let bdx_cdy = bdx * cdy;
+    let cdx_bdy = cdx * bdy;
+    let cdx_ady = cdx * ady;
+    let adx_cdy = adx * cdy;
+    let adx_bdy = adx * bdy;
+    let bdx_ady = bdx * ady;
+
+    let det = adz * (bdx_cdy - cdx_bdy) + bdz * (cdx_ady - adx_cdy) + cdz * (adx_bdy - bdx_ady);
+
+    let permanent = (bdx_cdy.abs() + cdx_bdy.abs()) * adz.abs()
+        + (cdx_ady.abs() + adx_cdy.abs()) * bdz.abs()
+        + (adx_bdy.abs() + bdx_ady.abs()) * cdz.abs();
+    if det.abs() > ORIENT3D_ERROR_BOUND * permanent {
+        return det;
+    }
+
+    let adx = difference(a.x, d.x);
+    let ady = difference(a.y, d.y);
+    let adz = difference(a.z, d.z);
+    let bdx = difference(b.x, d.x);
+    let bdy = difference(b.y, d.y);
+    let bdz = difference(b.z, d.z);
+    let cdx = difference(c.x, d.x);
+    let cdy = difference(c.y, d.y);
+    let cdz = difference(c.z, d.z);
+
+    let bc = expansion_diff(
+        &expansion_product(&bdx, &cdy),
+        &expansion_product(&cdx, &bdy),
+    );
+    let ca = expansion_diff(
+        &expansion_product(&cdx, &ady),
+        &expansion_product(&adx, &cdy),
+    );
+    let ab = expansion_diff(
+        &expansion_product(&adx, &bdy),
+        &expansion_product(&bdx, &ady),
+    );
+
+    estimate(&expansion_sum(
+        &expansion_sum(&expansion_product(&adz, &bc), &expansion_product(&bdz, &ca)),
+        &expansion_product(&cdz, &ab),
+    ))
+}
+
+/// Returns a positive value if the point `d` lies inside the circle
+/// through the points `a`, `b` and `c`, a negative value if it lies
+/// outside and zero if the points are cocircular. The points `a`, `b`
+/// and `c` must be in counterclockwise order, otherwise the sign is
+/// reversed. The sign of the value is always exact.
+pub fn incircle(a: Point2<f32>, b: Point2<f32>, c: Point2<f32>, d: Point2<f32>) -> f64 {
+    let (dx, dy) = (f64::from(d.x), f64::from(d.y));
+    let (adx, ady) = (f64::from(a.x) - dx, f64::from(a.y) - dy);
+    let (bdx, bdy) = (f64::from(b.x) - dx, f64::from(b.y) - dy);
+    let (cdx, cdy) = (f64::from(c.x) - dx, f64::from(c.y) - dy);
+
+    let bdx_cdy = bdx * cdy;
+    let cdx_bdy = cdx * bdy;
+    let a_lift = adx * adx + ady * ady;
+    let cdx_ady = cdx * ady;
+    let adx_cdy = adx * cdy;
+    let b_lift = bdx * bdx + bdy * bdy;
+    let adx_bdy = adx * bdy;
+    let bdx_ady = bdx * ady;
+    let c_lift = cdx * cdx + cdy * cdy;
+
+    let det =
+        a_lift * (bdx_cdy - cdx_bdy) + b_lift * (cdx_ady - adx_cdy) + c_lift * (adx_bdy - bdx_ady);
+
+    let permanent = (bdx_cdy.abs() + cdx_bdy.abs()) * a_lift
+        + (cdx_ady.abs() + adx_cdy.abs()) * b_lift
+        + (adx_bdy.abs() + bdx_ady.abs()) * c_lift;
+    if det.abs() > INCIRCLE_ERROR_BOUND * permanent {
+        return det;
+    }
+
+    let adx = difference(a.x, d.x);
+    let ady = difference(a.y, d.y);
+    let bdx = difference(b.x, d.x);
+    let bdy = difference(b.y, d.y);
+    let cdx = difference(c.x, d.x);
+    let cdy = difference(c.y, d.y);
+
+    let lift =
+        |x: &[f64], y: &[f64]| expansion_sum(&expansion_product(x, x), &expansion_product(y, y));
+    let bc = expansion_diff(
+        &expansion_product(&bdx, &cdy),
+        &expansion_product(&cdx, &bdy),
+    );
+    let ca = expansion_diff(
+        &expansion_product(&cdx, &ady),
+        &expansion_product(&adx, &cdy),
+    );
+    let ab = expansion_diff(
+        &expansion_product(&adx, &bdy),
+        &expansion_product(&bdx, &ady),
+    );
+
+    estimate(&expansion_sum(
+        &expansion_sum(
+            &expansion_product(&lift(&adx, &ady), &bc),
+            &expansion_product(&lift(&bdx, &bdy), &ca),
+        ),
+        &expansion_product(&lift(&cdx, &cdy), &ab),
+    ))
+}
+
+fn relative_to(point: &Point3<f32>, origin: &Point3<f32>) -> (f64, f64, f64) {
+    (
+        f64::from(point.x) - f64::from(origin.x),
+        f64::from(point.y) - f64::from(origin.y),
+        f64::from(point.z) - f64::from(origin.z),
+    )
+}
+
+/// Returns `a + b` rounded and the rounding error, so that their sum is
+/// exactly `a + b`.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    let b_roundoff = b - b_virtual;
+    let a_roundoff = a - a_virtual;
+
+    (sum, a_roundoff + b_roundoff)
+}
+
+/// Returns `a * b` rounded and the rounding error, so that their sum is
+/// exactly `a * b`.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    // The fused multiply-add rounds only once, so it gets the exact
+    // error of the rounded product
+    (product, a.mul_add(b, -product))
+}
+
+/// Returns the exact difference `a - b` as an expansion.
+fn difference(a: f32, b: f32) -> Vec<f64> {
+    grow_expansion(&[f64::from(a)], -f64::from(b))
+}
+
+/// Adds a number to an expansion. Zero components are left out, but
+/// the result has at least one component.
+fn grow_expansion(expansion: &[f64], b: f64) -> Vec<f64> {
+    let mut grown = Vec::with_capacity(expansion.len() + 1);
+    let mut carry = b;
+    for component in expansion {
+        let (sum, error) = two_sum(carry, *component);
+        carry = sum;
+        if error != 0.0 {
+            grown.push(error);
+        }
+    }
+    if carry != 0.0 || grown.is_empty() {
+        grown.push(carry);
+    }
+
+    grown
+}
+
+fn expansion_sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |sum, component| {
+        grow_expansion(&sum, *component)
+    })
+}
+
+fn expansion_diff(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |sum, component| {
+        grow_expansion(&sum, -component)
+    })
+}
+
+/// Multiplies an expansion by a number. Zero components are left out,
+/// but the result has at least one component.
+fn scale_expansion(expansion: &[f64], b: f64) -> Vec<f64> {
+    let mut scaled = Vec::with_capacity(2 * expansion.len());
+    let (mut carry, error) = two_product(expansion[0], b);
+    if error != 0.0 {
+        scaled.push(error);
+    }
+    for component in &expansion[1..] {
+        let (product, product_error) = two_product(*component, b);
+        let (sum, sum_error) = two_sum(carry, product_error);
+        if sum_error != 0.0 {
+            scaled.push(sum_error);
+        }
+        let (new_carry, error) = two_sum(product, sum);
+        carry = new_carry;
+        if error != 0.0 {
+            scaled.push(error);
+        }
+    }
+    if carry != 0.0 || scaled.is_empty() {
+        scaled.push(carry);
+    }
+
+    scaled
+}
+
+fn expansion_product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(vec![0.0], |sum, component| {
+        expansion_sum(&sum, &scale_expansion(e, *component))
+    })
+}
+
+/// Approximates the value of an expansion. The largest component
+/// dominates the sum, so the approximation has the exact sign.
+fn estimate(expansion: &[f64]) -> f64 {
+    expansion.iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orient2d_is_consistent_for_nearly_collinear_points() {
+        let b = Point2::new(12.0, 12.0);
+        let c = Point2::new(24.0, 24.0);
+        let ulp = std::f32::EPSILON / 2.0;
+
+        for i in 0..32 {
+            for j in 0..32 {
+                let a = Point2::new(0.5 + i as f32 * ulp, 0.5 + j as f32 * ulp);
+                let orientation = orient2d(a, b, c);
+
+                // The sign must not change by rotating or swapping
+                // the points, which plain floating point evaluation
+                // does not guarantee
+                assert_eq!(
+                    orient2d(b, c, a).partial_cmp(&0.0),
+                    orientation.partial_cmp(&0.0),
+                );
+                assert_eq!(
+                    orient2d(b, a, c).partial_cmp(&0.0),
+                    (-orientation).partial_cmp(&0.0),
+                );
+                // Points with equal coordinates are on the line y = x
+                assert_eq!(orientation == 0.0, i == j);
+                assert_eq!(orientation > 0.0, j > i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orient3d_finds_coplanar_points_and_sides() {
+        // All the points satisfy x + y + z = 1 exactly
+        let a = Point3::new(0.25, 0.25, 0.5);
+        let b = Point3::new(0.5, 0.25, 0.25);
+        let c = Point3::new(0.25, 0.5, 0.25);
+        let d = Point3::new(0.125, 0.375, 0.5);
+
+        assert_eq!(orient3d(&a, &b, &c, &d), 0.0);
+
+        let normal = (b - a).cross(&(c - a));
+        let above = d + normal;
+        let below = d - normal;
+        assert!(orient3d(&a, &b, &c, &above) < 0.0);
+        assert!(orient3d(&a, &b, &c, &below) > 0.0);
+    }
+
+    #[test]
+    fn test_incircle_finds_inside_outside_and_cocircular_points() {
+        let a = Point2::new(0.0, 0.0);
+        let b = Point2::new(1.0, 0.0);
+        let c = Point2::new(0.0, 1.0);
+
+        assert_eq!(incircle(a, b, c, Point2::new(1.0, 1.0)), 0.0);
+        assert!(incircle(a, b, c, Point2::new(0.5, 0.5)) > 0.0);
+        assert!(incircle(a, b, c, Point2::new(2.0, 2.0)) < 0.0);
+    }
+}
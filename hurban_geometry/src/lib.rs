@@ -0,0 +1,30 @@
+//! The geometry kernel of H.U.R.B.A.N. Selector.
+//!
+//! Contains the mesh representation and the operations on it, without
+//! any dependency on the renderer or the UI of the application, so
+//! that it can be reused by other projects:
+//!
+//! - `mesh` defines the indexed `Mesh` and its submodules implement
+//!   analysis, topology, smoothing, voxelization and other tools
+//!   operating on it,
+//! - `geometry` has the triangle and ray primitives used by the mesh
+//!   operations, with `predicates` for robust orientation tests,
+//! - `bounding_box`, `plane` and `locator` are the bounding volumes,
+//!   planes and named points used to position the meshes,
+//! - `curve` and `point_cloud` are the polylines and points taken
+//!   from the meshes, e.g. their border loops or vertices, or used to
+//!   build them,
+//! - `convert` has the checked numeric casts used throughout,
+//! - `collections` and `determinism` make the operations produce the
+//!   same meshes across runs and machines.
+
+pub mod bounding_box;
+pub mod collections;
+pub mod convert;
+pub mod curve;
+pub mod determinism;
+pub mod geometry;
+pub mod locator;
+pub mod mesh;
+pub mod plane;
+pub mod point_cloud;
@@ -0,0 +1,166 @@
+use std::f32;
+
+use nalgebra::{Isometry3, Matrix4, Point3, Translation3, UnitQuaternion, Vector3};
+
+use crate::plane::Plane;
+
+/// A named point with an orientation, marking a place in the scene,
+/// e.g. where meshes are moved to, mirrored by or measured from.
+///
+/// The orientation is given by the X, Y and Z axes of the locator,
+/// which are the world axes rotated by its rotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locator {
+    name: String,
+    origin: Point3<f32>,
+    rotation: UnitQuaternion<f32>,
+}
+
+impl Locator {
+    pub fn new<S: Into<String>>(
+        name: S,
+        origin: Point3<f32>,
+        rotation: UnitQuaternion<f32>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            origin,
+            rotation,
+        }
+    }
+
+    /// A locator at the origin, with its Z axis along the
+    /// `normal`. The rotation of its X and Y axes around the normal is
+    /// the shortest one from the world axes.
+    ///
+    /// # Panics
+    /// Panics if the normal is a zero vector.
+    pub fn from_origin_and_normal<S: Into<String>>(
+        name: S,
+        origin: Point3<f32>,
+        normal: &Vector3<f32>,
+    ) -> Self {
+        assert_ne!(
+            *normal,
+            Vector3::zeros(),
+            "Can't create a locator oriented by a zero normal vector",
+        );
+
+        // Opposite vectors have no shortest rotation, any half turn
+        // around a perpendicular axis does
+        let rotation =
+            UnitQuaternion::rotation_between(&Vector3::z(), normal).unwrap_or_else(|| {
+                UnitQuaternion::from_axis_angle(&Vector3::x_axis(), f32::consts::PI)
+            });
+
+        Self::new(name, origin, rotation)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn origin(&self) -> Point3<f32> {
+        self.origin
+    }
+
+    pub fn rotation(&self) -> UnitQuaternion<f32> {
+        self.rotation
+    }
+
+    pub fn x_axis(&self) -> Vector3<f32> {
+        self.rotation * Vector3::x()
+    }
+
+    pub fn y_axis(&self) -> Vector3<f32> {
+        self.rotation * Vector3::y()
+    }
+
+    pub fn z_axis(&self) -> Vector3<f32> {
+        self.rotation * Vector3::z()
+    }
+
+    /// The plane spanned by the X and Y axes of the locator, with the
+    /// Z axis as its normal.
+    pub fn to_plane(&self) -> Plane {
+        Plane::new(&self.origin, &self.x_axis(), &self.y_axis())
+    }
+
+    /// The rigid transformation from the world space to the space of
+    /// the locator, i.e. the transformation placing the world origin
+    /// and axes at the locator.
+    pub fn to_isometry(&self) -> Isometry3<f32> {
+        Isometry3::from_parts(Translation3::from(self.origin.coords), self.rotation)
+    }
+
+    /// The rigid transformation moving and rotating the locator onto
+    /// the `target`, e.g. to place meshes marked by this locator at
+    /// the target.
+    pub fn transformation_to(&self, target: &Locator) -> Matrix4<f32> {
+        (target.to_isometry() * self.to_isometry().inverse()).to_homogeneous()
+    }
+
+    pub fn distance_to(&self, other: &Locator) -> f32 {
+        nalgebra::distance(&self.origin, &other.origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locator_from_origin_and_normal_aligns_z_axis() {
+        let normal = Vector3::new(1.0, 1.0, 0.0);
+        let locator = Locator::from_origin_and_normal("Door", Point3::new(1.0, 2.0, 3.0), &normal);
+
+        assert!(approx::relative_eq!(locator.z_axis(), normal.normalize()));
+        assert!(approx::relative_eq!(
+            locator.to_plane().normal(),
+            normal.normalize(),
+            epsilon = 0.0001,
+        ));
+
+        let flipped = Locator::from_origin_and_normal(
+            "Floor",
+            Point3::origin(),
+            &Vector3::new(0.0, 0.0, -2.0),
+        );
+        assert!(approx::relative_eq!(
+            flipped.z_axis(),
+            Vector3::new(0.0, 0.0, -1.0),
+            epsilon = 0.0001,
+        ));
+    }
+
+    #[test]
+    fn test_locator_transformation_to_moves_locator_onto_target() {
+        let source = Locator::new(
+            "Source",
+            Point3::new(1.0, 0.0, 0.0),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, 0.5),
+        );
+        let target = Locator::new(
+            "Target",
+            Point3::new(-2.0, 4.0, 1.0),
+            UnitQuaternion::from_euler_angles(0.3, 0.0, -1.0),
+        );
+
+        let transformation = source.transformation_to(&target);
+
+        assert!(approx::relative_eq!(
+            transformation.transform_point(&source.origin()),
+            target.origin(),
+            epsilon = 0.0001,
+        ));
+        assert!(approx::relative_eq!(
+            transformation.transform_vector(&source.x_axis()),
+            target.x_axis(),
+            epsilon = 0.0001,
+        ));
+        assert!(approx::relative_eq!(
+            source.distance_to(&target),
+            26f32.sqrt()
+        ));
+    }
+}
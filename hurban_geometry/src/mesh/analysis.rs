@@ -0,0 +1,2717 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::f32;
+
+use arrayvec::ArrayVec;
+use nalgebra as na;
+use nalgebra::{Isometry3, Matrix3, Point3, Rotation3, Translation3, UnitQuaternion, Vector3};
+
+use crate::bounding_box::BoundingBox;
+use crate::collections::{HashMap, HashSet};
+use crate::convert::{cast_i32, cast_usize};
+use crate::plane::Plane;
+
+use super::bvh::Bvh;
+use super::{Face, Mesh, OrientedEdge, UnorientedEdge};
+
+// FIXME: Make more generic: take &[Point] or Iterator<Item=&Point>
+pub fn find_closest_point(position: &Point3<f32>, mesh: &Mesh) -> Option<Point3<f32>> {
+    let vertices = mesh.vertices();
+    if vertices.is_empty() {
+        return None;
+    }
+
+    let mut closest = vertices[0];
+    let mut closest_distance_squared = na::distance_squared(position, &closest);
+    for point in &vertices[1..] {
+        let distance_squared = na::distance_squared(position, &point);
+        if distance_squared < closest_distance_squared {
+            closest = *point;
+            closest_distance_squared = distance_squared;
+        }
+    }
+
+    Some(closest)
+}
+
+/// Computes the distance of each vertex of the `mesh` from the surface
+/// of the `reference` mesh, e.g. to measure how far smoothing or
+/// decimation moved the surface away from the original.
+///
+/// If `signed`, vertices behind the closest face of the reference
+/// mesh, i.e. against its winding, have negative distances.
+///
+/// # Panics
+/// Panics if the reference mesh has no faces.
+pub fn vertex_distances_to_mesh(mesh: &Mesh, reference: &Mesh, signed: bool) -> Vec<f32> {
+    let reference_bvh = Bvh::from_mesh(reference);
+    let reference_vertices = reference.vertices();
+
+    mesh.vertices()
+        .iter()
+        .map(|vertex| {
+            let closest = reference_bvh
+                .closest_point(vertex)
+                .expect("Reference mesh must have faces");
+
+            if signed {
+                let (v1, v2, v3) = closest.triangle_face.vertices;
+                let a = reference_vertices[cast_usize(v1)];
+                let b = reference_vertices[cast_usize(v2)];
+                let c = reference_vertices[cast_usize(v3)];
+                // Not normalized, so that degenerate faces don't
+                // produce NaN, but count as being in front
+                let face_normal = (b - a).cross(&(c - a));
+
+                if (vertex - closest.point).dot(&face_normal) < 0.0 {
+                    return -closest.distance;
+                }
+            }
+
+            closest.distance
+        })
+        .collect()
+}
+
+/// How a face of a mesh changed since an earlier version of the mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceChange {
+    /// A face of the earlier version is at the same place.
+    Unchanged,
+    /// The earlier version has a face with the same vertex indices,
+    /// but elsewhere, e.g. after smoothing or transforming the mesh.
+    Moved,
+    /// The earlier version has no such face.
+    Added,
+}
+
+/// Faces changed between two versions of a mesh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaceDiff {
+    /// The change of each face of the current version.
+    pub current_faces: Vec<FaceChange>,
+    /// Indices of the faces of the previous version, which are neither
+    /// at the same place, nor moved in the current version.
+    pub removed_faces: Vec<usize>,
+}
+
+/// Compares the faces of the `current` version of a mesh with the
+/// `previous` one, e.g. to show what a parameter edit changed.
+///
+/// Faces are at the same place if each of their vertices is within
+/// `tolerance` from a vertex of the other face, regardless of their
+/// winding. These are looked up in a spatial hash of face centroids,
+/// with cells of the `tolerance` size. Faces not found there are
+/// matched by their vertex indices, so that the faces of meshes with
+/// unchanged topology are reported as moved, not as removed and added.
+pub fn diff_faces(previous: &Mesh, current: &Mesh, tolerance: f32) -> FaceDiff {
+    let cell_size = tolerance.max(f32::EPSILON);
+    let cell = |centroid: &Point3<f32>| {
+        (
+            (centroid.x / cell_size).floor() as i64,
+            (centroid.y / cell_size).floor() as i64,
+            (centroid.z / cell_size).floor() as i64,
+        )
+    };
+
+    let previous_positions: Vec<ArrayVec<[Point3<f32>; 4]>> = previous
+        .faces()
+        .iter()
+        .map(|face| face_positions(previous, face))
+        .collect();
+
+    let mut previous_by_cell: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::default();
+    let mut previous_by_vertices: HashMap<ArrayVec<[u32; 4]>, usize> = HashMap::default();
+    for (face_index, (face, positions)) in
+        previous.faces().iter().zip(&previous_positions).enumerate()
+    {
+        previous_by_cell
+            .entry(cell(&centroid(positions)))
+            .or_insert_with(Vec::new)
+            .push(face_index);
+        previous_by_vertices
+            .entry(sorted_face_vertices(face))
+            .or_insert(face_index);
+    }
+
+    let mut previous_matched = vec![false; previous_positions.len()];
+    let current_faces = current
+        .faces()
+        .iter()
+        .map(|face| {
+            let positions = face_positions(current, face);
+            let (x, y, z) = cell(&centroid(&positions));
+
+            // The centroid of a face at the same place can be in a
+            // neighboring cell
+            for neighbor in (x - 1..=x + 1)
+                .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+                .flat_map(|(x, y)| (z - 1..=z + 1).map(move |z| (x, y, z)))
+            {
+                if let Some(face_indices) = previous_by_cell.get(&neighbor) {
+                    let same_place = face_indices.iter().find(|face_index| {
+                        positions.iter().all(|position| {
+                            previous_positions[**face_index]
+                                .iter()
+                                .any(|previous_position| {
+                                    na::distance_squared(position, previous_position)
+                                        <= tolerance * tolerance
+                                })
+                        })
+                    });
+                    if let Some(face_index) = same_place {
+                        previous_matched[*face_index] = true;
+                        return FaceChange::Unchanged;
+                    }
+                }
+            }
+
+            match previous_by_vertices.get(&sorted_face_vertices(face)) {
+                Some(face_index) => {
+                    previous_matched[*face_index] = true;
+                    FaceChange::Moved
+                }
+                None => FaceChange::Added,
+            }
+        })
+        .collect();
+
+    let removed_faces = previous_matched
+        .iter()
+        .enumerate()
+        .filter(|(_, matched)| !**matched)
+        .map(|(face_index, _)| face_index)
+        .collect();
+
+    FaceDiff {
+        current_faces,
+        removed_faces,
+    }
+}
+
+fn face_positions(mesh: &Mesh, face: &Face) -> ArrayVec<[Point3<f32>; 4]> {
+    let vertices = mesh.vertices();
+    face.vertices()
+        .iter()
+        .map(|vertex_index| vertices[cast_usize(*vertex_index)])
+        .collect()
+}
+
+fn sorted_face_vertices(face: &Face) -> ArrayVec<[u32; 4]> {
+    let mut vertices = face.vertices();
+    vertices.sort_unstable();
+    vertices
+}
+
+fn centroid(positions: &[Point3<f32>]) -> Point3<f32> {
+    let sum = positions
+        .iter()
+        .fold(Vector3::zeros(), |sum, position| sum + position.coords);
+    Point3::from(sum / positions.len() as f32)
+}
+
+/// The edges sharing the same vertex indices.
+/// ascending_edges contains edges oriented from lower index to higher
+/// descending_edges contains edges oriented from higher index to lower
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedEdges {
+    // FIXME: @Optimization this might be a good case for smallvec,
+    // but also: all the edges here share the same two vertex indices,
+    // so do we *really* need to remember them all in vectors?
+    // Wouldn't just remembering: `index_low`, `index_high`,
+    // `edge_count_ascending`, `edge_count_descending` give us the
+    // same information, but with much less overhead?
+    pub ascending_edges: Vec<OrientedEdge>,
+    pub descending_edges: Vec<OrientedEdge>,
+}
+
+pub type EdgeSharingMap = HashMap<UnorientedEdge, SharedEdges>;
+
+// FIXME: Implement edge_sharing also for UnorientedEdges?
+
+/// Computes edge sharing, a structure containing all edges occupying
+/// the same space in the mesh topology.
+///
+/// Edge sharing can be used to compute edge valency - the number of
+/// faces sharing an edge. The valency is 1 for border edges, 2 for
+/// manifold edges, and 3 or more for non-manifold edges.  Ideally our
+/// meshes would only contain manifold edges, but we can also deal
+/// with not watertight meshes (those that contain border edges).
+/// Meshes containing non-manifold edges are usually corrupted and
+/// little useful work can be done on them.
+pub fn edge_sharing<'a, I: IntoIterator<Item = &'a OrientedEdge>>(
+    oriented_edges: I,
+) -> EdgeSharingMap {
+    let mut edge_sharing_map: EdgeSharingMap = HashMap::default();
+    for edge in oriented_edges {
+        let unoriented_edge = UnorientedEdge(*edge);
+        let ascending_edges: Vec<OrientedEdge> = Vec::new();
+        let descending_edges: Vec<OrientedEdge> = Vec::new();
+
+        let shared_edges = edge_sharing_map
+            .entry(unoriented_edge)
+            .or_insert(SharedEdges {
+                ascending_edges,
+                descending_edges,
+            });
+
+        if edge.vertices.0 < edge.vertices.1 {
+            shared_edges.ascending_edges.push(*edge);
+        } else {
+            shared_edges.descending_edges.push(*edge);
+        }
+    }
+
+    edge_sharing_map
+}
+
+/// Finds edges with a certain valency in a mesh edge collection.
+///
+/// An edge valency indicates how many faces share the edge.
+fn find_edges_with_valency<'a>(
+    edge_sharing: &'a EdgeSharingMap,
+    valency: usize,
+) -> impl Iterator<Item = OrientedEdge> + 'a {
+    edge_sharing
+        .iter()
+        .filter(move |(_, similar_edges)| {
+            similar_edges.ascending_edges.len() + similar_edges.descending_edges.len() == valency
+        })
+        .flat_map(|(_, similar_edges)| {
+            similar_edges
+                .ascending_edges
+                .iter()
+                .copied()
+                .chain(similar_edges.descending_edges.iter().copied())
+        })
+}
+
+/// Finds border edges in a mesh edge collection.
+///
+/// An edge is border when its valency is 1.
+pub fn border_edges<'a>(
+    edge_sharing: &'a EdgeSharingMap,
+) -> impl Iterator<Item = OrientedEdge> + 'a {
+    find_edges_with_valency(edge_sharing, 1)
+}
+
+/// Finds manifold (inner) edges in a mesh edge collection.
+///
+/// An edge is manifold when its valency is 2.
+pub fn manifold_edges<'a>(
+    edge_sharing: &'a EdgeSharingMap,
+) -> impl Iterator<Item = OrientedEdge> + 'a {
+    find_edges_with_valency(edge_sharing, 2)
+}
+
+/// Finds non-manifold (erroneous) edges in a mesh edge collection.
+pub fn non_manifold_edges<'a>(
+    edge_sharing: &'a EdgeSharingMap,
+) -> impl Iterator<Item = OrientedEdge> + 'a {
+    edge_sharing
+        .iter()
+        .filter(|(_, edge_count)| {
+            edge_count.ascending_edges.len() + edge_count.descending_edges.len() > 2
+        })
+        .flat_map(|(_, similar_edges)| {
+            similar_edges
+                .ascending_edges
+                .iter()
+                .copied()
+                .chain(similar_edges.descending_edges.iter().copied())
+        })
+}
+
+/// Checks if mesh contains only manifold or border edges.
+pub fn is_mesh_manifold(edge_sharing: &EdgeSharingMap) -> bool {
+    non_manifold_edges(edge_sharing).next().is_none()
+}
+
+/// Finds border vertex indices in a mesh edge collection.
+///
+/// A vertex is border when its edge's valency is 1.
+pub fn border_vertex_indices(edge_sharing: &EdgeSharingMap) -> HashSet<u32> {
+    let mut border_vertices = HashSet::default();
+
+    border_edges(edge_sharing).for_each(|edge| {
+        border_vertices.insert(edge.vertices.0);
+        border_vertices.insert(edge.vertices.1);
+    });
+    border_vertices
+}
+
+/// Finds continuous loops of border edges, starting from a random edge.
+///
+/// The mesh may contain holes or islands, therefore it may have an unknown
+/// number of edge loops. If two edge loops meet at a single vertex, the result
+/// may be unpredictable and erratic.
+pub fn border_edge_loops(edge_sharing: &EdgeSharingMap) -> Vec<Vec<UnorientedEdge>> {
+    let mut border_edges: Vec<_> = border_edges(edge_sharing).map(UnorientedEdge).collect();
+
+    let mut edge_loops: Vec<Vec<UnorientedEdge>> = Vec::new();
+
+    while let Some(edge) = border_edges.pop() {
+        let mut current_loop: Vec<UnorientedEdge> = vec![edge];
+
+        while current_loop.len() < 3
+            || !current_loop[current_loop.len() - 1].shares_vertex(current_loop[0])
+        {
+            for (i, other_edge) in border_edges.iter().enumerate() {
+                if current_loop[current_loop.len() - 1].shares_vertex(*other_edge) {
+                    current_loop.push(border_edges.remove(i));
+                    break;
+                }
+            }
+        }
+
+        edge_loops.push(current_loop);
+    }
+
+    edge_loops
+}
+
+/// Joins edges sharing vertices into continuous chains of vertex
+/// indices, e.g. for displaying border or non-manifold edges as
+/// polylines.
+///
+/// Closed chains (loops) end with the same vertex index they start
+/// with. Unlike `border_edge_loops`, this does not require the edges
+/// to form loops, and where more than two edges meet at a vertex,
+/// the chain continues with one of them and the others start new
+/// chains.
+pub fn edge_chains<I>(edges: I) -> Vec<Vec<u32>>
+where
+    I: IntoIterator<Item = UnorientedEdge>,
+{
+    let mut unvisited_edges: HashSet<UnorientedEdge> = edges.into_iter().collect();
+
+    // Walk the edges in a stable order, so that the chains are the
+    // same for the same mesh
+    let mut sorted_edges: Vec<UnorientedEdge> = unvisited_edges.iter().copied().collect();
+    sorted_edges.sort_by_key(|edge| {
+        let (v1, v2) = edge.0.vertices;
+        (v1.min(v2), v1.max(v2))
+    });
+
+    let mut vertex_to_edges: HashMap<u32, Vec<UnorientedEdge>> = HashMap::default();
+    for edge in &sorted_edges {
+        let (v1, v2) = edge.0.vertices;
+        vertex_to_edges.entry(v1).or_default().push(*edge);
+        vertex_to_edges.entry(v2).or_default().push(*edge);
+    }
+
+    let mut chains = Vec::new();
+    for starting_edge in sorted_edges {
+        if !unvisited_edges.remove(&starting_edge) {
+            continue;
+        }
+
+        let (v1, v2) = starting_edge.0.vertices;
+        let mut chain = VecDeque::new();
+        chain.push_back(v1.min(v2));
+        chain.push_back(v1.max(v2));
+
+        // Extend the chain forward until it closes or ends, then
+        // backward, if it did not close.
+        while let Some(next_vertex) = take_next_chain_vertex(
+            &vertex_to_edges,
+            &mut unvisited_edges,
+            chain[chain.len() - 1],
+        ) {
+            chain.push_back(next_vertex);
+            if next_vertex == chain[0] {
+                break;
+            }
+        }
+
+        if chain[0] != chain[chain.len() - 1] {
+            while let Some(previous_vertex) =
+                take_next_chain_vertex(&vertex_to_edges, &mut unvisited_edges, chain[0])
+            {
+                chain.push_front(previous_vertex);
+            }
+        }
+
+        chains.push(chain.into_iter().collect());
+    }
+
+    chains
+}
+
+/// Marks the first unvisited edge of the vertex as visited and
+/// returns its other vertex.
+fn take_next_chain_vertex(
+    vertex_to_edges: &HashMap<u32, Vec<UnorientedEdge>>,
+    unvisited_edges: &mut HashSet<UnorientedEdge>,
+    vertex: u32,
+) -> Option<u32> {
+    let edge = vertex_to_edges
+        .get(&vertex)?
+        .iter()
+        .find(|edge| unvisited_edges.contains(edge))?;
+    unvisited_edges.remove(edge);
+
+    let (v1, v2) = edge.0.vertices;
+    if v1 == vertex {
+        Some(v2)
+    } else {
+        Some(v1)
+    }
+}
+
+/// Checks if all the face normals point the same way.
+///
+/// In a proper watertight orientable mesh each oriented edge should
+/// have a single counterpart in a reverted oriented edge. In an open
+/// orientable mesh each internal edge has its counterpart in a single
+/// reverted oriented edge and the border edges don't have any
+/// counterpart.
+pub fn is_mesh_orientable(edge_sharing: &EdgeSharingMap) -> bool {
+    edge_sharing.iter().all(|(_, edge_count)| {
+        // Ascending_count and descending_count can never be both 0 at the same
+        // time because there is never a case that the edge doesn't exist in any
+        // direction. Even if this happens, it means that the tested edge is
+        // non-existing and therefore doesn't affect edge winding.
+        edge_count.ascending_edges.len() <= 1 && edge_count.descending_edges.len() <= 1
+    })
+}
+
+/// Checks if mesh is watertight.
+///
+/// The mesh is watertight if there is no border or non-manifold edge,
+/// which means all the edge valencies are 2.
+pub fn is_mesh_watertight(edge_sharing: &EdgeSharingMap) -> bool {
+    edge_sharing.iter().all(|(_, edge_count)| {
+        edge_count.ascending_edges.len() == 1 && edge_count.descending_edges.len() == 1
+    })
+}
+
+/// Computes the mesh genus of a triangulated mesh geometry.
+///
+/// Genus of a mesh is the number of holes in topology / connectivity. The mesh
+/// **must** be triangulated and watertight for this to produce usable results.
+///
+/// The genus (G) is computed as: `V - E + F = 2*(1 - G)`.
+pub fn triangulated_mesh_genus(vertex_count: usize, edge_count: usize, face_count: usize) -> i32 {
+    1 - (cast_i32(vertex_count) - cast_i32(edge_count) + cast_i32(face_count)) / 2
+}
+
+/// Topological characteristics of a mesh geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopologyInfo {
+    /// Number of vertices referenced by faces.
+    pub vertex_count: usize,
+    /// Number of unique (unoriented) edges.
+    pub edge_count: usize,
+    pub face_count: usize,
+    /// The Euler characteristic `V - E + F`.
+    pub euler_characteristic: i32,
+    /// Number of parts of the mesh not connected by vertices.
+    pub component_count: usize,
+    /// Total genus of all the components. Only known for watertight
+    /// orientable meshes.
+    pub genus: Option<i32>,
+}
+
+/// Computes the Euler characteristic, number of connected components
+/// and genus of a mesh.
+///
+/// For watertight orientable meshes `V - E + F = 2 * (C - G)`, where
+/// C is the number of components and G is the total genus. The mesh
+/// should be welded, otherwise faces sharing only vertex positions
+/// are counted as separate components with borders.
+pub fn topology_info(mesh: &Mesh) -> TopologyInfo {
+    let vertex_count = mesh.vertices().len();
+    let mut vertex_used = vec![false; vertex_count];
+    let mut component_roots: Vec<usize> = (0..vertex_count).collect();
+
+    for face in mesh.faces() {
+        let vertices = face.vertices();
+        let first_vertex = cast_usize(vertices[0]);
+        for vertex in vertices.iter().map(|vertex| cast_usize(*vertex)) {
+            vertex_used[vertex] = true;
+            join_components(&mut component_roots, first_vertex, vertex);
+        }
+    }
+
+    let used_vertex_count = vertex_used.iter().filter(|used| **used).count();
+    let component_count = (0..vertex_count)
+        .filter(|vertex| vertex_used[*vertex] && component_roots[*vertex] == *vertex)
+        .count();
+
+    let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+    let edge_sharing = edge_sharing(&oriented_edges);
+    let edge_count = edge_sharing.len();
+    let face_count = mesh.faces().len();
+
+    let euler_characteristic =
+        cast_i32(used_vertex_count) - cast_i32(edge_count) + cast_i32(face_count);
+    let genus = if is_mesh_watertight(&edge_sharing) {
+        Some(cast_i32(component_count) - euler_characteristic / 2)
+    } else {
+        None
+    };
+
+    TopologyInfo {
+        vertex_count: used_vertex_count,
+        edge_count,
+        face_count,
+        euler_characteristic,
+        component_count,
+        genus,
+    }
+}
+
+/// Finds the root of the component containing the vertex in the
+/// union-find forest, compressing the path on the way.
+fn find_component_root(component_roots: &mut [usize], vertex: usize) -> usize {
+    let mut root = vertex;
+    while component_roots[root] != root {
+        root = component_roots[root];
+    }
+
+    let mut current = vertex;
+    while component_roots[current] != root {
+        let next = component_roots[current];
+        component_roots[current] = root;
+        current = next;
+    }
+
+    root
+}
+
+fn join_components(component_roots: &mut [usize], vertex1: usize, vertex2: usize) {
+    let root1 = find_component_root(component_roots, vertex1);
+    let root2 = find_component_root(component_roots, vertex2);
+    if root1 != root2 {
+        component_roots[root2.max(root1)] = root1.min(root2);
+    }
+}
+
+/// Faces of a mesh covering the same three vertices as other faces.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DuplicateFaces {
+    /// Faces repeating another face with the same winding.
+    pub duplicate_face_indices: Vec<usize>,
+    /// Pairs of faces with opposite winding, e.g. the wall shared by
+    /// two closed shells joined together. Such faces are inside the
+    /// joined volume.
+    pub internal_face_indices: Vec<usize>,
+}
+
+impl DuplicateFaces {
+    pub fn is_empty(&self) -> bool {
+        self.duplicate_face_indices.is_empty() && self.internal_face_indices.is_empty()
+    }
+}
+
+/// Finds faces covering the same vertices as other faces. Faces with
+/// opposite winding are paired up as internal faces. Of the remaining
+/// faces with the same winding, all but the first are duplicates.
+///
+/// The mesh should be welded, otherwise faces sharing only vertex
+/// positions are not found.
+pub fn find_duplicate_faces(mesh: &Mesh) -> DuplicateFaces {
+    // Faces are keyed by their vertices rotated to start with the
+    // lowest vertex index, which keeps the winding
+    let mut face_groups: HashMap<ArrayVec<[u32; 4]>, Vec<(bool, usize)>> = HashMap::default();
+    let mut group_keys: Vec<ArrayVec<[u32; 4]>> = Vec::new();
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        let mut rotated = face.vertices();
+        let lowest_position = rotated
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, vertex)| **vertex)
+            .map(|(position, _)| position)
+            .expect("Face must have vertices");
+        rotated.rotate_left(lowest_position);
+
+        let reverted = rotated[1] > rotated[rotated.len() - 1];
+        if reverted {
+            rotated[1..].reverse();
+        }
+        let key = rotated;
+
+        face_groups
+            .entry(key.clone())
+            .or_insert_with(|| {
+                group_keys.push(key);
+                Vec::new()
+            })
+            .push((reverted, face_index));
+    }
+
+    let mut duplicate_faces = DuplicateFaces::default();
+    for key in &group_keys {
+        let group = &face_groups[key];
+        if group.len() < 2 {
+            continue;
+        }
+
+        let (reverted, forward): (Vec<_>, Vec<_>) =
+            group.iter().partition(|(reverted, _)| *reverted);
+        let internal_count = forward.len().min(reverted.len());
+        for (_, face_index) in forward[..internal_count]
+            .iter()
+            .chain(&reverted[..internal_count])
+        {
+            duplicate_faces.internal_face_indices.push(*face_index);
+        }
+
+        let remaining = if forward.len() > internal_count {
+            &forward[internal_count..]
+        } else {
+            &reverted[internal_count..]
+        };
+        for (_, face_index) in remaining.iter().skip(1) {
+            duplicate_faces.duplicate_face_indices.push(*face_index);
+        }
+    }
+
+    duplicate_faces
+}
+
+/// Mass properties of the solid enclosed by a mesh geometry with unit
+/// density.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    /// Enclosed volume, also the mass for unit density.
+    pub volume: f32,
+    pub center_of_mass: Point3<f32>,
+    /// Inertia tensor relative to the center of mass.
+    pub inertia_tensor: Matrix3<f32>,
+}
+
+impl MassProperties {
+    /// Returns the moments of inertia around the principal axes of
+    /// inertia in ascending order.
+    pub fn principal_moments_of_inertia(&self) -> Vector3<f32> {
+        let mut moments: Vec<f32> = self
+            .inertia_tensor
+            .symmetric_eigenvalues()
+            .iter()
+            .copied()
+            .collect();
+        moments.sort_by(|moment, other_moment| {
+            moment
+                .partial_cmp(other_moment)
+                .unwrap_or(cmp::Ordering::Equal)
+        });
+
+        Vector3::from_column_slice(&moments)
+    }
+}
+
+/// Computes the volume, center of mass and inertia tensor of the solid
+/// enclosed by the mesh, assuming unit density.
+///
+/// The volume integrals are converted to integrals over the mesh faces
+/// using the divergence theorem, following David Eberly's Polyhedral
+/// Mass Properties. The mesh therefore has to be closed with faces
+/// consistently wound outwards, otherwise the results are meaningless.
+/// Returns `None` if the mesh encloses no volume, e.g. because it is
+/// inside out.
+pub fn compute_mass_properties(mesh: &Mesh) -> Option<MassProperties> {
+    // Computes the face integral subexpressions for one coordinate
+    fn subexpressions(w0: f64, w1: f64, w2: f64) -> (f64, f64, f64, [f64; 3]) {
+        let temp0 = w0 + w1;
+        let f1 = temp0 + w2;
+        let temp1 = w0 * w0;
+        let temp2 = temp1 + w1 * temp0;
+        let f2 = temp2 + w2 * f1;
+        let f3 = w0 * temp1 + w1 * temp2 + w2 * f2;
+        let g = [
+            f2 + w0 * (f1 + w0),
+            f2 + w1 * (f1 + w1),
+            f2 + w2 * (f1 + w2),
+        ];
+
+        (f1, f2, f3, g)
+    }
+
+    // Integrals of 1, x, y, z, x^2, y^2, z^2, xy, yz, zx
+    let mut integrals = [0.0f64; 10];
+    let vertices = mesh.vertices();
+
+    for triangle_face in mesh.faces().iter().flat_map(Face::to_triangles) {
+        let (v0, v1, v2) = triangle_face.vertices;
+        let p0 = vertices[cast_usize(v0)].coords.map(f64::from);
+        let p1 = vertices[cast_usize(v1)].coords.map(f64::from);
+        let p2 = vertices[cast_usize(v2)].coords.map(f64::from);
+        let normal = (p1 - p0).cross(&(p2 - p0));
+
+        let (f1x, f2x, f3x, gx) = subexpressions(p0.x, p1.x, p2.x);
+        let (_, f2y, f3y, gy) = subexpressions(p0.y, p1.y, p2.y);
+        let (_, f2z, f3z, gz) = subexpressions(p0.z, p1.z, p2.z);
+
+        integrals[0] += normal.x * f1x;
+        integrals[1] += normal.x * f2x;
+        integrals[2] += normal.y * f2y;
+        integrals[3] += normal.z * f2z;
+        integrals[4] += normal.x * f3x;
+        integrals[5] += normal.y * f3y;
+        integrals[6] += normal.z * f3z;
+        integrals[7] += normal.x * (p0.y * gx[0] + p1.y * gx[1] + p2.y * gx[2]);
+        integrals[8] += normal.y * (p0.z * gy[0] + p1.z * gy[1] + p2.z * gy[2]);
+        integrals[9] += normal.z * (p0.x * gz[0] + p1.x * gz[1] + p2.x * gz[2]);
+    }
+
+    let multipliers = [
+        1.0 / 6.0,
+        1.0 / 24.0,
+        1.0 / 24.0,
+        1.0 / 24.0,
+        1.0 / 60.0,
+        1.0 / 60.0,
+        1.0 / 60.0,
+        1.0 / 120.0,
+        1.0 / 120.0,
+        1.0 / 120.0,
+    ];
+    for (integral, multiplier) in integrals.iter_mut().zip(multipliers.iter()) {
+        *integral *= multiplier;
+    }
+
+    let volume = integrals[0];
+    if volume <= 0.0 {
+        return None;
+    }
+
+    let center_x = integrals[1] / volume;
+    let center_y = integrals[2] / volume;
+    let center_z = integrals[3] / volume;
+
+    let inertia_xx =
+        integrals[5] + integrals[6] - volume * (center_y * center_y + center_z * center_z);
+    let inertia_yy =
+        integrals[4] + integrals[6] - volume * (center_z * center_z + center_x * center_x);
+    let inertia_zz =
+        integrals[4] + integrals[5] - volume * (center_x * center_x + center_y * center_y);
+    let inertia_xy = -(integrals[7] - volume * center_x * center_y);
+    let inertia_yz = -(integrals[8] - volume * center_y * center_z);
+    let inertia_xz = -(integrals[9] - volume * center_z * center_x);
+
+    #[rustfmt::skip]
+    let inertia_tensor = Matrix3::new(
+        inertia_xx, inertia_xy, inertia_xz,
+        inertia_xy, inertia_yy, inertia_yz,
+        inertia_xz, inertia_yz, inertia_zz,
+    );
+
+    Some(MassProperties {
+        volume: volume as f32,
+        center_of_mass: Point3::new(center_x as f32, center_y as f32, center_z as f32),
+        inertia_tensor: inertia_tensor.map(|value| value as f32),
+    })
+}
+
+/// Maximum number of vertices matched to their closest counterparts
+/// when looking for symmetry or registering meshes.
+const SOURCE_SAMPLE_COUNT: usize = 1024;
+/// Maximum number of vertices the source vertices are matched to.
+const TARGET_SAMPLE_COUNT: usize = 4096;
+const SYMMETRY_ITERATIONS: usize = 8;
+
+/// Approximate mirror symmetry of a mesh geometry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MirrorSymmetry {
+    pub plane: Plane,
+    /// Root mean square distance of the mirrored vertices to the
+    /// closest original vertices. Zero for perfectly symmetric meshes.
+    pub deviation: f32,
+}
+
+/// Finds the plane the mesh is most symmetric about.
+///
+/// The candidate planes pass through the vertex centroid and are
+/// perpendicular to the principal axes of the vertices or the world
+/// axes. Each candidate is then refined similarly to the Iterative
+/// Closest Point algorithm: the vertices are mirrored, matched to
+/// their closest original vertices and the plane is moved to halve
+/// the matched pairs. The candidate with the smallest deviation wins.
+///
+/// Large meshes are sampled, so the result is approximate. Returns
+/// `None` for meshes with less than 3 vertices.
+pub fn detect_mirror_symmetry(mesh: &Mesh) -> Option<MirrorSymmetry> {
+    let vertices = mesh.vertices();
+    if vertices.len() < 3 {
+        return None;
+    }
+
+    let sources = sample_points(vertices, SOURCE_SAMPLE_COUNT);
+    let targets = sample_points(vertices, TARGET_SAMPLE_COUNT);
+
+    let centroid = compute_centroid(vertices);
+    let principal_axes = compute_principal_axes(vertices).into_inner();
+
+    let candidate_normals = [
+        principal_axes.column(0).into_owned(),
+        principal_axes.column(1).into_owned(),
+        principal_axes.column(2).into_owned(),
+        Vector3::x(),
+        Vector3::y(),
+        Vector3::z(),
+    ];
+
+    candidate_normals
+        .iter()
+        .filter(|normal| normal.norm_squared() > 0.5)
+        .map(|normal| refine_mirror_symmetry(&sources, &targets, Point3::from(centroid), *normal))
+        .min_by(|symmetry, other_symmetry| {
+            symmetry
+                .deviation
+                .partial_cmp(&other_symmetry.deviation)
+                .unwrap_or(cmp::Ordering::Equal)
+        })
+}
+
+fn refine_mirror_symmetry(
+    sources: &[Point3<f32>],
+    targets: &[Point3<f32>],
+    mut origin: Point3<f32>,
+    mut normal: Vector3<f32>,
+) -> MirrorSymmetry {
+    let mirror = |point: &Point3<f32>, origin: &Point3<f32>, normal: &Vector3<f32>| {
+        point - 2.0 * (point - origin).dot(normal) * normal
+    };
+    for _ in 0..SYMMETRY_ITERATIONS {
+        let mut midpoint_sum = Vector3::zeros();
+        let mut direction_sum = Vector3::zeros();
+
+        for source in sources {
+            let target = find_closest_sample(&mirror(source, &origin, &normal), targets);
+            midpoint_sum += (source.coords + target.coords) / 2.0;
+
+            let direction = source - target;
+            if direction.dot(&normal) < 0.0 {
+                direction_sum -= direction;
+            } else {
+                direction_sum += direction;
+            }
+        }
+
+        origin = Point3::from(midpoint_sum / sources.len() as f32);
+        if let Some(refined_normal) = direction_sum.try_normalize(f32::EPSILON) {
+            normal = refined_normal;
+        }
+    }
+
+    let squared_distance_sum: f32 = sources
+        .iter()
+        .map(|source| {
+            let mirrored = mirror(source, &origin, &normal);
+            na::distance_squared(&mirrored, &find_closest_sample(&mirrored, targets))
+        })
+        .sum();
+
+    MirrorSymmetry {
+        plane: Plane::from_origin_and_normal(&origin, &normal),
+        deviation: (squared_distance_sum / sources.len() as f32).sqrt(),
+    }
+}
+
+/// Computes the principal axes of the points, the directions in which
+/// the points vary the most, the second most and the least.
+///
+/// The axes are the columns of the returned rotation, ordered by
+/// descending variance. The rotation therefore transforms from the
+/// space aligned with the principal axes to the world space.
+pub fn compute_principal_axes(points: &[Point3<f32>]) -> Rotation3<f32> {
+    if points.is_empty() {
+        return Rotation3::identity();
+    }
+
+    let centroid = compute_centroid(points);
+    let covariance = points.iter().fold(Matrix3::zeros(), |sum, point| {
+        let offset = point.coords - centroid;
+        sum + offset * offset.transpose()
+    });
+    let eigen = covariance.symmetric_eigen();
+
+    let mut axis_indices = [0, 1, 2];
+    axis_indices.sort_by(|index, other_index| {
+        eigen.eigenvalues[*other_index]
+            .partial_cmp(&eigen.eigenvalues[*index])
+            .unwrap_or(cmp::Ordering::Equal)
+    });
+
+    let major_axis = eigen.eigenvectors.column(axis_indices[0]).normalize();
+    let middle_axis = eigen.eigenvectors.column(axis_indices[1]).normalize();
+    // Computing the minor axis keeps the rotation right-handed
+    let minor_axis = major_axis.cross(&middle_axis);
+
+    Rotation3::from_matrix_unchecked(Matrix3::from_columns(&[
+        major_axis,
+        middle_axis,
+        minor_axis,
+    ]))
+}
+
+/// Box, which is not necessarily aligned with the world axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBoundingBox {
+    pub center: Point3<f32>,
+    /// Rotation from the space aligned with the box to the world space.
+    pub rotation: Rotation3<f32>,
+    pub dimensions: Vector3<f32>,
+}
+
+impl OrientedBoundingBox {
+    pub fn volume(&self) -> f32 {
+        self.dimensions.x * self.dimensions.y * self.dimensions.z
+    }
+}
+
+/// Approximates the oriented bounding box of the points with the least
+/// volume.
+///
+/// The search starts from the boxes aligned with the principal axes and
+/// with the world axes and improves the better of them by rotating it
+/// around its own axes with gradually decreasing angle steps. The result
+/// is therefore a local optimum, which is however exact for boxes and
+/// good enough for most shapes. Large point sets are sampled for the
+/// search. Returns `None` if there are no points.
+pub fn compute_minimal_oriented_bounding_box(
+    points: &[Point3<f32>],
+) -> Option<OrientedBoundingBox> {
+    const ANGLE_STEP_COUNT: i32 = 9;
+    const REFINEMENT_COUNT: usize = 4;
+
+    if points.is_empty() {
+        return None;
+    }
+
+    let samples = sample_points(points, TARGET_SAMPLE_COUNT);
+    let volume = |rotation: &Rotation3<f32>| {
+        compute_oriented_bounding_box(&samples, rotation)
+            .expect("Samples must not be empty")
+            .volume()
+    };
+
+    let principal_axes = compute_principal_axes(points);
+    let mut best_volume = volume(&principal_axes);
+    let mut best_rotation = principal_axes;
+    if volume(&Rotation3::identity()) < best_volume {
+        best_rotation = Rotation3::identity();
+        best_volume = volume(&best_rotation);
+    }
+
+    let mut angle_step = f32::consts::FRAC_PI_4 / ANGLE_STEP_COUNT as f32;
+    for _ in 0..REFINEMENT_COUNT {
+        for axis in &[Vector3::x_axis(), Vector3::y_axis(), Vector3::z_axis()] {
+            let start_rotation = best_rotation;
+            for step in -ANGLE_STEP_COUNT..=ANGLE_STEP_COUNT {
+                let rotation =
+                    start_rotation * Rotation3::from_axis_angle(axis, step as f32 * angle_step);
+                let rotation_volume = volume(&rotation);
+                if rotation_volume < best_volume {
+                    best_rotation = rotation;
+                    best_volume = rotation_volume;
+                }
+            }
+        }
+        angle_step /= ANGLE_STEP_COUNT as f32;
+    }
+
+    compute_oriented_bounding_box(points, &best_rotation)
+}
+
+/// Computes the bounding box of the points in the space rotated by
+/// `rotation`. Returns `None` if there are no points.
+pub fn compute_oriented_bounding_box(
+    points: &[Point3<f32>],
+    rotation: &Rotation3<f32>,
+) -> Option<OrientedBoundingBox> {
+    let inverse_rotation = rotation.inverse();
+    let bounding_box =
+        BoundingBox::from_points(points.iter().map(|point| inverse_rotation * point))?;
+
+    Some(OrientedBoundingBox {
+        center: rotation * bounding_box.center(),
+        rotation: *rotation,
+        dimensions: bounding_box.diagonal(),
+    })
+}
+
+/// Rigid transformation registering one mesh geometry onto another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidRegistration {
+    pub transform: Isometry3<f32>,
+    /// Root mean square distance of the transformed source vertices
+    /// to the target surface.
+    pub deviation: f32,
+}
+
+/// Finds the rigid transformation that moves the source mesh onto
+/// the target mesh using the Iterative Closest Point algorithm.
+///
+/// The meshes are first aligned by their vertex centroids. Each
+/// iteration then matches the transformed source vertices to their
+/// closest points on the target faces, found with a BVH of the
+/// target, and finds the best fitting rotation and translation of the
+/// matched pairs. Iterating stops early once the deviation no longer
+/// improves.
+///
+/// As with any ICP, the result is only a local optimum: the meshes
+/// should be roughly aligned for the registration to succeed. Large
+/// source meshes are sampled. Returns `None` if the source mesh has no
+/// vertices or the target mesh has no faces.
+pub fn register_rigid(source: &Mesh, target: &Mesh, iterations: u32) -> Option<RigidRegistration> {
+    if source.vertices().is_empty() || target.faces().is_empty() {
+        return None;
+    }
+
+    let sources = sample_points(source.vertices(), SOURCE_SAMPLE_COUNT);
+    let target_bvh = Bvh::from_mesh(target);
+    let closest_target_point = |point: &Point3<f32>| {
+        target_bvh
+            .closest_point(point)
+            .expect("Target mesh must have faces")
+            .point
+    };
+
+    let centroid_translation =
+        compute_centroid(target.vertices()) - compute_centroid(source.vertices());
+    let mut transform = Isometry3::translation(
+        centroid_translation.x,
+        centroid_translation.y,
+        centroid_translation.z,
+    );
+    let mut deviation = f32::INFINITY;
+
+    for _ in 0..iterations {
+        let transformed_sources: Vec<Point3<f32>> =
+            sources.iter().map(|source| transform * source).collect();
+        let matched_targets: Vec<Point3<f32>> = transformed_sources
+            .iter()
+            .map(closest_target_point)
+            .collect();
+
+        let squared_distance_sum: f32 = transformed_sources
+            .iter()
+            .zip(&matched_targets)
+            .map(|(source, target)| na::distance_squared(source, target))
+            .sum();
+        let current_deviation = (squared_distance_sum / sources.len() as f32).sqrt();
+        if current_deviation >= deviation - f32::EPSILON {
+            break;
+        }
+        deviation = current_deviation;
+
+        transform = fit_rigid_transform(&transformed_sources, &matched_targets) * transform;
+    }
+
+    let squared_distance_sum: f32 = sources
+        .iter()
+        .map(|source| {
+            let transformed = transform * source;
+            na::distance_squared(&transformed, &closest_target_point(&transformed))
+        })
+        .sum();
+
+    Some(RigidRegistration {
+        transform,
+        deviation: (squared_distance_sum / sources.len() as f32).sqrt(),
+    })
+}
+
+/// Finds the rigid transformation best moving the source points onto
+/// the corresponding target points in the least squares sense
+/// (Kabsch algorithm).
+fn fit_rigid_transform(sources: &[Point3<f32>], targets: &[Point3<f32>]) -> Isometry3<f32> {
+    let source_centroid = compute_centroid(sources);
+    let target_centroid = compute_centroid(targets);
+
+    let covariance = sources
+        .iter()
+        .zip(targets)
+        .fold(Matrix3::zeros(), |sum, (source, target)| {
+            sum + (source.coords - source_centroid) * (target.coords - target_centroid).transpose()
+        });
+
+    let svd = covariance.svd(true, true);
+    let u = svd.u.expect("SVD must compute U");
+    let v = svd.v_t.expect("SVD must compute V^T").transpose();
+
+    // Prevent the rotation from becoming a reflection
+    let reflection_correction = (v * u.transpose()).determinant().signum();
+    let rotation_matrix =
+        v * Matrix3::from_diagonal(&Vector3::new(1.0, 1.0, reflection_correction)) * u.transpose();
+    let rotation =
+        UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(rotation_matrix));
+    let translation = target_centroid - rotation * source_centroid;
+
+    Isometry3::from_parts(Translation3::from(translation), rotation)
+}
+
+fn compute_centroid(points: &[Point3<f32>]) -> Vector3<f32> {
+    points
+        .iter()
+        .fold(Vector3::zeros(), |sum, point| sum + point.coords)
+        / points.len() as f32
+}
+
+/// Picks at most `count` points evenly spread over the slice.
+fn sample_points(points: &[Point3<f32>], count: usize) -> Vec<Point3<f32>> {
+    let step = (points.len() + count - 1) / count;
+    points.iter().step_by(step.max(1)).copied().collect()
+}
+
+fn find_closest_sample(point: &Point3<f32>, samples: &[Point3<f32>]) -> Point3<f32> {
+    samples
+        .iter()
+        .min_by(|sample, other_sample| {
+            na::distance_squared(point, sample)
+                .partial_cmp(&na::distance_squared(point, other_sample))
+                .unwrap_or(cmp::Ordering::Equal)
+        })
+        .copied()
+        .expect("Samples must not be empty")
+}
+
+/// Computes the total area of all mesh faces.
+pub fn surface_area(mesh: &Mesh) -> f32 {
+    let vertices = mesh.vertices();
+
+    mesh.faces()
+        .iter()
+        .flat_map(Face::to_triangles)
+        .map(|f| {
+            let v1 = vertices[cast_usize(f.vertices.0)];
+            let v2 = vertices[cast_usize(f.vertices.1)];
+            let v3 = vertices[cast_usize(f.vertices.2)];
+
+            (v2 - v1).cross(&(v3 - v1)).norm() / 2.0
+        })
+        .sum()
+}
+
+/// Computes the signed volume enclosed by the mesh as a sum of signed
+/// volumes of tetrahedrons formed by each face and the origin. The
+/// mesh **must** be watertight and consistently oriented for this to
+/// produce usable results. The volume is negative for meshes with
+/// faces oriented inwards.
+pub fn signed_volume(mesh: &Mesh) -> f32 {
+    let vertices = mesh.vertices();
+
+    mesh.faces()
+        .iter()
+        .flat_map(Face::to_triangles)
+        .map(|f| {
+            let v1 = vertices[cast_usize(f.vertices.0)].coords;
+            let v2 = vertices[cast_usize(f.vertices.1)].coords;
+            let v3 = vertices[cast_usize(f.vertices.2)].coords;
+
+            v1.dot(&v2.cross(&v3)) / 6.0
+        })
+        .sum()
+}
+
+/// Checks if two meshes are similar.
+///
+/// Two mesh geometries are similar when they are visually similar (see the
+/// definition of `are_visually_similar`), and they have the same number of
+/// vertices. Therefore they are going to be treated the same by all functions
+/// of this software and all their transformations result in similar mesh
+/// geometries.
+pub fn are_similar(mesh1: &Mesh, mesh2: &Mesh) -> bool {
+    mesh1.vertices().len() == mesh2.vertices().len() && are_visually_similar(mesh1, mesh2)
+}
+
+/// Checks if two meshes are visually similar.
+///
+/// Two mesh geometries are visually similar when the position of each vertex in
+/// one mesh geometry matches a position of some vertex in the other mesh
+/// geometry and each face in one mesh geometry refers vertices with the same
+/// position and has corner normals with the same direction, both in the same
+/// circular order, as exactly one face in the other mesh geometry.
+///
+/// The indices (order in which they are stored) of vertices and faces can
+/// differ but as long as the previous conditions are met, the mesh geometries
+/// are similar. It is not necessary that the count of vertices is identical,
+/// because one mesh may reuse (share) vertices in more faces and the other
+/// doesn't (applies to all or some faces).
+///
+/// The mesh geometries are not necessarily identical in memory but they look
+/// the same. If the number of vertices differs, the mesh geometries don't share
+/// the same qualities (they are not welded in the same places and at least one
+/// of them is not watertight). Despite that they are considered visually
+/// similar, they are not going to be treated the same by some functions of this
+/// software and all their transformations result in different mesh geometries.
+pub fn are_visually_similar(mesh1: &Mesh, mesh2: &Mesh) -> bool {
+    struct UnpackedFace {
+        vertices: (Point3<f32>, Point3<f32>, Point3<f32>),
+        normals: (Vector3<f32>, Vector3<f32>, Vector3<f32>),
+    }
+
+    impl PartialEq for UnpackedFace {
+        fn eq(&self, other: &Self) -> bool {
+            (approx::relative_eq!(self.vertices.0, other.vertices.0)
+                && approx::relative_eq!(self.vertices.1, other.vertices.1)
+                && approx::relative_eq!(self.vertices.2, other.vertices.2)
+                && approx::relative_eq!(self.normals.0, other.normals.0)
+                && approx::relative_eq!(self.normals.1, other.normals.1)
+                && approx::relative_eq!(self.normals.2, other.normals.2))
+                || (approx::relative_eq!(self.vertices.0, other.vertices.1)
+                    && approx::relative_eq!(self.vertices.1, other.vertices.2)
+                    && approx::relative_eq!(self.vertices.2, other.vertices.0)
+                    && approx::relative_eq!(self.normals.0, other.normals.1)
+                    && approx::relative_eq!(self.normals.1, other.normals.2)
+                    && approx::relative_eq!(self.normals.2, other.normals.0))
+                || (approx::relative_eq!(self.vertices.0, other.vertices.2)
+                    && approx::relative_eq!(self.vertices.1, other.vertices.0)
+                    && approx::relative_eq!(self.vertices.2, other.vertices.1)
+                    && approx::relative_eq!(self.normals.0, other.normals.2)
+                    && approx::relative_eq!(self.normals.1, other.normals.0)
+                    && approx::relative_eq!(self.normals.2, other.normals.1))
+        }
+    }
+
+    let unpacked_faces1 = mesh1
+        .faces()
+        .iter()
+        .enumerate()
+        .flat_map(|(face_index, face)| {
+            face.to_triangles().into_iter().map(move |f| UnpackedFace {
+                vertices: (
+                    mesh1.vertices()[cast_usize(f.vertices.0)],
+                    mesh1.vertices()[cast_usize(f.vertices.1)],
+                    mesh1.vertices()[cast_usize(f.vertices.2)],
+                ),
+                normals: {
+                    let [n1, n2, n3] = mesh1
+                        .vertex_normals()
+                        .face_corner_normals(face_index, f.vertices);
+                    (n1, n2, n3)
+                },
+            })
+        });
+
+    let unpacked_faces2 = mesh2
+        .faces()
+        .iter()
+        .enumerate()
+        .flat_map(|(face_index, face)| {
+            face.to_triangles().into_iter().map(move |f| UnpackedFace {
+                vertices: (
+                    mesh2.vertices()[cast_usize(f.vertices.0)],
+                    mesh2.vertices()[cast_usize(f.vertices.1)],
+                    mesh2.vertices()[cast_usize(f.vertices.2)],
+                ),
+                normals: {
+                    let [n1, n2, n3] = mesh2
+                        .vertex_normals()
+                        .face_corner_normals(face_index, f.vertices);
+                    (n1, n2, n3)
+                },
+            })
+        });
+
+    mesh1.faces().len() == mesh2.faces().len()
+        && unpacked_faces1
+            .clone()
+            .all(|f| unpacked_faces2.clone().any(|g| f == g))
+        && unpacked_faces2
+            .clone()
+            .all(|f| unpacked_faces1.clone().any(|g| f == g))
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Rotation3;
+
+    use crate::convert::cast_u32;
+    use crate::mesh::normals::VertexNormals;
+    use crate::mesh::{primitive, tools, NormalStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_diff_faces_reports_moved_faces_of_transformed_mesh() {
+        let previous = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let current = primitive::create_box(
+            Point3::new(0.0, 0.0, 2.0),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let unchanged_diff = diff_faces(&previous, &previous, 0.001);
+        let moved_diff = diff_faces(&previous, &current, 0.001);
+
+        assert!(unchanged_diff
+            .current_faces
+            .iter()
+            .all(|change| *change == FaceChange::Unchanged));
+        assert!(unchanged_diff.removed_faces.is_empty());
+        assert!(moved_diff
+            .current_faces
+            .iter()
+            .all(|change| *change == FaceChange::Moved));
+        assert!(moved_diff.removed_faces.is_empty());
+    }
+
+    #[test]
+    fn test_diff_faces_reports_added_and_removed_faces() {
+        let (faces, vertices) = quad();
+        let previous = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        // The second triangle is replaced by the other diagonal split
+        let current = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![faces[0], (1, 3, 0)],
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let diff = diff_faces(&previous, &current, 0.001);
+
+        assert_eq!(
+            diff.current_faces,
+            vec![FaceChange::Unchanged, FaceChange::Added],
+        );
+        assert_eq!(diff.removed_faces, vec![1]);
+    }
+
+    fn quad() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        #[rustfmt::skip]
+        let vertices = vec![
+            Point3::new(-1.0, -1.0,  0.0),
+            Point3::new( 1.0, -1.0,  0.0),
+            Point3::new( 1.0,  1.0,  0.0),
+            Point3::new(-1.0,  1.0,  0.0),
+        ];
+
+        let faces = vec![(0, 1, 2), (2, 3, 0)];
+
+        (faces, vertices)
+    }
+
+    pub fn quad_with_normals() -> Mesh {
+        #[rustfmt::skip]
+        let vertices = vec![
+            Point3::new(-1.0, -1.0,  0.0),
+            Point3::new( 1.0, -1.0,  0.0),
+            Point3::new( 1.0,  1.0,  0.0),
+            Point3::new(-1.0,  1.0,  0.0),
+        ];
+
+        #[rustfmt::skip]
+        let vertex_normals = vec![
+            Vector3::new(-1.0, -1.0,  1.0),
+            Vector3::new( 1.0, -1.0,  1.0),
+            Vector3::new( 1.0,  1.0,  1.0),
+            Vector3::new(-1.0,  1.0,  1.0),
+        ];
+
+        let faces = vec![(0, 1, 2), (2, 3, 0)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    pub fn quad_with_extra_vertices_and_normals() -> Mesh {
+        let vertices = vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0), // first copy of the same vertex
+            Point3::new(1.0, 1.0, 0.0), // second copy of the same vertex
+            Point3::new(-1.0, 1.0, 0.0),
+        ];
+
+        let vertex_normals = vec![
+            Vector3::new(-1.0, -1.0, 1.0),
+            Vector3::new(1.0, -1.0, 1.0),
+            Vector3::new(1.0, 1.0, 1.0), // first copy of the same normal
+            Vector3::new(1.0, 1.0, 1.0), // second copy of the same normal
+            Vector3::new(-1.0, 1.0, 1.0),
+        ];
+
+        let faces = vec![(0, 1, 2), (3, 4, 0)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    pub fn quad_renumbered_with_normals() -> Mesh {
+        let vertices = vec![
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+        ];
+
+        let vertex_normals = vec![
+            Vector3::new(1.0, -1.0, 1.0),
+            Vector3::new(-1.0, -1.0, 1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(-1.0, 1.0, 1.0),
+        ];
+
+        let faces = vec![(1, 0, 2), (3, 1, 2)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    pub fn quad_renumbered_more_with_normals() -> Mesh {
+        let vertices = vec![
+            Point3::new(1.0, -1.0, 0.0),  //1
+            Point3::new(-1.0, 1.0, 0.0),  //3
+            Point3::new(-1.0, -1.0, 0.0), //0
+            Point3::new(1.0, 1.0, 0.0),   //2
+        ];
+
+        let vertex_normals = vec![
+            Vector3::new(1.0, -1.0, 1.0),  //1
+            Vector3::new(-1.0, 1.0, 1.0),  //3
+            Vector3::new(-1.0, -1.0, 1.0), //0
+            Vector3::new(1.0, 1.0, 1.0),   //2
+        ];
+
+        let faces = vec![(2, 0, 3), (2, 3, 1)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn torus() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(0.566987, -1.129e-11, 0.25),
+            Point3::new(-0.716506, 1.241025, 0.25),
+            Point3::new(-0.283494, 0.491025, 0.25),
+            Point3::new(-0.716506, -1.241025, 0.25),
+            Point3::new(-0.283494, -0.491025, 0.25),
+            Point3::new(1.0, -1.129e-11, -0.5),
+            Point3::new(1.433013, -1.129e-11, 0.25),
+            Point3::new(-0.5, 0.866025, -0.5),
+            Point3::new(-0.5, -0.866025, -0.5),
+        ];
+
+        let faces = vec![
+            (4, 3, 6),
+            (0, 6, 2),
+            (2, 1, 3),
+            (8, 4, 0),
+            (3, 8, 6),
+            (5, 0, 7),
+            (6, 5, 7),
+            (7, 2, 4),
+            (1, 7, 8),
+            (4, 6, 0),
+            (6, 1, 2),
+            (2, 3, 4),
+            (8, 0, 5),
+            (8, 5, 6),
+            (0, 2, 7),
+            (6, 7, 1),
+            (7, 4, 8),
+            (1, 8, 3),
+        ];
+
+        (faces, vertices)
+    }
+
+    fn double_torus() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(5.566988, -1.129e-11, 0.25),
+            Point3::new(4.283494, 1.241025, 0.25),
+            Point3::new(4.716506, 0.491025, 0.25),
+            Point3::new(4.283494, -1.241025, 0.25),
+            Point3::new(4.716506, -0.491025, 0.25),
+            Point3::new(6.0, 0.75, 0.25),
+            Point3::new(5.149519, 1.241025, 0.25),
+            Point3::new(6.0, 1.732051, 0.25),
+            Point3::new(4.608253, 1.053525, -0.5),
+            Point3::new(4.5, -0.866025, -0.5),
+            Point3::new(6.108253, 0.1875, -0.5),
+            Point3::new(6.433012, -1.129e-11, 0.25),
+            Point3::new(6.216506, 2.107051, -0.5),
+            Point3::new(6.433012, 2.482051, 0.25),
+        ];
+
+        let faces = vec![
+            (4, 3, 11),
+            (0, 11, 2),
+            (2, 1, 3),
+            (7, 5, 11),
+            (5, 6, 11),
+            (6, 7, 13),
+            (8, 2, 9),
+            (1, 8, 9),
+            (8, 12, 6),
+            (1, 13, 12),
+            (9, 4, 0),
+            (3, 9, 11),
+            (10, 0, 2),
+            (12, 10, 5),
+            (13, 11, 12),
+            (10, 8, 6),
+            (4, 11, 0),
+            (11, 1, 2),
+            (2, 3, 4),
+            (7, 11, 13),
+            (6, 1, 11),
+            (6, 13, 1),
+            (2, 4, 9),
+            (1, 9, 3),
+            (12, 7, 6),
+            (1, 12, 8),
+            (9, 0, 10),
+            (9, 10, 11),
+            (10, 2, 8),
+            (12, 5, 7),
+            (11, 10, 12),
+            (10, 6, 5),
+        ];
+
+        (faces, vertices)
+    }
+
+    fn triple_torus() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(15.566987, -1.129e-11, 0.25),
+            Point3::new(14.283494, 1.241025, 0.25),
+            Point3::new(14.716506, 0.491025, 0.25),
+            Point3::new(14.283494, -1.241025, 0.25),
+            Point3::new(14.716506, -0.491025, 0.25),
+            Point3::new(16.0, 0.75, 0.25),
+            Point3::new(15.149519, 1.241025, 0.25),
+            Point3::new(16.0, 1.732051, 0.25),
+            Point3::new(16.108253, 0.1875, -0.5),
+            Point3::new(16.433012, -1.129e-11, 0.25),
+            Point3::new(14.716506, 1.991025, 0.25),
+            Point3::new(15.566987, 2.482051, 0.25),
+            Point3::new(14.283494, 3.723076, 0.25),
+            Point3::new(14.716506, 2.973076, 0.25),
+            Point3::new(14.554127, 1.334775, -0.5),
+            Point3::new(14.5, -0.866025, -0.5),
+            Point3::new(14.5, 3.348076, -0.5),
+            Point3::new(16.108253, 2.294551, -0.5),
+            Point3::new(16.433012, 2.482051, 0.25),
+        ];
+
+        let faces = vec![
+            (4, 3, 0),
+            (0, 9, 1),
+            (2, 1, 3),
+            (7, 5, 9),
+            (5, 6, 9),
+            (6, 7, 18),
+            (15, 4, 0),
+            (3, 15, 9),
+            (10, 1, 11),
+            (11, 18, 12),
+            (13, 12, 1),
+            (14, 2, 15),
+            (1, 14, 15),
+            (8, 0, 2),
+            (8, 14, 6),
+            (16, 13, 10),
+            (12, 16, 1),
+            (17, 8, 7),
+            (18, 9, 8),
+            (14, 17, 6),
+            (17, 11, 16),
+            (18, 17, 16),
+            (14, 10, 17),
+            (3, 9, 0),
+            (0, 1, 2),
+            (2, 3, 4),
+            (7, 9, 18),
+            (6, 1, 9),
+            (6, 18, 1),
+            (15, 0, 8),
+            (15, 8, 9),
+            (1, 18, 11),
+            (11, 12, 13),
+            (13, 1, 10),
+            (2, 4, 15),
+            (1, 15, 3),
+            (8, 2, 14),
+            (8, 6, 5),
+            (16, 10, 14),
+            (16, 14, 1),
+            (8, 5, 7),
+            (18, 8, 17),
+            (17, 7, 6),
+            (11, 13, 16),
+            (18, 16, 12),
+            (10, 11, 17),
+        ];
+
+        (faces, vertices)
+    }
+
+    pub fn non_manifold_shape() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+        ];
+
+        let faces = vec![(0, 1, 2), (2, 3, 0), (2, 4, 0)];
+
+        (faces, vertices)
+    }
+
+    pub fn box_sharp_mismatching_winding() -> Mesh {
+        let vertex_positions = vec![
+            // back
+            Point3::new(-1.0, 1.0, -1.0),
+            Point3::new(-1.0, 1.0, 1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(1.0, 1.0, -1.0),
+            // front
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(-1.0, -1.0, 1.0),
+            Point3::new(1.0, -1.0, 1.0),
+            Point3::new(1.0, -1.0, -1.0),
+        ];
+
+        let vertex_normals = vec![
+            // back
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            // front
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+        ];
+
+        let faces = vec![
+            // back
+            (2, 1, 0),
+            (2, 3, 0),
+            // top
+            (2, 1, 5),
+            (2, 5, 6),
+            // right
+            (2, 6, 7),
+            (7, 3, 2),
+            // bottom
+            (3, 7, 4),
+            (4, 0, 3),
+            // front
+            (6, 4, 7),
+            (4, 6, 5),
+            // left
+            (0, 4, 5),
+            (5, 1, 0),
+        ];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertex_positions,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn tessellated_triangle() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+
+        let faces = vec![(0, 3, 1), (1, 3, 4), (1, 4, 2), (3, 5, 4)];
+
+        (faces, vertices)
+    }
+
+    fn tessellated_triangle_with_island() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            Point3::new(-1.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 2.0, 1.0),
+        ];
+
+        let faces = vec![(0, 3, 1), (1, 3, 4), (1, 4, 2), (3, 5, 4), (6, 7, 8)];
+
+        (faces, vertices)
+    }
+
+    #[test]
+    fn test_vertex_distances_to_mesh_of_nested_boxes() {
+        let small_box = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+        );
+        let large_box = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(2.0),
+        );
+
+        let unsigned_distances = vertex_distances_to_mesh(&small_box, &large_box, false);
+        let signed_distances = vertex_distances_to_mesh(&small_box, &large_box, true);
+        let outer_distances = vertex_distances_to_mesh(&large_box, &small_box, true);
+
+        for distance in unsigned_distances {
+            assert!(approx::relative_eq!(distance, 0.5));
+        }
+        // The small box is inside the large one
+        for distance in signed_distances {
+            assert!(approx::relative_eq!(distance, -0.5));
+        }
+        for distance in outer_distances {
+            assert!(approx::relative_eq!(distance, 0.75f32.sqrt()));
+        }
+    }
+
+    #[test]
+    fn test_edge_sharing() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let unoriented_edges_one_way_correct = vec![
+            UnorientedEdge(OrientedEdge::new(0, 1)),
+            UnorientedEdge(OrientedEdge::new(1, 2)),
+            UnorientedEdge(OrientedEdge::new(2, 3)),
+            UnorientedEdge(OrientedEdge::new(3, 0)),
+        ];
+        let unoriented_edges_two_ways_correct = vec![
+            UnorientedEdge(OrientedEdge::new(2, 0)),
+            UnorientedEdge(OrientedEdge::new(0, 2)),
+        ];
+
+        for ue in unoriented_edges_one_way_correct {
+            assert!(edge_sharing_map.contains_key(&ue));
+            if ue.0.vertices.0 < ue.0.vertices.1 {
+                assert_eq!(edge_sharing_map.get(&ue).unwrap().ascending_edges.len(), 1);
+                assert_eq!(edge_sharing_map.get(&ue).unwrap().descending_edges.len(), 0);
+            } else {
+                assert_eq!(edge_sharing_map.get(&ue).unwrap().ascending_edges.len(), 0);
+                assert_eq!(edge_sharing_map.get(&ue).unwrap().descending_edges.len(), 1);
+            }
+        }
+
+        for ue in unoriented_edges_two_ways_correct {
+            assert!(edge_sharing_map.contains_key(&ue));
+            assert_eq!(edge_sharing_map.get(&ue).unwrap().ascending_edges.len(), 1);
+            assert_eq!(edge_sharing_map.get(&ue).unwrap().descending_edges.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_find_edge_with_valency() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let oriented_edges_with_valency_1_correct = vec![
+            OrientedEdge::new(0, 1),
+            OrientedEdge::new(1, 2),
+            OrientedEdge::new(2, 3),
+            OrientedEdge::new(3, 0),
+        ];
+        let oriented_edges_with_valency_2_correct =
+            vec![OrientedEdge::new(2, 0), OrientedEdge::new(0, 2)];
+
+        let oriented_edges_with_valency_1: Vec<_> =
+            find_edges_with_valency(&edge_sharing_map, 1).collect();
+        let oriented_edges_with_valency_2: Vec<_> =
+            find_edges_with_valency(&edge_sharing_map, 2).collect();
+
+        assert_eq!(oriented_edges_with_valency_1.len(), 4);
+        assert_eq!(oriented_edges_with_valency_2.len(), 2);
+
+        for o_e in oriented_edges_with_valency_1_correct {
+            assert!(oriented_edges_with_valency_1.iter().any(|e| *e == o_e));
+        }
+
+        for o_e in oriented_edges_with_valency_2_correct {
+            assert!(oriented_edges_with_valency_2.iter().any(|e| *e == o_e));
+        }
+    }
+
+    #[test]
+    fn test_border_edges() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let oriented_edges_border_correct = vec![
+            OrientedEdge::new(0, 1),
+            OrientedEdge::new(1, 2),
+            OrientedEdge::new(2, 3),
+            OrientedEdge::new(3, 0),
+        ];
+
+        let oriented_edges_border_check: Vec<_> = border_edges(&edge_sharing_map).collect();
+
+        for o_e in &oriented_edges_border_correct {
+            assert!(oriented_edges_border_check.iter().any(|e| e == o_e));
+        }
+    }
+
+    #[test]
+    fn test_manifold_edges() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let oriented_edges_manifold_correct =
+            vec![OrientedEdge::new(2, 0), OrientedEdge::new(0, 2)];
+
+        let oriented_edges_manifold_check: Vec<_> = manifold_edges(&edge_sharing_map).collect();
+
+        for o_e in oriented_edges_manifold_correct {
+            assert!(oriented_edges_manifold_check.iter().any(|e| *e == o_e));
+        }
+    }
+
+    #[test]
+    fn test_non_manifold_edges() {
+        let (faces, vertices) = non_manifold_shape();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let oriented_edges_non_manifold_correct =
+            vec![OrientedEdge::new(2, 0), OrientedEdge::new(0, 2)];
+
+        let oriented_edges_non_manifold_check: Vec<_> =
+            non_manifold_edges(&edge_sharing_map).collect();
+
+        for o_e in &oriented_edges_non_manifold_correct {
+            assert!(oriented_edges_non_manifold_check.iter().any(|e| e == o_e));
+        }
+
+        assert_eq!(oriented_edges_non_manifold_check.len(), 3);
+    }
+
+    #[test]
+    fn test_is_mesh_manifold_returns_false_because_non_manifold() {
+        let (faces, vertices) = non_manifold_shape();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(!is_mesh_manifold(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_is_mesh_manifold_returns_true_because_manifold() {
+        let (faces, vertices) = torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(is_mesh_manifold(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_border_vertex_indices() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let border_vertex_indices_correct = vec![0, 1, 2, 3];
+
+        let border_vertex_indices_check = border_vertex_indices(&edge_sharing_map);
+
+        for v_i in border_vertex_indices_correct {
+            assert!(border_vertex_indices_check.iter().any(|v| *v == v_i));
+        }
+    }
+
+    #[test]
+    fn test_surface_area_and_signed_volume_of_box() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+
+        assert!(approx::relative_eq!(surface_area(&mesh), 22.0));
+        assert!(approx::relative_eq!(
+            signed_volume(&mesh),
+            6.0,
+            epsilon = 0.0001,
+        ));
+    }
+
+    #[test]
+    fn test_signed_volume_of_open_mesh_is_zero_for_planar_quad_at_origin() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        assert!(approx::relative_eq!(surface_area(&mesh), 4.0));
+        assert!(approx::relative_eq!(signed_volume(&mesh), 0.0));
+    }
+
+    #[test]
+    fn test_is_mesh_orientable_returns_true_watertight_mesh() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(is_mesh_orientable(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_is_mesh_orientable_returns_true_open_mesh() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(is_mesh_orientable(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_is_mesh_orientable_returns_false_for_nonorientable_mesh() {
+        let mesh = box_sharp_mismatching_winding();
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(!is_mesh_orientable(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_is_mesh_watertight_returns_true_for_watertight_mesh() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(is_mesh_watertight(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_is_mesh_watertight_returns_false_for_open_mesh() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        assert!(!is_mesh_watertight(&edge_sharing_map));
+    }
+
+    #[test]
+    fn test_triangulated_mesh_genus_box_should_be_0() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        assert!(mesh.is_triangulated());
+
+        let edges: HashSet<UnorientedEdge> = mesh.unoriented_edges_iter().collect();
+
+        let genus = triangulated_mesh_genus(mesh.vertices().len(), edges.len(), mesh.faces().len());
+        assert_eq!(genus, 0);
+    }
+
+    #[test]
+    fn test_triangulated_mesh_genus_torus_should_be_1() {
+        let (faces, vertices) = torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        assert!(mesh.is_triangulated());
+
+        let edges: HashSet<UnorientedEdge> = mesh.unoriented_edges_iter().collect();
+
+        let genus = triangulated_mesh_genus(mesh.vertices().len(), edges.len(), mesh.faces().len());
+        assert_eq!(genus, 1);
+    }
+
+    #[test]
+    fn test_topology_info_for_box() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let info = topology_info(&mesh);
+
+        assert_eq!(info.vertex_count, 8);
+        assert_eq!(info.edge_count, 18);
+        assert_eq!(info.face_count, 12);
+        assert_eq!(info.euler_characteristic, 2);
+        assert_eq!(info.component_count, 1);
+        assert_eq!(info.genus, Some(0));
+    }
+
+    #[test]
+    fn test_topology_info_for_torus() {
+        let (faces, vertices) = torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let info = topology_info(&mesh);
+
+        assert_eq!(info.euler_characteristic, 0);
+        assert_eq!(info.component_count, 1);
+        assert_eq!(info.genus, Some(1));
+    }
+
+    #[test]
+    fn test_topology_info_for_open_mesh_with_island() {
+        let (faces, vertices) = tessellated_triangle_with_island();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let info = topology_info(&mesh);
+
+        assert_eq!(info.euler_characteristic, 2);
+        assert_eq!(info.component_count, 2);
+        assert_eq!(info.genus, None);
+    }
+
+    #[test]
+    fn test_find_duplicate_faces_pairs_opposite_faces() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let face0 = mesh.triangle_face(0);
+        let face1 = mesh.triangle_face(1);
+        let faces = mesh.faces().iter().copied().chain(vec![
+            Face::Triangle(face0),
+            Face::Triangle(face0.to_reverted()),
+            Face::Triangle(face1),
+        ]);
+        let mesh_with_duplicates = Mesh::from_faces_with_vertices_and_vertex_normals(
+            faces,
+            mesh.vertices().to_vec(),
+            mesh.vertex_normals().clone(),
+        );
+
+        let duplicate_faces = find_duplicate_faces(&mesh_with_duplicates);
+
+        let mut internal_face_indices = duplicate_faces.internal_face_indices.clone();
+        internal_face_indices.sort();
+        assert_eq!(internal_face_indices, vec![0, 13]);
+        assert_eq!(duplicate_faces.duplicate_face_indices, vec![14]);
+    }
+
+    #[test]
+    fn test_find_duplicate_faces_finds_nothing_in_box() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        assert!(find_duplicate_faces(&mesh).is_empty());
+    }
+
+    #[test]
+    fn test_compute_mass_properties_for_box() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::identity(),
+            Vector3::new(2.0, 4.0, 6.0),
+        );
+
+        let mass_properties = compute_mass_properties(&mesh).unwrap();
+
+        assert!(approx::relative_eq!(
+            mass_properties.volume,
+            48.0,
+            epsilon = 0.001
+        ));
+        assert!(mass_properties.center_of_mass.coords.relative_eq(
+            &Vector3::new(1.0, 2.0, 3.0),
+            0.0001,
+            0.0001
+        ));
+
+        // Box with mass m and sides a, b, c has Ixx = m(b^2 + c^2)/12
+        #[rustfmt::skip]
+        let expected_inertia_tensor = Matrix3::new(
+            208.0, 0.0, 0.0,
+            0.0, 160.0, 0.0,
+            0.0, 0.0, 80.0,
+        );
+        assert!(mass_properties
+            .inertia_tensor
+            .relative_eq(&expected_inertia_tensor, 0.01, 0.001));
+        assert!(mass_properties.principal_moments_of_inertia().relative_eq(
+            &Vector3::new(80.0, 160.0, 208.0),
+            0.01,
+            0.001
+        ));
+    }
+
+    #[test]
+    fn test_compute_mass_properties_for_rotated_box_has_same_principal_moments() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::from_euler_angles(0.3, 0.2, 0.1),
+            Vector3::new(2.0, 4.0, 6.0),
+        );
+
+        let mass_properties = compute_mass_properties(&mesh).unwrap();
+
+        assert!(mass_properties.principal_moments_of_inertia().relative_eq(
+            &Vector3::new(80.0, 160.0, 208.0),
+            0.01,
+            0.001
+        ));
+    }
+
+    #[test]
+    fn test_compute_mass_properties_for_inside_out_box_returns_none() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let reverted_mesh = tools::revert_mesh_faces(&mesh);
+
+        assert!(compute_mass_properties(&reverted_mesh).is_none());
+    }
+
+    #[test]
+    fn test_detect_mirror_symmetry_finds_plane_of_box() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::from_euler_angles(0.3, 0.2, 0.1),
+            Vector3::new(1.0, 2.0, 4.0),
+        );
+
+        let symmetry = detect_mirror_symmetry(&mesh).unwrap();
+
+        assert!(symmetry.deviation < 0.001);
+        assert!(symmetry.plane.origin().coords.relative_eq(
+            &Vector3::new(1.0, 2.0, 3.0),
+            0.001,
+            0.001
+        ));
+    }
+
+    #[test]
+    fn test_detect_mirror_symmetry_mirrors_vertices_onto_vertices() {
+        // A prism with isosceles triangle base, only symmetric by the
+        // YZ plane and the plane halving its length
+        let vertices = vec![
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 3.0, 0.0),
+            Point3::new(-1.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 3.0, 1.0),
+        ];
+        let faces = vec![
+            (0, 2, 1),
+            (3, 4, 5),
+            (0, 1, 4),
+            (0, 4, 3),
+            (1, 2, 5),
+            (1, 5, 4),
+            (2, 0, 3),
+            (2, 3, 5),
+        ];
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let symmetry = detect_mirror_symmetry(&mesh).unwrap();
+        let origin = symmetry.plane.origin();
+        let normal = symmetry.plane.normal();
+
+        assert!(symmetry.deviation < 0.001);
+        for vertex in &vertices {
+            let mirrored = vertex - 2.0 * (vertex - origin).dot(&normal) * normal;
+            assert!(vertices
+                .iter()
+                .any(|other| na::distance(&mirrored, other) < 0.001));
+        }
+    }
+
+    #[test]
+    fn test_compute_principal_axes_of_rotated_box() {
+        let rotation = Rotation3::from_euler_angles(0.3, 0.2, 0.1);
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            rotation,
+            Vector3::new(4.0, 2.0, 1.0),
+        );
+
+        let principal_axes = compute_principal_axes(mesh.vertices());
+
+        for axis_index in 0..3 {
+            let axis = principal_axes.matrix().column(axis_index).into_owned();
+            let expected_axis = rotation.matrix().column(axis_index).into_owned();
+            assert!(approx::relative_eq!(
+                axis.dot(&expected_axis).abs(),
+                1.0,
+                epsilon = 0.0001,
+            ));
+        }
+        assert!(approx::relative_eq!(
+            principal_axes.matrix().determinant(),
+            1.0,
+            epsilon = 0.0001,
+        ));
+    }
+
+    #[test]
+    fn test_compute_minimal_oriented_bounding_box_of_rotated_box() {
+        let rotation = Rotation3::from_euler_angles(0.3, 0.2, 0.1);
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            rotation,
+            Vector3::new(4.0, 2.0, 1.0),
+        );
+
+        let oriented_bounding_box = compute_minimal_oriented_bounding_box(mesh.vertices()).unwrap();
+
+        assert!(oriented_bounding_box.center.coords.relative_eq(
+            &Vector3::new(1.0, 2.0, 3.0),
+            0.001,
+            0.001
+        ));
+        assert!(approx::relative_eq!(
+            oriented_bounding_box.volume(),
+            8.0,
+            epsilon = 0.001,
+        ));
+        assert!(oriented_bounding_box.volume() < mesh.bounding_box().diagonal().iter().product());
+    }
+
+    #[test]
+    fn test_compute_minimal_oriented_bounding_box_improves_on_principal_axes() {
+        // A square with many points clustered around two opposite
+        // corners, which tilts the principal axes to the diagonal
+        let mut points = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(0.0, 1.0, 1.0),
+        ];
+        for i in 0..10 {
+            let offset = i as f32 * 0.01;
+            points.push(Point3::new(offset, offset, 0.5));
+            points.push(Point3::new(1.0 - offset, 1.0 - offset, 0.5));
+        }
+
+        let principal_axes = compute_principal_axes(&points);
+        let principal_bounding_box =
+            compute_oriented_bounding_box(&points, &principal_axes).unwrap();
+        let minimal_bounding_box = compute_minimal_oriented_bounding_box(&points).unwrap();
+
+        assert!(principal_bounding_box.volume() > 1.1);
+        assert!(approx::relative_eq!(
+            minimal_bounding_box.volume(),
+            1.0,
+            epsilon = 0.001,
+        ));
+    }
+
+    #[test]
+    fn test_register_rigid_finds_transform_of_moved_box() {
+        let target = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 4.0),
+        );
+        let transform = Isometry3::new(Vector3::new(0.5, -0.3, 0.2), Vector3::new(0.1, 0.05, 0.15));
+        let mut source = target.clone();
+        for vertex in source.vertices_mut() {
+            *vertex = transform * *vertex;
+        }
+
+        let registration = register_rigid(&source, &target, 30).unwrap();
+
+        assert!(registration.deviation < 0.001);
+        for (source_vertex, target_vertex) in source.vertices().iter().zip(target.vertices()) {
+            let registered_vertex = registration.transform * source_vertex;
+            assert!(na::distance(&registered_vertex, target_vertex) < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_register_rigid_matches_vertices_to_target_faces() {
+        let target = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 4.0),
+        );
+
+        // Split each face at its centroid, so that most source vertices
+        // lie inside the target faces rather than on its vertices
+        let mut vertices = target.vertices().to_vec();
+        let mut faces = Vec::new();
+        for f in target.triangle_faces_iter() {
+            let (v1, v2, v3) = f.vertices;
+            let centroid = Point3::from(
+                (vertices[cast_usize(v1)].coords
+                    + vertices[cast_usize(v2)].coords
+                    + vertices[cast_usize(v3)].coords)
+                    / 3.0,
+            );
+            let centroid_index = cast_u32(vertices.len());
+            vertices.push(centroid);
+            faces.push((v1, v2, centroid_index));
+            faces.push((v2, v3, centroid_index));
+            faces.push((v3, v1, centroid_index));
+        }
+        let transform = Isometry3::new(Vector3::new(0.2, -0.1, 0.1), Vector3::new(0.05, 0.0, 0.1));
+        let source = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices.iter().map(|vertex| transform * vertex),
+            NormalStrategy::Sharp,
+        );
+
+        let registration = register_rigid(&source, &target, 30).unwrap();
+
+        assert!(registration.deviation < 0.001);
+        for (source_vertex, vertex) in source.vertices().iter().zip(&vertices) {
+            let registered_vertex = registration.transform * source_vertex;
+            assert!(na::distance(&registered_vertex, vertex) < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_register_rigid_of_same_mesh_is_identity() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 4.0),
+        );
+
+        let registration = register_rigid(&mesh, &mesh, 30).unwrap();
+
+        assert!(registration.deviation < 0.001);
+        assert!(registration.transform.to_homogeneous().relative_eq(
+            &na::Matrix4::identity(),
+            0.001,
+            0.001
+        ));
+    }
+
+    #[test]
+    fn test_triangulated_mesh_genus_double_torus_should_be_2() {
+        let (faces, vertices) = double_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        assert!(mesh.is_triangulated());
+
+        let edges: HashSet<UnorientedEdge> = mesh.unoriented_edges_iter().collect();
+
+        let genus = triangulated_mesh_genus(mesh.vertices().len(), edges.len(), mesh.faces().len());
+        assert_eq!(genus, 2);
+    }
+
+    #[test]
+    fn test_triangulated_mesh_genus_triple_torus_should_be_3() {
+        let (faces, vertices) = triple_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        assert!(mesh.is_triangulated());
+
+        let edges: HashSet<UnorientedEdge> = mesh.unoriented_edges_iter().collect();
+
+        let genus = triangulated_mesh_genus(mesh.vertices().len(), edges.len(), mesh.faces().len());
+        assert_eq!(genus, 3);
+    }
+
+    #[test]
+    fn test_border_edge_loops_returns_one_for_tessellated_triangle() {
+        let (faces, vertices) = tessellated_triangle();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let correct_loop: Vec<UnorientedEdge> = vec![
+            UnorientedEdge(OrientedEdge::new(0, 1)),
+            UnorientedEdge(OrientedEdge::new(1, 2)),
+            UnorientedEdge(OrientedEdge::new(2, 4)),
+            UnorientedEdge(OrientedEdge::new(4, 5)),
+            UnorientedEdge(OrientedEdge::new(5, 3)),
+            UnorientedEdge(OrientedEdge::new(3, 0)),
+        ];
+
+        let computed_loops = border_edge_loops(&edge_sharing_map);
+
+        assert_eq!(computed_loops.len(), 1);
+        assert_eq!(computed_loops[0].len(), correct_loop.len());
+        for edge in correct_loop {
+            assert!(computed_loops[0].iter().any(|e| *e == edge));
+        }
+    }
+
+    #[test]
+    fn test_border_edge_loops_returns_one_for_box() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let computed_loops = border_edge_loops(&edge_sharing_map);
+
+        assert!(computed_loops.is_empty());
+    }
+
+    #[test]
+    fn test_border_edge_loops_returns_two_for_tessellated_triangle_with_island() {
+        let (faces, vertices) = tessellated_triangle_with_island();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let correct_loops: Vec<Vec<UnorientedEdge>> = vec![
+            vec![
+                UnorientedEdge(OrientedEdge::new(0, 1)),
+                UnorientedEdge(OrientedEdge::new(1, 2)),
+                UnorientedEdge(OrientedEdge::new(2, 4)),
+                UnorientedEdge(OrientedEdge::new(4, 5)),
+                UnorientedEdge(OrientedEdge::new(5, 3)),
+                UnorientedEdge(OrientedEdge::new(3, 0)),
+            ],
+            vec![
+                UnorientedEdge(OrientedEdge::new(6, 7)),
+                UnorientedEdge(OrientedEdge::new(7, 8)),
+                UnorientedEdge(OrientedEdge::new(8, 6)),
+            ],
+        ];
+
+        let computed_loops = border_edge_loops(&edge_sharing_map);
+
+        assert_eq!(computed_loops.len(), 2);
+        assert!(
+            computed_loops[0].len() == correct_loops[0].len()
+                || computed_loops[0].len() == correct_loops[1].len()
+        );
+
+        if computed_loops[0].len() == correct_loops[0].len() {
+            assert_eq!(computed_loops[1].len(), correct_loops[1].len());
+            for (i, correct_loop) in correct_loops.iter().enumerate() {
+                for edge in correct_loop {
+                    assert!(computed_loops[i].iter().any(|e| e == edge));
+                }
+            }
+        } else {
+            assert_eq!(computed_loops[1].len(), correct_loops[0].len());
+            for (i, correct_loop) in correct_loops.iter().enumerate() {
+                for edge in correct_loop {
+                    assert!(computed_loops[(i + 1) % 2].iter().any(|e| e == edge));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_chains_returns_loops_for_tessellated_triangle_with_island() {
+        let (faces, vertices) = tessellated_triangle_with_island();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = edge_sharing(&oriented_edges);
+
+        let chains = edge_chains(border_edges(&edge_sharing_map).map(UnorientedEdge));
+
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0], vec![0, 1, 2, 4, 5, 3, 0]);
+        assert_eq!(chains[1], vec![6, 7, 8, 6]);
+    }
+
+    #[test]
+    fn test_edge_chains_returns_open_chains_for_branching_edges() {
+        let edges = vec![
+            UnorientedEdge(OrientedEdge::new(1, 2)),
+            UnorientedEdge(OrientedEdge::new(0, 1)),
+            UnorientedEdge(OrientedEdge::new(1, 3)),
+        ];
+
+        let chains = edge_chains(edges);
+
+        assert_eq!(chains, vec![vec![0, 1, 2], vec![1, 3]]);
+    }
+
+    #[test]
+    fn test_are_similar_returns_true_for_same() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        assert!(are_similar(&mesh, &mesh));
+    }
+
+    #[test]
+    fn test_are_similar_returns_true_for_renumbered() {
+        let mesh = quad_with_normals();
+        let mesh_renumbered = quad_renumbered_with_normals();
+
+        assert!(are_similar(&mesh, &mesh_renumbered));
+    }
+
+    #[test]
+    fn test_are_visually_similar_returns_true_for_not_watertight() {
+        let mesh = quad_with_normals();
+        let mesh_not_watertight = quad_with_extra_vertices_and_normals();
+
+        assert!(are_visually_similar(&mesh, &mesh_not_watertight));
+    }
+
+    #[test]
+    fn test_are_similar_returns_false_for_not_watertight() {
+        let mesh = quad_with_normals();
+        let mesh_not_watertight = quad_with_extra_vertices_and_normals();
+
+        assert!(!are_similar(&mesh, &mesh_not_watertight));
+    }
+
+    #[test]
+    fn test_are_visually_similar_returns_true_for_renumbered_more() {
+        let mesh = quad_with_normals();
+        let mesh_renumbered = quad_renumbered_more_with_normals();
+
+        assert!(are_visually_similar(&mesh, &mesh_renumbered));
+    }
+
+    #[test]
+    fn test_are_similar_returns_false_for_different() {
+        let mesh = quad_with_normals();
+        let (faces_d, vertices_d) = tessellated_triangle();
+        let mesh_d = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces_d.clone(),
+            vertices_d.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        assert!(!are_similar(&mesh, &mesh_d));
+    }
+}
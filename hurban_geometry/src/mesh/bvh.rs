@@ -0,0 +1,527 @@
+use std::cmp::Ordering;
+use std::f32;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::bounding_box::BoundingBox;
+use crate::convert::cast_usize;
+use crate::geometry;
+
+use super::{Mesh, TriangleFace};
+
+/// How many faces a leaf node of the hierarchy holds at most.
+const LEAF_FACE_COUNT_MAX: usize = 4;
+
+/// A triangle of a mesh face with the index of the face and the
+/// positions of its vertices.
+type FaceTriangle = ((usize, TriangleFace), [Point3<f32>; 3]);
+
+/// The closest intersection of a ray with the faces of a mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Distance from the ray origin in the units of the ray direction.
+    pub distance: f32,
+    /// Index of the hit face in the mesh.
+    pub face_index: usize,
+}
+
+/// The point on the faces of a mesh closest to a query point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestPoint {
+    /// The closest point itself.
+    pub point: Point3<f32>,
+    /// Barycentric coordinates of the point within the face, e.g. to
+    /// interpolate vertex attributes.
+    pub barycentric_coords: Point3<f32>,
+    /// Distance of the point from the query point.
+    pub distance: f32,
+    /// Index of the face containing the point in the mesh.
+    pub face_index: usize,
+    /// The triangle of the face containing the point, which the
+    /// barycentric coordinates refer to. It is the face itself, or
+    /// one of the triangles of a quad, see `Face::to_triangles`.
+    pub triangle_face: TriangleFace,
+}
+
+/// Bounding volume hierarchy over the faces of a mesh, split into
+/// triangles,
+/// answering ray casts, closest point and bounding box queries
+/// without testing every face.
+///
+/// The hierarchy is a binary tree of axis-aligned bounding boxes,
+/// built by splitting the faces at the median of their centroids
+/// along the longest axis of the node. It keeps its own copy of the
+/// triangles, so it does not borrow the mesh it was built from.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<[Point3<f32>; 3]>,
+    faces: Vec<(usize, TriangleFace)>,
+}
+
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounding_box: BoundingBox<f32>,
+    content: BvhNodeContent,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BvhNodeContent {
+    /// Range of the faces in `faces` (and `triangles`).
+    Leaf { start: usize, end: usize },
+    /// Indices of the child nodes.
+    Interior { left: usize, right: usize },
+}
+
+impl Bvh {
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let vertices = mesh.vertices();
+        let mut items: Vec<FaceTriangle> = mesh
+            .faces()
+            .iter()
+            .enumerate()
+            .flat_map(|(face_index, face)| {
+                face.to_triangles().into_iter().map(move |triangle_face| {
+                    let (v1, v2, v3) = triangle_face.vertices;
+                    (
+                        (face_index, triangle_face),
+                        [
+                            vertices[cast_usize(v1)],
+                            vertices[cast_usize(v2)],
+                            vertices[cast_usize(v3)],
+                        ],
+                    )
+                })
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            let item_count = items.len();
+            build_node(&mut nodes, &mut items, 0, item_count);
+        }
+
+        let (faces, triangles) = items.into_iter().unzip();
+
+        Bvh {
+            nodes,
+            triangles,
+            faces,
+        }
+    }
+
+    /// Finds the closest face hit by the ray, regardless of its
+    /// winding.
+    pub fn ray_intersection(
+        &self,
+        ray_origin: &Point3<f32>,
+        ray_direction: &Vector3<f32>,
+    ) -> Option<RayHit> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let inverse_direction = ray_direction.map(|component| 1.0 / component);
+        let mut closest: Option<RayHit> = None;
+        let mut stack = vec![0];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let max_distance = closest.map_or(f32::INFINITY, |hit| hit.distance);
+            if !ray_intersects_bounding_box(
+                ray_origin,
+                &inverse_direction,
+                &node.bounding_box,
+                max_distance,
+            ) {
+                continue;
+            }
+
+            match node.content {
+                BvhNodeContent::Leaf { start, end } => {
+                    for index in start..end {
+                        let [v0, v1, v2] = &self.triangles[index];
+                        let distance = geometry::compute_ray_triangle_intersection(
+                            ray_origin,
+                            ray_direction,
+                            v0,
+                            v1,
+                            v2,
+                        );
+
+                        if let Some(distance) = distance {
+                            if closest.map_or(true, |hit| distance < hit.distance) {
+                                closest = Some(RayHit {
+                                    distance,
+                                    face_index: self.faces[index].0,
+                                });
+                            }
+                        }
+                    }
+                }
+                BvhNodeContent::Interior { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Finds the point on the faces closest to the `point`. Returns
+    /// `None` only if the mesh has no faces.
+    pub fn closest_point(&self, point: &Point3<f32>) -> Option<ClosestPoint> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut closest: Option<ClosestPoint> = None;
+        let mut closest_distance_squared = f32::INFINITY;
+        let mut stack = vec![0];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if distance_squared_to_bounding_box(point, &node.bounding_box)
+                >= closest_distance_squared
+            {
+                continue;
+            }
+
+            match node.content {
+                BvhNodeContent::Leaf { start, end } => {
+                    for index in start..end {
+                        let [v0, v1, v2] = &self.triangles[index];
+                        let barycentric_coords =
+                            geometry::compute_closest_point_on_triangle_barycentric_coords(
+                                point, v0, v1, v2,
+                            );
+                        let closest_point =
+                            geometry::barycentric_to_cartesian(&barycentric_coords, v0, v1, v2);
+                        let distance_squared = nalgebra::distance_squared(point, &closest_point);
+
+                        if distance_squared < closest_distance_squared {
+                            closest_distance_squared = distance_squared;
+                            closest = Some(ClosestPoint {
+                                point: closest_point,
+                                barycentric_coords,
+                                distance: distance_squared.sqrt(),
+                                face_index: self.faces[index].0,
+                                triangle_face: self.faces[index].1,
+                            });
+                        }
+                    }
+                }
+                BvhNodeContent::Interior { left, right } => {
+                    // Visit the nearer child first, so that the other
+                    // one is more likely to be skipped
+                    let left_distance_squared =
+                        distance_squared_to_bounding_box(point, &self.nodes[left].bounding_box);
+                    let right_distance_squared =
+                        distance_squared_to_bounding_box(point, &self.nodes[right].bounding_box);
+                    if left_distance_squared < right_distance_squared {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Returns the indices of the faces whose bounding boxes overlap
+    /// the `bounding_box`, e.g. to only process the faces near a slab
+    /// of voxels. The faces themselves don't have to intersect it.
+    pub fn faces_overlapping_bounding_box(&self, bounding_box: &BoundingBox<f32>) -> Vec<usize> {
+        let mut face_indices = Vec::new();
+        if self.nodes.is_empty() {
+            return face_indices;
+        }
+
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !bounding_boxes_overlap(&node.bounding_box, bounding_box) {
+                continue;
+            }
+
+            match node.content {
+                BvhNodeContent::Leaf { start, end } => {
+                    for index in start..end {
+                        let triangle_bounding_box =
+                            BoundingBox::from_points(self.triangles[index].iter().copied())
+                                .expect("Triangle must have vertices");
+                        if bounding_boxes_overlap(&triangle_bounding_box, bounding_box) {
+                            face_indices.push(self.faces[index].0);
+                        }
+                    }
+                }
+                BvhNodeContent::Interior { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        // Both triangles of a quad may overlap
+        face_indices.sort_unstable();
+        face_indices.dedup();
+
+        face_indices
+    }
+}
+
+/// Builds the node for the items in `start..end`, reordering them so
+/// that each leaf holds a contiguous range. Returns the index of the
+/// node.
+fn build_node(
+    nodes: &mut Vec<BvhNode>,
+    items: &mut [FaceTriangle],
+    start: usize,
+    end: usize,
+) -> usize {
+    let node_items = &mut items[start..end];
+    let bounding_box = BoundingBox::from_points(
+        node_items
+            .iter()
+            .flat_map(|(_, triangle)| triangle.iter().copied()),
+    )
+    .expect("BVH node must contain at least one face");
+
+    let node_index = nodes.len();
+    nodes.push(BvhNode {
+        bounding_box,
+        content: BvhNodeContent::Leaf { start, end },
+    });
+
+    if node_items.len() <= LEAF_FACE_COUNT_MAX {
+        return node_index;
+    }
+
+    let diagonal = bounding_box.diagonal();
+    let axis = if diagonal.x >= diagonal.y && diagonal.x >= diagonal.z {
+        0
+    } else if diagonal.y >= diagonal.z {
+        1
+    } else {
+        2
+    };
+
+    node_items.sort_unstable_by(|(_, triangle1), (_, triangle2)| {
+        let centroid1 = triangle1[0][axis] + triangle1[1][axis] + triangle1[2][axis];
+        let centroid2 = triangle2[0][axis] + triangle2[1][axis] + triangle2[2][axis];
+        centroid1.partial_cmp(&centroid2).unwrap_or(Ordering::Equal)
+    });
+
+    let middle = start + node_items.len() / 2;
+    let left = build_node(nodes, items, start, middle);
+    let right = build_node(nodes, items, middle, end);
+    nodes[node_index].content = BvhNodeContent::Interior { left, right };
+
+    node_index
+}
+
+/// Checks whether the ray enters the bounding box closer than
+/// `max_distance`, using the slab method.
+fn ray_intersects_bounding_box(
+    ray_origin: &Point3<f32>,
+    inverse_direction: &Vector3<f32>,
+    bounding_box: &BoundingBox<f32>,
+    max_distance: f32,
+) -> bool {
+    let minimum_point = bounding_box.minimum_point();
+    let maximum_point = bounding_box.maximum_point();
+
+    let mut t_enter = 0.0f32;
+    let mut t_exit = max_distance;
+    for axis in 0..3 {
+        let t1 = (minimum_point[axis] - ray_origin[axis]) * inverse_direction[axis];
+        let t2 = (maximum_point[axis] - ray_origin[axis]) * inverse_direction[axis];
+
+        // NaN appears for rays parallel to the slab starting on its
+        // boundary, and is ignored by min and max
+        t_enter = t_enter.max(t1.min(t2));
+        t_exit = t_exit.min(t1.max(t2));
+    }
+
+    t_enter <= t_exit
+}
+
+/// Computes the squared distance of the point from the bounding box,
+/// which is zero for points inside it.
+fn distance_squared_to_bounding_box(point: &Point3<f32>, bounding_box: &BoundingBox<f32>) -> f32 {
+    let minimum_point = bounding_box.minimum_point();
+    let maximum_point = bounding_box.maximum_point();
+
+    let mut distance_squared = 0.0;
+    for axis in 0..3 {
+        let outside = if point[axis] < minimum_point[axis] {
+            minimum_point[axis] - point[axis]
+        } else if point[axis] > maximum_point[axis] {
+            point[axis] - maximum_point[axis]
+        } else {
+            0.0
+        };
+        distance_squared += outside * outside;
+    }
+
+    distance_squared
+}
+
+/// Checks whether the bounding boxes share any space, including just
+/// touching faces.
+fn bounding_boxes_overlap(
+    bounding_box1: &BoundingBox<f32>,
+    bounding_box2: &BoundingBox<f32>,
+) -> bool {
+    let minimum_point1 = bounding_box1.minimum_point();
+    let maximum_point1 = bounding_box1.maximum_point();
+    let minimum_point2 = bounding_box2.minimum_point();
+    let maximum_point2 = bounding_box2.maximum_point();
+
+    (0..3).all(|axis| {
+        minimum_point1[axis] <= maximum_point2[axis] && minimum_point2[axis] <= maximum_point1[axis]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Rotation3;
+
+    use crate::mesh::{primitive, NormalStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_bvh_ray_intersection_matches_brute_force() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(2.0),
+            12,
+            12,
+            NormalStrategy::Sharp,
+        );
+        let bvh = Bvh::from_mesh(&mesh);
+
+        let directions = [
+            Vector3::new(1.0, 0.1, 0.2),
+            Vector3::new(-0.3, 1.0, 0.4),
+            Vector3::new(0.2, -0.5, -1.0),
+        ];
+        for direction in directions.iter() {
+            let direction = direction.normalize();
+            let origin = Point3::origin() - direction * 5.0;
+
+            let hit = bvh
+                .ray_intersection(&origin, &direction)
+                .expect("Ray must hit the sphere");
+
+            let vertices = mesh.vertices();
+            let brute_force_distance = mesh
+                .faces()
+                .iter()
+                .filter_map(|face| {
+                    let triangle_face = face.to_triangles()[0];
+                    let (v1, v2, v3) = triangle_face.vertices;
+                    geometry::compute_ray_triangle_intersection(
+                        &origin,
+                        &direction,
+                        &vertices[cast_usize(v1)],
+                        &vertices[cast_usize(v2)],
+                        &vertices[cast_usize(v3)],
+                    )
+                })
+                .fold(f32::INFINITY, f32::min);
+
+            assert!((hit.distance - brute_force_distance).abs() < 0.0001);
+            assert!(hit.distance > 3.9 && hit.distance < 4.1);
+        }
+    }
+
+    #[test]
+    fn test_bvh_closest_point_matches_brute_force() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(2.0),
+            12,
+            12,
+            NormalStrategy::Sharp,
+        );
+        let bvh = Bvh::from_mesh(&mesh);
+
+        let points = [
+            Point3::new(3.0, 0.5, -0.2),
+            Point3::new(0.1, 0.2, 0.3),
+            Point3::new(-0.4, -5.0, 1.0),
+        ];
+        for point in points.iter() {
+            let closest = bvh
+                .closest_point(point)
+                .expect("Sphere must have a closest point");
+
+            let vertices = mesh.vertices();
+            let brute_force_distance = mesh
+                .faces()
+                .iter()
+                .map(|face| {
+                    let (v1, v2, v3) = face.to_triangles()[0].vertices;
+                    let a = &vertices[cast_usize(v1)];
+                    let b = &vertices[cast_usize(v2)];
+                    let c = &vertices[cast_usize(v3)];
+                    let coords = geometry::compute_closest_point_on_triangle_barycentric_coords(
+                        point, a, b, c,
+                    );
+                    nalgebra::distance(point, &geometry::barycentric_to_cartesian(&coords, a, b, c))
+                })
+                .fold(f32::INFINITY, f32::min);
+
+            assert!((closest.distance - brute_force_distance).abs() < 0.0001);
+            assert!((nalgebra::distance(point, &closest.point) - closest.distance).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_bvh_faces_overlapping_bounding_box_finds_the_box_side() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+        );
+        let bvh = Bvh::from_mesh(&mesh);
+
+        // A thin slab around the top side of the box
+        let slab = BoundingBox::new(&Point3::new(-1.0, -1.0, 0.45), &Point3::new(1.0, 1.0, 0.55));
+        let face_indices = bvh.faces_overlapping_bounding_box(&slab);
+
+        assert!(!face_indices.is_empty());
+        assert!(face_indices.len() < mesh.faces().len());
+        for face_index in face_indices {
+            let max_z = mesh.faces()[face_index]
+                .vertices()
+                .iter()
+                .map(|vertex_index| mesh.vertices()[cast_usize(*vertex_index)].z)
+                .fold(f32::NEG_INFINITY, f32::max);
+            assert!(max_z >= 0.45);
+        }
+    }
+
+    #[test]
+    fn test_bvh_ray_intersection_returns_none_for_miss() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+        );
+        let bvh = Bvh::from_mesh(&mesh);
+
+        let hit = bvh.ray_intersection(&Point3::new(0.0, 0.0, 5.0), &Vector3::new(1.0, 0.0, 0.0));
+
+        assert_eq!(hit, None);
+    }
+}
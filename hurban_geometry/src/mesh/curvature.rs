@@ -0,0 +1,184 @@
+use std::f32;
+
+use nalgebra::Vector3;
+
+use crate::convert::{cast_u32, cast_usize};
+
+use super::{analysis, Mesh, OrientedEdge};
+
+/// Discrete curvatures of each vertex of a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexCurvatures {
+    /// Mean curvature of each vertex. Positive where the surface bends
+    /// away from its normals, e.g. on the outside of a sphere.
+    pub mean: Vec<f32>,
+    /// Gaussian curvature of each vertex. Positive on elliptic (dome
+    /// like), negative on hyperbolic (saddle like) surface.
+    pub gaussian: Vec<f32>,
+}
+
+/// Estimates the mean and Gaussian curvature of each mesh vertex.
+///
+/// The mean curvature comes from the cotangent-weighted Laplacian of
+/// the vertex positions, signed by the area-weighted vertex normal.
+/// The Gaussian curvature is the angle defect of the vertex. Both are
+/// divided by the mixed Voronoi area of the vertex, as described in
+/// Meyer et al.: Discrete Differential-Geometry Operators for
+/// Triangulated 2-Manifolds.
+///
+/// Vertices on the mesh border have the angle defect measured from a
+/// straight angle instead of the full one, so that flat borders have
+/// no Gaussian curvature. Vertices not belonging to any non-degenerate
+/// face have zero curvatures.
+///
+/// The mesh should be welded, otherwise the curvatures of the vertices
+/// on its seams are only estimated from the faces on one side.
+pub fn compute_vertex_curvatures(mesh: &Mesh) -> VertexCurvatures {
+    let vertices = mesh.vertices();
+    let vertex_count = vertices.len();
+
+    let mut laplacians: Vec<Vector3<f32>> = vec![Vector3::zeros(); vertex_count];
+    let mut normals: Vec<Vector3<f32>> = vec![Vector3::zeros(); vertex_count];
+    let mut mixed_areas = vec![0.0; vertex_count];
+    let mut angle_sums = vec![0.0; vertex_count];
+
+    for face in mesh.faces() {
+        for triangle_face in face.to_triangles() {
+            let (v1, v2, v3) = triangle_face.vertices;
+            let indices = [cast_usize(v1), cast_usize(v2), cast_usize(v3)];
+            let positions = [
+                vertices[indices[0]],
+                vertices[indices[1]],
+                vertices[indices[2]],
+            ];
+
+            let cross = (positions[1] - positions[0]).cross(&(positions[2] - positions[0]));
+            let double_area = cross.norm();
+            if double_area <= 0.0 {
+                continue;
+            }
+            let area = double_area / 2.0;
+
+            let mut angles = [0.0; 3];
+            let mut cotangents = [0.0; 3];
+            for corner in 0..3 {
+                let to_next = positions[(corner + 1) % 3] - positions[corner];
+                let to_previous = positions[(corner + 2) % 3] - positions[corner];
+                angles[corner] = to_next.angle(&to_previous);
+                cotangents[corner] = to_next.dot(&to_previous) / double_area;
+            }
+            let is_obtuse = angles.iter().any(|angle| *angle > f32::consts::FRAC_PI_2);
+
+            for corner in 0..3 {
+                let next = (corner + 1) % 3;
+                let previous = (corner + 2) % 3;
+                let index = indices[corner];
+
+                // Each corner weights the edge opposite to it
+                let opposite_edge = positions[next] - positions[previous];
+                laplacians[indices[next]] += opposite_edge * cotangents[corner];
+                laplacians[indices[previous]] -= opposite_edge * cotangents[corner];
+
+                normals[index] += cross;
+                angle_sums[index] += angles[corner];
+
+                // Obtuse triangles don't contain the circumcenter,
+                // so the Voronoi region would reach outside of them
+                mixed_areas[index] += if angles[corner] > f32::consts::FRAC_PI_2 {
+                    area / 2.0
+                } else if is_obtuse {
+                    area / 4.0
+                } else {
+                    let to_next = positions[next] - positions[corner];
+                    let to_previous = positions[previous] - positions[corner];
+                    (to_next.norm_squared() * cotangents[previous]
+                        + to_previous.norm_squared() * cotangents[next])
+                        / 8.0
+                };
+            }
+        }
+    }
+
+    let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+    let edge_sharing = analysis::edge_sharing(&oriented_edges);
+    let border_vertex_indices = analysis::border_vertex_indices(&edge_sharing);
+
+    let mut mean = vec![0.0; vertex_count];
+    let mut gaussian = vec![0.0; vertex_count];
+    for vertex_index in 0..vertex_count {
+        let mixed_area = mixed_areas[vertex_index];
+        if mixed_area <= 0.0 {
+            continue;
+        }
+
+        let normal = normals[vertex_index]
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::zeros);
+        mean[vertex_index] = laplacians[vertex_index].dot(&normal) / (4.0 * mixed_area);
+
+        let full_angle = if border_vertex_indices.contains(&cast_u32(vertex_index)) {
+            f32::consts::PI
+        } else {
+            2.0 * f32::consts::PI
+        };
+        gaussian[vertex_index] = (full_angle - angle_sums[vertex_index]) / mixed_area;
+    }
+
+    VertexCurvatures { mean, gaussian }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Point3, Rotation3, Vector2};
+
+    use crate::mesh::{primitive, NormalStrategy};
+    use crate::plane::Plane;
+
+    use super::*;
+
+    #[test]
+    fn test_compute_vertex_curvatures_of_sphere_match_radius() {
+        let radius = 2.0;
+        // The sphere is scaled by its diameter
+        let sphere = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0 * radius, 2.0 * radius, 2.0 * radius),
+            32,
+            32,
+            NormalStrategy::Smooth,
+        );
+
+        let curvatures = compute_vertex_curvatures(&sphere);
+        let vertex_count = sphere.vertices().len() as f32;
+        let average_mean = curvatures.mean.iter().sum::<f32>() / vertex_count;
+        let average_gaussian = curvatures.gaussian.iter().sum::<f32>() / vertex_count;
+
+        assert!(approx::relative_eq!(
+            average_mean,
+            1.0 / radius,
+            max_relative = 0.1,
+        ));
+        assert!(approx::relative_eq!(
+            average_gaussian,
+            1.0 / (radius * radius),
+            max_relative = 0.2,
+        ));
+    }
+
+    #[test]
+    fn test_compute_vertex_curvatures_of_plane_are_zero() {
+        let plane = Plane::from_origin_and_normal(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+        );
+        let plane_mesh = primitive::create_mesh_plane(plane, Vector2::new(2.0, 2.0));
+
+        let curvatures = compute_vertex_curvatures(&plane_mesh);
+
+        for (mean, gaussian) in curvatures.mean.iter().zip(&curvatures.gaussian) {
+            assert!(approx::relative_eq!(*mean, 0.0, epsilon = 0.0001));
+            assert!(gaussian.is_finite());
+        }
+    }
+}
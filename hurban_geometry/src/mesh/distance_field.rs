@@ -0,0 +1,751 @@
+use std::collections::VecDeque;
+use std::f32;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::bounding_box::BoundingBox;
+use crate::collections::HashMap;
+use crate::convert::{cast_i32, cast_u32, cast_usize};
+use crate::geometry;
+
+use super::{Mesh, NormalStrategy};
+
+/// Number of samples around each face, within which the distances are
+/// computed exactly. Distances further away are approximated.
+const EXACT_BAND_WIDTH: i32 = 2;
+
+/// Distance of samples that are not known, e.g. outside of the block.
+const FAR_OUTSIDE_DISTANCE: f32 = f32::MAX;
+
+/// Signed distance field is a representation of a volume in a block of
+/// space. Unlike the voxel cloud, which only remembers whether a voxel is
+/// occupied, the distance field stores the distance to the closest
+/// surface of the volume for each sample: negative inside the volume and
+/// positive outside. Distances can be offset and blended, which makes
+/// operations like offsetting, smooth blending or filleting possible.
+///
+/// The samples are placed in the same voxel space as voxel cloud voxels:
+/// the block is delimited by its beginning and its dimensions, both in
+/// the units of the voxels, and the samples are in voxel centers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceField {
+    block_start: Point3<i32>,
+    block_dimensions: Vector3<u32>,
+    voxel_dimensions: Vector3<f32>,
+    distances: Vec<f32>,
+}
+
+impl DistanceField {
+    /// Define a new block of voxel space, which begins at `block_start`
+    /// (in discrete voxel units), has dimensions `block_dimensions` (in
+    /// discrete voxel units) and contains voxels sized `voxel_dimensions`
+    /// (in model space units). All samples are far outside of any volume.
+    pub fn new(
+        block_start: &Point3<i32>,
+        block_dimensions: &Vector3<u32>,
+        voxel_dimensions: &Vector3<f32>,
+    ) -> Self {
+        assert!(
+            voxel_dimensions.x > 0.0 && voxel_dimensions.y > 0.0 && voxel_dimensions.z > 0.0,
+            "One or more voxel dimensions are 0.0"
+        );
+        let map_length = block_dimensions.x * block_dimensions.y * block_dimensions.z;
+        DistanceField {
+            block_start: *block_start,
+            block_dimensions: *block_dimensions,
+            voxel_dimensions: *voxel_dimensions,
+            distances: vec![FAR_OUTSIDE_DISTANCE; cast_usize(map_length)],
+        }
+    }
+
+    /// Creates a new distance field with all samples far outside of any
+    /// volume from a bounding box defined in cartesian units.
+    pub fn from_cartesian_bounding_box(
+        bounding_box: &BoundingBox<f32>,
+        voxel_dimensions: &Vector3<f32>,
+    ) -> Self {
+        let block_start = cartesian_to_absolute_voxel_coords(
+            &bounding_box.minimum_point(),
+            voxel_dimensions,
+            f32::floor,
+        );
+        let block_end = cartesian_to_absolute_voxel_coords(
+            &bounding_box.maximum_point(),
+            voxel_dimensions,
+            f32::ceil,
+        );
+        let block_dimensions = Vector3::new(
+            cast_u32(block_end.x - block_start.x) + 1,
+            cast_u32(block_end.y - block_start.y) + 1,
+            cast_u32(block_end.z - block_start.z) + 1,
+        );
+
+        DistanceField::new(&block_start, &block_dimensions, voxel_dimensions)
+    }
+
+    /// Creates a distance field of the volume enclosed by the mesh.
+    ///
+    /// The block covers the mesh with an extra `margin` (in model space
+    /// units) around it, so that the volume can be offset outwards by up
+    /// to `margin`. Near the mesh, the distances are exact, further away
+    /// they are approximated by propagating the distances through the
+    /// grid. The sign of the distances near the mesh is decided by the
+    /// face normals, the mesh therefore should be closed and have
+    /// synchronized, outward pointing faces.
+    pub fn from_mesh(mesh: &Mesh, voxel_dimensions: &Vector3<f32>, margin: f32) -> Self {
+        assert!(margin >= 0.0, "Margin must not be negative");
+
+        let mesh_bounding_box = mesh.bounding_box();
+        let margin_vector = Vector3::repeat(margin) + voxel_dimensions * EXACT_BAND_WIDTH as f32;
+        let bounding_box = BoundingBox::new(
+            &(mesh_bounding_box.minimum_point() - margin_vector),
+            &(mesh_bounding_box.maximum_point() + margin_vector),
+        );
+
+        let mut distance_field =
+            DistanceField::from_cartesian_bounding_box(&bounding_box, voxel_dimensions);
+        let band = Vector3::repeat(EXACT_BAND_WIDTH);
+        let band_distance = voxel_dimensions.min() * EXACT_BAND_WIDTH as f32;
+        let vertices = mesh.vertices();
+
+        for face in mesh.faces() {
+            for f in face.to_triangles() {
+                let point_a = &vertices[cast_usize(f.vertices.0)];
+                let point_b = &vertices[cast_usize(f.vertices.1)];
+                let point_c = &vertices[cast_usize(f.vertices.2)];
+                // Degenerate faces have no normal to decide the sign,
+                // but the neighboring faces cover them anyway
+                let normal = match (point_b - point_a)
+                    .cross(&(point_c - point_a))
+                    .try_normalize(0.0)
+                {
+                    Some(normal) => normal,
+                    None => continue,
+                };
+
+                let face_bounding_box =
+                    BoundingBox::from_points(vec![*point_a, *point_b, *point_c])
+                        .expect("Face must have vertices");
+                let start = cartesian_to_absolute_voxel_coords(
+                    &face_bounding_box.minimum_point(),
+                    voxel_dimensions,
+                    f32::floor,
+                ) - band;
+                let end = cartesian_to_absolute_voxel_coords(
+                    &face_bounding_box.maximum_point(),
+                    voxel_dimensions,
+                    f32::ceil,
+                ) + band;
+
+                for x in start.x..=end.x {
+                    for y in start.y..=end.y {
+                        for z in start.z..=end.z {
+                            let absolute_coords = Point3::new(x, y, z);
+                            let index = match distance_field.index(&absolute_coords) {
+                                Some(index) => index,
+                                None => continue,
+                            };
+
+                            let point = absolute_voxel_to_cartesian_coords(
+                                &absolute_coords,
+                                voxel_dimensions,
+                            );
+                            let barycentric =
+                                geometry::compute_closest_point_on_triangle_barycentric_coords(
+                                    &point, point_a, point_b, point_c,
+                                );
+                            let closest = geometry::barycentric_to_cartesian(
+                                &barycentric,
+                                point_a,
+                                point_b,
+                                point_c,
+                            );
+                            let offset = point - closest;
+                            let distance = offset.norm();
+
+                            if distance < distance_field.distances[index].abs() {
+                                distance_field.distances[index] = if offset.dot(&normal) < 0.0 {
+                                    -distance
+                                } else {
+                                    distance
+                                };
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Samples further than the band width from their closest face may
+        // not have been visited by the face, and their distance and sign
+        // are not certain.
+        let exact: Vec<bool> = distance_field
+            .distances
+            .iter()
+            .map(|distance| distance.abs() <= band_distance)
+            .collect();
+        distance_field.propagate_distances(&exact);
+        distance_field
+    }
+
+    /// Returns single voxel dimensions in model space units.
+    pub fn voxel_dimensions(&self) -> Vector3<f32> {
+        self.voxel_dimensions
+    }
+
+    /// Returns the bounding box of the samples of this distance field in
+    /// world space cartesian units.
+    pub fn bounding_box_cartesian(&self) -> BoundingBox<f32> {
+        BoundingBox::new(
+            &absolute_voxel_to_cartesian_coords(&self.block_start, &self.voxel_dimensions),
+            &absolute_voxel_to_cartesian_coords(&self.block_end(), &self.voxel_dimensions),
+        )
+    }
+
+    /// Returns the signed distance at absolute voxel coordinates or `None`
+    /// if outside of the block.
+    pub fn distance_at_absolute_coords(&self, absolute_coords: &Point3<i32>) -> Option<f32> {
+        self.index(absolute_coords)
+            .map(|index| self.distances[index])
+    }
+
+    /// Returns the signed distance at a point in model space units,
+    /// trilinearly interpolated from the surrounding samples, or `None` if
+    /// some of the samples are outside of the block.
+    pub fn distance_at_cartesian_coords(&self, point: &Point3<f32>) -> Option<f32> {
+        let grid_coords = point.coords.component_div(&self.voxel_dimensions);
+        let cell_start_f32 = grid_coords.map(f32::floor);
+        let cell_start = Point3::new(
+            cell_start_f32.x as i32,
+            cell_start_f32.y as i32,
+            cell_start_f32.z as i32,
+        );
+        let weights = grid_coords - cell_start_f32;
+
+        let mut distance = 0.0;
+        for corner_index in 0..8 {
+            let corner = Vector3::new(corner_index & 1, (corner_index >> 1) & 1, corner_index >> 2);
+            let corner_weight = (0..3)
+                .map(|axis_index| {
+                    if corner[axis_index] == 1 {
+                        weights[axis_index]
+                    } else {
+                        1.0 - weights[axis_index]
+                    }
+                })
+                .product::<f32>();
+            distance += corner_weight * self.distance_at_absolute_coords(&(cell_start + corner))?;
+        }
+
+        Some(distance)
+    }
+
+    /// Returns the gradient of the signed distance at a point in model
+    /// space units, estimated from the interpolated distances half a
+    /// voxel around the point, or `None` if some of them are outside of
+    /// the block.
+    ///
+    /// Near the surface, the gradient points away from the volume and
+    /// its length is close to 1.
+    pub fn gradient_at_cartesian_coords(&self, point: &Point3<f32>) -> Option<Vector3<f32>> {
+        let mut gradient = Vector3::zeros();
+        for axis_index in 0..3 {
+            let mut step = Vector3::zeros();
+            step[axis_index] = self.voxel_dimensions[axis_index] / 2.0;
+
+            let distance_after = self.distance_at_cartesian_coords(&(point + step))?;
+            let distance_before = self.distance_at_cartesian_coords(&(point - step))?;
+            gradient[axis_index] =
+                (distance_after - distance_before) / self.voxel_dimensions[axis_index];
+        }
+
+        Some(gradient)
+    }
+
+    /// Moves the surface of the volume by `distance` in the direction of
+    /// its normals. Positive distances grow the volume, negative shrink it.
+    ///
+    /// The volume can only grow as far as the block reaches.
+    pub fn offset(&mut self, distance: f32) {
+        for sample_distance in &mut self.distances {
+            if *sample_distance < FAR_OUTSIDE_DISTANCE {
+                *sample_distance -= distance;
+            }
+        }
+    }
+
+    /// Creates a union of this and the other distance field, with the
+    /// volumes smoothly blended where they are closer than `blend_radius`.
+    ///
+    /// The resulting distance field covers both blocks and has the voxel
+    /// dimensions of this distance field. The distances of the other
+    /// distance field are interpolated if its voxels are not aligned.
+    pub fn smooth_union(&self, other: &DistanceField, blend_radius: f32) -> DistanceField {
+        let bounding_box = BoundingBox::union(vec![
+            self.bounding_box_cartesian(),
+            other.bounding_box_cartesian(),
+        ])
+        .expect("There must be bounding boxes to union");
+        let mut union =
+            DistanceField::from_cartesian_bounding_box(&bounding_box, &self.voxel_dimensions);
+
+        for index in 0..union.distances.len() {
+            let point = absolute_voxel_to_cartesian_coords(
+                &union.absolute_coords(index),
+                &union.voxel_dimensions,
+            );
+            let distance = self.distance_at_cartesian_coords(&point);
+            let other_distance = other.distance_at_cartesian_coords(&point);
+
+            union.distances[index] = match (distance, other_distance) {
+                (Some(distance), Some(other_distance)) => {
+                    smooth_min(distance, other_distance, blend_radius)
+                }
+                (Some(distance), None) | (None, Some(distance)) => distance,
+                (None, None) => FAR_OUTSIDE_DISTANCE,
+            };
+        }
+
+        union
+    }
+
+    /// Computes a mesh of the surface where the distance is zero.
+    /// Returns `None` if there is no volume.
+    ///
+    /// The mesh is extracted with naive surface nets, like in
+    /// `VoxelCloud::to_smooth_mesh`, but the vertices are placed in the
+    /// average of the zero crossings interpolated along the cell edges.
+    pub fn to_mesh(&self) -> Option<Mesh> {
+        if !self.distances.iter().any(|distance| *distance < 0.0) {
+            return None;
+        }
+
+        let axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+        let block_start = self.block_start;
+        let block_end = self.block_end();
+
+        let mut vertices: Vec<Point3<f32>> = Vec::new();
+        let mut cell_vertex_indices: HashMap<Point3<i32>, u32> = HashMap::default();
+        let mut faces: Vec<(u32, u32, u32)> = Vec::new();
+
+        // Samples just outside the block are outside of the volume,
+        // therefore the iteration starts one sample before the block to
+        // find its boundary there.
+        for x in block_start.x - 1..=block_end.x {
+            for y in block_start.y - 1..=block_end.y {
+                for z in block_start.z - 1..=block_end.z {
+                    let sample = Point3::new(x, y, z);
+                    let sample_inside = self.sample_distance(&sample) < 0.0;
+
+                    for (axis_index, axis) in axes.iter().enumerate() {
+                        let neighbor_inside = self.sample_distance(&(sample + axis)) < 0.0;
+                        if sample_inside == neighbor_inside {
+                            continue;
+                        }
+
+                        // The 4 cells around the edge between the samples,
+                        // counter-clockwise when looking against the axis
+                        let u = axes[(axis_index + 1) % 3];
+                        let v = axes[(axis_index + 2) % 3];
+                        let cells = [sample - u - v, sample - v, sample, sample - u];
+
+                        let mut quad = [0; 4];
+                        for (quad_vertex, cell) in quad.iter_mut().zip(cells.iter()) {
+                            *quad_vertex = *cell_vertex_indices.entry(*cell).or_insert_with(|| {
+                                vertices.push(self.surface_nets_cell_vertex(cell));
+                                cast_u32(vertices.len() - 1)
+                            });
+                        }
+
+                        // The quad faces along the axis, which has to point
+                        // from the inside to the outside.
+                        if sample_inside {
+                            faces.push((quad[0], quad[1], quad[2]));
+                            faces.push((quad[0], quad[2], quad[3]));
+                        } else {
+                            faces.push((quad[2], quad[1], quad[0]));
+                            faces.push((quad[3], quad[2], quad[0]));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(
+            Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+                faces,
+                vertices,
+                NormalStrategy::Smooth,
+            ),
+        )
+    }
+
+    /// Returns distance field block end in absolute voxel coordinates.
+    fn block_end(&self) -> Point3<i32> {
+        Point3::new(
+            self.block_start.x + cast_i32(self.block_dimensions.x) - 1,
+            self.block_start.y + cast_i32(self.block_dimensions.y) - 1,
+            self.block_start.z + cast_i32(self.block_dimensions.z) - 1,
+        )
+    }
+
+    /// Gets the index to the distances from absolute voxel coordinates.
+    /// Returns `None` if out of bounds.
+    fn index(&self, absolute_coords: &Point3<i32>) -> Option<usize> {
+        let relative_coords = absolute_coords - self.block_start.coords;
+        if relative_coords
+            .iter()
+            .enumerate()
+            .all(|(i, coord)| *coord >= 0 && *coord < cast_i32(self.block_dimensions[i]))
+        {
+            let dimension_x = cast_i32(self.block_dimensions.x);
+            let dimension_y = cast_i32(self.block_dimensions.y);
+            let index = (relative_coords.z * dimension_y + relative_coords.y) * dimension_x
+                + relative_coords.x;
+            Some(cast_usize(index))
+        } else {
+            None
+        }
+    }
+
+    /// Computes absolute voxel coordinates from an index to the distances.
+    fn absolute_coords(&self, index: usize) -> Point3<i32> {
+        let index_i32 = cast_i32(index);
+        let dimension_x = cast_i32(self.block_dimensions.x);
+        let dimension_y = cast_i32(self.block_dimensions.y);
+        let relative_coords = Vector3::new(
+            index_i32 % dimension_x,
+            (index_i32 / dimension_x) % dimension_y,
+            index_i32 / (dimension_x * dimension_y),
+        );
+
+        self.block_start + relative_coords
+    }
+
+    /// Returns the signed distance at the absolute voxel coordinates,
+    /// treating samples outside of the block as far outside of the volume.
+    fn sample_distance(&self, absolute_coords: &Point3<i32>) -> f32 {
+        self.distance_at_absolute_coords(absolute_coords)
+            .unwrap_or(FAR_OUTSIDE_DISTANCE)
+    }
+
+    /// Computes the surface nets vertex of the cell spanning between the
+    /// samples from `cell_start` to `cell_start + (1, 1, 1)`. The vertex is
+    /// in the average of the zero crossings on the cell edges connecting a
+    /// sample inside and a sample outside of the volume.
+    fn surface_nets_cell_vertex(&self, cell_start: &Point3<i32>) -> Point3<f32> {
+        let mut crossing_sum = Vector3::zeros();
+        let mut crossing_count = 0;
+
+        for corner_index in 0..8 {
+            let corner = Vector3::new(corner_index & 1, (corner_index >> 1) & 1, corner_index >> 2);
+            let corner_distance = self.sample_distance(&(cell_start + corner));
+
+            // Visit each edge once, from its corner with the lower
+            // coordinate along the edge axis
+            for axis_index in 0..3 {
+                if corner[axis_index] == 1 {
+                    continue;
+                }
+
+                let mut other_corner = corner;
+                other_corner[axis_index] = 1;
+                let other_corner_distance = self.sample_distance(&(cell_start + other_corner));
+
+                if (corner_distance < 0.0) != (other_corner_distance < 0.0) {
+                    let parameter = corner_distance / (corner_distance - other_corner_distance);
+                    let mut crossing =
+                        Vector3::new(corner.x as f32, corner.y as f32, corner.z as f32);
+                    crossing[axis_index] += parameter;
+                    crossing_sum += crossing;
+                    crossing_count += 1;
+                }
+            }
+        }
+
+        assert!(
+            crossing_count > 0,
+            "Surface nets vertex requested for a cell not on the boundary"
+        );
+        let position = Vector3::new(
+            cell_start.x as f32,
+            cell_start.y as f32,
+            cell_start.z as f32,
+        ) + crossing_sum / crossing_count as f32;
+
+        Point3::from(position.component_mul(&self.voxel_dimensions))
+    }
+
+    /// Approximates the distances of samples, which are not `exact`, by
+    /// propagating the exact distances through the grid in two sweeps
+    /// (chamfer distance transform). The samples are inside the volume if
+    /// they can not be reached from the block boundary without crossing
+    /// the exact samples.
+    fn propagate_distances(&mut self, exact: &[bool]) {
+        let sample_count = self.distances.len();
+
+        let mut outside = vec![false; sample_count];
+        let mut queue = VecDeque::new();
+        let block_end = self.block_end();
+        for (index, is_outside) in outside.iter_mut().enumerate() {
+            let absolute_coords = self.absolute_coords(index);
+            let on_block_boundary = (0..3).any(|axis_index| {
+                absolute_coords[axis_index] == self.block_start[axis_index]
+                    || absolute_coords[axis_index] == block_end[axis_index]
+            });
+            if on_block_boundary && !exact[index] {
+                *is_outside = true;
+                queue.push_back(absolute_coords);
+            }
+        }
+
+        let axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+        while let Some(absolute_coords) = queue.pop_front() {
+            for axis in &axes {
+                for neighbor in &[absolute_coords + axis, absolute_coords - axis] {
+                    if let Some(neighbor_index) = self.index(neighbor) {
+                        if !exact[neighbor_index] && !outside[neighbor_index] {
+                            outside[neighbor_index] = true;
+                            queue.push_back(*neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut magnitudes: Vec<f32> = self
+            .distances
+            .iter()
+            .map(|distance| distance.abs())
+            .collect();
+
+        let mut neighbor_steps = Vec::with_capacity(26);
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    if (x, y, z) != (0, 0, 0) {
+                        let neighbor = Vector3::new(x, y, z);
+                        let step = Vector3::new(x as f32, y as f32, z as f32)
+                            .component_mul(&self.voxel_dimensions)
+                            .norm();
+                        neighbor_steps.push((neighbor, step));
+                    }
+                }
+            }
+        }
+
+        // The first sweep propagates distances from the neighbors preceding
+        // the sample in the memory order, the second from the following.
+        let (preceding_steps, following_steps): (Vec<_>, Vec<_>) = neighbor_steps
+            .into_iter()
+            .partition(|(neighbor, _)| (neighbor.z, neighbor.y, neighbor.x) < (0, 0, 0));
+        let forward_indices: Vec<usize> = (0..sample_count).collect();
+        let backward_indices: Vec<usize> = (0..sample_count).rev().collect();
+
+        for (indices, steps) in &[
+            (forward_indices, preceding_steps),
+            (backward_indices, following_steps),
+        ] {
+            for index in indices {
+                if exact[*index] {
+                    continue;
+                }
+
+                let absolute_coords = self.absolute_coords(*index);
+                for (neighbor, step) in steps {
+                    if let Some(neighbor_index) = self.index(&(absolute_coords + neighbor)) {
+                        let distance = magnitudes[neighbor_index] + step;
+                        if distance < magnitudes[*index] {
+                            magnitudes[*index] = distance;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (index, distance) in self.distances.iter_mut().enumerate() {
+            if !exact[index] {
+                *distance = if outside[index] {
+                    magnitudes[index]
+                } else {
+                    -magnitudes[index]
+                };
+            }
+        }
+    }
+}
+
+/// Polynomial smooth minimum, blending the values if they are closer than
+/// `blend_radius`.
+fn smooth_min(value: f32, other_value: f32, blend_radius: f32) -> f32 {
+    if blend_radius <= 0.0 || value >= FAR_OUTSIDE_DISTANCE || other_value >= FAR_OUTSIDE_DISTANCE {
+        return value.min(other_value);
+    }
+
+    let ratio = (0.5 + 0.5 * (other_value - value) / blend_radius)
+        .max(0.0)
+        .min(1.0);
+    other_value + (value - other_value) * ratio - blend_radius * ratio * (1.0 - ratio)
+}
+
+/// Computes the voxel-space coordinates of a voxel near the input point,
+/// rounding the coordinates with `round_fn`.
+fn cartesian_to_absolute_voxel_coords(
+    point: &Point3<f32>,
+    voxel_dimensions: &Vector3<f32>,
+    round_fn: fn(f32) -> f32,
+) -> Point3<i32> {
+    Point3::new(
+        round_fn(point.x / voxel_dimensions.x) as i32,
+        round_fn(point.y / voxel_dimensions.y) as i32,
+        round_fn(point.z / voxel_dimensions.z) as i32,
+    )
+}
+
+/// Computes the center of a voxel in model-space coordinates from
+/// absolute voxel coordinates (relative to the voxel space origin).
+fn absolute_voxel_to_cartesian_coords(
+    absolute_coords: &Point3<i32>,
+    voxel_dimensions: &Vector3<f32>,
+) -> Point3<f32> {
+    Point3::new(
+        absolute_coords.x as f32 * voxel_dimensions.x,
+        absolute_coords.y as f32 * voxel_dimensions.y,
+        absolute_coords.z as f32 * voxel_dimensions.z,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Rotation3;
+
+    use crate::mesh::{analysis, primitive, OrientedEdge};
+
+    use super::*;
+
+    fn unit_box() -> Mesh {
+        primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+        )
+    }
+
+    fn sphere(center: Point3<f32>) -> Mesh {
+        primitive::create_uv_sphere(
+            center,
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+            32,
+            32,
+            NormalStrategy::Smooth,
+        )
+    }
+
+    #[test]
+    fn test_distance_field_from_mesh_for_box_has_signed_distances() {
+        let distance_field = DistanceField::from_mesh(&unit_box(), &Vector3::repeat(0.1), 1.0);
+
+        let distance_near_outside = distance_field
+            .distance_at_absolute_coords(&Point3::new(11, 0, 0))
+            .unwrap();
+        let distance_far_outside = distance_field
+            .distance_at_absolute_coords(&Point3::new(18, 0, 0))
+            .unwrap();
+        let distance_inside = distance_field
+            .distance_at_absolute_coords(&Point3::new(0, -5, 0))
+            .unwrap();
+        let distance_center = distance_field
+            .distance_at_absolute_coords(&Point3::origin())
+            .unwrap();
+
+        assert!(approx::relative_eq!(
+            distance_near_outside,
+            0.1,
+            epsilon = 0.001
+        ));
+        assert!(approx::relative_eq!(
+            distance_far_outside,
+            0.8,
+            epsilon = 0.001
+        ));
+        assert!(approx::relative_eq!(distance_inside, -0.5, epsilon = 0.001));
+        assert!(approx::relative_eq!(distance_center, -1.0, epsilon = 0.001));
+    }
+
+    #[test]
+    fn test_distance_field_to_mesh_for_sphere_produces_watertight_sphere() {
+        let distance_field =
+            DistanceField::from_mesh(&sphere(Point3::origin()), &Vector3::repeat(0.1), 0.0);
+
+        let mesh = distance_field.to_mesh().unwrap();
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        assert!(analysis::is_mesh_watertight(&edge_sharing));
+        for vertex in mesh.vertices() {
+            let radius = vertex.coords.norm();
+            assert!(radius > 0.97 && radius < 1.01);
+        }
+    }
+
+    #[test]
+    fn test_distance_field_offset_grows_box() {
+        let mut distance_field = DistanceField::from_mesh(&unit_box(), &Vector3::repeat(0.1), 0.5);
+
+        distance_field.offset(0.25);
+        let mesh = distance_field.to_mesh().unwrap();
+        let bounding_box = mesh.bounding_box();
+
+        assert!(bounding_box.minimum_point().coords.relative_eq(
+            &Vector3::repeat(-1.25),
+            0.01,
+            0.01
+        ));
+        assert!(bounding_box.maximum_point().coords.relative_eq(
+            &Vector3::repeat(1.25),
+            0.01,
+            0.01
+        ));
+    }
+
+    #[test]
+    fn test_distance_field_smooth_union_blends_below_minimum() {
+        let voxel_dimensions = Vector3::repeat(0.1);
+        let distance_field =
+            DistanceField::from_mesh(&sphere(Point3::new(-0.7, 0.0, 0.0)), &voxel_dimensions, 0.5);
+        let other_distance_field =
+            DistanceField::from_mesh(&sphere(Point3::new(0.7, 0.0, 0.0)), &voxel_dimensions, 0.5);
+        let blend_radius = 0.4;
+
+        let union = distance_field.smooth_union(&other_distance_field, blend_radius);
+
+        for x in -8..=8 {
+            let absolute_coords = Point3::new(x, 5, 0);
+            let point = absolute_voxel_to_cartesian_coords(&absolute_coords, &voxel_dimensions);
+            let minimum = distance_field
+                .distance_at_cartesian_coords(&point)
+                .unwrap()
+                .min(
+                    other_distance_field
+                        .distance_at_cartesian_coords(&point)
+                        .unwrap(),
+                );
+            let union_distance = union.distance_at_absolute_coords(&absolute_coords).unwrap();
+
+            assert!(union_distance <= minimum + 0.0001);
+            assert!(union_distance >= minimum - blend_radius / 4.0 - 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_distance_field_new_to_mesh_returns_none() {
+        let distance_field = DistanceField::new(
+            &Point3::origin(),
+            &Vector3::new(4, 4, 4),
+            &Vector3::repeat(1.0),
+        );
+
+        assert!(distance_field.to_mesh().is_none());
+    }
+}
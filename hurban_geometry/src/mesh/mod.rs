@@ -0,0 +1,2103 @@
+use std::cmp;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::IntoIterator;
+
+use arrayvec::ArrayVec;
+use nalgebra::{Point3, Vector3};
+use smallvec::SmallVec;
+
+use crate::bounding_box::BoundingBox;
+use crate::collections::HashSet;
+use crate::convert::{cast_u32, cast_usize};
+use crate::geometry;
+
+use self::normals::VertexNormals;
+
+pub mod analysis;
+pub mod bvh;
+pub mod curvature;
+pub mod distance_field;
+pub mod normals;
+pub mod primitive;
+pub mod rigid_body;
+pub mod smoothing;
+pub mod tools;
+pub mod topology;
+pub mod voxel_cloud;
+
+#[derive(Debug, Clone, Copy)]
+pub enum NormalStrategy {
+    Sharp,
+    Smooth,
+}
+
+/// Geometric data containing multiple possibly _variable-length_
+/// lists of geometric data, such as vertices and normals, and faces -
+/// a single list containing the index topology that describes the
+/// structure of data in those lists.
+///
+/// Faces are either `Face::Triangle` or `Face::Quad`, binding vertices
+/// in triangular or quadrilateral faces with counter-clockwise winding.
+/// Quads are kept where they carry meaning, e.g. when imported or made
+/// by Catmull-Clark subdivision. Tools working on triangles require
+/// triangulated meshes, see `triangulated`. Polygonal faces are not
+/// supported.
+///
+/// Each vertex has a smooth normal and face corners may override it,
+/// see `VertexNormals`.
+///
+/// The mesh data lives in right-handed coordinate space with the
+/// XY plane being the ground and Z axis growing upwards.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Mesh {
+    faces: Vec<Face>,
+    vertices: Vec<Point3<f32>>,
+    normals: VertexNormals,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    vertex_attributes: Vec<VertexAttribute>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vertex_colors: Option<Vec<[f32; 3]>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    face_materials: Option<Vec<u32>>,
+}
+
+impl Mesh {
+    /// Creates new mesh of any face kind from provided faces and
+    /// vertices. It discards the existing normals and computes normals
+    /// based on `normal_strategy` instead.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_faces_with_vertices_and_computed_normals<F, V>(
+        faces: F,
+        vertices: V,
+        normal_strategy: NormalStrategy,
+    ) -> Self
+    where
+        F: IntoIterator<Item = Face>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let vertices_collection: Vec<_> = vertices.into_iter().collect();
+        let faces_collection: Vec<_> = faces.into_iter().collect();
+
+        assert!(
+            !faces_collection.is_empty(),
+            "Empty (faceless) meshes are not supported",
+        );
+        assert_faces_in_bounds(&faces_collection, vertices_collection.len());
+
+        let normals_collection = match normal_strategy {
+            NormalStrategy::Sharp => {
+                // Each face corner gets the face normal. Vertices of
+                // coplanar faces end up with the same normal, only the
+                // corners at edges between faces meeting at an angle
+                // are overridden.
+                VertexNormals::from_face_corner_normals(
+                    faces_collection.iter().map(|face| {
+                        let face_normal = compute_face_normal(&vertices_collection, face);
+                        let corner_normals = face.vertices().iter().map(|_| face_normal).collect();
+
+                        (*face, corner_normals)
+                    }),
+                    vertices_collection.len(),
+                )
+            }
+            NormalStrategy::Smooth => {
+                let v2f = topology::compute_vertex_to_face_topology_from_components(
+                    &faces_collection,
+                    cast_u32(vertices_collection.len()),
+                );
+
+                VertexNormals::from_vertex_normals(compute_smooth_normals_from_components(
+                    &vertices_collection,
+                    &faces_collection,
+                    &v2f,
+                ))
+            }
+        };
+
+        Self {
+            faces: faces_collection,
+            vertices: vertices_collection,
+            normals: normals_collection,
+            vertex_attributes: Vec::new(),
+            vertex_colors: None,
+            face_materials: None,
+        }
+    }
+
+    /// Creates new triangulated mesh geometry from provided triangle
+    /// faces and vertices, and computes normals based on
+    /// `normal_strategy`.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_triangle_faces_with_vertices_and_computed_normals<F, V>(
+        faces: F,
+        vertices: V,
+        normal_strategy: NormalStrategy,
+    ) -> Self
+    where
+        F: IntoIterator<Item = (u32, u32, u32)>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        Self::from_faces_with_vertices_and_computed_normals(
+            faces
+                .into_iter()
+                .map(|(i1, i2, i3)| Face::from(TriangleFace::new(i1, i2, i3))),
+            vertices,
+            normal_strategy,
+        )
+    }
+
+    /// Creates new mesh of any face kind from provided faces and
+    /// vertices, removes orphan vertices, and computes normals based on
+    /// `normal_strategy`.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_faces_with_vertices_and_computed_normals_remove_orphans<F, V>(
+        faces: F,
+        vertices: V,
+        normal_strategy: NormalStrategy,
+    ) -> Self
+    where
+        F: IntoIterator<Item = Face>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let faces_collection: Vec<_> = faces.into_iter().collect();
+        let vertices_collection: Vec<_> = vertices.into_iter().collect();
+        assert_faces_in_bounds(&faces_collection, vertices_collection.len());
+
+        let vertex_map = orphan_removal_vertex_map(&faces_collection, vertices_collection.len());
+        let mut vertices_purged = vec![Point3::origin(); vertex_map.iter().flatten().count()];
+        for (vertex, vertex_index) in vertices_collection.iter().zip(&vertex_map) {
+            if let Some(vertex_index) = vertex_index {
+                vertices_purged[cast_usize(*vertex_index)] = *vertex;
+            }
+        }
+        let new_index = |vertex_index: u32| {
+            vertex_map[cast_usize(vertex_index)].expect("Face vertices must not be orphans")
+        };
+
+        Self::from_faces_with_vertices_and_computed_normals(
+            faces_collection
+                .iter()
+                .map(|face| face.map_vertices(new_index)),
+            vertices_purged,
+            normal_strategy,
+        )
+    }
+
+    /// Creates new triangulated mesh geometry from provided triangle
+    /// faces and vertices, removes orphan vertices, and computes
+    /// normals based on `normal_strategy`.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_triangle_faces_with_vertices_and_computed_normals_remove_orphans<F, V>(
+        faces: F,
+        vertices: V,
+        normal_strategy: NormalStrategy,
+    ) -> Self
+    where
+        F: IntoIterator<Item = (u32, u32, u32)>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let (faces_purged, vertices_purged) =
+            remove_orphan_vertices(faces.into_iter().collect(), vertices.into_iter().collect());
+        Self::from_triangle_faces_with_vertices_and_computed_normals(
+            faces_purged,
+            vertices_purged,
+            normal_strategy,
+        )
+    }
+
+    /// Creates new triangulated mesh geometry from provided triangle
+    /// faces, vertices and canonical normals, with a normal for each
+    /// vertex.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices or if the normals
+    /// are not for the faces and vertices.
+    pub fn from_triangle_faces_with_vertices_and_vertex_normals<F, V>(
+        faces: F,
+        vertices: V,
+        vertex_normals: VertexNormals,
+    ) -> Self
+    where
+        F: IntoIterator<Item = (u32, u32, u32)>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        Self::from_faces_with_vertices_and_vertex_normals(
+            faces
+                .into_iter()
+                .map(|(i1, i2, i3)| Face::from(TriangleFace::new(i1, i2, i3))),
+            vertices,
+            vertex_normals,
+        )
+    }
+
+    /// Creates new mesh of any face kind from provided faces, vertices
+    /// and canonical normals, with a normal for each vertex.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices or if the normals
+    /// are not for the faces and vertices.
+    pub fn from_faces_with_vertices_and_vertex_normals<F, V>(
+        faces: F,
+        vertices: V,
+        vertex_normals: VertexNormals,
+    ) -> Self
+    where
+        F: IntoIterator<Item = Face>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let faces_collection: Vec<_> = faces.into_iter().collect();
+        assert!(
+            !faces_collection.is_empty(),
+            "Empty (faceless) meshes are not supported.",
+        );
+
+        let vertices_collection: Vec<_> = vertices.into_iter().collect();
+        assert_faces_in_bounds(&faces_collection, vertices_collection.len());
+        assert_eq!(
+            vertex_normals.vertex_count(),
+            vertices_collection.len(),
+            "Each vertex must have a normal",
+        );
+        assert!(
+            vertex_normals.is_consistent_with(&faces_collection, vertices_collection.len()),
+            "Normal overrides must refer to face corners",
+        );
+
+        Self {
+            faces: faces_collection,
+            vertices: vertices_collection,
+            normals: vertex_normals,
+            vertex_attributes: Vec::new(),
+            vertex_colors: None,
+            face_materials: None,
+        }
+    }
+
+    /// Creates new triangulated mesh geometry from provided triangle
+    /// faces with the normals of their corners and vertices. See
+    /// `VertexNormals::from_corner_normals`.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_triangle_faces_with_vertices_and_corner_normals<F, V>(faces: F, vertices: V) -> Self
+    where
+        F: IntoIterator<Item = ((u32, u32, u32), [Vector3<f32>; 3])>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let (faces_collection, corner_normals): (Vec<_>, Vec<_>) = faces.into_iter().unzip();
+        let vertices_collection: Vec<_> = vertices.into_iter().collect();
+        assert!(
+            !faces_collection.is_empty(),
+            "Empty (faceless) meshes are not supported.",
+        );
+        let vertices_range = 0..cast_u32(vertices_collection.len());
+        assert!(
+            faces_collection.iter().all(|(v1, v2, v3)| {
+                vertices_range.contains(v1)
+                    && vertices_range.contains(v2)
+                    && vertices_range.contains(v3)
+            }),
+            "Faces reference out of bounds position data"
+        );
+
+        let vertex_normals = VertexNormals::from_corner_normals(
+            faces_collection.iter().copied().zip(corner_normals),
+            vertices_collection.len(),
+        );
+
+        Self::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces_collection,
+            vertices_collection,
+            vertex_normals,
+        )
+    }
+
+    /// Creates new triangulated mesh geometry from provided triangle
+    /// faces with the normals of their corners and vertices, and
+    /// removes orphan vertices.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices.
+    pub fn from_triangle_faces_with_vertices_and_corner_normals_remove_orphans<F, V>(
+        faces: F,
+        vertices: V,
+    ) -> Self
+    where
+        F: IntoIterator<Item = ((u32, u32, u32), [Vector3<f32>; 3])>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let (faces_collection, corner_normals): (Vec<_>, Vec<_>) = faces.into_iter().unzip();
+        let (faces_purged, vertices_purged) =
+            remove_orphan_vertices(faces_collection, vertices.into_iter().collect());
+
+        Self::from_triangle_faces_with_vertices_and_corner_normals(
+            faces_purged.into_iter().zip(corner_normals),
+            vertices_purged,
+        )
+    }
+
+    /// Creates new mesh of any face kind from provided faces, vertices
+    /// and canonical normals, and removes orphan vertices.
+    ///
+    /// # Panics
+    /// Panics if faces refer to out-of-bounds vertices or if the normals
+    /// are not for the faces and vertices.
+    pub fn from_faces_with_vertices_and_vertex_normals_remove_orphans<F, V>(
+        faces: F,
+        vertices: V,
+        vertex_normals: VertexNormals,
+    ) -> Self
+    where
+        F: IntoIterator<Item = Face>,
+        V: IntoIterator<Item = Point3<f32>>,
+    {
+        let faces_collection: Vec<_> = faces.into_iter().collect();
+        let vertices_collection: Vec<_> = vertices.into_iter().collect();
+        assert_faces_in_bounds(&faces_collection, vertices_collection.len());
+
+        let vertex_map = orphan_removal_vertex_map(&faces_collection, vertices_collection.len());
+        let mut vertices_purged = vec![Point3::origin(); vertex_map.iter().flatten().count()];
+        for (vertex, vertex_index) in vertices_collection.iter().zip(&vertex_map) {
+            if let Some(vertex_index) = vertex_index {
+                vertices_purged[cast_usize(*vertex_index)] = *vertex;
+            }
+        }
+        let new_index = |vertex_index: u32| {
+            vertex_map[cast_usize(vertex_index)].expect("Face vertices must not be orphans")
+        };
+        let faces_purged = faces_collection
+            .iter()
+            .map(|face| face.map_vertices(new_index));
+
+        Self::from_faces_with_vertices_and_vertex_normals(
+            faces_purged,
+            vertices_purged,
+            vertex_normals.remove_orphan_vertices(&vertex_map),
+        )
+    }
+
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    pub fn faces_mut(&mut self) -> &mut [Face] {
+        &mut self.faces
+    }
+
+    pub fn vertices(&self) -> &[Point3<f32>] {
+        &self.vertices
+    }
+
+    pub fn vertices_mut(&mut self) -> &mut [Point3<f32>] {
+        &mut self.vertices
+    }
+
+    /// Returns the normals of the mesh: a smooth normal for each vertex
+    /// and the overrides of the face corners differing from them.
+    pub fn vertex_normals(&self) -> &VertexNormals {
+        &self.normals
+    }
+
+    /// Returns all the stored normals of the mesh, i.e. the vertex
+    /// normals and the corner overrides, e.g. to transform them.
+    pub fn normals_mut(&mut self) -> impl Iterator<Item = &mut Vector3<f32>> + '_ {
+        self.normals.normals_mut()
+    }
+
+    /// Returns the normals at the corners of the triangle face, in the
+    /// order of its vertices.
+    ///
+    /// # Panics
+    /// Panics if the face index is out of bounds or if the face is not a
+    /// triangle.
+    pub fn corner_normals(&self, face_index: usize) -> [Vector3<f32>; 3] {
+        let triangle_face = self.triangle_face(face_index);
+        self.normals
+            .face_corner_normals(face_index, triangle_face.vertices)
+    }
+
+    /// Returns the normals at the corners of the face of any kind, in
+    /// the order of its vertices.
+    ///
+    /// # Panics
+    /// Panics if the face index is out of bounds.
+    pub fn face_corner_normals(&self, face_index: usize) -> ArrayVec<[Vector3<f32>; 4]> {
+        self.faces[face_index]
+            .vertices()
+            .iter()
+            .map(|vertex_index| self.normals.corner_normal(face_index, *vertex_index))
+            .collect()
+    }
+
+    /// Returns the triangle face at `face_index` of a triangulated mesh,
+    /// e.g. in tools working on triangles.
+    ///
+    /// # Panics
+    /// Panics if the face index is out of bounds or if the face is not a
+    /// triangle.
+    pub fn triangle_face(&self, face_index: usize) -> TriangleFace {
+        match self.faces[face_index] {
+            Face::Triangle(triangle_face) => triangle_face,
+            Face::Quad(_) => panic!("The mesh must be triangulated"),
+        }
+    }
+
+    /// Returns the faces of a triangulated mesh as triangle faces, e.g.
+    /// in tools working on triangles.
+    ///
+    /// # Panics
+    /// Panics if the mesh contains faces other than triangles.
+    pub fn triangle_faces_iter<'a>(&'a self) -> impl Iterator<Item = TriangleFace> + 'a {
+        self.faces.iter().map(|face| match face {
+            Face::Triangle(triangle_face) => *triangle_face,
+            Face::Quad(_) => panic!("The mesh must be triangulated"),
+        })
+    }
+
+    /// Returns the mesh with its quads split into triangles, see
+    /// `Face::to_triangles`, for the tools working on triangles. The
+    /// vertices and their data are kept. The triangles keep the corner
+    /// normals and the material of the face they were split from.
+    pub fn triangulated(&self) -> Mesh {
+        if self.is_triangulated() {
+            return self.clone();
+        }
+
+        let mut faces = Vec::with_capacity(self.faces.len() * 2);
+        let mut face_materials = self
+            .face_materials
+            .as_ref()
+            .map(|_| Vec::with_capacity(self.faces.len() * 2));
+        for (face_index, face) in self.faces.iter().enumerate() {
+            for triangle_face in face.to_triangles() {
+                faces.push(Face::Triangle(triangle_face));
+                if let (Some(face_materials), Some(materials)) =
+                    (&mut face_materials, &self.face_materials)
+                {
+                    face_materials.push(materials[face_index]);
+                }
+            }
+        }
+
+        Mesh {
+            faces,
+            vertices: self.vertices.clone(),
+            normals: self.normals.triangulated(&self.faces),
+            vertex_attributes: self.vertex_attributes.clone(),
+            vertex_colors: self.vertex_colors.clone(),
+            face_materials,
+        }
+    }
+
+    /// Returns all named per-vertex attributes of the mesh.
+    pub fn vertex_attributes(&self) -> &[VertexAttribute] {
+        &self.vertex_attributes
+    }
+
+    /// Returns the per-vertex attribute with `name`, if present.
+    pub fn vertex_attribute(&self, name: &str) -> Option<&VertexAttribute> {
+        self.vertex_attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Sets a named per-vertex attribute, replacing the attribute of
+    /// the same name if it already exists.
+    ///
+    /// # Panics
+    /// Panics if the number of values differs from the number of
+    /// mesh vertices.
+    pub fn set_vertex_attribute<S: Into<String>>(&mut self, name: S, values: Vec<f32>) {
+        assert_eq!(
+            values.len(),
+            self.vertices.len(),
+            "Vertex attribute must have a value for each vertex",
+        );
+
+        let name = name.into();
+        if let Some(attribute) = self
+            .vertex_attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+        {
+            attribute.values = values;
+        } else {
+            self.vertex_attributes
+                .push(VertexAttribute { name, values });
+        }
+    }
+
+    /// Returns the per-vertex display colors of the mesh, if set.
+    pub fn vertex_colors(&self) -> Option<&[[f32; 3]]> {
+        self.vertex_colors.as_ref().map(|colors| &colors[..])
+    }
+
+    /// Sets per-vertex display colors of the mesh. The colors are
+    /// linear RGB.
+    ///
+    /// # Panics
+    /// Panics if the number of colors differs from the number of
+    /// mesh vertices.
+    pub fn set_vertex_colors(&mut self, colors: Vec<[f32; 3]>) {
+        assert_eq!(
+            colors.len(),
+            self.vertices.len(),
+            "Vertex colors must have a color for each vertex",
+        );
+
+        self.vertex_colors = Some(colors);
+    }
+
+    /// Returns the material index of each face of the mesh, if set.
+    /// Faces of meshes without materials all have the material 0.
+    pub fn face_materials(&self) -> Option<&[u32]> {
+        self.face_materials.as_ref().map(|materials| &materials[..])
+    }
+
+    /// Sets the material index of each face of the mesh, e.g. so that
+    /// the parts of the mesh are exported as separate submeshes.
+    ///
+    /// # Panics
+    /// Panics if the number of materials differs from the number of
+    /// mesh faces.
+    pub fn set_face_materials(&mut self, materials: Vec<u32>) {
+        assert_eq!(
+            materials.len(),
+            self.faces.len(),
+            "Face materials must have a material for each face",
+        );
+
+        self.face_materials = Some(materials);
+    }
+
+    /// Copies the face materials of `source`, whose faces correspond
+    /// one to one to the faces of this mesh, e.g. because this mesh was
+    /// made by moving the source vertices.
+    ///
+    /// # Panics
+    /// Panics if the meshes have a different number of faces.
+    pub fn copy_face_materials_from(&mut self, source: &Mesh) {
+        assert_eq!(
+            self.faces.len(),
+            source.faces.len(),
+            "Meshes must have the same number of faces",
+        );
+
+        self.face_materials = source.face_materials.clone();
+    }
+
+    /// Copies the vertex attributes and colors of `source`, whose
+    /// vertices correspond one to one to the vertices of this mesh,
+    /// e.g. because this mesh was made by moving the source vertices
+    /// or by rewinding the source faces.
+    ///
+    /// # Panics
+    /// Panics if the meshes have a different number of vertices.
+    pub fn copy_vertex_data_from(&mut self, source: &Mesh) {
+        assert_eq!(
+            self.vertices.len(),
+            source.vertices.len(),
+            "Meshes must have the same number of vertices",
+        );
+
+        self.vertex_attributes = source.vertex_attributes.clone();
+        self.vertex_colors = source.vertex_colors.clone();
+    }
+
+    /// Carries the vertex attributes and colors of `source` over to
+    /// this mesh, whose vertices were made from the source vertices.
+    /// The `vertex_map` has the index of the vertex of this mesh made
+    /// from each source vertex, or `None` if the source vertex was
+    /// removed. Values of source vertices merged into a single vertex
+    /// are averaged.
+    ///
+    /// # Panics
+    /// Panics if the map doesn't have an entry for each source vertex,
+    /// or if a vertex of this mesh has no source vertex.
+    pub fn remap_vertex_data_from(&mut self, source: &Mesh, vertex_map: &[Option<u32>]) {
+        assert_eq!(
+            vertex_map.len(),
+            source.vertices.len(),
+            "Vertex map must have an entry for each source vertex",
+        );
+
+        let mut source_counts = vec![0u32; self.vertices.len()];
+        for vertex_index in vertex_map.iter().flatten() {
+            source_counts[cast_usize(*vertex_index)] += 1;
+        }
+        assert!(
+            source_counts.iter().all(|count| *count > 0),
+            "Each vertex must have a source vertex",
+        );
+
+        self.vertex_attributes = source
+            .vertex_attributes
+            .iter()
+            .map(|attribute| VertexAttribute {
+                name: attribute.name.clone(),
+                values: average_mapped_vertex_values(
+                    &attribute.values,
+                    1,
+                    vertex_map,
+                    &source_counts,
+                ),
+            })
+            .collect();
+        self.vertex_colors = source.vertex_colors.as_ref().map(|colors| {
+            let channels: Vec<f32> = colors
+                .iter()
+                .flat_map(|color| color.iter().copied())
+                .collect();
+            average_mapped_vertex_values(&channels, 3, vertex_map, &source_counts)
+                .chunks(3)
+                .map(|color| [color[0], color[1], color[2]])
+                .collect()
+        });
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox<f32> {
+        let points = self.vertices();
+
+        BoundingBox::from_points(points.iter().copied())
+            .expect("Trying to compute a bounding box of an empty mesh")
+    }
+
+    /// Extracts oriented edges from all mesh faces.
+    pub fn oriented_edges_iter<'a>(&'a self) -> impl Iterator<Item = OrientedEdge> + 'a {
+        self.faces.iter().flat_map(Face::to_oriented_edges)
+    }
+
+    /// Extracts unoriented edges from all mesh faces.
+    pub fn unoriented_edges_iter<'a>(&'a self) -> impl Iterator<Item = UnorientedEdge> + 'a {
+        self.faces.iter().flat_map(Face::to_unoriented_edges)
+    }
+
+    /// Returns whether the mesh is triangulated - contains
+    /// exclusively triangle faces.
+    pub fn is_triangulated(&self) -> bool {
+        self.faces().iter().all(|face| match face {
+            Face::Triangle(_) => true,
+            Face::Quad(_) => false,
+        })
+    }
+
+    /// Returns whether the mesh contains unused (not referenced in
+    /// faces) vertices.
+    pub fn has_no_orphan_vertices(&self) -> bool {
+        let mut used_vertices = HashSet::default();
+
+        for face in self.faces() {
+            used_vertices.extend(face.vertices());
+        }
+
+        used_vertices.len() == self.vertices().len()
+    }
+
+    /// Returns whether the mesh upholds the invariants its constructors
+    /// and setters ensure: it has faces, faces only refer to existing
+    /// vertices, the normals are for the faces and vertices, and the
+    /// vertex attributes, vertex colors and face materials have a value
+    /// for each vertex or face.
+    ///
+    /// Meshes deserialized from untrusted data, e.g. loaded from a file
+    /// or received over the network, must be checked before use.
+    pub fn is_consistent(&self) -> bool {
+        let vertex_count = self.vertices.len();
+        let face_count = self.faces.len();
+        let in_bounds = |index: u32| cast_usize(index) < vertex_count;
+
+        let faces_valid = self
+            .faces
+            .iter()
+            .all(|face| face.vertices().iter().all(|v| in_bounds(*v)));
+        let normals_valid =
+            faces_valid && self.normals.is_consistent_with(&self.faces, vertex_count);
+
+        let attributes_valid = self
+            .vertex_attributes
+            .iter()
+            .all(|attribute| attribute.values.len() == vertex_count);
+        let colors_valid = self
+            .vertex_colors
+            .as_ref()
+            .map_or(true, |colors| colors.len() == vertex_count);
+        let materials_valid = self
+            .face_materials
+            .as_ref()
+            .map_or(true, |materials| materials.len() == face_count);
+
+        face_count > 0
+            && faces_valid
+            && normals_valid
+            && attributes_valid
+            && colors_valid
+            && materials_valid
+    }
+}
+
+impl fmt::Display for Mesh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let vertices: Vec<_> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{}: {}", i, v))
+            .collect();
+        let faces: Vec<_> = self
+            .faces
+            .iter()
+            .enumerate()
+            .map(|(i, f)| format!("{}: {}", i, f))
+            .collect();
+        let normals: Vec<_> = self
+            .normals
+            .vertex_normals()
+            .iter()
+            .enumerate()
+            .map(|(i, n)| format!("{}: ({}, {}, {})", i, n.x, n.y, n.z))
+            .collect();
+
+        writeln!(
+            f,
+            "G(\nV({}): {:?},\nN({}): {:?},\nF({}): {:?}\n)",
+            self.vertices.len(),
+            vertices,
+            normals.len(),
+            normals,
+            self.faces.len(),
+            faces,
+        )
+    }
+}
+
+/// Named per-vertex scalar data attached to a mesh, e.g. a weight
+/// or a result of an analysis. Contains exactly one value for each
+/// mesh vertex.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VertexAttribute {
+    name: String,
+    values: Vec<f32>,
+}
+
+impl VertexAttribute {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+/// A mesh face. Contains indices to other mesh data, such as vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Face {
+    Triangle(TriangleFace),
+    Quad(QuadFace),
+}
+
+impl Face {
+    /// Returns the vertex indices of the face in the winding order.
+    pub fn vertices(&self) -> ArrayVec<[u32; 4]> {
+        match self {
+            Face::Triangle(triangle_face) => {
+                let (v1, v2, v3) = triangle_face.vertices;
+                [v1, v2, v3].iter().copied().collect()
+            }
+            Face::Quad(quad_face) => {
+                let (v1, v2, v3, v4) = quad_face.vertices;
+                ArrayVec::from([v1, v2, v3, v4])
+            }
+        }
+    }
+
+    pub fn contains_vertex(&self, vertex_index: u32) -> bool {
+        match self {
+            Face::Triangle(triangle_face) => triangle_face.contains_vertex(vertex_index),
+            Face::Quad(quad_face) => quad_face.contains_vertex(vertex_index),
+        }
+    }
+
+    /// Generates the oriented edges of the face in the winding order.
+    pub fn to_oriented_edges(&self) -> ArrayVec<[OrientedEdge; 4]> {
+        match self {
+            Face::Triangle(triangle_face) => {
+                triangle_face.to_oriented_edges().iter().copied().collect()
+            }
+            Face::Quad(quad_face) => ArrayVec::from(quad_face.to_oriented_edges()),
+        }
+    }
+
+    /// Generates the unoriented edges of the face in the winding order.
+    pub fn to_unoriented_edges(&self) -> ArrayVec<[UnorientedEdge; 4]> {
+        match self {
+            Face::Triangle(triangle_face) => triangle_face
+                .to_unoriented_edges()
+                .iter()
+                .copied()
+                .collect(),
+            Face::Quad(quad_face) => ArrayVec::from(quad_face.to_unoriented_edges()),
+        }
+    }
+
+    /// Returns whether the face contains the oriented edge.
+    pub fn contains_oriented_edge(&self, oriented_edge: OrientedEdge) -> bool {
+        self.to_oriented_edges().contains(&oriented_edge)
+    }
+
+    /// Returns whether the face contains the unoriented edge.
+    pub fn contains_unoriented_edge(&self, unoriented_edge: UnorientedEdge) -> bool {
+        self.to_unoriented_edges().contains(&unoriented_edge)
+    }
+
+    /// Returns the same face with reverted vertex winding.
+    pub fn to_reverted(&self) -> Face {
+        match self {
+            Face::Triangle(triangle_face) => Face::Triangle(triangle_face.to_reverted()),
+            Face::Quad(quad_face) => Face::Quad(quad_face.to_reverted()),
+        }
+    }
+
+    /// Returns the triangles covering the face: the face itself, if it
+    /// is a triangle, or the two halves of a quad, see
+    /// `QuadFace::to_triangles`.
+    pub fn to_triangles(&self) -> ArrayVec<[TriangleFace; 2]> {
+        match self {
+            Face::Triangle(triangle_face) => {
+                let mut triangles = ArrayVec::new();
+                triangles.push(*triangle_face);
+                triangles
+            }
+            Face::Quad(quad_face) => ArrayVec::from(quad_face.to_triangles()),
+        }
+    }
+
+    /// Returns the face of the same kind with each vertex index mapped
+    /// by `map`, e.g. after the vertices were renumbered.
+    ///
+    /// # Panics
+    /// Panics if vertices of the face are mapped to the same index.
+    pub fn map_vertices<M: Fn(u32) -> u32>(&self, map: M) -> Face {
+        match self {
+            Face::Triangle(triangle_face) => {
+                let (v1, v2, v3) = triangle_face.vertices;
+                Face::Triangle(TriangleFace::new(map(v1), map(v2), map(v3)))
+            }
+            Face::Quad(quad_face) => {
+                let (v1, v2, v3, v4) = quad_face.vertices;
+                Face::Quad(QuadFace::new(map(v1), map(v2), map(v3), map(v4)))
+            }
+        }
+    }
+}
+
+impl From<TriangleFace> for Face {
+    fn from(triangle_face: TriangleFace) -> Face {
+        Face::Triangle(triangle_face)
+    }
+}
+
+impl From<QuadFace> for Face {
+    fn from(quad_face: QuadFace) -> Face {
+        Face::Quad(quad_face)
+    }
+}
+
+impl fmt::Display for Face {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Face::Triangle(face) => write!(f, "{}", face),
+            Face::Quad(face) => write!(f, "{}", face),
+        }
+    }
+}
+
+/// A triangular mesh face. Contains indices to other mesh data, such
+/// as vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TriangleFace {
+    pub vertices: (u32, u32, u32),
+}
+
+impl TriangleFace {
+    pub fn new(vi1: u32, vi2: u32, vi3: u32) -> TriangleFace {
+        assert!(
+            vi1 != vi2 && vi1 != vi3 && vi2 != vi3,
+            "One or more face edges consists of the same vertex"
+        );
+
+        if vi1 < vi2 && vi1 < vi3 {
+            TriangleFace {
+                vertices: (vi1, vi2, vi3),
+            }
+        } else if vi2 < vi1 && vi2 < vi3 {
+            TriangleFace {
+                vertices: (vi2, vi3, vi1),
+            }
+        } else {
+            TriangleFace {
+                vertices: (vi3, vi1, vi2),
+            }
+        }
+    }
+
+    /// Generates 3 oriented edges from the respective triangular face.
+    pub fn to_oriented_edges(&self) -> [OrientedEdge; 3] {
+        [
+            OrientedEdge::new(self.vertices.0, self.vertices.1),
+            OrientedEdge::new(self.vertices.1, self.vertices.2),
+            OrientedEdge::new(self.vertices.2, self.vertices.0),
+        ]
+    }
+
+    /// Generates 3 unoriented edges from the respective triangular face.
+    pub fn to_unoriented_edges(&self) -> [UnorientedEdge; 3] {
+        [
+            UnorientedEdge(OrientedEdge::new(self.vertices.0, self.vertices.1)),
+            UnorientedEdge(OrientedEdge::new(self.vertices.1, self.vertices.2)),
+            UnorientedEdge(OrientedEdge::new(self.vertices.2, self.vertices.0)),
+        ]
+    }
+
+    /// Returns whether the face contains the vertex index.
+    pub fn contains_vertex(&self, vertex_index: u32) -> bool {
+        self.vertices.0 == vertex_index
+            || self.vertices.1 == vertex_index
+            || self.vertices.2 == vertex_index
+    }
+
+    /// Returns whether the face contains the oriented edge.
+    pub fn contains_oriented_edge(&self, oriented_edge: OrientedEdge) -> bool {
+        let [oe0, oe1, oe2] = self.to_oriented_edges();
+        oe0 == oriented_edge || oe1 == oriented_edge || oe2 == oriented_edge
+    }
+
+    /// Returns whether the face contains the unoriented edge.
+    pub fn contains_unoriented_edge(&self, unoriented_edge: UnorientedEdge) -> bool {
+        let [ue0, ue1, ue2] = self.to_unoriented_edges();
+        ue0 == unoriented_edge || ue1 == unoriented_edge || ue2 == unoriented_edge
+    }
+
+    /// Returns the same face with reverted vertex winding.
+    pub fn to_reverted(&self) -> TriangleFace {
+        TriangleFace::new(self.vertices.2, self.vertices.1, self.vertices.0)
+    }
+
+    /// Checks if the other face references the same vertices in a
+    /// reverted order.
+    pub fn is_reverted(&self, other: &Self) -> bool {
+        self.to_reverted() == *other
+    }
+}
+
+impl From<(u32, u32, u32)> for TriangleFace {
+    fn from((i1, i2, i3): (u32, u32, u32)) -> TriangleFace {
+        TriangleFace::new(i1, i2, i3)
+    }
+}
+
+impl fmt::Display for TriangleFace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "T(V: ({}, {}, {}))",
+            self.vertices.0, self.vertices.1, self.vertices.2,
+        )
+    }
+}
+
+/// A quadrilateral mesh face. Contains indices to other mesh data,
+/// such as vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct QuadFace {
+    pub vertices: (u32, u32, u32, u32),
+}
+
+impl QuadFace {
+    /// Creates a quad face starting its winding from the lowest vertex
+    /// index, the same as `TriangleFace::new`.
+    pub fn new(vi1: u32, vi2: u32, vi3: u32, vi4: u32) -> QuadFace {
+        assert!(
+            vi1 != vi2 && vi1 != vi3 && vi1 != vi4 && vi2 != vi3 && vi2 != vi4 && vi3 != vi4,
+            "One or more face edges consists of the same vertex"
+        );
+
+        let lowest = cmp::min(cmp::min(vi1, vi2), cmp::min(vi3, vi4));
+        let vertices = if lowest == vi1 {
+            (vi1, vi2, vi3, vi4)
+        } else if lowest == vi2 {
+            (vi2, vi3, vi4, vi1)
+        } else if lowest == vi3 {
+            (vi3, vi4, vi1, vi2)
+        } else {
+            (vi4, vi1, vi2, vi3)
+        };
+
+        QuadFace { vertices }
+    }
+
+    /// Generates 4 oriented edges from the respective quad face.
+    pub fn to_oriented_edges(&self) -> [OrientedEdge; 4] {
+        let (v1, v2, v3, v4) = self.vertices;
+        [
+            OrientedEdge::new(v1, v2),
+            OrientedEdge::new(v2, v3),
+            OrientedEdge::new(v3, v4),
+            OrientedEdge::new(v4, v1),
+        ]
+    }
+
+    /// Generates 4 unoriented edges from the respective quad face.
+    pub fn to_unoriented_edges(&self) -> [UnorientedEdge; 4] {
+        let [e1, e2, e3, e4] = self.to_oriented_edges();
+        [
+            UnorientedEdge(e1),
+            UnorientedEdge(e2),
+            UnorientedEdge(e3),
+            UnorientedEdge(e4),
+        ]
+    }
+
+    /// Returns whether the face contains the vertex index.
+    pub fn contains_vertex(&self, vertex_index: u32) -> bool {
+        self.vertices.0 == vertex_index
+            || self.vertices.1 == vertex_index
+            || self.vertices.2 == vertex_index
+            || self.vertices.3 == vertex_index
+    }
+
+    /// Returns the same face with reverted vertex winding.
+    pub fn to_reverted(&self) -> QuadFace {
+        let (v1, v2, v3, v4) = self.vertices;
+        QuadFace::new(v4, v3, v2, v1)
+    }
+
+    /// Splits the quad into two triangles along the diagonal from its
+    /// first vertex.
+    pub fn to_triangles(&self) -> [TriangleFace; 2] {
+        let (v1, v2, v3, v4) = self.vertices;
+        [TriangleFace::new(v1, v2, v3), TriangleFace::new(v1, v3, v4)]
+    }
+}
+
+impl From<(u32, u32, u32, u32)> for QuadFace {
+    fn from((i1, i2, i3, i4): (u32, u32, u32, u32)) -> QuadFace {
+        QuadFace::new(i1, i2, i3, i4)
+    }
+}
+
+impl fmt::Display for QuadFace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Q(V: ({}, {}, {}, {}))",
+            self.vertices.0, self.vertices.1, self.vertices.2, self.vertices.3,
+        )
+    }
+}
+
+/// Oriented face edge.
+///
+/// Contains indices to other mesh data - vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OrientedEdge {
+    pub vertices: (u32, u32),
+}
+
+impl OrientedEdge {
+    pub fn new(i1: u32, i2: u32) -> Self {
+        assert!(
+            i1 != i2,
+            "The oriented edge is constituted of the same vertex"
+        );
+        OrientedEdge { vertices: (i1, i2) }
+    }
+
+    pub fn is_reverted(self, other: OrientedEdge) -> bool {
+        self.vertices.0 == other.vertices.1 && self.vertices.1 == other.vertices.0
+    }
+
+    pub fn contains_vertex(self, vertex_index: u32) -> bool {
+        self.vertices.0 == vertex_index || self.vertices.1 == vertex_index
+    }
+
+    pub fn to_reverted(self) -> Self {
+        OrientedEdge::new(self.vertices.1, self.vertices.0)
+    }
+
+    pub fn to_unoriented(self) -> UnorientedEdge {
+        UnorientedEdge(self)
+    }
+}
+
+/// Unoriented face edge.
+///
+/// Contains indices to other mesh data - vertices. Implements
+/// orientation indifferent hash and equal methods
+#[derive(Debug, Clone, Copy, Eq)]
+pub struct UnorientedEdge(pub OrientedEdge);
+
+impl UnorientedEdge {
+    pub fn shares_vertex(self, other: UnorientedEdge) -> bool {
+        other.0.contains_vertex(self.0.vertices.0) || other.0.contains_vertex(self.0.vertices.1)
+    }
+}
+
+impl PartialEq for UnorientedEdge {
+    fn eq(&self, other: &Self) -> bool {
+        (self.0.vertices.0 == other.0.vertices.0 && self.0.vertices.1 == other.0.vertices.1)
+            || (self.0.vertices.0 == other.0.vertices.1 && self.0.vertices.1 == other.0.vertices.0)
+    }
+}
+
+// FIXME: test
+impl Hash for UnorientedEdge {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        cmp::min(self.0.vertices.0, self.0.vertices.1).hash(state);
+        cmp::max(self.0.vertices.0, self.0.vertices.1).hash(state);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn remove_orphan_vertices(
+    faces: Vec<(u32, u32, u32)>,
+    vertices: Vec<Point3<f32>>,
+) -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+    let mut vertices_reduced: Vec<Point3<f32>> = Vec::with_capacity(vertices.len());
+    let original_vertex_len = vertices.len();
+    let unused_vertex_marker = vertices.len();
+    let mut old_new_vertex_map: Vec<usize> = vec![unused_vertex_marker; original_vertex_len];
+    let mut faces_renumbered: Vec<(u32, u32, u32)> = Vec::with_capacity(faces.len());
+
+    for face in faces {
+        let old_vertex_index_0 = cast_usize(face.0);
+        let new_vertex_index_0 = if old_new_vertex_map[old_vertex_index_0] == unused_vertex_marker {
+            let new_index = vertices_reduced.len();
+            vertices_reduced.push(vertices[old_vertex_index_0]);
+            old_new_vertex_map[old_vertex_index_0] = new_index;
+            new_index
+        } else {
+            old_new_vertex_map[old_vertex_index_0]
+        };
+
+        let old_vertex_index_1 = cast_usize(face.1);
+        let new_vertex_index_1 = if old_new_vertex_map[old_vertex_index_1] == unused_vertex_marker {
+            let new_index = vertices_reduced.len();
+            vertices_reduced.push(vertices[old_vertex_index_1]);
+            old_new_vertex_map[old_vertex_index_1] = new_index;
+            new_index
+        } else {
+            old_new_vertex_map[old_vertex_index_1]
+        };
+
+        let old_vertex_index_2 = cast_usize(face.2);
+        let new_vertex_index_2 = if old_new_vertex_map[old_vertex_index_2] == unused_vertex_marker {
+            let new_index = vertices_reduced.len();
+            vertices_reduced.push(vertices[old_vertex_index_2]);
+            old_new_vertex_map[old_vertex_index_2] = new_index;
+            new_index
+        } else {
+            old_new_vertex_map[old_vertex_index_2]
+        };
+
+        faces_renumbered.push((
+            cast_u32(new_vertex_index_0),
+            cast_u32(new_vertex_index_1),
+            cast_u32(new_vertex_index_2),
+        ));
+    }
+
+    faces_renumbered.shrink_to_fit();
+    vertices_reduced.shrink_to_fit();
+
+    (faces_renumbered, vertices_reduced)
+}
+
+/// Averages per-vertex `values` with `channels` values per vertex of
+/// the source vertices mapped to the same vertex by `vertex_map`.
+/// `source_counts` has the number of source vertices of each vertex.
+fn average_mapped_vertex_values(
+    values: &[f32],
+    channels: usize,
+    vertex_map: &[Option<u32>],
+    source_counts: &[u32],
+) -> Vec<f32> {
+    let mut averaged = vec![0.0; source_counts.len() * channels];
+    for (source_values, vertex_index) in values.chunks(channels).zip(vertex_map) {
+        if let Some(vertex_index) = vertex_index {
+            let start = cast_usize(*vertex_index) * channels;
+            for (sum, value) in averaged[start..start + channels]
+                .iter_mut()
+                .zip(source_values)
+            {
+                *sum += value;
+            }
+        }
+    }
+
+    for (sums, count) in averaged.chunks_mut(channels).zip(source_counts) {
+        for sum in sums {
+            *sum /= *count as f32;
+        }
+    }
+
+    averaged
+}
+
+/// Maps the vertices to their indices after the orphans among them are
+/// removed from a mesh with the `faces`. The remaining vertices are numbered in the order the faces
+/// first refer to them. Orphan vertices map to `None`.
+fn orphan_removal_vertex_map(faces: &[Face], vertex_count: usize) -> Vec<Option<u32>> {
+    let mut vertex_map = vec![None; vertex_count];
+    let mut next_vertex_index = 0;
+
+    for face in faces {
+        for vertex_index in face.vertices() {
+            let mapped = &mut vertex_map[cast_usize(vertex_index)];
+            if mapped.is_none() {
+                *mapped = Some(next_vertex_index);
+                next_vertex_index += 1;
+            }
+        }
+    }
+
+    vertex_map
+}
+
+/// Asserts that the faces only refer to vertices out of `vertex_count`.
+fn assert_faces_in_bounds(faces: &[Face], vertex_count: usize) {
+    let vertices_range = 0..cast_u32(vertex_count);
+    for face in faces {
+        for vertex_index in face.vertices() {
+            assert!(
+                vertices_range.contains(&vertex_index),
+                "Faces reference out of bounds position data"
+            );
+        }
+    }
+}
+
+/// Computes the unit normal of the face. The normal of a quad, which
+/// need not be planar, is the normal of the plane best fitting it.
+pub fn compute_face_normal(vertices: &[Point3<f32>], face: &Face) -> Vector3<f32> {
+    match face {
+        Face::Triangle(triangle_face) => {
+            let (v1, v2, v3) = triangle_face.vertices;
+            geometry::compute_triangle_normal(
+                &vertices[cast_usize(v1)],
+                &vertices[cast_usize(v2)],
+                &vertices[cast_usize(v3)],
+            )
+        }
+        Face::Quad(quad_face) => {
+            // The cross product of the diagonals is the normal of a
+            // planar quad and averages the halves of a non-planar one
+            let (v1, v2, v3, v4) = quad_face.vertices;
+            let diagonal_1 = vertices[cast_usize(v3)] - vertices[cast_usize(v1)];
+            let diagonal_2 = vertices[cast_usize(v4)] - vertices[cast_usize(v2)];
+            diagonal_1.cross(&diagonal_2).normalize()
+        }
+    }
+}
+
+pub fn compute_smooth_normals_from_components(
+    vertices: &[Point3<f32>],
+    faces: &[Face],
+    vertex_to_face_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+) -> Vec<Vector3<f32>> {
+    let vertex_count = vertices.len();
+
+    let mut normals: Vec<Vector3<f32>> = Vec::with_capacity(vertex_count);
+
+    for shared_face_indices in vertex_to_face_topology {
+        let mut normal: Vector3<f32> = Vector3::zeros();
+        for face_index in shared_face_indices {
+            normal += compute_face_normal(vertices, &faces[cast_usize(*face_index)]);
+        }
+        normals.push(normal.normalize());
+    }
+
+    normals
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn quad() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+        ];
+
+        // When comparing TriangleFaces or Faces from Mesh, make sure the
+        // manually defined faces start their winding from the lowest vertex
+        // index. See TriangleFace constructors for more info.
+        let faces = vec![(0, 1, 2), (0, 2, 3)];
+
+        (faces, vertices)
+    }
+
+    fn quad_with_normals() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>, VertexNormals) {
+        let vertices = vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+        ];
+
+        let normals = VertexNormals::from_vertex_normals(vec![
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ]);
+
+        // When comparing TriangleFaces or Faces from Mesh, make sure the
+        // manually defined faces start their winding from the lowest vertex
+        // index. See TriangleFace constructors for more info.
+        let faces = vec![(0, 1, 2), (0, 2, 3)];
+
+        (faces, vertices, normals)
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_triangle_faces_with_vertices_and_computed_normals_empty_mesh() {
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![],
+            vec![],
+            NormalStrategy::Sharp,
+        );
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_triangle_faces_with_vertices_and_computed_normals_remove_orphans_empty_mesh()
+    {
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals_remove_orphans(
+            vec![],
+            vec![],
+            NormalStrategy::Sharp,
+        );
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_triangle_faces_with_vertices_and_vertex_normals_empty_mesh() {
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            vec![],
+            vec![],
+            VertexNormals::from_vertex_normals(vec![]),
+        );
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_triangle_faces_with_vertices_and_corner_normals_remove_orphans_empty_mesh() {
+        Mesh::from_triangle_faces_with_vertices_and_corner_normals_remove_orphans(vec![], vec![]);
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_faces_with_vertices_and_vertex_normals_empty_mesh() {
+        Mesh::from_faces_with_vertices_and_vertex_normals(
+            vec![],
+            vec![],
+            VertexNormals::from_vertex_normals(vec![]),
+        );
+    }
+
+    #[test]
+    #[should_panic = "Empty (faceless) meshes are not supported"]
+    fn test_mesh_from_faces_with_vertices_and_vertex_normals_remove_orphans_empty_mesh() {
+        Mesh::from_faces_with_vertices_and_vertex_normals_remove_orphans(
+            vec![],
+            vec![],
+            VertexNormals::from_vertex_normals(vec![]),
+        );
+    }
+
+    #[test]
+    fn test_mesh_from_triangle_faces_with_vertices_and_computed_normals() {
+        let (faces, vertices) = quad();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        assert!(mesh.is_triangulated());
+
+        let mesh_faces: Vec<_> = mesh.triangle_faces_iter().collect();
+
+        assert_eq!(vertices.as_slice(), mesh.vertices());
+        assert_eq!(
+            faces,
+            mesh_faces
+                .into_iter()
+                .map(|triangle_face| triangle_face.vertices)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Faces reference out of bounds position data")]
+    fn test_mesh_from_triangle_faces_with_vertices_and_computed_normals_bounds_check() {
+        let (_, vertices) = quad();
+
+        // When comparing TriangleFaces or Faces from Mesh, make sure the
+        // manually defined faces start their winding from the lowest vertex
+        // index. See TriangleFace constructors for more info.
+        let faces = vec![(0, 1, 2), (2, 3, 4)];
+
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+    }
+
+    #[test]
+    fn test_mesh_from_triangle_faces_with_vertices_and_vertex_normals() {
+        let (faces, vertices, normals) = quad_with_normals();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces.clone(),
+            vertices.clone(),
+            normals.clone(),
+        );
+        assert!(mesh.is_triangulated());
+
+        let mesh_faces: Vec<_> = mesh
+            .triangle_faces_iter()
+            .map(|triangle_face| triangle_face.vertices)
+            .collect();
+
+        assert_eq!(vertices.as_slice(), mesh.vertices());
+        assert_eq!(&normals, mesh.vertex_normals());
+        assert_eq!(faces, mesh_faces);
+    }
+
+    #[test]
+    #[should_panic(expected = "Faces reference out of bounds position data")]
+    fn test_mesh_from_triangle_faces_with_vertices_and_vertex_normals_bounds_check() {
+        let (_, vertices, normals) = quad_with_normals();
+
+        // When comparing TriangleFaces or Faces from Mesh, make sure the
+        // manually defined faces start their winding from the lowest vertex
+        // index. See TriangleFace constructors for more info.
+        let faces = vec![(0, 1, 2), (2, 3, 4)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+    }
+
+    #[test]
+    #[should_panic(expected = "Each vertex must have a normal")]
+    fn test_mesh_from_triangle_faces_with_vertices_and_vertex_normals_count_check() {
+        let (faces, vertices, _) = quad_with_normals();
+        let normals = VertexNormals::from_vertex_normals(vec![Vector3::z(); 3]);
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+    }
+
+    #[test]
+    fn test_mesh_from_triangle_faces_with_vertices_and_corner_normals_overrides_creases() {
+        let (faces, vertices) = quad();
+        let side = Vector3::y();
+        let corner_normals = vec![[Vector3::z(); 3], [side; 3]];
+
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_corner_normals(
+            faces.into_iter().zip(corner_normals),
+            vertices,
+        );
+
+        assert_eq!(mesh.corner_normals(0), [Vector3::z(); 3]);
+        assert_eq!(mesh.corner_normals(1), [side; 3]);
+        assert_eq!(mesh.vertex_normals().vertex_normal(1), Vector3::z());
+        assert_eq!(mesh.vertex_normals().vertex_normal(3), side);
+    }
+
+    #[test]
+    fn test_oriented_edge_eq_returns_true() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(0, 1);
+        assert_eq!(oriented_edge_one_way, oriented_edge_other_way);
+    }
+
+    #[test]
+    fn test_oriented_edge_eq_returns_false_because_different() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(2, 1);
+        assert_ne!(oriented_edge_one_way, oriented_edge_other_way);
+    }
+
+    #[test]
+    fn test_oriented_edge_eq_returns_false_because_reverted() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(1, 0);
+        assert_ne!(oriented_edge_one_way, oriented_edge_other_way);
+    }
+
+    #[test]
+    #[should_panic(expected = "The oriented edge is constituted of the same vertex")]
+    fn test_oriented_edge_constructor_consists_of_the_same_vertex_should_panic() {
+        OrientedEdge::new(0, 0);
+    }
+
+    #[test]
+    fn test_oriented_edge_constructor_does_not_consist_of_the_same_vertex_should_pass() {
+        OrientedEdge::new(0, 1);
+    }
+
+    #[test]
+    fn test_oriented_edge_is_reverted_returns_true_because_same() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(1, 0);
+        assert!(oriented_edge_one_way.is_reverted(oriented_edge_other_way));
+    }
+
+    #[test]
+    fn test_oriented_edge_is_reverted_returns_false_because_is_same() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(0, 1);
+        assert!(!oriented_edge_one_way.is_reverted(oriented_edge_other_way));
+    }
+
+    #[test]
+    fn test_oriented_edge_is_reverted_returns_false_because_is_different() {
+        let oriented_edge_one_way = OrientedEdge::new(0, 1);
+        let oriented_edge_other_way = OrientedEdge::new(2, 1);
+        assert!(!oriented_edge_one_way.is_reverted(oriented_edge_other_way));
+    }
+
+    #[test]
+    fn test_unoriented_edge_eq_returns_true_because_same() {
+        let unoriented_edge_one_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let unoriented_edge_other_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        assert_eq!(unoriented_edge_one_way, unoriented_edge_other_way);
+    }
+
+    #[test]
+    fn test_unoriented_edge_eq_returns_true_because_reverted() {
+        let unoriented_edge_one_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let unoriented_edge_other_way = UnorientedEdge(OrientedEdge::new(1, 0));
+        assert_eq!(unoriented_edge_one_way, unoriented_edge_other_way);
+    }
+
+    #[test]
+    fn test_unoriented_edge_eq_returns_false_because_different() {
+        let unoriented_edge_one_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let unoriented_edge_other_way = UnorientedEdge(OrientedEdge::new(2, 1));
+        assert_ne!(unoriented_edge_one_way, unoriented_edge_other_way);
+    }
+
+    #[test]
+    fn test_unoriented_edge_hash_returns_true_because_same() {
+        let unoriented_edge_one_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let unoriented_edge_other_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let mut hasher_1 = DefaultHasher::new();
+        let mut hasher_2 = DefaultHasher::new();
+        unoriented_edge_one_way.hash(&mut hasher_1);
+        unoriented_edge_other_way.hash(&mut hasher_2);
+        assert_eq!(hasher_1.finish(), hasher_2.finish());
+    }
+
+    #[test]
+    fn test_unoriented_edge_hash_returns_true_because_reverted() {
+        let unoriented_edge_one_way = UnorientedEdge(OrientedEdge::new(0, 1));
+        let unoriented_edge_other_way = UnorientedEdge(OrientedEdge::new(1, 0));
+        let mut hasher_1 = DefaultHasher::new();
+        let mut hasher_2 = DefaultHasher::new();
+        unoriented_edge_one_way.hash(&mut hasher_1);
+        unoriented_edge_other_way.hash(&mut hasher_2);
+        assert_eq!(hasher_1.finish(), hasher_2.finish());
+    }
+
+    #[test]
+    fn test_triangle_face_to_oriented_edges() {
+        let face = TriangleFace::new(0, 1, 2);
+
+        let oriented_edges_correct: [OrientedEdge; 3] = [
+            OrientedEdge::new(0, 1),
+            OrientedEdge::new(1, 2),
+            OrientedEdge::new(2, 0),
+        ];
+
+        let oriented_edges_to_check: [OrientedEdge; 3] = face.to_oriented_edges();
+
+        assert_eq!(oriented_edges_to_check[0], oriented_edges_correct[0]);
+        assert_eq!(oriented_edges_to_check[1], oriented_edges_correct[1]);
+        assert_eq!(oriented_edges_to_check[2], oriented_edges_correct[2]);
+    }
+
+    #[test]
+    fn test_triangle_face_to_unoriented_edges() {
+        let face = TriangleFace::new(0, 1, 2);
+
+        let unoriented_edges_correct: [UnorientedEdge; 3] = [
+            UnorientedEdge(OrientedEdge::new(0, 1)),
+            UnorientedEdge(OrientedEdge::new(1, 2)),
+            UnorientedEdge(OrientedEdge::new(2, 0)),
+        ];
+
+        let unoriented_edges_to_check: [UnorientedEdge; 3] = face.to_unoriented_edges();
+
+        assert_eq!(unoriented_edges_to_check[0], unoriented_edges_correct[0]);
+        assert_eq!(unoriented_edges_to_check[1], unoriented_edges_correct[1]);
+        assert_eq!(unoriented_edges_to_check[2], unoriented_edges_correct[2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "One or more face edges consists of the same vertex")]
+    fn test_triangle_face_new_with_invalid_vertex_indices_0_1_should_panic() {
+        TriangleFace::new(0, 0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "One or more face edges consists of the same vertex")]
+    fn test_triangle_face_new_with_invalid_vertex_indices_1_2_should_panic() {
+        TriangleFace::new(0, 2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "One or more face edges consists of the same vertex")]
+    fn test_triangle_face_new_with_invalid_vertex_indices_0_2_should_panic() {
+        TriangleFace::new(0, 2, 0);
+    }
+
+    #[test]
+    fn test_has_no_orphan_vertices_returns_true_if_there_are_some() {
+        let (faces, vertices, normals) = quad_with_normals();
+
+        let mesh_without_orphans =
+            Mesh::from_triangle_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+
+        assert!(mesh_without_orphans.has_no_orphan_vertices());
+    }
+
+    #[test]
+    fn test_has_no_orphan_vertices_returns_false_if_there_are_none() {
+        let (faces, vertices, _) = quad_with_normals();
+        let extra_vertex = vec![Point3::new(0.0, 0.0, 0.0)];
+        let vertices_extended = [&vertices[..], &extra_vertex[..]].concat();
+        let normals = VertexNormals::from_vertex_normals(vec![Vector3::z(); 5]);
+
+        let mesh_with_orphans = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices_extended,
+            normals,
+        );
+
+        assert!(!mesh_with_orphans.has_no_orphan_vertices());
+    }
+
+    #[test]
+    fn test_mesh_unoriented_edges_iter() {
+        let (faces, vertices, normals) = quad_with_normals();
+        let mesh =
+            Mesh::from_triangle_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+        let unoriented_edges_correct = vec![
+            UnorientedEdge(OrientedEdge::new(0, 1)),
+            UnorientedEdge(OrientedEdge::new(1, 2)),
+            UnorientedEdge(OrientedEdge::new(2, 0)),
+            UnorientedEdge(OrientedEdge::new(2, 3)),
+            UnorientedEdge(OrientedEdge::new(3, 0)),
+            UnorientedEdge(OrientedEdge::new(0, 2)),
+        ];
+        let unoriented_edges_to_check: Vec<UnorientedEdge> = mesh.unoriented_edges_iter().collect();
+
+        assert!(unoriented_edges_correct
+            .iter()
+            .all(|u_e| unoriented_edges_to_check.iter().any(|e| e == u_e)));
+
+        let len_1 = unoriented_edges_to_check.len();
+        let len_2 = unoriented_edges_correct.len();
+        assert_eq!(len_1, len_2);
+    }
+
+    #[test]
+    fn test_mesh_oriented_edges_iter() {
+        let (faces, vertices, normals) = quad_with_normals();
+        let mesh =
+            Mesh::from_triangle_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+
+        let oriented_edges_correct = vec![
+            OrientedEdge::new(0, 1),
+            OrientedEdge::new(1, 2),
+            OrientedEdge::new(2, 0),
+            OrientedEdge::new(2, 3),
+            OrientedEdge::new(3, 0),
+            OrientedEdge::new(0, 2),
+        ];
+        let oriented_edges_to_check: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+
+        assert!(oriented_edges_correct
+            .iter()
+            .all(|o_e| oriented_edges_to_check.iter().any(|e| e == o_e)));
+
+        let len_1 = oriented_edges_to_check.len();
+        let len_2 = oriented_edges_correct.len();
+
+        assert_eq!(len_1, len_2);
+    }
+
+    #[test]
+    fn test_remove_orphan_vertices() {
+        let (faces, vertices) = quad();
+        let extra_vertex = vec![Point3::new(0.0, 0.0, 0.0)];
+        let vertices_extended = [&extra_vertex[..], &vertices[..]].concat();
+        let faces_renumbered_to_match_extend_vertices: Vec<_> =
+            faces.iter().map(|f| (f.0 + 1, f.1 + 1, f.2 + 1)).collect();
+
+        let faces_length = &faces.len();
+
+        let (faces_purged, vertices_purged) =
+            remove_orphan_vertices(faces_renumbered_to_match_extend_vertices, vertices_extended);
+
+        let faces_purged_length = &faces_purged.len();
+
+        assert_eq!(faces_length, faces_purged_length);
+        assert_eq!(vertices_purged, vertices);
+    }
+
+    #[test]
+    fn test_mesh_from_faces_with_vertices_and_vertex_normals_remove_orphans_keeps_overrides() {
+        let (faces, vertices) = quad();
+        let extra_vertex = vec![Point3::new(0.0, 0.0, 0.0)];
+        let vertices_extended = [&extra_vertex[..], &vertices[..]].concat();
+        let side = Vector3::y();
+        let faces_renumbered_to_match_extend_vertices: Vec<_> =
+            faces.iter().map(|f| (f.0 + 1, f.1 + 1, f.2 + 1)).collect();
+        let normals = VertexNormals::from_corner_normals(
+            faces_renumbered_to_match_extend_vertices
+                .iter()
+                .copied()
+                .zip(vec![[Vector3::z(); 3], [side; 3]]),
+            vertices_extended.len(),
+        );
+
+        let mesh = Mesh::from_faces_with_vertices_and_vertex_normals_remove_orphans(
+            faces_renumbered_to_match_extend_vertices
+                .into_iter()
+                .map(|(v1, v2, v3)| Face::from(TriangleFace::new(v1, v2, v3))),
+            vertices_extended,
+            normals,
+        );
+
+        assert!(mesh.has_no_orphan_vertices());
+        assert_eq!(mesh.vertices(), vertices.as_slice());
+        assert_eq!(mesh.corner_normals(0), [Vector3::z(); 3]);
+        assert_eq!(mesh.corner_normals(1), [side; 3]);
+    }
+
+    #[test]
+    fn test_mesh_from_triangle_faces_with_vertices_and_computed_normals_remove_orphans() {
+        let (faces, vertices) = quad();
+        let extra_vertex = vec![Point3::new(0.0, 0.0, 0.0)];
+        let vertices_extended = [&extra_vertex[..], &vertices[..]].concat();
+        let faces_renumbered_to_match_extend_vertices: Vec<_> =
+            faces.iter().map(|f| (f.0 + 1, f.1 + 1, f.2 + 1)).collect();
+
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals_remove_orphans(
+            faces_renumbered_to_match_extend_vertices,
+            vertices_extended,
+            NormalStrategy::Sharp,
+        );
+
+        assert!(mesh.has_no_orphan_vertices());
+    }
+
+    #[test]
+    fn test_mesh_from_triangle_faces_with_vertices_and_corner_normals_remove_orphans() {
+        let (faces, vertices) = quad();
+        let extra_vertex = vec![Point3::new(0.0, 0.0, 0.0)];
+        let vertices_extended = [&extra_vertex[..], &vertices[..]].concat();
+        let faces_renumbered_to_match_extend_vertices: Vec<_> = faces
+            .iter()
+            .map(|f| ((f.0 + 1, f.1 + 1, f.2 + 1), [Vector3::z(); 3]))
+            .collect();
+
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_corner_normals_remove_orphans(
+            faces_renumbered_to_match_extend_vertices,
+            vertices_extended,
+        );
+
+        assert!(mesh.has_no_orphan_vertices());
+        assert!(!mesh.vertex_normals().has_overrides());
+    }
+
+    #[test]
+    fn test_triangle_face_new_lowest_first() {
+        let face = TriangleFace::new(0, 1, 2);
+        assert_eq!(face.vertices, (0, 1, 2));
+    }
+
+    #[test]
+    fn test_triangle_face_new_lowest_second() {
+        let face = TriangleFace::new(2, 0, 1);
+        assert_eq!(face.vertices, (0, 1, 2));
+    }
+
+    #[test]
+    fn test_triangle_face_new_lowest_third() {
+        let face = TriangleFace::new(1, 2, 0);
+        assert_eq!(face.vertices, (0, 1, 2));
+    }
+
+    #[test]
+    fn test_triangle_face_to_reverted_comparison_to_reverted() {
+        let face = TriangleFace::new(1, 2, 3);
+        let face_reverted_correct = TriangleFace::new(3, 2, 1);
+
+        let face_reverted_computed = face.to_reverted();
+        assert_eq!(face_reverted_correct, face_reverted_computed);
+    }
+
+    #[test]
+    fn test_triangle_face_to_reverted_comparison_to_same() {
+        let face = TriangleFace::new(1, 2, 3);
+
+        let face_reverted_computed = face.to_reverted();
+        assert_ne!(face, face_reverted_computed);
+    }
+
+    #[test]
+    fn test_triangle_face_to_reverted_comparison_to_reverted_and_shifted() {
+        let face = TriangleFace::new(1, 2, 3);
+        let face_reverted_correct_shifted = TriangleFace::new(2, 1, 3);
+
+        let face_reverted_computed = face.to_reverted();
+        assert_eq!(face_reverted_correct_shifted, face_reverted_computed);
+    }
+
+    #[test]
+    fn test_triangle_face_is_reverted_comparison_to_reverted_and_shifted() {
+        let face = TriangleFace::new(1, 2, 3);
+        let face_reverted_correct_shifted = TriangleFace::new(2, 1, 3);
+
+        assert!(face_reverted_correct_shifted.is_reverted(&face));
+    }
+
+    #[test]
+    fn test_triangle_face_is_reverted_comparison_to_self() {
+        let face = TriangleFace::new(1, 2, 3);
+
+        assert!(!face.is_reverted(&face));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_oriented_edge_returns_true_because_contains_all() {
+        let face = TriangleFace::new(1, 2, 3);
+        let oriented_edge_1 = OrientedEdge::new(1, 2);
+        let oriented_edge_2 = OrientedEdge::new(2, 3);
+        let oriented_edge_3 = OrientedEdge::new(3, 1);
+
+        assert!(face.contains_oriented_edge(oriented_edge_1));
+        assert!(face.contains_oriented_edge(oriented_edge_2));
+        assert!(face.contains_oriented_edge(oriented_edge_3));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_oriented_edge_returns_false_because_contains_all_reverted() {
+        let face = TriangleFace::new(1, 2, 3);
+        let oriented_edge_1 = OrientedEdge::new(2, 1);
+        let oriented_edge_2 = OrientedEdge::new(3, 2);
+        let oriented_edge_3 = OrientedEdge::new(1, 3);
+
+        assert!(!face.contains_oriented_edge(oriented_edge_1));
+        assert!(!face.contains_oriented_edge(oriented_edge_2));
+        assert!(!face.contains_oriented_edge(oriented_edge_3));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_oriented_edge_returns_false_because_different() {
+        let face = TriangleFace::new(1, 2, 3);
+        let oriented_edge = OrientedEdge::new(4, 5);
+
+        assert!(!face.contains_oriented_edge(oriented_edge));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_unoriented_edge_returns_true_because_contains_all() {
+        let face = TriangleFace::new(1, 2, 3);
+        let unoriented_edge_1 = UnorientedEdge(OrientedEdge::new(1, 2));
+        let unoriented_edge_2 = UnorientedEdge(OrientedEdge::new(2, 3));
+        let unoriented_edge_3 = UnorientedEdge(OrientedEdge::new(3, 1));
+
+        assert!(face.contains_unoriented_edge(unoriented_edge_1));
+        assert!(face.contains_unoriented_edge(unoriented_edge_2));
+        assert!(face.contains_unoriented_edge(unoriented_edge_3));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_unoriented_edge_returns_true_because_contains_all_reverted() {
+        let face = TriangleFace::new(1, 2, 3);
+        let unoriented_edge_1 = UnorientedEdge(OrientedEdge::new(2, 1));
+        let unoriented_edge_2 = UnorientedEdge(OrientedEdge::new(3, 2));
+        let unoriented_edge_3 = UnorientedEdge(OrientedEdge::new(1, 3));
+
+        assert!(face.contains_unoriented_edge(unoriented_edge_1));
+        assert!(face.contains_unoriented_edge(unoriented_edge_2));
+        assert!(face.contains_unoriented_edge(unoriented_edge_3));
+    }
+
+    #[test]
+    fn test_triangle_face_contains_unoriented_edge_returns_false_because_different() {
+        let face = TriangleFace::new(1, 2, 3);
+        let unoriented_edge = UnorientedEdge(OrientedEdge::new(4, 5));
+
+        assert!(!face.contains_unoriented_edge(unoriented_edge));
+    }
+
+    #[test]
+    fn test_mesh_set_vertex_attribute_replaces_attribute_with_same_name() {
+        let (faces, vertices) = quad();
+        let mut mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        mesh.set_vertex_attribute("weight", vec![0.0, 1.0, 2.0, 3.0]);
+        mesh.set_vertex_attribute("weight", vec![3.0, 2.0, 1.0, 0.0]);
+
+        assert_eq!(mesh.vertex_attributes().len(), 1);
+        assert_eq!(
+            mesh.vertex_attribute("weight")
+                .expect("Attribute must exist")
+                .values(),
+            &[3.0, 2.0, 1.0, 0.0],
+        );
+    }
+
+    #[test]
+    #[should_panic = "Vertex attribute must have a value for each vertex"]
+    fn test_mesh_set_vertex_attribute_panics_on_wrong_length() {
+        let (faces, vertices) = quad();
+        let mut mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        mesh.set_vertex_attribute("weight", vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_mesh_is_consistent_returns_false_for_out_of_bounds_face() {
+        let (faces, vertices) = quad();
+        let mut mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        assert!(mesh.is_consistent());
+
+        if let Face::Triangle(triangle_face) = &mut mesh.faces_mut()[0] {
+            triangle_face.vertices.0 = 4;
+        }
+
+        assert!(!mesh.is_consistent());
+    }
+
+    fn folded_quads() -> Mesh {
+        Mesh::from_faces_with_vertices_and_computed_normals(
+            vec![
+                Face::from(QuadFace::new(0, 1, 2, 3)),
+                Face::from(QuadFace::new(1, 4, 5, 2)),
+            ],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+                Point3::new(1.0, 0.0, -1.0),
+                Point3::new(1.0, 1.0, -1.0),
+            ],
+            NormalStrategy::Sharp,
+        )
+    }
+
+    #[test]
+    fn test_quad_face_new_lowest_first() {
+        assert_eq!(QuadFace::new(0, 1, 2, 3).vertices, (0, 1, 2, 3));
+        assert_eq!(QuadFace::new(3, 0, 1, 2).vertices, (0, 1, 2, 3));
+        assert_eq!(QuadFace::new(2, 3, 0, 1).vertices, (0, 1, 2, 3));
+        assert_eq!(QuadFace::new(1, 2, 3, 0).vertices, (0, 1, 2, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "One or more face edges consists of the same vertex")]
+    fn test_quad_face_new_with_invalid_vertex_indices_should_panic() {
+        QuadFace::new(0, 1, 0, 2);
+    }
+
+    #[test]
+    fn test_quad_face_to_reverted_and_to_triangles() {
+        let face = QuadFace::new(0, 1, 2, 3);
+
+        assert_eq!(face.to_reverted().vertices, (0, 3, 2, 1));
+        assert_eq!(
+            face.to_triangles(),
+            [TriangleFace::new(0, 1, 2), TriangleFace::new(0, 2, 3)],
+        );
+    }
+
+    #[test]
+    fn test_mesh_with_quads_sharp_normals_and_edges() {
+        let mesh = folded_quads();
+
+        assert!(!mesh.is_triangulated());
+        assert!(mesh.is_consistent());
+        assert_eq!(mesh.oriented_edges_iter().count(), 8);
+        assert_eq!(
+            mesh.unoriented_edges_iter().collect::<HashSet<_>>().len(),
+            7
+        );
+        assert_eq!(
+            mesh.face_corner_normals(0).as_slice(),
+            &[Vector3::z(); 4][..],
+        );
+        assert_eq!(
+            mesh.face_corner_normals(1).as_slice(),
+            &[Vector3::x(); 4][..],
+        );
+    }
+
+    #[test]
+    fn test_mesh_triangulated_keeps_corner_normals_and_materials() {
+        let mut mesh = folded_quads();
+        mesh.set_face_materials(vec![1, 2]);
+
+        let triangulated = mesh.triangulated();
+
+        assert!(triangulated.is_triangulated());
+        assert!(triangulated.is_consistent());
+        assert_eq!(triangulated.vertices(), mesh.vertices());
+        assert_eq!(triangulated.faces().len(), 4);
+        assert_eq!(triangulated.face_materials(), Some(&[1, 1, 2, 2][..]));
+        for face_index in 0..4 {
+            let expected_normal = if face_index < 2 {
+                Vector3::z()
+            } else {
+                Vector3::x()
+            };
+            assert_eq!(
+                triangulated.corner_normals(face_index),
+                [expected_normal; 3]
+            );
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use std::f32;
+
+use arrayvec::ArrayVec;
+use nalgebra::Vector3;
+
+use crate::collections::HashMap;
+use crate::convert::{cast_u32, cast_usize};
+
+use super::{Face, TriangleFace};
+
+/// Corner normals deviating from their vertex normal by less than this
+/// (as the cosine of the angle between them) are considered the same.
+const SAME_NORMAL_MIN_COS: f32 = 0.9999;
+
+/// Normal indices of the corners of each face, in the order of its
+/// vertices, with the normals they refer to.
+pub type IndexedNormals = (Vec<ArrayVec<[u32; 4]>>, Vec<Vector3<f32>>);
+
+/// Vertex indices of a face with the normals of its corners.
+type FaceCornerNormals = (ArrayVec<[u32; 4]>, ArrayVec<[Vector3<f32>; 4]>);
+
+/// Normals of a mesh stored canonically: one smooth normal for each
+/// vertex, and optional overrides for face corners, e.g. where faces
+/// meet at a sharp edge.
+///
+/// A corner is identified by the index of its face and vertex, so the
+/// overrides stay valid when the winding of the faces changes. Faces
+/// with the same overridden normal at all corners, such as the faces of
+/// a sharp mesh, store a single override for the whole face.
+///
+/// Tools moving, merging or creating vertices only need to handle the
+/// vertex normals, and can decide to keep or drop the overrides. Tools
+/// rebuilding the faces create the normals from the normals of the new
+/// face corners with `from_corner_normals`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VertexNormals {
+    vertex_normals: Vec<Vector3<f32>>,
+    /// Normals overriding all corners of a face as `(face_index,
+    /// normal)`, sorted by the face index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    face_overrides: Vec<(u32, Vector3<f32>)>,
+    /// Normals overriding single face corners as `(face_index,
+    /// vertex_index, normal)`, sorted by the face and vertex index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    corner_overrides: Vec<(u32, u32, Vector3<f32>)>,
+}
+
+impl VertexNormals {
+    /// Creates smooth normals from a normal for each vertex. The normals
+    /// are normalized, zero normals are replaced by the Z axis.
+    pub fn from_vertex_normals<N>(vertex_normals: N) -> Self
+    where
+        N: IntoIterator<Item = Vector3<f32>>,
+    {
+        Self {
+            vertex_normals: vertex_normals.into_iter().map(normalize_or_z).collect(),
+            face_overrides: Vec::new(),
+            corner_overrides: Vec::new(),
+        }
+    }
+
+    /// Creates normals from the normals of all face corners, given as
+    /// the vertex indices of each face together with the normals of its
+    /// corners in the same order.
+    ///
+    /// Each vertex gets the normalized sum of the normals of its
+    /// corners, or their normal, if they all have the same one. Corners
+    /// whose normal differs from their vertex normal get an override.
+    /// Vertices without corners get the Z axis.
+    ///
+    /// # Panics
+    /// Panics if faces refer to vertices out of `vertex_count`.
+    pub fn from_corner_normals<C>(corners: C, vertex_count: usize) -> Self
+    where
+        C: IntoIterator<Item = ((u32, u32, u32), [Vector3<f32>; 3])>,
+    {
+        Self::from_face_corner_normals(
+            corners.into_iter().map(|((v1, v2, v3), normals)| {
+                (
+                    Face::from(TriangleFace::new(v1, v2, v3)),
+                    normals.iter().copied().collect(),
+                )
+            }),
+            vertex_count,
+        )
+    }
+
+    /// Creates normals from the normals of all face corners like
+    /// `from_corner_normals`, for faces of any kind given together with
+    /// the normals of their corners in the order of their vertices.
+    ///
+    /// # Panics
+    /// Panics if faces refer to vertices out of `vertex_count`, or if a
+    /// face doesn't have a normal for each corner.
+    pub fn from_face_corner_normals<C>(corners: C, vertex_count: usize) -> Self
+    where
+        C: IntoIterator<Item = (Face, ArrayVec<[Vector3<f32>; 4]>)>,
+    {
+        let faces: Vec<FaceCornerNormals> = corners
+            .into_iter()
+            .map(|(face, normals)| {
+                let vertices = face.vertices();
+                assert_eq!(
+                    vertices.len(),
+                    normals.len(),
+                    "Each face corner must have a normal",
+                );
+
+                (vertices, normals.into_iter().map(normalize_or_z).collect())
+            })
+            .collect();
+
+        let mut normal_sums = vec![Vector3::zeros(); vertex_count];
+        let mut first_normals: Vec<Option<Vector3<f32>>> = vec![None; vertex_count];
+        let mut same_normals = vec![true; vertex_count];
+        for (vertices, normals) in &faces {
+            for (vertex_index, normal) in vertices.iter().zip(normals) {
+                let vertex_index = cast_usize(*vertex_index);
+                normal_sums[vertex_index] += normal;
+                match first_normals[vertex_index] {
+                    Some(first_normal) => same_normals[vertex_index] &= first_normal == *normal,
+                    None => first_normals[vertex_index] = Some(*normal),
+                }
+            }
+        }
+
+        // Opposite corner normals may cancel out, in which case the
+        // vertex keeps the normal of its first corner
+        let vertex_normals = first_normals
+            .iter()
+            .zip(&normal_sums)
+            .zip(&same_normals)
+            .map(|((first_normal, normal_sum), same)| match first_normal {
+                Some(first_normal) if *same => *first_normal,
+                Some(first_normal) => normal_sum
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or(*first_normal),
+                None => Vector3::z(),
+            })
+            .collect();
+
+        let mut vertex_normals = Self {
+            vertex_normals,
+            face_overrides: Vec::new(),
+            corner_overrides: Vec::new(),
+        };
+        for (face_index, (vertices, normals)) in faces.iter().enumerate() {
+            let face_index = cast_u32(face_index);
+            let mut overridden: ArrayVec<[(u32, u32, Vector3<f32>); 4]> = vertices
+                .iter()
+                .zip(normals)
+                .filter(|(vertex_index, normal)| {
+                    normal.dot(&vertex_normals.vertex_normal(**vertex_index)) < SAME_NORMAL_MIN_COS
+                })
+                .map(|(vertex_index, normal)| (face_index, *vertex_index, *normal))
+                .collect();
+
+            if !overridden.is_empty() && normals.iter().all(|normal| *normal == normals[0]) {
+                vertex_normals.face_overrides.push((face_index, normals[0]));
+            } else {
+                overridden.sort_unstable_by_key(|(_, vertex_index, _)| *vertex_index);
+                vertex_normals.corner_overrides.extend(overridden);
+            }
+        }
+
+        vertex_normals
+    }
+
+    /// Creates normals from their stored parts as returned by
+    /// `vertex_normals`, `face_overrides` and `corner_overrides`, e.g.
+    /// when decoding them. The normals are kept as they are, so they
+    /// should be checked with `is_consistent_with`.
+    pub fn from_parts(
+        vertex_normals: Vec<Vector3<f32>>,
+        face_overrides: Vec<(u32, Vector3<f32>)>,
+        corner_overrides: Vec<(u32, u32, Vector3<f32>)>,
+    ) -> Self {
+        Self {
+            vertex_normals,
+            face_overrides,
+            corner_overrides,
+        }
+    }
+
+    /// Converts indexed normals, where each face corner refers to any of
+    /// the `normals`, e.g. as stored in OBJ files or by older versions
+    /// of the mesh format. The faces are given as their vertex indices
+    /// and the normal indices of their corners, see
+    /// `from_corner_normals`.
+    ///
+    /// # Panics
+    /// Panics if faces refer to vertices out of `vertex_count` or to
+    /// normals out of bounds.
+    pub fn from_indexed_normals<F>(faces: F, normals: &[Vector3<f32>], vertex_count: usize) -> Self
+    where
+        F: IntoIterator<Item = ((u32, u32, u32), (u32, u32, u32))>,
+    {
+        let normal = |normal_index: u32| normals[cast_usize(normal_index)];
+        Self::from_corner_normals(
+            faces
+                .into_iter()
+                .map(|(vertices, (n1, n2, n3))| (vertices, [normal(n1), normal(n2), normal(n3)])),
+            vertex_count,
+        )
+    }
+
+    /// The number of vertices these normals are for.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_normals.len()
+    }
+
+    /// Returns the smooth normal of each vertex.
+    pub fn vertex_normals(&self) -> &[Vector3<f32>] {
+        &self.vertex_normals
+    }
+
+    pub fn vertex_normal(&self, vertex_index: u32) -> Vector3<f32> {
+        self.vertex_normals[cast_usize(vertex_index)]
+    }
+
+    /// Returns the normals overriding all corners of a face as
+    /// `(face_index, normal)`, sorted by the face index.
+    pub fn face_overrides(&self) -> &[(u32, Vector3<f32>)] {
+        &self.face_overrides
+    }
+
+    /// Returns the normals overriding single face corners as
+    /// `(face_index, vertex_index, normal)`, sorted by the face and
+    /// vertex index.
+    pub fn corner_overrides(&self) -> &[(u32, u32, Vector3<f32>)] {
+        &self.corner_overrides
+    }
+
+    /// Returns whether any face corner has a normal differing from its
+    /// vertex normal.
+    pub fn has_overrides(&self) -> bool {
+        !self.face_overrides.is_empty() || !self.corner_overrides.is_empty()
+    }
+
+    /// Returns the number of the stored normals, i.e. the vertex normals
+    /// and the overrides.
+    pub fn stored_normal_count(&self) -> usize {
+        self.vertex_normals.len() + self.face_overrides.len() + self.corner_overrides.len()
+    }
+
+    /// Returns the normal overriding the vertex normal at the corner of
+    /// the face, if any.
+    pub fn corner_override(&self, face_index: usize, vertex_index: u32) -> Option<Vector3<f32>> {
+        let face_index = cast_u32(face_index);
+        if let Ok(position) = self
+            .face_overrides
+            .binary_search_by_key(&face_index, |(face_index, _)| *face_index)
+        {
+            return Some(self.face_overrides[position].1);
+        }
+
+        self.corner_overrides
+            .binary_search_by_key(
+                &(face_index, vertex_index),
+                |(face_index, vertex_index, _)| (*face_index, *vertex_index),
+            )
+            .ok()
+            .map(|position| self.corner_overrides[position].2)
+    }
+
+    /// Returns the normal at the corner of the face: its override, if
+    /// any, or the normal of its vertex.
+    pub fn corner_normal(&self, face_index: usize, vertex_index: u32) -> Vector3<f32> {
+        self.corner_override(face_index, vertex_index)
+            .unwrap_or_else(|| self.vertex_normal(vertex_index))
+    }
+
+    /// Returns the normals at the corners of the face with the
+    /// `vertices`, in the same order.
+    pub fn face_corner_normals(
+        &self,
+        face_index: usize,
+        (v1, v2, v3): (u32, u32, u32),
+    ) -> [Vector3<f32>; 3] {
+        [
+            self.corner_normal(face_index, v1),
+            self.corner_normal(face_index, v2),
+            self.corner_normal(face_index, v3),
+        ]
+    }
+
+    /// Returns all the stored normals, i.e. the vertex normals and the
+    /// overrides, e.g. to transform them.
+    pub fn normals_mut(&mut self) -> impl Iterator<Item = &mut Vector3<f32>> + '_ {
+        self.vertex_normals
+            .iter_mut()
+            .chain(self.face_overrides.iter_mut().map(|(_, normal)| normal))
+            .chain(
+                self.corner_overrides
+                    .iter_mut()
+                    .map(|(_, _, normal)| normal),
+            )
+    }
+
+    /// Appends the normals of another mesh, whose vertices and faces are
+    /// joined after the vertices of this one and the `face_count` faces
+    /// of this one.
+    pub fn append(&mut self, other: &VertexNormals, face_count: usize) {
+        let vertex_offset = cast_u32(self.vertex_normals.len());
+        let face_offset = cast_u32(face_count);
+
+        self.vertex_normals.extend_from_slice(&other.vertex_normals);
+        self.face_overrides.extend(
+            other
+                .face_overrides
+                .iter()
+                .map(|(face_index, normal)| (face_index + face_offset, *normal)),
+        );
+        self.corner_overrides
+            .extend(
+                other
+                    .corner_overrides
+                    .iter()
+                    .map(|(face_index, vertex_index, normal)| {
+                        (
+                            face_index + face_offset,
+                            vertex_index + vertex_offset,
+                            *normal,
+                        )
+                    }),
+            );
+    }
+
+    /// Renumbers the vertices after the orphans among them were removed
+    /// from a mesh with the same faces. The `vertex_map` has the new
+    /// index of each vertex, or `None` for the removed ones.
+    ///
+    /// # Panics
+    /// Panics if the map doesn't have an entry for each vertex, or if an
+    /// override refers to a removed vertex.
+    pub fn remove_orphan_vertices(&self, vertex_map: &[Option<u32>]) -> Self {
+        assert_eq!(
+            vertex_map.len(),
+            self.vertex_normals.len(),
+            "Vertex map must have an entry for each vertex",
+        );
+
+        let mut vertex_normals = vec![Vector3::zeros(); vertex_map.iter().flatten().count()];
+        for (normal, vertex_index) in self.vertex_normals.iter().zip(vertex_map) {
+            if let Some(vertex_index) = vertex_index {
+                vertex_normals[cast_usize(*vertex_index)] = *normal;
+            }
+        }
+
+        let mut corner_overrides: Vec<_> = self
+            .corner_overrides
+            .iter()
+            .map(|(face_index, vertex_index, normal)| {
+                let vertex_index = vertex_map[cast_usize(*vertex_index)]
+                    .expect("Overridden corners must not refer to orphan vertices");
+                (*face_index, vertex_index, *normal)
+            })
+            .collect();
+        corner_overrides
+            .sort_unstable_by_key(|(face_index, vertex_index, _)| (*face_index, *vertex_index));
+
+        Self {
+            vertex_normals,
+            face_overrides: self.face_overrides.clone(),
+            corner_overrides,
+        }
+    }
+
+    /// Remaps the face corner overrides for the faces split into
+    /// triangles by `Mesh::triangulated`. The `faces` are the faces
+    /// before the split, each replaced by its triangles in order.
+    pub fn triangulated(&self, faces: &[Face]) -> Self {
+        let mut first_triangle_indices = Vec::with_capacity(faces.len());
+        let mut triangle_count = 0;
+        for face in faces {
+            first_triangle_indices.push(triangle_count);
+            triangle_count += cast_u32(face.to_triangles().len());
+        }
+
+        let mut face_overrides = Vec::with_capacity(self.face_overrides.len());
+        for (face_index, normal) in &self.face_overrides {
+            let face = &faces[cast_usize(*face_index)];
+            let first_triangle_index = first_triangle_indices[cast_usize(*face_index)];
+            for triangle_index in 0..face.to_triangles().len() {
+                face_overrides.push((first_triangle_index + cast_u32(triangle_index), *normal));
+            }
+        }
+
+        let mut corner_overrides = Vec::with_capacity(self.corner_overrides.len());
+        for (face_index, vertex_index, normal) in &self.corner_overrides {
+            let face = &faces[cast_usize(*face_index)];
+            let first_triangle_index = first_triangle_indices[cast_usize(*face_index)];
+            for (triangle_index, triangle_face) in face.to_triangles().iter().enumerate() {
+                if triangle_face.contains_vertex(*vertex_index) {
+                    corner_overrides.push((
+                        first_triangle_index + cast_u32(triangle_index),
+                        *vertex_index,
+                        *normal,
+                    ));
+                }
+            }
+        }
+        corner_overrides
+            .sort_unstable_by_key(|(face_index, vertex_index, _)| (*face_index, *vertex_index));
+
+        Self {
+            vertex_normals: self.vertex_normals.clone(),
+            face_overrides,
+            corner_overrides,
+        }
+    }
+
+    /// Converts the normals to indexed normals for the `faces`, with the
+    /// normal indices of each face corner referring to the returned
+    /// normals, e.g. to export them to formats storing normals this
+    /// way. Each distinct override is stored once.
+    pub fn to_indexed_normals(&self, faces: &[Face]) -> IndexedNormals {
+        let mut normals = self.vertex_normals.clone();
+        let mut override_indices: HashMap<[u32; 3], u32> = HashMap::default();
+
+        let face_normal_indices = faces
+            .iter()
+            .enumerate()
+            .map(|(face_index, face)| {
+                let normal_index =
+                    |vertex_index: u32| match self.corner_override(face_index, vertex_index) {
+                        Some(normal) => {
+                            let key = [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()];
+                            *override_indices.entry(key).or_insert_with(|| {
+                                normals.push(normal);
+                                cast_u32(normals.len() - 1)
+                            })
+                        }
+                        None => vertex_index,
+                    };
+
+                face.vertices().into_iter().map(normal_index).collect()
+            })
+            .collect();
+
+        (face_normal_indices, normals)
+    }
+
+    /// Returns whether these are valid normals for a mesh with the
+    /// `faces` and `vertex_count` vertices: there is a normal for each
+    /// vertex, the overrides are sorted and only refer to existing face
+    /// corners, and no corner is overridden twice.
+    pub fn is_consistent_with(&self, faces: &[Face], vertex_count: usize) -> bool {
+        let face_count = faces.len();
+        let has_corner = |face_index: u32, vertex_index: u32| {
+            cast_usize(face_index) < face_count
+                && faces[cast_usize(face_index)].contains_vertex(vertex_index)
+        };
+
+        let face_overrides_valid = self
+            .face_overrides
+            .iter()
+            .all(|(face_index, _)| cast_usize(*face_index) < face_count)
+            && self
+                .face_overrides
+                .windows(2)
+                .all(|pair| pair[0].0 < pair[1].0);
+        let corner_overrides_valid =
+            self.corner_overrides
+                .iter()
+                .all(|(face_index, vertex_index, _)| {
+                    has_corner(*face_index, *vertex_index)
+                        && self
+                            .face_overrides
+                            .binary_search_by_key(face_index, |(face_index, _)| *face_index)
+                            .is_err()
+                })
+                && self
+                    .corner_overrides
+                    .windows(2)
+                    .all(|pair| (pair[0].0, pair[0].1) < (pair[1].0, pair[1].1));
+
+        self.vertex_normals.len() == vertex_count && face_overrides_valid && corner_overrides_valid
+    }
+}
+
+fn normalize_or_z(normal: Vector3<f32>) -> Vector3<f32> {
+    normal
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::z)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Point3, Rotation3};
+
+    use crate::mesh::{primitive, Mesh, NormalStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_vertex_normals_from_vertex_normals_replaces_zero_normals() {
+        let vertex_normals =
+            VertexNormals::from_vertex_normals(vec![Vector3::new(0.0, 2.0, 0.0), Vector3::zeros()]);
+
+        assert_eq!(vertex_normals.vertex_normal(0), Vector3::y());
+        assert_eq!(vertex_normals.vertex_normal(1), Vector3::z());
+    }
+
+    #[test]
+    fn test_vertex_normals_of_sharp_box_override_all_corners() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let vertex_normals = mesh.vertex_normals();
+
+        for (face_index, face) in mesh.triangle_faces_iter().enumerate() {
+            let (v1, _, _) = face.vertices;
+            let corner_override = vertex_normals
+                .corner_override(face_index, v1)
+                .expect("Sharp box corners must be overridden");
+            let face_normal = crate::geometry::compute_triangle_normal(
+                &mesh.vertices()[cast_usize(face.vertices.0)],
+                &mesh.vertices()[cast_usize(face.vertices.1)],
+                &mesh.vertices()[cast_usize(face.vertices.2)],
+            );
+            assert!(corner_override.relative_eq(&face_normal, 0.0001, 0.0001));
+        }
+    }
+
+    #[test]
+    fn test_vertex_normals_of_smooth_sphere_have_no_overrides() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            8,
+            8,
+            NormalStrategy::Smooth,
+        );
+
+        assert!(!mesh.vertex_normals().has_overrides());
+    }
+
+    #[test]
+    fn test_vertex_normals_from_corner_normals_override_differing_corners() {
+        let up = Vector3::z();
+        let tilted = Vector3::new(1.0, 0.0, 1.0);
+        let vertex_normals = VertexNormals::from_corner_normals(
+            vec![((0, 1, 2), [up, up, up]), ((0, 2, 3), [up, up, tilted])],
+            4,
+        );
+
+        assert_eq!(vertex_normals.vertex_normal(0), up);
+        assert!(vertex_normals
+            .vertex_normal(3)
+            .relative_eq(&tilted.normalize(), 0.0001, 0.0001));
+        assert_eq!(vertex_normals.corner_override(0, 0), None);
+        assert_eq!(vertex_normals.corner_override(1, 2), None);
+        assert_eq!(vertex_normals.corner_override(1, 3), None);
+        assert!(!vertex_normals.has_overrides());
+    }
+
+    #[test]
+    fn test_vertex_normals_from_corner_normals_store_flat_faces_once() {
+        let up = Vector3::z();
+        let side = Vector3::x();
+        let vertex_normals = VertexNormals::from_corner_normals(
+            vec![((0, 1, 2), [up, up, up]), ((1, 3, 2), [side, side, side])],
+            4,
+        );
+
+        assert_eq!(vertex_normals.face_overrides.len(), 2);
+        assert!(vertex_normals.corner_overrides.is_empty());
+        assert_eq!(vertex_normals.corner_normal(1, 3), side);
+        assert_eq!(vertex_normals.corner_normal(0, 0), up);
+        assert_eq!(vertex_normals.vertex_normal(3), side);
+    }
+
+    #[test]
+    fn test_vertex_normals_from_indexed_normals_round_trip() {
+        let faces = vec![((0, 1, 2), (0, 0, 0)), ((2, 1, 3), (1, 2, 2))];
+        let normals = vec![Vector3::z(), Vector3::x(), Vector3::y()];
+        let vertex_normals = VertexNormals::from_indexed_normals(faces.clone(), &normals, 4);
+        let mesh_faces: Vec<Face> = faces
+            .iter()
+            .map(|((v1, v2, v3), _)| Face::from(crate::mesh::TriangleFace::new(*v1, *v2, *v3)))
+            .collect();
+
+        let (normal_indices, indexed_normals) = vertex_normals.to_indexed_normals(&mesh_faces);
+
+        for (face_index, (face, normal_indices)) in
+            mesh_faces.iter().zip(normal_indices).enumerate()
+        {
+            let face = face.to_triangles()[0];
+            let (n1, n2, n3) = (normal_indices[0], normal_indices[1], normal_indices[2]);
+            let corner_normals = vertex_normals.face_corner_normals(face_index, face.vertices);
+            assert_eq!(
+                [
+                    indexed_normals[cast_usize(n1)],
+                    indexed_normals[cast_usize(n2)],
+                    indexed_normals[cast_usize(n3)],
+                ],
+                corner_normals,
+            );
+        }
+        assert!(vertex_normals.is_consistent_with(&mesh_faces, 4));
+    }
+
+    #[test]
+    fn test_vertex_normals_survive_face_reverting() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+
+        let reverted = crate::mesh::tools::revert_mesh_faces(&mesh);
+
+        for (face_index, face) in mesh.triangle_faces_iter().enumerate() {
+            let (v1, v2, v3) = face.vertices;
+            let [n1, n2, n3] = mesh.corner_normals(face_index);
+            let reverted_normals = reverted.corner_normals(face_index);
+            let reverted_face = reverted.triangle_face(face_index);
+            for (vertex_index, normal) in &[(v1, n1), (v2, n2), (v3, n3)] {
+                let (r1, r2, r3) = reverted_face.vertices;
+                let position = [r1, r2, r3]
+                    .iter()
+                    .position(|reverted_index| reverted_index == vertex_index)
+                    .expect("Reverted face must have the same vertices");
+                assert_eq!(reverted_normals[position], *normal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_vertex_normals_is_consistent_with_rejects_foreign_corner() {
+        let faces = vec![Face::from(crate::mesh::TriangleFace::new(0, 1, 2))];
+        let mut vertex_normals = VertexNormals::from_vertex_normals(vec![Vector3::z(); 4]);
+        vertex_normals.corner_overrides.push((0, 3, Vector3::x()));
+
+        assert!(!vertex_normals.is_consistent_with(&faces, 4));
+        assert!(!VertexNormals::from_vertex_normals(vec![Vector3::z(); 3])
+            .is_consistent_with(&faces, 4));
+    }
+
+    #[test]
+    fn test_vertex_normals_append_offsets_faces_and_vertices() {
+        let up = Vector3::z();
+        let side = Vector3::x();
+        let mut vertex_normals =
+            VertexNormals::from_corner_normals(vec![((0, 1, 2), [up, up, up])], 3);
+        let other = VertexNormals::from_corner_normals(
+            vec![((0, 1, 2), [up, up, up]), ((1, 3, 2), [side, up, up])],
+            4,
+        );
+
+        vertex_normals.append(&other, 1);
+
+        assert_eq!(vertex_normals.vertex_count(), 7);
+        assert_eq!(vertex_normals.corner_override(0, 1), None);
+        assert_eq!(vertex_normals.corner_override(1, 4), Some(up));
+        assert_eq!(vertex_normals.corner_override(2, 4), Some(side));
+        assert_eq!(vertex_normals.corner_override(2, 6), None);
+    }
+
+    #[test]
+    fn test_mesh_with_vertex_normals_is_visually_similar_after_round_trip() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+        let faces = mesh.triangle_faces_iter().map(|face| face.vertices);
+
+        let converted = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            mesh.vertices().to_vec(),
+            mesh.vertex_normals().clone(),
+        );
+
+        assert!(crate::mesh::analysis::are_visually_similar(
+            &mesh, &converted
+        ));
+        assert_eq!(mesh, converted);
+    }
+}
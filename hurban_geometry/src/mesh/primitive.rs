@@ -0,0 +1,868 @@
+use std::cmp;
+use std::f32;
+
+use nalgebra::{Matrix4, Point3, Rotation3, Vector2, Vector3};
+
+use crate::collections::HashMap;
+use crate::convert::{cast_u32, cast_usize};
+use crate::curve::Curve;
+use crate::plane::Plane;
+
+use super::normals::VertexNormals;
+use super::{Mesh, NormalStrategy};
+
+pub fn create_mesh_plane(plane: Plane, scale: Vector2<f32>) -> Mesh {
+    #[rustfmt::skip]
+    let vertex_positions = vec![
+        plane.origin()
+            + (-0.5 * plane.x_vector() * scale.x)
+            + (-0.5 * plane.y_vector() * scale.y),
+        plane.origin()
+            + ( 0.5 * plane.x_vector() * scale.x)
+            + (-0.5 * plane.y_vector() * scale.y),
+        plane.origin()
+            + ( 0.5 * plane.x_vector() * scale.x)
+            + ( 0.5 * plane.y_vector() * scale.y),
+        plane.origin()
+            + (-0.5 * plane.x_vector() * scale.x)
+            + ( 0.5 * plane.y_vector() * scale.y),
+    ];
+
+    let vertex_normals = VertexNormals::from_vertex_normals(vec![plane.normal(); 4]);
+
+    let faces = vec![(0, 1, 2), (2, 3, 0)];
+
+    Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+        faces,
+        vertex_positions,
+        vertex_normals,
+    )
+}
+
+pub fn create_box(center: Point3<f32>, rotate: Rotation3<f32>, scale: Vector3<f32>) -> Mesh {
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let scaling = Matrix4::new_nonuniform_scaling(&scale);
+
+    let t = translation * rotation * scaling;
+
+    #[rustfmt::skip]
+    let vertex_positions = vec![
+        // back
+        t.transform_point(&Point3::new(-0.5,  0.5, -0.5)),
+        t.transform_point(&Point3::new(-0.5,  0.5,  0.5)),
+        t.transform_point(&Point3::new( 0.5,  0.5,  0.5)),
+        t.transform_point(&Point3::new( 0.5,  0.5, -0.5)),
+        // front
+        t.transform_point(&Point3::new(-0.5, -0.5, -0.5)),
+        t.transform_point(&Point3::new( 0.5, -0.5, -0.5)),
+        t.transform_point(&Point3::new( 0.5, -0.5,  0.5)),
+        t.transform_point(&Point3::new(-0.5, -0.5,  0.5)),
+    ];
+
+    #[rustfmt::skip]
+    let face_normals = [
+        // back
+        Vector3::new( 0.0,  1.0,  0.0),
+        // front
+        Vector3::new( 0.0, -1.0,  0.0),
+        // top
+        Vector3::new( 0.0,  0.0,  1.0),
+        // bottom
+        Vector3::new( 0.0,  0.0, -1.0),
+        // right
+        Vector3::new( 1.0,  0.0,  0.0),
+        // left
+        Vector3::new(-1.0,  0.0,  0.0),
+    ];
+
+    let faces = vec![
+        // back
+        ((0, 1, 2), 0),
+        ((2, 3, 0), 0),
+        // front
+        ((4, 5, 6), 1),
+        ((6, 7, 4), 1),
+        // top
+        ((7, 6, 1), 2),
+        ((2, 1, 6), 2),
+        // bottom
+        ((5, 0, 3), 3),
+        ((0, 5, 4), 3),
+        // right
+        ((6, 3, 2), 4),
+        ((3, 6, 5), 4),
+        // left
+        ((4, 7, 0), 5),
+        ((1, 0, 7), 5),
+    ];
+
+    Mesh::from_triangle_faces_with_vertices_and_corner_normals(
+        faces
+            .into_iter()
+            .map(|(face, normal_index)| (face, [face_normals[normal_index]; 3])),
+        vertex_positions,
+    )
+}
+
+/// Create UV Sphere primitive at `position` with `scale`,
+/// `n_parallels` and `n_meridians`.
+///
+/// # Panics
+/// Panics if number of parallels is less than 2 or number of
+/// meridians is less than 3.
+pub fn create_uv_sphere(
+    center: Point3<f32>,
+    rotate: Rotation3<f32>,
+    scale: Vector3<f32>,
+    n_parallels: u32,
+    n_meridians: u32,
+    normal_strategy: NormalStrategy,
+) -> Mesh {
+    assert!(n_parallels >= 2, "Need at least 2 parallels");
+    assert!(n_meridians >= 3, "Need at least 3 meridians");
+
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let scaling = Matrix4::new_nonuniform_scaling(&scale);
+
+    let t = translation * rotation * scaling;
+
+    // Add the poles
+    let lat_line_max = n_parallels + 2;
+    // Add the last, wrapping meridian
+    let lng_line_max = n_meridians + 1;
+
+    use std::f32::consts::PI;
+    const TWO_PI: f32 = 2.0 * PI;
+
+    // 1 North pole + 1 South pole + `n_parallels` * `n_meridians`
+    let vertex_data_count = cast_usize(2 + n_parallels * n_meridians);
+    let mut vertex_positions = Vec::with_capacity(vertex_data_count);
+
+    // Produce vertex data for bands in between parallels
+
+    for lat_line in 0..n_parallels {
+        for lng_line in 0..n_meridians {
+            let polar_t = (lat_line + 1) as f32 / (lat_line_max - 1) as f32;
+            let azimuthal_t = lng_line as f32 / (lng_line_max - 1) as f32;
+
+            let x = (PI * polar_t).sin() * (TWO_PI * azimuthal_t).cos();
+            let y = (PI * polar_t).sin() * (TWO_PI * azimuthal_t).sin();
+            let z = (PI * polar_t).cos();
+
+            let point = t.transform_point(&Point3::new(x * 0.5, y * 0.5, z * 0.5));
+            vertex_positions.push(point);
+        }
+    }
+
+    // Triangles from North and South poles to the nearest band + 2 * quads in bands
+    let faces_count = cast_usize(2 * n_meridians + 2 * n_meridians * (n_parallels - 1));
+    let mut faces = Vec::with_capacity(faces_count);
+
+    // Produce faces for bands in-between parallels
+
+    for i in 1..n_parallels {
+        for j in 0..n_meridians {
+            // Produce 2 CCW wound triangles: (p1, p2, p3) and (p3, p4, p1)
+
+            let p1 = i * n_meridians + j;
+            let p2 = i * n_meridians + ((j + 1) % n_meridians);
+
+            let p4 = (i - 1) * n_meridians + j;
+            let p3 = (i - 1) * n_meridians + ((j + 1) % n_meridians);
+
+            faces.push((p1, p2, p3));
+            faces.push((p3, p4, p1));
+        }
+    }
+
+    // Add vertex data and band-connecting faces for North and South poles
+
+    let north_pole = cast_u32(vertex_positions.len());
+    vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, 0.5)));
+
+    let south_pole = cast_u32(vertex_positions.len());
+    vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, -0.5)));
+
+    for i in 0..n_meridians {
+        let north_p1 = i;
+        let north_p2 = (i + 1) % n_meridians;
+
+        let south_p1 = (n_parallels - 1) * n_meridians + i;
+        let south_p2 = (n_parallels - 1) * n_meridians + ((i + 1) % n_meridians);
+
+        faces.push((north_p1, north_p2, north_pole));
+        faces.push((south_p2, south_p1, south_pole));
+    }
+
+    assert_eq!(vertex_positions.len(), vertex_data_count);
+    assert_eq!(vertex_positions.capacity(), vertex_data_count);
+    assert_eq!(faces.len(), faces_count);
+    assert_eq!(faces.capacity(), faces_count);
+
+    Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+        faces,
+        vertex_positions,
+        normal_strategy,
+    )
+}
+
+/// Create an icosphere primitive with `radius` at `center`. The
+/// triangles of an icosahedron are recursively split into 4
+/// `subdivisions` times and their vertices pushed to the sphere, so
+/// unlike the UV sphere, the triangles have similar sizes everywhere.
+pub fn create_icosphere(
+    center: Point3<f32>,
+    rotate: Rotation3<f32>,
+    radius: f32,
+    subdivisions: u32,
+    normal_strategy: NormalStrategy,
+) -> Mesh {
+    let golden_ratio = (1.0 + 5.0f32.sqrt()) / 2.0;
+
+    #[rustfmt::skip]
+    let mut unit_positions: Vec<Vector3<f32>> = vec![
+        Vector3::new(-1.0,  golden_ratio,  0.0),
+        Vector3::new( 1.0,  golden_ratio,  0.0),
+        Vector3::new(-1.0, -golden_ratio,  0.0),
+        Vector3::new( 1.0, -golden_ratio,  0.0),
+        Vector3::new( 0.0, -1.0,  golden_ratio),
+        Vector3::new( 0.0,  1.0,  golden_ratio),
+        Vector3::new( 0.0, -1.0, -golden_ratio),
+        Vector3::new( 0.0,  1.0, -golden_ratio),
+        Vector3::new( golden_ratio,  0.0, -1.0),
+        Vector3::new( golden_ratio,  0.0,  1.0),
+        Vector3::new(-golden_ratio,  0.0, -1.0),
+        Vector3::new(-golden_ratio,  0.0,  1.0),
+    ]
+    .into_iter()
+    .map(|position| position.normalize())
+    .collect();
+
+    let mut faces: Vec<(u32, u32, u32)> = vec![
+        (0, 11, 5),
+        (0, 5, 1),
+        (0, 1, 7),
+        (0, 7, 10),
+        (0, 10, 11),
+        (1, 5, 9),
+        (5, 11, 4),
+        (11, 10, 2),
+        (10, 7, 6),
+        (7, 1, 8),
+        (3, 9, 4),
+        (3, 4, 2),
+        (3, 2, 6),
+        (3, 6, 8),
+        (3, 8, 9),
+        (4, 9, 5),
+        (2, 4, 11),
+        (6, 2, 10),
+        (8, 6, 7),
+        (9, 8, 1),
+    ];
+
+    for _ in 0..subdivisions {
+        // Each edge is split once for both of its faces, so that the
+        // sphere stays watertight
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::default();
+        let mut midpoint = |v1: u32, v2: u32| {
+            let key = (v1.min(v2), v1.max(v2));
+            *midpoints.entry(key).or_insert_with(|| {
+                let position =
+                    (unit_positions[cast_usize(v1)] + unit_positions[cast_usize(v2)]).normalize();
+                unit_positions.push(position);
+                cast_u32(unit_positions.len() - 1)
+            })
+        };
+
+        faces = faces
+            .iter()
+            .flat_map(|&(v1, v2, v3)| {
+                let m12 = midpoint(v1, v2);
+                let m23 = midpoint(v2, v3);
+                let m31 = midpoint(v3, v1);
+
+                vec![
+                    (v1, m12, m31),
+                    (v2, m23, m12),
+                    (v3, m31, m23),
+                    (m12, m23, m31),
+                ]
+            })
+            .collect();
+    }
+
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let t = translation * rotation;
+
+    let vertex_positions: Vec<Point3<f32>> = unit_positions
+        .iter()
+        .map(|position| t.transform_point(&Point3::from(position * radius)))
+        .collect();
+
+    Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+        faces,
+        vertex_positions,
+        normal_strategy,
+    )
+}
+
+/// Create a cylinder primitive with `radius` and `height` along the Z
+/// axis, centered at `center`. The side has smooth normals, the
+/// optional `caps` are flat.
+///
+/// # Panics
+/// Panics if number of segments is less than 3.
+pub fn create_cylinder(
+    center: Point3<f32>,
+    rotate: Rotation3<f32>,
+    radius: f32,
+    height: f32,
+    n_segments: u32,
+    caps: bool,
+) -> Mesh {
+    assert!(n_segments >= 3, "Need at least 3 segments");
+
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let t = translation * rotation;
+
+    let half_height = height / 2.0;
+    let directions = radial_directions(n_segments);
+
+    // Bottom ring, top ring and the centers of the caps
+    let mut vertex_positions = Vec::with_capacity(directions.len() * 2 + 2);
+    for z in &[-half_height, half_height] {
+        for direction in &directions {
+            let position = direction * radius + Vector3::new(0.0, 0.0, *z);
+            vertex_positions.push(t.transform_point(&Point3::from(position)));
+        }
+    }
+
+    // Radial normals of the side and the normals of the caps
+    let side_normals: Vec<Vector3<f32>> = directions
+        .iter()
+        .map(|direction| rotate * direction)
+        .collect();
+    let top_normal = rotate * Vector3::z();
+    let bottom_normal = rotate * -Vector3::z();
+
+    let mut faces = Vec::new();
+    for i in 0..n_segments {
+        let j = (i + 1) % n_segments;
+        let (bottom_i, bottom_j) = (i, j);
+        let (top_i, top_j) = (n_segments + i, n_segments + j);
+        let (normal_i, normal_j) = (side_normals[cast_usize(i)], side_normals[cast_usize(j)]);
+
+        faces.push(((bottom_i, bottom_j, top_j), [normal_i, normal_j, normal_j]));
+        faces.push(((top_j, top_i, bottom_i), [normal_j, normal_i, normal_i]));
+    }
+
+    if caps {
+        let bottom_center = cast_u32(vertex_positions.len());
+        vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, -half_height)));
+        let top_center = cast_u32(vertex_positions.len());
+        vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, half_height)));
+
+        for i in 0..n_segments {
+            let j = (i + 1) % n_segments;
+            faces.push((
+                (top_center, n_segments + i, n_segments + j),
+                [top_normal; 3],
+            ));
+            faces.push(((bottom_center, j, i), [bottom_normal; 3]));
+        }
+    }
+
+    Mesh::from_triangle_faces_with_vertices_and_corner_normals(faces, vertex_positions)
+}
+
+/// Create a cone primitive with base `radius` and `height` along the Z
+/// axis, centered at `center`, with the apex on top. The side has
+/// smooth normals, the optional base `cap` is flat.
+///
+/// # Panics
+/// Panics if number of segments is less than 3.
+pub fn create_cone(
+    center: Point3<f32>,
+    rotate: Rotation3<f32>,
+    radius: f32,
+    height: f32,
+    n_segments: u32,
+    cap: bool,
+) -> Mesh {
+    assert!(n_segments >= 3, "Need at least 3 segments");
+
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let t = translation * rotation;
+
+    let half_height = height / 2.0;
+    let directions = radial_directions(n_segments);
+
+    // Base ring, apex and the center of the cap
+    let mut vertex_positions: Vec<Point3<f32>> = directions
+        .iter()
+        .map(|direction| {
+            let position = direction * radius - Vector3::new(0.0, 0.0, half_height);
+            t.transform_point(&Point3::from(position))
+        })
+        .collect();
+    let apex = cast_u32(vertex_positions.len());
+    vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, half_height)));
+
+    // The side normals tilt up by the slope of the cone. The apex has
+    // a normal for each face, pointing between its base vertices.
+    let side_normal = |direction: Vector3<f32>| {
+        rotate * (direction * height + Vector3::new(0.0, 0.0, radius)).normalize()
+    };
+    let side_normals: Vec<Vector3<f32>> = directions
+        .iter()
+        .map(|direction| side_normal(*direction))
+        .collect();
+    let bottom_normal = rotate * -Vector3::z();
+
+    let mut faces = Vec::new();
+    for i in 0..n_segments {
+        let j = (i + 1) % n_segments;
+        let (direction_i, direction_j) = (directions[cast_usize(i)], directions[cast_usize(j)]);
+        let apex_normal = side_normal((direction_i + direction_j).normalize());
+        faces.push((
+            (i, j, apex),
+            [
+                side_normals[cast_usize(i)],
+                side_normals[cast_usize(j)],
+                apex_normal,
+            ],
+        ));
+    }
+
+    if cap {
+        let bottom_center = cast_u32(vertex_positions.len());
+        vertex_positions.push(t.transform_point(&Point3::new(0.0, 0.0, -half_height)));
+
+        for i in 0..n_segments {
+            let j = (i + 1) % n_segments;
+            faces.push(((bottom_center, j, i), [bottom_normal; 3]));
+        }
+    }
+
+    Mesh::from_triangle_faces_with_vertices_and_corner_normals(faces, vertex_positions)
+}
+
+/// Create a torus primitive around the Z axis, centered at `center`.
+/// The `major_radius` is the distance from the center to the middle of
+/// the tube, whose own radius is the `minor_radius`.
+///
+/// # Panics
+/// Panics if number of major or minor segments is less than 3.
+pub fn create_torus(
+    center: Point3<f32>,
+    rotate: Rotation3<f32>,
+    major_radius: f32,
+    minor_radius: f32,
+    n_major_segments: u32,
+    n_minor_segments: u32,
+    normal_strategy: NormalStrategy,
+) -> Mesh {
+    assert!(n_major_segments >= 3, "Need at least 3 major segments");
+    assert!(n_minor_segments >= 3, "Need at least 3 minor segments");
+
+    let translation = Matrix4::new_translation(&center.coords);
+    let rotation = Matrix4::from(rotate);
+    let t = translation * rotation;
+
+    let major_directions = radial_directions(n_major_segments);
+    let minor_directions = radial_directions(n_minor_segments);
+
+    let mut vertex_positions = Vec::with_capacity(major_directions.len() * minor_directions.len());
+    for major_direction in &major_directions {
+        for minor_direction in &minor_directions {
+            // The X of the minor direction points away from the torus
+            // center, its Y up
+            let position = major_direction * (major_radius + minor_radius * minor_direction.x)
+                + Vector3::new(0.0, 0.0, minor_radius * minor_direction.y);
+            vertex_positions.push(t.transform_point(&Point3::from(position)));
+        }
+    }
+
+    let vertex_index = |major: u32, minor: u32| {
+        (major % n_major_segments) * n_minor_segments + (minor % n_minor_segments)
+    };
+    let mut faces = Vec::with_capacity(cast_usize(2 * n_major_segments * n_minor_segments));
+    for i in 0..n_major_segments {
+        for j in 0..n_minor_segments {
+            let p1 = vertex_index(i, j);
+            let p2 = vertex_index(i + 1, j);
+            let p3 = vertex_index(i + 1, j + 1);
+            let p4 = vertex_index(i, j + 1);
+
+            faces.push((p1, p2, p3));
+            faces.push((p3, p4, p1));
+        }
+    }
+
+    Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+        faces,
+        vertex_positions,
+        normal_strategy,
+    )
+}
+
+/// Create a pipe of `radius` around each polyline of the `curve`,
+/// with `n_segments` around its circumference. Open polylines get
+/// flat caps at their ends, so that all the pipes are watertight.
+/// Returns `None` if the curve has no segments of non-zero length.
+///
+/// The cross-sections are oriented by parallel transport along the
+/// polyline, so that the pipe does not twist. Pipes of closed
+/// polylines spread the twist remaining at their seam evenly along
+/// their length.
+///
+/// # Panics
+/// Panics if number of segments is less than 3.
+pub fn create_pipe(curve: &Curve, radius: f32, n_segments: u32) -> Option<Mesh> {
+    assert!(n_segments >= 3, "Need at least 3 segments");
+
+    let directions = radial_directions(n_segments);
+    let mut vertex_positions = Vec::new();
+    let mut faces = Vec::new();
+
+    for polyline in curve.polylines() {
+        // Coincident consecutive vertices have no direction to follow
+        let mut points: Vec<Point3<f32>> = Vec::with_capacity(polyline.vertices().len());
+        for vertex in polyline.vertices() {
+            let coincident = points.last().map_or(false, |last| {
+                nalgebra::distance(last, vertex) <= f32::EPSILON
+            });
+            if !coincident {
+                points.push(*vertex);
+            }
+        }
+        if polyline.is_closed()
+            && points.len() > 1
+            && nalgebra::distance(&points[0], &points[points.len() - 1]) <= f32::EPSILON
+        {
+            points.pop();
+        }
+
+        let n_points = points.len();
+        if n_points < 2 {
+            continue;
+        }
+        let closed = polyline.is_closed() && n_points >= 3;
+
+        let n_lengths = if closed { n_points } else { n_points - 1 };
+        let segment_directions: Vec<Vector3<f32>> = (0..n_lengths)
+            .map(|i| (points[(i + 1) % n_points] - points[i]).normalize())
+            .collect();
+
+        // The cross-sections at the joints bisect the segments
+        let tangents: Vec<Vector3<f32>> = (0..n_points)
+            .map(|i| {
+                let (incoming, outgoing) = if closed {
+                    (
+                        segment_directions[(i + n_lengths - 1) % n_lengths],
+                        segment_directions[i],
+                    )
+                } else {
+                    (
+                        segment_directions[i.saturating_sub(1)],
+                        segment_directions[cmp::min(i, n_lengths - 1)],
+                    )
+                };
+                let bisector = incoming + outgoing;
+                if bisector.norm() <= f32::EPSILON {
+                    outgoing
+                } else {
+                    bisector.normalize()
+                }
+            })
+            .collect();
+
+        let first_tangent = tangents[0];
+        let helper = if first_tangent.x.abs() < 0.9 {
+            Vector3::x()
+        } else {
+            Vector3::y()
+        };
+        let mut normals = Vec::with_capacity(n_points);
+        normals.push(first_tangent.cross(&helper).normalize());
+        for i in 1..n_points {
+            let normal = transport_normal(&normals[i - 1], &tangents[i - 1], &tangents[i]);
+            normals.push(normal);
+        }
+
+        if closed {
+            let seam_normal = transport_normal(
+                &normals[n_points - 1],
+                &tangents[n_points - 1],
+                &first_tangent,
+            );
+            let twist = seam_normal
+                .cross(&normals[0])
+                .dot(&first_tangent)
+                .atan2(seam_normal.dot(&normals[0]));
+            for (i, (normal, tangent)) in normals.iter_mut().zip(&tangents).enumerate() {
+                let angle = twist * i as f32 / n_points as f32;
+                *normal = *normal * angle.cos() + tangent.cross(normal) * angle.sin();
+            }
+        }
+
+        // A ring of vertices around each point, with their radial
+        // directions as the normals of the side
+        let first_vertex_index = cast_u32(vertex_positions.len());
+        let ring_vertex_index = |point: usize, segment: u32| {
+            first_vertex_index + cast_u32(point) * n_segments + segment % n_segments
+        };
+        let mut radials = Vec::with_capacity(n_points * directions.len());
+        for ((point, normal), tangent) in points.iter().zip(&normals).zip(&tangents) {
+            let binormal = tangent.cross(normal);
+            for direction in &directions {
+                let radial = normal * direction.x + binormal * direction.y;
+                vertex_positions.push(point + radial * radius);
+                radials.push(radial);
+            }
+        }
+        let radial = |vertex_index: u32| radials[cast_usize(vertex_index - first_vertex_index)];
+
+        for i in 0..n_lengths {
+            let next = (i + 1) % n_points;
+            for j in 0..n_segments {
+                let p1 = ring_vertex_index(i, j);
+                let p2 = ring_vertex_index(i, j + 1);
+                let p3 = ring_vertex_index(next, j + 1);
+                let p4 = ring_vertex_index(next, j);
+
+                faces.push(((p1, p2, p3), [radial(p1), radial(p2), radial(p3)]));
+                faces.push(((p3, p4, p1), [radial(p3), radial(p4), radial(p1)]));
+            }
+        }
+
+        if !closed {
+            let last = n_points - 1;
+            let start_normal = -tangents[0];
+            let end_normal = tangents[last];
+
+            let start_center = cast_u32(vertex_positions.len());
+            vertex_positions.push(points[0]);
+            let end_center = cast_u32(vertex_positions.len());
+            vertex_positions.push(points[last]);
+
+            for j in 0..n_segments {
+                faces.push((
+                    (
+                        start_center,
+                        ring_vertex_index(0, j + 1),
+                        ring_vertex_index(0, j),
+                    ),
+                    [start_normal; 3],
+                ));
+                faces.push((
+                    (
+                        end_center,
+                        ring_vertex_index(last, j),
+                        ring_vertex_index(last, j + 1),
+                    ),
+                    [end_normal; 3],
+                ));
+            }
+        }
+    }
+
+    if faces.is_empty() {
+        None
+    } else {
+        Some(Mesh::from_triangle_faces_with_vertices_and_corner_normals(
+            faces,
+            vertex_positions,
+        ))
+    }
+}
+
+/// Carries the `normal` of a cross-section from a point with tangent
+/// `from` to the next point with tangent `to`, rotating it as little as
+/// possible.
+fn transport_normal(normal: &Vector3<f32>, from: &Vector3<f32>, to: &Vector3<f32>) -> Vector3<f32> {
+    let rotated =
+        Rotation3::rotation_between(from, to).map_or(*normal, |rotation| rotation * normal);
+    // Keep the normal in the plane of the cross-section, despite the
+    // rounding errors
+    (rotated - to * rotated.dot(to)).normalize()
+}
+
+/// Unit vectors in the XY plane, evenly spread counterclockwise around
+/// the Z axis, starting at the X axis.
+fn radial_directions(n_segments: u32) -> Vec<Vector3<f32>> {
+    (0..n_segments)
+        .map(|segment| {
+            let angle = 2.0 * f32::consts::PI * segment as f32 / n_segments as f32;
+            Vector3::new(angle.cos(), angle.sin(), 0.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::Polyline;
+    use crate::mesh::analysis;
+    use crate::mesh::OrientedEdge;
+
+    use super::*;
+
+    fn is_watertight(mesh: &Mesh) -> bool {
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        analysis::is_mesh_watertight(&analysis::edge_sharing(&oriented_edges))
+    }
+
+    #[test]
+    fn test_create_icosphere_is_watertight_and_encloses_sphere_volume() {
+        let radius = 2.0;
+        let icosphere = create_icosphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            radius,
+            3,
+            NormalStrategy::Smooth,
+        );
+
+        assert_eq!(icosphere.faces().len(), 20 * 4 * 4 * 4);
+        assert_eq!(icosphere.vertices().len(), 642);
+        assert!(is_watertight(&icosphere));
+        for vertex in icosphere.vertices() {
+            assert!(approx::relative_eq!(
+                vertex.coords.norm(),
+                radius,
+                epsilon = 0.0001,
+            ));
+        }
+
+        let sphere_volume = 4.0 / 3.0 * f32::consts::PI * radius.powi(3);
+        assert!(approx::relative_eq!(
+            analysis::signed_volume(&icosphere),
+            sphere_volume,
+            max_relative = 0.03,
+        ));
+    }
+
+    #[test]
+    fn test_create_cylinder_with_caps_is_watertight_and_encloses_cylinder_volume() {
+        let cylinder = create_cylinder(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::from_euler_angles(0.3, 0.2, 0.1),
+            0.5,
+            2.0,
+            64,
+            true,
+        );
+
+        assert!(is_watertight(&cylinder));
+        let cylinder_volume = f32::consts::PI * 0.5 * 0.5 * 2.0;
+        assert!(approx::relative_eq!(
+            analysis::signed_volume(&cylinder),
+            cylinder_volume,
+            max_relative = 0.01,
+        ));
+    }
+
+    #[test]
+    fn test_create_cylinder_without_caps_is_open_tube() {
+        let tube = create_cylinder(Point3::origin(), Rotation3::identity(), 0.5, 1.0, 8, false);
+
+        assert_eq!(tube.vertices().len(), 16);
+        assert_eq!(tube.faces().len(), 16);
+        assert!(!is_watertight(&tube));
+    }
+
+    #[test]
+    fn test_create_cone_with_cap_is_watertight_and_encloses_cone_volume() {
+        let cone = create_cone(Point3::origin(), Rotation3::identity(), 1.0, 3.0, 64, true);
+
+        assert!(is_watertight(&cone));
+        let cone_volume = f32::consts::PI * 1.0 * 1.0 * 3.0 / 3.0;
+        assert!(approx::relative_eq!(
+            analysis::signed_volume(&cone),
+            cone_volume,
+            max_relative = 0.01,
+        ));
+    }
+
+    #[test]
+    fn test_create_pipe_of_open_polyline_is_watertight_and_encloses_cylinder_volume() {
+        let curve = Curve::new(vec![Polyline::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(0.0, 0.0, 1.0),
+                Point3::new(0.0, 0.0, 1.0),
+                Point3::new(0.0, 0.0, 2.0),
+            ],
+            false,
+        )]);
+
+        let pipe = create_pipe(&curve, 0.5, 64).expect("Pipe should be created");
+
+        assert_eq!(pipe.vertices().len(), 3 * 64 + 2);
+        assert!(is_watertight(&pipe));
+        let cylinder_volume = f32::consts::PI * 0.5 * 0.5 * 2.0;
+        assert!(approx::relative_eq!(
+            analysis::signed_volume(&pipe),
+            cylinder_volume,
+            max_relative = 0.01,
+        ));
+    }
+
+    #[test]
+    fn test_create_pipe_of_closed_polyline_is_watertight_without_caps() {
+        let curve = Curve::new(vec![Polyline::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(4.0, 0.0, 0.0),
+                Point3::new(4.0, 4.0, 1.0),
+                Point3::new(0.0, 4.0, 0.0),
+            ],
+            true,
+        )]);
+
+        let pipe = create_pipe(&curve, 0.25, 8).expect("Pipe should be created");
+
+        assert_eq!(pipe.vertices().len(), 4 * 8);
+        assert_eq!(pipe.faces().len(), 4 * 8 * 2);
+        assert!(is_watertight(&pipe));
+        assert!(analysis::signed_volume(&pipe) > 0.0);
+    }
+
+    #[test]
+    fn test_create_pipe_of_degenerate_curve_returns_none() {
+        let curve = Curve::new(vec![Polyline::new(
+            vec![Point3::new(1.0, 1.0, 1.0), Point3::new(1.0, 1.0, 1.0)],
+            false,
+        )]);
+
+        assert!(create_pipe(&curve, 0.5, 8).is_none());
+    }
+
+    #[test]
+    fn test_create_torus_is_watertight_and_encloses_torus_volume() {
+        let (major_radius, minor_radius) = (2.0, 0.5);
+        let torus = create_torus(
+            Point3::origin(),
+            Rotation3::identity(),
+            major_radius,
+            minor_radius,
+            64,
+            32,
+            NormalStrategy::Smooth,
+        );
+
+        assert!(is_watertight(&torus));
+        let torus_volume = 2.0 * f32::consts::PI.powi(2) * major_radius * minor_radius.powi(2);
+        assert!(approx::relative_eq!(
+            analysis::signed_volume(&torus),
+            torus_volume,
+            max_relative = 0.02,
+        ));
+    }
+}
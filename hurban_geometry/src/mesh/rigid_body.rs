@@ -0,0 +1,284 @@
+use std::f32;
+
+use nalgebra::{Isometry3, Matrix3, Point3, Translation3, UnitQuaternion, Vector3};
+
+use crate::collections::HashSet;
+
+use super::{analysis, Mesh};
+
+/// Simulation time step in seconds.
+const TIME_STEP: f32 = 1.0 / 120.0;
+/// Simulated time after which the body is considered not to settle.
+const SIMULATION_TIME_MAX: f32 = 30.0;
+/// Gravitational acceleration in mesh sizes per second squared.
+const GRAVITY: f32 = 9.81;
+const SOLVER_ITERATIONS: usize = 10;
+const FRICTION_COEFFICIENT: f32 = 0.6;
+/// How much of the ground penetration is corrected in each step.
+const PENETRATION_CORRECTION_FACTOR: f32 = 0.2;
+/// Penetration tolerated without correction, avoiding jitter of
+/// resting contacts.
+const PENETRATION_SLOP: f32 = 0.001;
+/// Distance above the ground at which vertices become contacts.
+const CONTACT_MARGIN: f32 = 0.002;
+const LINEAR_DAMPING: f32 = 0.999;
+const ANGULAR_DAMPING: f32 = 0.995;
+const SLEEP_LINEAR_VELOCITY: f32 = 0.005;
+const SLEEP_ANGULAR_VELOCITY: f32 = 0.01;
+/// How long the body has to stay still to be considered settled.
+const SLEEP_TIME: f32 = 0.5;
+/// Number of directions in which extreme vertices are collected as
+/// contact candidates.
+const SUPPORT_DIRECTION_COUNT: usize = 512;
+
+/// The result of dropping a mesh onto the ground plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropSimulation {
+    /// Transform placing the original mesh in its resting pose.
+    pub transform: Isometry3<f32>,
+    /// Whether the body came to rest before the simulation time ran
+    /// out.
+    pub settled: bool,
+    /// Simulated time in seconds.
+    pub time: f32,
+}
+
+/// Simulates the mesh as a rigid body with unit density falling onto
+/// the ground plane (`z = 0`) from `drop_height` above it, until it
+/// comes to rest.
+///
+/// The body collides with the ground with the extreme vertices of its
+/// convex envelope and has no bounce. The simulation runs in units of
+/// the mesh size, so that its outcome does not depend on the mesh
+/// scale. Returns `None` if the mesh encloses no volume.
+pub fn simulate_drop(mesh: &Mesh, drop_height: f32) -> Option<DropSimulation> {
+    let mass_properties = analysis::compute_mass_properties(mesh)?;
+    let size = mesh.bounding_box().diagonal().norm();
+    let scale = 1.0 / size;
+
+    // Body space has the origin in the center of mass and is scaled
+    // to the unit mesh size
+    let center_of_mass = mass_properties.center_of_mass;
+    let contact_candidates: Vec<Vector3<f32>> = support_vertices(mesh.vertices())
+        .iter()
+        .map(|vertex| (vertex - center_of_mass) * scale)
+        .collect();
+    let inverse_mass = 1.0 / (mass_properties.volume * scale.powi(3));
+    let inverse_inertia_body = (mass_properties.inertia_tensor * scale.powi(5))
+        .try_inverse()
+        .unwrap_or_else(Matrix3::zeros);
+
+    let lowest_point = contact_candidates
+        .iter()
+        .map(|vertex| vertex.z)
+        .fold(f32::INFINITY, f32::min);
+    let mut position = Vector3::new(
+        center_of_mass.x * scale,
+        center_of_mass.y * scale,
+        drop_height.max(0.0) * scale - lowest_point,
+    );
+    let mut orientation = UnitQuaternion::identity();
+    let mut linear_velocity = Vector3::zeros();
+    let mut angular_velocity = Vector3::zeros();
+
+    let step_count = (SIMULATION_TIME_MAX / TIME_STEP) as usize;
+    let sleep_step_count = (SLEEP_TIME / TIME_STEP) as usize;
+    let mut still_step_count = 0;
+    let mut step = 0;
+
+    while step < step_count && still_step_count < sleep_step_count {
+        step += 1;
+        linear_velocity.z -= GRAVITY * TIME_STEP;
+
+        let rotation_matrix = orientation.to_rotation_matrix();
+        let inverse_inertia =
+            rotation_matrix.matrix() * inverse_inertia_body * rotation_matrix.matrix().transpose();
+
+        // Contacts are the vertex offsets from the center of mass and
+        // their penetration of the ground
+        let contacts: Vec<(Vector3<f32>, f32)> = contact_candidates
+            .iter()
+            .map(|vertex| orientation * vertex)
+            .filter(|offset| position.z + offset.z < CONTACT_MARGIN)
+            .map(|offset| (offset, -(position.z + offset.z)))
+            .collect();
+
+        let mut normal_impulses = vec![0.0; contacts.len()];
+        let mut friction_impulses = vec![Vector3::zeros(); contacts.len()];
+        for _ in 0..SOLVER_ITERATIONS {
+            for (index, (offset, penetration)) in contacts.iter().enumerate() {
+                let apply_impulse =
+                    |impulse: Vector3<f32>,
+                     linear_velocity: &mut Vector3<f32>,
+                     angular_velocity: &mut Vector3<f32>| {
+                        *linear_velocity += impulse * inverse_mass;
+                        *angular_velocity += inverse_inertia * offset.cross(&impulse);
+                    };
+                let effective_inverse_mass = |direction: &Vector3<f32>| {
+                    inverse_mass
+                        + direction.dot(&(inverse_inertia * offset.cross(direction)).cross(offset))
+                };
+
+                // Normal impulse stops the contact from sinking and
+                // pushes it out of the ground
+                let velocity = linear_velocity + angular_velocity.cross(offset);
+                let bias = PENETRATION_CORRECTION_FACTOR / TIME_STEP
+                    * (penetration - PENETRATION_SLOP).max(0.0);
+                let delta = (bias - velocity.z) / effective_inverse_mass(&Vector3::z());
+                let normal_impulse = (normal_impulses[index] + delta).max(0.0);
+                let delta = normal_impulse - normal_impulses[index];
+                normal_impulses[index] = normal_impulse;
+                apply_impulse(
+                    Vector3::z() * delta,
+                    &mut linear_velocity,
+                    &mut angular_velocity,
+                );
+
+                // Friction impulse stops the contact from sliding,
+                // limited by the Coulomb friction cone
+                let velocity = linear_velocity + angular_velocity.cross(offset);
+                let tangent_velocity = Vector3::new(velocity.x, velocity.y, 0.0);
+                let tangent_speed = tangent_velocity.norm();
+                if tangent_speed > f32::EPSILON {
+                    let tangent = tangent_velocity / tangent_speed;
+                    let delta = -tangent_speed / effective_inverse_mass(&tangent);
+                    let mut friction_impulse = friction_impulses[index] + tangent * delta;
+                    let friction_impulse_max = FRICTION_COEFFICIENT * normal_impulse;
+                    if friction_impulse.norm() > friction_impulse_max {
+                        friction_impulse = friction_impulse.normalize() * friction_impulse_max;
+                    }
+                    let delta = friction_impulse - friction_impulses[index];
+                    friction_impulses[index] = friction_impulse;
+                    apply_impulse(delta, &mut linear_velocity, &mut angular_velocity);
+                }
+            }
+        }
+
+        linear_velocity *= LINEAR_DAMPING;
+        angular_velocity *= ANGULAR_DAMPING;
+
+        position += linear_velocity * TIME_STEP;
+        orientation = UnitQuaternion::from_scaled_axis(angular_velocity * TIME_STEP) * orientation;
+
+        let is_still = !contacts.is_empty()
+            && linear_velocity.norm() < SLEEP_LINEAR_VELOCITY
+            && angular_velocity.norm() < SLEEP_ANGULAR_VELOCITY;
+        if is_still {
+            still_step_count += 1;
+        } else {
+            still_step_count = 0;
+        }
+    }
+
+    // Place the resting body exactly on the ground, removing the
+    // tolerated penetration
+    let lowest_point = contact_candidates
+        .iter()
+        .map(|vertex| position.z + (orientation * vertex).z)
+        .fold(f32::INFINITY, f32::min);
+    position.z -= lowest_point;
+
+    let translation = position * size - orientation * center_of_mass.coords;
+
+    Some(DropSimulation {
+        transform: Isometry3::from_parts(Translation3::from(translation), orientation),
+        settled: still_step_count >= sleep_step_count,
+        time: step as f32 * TIME_STEP,
+    })
+}
+
+/// Returns the vertices extreme in a set of directions evenly covering
+/// the sphere. These approximate the convex envelope of the vertices,
+/// which is all that collides with the ground.
+fn support_vertices(vertices: &[Point3<f32>]) -> Vec<Point3<f32>> {
+    let golden_angle = f32::consts::PI * (3.0 - 5f32.sqrt());
+    let mut support_indices = HashSet::default();
+
+    for direction_index in 0..SUPPORT_DIRECTION_COUNT {
+        // Directions on the Fibonacci sphere
+        let z = 1.0 - 2.0 * (direction_index as f32 + 0.5) / SUPPORT_DIRECTION_COUNT as f32;
+        let radius = (1.0 - z * z).sqrt();
+        let angle = golden_angle * direction_index as f32;
+        let direction = Vector3::new(radius * angle.cos(), radius * angle.sin(), z);
+
+        let extreme_index = vertices
+            .iter()
+            .enumerate()
+            .map(|(index, vertex)| (index, vertex.coords.dot(&direction)))
+            .fold(
+                None,
+                |extreme: Option<(usize, f32)>, (index, extent)| match extreme {
+                    Some((_, extreme_extent)) if extreme_extent >= extent => extreme,
+                    _ => Some((index, extent)),
+                },
+            );
+        if let Some((index, _)) = extreme_index {
+            support_indices.insert(index);
+        }
+    }
+
+    let mut support_indices: Vec<usize> = support_indices.into_iter().collect();
+    support_indices.sort();
+
+    support_indices
+        .into_iter()
+        .map(|index| vertices[index])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Rotation3;
+
+    use crate::mesh::{primitive, tools};
+
+    use super::*;
+
+    #[test]
+    fn test_simulate_drop_tilted_box_settles_on_a_face() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::from_euler_angles(0.5, 0.3, 0.0),
+            Vector3::new(2.0, 1.0, 0.5),
+        );
+
+        let simulation = simulate_drop(&mesh, 1.0).expect("Box must have volume");
+        let settled_mesh = tools::transform_mesh(&mesh, &simulation.transform);
+
+        assert!(simulation.settled);
+        let bounding_box = settled_mesh.bounding_box();
+        assert!(bounding_box.minimum_point().z.abs() < 0.0001);
+        // Lying on any face, the box is as high as one of its sides
+        let height = bounding_box.diagonal().z;
+        assert!([2.0, 1.0, 0.5]
+            .iter()
+            .any(|side: &f32| (side - height).abs() < 0.02));
+    }
+
+    #[test]
+    fn test_simulate_drop_keeps_resting_box_in_place() {
+        let mesh = primitive::create_box(
+            Point3::new(0.0, 0.0, 0.25),
+            Rotation3::identity(),
+            Vector3::new(2.0, 1.0, 0.5),
+        );
+
+        let simulation = simulate_drop(&mesh, 0.0).expect("Box must have volume");
+
+        assert!(simulation.settled);
+        assert!(simulation.transform.rotation.angle() < 0.01);
+        assert!(simulation.transform.translation.vector.norm() < 0.01);
+    }
+
+    #[test]
+    fn test_simulate_drop_returns_none_for_inside_out_mesh() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+        );
+        let inside_out_mesh = tools::revert_mesh_faces(&mesh);
+
+        assert_eq!(simulate_drop(&inside_out_mesh, 0.0), None);
+    }
+}
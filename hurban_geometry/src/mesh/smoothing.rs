@@ -0,0 +1,1443 @@
+use std::cmp;
+use std::collections::hash_map::Entry;
+use std::f32;
+use std::hash::{Hash, Hasher};
+
+use smallvec::SmallVec;
+
+use nalgebra as na;
+use nalgebra::{Point3, Vector3};
+
+use crate::collections::HashMap;
+use crate::convert::{cast_u32, cast_usize};
+use crate::mesh::distance_field::DistanceField;
+use crate::mesh::{
+    topology, Face, Mesh, NormalStrategy, OrientedEdge, QuadFace, TriangleFace, UnorientedEdge,
+};
+
+/// The number of steps pulling a relaxed vertex to the surface.
+const RELAX_ON_SURFACE_PROJECTION_STEPS: usize = 3;
+
+/// Relaxes angles between mesh edges, resulting in a smoother
+/// mesh, optionally keeping some vertices anchored, resulting in
+/// an evenly distributed geometry optionally stretched between the
+/// anchor points
+///
+/// The number of vertices, faces and the overall topology remains unchanged.
+/// The more iterations, the smoother result. Too many iterations may cause slow
+/// calculation time. In case the `stop_when_stable` flag is set on, the smoothing
+/// stops when the mesh stops transforming between iterations, or when it
+/// reaches the maximum number of iterations.
+///
+/// The algorithm is based on replacing each vertex position with an average
+/// position of its immediate neighbors.
+///
+/// Returns `(smooth_mesh: Mesh, executed_iterations: u32, stable: bool)`.
+pub fn laplacian_smoothing(
+    mesh: &Mesh,
+    vertex_to_vertex_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+    max_iterations: u32,
+    fixed_vertex_indices: &[u32],
+    stop_when_stable: bool,
+    normal_strategy: NormalStrategy,
+) -> (Mesh, u32, bool) {
+    laplacian_smoothing_cancellable(
+        mesh,
+        vertex_to_vertex_topology,
+        max_iterations,
+        fixed_vertex_indices,
+        stop_when_stable,
+        normal_strategy,
+        |_| false,
+    )
+    .expect("Smoothing that can't be cancelled must produce a result")
+}
+
+/// Relaxes the mesh the same way as `laplacian_smoothing`, but asks
+/// `cancelled` before each iteration whether to stop early. The
+/// callback receives the number of iterations executed so far, e.g. to
+/// report progress.
+///
+/// Returns `None`, if the smoothing was cancelled.
+pub fn laplacian_smoothing_cancellable<C>(
+    mesh: &Mesh,
+    vertex_to_vertex_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+    max_iterations: u32,
+    fixed_vertex_indices: &[u32],
+    stop_when_stable: bool,
+    normal_strategy: NormalStrategy,
+    mut cancelled: C,
+) -> Option<(Mesh, u32, bool)>
+where
+    C: FnMut(u32) -> bool,
+{
+    if max_iterations == 0 {
+        return Some((mesh.clone(), 0, false));
+    }
+
+    let mut vertices: Vec<Point3<f32>> = Vec::from(mesh.vertices());
+    let mut mesh_vertices: Vec<Point3<f32>>;
+
+    let mut iteration: u32 = 0;
+
+    // Looked up for every vertex in every iteration, which would be
+    // slow for the many border vertices of e.g. open scans
+    let mut is_fixed = vec![false; vertices.len()];
+    for fixed_vertex_index in fixed_vertex_indices {
+        is_fixed[cast_usize(*fixed_vertex_index)] = true;
+    }
+
+    // Only relevant when fixed vertices are specified
+    let mut stable = !fixed_vertex_indices.is_empty();
+    while iteration < max_iterations {
+        if cancelled(iteration) {
+            return None;
+        }
+
+        stable = !fixed_vertex_indices.is_empty();
+        mesh_vertices = vertices.clone();
+
+        for (current_vertex_index, neighbors_indices) in
+            vertex_to_vertex_topology.iter().enumerate()
+        {
+            if !is_fixed[current_vertex_index] && !neighbors_indices.is_empty() {
+                let mut average_position: Point3<f32> = Point3::origin();
+                for neighbor_index in neighbors_indices {
+                    average_position += mesh_vertices[cast_usize(*neighbor_index)].coords;
+                }
+                average_position /= neighbors_indices.len() as f32;
+                stable &= approx::relative_eq!(
+                    &average_position.coords,
+                    &vertices[current_vertex_index].coords,
+                );
+                vertices[current_vertex_index] = average_position;
+            }
+        }
+        iteration += 1;
+
+        if stop_when_stable && stable {
+            break;
+        }
+    }
+
+    let mut smoothed = Mesh::from_faces_with_vertices_and_computed_normals(
+        mesh.faces().iter().copied(),
+        vertices,
+        normal_strategy,
+    );
+    smoothed.copy_vertex_data_from(mesh);
+    smoothed.copy_face_materials_from(mesh);
+
+    Some((smoothed, iteration, stable))
+}
+
+/// Relaxes the mesh like `laplacian_smoothing_cancellable`, but keeps
+/// the vertices on the surface of the `distance_field`, e.g. to remove
+/// the staircase artifacts of meshes extracted from voxels.
+///
+/// After moving to the average position of its neighbors, each vertex
+/// is pulled back to the zero distance along the distance gradient and
+/// stays within half a voxel from its original position in each
+/// axis. Unlike plain Laplacian smoothing, the mesh therefore doesn't
+/// shrink. The distance field should have the voxel dimensions the mesh
+/// was extracted with.
+///
+/// Returns `None`, if the relaxation was cancelled.
+pub fn relax_on_surface<C>(
+    mesh: &Mesh,
+    vertex_to_vertex_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+    distance_field: &DistanceField,
+    max_iterations: u32,
+    normal_strategy: NormalStrategy,
+    mut cancelled: C,
+) -> Option<Mesh>
+where
+    C: FnMut(u32) -> bool,
+{
+    let max_displacement = distance_field.voxel_dimensions() / 2.0;
+    let mut vertices: Vec<Point3<f32>> = Vec::from(mesh.vertices());
+
+    for iteration in 0..max_iterations {
+        if cancelled(iteration) {
+            return None;
+        }
+
+        let mesh_vertices = vertices.clone();
+        for (current_vertex_index, neighbors_indices) in
+            vertex_to_vertex_topology.iter().enumerate()
+        {
+            if neighbors_indices.is_empty() {
+                continue;
+            }
+
+            let mut position: Point3<f32> = Point3::origin();
+            for neighbor_index in neighbors_indices {
+                position += mesh_vertices[cast_usize(*neighbor_index)].coords;
+            }
+            position /= neighbors_indices.len() as f32;
+
+            // A few Newton steps are enough, because the averaged
+            // position is already close to the surface
+            for _ in 0..RELAX_ON_SURFACE_PROJECTION_STEPS {
+                let distance = distance_field.distance_at_cartesian_coords(&position);
+                let gradient = distance_field.gradient_at_cartesian_coords(&position);
+                if let (Some(distance), Some(gradient)) = (distance, gradient) {
+                    let gradient_norm_squared = gradient.norm_squared();
+                    if gradient_norm_squared > f32::EPSILON {
+                        position -= gradient * (distance / gradient_norm_squared);
+                    }
+                }
+            }
+
+            let original = mesh.vertices()[current_vertex_index];
+            let displacement = (position - original)
+                .zip_map(&max_displacement, |offset, max| offset.max(-max).min(max));
+            vertices[current_vertex_index] = original + displacement;
+        }
+    }
+
+    let mut relaxed = Mesh::from_faces_with_vertices_and_computed_normals(
+        mesh.faces().iter().copied(),
+        vertices,
+        normal_strategy,
+    );
+    relaxed.copy_vertex_data_from(mesh);
+    relaxed.copy_face_materials_from(mesh);
+
+    Some(relaxed)
+}
+
+/// Performs one iteration of Loop Subdivision on mesh.
+///
+/// The subdivision works in two steps:
+///
+/// 1) Split each triangle into 4 smaller triangles,
+/// 2) Update the position of each vertex of the mesh based on
+///    weighted averages of its neighboring vertex positions,
+///    depending on where the vertex is in the topology and whether
+///    the vertex is newly created, or did already exist.
+///
+/// The mesh **must** be triangulated and manifold.
+///
+/// Implementation based on [mdfisher]
+/// (https://graphics.stanford.edu/~mdfisher/subdivision.html).
+pub fn loop_subdivision(
+    mesh: &Mesh,
+    vertex_to_vertex_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+    face_to_face_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+    normal_strategy: NormalStrategy,
+) -> Option<Mesh> {
+    #[derive(Debug, Eq)]
+    struct UnorderedPair(u32, u32);
+
+    impl PartialEq for UnorderedPair {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0 && self.1 == other.1 || self.0 == other.1 && self.1 == other.0
+        }
+    }
+
+    impl Hash for UnorderedPair {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            cmp::min(self.0, self.1).hash(state);
+            cmp::max(self.0, self.1).hash(state);
+        }
+    }
+
+    if !mesh.is_triangulated() {
+        return None;
+    }
+
+    let mut vertices: Vec<Point3<f32>> = mesh.vertices().iter().copied().collect();
+
+    // Relocate existing vertices first
+    for (i, vertex) in vertices.iter_mut().enumerate() {
+        let neighbors = &vertex_to_vertex_topology[i];
+
+        match neighbors.len() {
+            // N == 0 means this is an orphan vertex. N == 1 can't
+            // happen in our mesh representation.
+            0 | 1 => (),
+            2 => {
+                // For edge valency N == 2 (a naked edge vertex), use
+                // (3/4, 1/8, 1/8) relocation scheme.
+
+                let vi1 = cast_usize(neighbors[0]);
+                let vi2 = cast_usize(neighbors[1]);
+
+                let v1 = mesh.vertices()[vi1];
+                let v2 = mesh.vertices()[vi2];
+
+                *vertex = Point3::origin()
+                    + vertex.coords * 3.0 / 4.0
+                    + v1.coords * 1.0 / 8.0
+                    + v2.coords * 1.0 / 8.0;
+            }
+            3 => {
+                // For edge valency N == 3, use (1 - N*BETA, BETA,
+                // BETA, BETA) relocation scheme, where BETA is 3/16.
+
+                const N: f32 = 3.0;
+                const BETA: f32 = 3.0 / 16.0;
+
+                let vi1 = cast_usize(neighbors[0]);
+                let vi2 = cast_usize(neighbors[1]);
+                let vi3 = cast_usize(neighbors[2]);
+
+                let v1 = mesh.vertices()[vi1];
+                let v2 = mesh.vertices()[vi2];
+                let v3 = mesh.vertices()[vi3];
+
+                *vertex = Point3::origin()
+                    + vertex.coords * (1.0 - N * BETA)
+                    + v1.coords * BETA
+                    + v2.coords * BETA
+                    + v3.coords * BETA;
+            }
+            n => {
+                // For edge valency N >= 3, use (1 - N*BETA, BETA,
+                // ...) relocation scheme, where BETA is 3 / (8*N).
+
+                let n_f32 = n as f32;
+                let beta = 3.0 / (8.0 * n_f32);
+
+                *vertex = Point3::origin() + vertex.coords * (1.0 - n_f32 * beta);
+                for vi in neighbors {
+                    let v = mesh.vertices()[cast_usize(*vi)];
+                    *vertex += v.coords * beta;
+                }
+            }
+        }
+    }
+
+    // Subdivide existing triangle faces and create new vertices
+
+    let faces_len_estimate = mesh.faces().len() * 4;
+    let mut faces: Vec<(u32, u32, u32)> = Vec::with_capacity(faces_len_estimate);
+
+    // We will be creating new mid-edge vertices per face soon. Faces
+    // will share and re-use these newly created vertices.
+
+    // The key is an unordered pair of faces that share the mid-edge
+    // vertex. The value is the index of the vertex they share.
+    let mut created_mid_vertex_indices: HashMap<UnorderedPair, u32> = HashMap::default();
+
+    for (face_index, triangle_face) in mesh.triangle_faces_iter().enumerate() {
+        let face_index_u32 = cast_u32(face_index);
+        let (vi1, vi2, vi3) = triangle_face.vertices;
+        let face_neighbors = &face_to_face_topology[face_index];
+
+        // Our current face should have up to 3 neighboring
+        // faces. The mid vertices we are going to create need
+        // to be shared with those faces if they exist, so
+        // that they are only created once. The array below
+        // will be filled with either vertices created here,
+        // or obtained from `created_mid_vertex_indices`
+        // cache.
+        let mut mid_vertex_indices: [Option<u32>; 3] = [None, None, None];
+
+        for (edge_index, (vi_from, vi_to)) in
+            [(vi1, vi2), (vi2, vi3), (vi3, vi1)].iter().enumerate()
+        {
+            let neighbor_face_index = face_neighbors
+                .iter()
+                .copied()
+                .map(|i| (i, mesh.faces()[cast_usize(i)]))
+                .find_map(|(i, face)| {
+                    if face.contains_vertex(*vi_from) && face.contains_vertex(*vi_to) {
+                        Some(i)
+                    } else {
+                        None
+                    }
+                });
+
+            let mid_vertex_index = if let Some(neighbor_face_index) = neighbor_face_index {
+                let pair = UnorderedPair(face_index_u32, neighbor_face_index);
+
+                match created_mid_vertex_indices.entry(pair) {
+                    // The vertex exists and was therefore
+                    // already relocated by visiting a
+                    // neighboring face in a previous
+                    // iteration
+                    Entry::Occupied(occupied) => *occupied.get(),
+                    Entry::Vacant(vacant) => {
+                        // Create and relocate the vertex
+                        // using the (1/8, 3/8, 3/8, 1/8)
+                        // scheme. Since there is a neighbor
+                        // face, we also write the created
+                        // vertex to the cache to be picked up
+                        // by subsequent iterations.
+
+                        let edge_vertex_from = mesh.vertices()[cast_usize(*vi_from)];
+                        let edge_vertex_to = mesh.vertices()[cast_usize(*vi_to)];
+
+                        let triangle_face1 = mesh.triangle_face(face_index);
+                        let triangle_face2 = mesh.triangle_face(cast_usize(neighbor_face_index));
+
+                        // Find the two vertices that are
+                        // opposite to the shared edge of the
+                        // face pair.
+                        let (opposite_vertex_index1, opposite_vertex_index2) = {
+                            let f1vi1 = triangle_face1.vertices.0;
+                            let f1vi2 = triangle_face1.vertices.1;
+                            let f1vi3 = triangle_face1.vertices.2;
+
+                            let f2vi1 = triangle_face2.vertices.0;
+                            let f2vi2 = triangle_face2.vertices.1;
+                            let f2vi3 = triangle_face2.vertices.2;
+
+                            let f1v = [f1vi1, f1vi2, f1vi3];
+                            let f2v = [f2vi1, f2vi2, f2vi3];
+
+                            let f1_opposite_vertex =
+                                f1v.iter().copied().find(|vi| !f2v.contains(&vi))?;
+
+                            let f2_opposite_vertex =
+                                f2v.iter().copied().find(|vi| !f1v.contains(&vi))?;
+
+                            (f1_opposite_vertex, f2_opposite_vertex)
+                        };
+
+                        let opposite_vertex1 = mesh.vertices()[cast_usize(opposite_vertex_index1)];
+                        let opposite_vertex2 = mesh.vertices()[cast_usize(opposite_vertex_index2)];
+
+                        let new_vertex = Point3::origin()
+                            + opposite_vertex1.coords * 1.0 / 8.0
+                            + opposite_vertex2.coords * 1.0 / 8.0
+                            + edge_vertex_from.coords * 3.0 / 8.0
+                            + edge_vertex_to.coords * 3.0 / 8.0;
+
+                        let index = cast_u32(vertices.len());
+                        vacant.insert(index);
+                        vertices.push(new_vertex);
+
+                        index
+                    }
+                }
+            } else {
+                // Create and relocate the vertex using the (1/2, 1/2) scheme
+                let vertex_from = mesh.vertices()[cast_usize(*vi_from)];
+                let vertex_to = mesh.vertices()[cast_usize(*vi_to)];
+
+                let new_vertex = na::center(&vertex_from, &vertex_to);
+
+                let index = cast_u32(vertices.len());
+                vertices.push(new_vertex);
+
+                index
+            };
+
+            mid_vertex_indices[edge_index] = Some(mid_vertex_index);
+        }
+
+        let mid_v1v2_index =
+            mid_vertex_indices[0].expect("Must have been produced by earlier loop");
+        let mid_v2v3_index =
+            mid_vertex_indices[1].expect("Must have been produced by earlier loop");
+        let mid_v3v1_index =
+            mid_vertex_indices[2].expect("Must have been produced by earlier loop");
+
+        faces.push((vi1, mid_v1v2_index, mid_v3v1_index));
+        faces.push((vi2, mid_v2v3_index, mid_v1v2_index));
+        faces.push((vi3, mid_v3v1_index, mid_v2v3_index));
+        faces.push((mid_v1v2_index, mid_v2v3_index, mid_v3v1_index));
+    }
+
+    assert_eq!(faces.len(), faces_len_estimate);
+    assert_eq!(faces.capacity(), faces_len_estimate);
+
+    Some(
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            normal_strategy,
+        ),
+    )
+}
+
+/// Maximum angle between the normals of two triangles, which can be
+/// joined into a quad for Catmull-Clark subdivision.
+const QUAD_MAX_FOLD_ANGLE: f32 = f32::consts::PI / 6.0;
+/// Maximum deviation of a quad corner from the right angle for two
+/// triangles to be joined into a quad for Catmull-Clark subdivision.
+const QUAD_MAX_CORNER_DEVIATION: f32 = f32::consts::PI / 4.0;
+
+/// Catmull-Clark subdivision of a mesh.
+///
+/// Catmull-Clark subdivision works on polygons and produces quads, which
+/// suits meshes modeled with quads better than Loop subdivision. The
+/// quads of the mesh are subdivided as they are. Pairs of neighboring
+/// triangles are first joined back into the quads they likely came from,
+/// e.g. when triangulated by a previous operation. Triangles which can
+/// not be paired are subdivided as triangles.
+///
+/// Each iteration:
+///
+/// 1) Adds a face point to the centroid of each face,
+/// 2) Adds an edge point to each edge, averaging the edge vertices and
+///    the face points of the neighboring faces,
+/// 3) Relocates each existing vertex based on weighted averages of the
+///    neighboring face points and edge midpoints,
+/// 4) Splits each face into quads connecting the face point, the edge
+///    points and the vertices.
+///
+/// Border and non-manifold edges are kept sharp. The resulting mesh
+/// consists of quads, unless there are no iterations.
+pub fn catmull_clark_subdivision(
+    mesh: &Mesh,
+    iterations: u32,
+    normal_strategy: NormalStrategy,
+) -> Mesh {
+    let mut vertices: Vec<Point3<f32>> = mesh.vertices().to_vec();
+    let mut polygons = join_triangles_into_quads(mesh);
+
+    for _ in 0..iterations {
+        let (subdivided_vertices, subdivided_polygons) =
+            catmull_clark_subdivision_step(&vertices, &polygons);
+        vertices = subdivided_vertices;
+        polygons = subdivided_polygons;
+    }
+
+    let faces = polygons.iter().map(|polygon| match polygon[..] {
+        [v1, v2, v3] => Face::from(TriangleFace::new(v1, v2, v3)),
+        [v1, v2, v3, v4] => Face::from(QuadFace::new(v1, v2, v3, v4)),
+        _ => unreachable!("Catmull-Clark polygons must be triangles or quads"),
+    });
+
+    Mesh::from_faces_with_vertices_and_computed_normals_remove_orphans(
+        faces,
+        vertices,
+        normal_strategy,
+    )
+}
+
+/// Joins pairs of neighboring triangles into quads, if they are nearly
+/// coplanar and the quad corners are close to right angles. The pairs
+/// are found greedily in the face order, each triangle joining with its
+/// neighbor forming the most rectangular quad. The quads of the mesh are
+/// kept. Returns polygons as lists of vertex indices in the winding
+/// order.
+fn join_triangles_into_quads(mesh: &Mesh) -> Vec<Vec<u32>> {
+    let vertices = mesh.vertices();
+    // Indexed by the face index, the quads are not joined with anything
+    let triangles: Vec<Option<[u32; 3]>> = mesh
+        .faces()
+        .iter()
+        .map(|face| match face {
+            Face::Triangle(triangle_face) => Some([
+                triangle_face.vertices.0,
+                triangle_face.vertices.1,
+                triangle_face.vertices.2,
+            ]),
+            Face::Quad(_) => None,
+        })
+        .collect();
+
+    let mut edge_to_triangle: HashMap<(u32, u32), usize> = HashMap::default();
+    for (triangle_index, triangle) in triangles.iter().enumerate() {
+        if let Some(triangle) = triangle {
+            for i in 0..3 {
+                edge_to_triangle.insert((triangle[i], triangle[(i + 1) % 3]), triangle_index);
+            }
+        }
+    }
+
+    let corner_deviation = |previous: u32, corner: u32, next: u32| {
+        let corner_position = vertices[cast_usize(corner)];
+        let angle = (vertices[cast_usize(previous)] - corner_position)
+            .angle(&(vertices[cast_usize(next)] - corner_position));
+        (angle - f32::consts::FRAC_PI_2).abs()
+    };
+    let triangle_normal = |triangle: &[u32; 3]| {
+        let [a, b, c] = *triangle;
+        let a_position = vertices[cast_usize(a)];
+        (vertices[cast_usize(b)] - a_position).cross(&(vertices[cast_usize(c)] - a_position))
+    };
+
+    let mut paired = vec![false; triangles.len()];
+    let mut polygons = Vec::with_capacity(triangles.len());
+
+    for (triangle_index, face) in mesh.faces().iter().enumerate() {
+        let triangle = match &triangles[triangle_index] {
+            Some(triangle) => triangle,
+            None => {
+                polygons.push(face.vertices().to_vec());
+                continue;
+            }
+        };
+        if paired[triangle_index] {
+            continue;
+        }
+
+        let mut best_quad: Option<(usize, [u32; 4], f32)> = None;
+        for i in 0..3 {
+            let a = triangle[i];
+            let b = triangle[(i + 1) % 3];
+            let c = triangle[(i + 2) % 3];
+
+            let neighbor_index = match edge_to_triangle.get(&(b, a)) {
+                Some(neighbor_index) => *neighbor_index,
+                None => continue,
+            };
+            if neighbor_index == triangle_index || paired[neighbor_index] {
+                continue;
+            }
+
+            let neighbor = triangles[neighbor_index]
+                .as_ref()
+                .expect("Only triangles are joined");
+            let w = match neighbor
+                .iter()
+                .find(|vertex| **vertex != a && **vertex != b)
+            {
+                Some(w) => *w,
+                None => continue,
+            };
+            if w == c {
+                continue;
+            }
+
+            let fold_angle = triangle_normal(triangle).angle(&triangle_normal(neighbor));
+            if fold_angle.is_nan() || fold_angle > QUAD_MAX_FOLD_ANGLE {
+                continue;
+            }
+
+            let quad = [a, w, b, c];
+            let max_corner_deviation = (0..4)
+                .map(|j| corner_deviation(quad[(j + 3) % 4], quad[j], quad[(j + 1) % 4]))
+                .fold(0.0, f32::max);
+            if max_corner_deviation > QUAD_MAX_CORNER_DEVIATION {
+                continue;
+            }
+
+            let is_better = best_quad.map_or(true, |(_, _, best_deviation)| {
+                max_corner_deviation < best_deviation
+            });
+            if is_better {
+                best_quad = Some((neighbor_index, quad, max_corner_deviation));
+            }
+        }
+
+        paired[triangle_index] = true;
+        match best_quad {
+            Some((neighbor_index, quad, _)) => {
+                paired[neighbor_index] = true;
+                polygons.push(quad.to_vec());
+            }
+            None => polygons.push(triangle.to_vec()),
+        }
+    }
+
+    polygons
+}
+
+/// Performs one iteration of Catmull-Clark subdivision on polygons.
+/// Returns the new vertices and quads.
+fn catmull_clark_subdivision_step(
+    vertices: &[Point3<f32>],
+    polygons: &[Vec<u32>],
+) -> (Vec<Point3<f32>>, Vec<Vec<u32>>) {
+    let face_points: Vec<Point3<f32>> = polygons
+        .iter()
+        .map(|polygon| {
+            let sum = polygon.iter().fold(Vector3::zeros(), |sum, vertex| {
+                sum + vertices[cast_usize(*vertex)].coords
+            });
+            Point3::from(sum / polygon.len() as f32)
+        })
+        .collect();
+
+    // Faces around each edge, the edges are indexed in order of discovery
+    let mut edge_indices: HashMap<UnorientedEdge, usize> = HashMap::default();
+    let mut edges: Vec<UnorientedEdge> = Vec::new();
+    let mut edge_faces: Vec<SmallVec<[usize; 2]>> = Vec::new();
+    for (polygon_index, polygon) in polygons.iter().enumerate() {
+        for i in 0..polygon.len() {
+            let edge = UnorientedEdge(OrientedEdge::new(
+                polygon[i],
+                polygon[(i + 1) % polygon.len()],
+            ));
+            let edge_index = *edge_indices.entry(edge).or_insert_with(|| {
+                edges.push(edge);
+                edge_faces.push(SmallVec::new());
+                edge_faces.len() - 1
+            });
+            edge_faces[edge_index].push(polygon_index);
+        }
+    }
+
+    let edge_midpoint = |edge: &UnorientedEdge| {
+        na::center(
+            &vertices[cast_usize((edge.0).vertices.0)],
+            &vertices[cast_usize((edge.0).vertices.1)],
+        )
+    };
+    let is_smooth_edge = |edge_index: usize| edge_faces[edge_index].len() == 2;
+
+    let edge_points: Vec<Point3<f32>> = edges
+        .iter()
+        .enumerate()
+        .map(|(edge_index, edge)| {
+            let midpoint = edge_midpoint(edge);
+            if is_smooth_edge(edge_index) {
+                let faces = &edge_faces[edge_index];
+                na::center(
+                    &midpoint,
+                    &na::center(&face_points[faces[0]], &face_points[faces[1]]),
+                )
+            } else {
+                midpoint
+            }
+        })
+        .collect();
+
+    let mut vertex_faces: Vec<SmallVec<[usize; 8]>> = vec![SmallVec::new(); vertices.len()];
+    for (polygon_index, polygon) in polygons.iter().enumerate() {
+        for vertex in polygon {
+            vertex_faces[cast_usize(*vertex)].push(polygon_index);
+        }
+    }
+    let mut vertex_edges: Vec<SmallVec<[usize; 8]>> = vec![SmallVec::new(); vertices.len()];
+    for (edge_index, edge) in edges.iter().enumerate() {
+        vertex_edges[cast_usize((edge.0).vertices.0)].push(edge_index);
+        vertex_edges[cast_usize((edge.0).vertices.1)].push(edge_index);
+    }
+
+    let mut subdivided_vertices: Vec<Point3<f32>> =
+        Vec::with_capacity(vertices.len() + edges.len() + polygons.len());
+
+    for (vertex_index, vertex) in vertices.iter().enumerate() {
+        let faces = &vertex_faces[vertex_index];
+        let incident_edges = &vertex_edges[vertex_index];
+        let sharp_edges: SmallVec<[usize; 8]> = incident_edges
+            .iter()
+            .copied()
+            .filter(|edge_index| !is_smooth_edge(*edge_index))
+            .collect();
+
+        let relocated_vertex = if faces.is_empty() {
+            *vertex
+        } else if sharp_edges.is_empty() {
+            let valence = incident_edges.len() as f32;
+            let face_average = faces.iter().fold(Vector3::zeros(), |sum, face| {
+                sum + face_points[*face].coords
+            }) / faces.len() as f32;
+            let edge_average = incident_edges
+                .iter()
+                .fold(Vector3::zeros(), |sum, edge_index| {
+                    sum + edge_midpoint(&edges[*edge_index]).coords
+                })
+                / valence;
+            Point3::from(
+                (face_average + 2.0 * edge_average + (valence - 3.0) * vertex.coords) / valence,
+            )
+        } else if sharp_edges.len() == 2 {
+            // Vertex on a sharp curve follows the curve
+            let neighbor_sum = sharp_edges
+                .iter()
+                .fold(Vector3::zeros(), |sum, edge_index| {
+                    let edge = edges[*edge_index].0;
+                    let neighbor = if cast_usize(edge.vertices.0) == vertex_index {
+                        edge.vertices.1
+                    } else {
+                        edge.vertices.0
+                    };
+                    sum + vertices[cast_usize(neighbor)].coords
+                });
+            Point3::from((6.0 * vertex.coords + neighbor_sum) / 8.0)
+        } else {
+            // Corners, where sharp curves meet or end, stay in place
+            *vertex
+        };
+
+        subdivided_vertices.push(relocated_vertex);
+    }
+
+    let edge_point_offset = cast_u32(subdivided_vertices.len());
+    subdivided_vertices.extend(edge_points);
+    let face_point_offset = cast_u32(subdivided_vertices.len());
+    subdivided_vertices.extend(face_points);
+
+    let edge_point_index = |v1: u32, v2: u32| {
+        edge_point_offset + cast_u32(edge_indices[&UnorientedEdge(OrientedEdge::new(v1, v2))])
+    };
+
+    let mut subdivided_polygons = Vec::with_capacity(polygons.len() * 4);
+    for (polygon_index, polygon) in polygons.iter().enumerate() {
+        let face_point_index = face_point_offset + cast_u32(polygon_index);
+        let len = polygon.len();
+        for i in 0..len {
+            let previous = polygon[(i + len - 1) % len];
+            let current = polygon[i];
+            let next = polygon[(i + 1) % len];
+            subdivided_polygons.push(vec![
+                current,
+                edge_point_index(current, next),
+                face_point_index,
+                edge_point_index(previous, current),
+            ]);
+        }
+    }
+
+    (subdivided_vertices, subdivided_polygons)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use nalgebra::Rotation3;
+
+    use crate::mesh::voxel_cloud::VoxelCloud;
+    use crate::mesh::{analysis, primitive, topology, NormalStrategy};
+
+    use super::*;
+
+    fn torus() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(0.566987, -1.129e-11, 0.25),
+            Point3::new(-0.716506, 1.241025, 0.25),
+            Point3::new(-0.283494, 0.491025, 0.25),
+            Point3::new(-0.716506, -1.241025, 0.25),
+            Point3::new(-0.283494, -0.491025, 0.25),
+            Point3::new(1.0, -1.129e-11, -0.5),
+            Point3::new(1.433013, -1.129e-11, 0.25),
+            Point3::new(-0.5, 0.866025, -0.5),
+            Point3::new(-0.5, -0.866025, -0.5),
+        ];
+
+        let faces = vec![
+            (4, 3, 6),
+            (0, 6, 2),
+            (2, 1, 3),
+            (8, 4, 0),
+            (3, 8, 6),
+            (5, 0, 7),
+            (6, 5, 7),
+            (7, 2, 4),
+            (1, 7, 8),
+            (4, 6, 0),
+            (6, 1, 2),
+            (2, 3, 4),
+            (8, 0, 5),
+            (8, 5, 6),
+            (0, 2, 7),
+            (6, 7, 1),
+            (7, 4, 8),
+            (1, 8, 3),
+        ];
+
+        (faces, vertices)
+    }
+
+    fn triple_torus() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(15.566987, -1.129e-11, 0.25),
+            Point3::new(14.283494, 1.241025, 0.25),
+            Point3::new(14.716506, 0.491025, 0.25),
+            Point3::new(14.283494, -1.241025, 0.25),
+            Point3::new(14.716506, -0.491025, 0.25),
+            Point3::new(16.0, 0.75, 0.25),
+            Point3::new(15.149519, 1.241025, 0.25),
+            Point3::new(16.0, 1.732051, 0.25),
+            Point3::new(16.108253, 0.1875, -0.5),
+            Point3::new(16.433012, -1.129e-11, 0.25),
+            Point3::new(14.716506, 1.991025, 0.25),
+            Point3::new(15.566987, 2.482051, 0.25),
+            Point3::new(14.283494, 3.723076, 0.25),
+            Point3::new(14.716506, 2.973076, 0.25),
+            Point3::new(14.554127, 1.334775, -0.5),
+            Point3::new(14.5, -0.866025, -0.5),
+            Point3::new(14.5, 3.348076, -0.5),
+            Point3::new(16.108253, 2.294551, -0.5),
+            Point3::new(16.433012, 2.482051, 0.25),
+        ];
+
+        let faces = vec![
+            (4, 3, 0),
+            (0, 9, 1),
+            (2, 1, 3),
+            (7, 5, 9),
+            (5, 6, 9),
+            (6, 7, 18),
+            (15, 4, 0),
+            (3, 15, 9),
+            (10, 1, 11),
+            (11, 18, 12),
+            (13, 12, 1),
+            (14, 2, 15),
+            (1, 14, 15),
+            (8, 0, 2),
+            (8, 14, 6),
+            (16, 13, 10),
+            (12, 16, 1),
+            (17, 8, 7),
+            (18, 9, 8),
+            (14, 17, 6),
+            (17, 11, 16),
+            (18, 17, 16),
+            (14, 10, 17),
+            (3, 9, 0),
+            (0, 1, 2),
+            (2, 3, 4),
+            (7, 9, 18),
+            (6, 1, 9),
+            (6, 18, 1),
+            (15, 0, 8),
+            (15, 8, 9),
+            (1, 18, 11),
+            (11, 12, 13),
+            (13, 1, 10),
+            (2, 4, 15),
+            (1, 15, 3),
+            (8, 2, 14),
+            (8, 6, 5),
+            (16, 10, 14),
+            (16, 14, 1),
+            (8, 5, 7),
+            (18, 8, 17),
+            (17, 7, 6),
+            (11, 13, 16),
+            (18, 16, 12),
+            (10, 11, 17),
+        ];
+
+        (faces, vertices)
+    }
+
+    fn shape_for_smoothing_with_anchors() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(30.21796, -6.119943, 0.0),
+            Point3::new(32.031532, 1.328689, 0.0),
+            Point3::new(33.875141, -3.522298, 3.718605),
+            Point3::new(34.571838, -2.071111, 2.77835),
+            Point3::new(34.778172, -5.285372, 3.718605),
+            Point3::new(36.243252, -3.80194, 3.718605),
+            Point3::new(36.741604, -10.146505, 0.0),
+            Point3::new(39.676025, 1.905633, 0.0),
+            Point3::new(42.587009, -5.186427, 0.0),
+        ];
+
+        let faces = vec![
+            (4, 8, 5),
+            (4, 6, 8),
+            (5, 8, 7),
+            (3, 5, 7),
+            (0, 2, 1),
+            (1, 2, 3),
+            (0, 4, 2),
+            (1, 3, 7),
+            (0, 6, 4),
+            (2, 4, 5),
+            (2, 5, 3),
+        ];
+
+        (faces, vertices)
+    }
+
+    fn shape_for_smoothing_with_anchors_50_iterations() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>)
+    {
+        let vertices = vec![
+            Point3::new(30.21796, -6.119943, 0.0),
+            Point3::new(32.031532, 1.328689, 0.0),
+            Point3::new(34.491065, -2.551039, 0.0),
+            Point3::new(36.00632, -0.404003, 0.0),
+            Point3::new(36.372859, -5.260642, 0.0),
+            Point3::new(37.826656, -2.299296, 0.0),
+            Point3::new(36.741604, -10.146505, 0.0),
+            Point3::new(39.676025, 1.905633, 0.0),
+            Point3::new(42.587009, -5.186427, 0.0),
+        ];
+
+        let faces = vec![
+            (4, 8, 5),
+            (4, 6, 8),
+            (5, 8, 7),
+            (3, 5, 7),
+            (0, 2, 1),
+            (1, 2, 3),
+            (0, 4, 2),
+            (1, 3, 7),
+            (0, 6, 4),
+            (2, 4, 5),
+            (2, 5, 3),
+        ];
+
+        (faces, vertices)
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_vertex_normal_count_equals_vertex_count() {
+        let (faces, vertices) = torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Smooth,
+        );
+
+        let vertex_to_vertex_topology = topology::compute_vertex_to_vertex_topology(&mesh);
+        let (relaxed_mesh_0, _, _) = laplacian_smoothing(
+            &mesh,
+            &vertex_to_vertex_topology,
+            0,
+            &[],
+            false,
+            NormalStrategy::Smooth,
+        );
+        let (relaxed_mesh_1, _, _) = laplacian_smoothing(
+            &mesh,
+            &vertex_to_vertex_topology,
+            1,
+            &[],
+            false,
+            NormalStrategy::Smooth,
+        );
+        let (relaxed_mesh_10, _, _) = laplacian_smoothing(
+            &mesh,
+            &vertex_to_vertex_topology,
+            10,
+            &[],
+            false,
+            NormalStrategy::Smooth,
+        );
+
+        assert_eq!(relaxed_mesh_0.faces().len(), mesh.faces().len());
+        assert_eq!(relaxed_mesh_1.faces().len(), mesh.faces().len());
+        assert_eq!(relaxed_mesh_10.faces().len(), mesh.faces().len());
+        assert_eq!(relaxed_mesh_0.vertices().len(), mesh.vertices().len());
+        assert_eq!(relaxed_mesh_1.vertices().len(), mesh.vertices().len());
+        assert_eq!(relaxed_mesh_10.vertices().len(), mesh.vertices().len());
+        // In this specific case (zero smoothing iterations) nothing changes,
+        // therefore the smoothened mesh should be equal to the original mesh.
+        assert_eq!(relaxed_mesh_0.vertex_normals(), mesh.vertex_normals());
+        // In all other cases the laplacian smoothing generates exactly one
+        // smooth normal for each vertex, without any corner overrides.
+        assert_eq!(
+            relaxed_mesh_1.vertex_normals().vertex_count(),
+            mesh.vertices().len(),
+        );
+        assert!(!relaxed_mesh_1.vertex_normals().has_overrides());
+        assert_eq!(
+            relaxed_mesh_10.vertex_normals().vertex_count(),
+            mesh.vertices().len(),
+        );
+        assert!(!relaxed_mesh_10.vertex_normals().has_overrides());
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_preserves_original_mesh_with_0_iterations() {
+        let (faces, vertices) = triple_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+
+        let (relaxed_mesh, _, _) =
+            laplacian_smoothing(&mesh, &v2v, 0, &[], false, NormalStrategy::Sharp);
+        assert_eq!(mesh, relaxed_mesh);
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_snapshot_triple_torus_1_iteration() {
+        let (faces, vertices) = triple_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+
+        let (relaxed_mesh, _, _) =
+            laplacian_smoothing(&mesh, &v2v, 1, &[], false, NormalStrategy::Sharp);
+        insta::assert_json_snapshot!(
+            "triple_torus_after_1_iteration_of_laplacian_smoothing",
+            &relaxed_mesh
+        );
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_snapshot_triple_torus_2_iterations() {
+        let (faces, vertices) = triple_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+
+        let (relaxed_mesh, _, _) =
+            laplacian_smoothing(&mesh, &v2v, 2, &[], false, NormalStrategy::Sharp);
+        insta::assert_json_snapshot!(
+            "triple_torus_after_2_iteration2_of_laplacian_smoothing",
+            &relaxed_mesh
+        );
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_snapshot_triple_torus_3_iterations() {
+        let (faces, vertices) = triple_torus();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+
+        let (relaxed_mesh, _, _) =
+            laplacian_smoothing(&mesh, &v2v, 3, &[], false, NormalStrategy::Sharp);
+        insta::assert_json_snapshot!(
+            "triple_torus_after_3_iterations_of_laplacian_smoothing",
+            &relaxed_mesh
+        );
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_with_anchors() {
+        let (faces, vertices) = shape_for_smoothing_with_anchors();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let fixed_vertex_indices: Vec<u32> = vec![0, 1, 7, 8, 6];
+
+        let (faces_correct, vertices_correct) = shape_for_smoothing_with_anchors_50_iterations();
+        let test_mesh_correct = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces_correct.clone(),
+            vertices_correct.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+        let (relaxed_mesh, _, _) = laplacian_smoothing(
+            &mesh,
+            &v2v,
+            50,
+            &fixed_vertex_indices,
+            false,
+            NormalStrategy::Sharp,
+        );
+
+        let relaxed_mesh_faces = relaxed_mesh.faces();
+        let test_mesh_faces = test_mesh_correct.faces();
+
+        assert_eq!(relaxed_mesh_faces, test_mesh_faces);
+
+        const TOLERANCE_SQUARED: f32 = 0.01 * 0.01;
+
+        let relaxed_mesh_vertices = relaxed_mesh.vertices();
+        let test_mesh_vertices = test_mesh_correct.vertices();
+
+        for i in 0..test_mesh_vertices.len() {
+            assert!(
+                nalgebra::distance_squared(&test_mesh_vertices[i], &relaxed_mesh_vertices[i])
+                    < TOLERANCE_SQUARED
+            );
+        }
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_with_anchors_find_border_vertices() {
+        let (faces, vertices) = shape_for_smoothing_with_anchors();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = analysis::edge_sharing(&oriented_edges);
+        let fixed_vertex_indices =
+            Vec::from_iter(analysis::border_vertex_indices(&edge_sharing_map).into_iter());
+
+        let (faces_correct, vertices_correct) = shape_for_smoothing_with_anchors_50_iterations();
+        let test_mesh_correct = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces_correct.clone(),
+            vertices_correct.clone(),
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+        let (relaxed_mesh, _, _) = laplacian_smoothing(
+            &mesh,
+            &v2v,
+            50,
+            &fixed_vertex_indices,
+            false,
+            NormalStrategy::Sharp,
+        );
+
+        // The faces should be made of the same vertex indices (and they should
+        // remain in the original order) but the normals can be different due to
+        // smoothing.
+        let relaxed_mesh_faces_vertices: Vec<_> = relaxed_mesh
+            .triangle_faces_iter()
+            .map(|t| t.vertices)
+            .collect();
+        let test_mesh_faces_vertices: Vec<_> = test_mesh_correct
+            .triangle_faces_iter()
+            .map(|t| t.vertices)
+            .collect();
+
+        assert_eq!(relaxed_mesh_faces_vertices, test_mesh_faces_vertices);
+
+        let relaxed_mesh_vertices = relaxed_mesh.vertices();
+        let test_mesh_vertices = test_mesh_correct.vertices();
+
+        for i in 0..test_mesh_vertices.len() {
+            assert!(test_mesh_vertices[i].coords.relative_eq(
+                &relaxed_mesh_vertices[i].coords,
+                0.001,
+                0.001,
+            ));
+        }
+    }
+
+    #[test]
+    fn test_laplacian_smoothing_with_anchors_stop_when_stable_find_border_vertices() {
+        let (faces, vertices) = shape_for_smoothing_with_anchors();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing_map = analysis::edge_sharing(&oriented_edges);
+        let fixed_vertex_indices =
+            Vec::from_iter(analysis::border_vertex_indices(&edge_sharing_map).into_iter());
+
+        let (faces_correct, vertices_correct) = shape_for_smoothing_with_anchors_50_iterations();
+        let test_mesh_correct = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces_correct.clone(),
+            vertices_correct.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+        let (relaxed_mesh, _, _) = laplacian_smoothing(
+            &mesh,
+            &v2v,
+            255,
+            &fixed_vertex_indices,
+            true,
+            NormalStrategy::Sharp,
+        );
+
+        // The faces should be made of the same vertex indices (and they should
+        // remain in the original order) but the normals can be different due to
+        // smoothing.
+        let relaxed_mesh_faces_vertices: Vec<_> = relaxed_mesh
+            .triangle_faces_iter()
+            .map(|t| t.vertices)
+            .collect();
+        let test_mesh_faces_vertices: Vec<_> = test_mesh_correct
+            .triangle_faces_iter()
+            .map(|t| t.vertices)
+            .collect();
+
+        assert_eq!(relaxed_mesh_faces_vertices, test_mesh_faces_vertices);
+
+        let relaxed_mesh_vertices = relaxed_mesh.vertices();
+        let test_mesh_vertices = test_mesh_correct.vertices();
+
+        for i in 0..test_mesh_vertices.len() {
+            assert!(test_mesh_vertices[i].coords.relative_eq(
+                &relaxed_mesh_vertices[i].coords,
+                0.001,
+                0.001,
+            ));
+        }
+    }
+
+    #[test]
+    fn test_relax_on_surface_shrinks_voxel_sphere_less_than_laplacian_smoothing() {
+        let sphere = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+            16,
+            16,
+            NormalStrategy::Sharp,
+        );
+        let voxel_dimensions = Vector3::repeat(0.1);
+        let mut voxel_cloud = VoxelCloud::from_mesh(&sphere, &voxel_dimensions);
+        voxel_cloud.fill_volumes();
+        let voxel_mesh = voxel_cloud.to_mesh().unwrap();
+        let v2v = topology::compute_vertex_to_vertex_topology(&voxel_mesh);
+        let distance_field = DistanceField::from_mesh(&voxel_mesh, &voxel_dimensions, 0.0);
+
+        let relaxed = relax_on_surface(
+            &voxel_mesh,
+            &v2v,
+            &distance_field,
+            10,
+            NormalStrategy::Smooth,
+            |_| false,
+        )
+        .unwrap();
+        let (smoothed, _, _) =
+            laplacian_smoothing(&voxel_mesh, &v2v, 10, &[], false, NormalStrategy::Smooth);
+
+        let mean_radius = |mesh: &Mesh| {
+            mesh.vertices()
+                .iter()
+                .map(|vertex| vertex.coords.norm())
+                .sum::<f32>()
+                / mesh.vertices().len() as f32
+        };
+        assert!(mean_radius(&relaxed) > mean_radius(&smoothed));
+        for (relaxed_vertex, voxel_vertex) in relaxed.vertices().iter().zip(voxel_mesh.vertices()) {
+            let displacement = relaxed_vertex - voxel_vertex;
+            assert!(displacement.amax() <= 0.05 + 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_loop_subdivision_snapshot_uv_sphere() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+            2,
+            3,
+            NormalStrategy::Sharp,
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+        let e2f = topology::compute_edge_to_face_topology(&mesh);
+        let f2f = topology::compute_face_to_face_topology(&mesh, &e2f);
+
+        let subdivided_mesh = loop_subdivision(&mesh, &v2v, &f2f, NormalStrategy::Sharp)
+            .expect("The mesh doesn't meet the loop subdivision prerequisites");
+
+        insta::assert_json_snapshot!(
+            "uv_sphere_2_3_after_1_iteration_of_loop_subdivision",
+            &subdivided_mesh
+        );
+    }
+
+    #[test]
+    fn test_loop_subdivision_snapshot_box_sharp() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let v2v = topology::compute_vertex_to_vertex_topology(&mesh);
+        let e2f = topology::compute_edge_to_face_topology(&mesh);
+        let f2f = topology::compute_face_to_face_topology(&mesh, &e2f);
+
+        let subdivided_mesh = loop_subdivision(&mesh, &v2v, &f2f, NormalStrategy::Sharp)
+            .expect("The mesh doesn't meet the loop subdivision prerequisites");
+
+        insta::assert_json_snapshot!(
+            "box_sharp_after_1_iteration_of_loop_subdivision",
+            &subdivided_mesh
+        );
+    }
+
+    #[test]
+    fn test_catmull_clark_subdivision_box_joins_quads_and_rounds_corners() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+        );
+
+        let subdivided_mesh = catmull_clark_subdivision(&mesh, 1, NormalStrategy::Smooth);
+
+        // 8 vertices, 12 edge points and 6 face points of 6 quads
+        assert_eq!(subdivided_mesh.vertices().len(), 26);
+        assert_eq!(subdivided_mesh.faces().len(), 24);
+        assert!(subdivided_mesh
+            .faces()
+            .iter()
+            .all(|face| face.vertices().len() == 4));
+
+        let corner = Point3::new(5.0 / 9.0, 5.0 / 9.0, 5.0 / 9.0);
+        assert!(subdivided_mesh
+            .vertices()
+            .iter()
+            .any(|vertex| na::distance(vertex, &corner) < 0.0001));
+
+        let oriented_edges: Vec<OrientedEdge> = subdivided_mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        assert!(analysis::is_mesh_watertight(&edge_sharing));
+    }
+
+    #[test]
+    fn test_catmull_clark_subdivision_triangle_produces_3_quads() {
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2)],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let subdivided_mesh = catmull_clark_subdivision(&mesh, 1, NormalStrategy::Sharp);
+
+        assert_eq!(subdivided_mesh.vertices().len(), 7);
+        assert_eq!(subdivided_mesh.faces().len(), 3);
+    }
+
+    #[test]
+    fn test_catmull_clark_subdivision_keeps_quads_of_the_mesh() {
+        // Two quads folded at a right angle, which would not be joined
+        // if they were triangulated
+        let mesh = Mesh::from_faces_with_vertices_and_computed_normals(
+            vec![
+                Face::from(QuadFace::new(0, 1, 2, 3)),
+                Face::from(QuadFace::new(1, 4, 5, 2)),
+            ],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+                Point3::new(1.0, 0.0, -1.0),
+                Point3::new(1.0, 1.0, -1.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let unchanged_mesh = catmull_clark_subdivision(&mesh, 0, NormalStrategy::Sharp);
+        assert_eq!(unchanged_mesh.faces(), mesh.faces());
+
+        let subdivided_mesh = catmull_clark_subdivision(&mesh, 1, NormalStrategy::Sharp);
+
+        // 6 vertices, 7 edge points and 2 face points of 8 quads
+        assert_eq!(subdivided_mesh.vertices().len(), 15);
+        assert_eq!(subdivided_mesh.faces().len(), 8);
+        assert!(subdivided_mesh
+            .faces()
+            .iter()
+            .all(|face| face.vertices().len() == 4));
+    }
+
+    #[test]
+    fn test_catmull_clark_subdivision_keeps_winding() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+        );
+
+        let subdivided_mesh = catmull_clark_subdivision(&mesh, 2, NormalStrategy::Sharp);
+
+        // All face normals of the convex mesh point away from its center
+        for face in subdivided_mesh.faces() {
+            for triangle_face in face.to_triangles() {
+                let vertices = subdivided_mesh.vertices();
+                let a = vertices[cast_usize(triangle_face.vertices.0)];
+                let b = vertices[cast_usize(triangle_face.vertices.1)];
+                let c = vertices[cast_usize(triangle_face.vertices.2)];
+                let normal = (b - a).cross(&(c - a));
+                assert!(normal.dot(&a.coords) > 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,3136 @@
+use std::collections::VecDeque;
+use std::f32;
+use std::iter;
+use std::mem;
+
+use arrayvec::ArrayVec;
+use nalgebra::{
+    Isometry3, Matrix3, Matrix4, Point2, Point3, Translation3, UnitQuaternion, Vector3, U1, U3,
+};
+use smallvec::{smallvec, SmallVec};
+
+use crate::bounding_box::BoundingBox;
+use crate::collections::{HashMap, HashSet};
+use crate::convert::{cast_u32, cast_usize};
+use crate::geometry::predicates;
+use crate::plane::Plane;
+
+use super::bvh::Bvh;
+use super::normals::VertexNormals;
+use super::{
+    analysis, orphan_removal_vertex_map, topology, Face, Mesh, NormalStrategy, OrientedEdge,
+    TriangleFace, UnorientedEdge,
+};
+
+/// Vertex indices of a triangle face with the normals of its corners.
+type CornerNormalFace = ((u32, u32, u32), [Vector3<f32>; 3]);
+
+/// Corners of a triangle face as their vertex indices and normals.
+type FaceCorners = [(u32, Vector3<f32>); 3];
+
+/// Orients all the faces the same way - matches their winding (vertex order).
+///
+/// This function crawls the mesh geometry and flips all the faces, which are
+/// not facing the same way as the previously checked neighboring faces,
+/// starting with the first face in the list for each mesh island.
+///
+/// The algorithm relies on the fact that in a proper manifold mesh, each
+/// oriented edge has exactly one (for watertight mesh geometry) or none (for
+/// mesh patch naked edges) counter-edge oriented the opposite direction. It
+/// crawls the geometry and if a face neighboring the current one doesn't have
+/// the proper winding, it's being reverted and only then triggers checking its
+/// own neighbors.
+///
+/// This method doesn't flip the normals associated with the face vertices, as
+/// there is no unambiguous way to do so automatically.
+///
+/// # Warning
+/// As a result, the entire mesh can end up facing inwards (be entirely
+/// reverted). At the moment we have no tools to detect such a case
+/// automatically, so we need to rely on the user to check it and potentially
+/// revert winding of the entire mesh.
+///
+/// # Warning
+/// The results might be unpredictable for non-manifold meshes and moebius-like
+/// topologies.
+pub fn synchronize_mesh_winding(
+    mesh: &Mesh,
+    face_to_face_topology: &[SmallVec<[u32; topology::MAX_INLINE_NEIGHBOR_COUNT]>],
+) -> Mesh {
+    // FIXME: Flip also vertex normals if the visual/practical tests prove it's
+    // needed
+
+    // Processing queue: indices of faces sharing edges with the current face,
+    // zipped with the oriented edge they should contain if they have a proper
+    // winding.
+    let mut queue_to_process: VecDeque<(usize, OrientedEdge)> = VecDeque::new();
+    let mut discovered = vec![false; mesh.faces().len()];
+    // Synchronized faces with the indices of the original faces they
+    // come from, to carry over the normals of their corners.
+    let mut synchronized_faces: Vec<(usize, Face)> = Vec::with_capacity(mesh.faces().len());
+    // All faces before this index are already discovered, so the search for
+    // the next island doesn't have to start over from the first face.
+    let mut undiscovered_search_start = 0;
+
+    // For each island in the mesh geometry
+    while synchronized_faces.len() < mesh.faces().len() {
+        // find first undiscovered face
+        let first_face_index = undiscovered_search_start
+            + discovered[undiscovered_search_start..]
+                .iter()
+                .position(|v| !v)
+                .expect("All faces already discovered");
+        undiscovered_search_start = first_face_index + 1;
+
+        // add it to the processing queue.
+        queue_to_process.push_front((
+            // the face
+            first_face_index,
+            // one of the edges it should contain
+            mesh.faces()[first_face_index].to_oriented_edges()[0],
+        ));
+        // Mark the face already inserted into the queue.
+        discovered[cast_usize(first_face_index)] = true;
+
+        // While there is anything in the queue (crawl the entire mesh island)
+        while let Some((face_index, desired_oriented_edge)) = queue_to_process.pop_front() {
+            // get the actual face
+            let original_face = mesh.faces()[cast_usize(face_index)];
+            // and check if it contains the desired oriented edge. If it does,
+            // the winding is ok, otherwise revert the face.
+            let proper_face = if original_face.contains_oriented_edge(desired_oriented_edge) {
+                original_face
+            } else {
+                original_face.to_reverted()
+            };
+            // Put the properly winded face into the stack of processed faces.
+            synchronized_faces.push((face_index, proper_face));
+
+            // Compute properly oriented edges of face's neighbors
+            let proper_neighbor_oriented_edges: ArrayVec<[OrientedEdge; 4]> = proper_face
+                .to_oriented_edges()
+                .into_iter()
+                .map(|o_e| o_e.to_reverted())
+                .collect();
+
+            // For each face's neighbor index
+            for &neighbor_face_index in &face_to_face_topology[face_index] {
+                // check if it was already discovered and added to the queue.
+                if !discovered[cast_usize(neighbor_face_index)] {
+                    // If it wasn't, get the neighboring face
+                    let neighbor_face = mesh.faces()[cast_usize(neighbor_face_index)];
+                    // and for each properly oriented edge which should be in
+                    // the neighboring faces
+                    for edge in &proper_neighbor_oriented_edges {
+                        // check which edge belongs to which face.
+                        if neighbor_face.contains_unoriented_edge(UnorientedEdge(*edge)) {
+                            // If it's this one, add it to the processing
+                            // queue together with the properly oriented edge
+                            // it should contain.
+                            queue_to_process.push_back((cast_usize(neighbor_face_index), *edge));
+                            // Stop looking for other edges to be found in the
+                            // current neighboring face.
+                            break;
+                        }
+                    }
+
+                    // And finally mark the faces as discovered
+                    discovered[cast_usize(neighbor_face_index)] = true;
+                }
+            }
+        }
+    }
+
+    let vertex_normals = VertexNormals::from_face_corner_normals(
+        synchronized_faces.iter().map(|(face_index, face)| {
+            // The corners of a reverted face keep their normals
+            let corner_normals = face
+                .vertices()
+                .iter()
+                .map(|vertex_index| {
+                    mesh.vertex_normals()
+                        .corner_normal(*face_index, *vertex_index)
+                })
+                .collect();
+
+            (*face, corner_normals)
+        }),
+        mesh.vertices().len(),
+    );
+    let mut synchronized = Mesh::from_faces_with_vertices_and_vertex_normals(
+        synchronized_faces.into_iter().map(|(_, face)| face),
+        mesh.vertices().iter().copied(),
+        vertex_normals,
+    );
+    synchronized.copy_vertex_data_from(mesh);
+
+    synchronized
+}
+
+/// Reverts vertex and normal winding of all faces in the mesh geometry and
+/// returns a reverted mesh geometry
+pub fn revert_mesh_faces(mesh: &Mesh) -> Mesh {
+    let reverted_faces = mesh.faces().iter().map(Face::to_reverted);
+    let mut reverted = Mesh::from_faces_with_vertices_and_vertex_normals(
+        reverted_faces,
+        mesh.vertices().iter().copied(),
+        mesh.vertex_normals().clone(),
+    );
+    reverted.copy_vertex_data_from(mesh);
+    reverted.copy_face_materials_from(mesh);
+
+    reverted
+}
+
+/// Moves and rotates the mesh geometry, keeping its topology and
+/// vertex attributes.
+pub fn transform_mesh(mesh: &Mesh, transform: &Isometry3<f32>) -> Mesh {
+    let mut transformed_mesh = mesh.clone();
+    for vertex in transformed_mesh.vertices_mut() {
+        *vertex = transform * *vertex;
+    }
+    for normal in transformed_mesh.normals_mut() {
+        *normal = transform * *normal;
+    }
+
+    transformed_mesh
+}
+
+/// Applies an affine transformation to the mesh geometry, keeping its
+/// topology and vertex attributes.
+///
+/// Normals are transformed by the inverse transpose of the linear part
+/// of the transformation, so that they stay perpendicular to the
+/// surface even with non-uniform scaling. If the transformation
+/// mirrors the geometry, the faces are reverted to keep it oriented
+/// the same way.
+///
+/// # Panics
+/// Panics if the transformation is singular, e.g. it scales by zero.
+pub fn transform_mesh_by_matrix(mesh: &Mesh, transform: &Matrix4<f32>) -> Mesh {
+    let linear: Matrix3<f32> = transform.fixed_slice::<U3, U3>(0, 0).into_owned();
+    let normal_transform = linear
+        .try_inverse()
+        .expect("Transformation must be invertible")
+        .transpose();
+
+    let mut transformed_mesh = mesh.clone();
+    if linear.determinant() < 0.0 {
+        for face in transformed_mesh.faces_mut() {
+            *face = face.to_reverted();
+        }
+    }
+    for vertex in transformed_mesh.vertices_mut() {
+        *vertex = transform.transform_point(vertex);
+    }
+    for normal in transformed_mesh.normals_mut() {
+        *normal = (normal_transform * *normal).normalize();
+    }
+
+    transformed_mesh
+}
+
+/// Uniformly scales the mesh geometry around the origin, keeping its
+/// topology and vertex attributes.
+pub fn scale_mesh(mesh: &Mesh, factor: f32) -> Mesh {
+    let mut scaled_mesh = mesh.clone();
+    for vertex in scaled_mesh.vertices_mut() {
+        vertex.coords *= factor;
+    }
+
+    scaled_mesh
+}
+
+/// Mirrors the mesh geometry by the plane, keeping its topology and
+/// vertex attributes. The faces are reverted, so that the mirrored
+/// mesh is oriented the same way as the original.
+pub fn mirror_mesh(mesh: &Mesh, plane: &Plane) -> Mesh {
+    let normal = plane.normal();
+    let reflection = Matrix3::identity() - 2.0 * normal * normal.transpose();
+    let translation = 2.0 * plane.origin().coords.dot(&normal) * normal;
+
+    let mut transform = reflection.to_homogeneous();
+    transform
+        .fixed_slice_mut::<U3, U1>(0, 3)
+        .copy_from(&translation);
+
+    transform_mesh_by_matrix(mesh, &transform)
+}
+
+/// Rotates the mesh geometry so that its principal axes align with the
+/// world axes, the axis of the least variance pointing up, and moves it
+/// so that it stands centered on the XY plane at the origin. The
+/// largest face of the bounding box aligned with the principal axes
+/// therefore lies on the ground.
+///
+/// Of the two largest faces, the one closer to the vertex centroid is
+/// put on the ground, so that the heavier side of the mesh is down.
+pub fn orient_mesh_to_ground(mesh: &Mesh) -> Mesh {
+    let principal_axes = analysis::compute_principal_axes(mesh.vertices());
+    let rotation = UnitQuaternion::from_rotation_matrix(&principal_axes.inverse());
+
+    let rotated_vertices: Vec<Point3<f32>> = mesh
+        .vertices()
+        .iter()
+        .map(|vertex| rotation * vertex)
+        .collect();
+    let bounding_box = BoundingBox::from_points(rotated_vertices.iter().copied())
+        .expect("Mesh must have vertices");
+    let centroid_height =
+        rotated_vertices.iter().map(|vertex| vertex.z).sum::<f32>() / rotated_vertices.len() as f32;
+
+    let center = bounding_box.center();
+    let (rotation, center, bottom) = if centroid_height > center.z {
+        let flip = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), f32::consts::PI);
+        (
+            flip * rotation,
+            flip * center,
+            -bounding_box.maximum_point().z,
+        )
+    } else {
+        (rotation, center, bounding_box.minimum_point().z)
+    };
+
+    let translation = Translation3::new(-center.x, -center.y, -bottom);
+    transform_mesh(mesh, &Isometry3::from_parts(translation, rotation))
+}
+
+/// Reverts vertex and normal winding of a single face or of the
+/// consistently wound patch of faces around it and returns a mesh
+/// geometry with the faces reverted.
+///
+/// The patch contains all faces reachable from the given face over
+/// edges shared with the opposite orientation, so it stops at edges
+/// where winding of the neighboring faces disagrees. Returns `None` if
+/// the face index is out of bounds.
+pub fn revert_mesh_faces_at(mesh: &Mesh, face_index: usize, connected_patch: bool) -> Option<Mesh> {
+    let faces = mesh.faces();
+    if face_index >= faces.len() {
+        return None;
+    }
+
+    let mut to_revert = vec![false; faces.len()];
+    to_revert[face_index] = true;
+
+    if connected_patch {
+        let mut edge_to_face: HashMap<OrientedEdge, usize> = HashMap::default();
+        for (index, face) in faces.iter().enumerate() {
+            for edge in face.to_oriented_edges() {
+                edge_to_face.insert(edge, index);
+            }
+        }
+
+        let mut queue_to_process = VecDeque::new();
+        queue_to_process.push_back(face_index);
+
+        while let Some(index) = queue_to_process.pop_front() {
+            for edge in faces[index].to_oriented_edges() {
+                if let Some(&neighbor_index) = edge_to_face.get(&edge.to_reverted()) {
+                    if !to_revert[neighbor_index] {
+                        to_revert[neighbor_index] = true;
+                        queue_to_process.push_back(neighbor_index);
+                    }
+                }
+            }
+        }
+    }
+
+    let faces = faces
+        .iter()
+        .zip(to_revert)
+        .map(|(face, revert)| if revert { face.to_reverted() } else { *face });
+
+    let mut reverted = Mesh::from_faces_with_vertices_and_vertex_normals(
+        faces,
+        mesh.vertices().iter().copied(),
+        mesh.vertex_normals().clone(),
+    );
+    reverted.copy_vertex_data_from(mesh);
+    reverted.copy_face_materials_from(mesh);
+
+    Some(reverted)
+}
+
+/// Recomputes the normals of the mesh, smooth across the edges where
+/// faces meet at an angle of at most `crease_angle` (in radians) and
+/// sharp across steeper edges.
+///
+/// Each face corner gets the area weighted average of the normals of
+/// the faces around its vertex, which deviate from the normal of the
+/// corner's face by at most `crease_angle`. Corners of a vertex ending
+/// up with the same normal share it. The crease angle of a half turn
+/// makes all the normals smooth. Vertex attributes and colors are kept.
+pub fn recompute_normals(mesh: &Mesh, crease_angle: f32) -> Mesh {
+    let vertices = mesh.vertices();
+    // The cross product of the diagonals of a quad is twice its area
+    // in the direction of its normal, the same as for a triangle
+    let face_crosses: Vec<Vector3<f32>> = mesh
+        .faces()
+        .iter()
+        .map(|face| match face {
+            Face::Triangle(face) => {
+                let (v1, v2, v3) = face.vertices;
+                let p1 = vertices[cast_usize(v1)];
+                (vertices[cast_usize(v2)] - p1).cross(&(vertices[cast_usize(v3)] - p1))
+            }
+            Face::Quad(face) => {
+                let (v1, v2, v3, v4) = face.vertices;
+                (vertices[cast_usize(v3)] - vertices[cast_usize(v1)])
+                    .cross(&(vertices[cast_usize(v4)] - vertices[cast_usize(v2)]))
+            }
+        })
+        .collect();
+    let face_normals: Vec<Vector3<f32>> = face_crosses
+        .iter()
+        .map(|cross| {
+            if cross.norm_squared() > 0.0 {
+                cross.normalize()
+            } else {
+                Vector3::zeros()
+            }
+        })
+        .collect();
+    let min_cos = crease_angle.cos();
+
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        for vertex_index in face.vertices() {
+            vertex_faces[cast_usize(vertex_index)].push(face_index);
+        }
+    }
+
+    let corners = mesh.faces().iter().enumerate().map(|(face_index, face)| {
+        let corner_normal = |vertex_index: u32| {
+            let mut normal = Vector3::zeros();
+            for neighbor_index in &vertex_faces[cast_usize(vertex_index)] {
+                if *neighbor_index == face_index
+                    || face_normals[*neighbor_index].dot(&face_normals[face_index]) >= min_cos
+                {
+                    normal += face_crosses[*neighbor_index];
+                }
+            }
+            // Corners summing the same faces get bitwise identical
+            // normals and share the normal of the vertex
+            if normal.norm_squared() > 0.0 {
+                normal.normalize()
+            } else {
+                Vector3::z()
+            }
+        };
+
+        (
+            *face,
+            face.vertices().into_iter().map(corner_normal).collect(),
+        )
+    });
+
+    let mut recomputed = Mesh::from_faces_with_vertices_and_vertex_normals(
+        mesh.faces().iter().copied(),
+        vertices.iter().copied(),
+        VertexNormals::from_face_corner_normals(corners, vertices.len()),
+    );
+    for attribute in mesh.vertex_attributes() {
+        recomputed.set_vertex_attribute(attribute.name(), attribute.values().to_vec());
+    }
+    if let Some(colors) = mesh.vertex_colors() {
+        recomputed.set_vertex_colors(colors.to_vec());
+    }
+    recomputed.copy_face_materials_from(mesh);
+
+    recomputed
+}
+
+/// Weld similar (their distance is within the given tolerance) vertices into
+/// one and reuse such vertices in connected faces.
+///
+/// Weld is used to actually connect faces (often resulting in a watertight
+/// mesh), which are connected merely visually or to reduce number of vertices
+/// in case when vertices are multiplied because vertices at the same
+/// coordinates are not referenced by more faces but rather each face references
+/// its own copy of the vertex.
+///
+/// Weld is one of the auto-fixes leading to a simplified, watertight or
+/// true-to-its-genus mesh geometries. Vertex attributes and colors of
+/// the welded vertices are averaged.
+///
+/// The mesh **must** be triangulated.
+pub fn weld(mesh: &Mesh, tolerance: f32) -> Option<Mesh> {
+    // key = rounded vertex position with a tolerance (it's expected that the
+    // same value will be shared by more close vertices)
+    // value = actual positions of close vertices
+    let mut vertex_proximity_map: HashMap<(i64, i64, i64), SmallVec<[usize; 8]>> =
+        HashMap::default();
+    for (current_vertex_index, vertex) in mesh.vertices().iter().enumerate() {
+        let vertex_with_tolerance = (
+            (vertex.x / tolerance).round() as i64,
+            (vertex.y / tolerance).round() as i64,
+            (vertex.z / tolerance).round() as i64,
+        );
+
+        vertex_proximity_map
+            .entry(vertex_with_tolerance)
+            .and_modify(|close_vertices| close_vertices.push(current_vertex_index))
+            .or_insert_with(|| smallvec![current_vertex_index]);
+    }
+
+    // All vertices sorted into clusters of positionally close items. These will
+    // be later averaged into a single vertex.
+    let close_vertex_clusters = vertex_proximity_map.values();
+
+    // key = original vertex index
+    // value = new (averaged) vertex index It is expected that more keys will
+    // share the same value; more original vertices will be replaced by a single
+    // averaged vertex
+    let mut old_new_vertex_map: Vec<u32> = vec![u32::max_value(); mesh.vertices().len()];
+    for (new_vertex_index, old_vertex_indices) in close_vertex_clusters.clone().enumerate() {
+        for old_vertex_index in old_vertex_indices {
+            old_new_vertex_map[cast_usize(*old_vertex_index)] = cast_u32(new_vertex_index);
+        }
+    }
+
+    // Vertices of the new mesh geometry averaged from the clusters of
+    // original vertices.
+    let new_vertices = close_vertex_clusters.map(|old_vertex_indices| {
+        old_vertex_indices
+            .iter()
+            .fold(Point3::origin(), |summed: Point3<f32>, old_vertex_index| {
+                summed + mesh.vertices()[*old_vertex_index].coords
+            })
+            / old_vertex_indices.len() as f32
+    });
+
+    // New faces with renumbered vertex indices. Some faces might end up
+    // invalid (not referencing three distinct vertices). Those will be
+    // removed as they don't affect the visual appearance of the mesh
+    // geometry.
+    let new_faces: Vec<(u32, u32, u32)> = mesh
+        .triangle_faces_iter()
+        .filter_map(|f| {
+            let (v1, v2, v3) = (
+                old_new_vertex_map[cast_usize(f.vertices.0)],
+                old_new_vertex_map[cast_usize(f.vertices.1)],
+                old_new_vertex_map[cast_usize(f.vertices.2)],
+            );
+            if v1 != v2 && v1 != v3 && v2 != v3 {
+                Some((v1, v2, v3))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Each new vertex gets a single smooth normal averaged from the
+    // normals of all the face corners referring to the original
+    // vertices merged into it.
+    let mut new_normals: Vec<Vector3<f32>> = vec![Vector3::zeros(); new_vertices.len()];
+    for (face_index, f) in mesh.triangle_faces_iter().enumerate() {
+        let (v1, v2, v3) = f.vertices;
+        let [n1, n2, n3] = mesh.corner_normals(face_index);
+        for (old_vertex_index, normal) in &[(v1, n1), (v2, n2), (v3, n3)] {
+            let new_vertex_index = old_new_vertex_map[cast_usize(*old_vertex_index)];
+            new_normals[cast_usize(new_vertex_index)] += normal;
+        }
+    }
+
+    if new_faces.is_empty() {
+        None
+    } else {
+        let mut welded = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            new_faces,
+            new_vertices,
+            VertexNormals::from_vertex_normals(new_normals),
+        );
+        let vertex_map: Vec<_> = old_new_vertex_map.into_iter().map(Some).collect();
+        welded.remap_vertex_data_from(mesh, &vertex_map);
+
+        Some(welded)
+    }
+}
+
+/// Weld vertices on open borders of the mesh, which are within the
+/// given tolerance, like `weld`, but only if the welding doesn't
+/// change the topology of the mesh for the worse.
+///
+/// Vertices inside the mesh are never moved. Merging two border
+/// vertices is rejected, if it would make an edge shared by more than
+/// two faces, or collapse a face. Returns the welded mesh and the
+/// number of rejected merges, or `None` if the mesh has no faces. Face
+/// normals and materials are kept. Vertex attributes and colors of the
+/// merged vertices are averaged.
+///
+/// The mesh **must** be triangulated.
+pub fn weld_borders(mesh: &Mesh, tolerance: f32) -> Option<(Mesh, usize)> {
+    let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+    let edge_sharing = analysis::edge_sharing(&oriented_edges);
+    let mut is_border_vertex = vec![false; mesh.vertices().len()];
+    for edge in analysis::border_edges(&edge_sharing) {
+        is_border_vertex[cast_usize(edge.vertices.0)] = true;
+        is_border_vertex[cast_usize(edge.vertices.1)] = true;
+    }
+
+    let mut vertex_proximity_map: HashMap<(i64, i64, i64), SmallVec<[u32; 8]>> = HashMap::default();
+    for (vertex_index, vertex) in mesh.vertices().iter().enumerate() {
+        if is_border_vertex[vertex_index] {
+            let vertex_with_tolerance = (
+                (vertex.x / tolerance).round() as i64,
+                (vertex.y / tolerance).round() as i64,
+                (vertex.z / tolerance).round() as i64,
+            );
+            vertex_proximity_map
+                .entry(vertex_with_tolerance)
+                .or_default()
+                .push(cast_u32(vertex_index));
+        }
+    }
+
+    // Clusters are processed in a stable order, so that the same
+    // merges are rejected each time
+    let mut close_vertex_clusters: Vec<_> = vertex_proximity_map
+        .into_iter()
+        .map(|(_, cluster)| cluster)
+        .filter(|cluster| cluster.len() > 1)
+        .collect();
+    close_vertex_clusters.sort_unstable_by_key(|cluster| cluster[0]);
+
+    let mut faces: Vec<TriangleFace> = mesh.triangle_faces_iter().collect();
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); mesh.vertices().len()];
+    for (face_index, face) in faces.iter().enumerate() {
+        let (v1, v2, v3) = face.vertices;
+        vertex_faces[cast_usize(v1)].push(face_index);
+        vertex_faces[cast_usize(v2)].push(face_index);
+        vertex_faces[cast_usize(v3)].push(face_index);
+    }
+    let mut edge_valencies: HashMap<UnorientedEdge, usize> = edge_sharing
+        .iter()
+        .map(|(edge, shared_edges)| {
+            let valency = shared_edges.ascending_edges.len() + shared_edges.descending_edges.len();
+            (*edge, valency)
+        })
+        .collect();
+
+    let mut vertices = mesh.vertices().to_vec();
+    let mut merged_into: Vec<u32> = (0..cast_u32(vertices.len())).collect();
+    let mut rejected_count = 0;
+    for cluster in close_vertex_clusters {
+        let target = cluster[0];
+        let mut position_sum = vertices[cast_usize(target)].coords;
+        let mut merged_count = 1;
+
+        for &source in &cluster[1..] {
+            // The number of faces each edge from the source would add
+            // to the edge from the target to the same vertex
+            let mut moved_edges: SmallVec<[(u32, usize); 16]> = SmallVec::new();
+            let mut collapses_face = false;
+            for &face_index in &vertex_faces[cast_usize(source)] {
+                let (v1, v2, v3) = faces[face_index].vertices;
+                for &other in &[v1, v2, v3] {
+                    if other == target {
+                        collapses_face = true;
+                    } else if other != source {
+                        match moved_edges.iter_mut().find(|(vertex, _)| *vertex == other) {
+                            Some((_, count)) => *count += 1,
+                            None => moved_edges.push((other, 1)),
+                        }
+                    }
+                }
+            }
+
+            let creates_non_manifold_edge = moved_edges.iter().any(|&(other, count)| {
+                let edge = UnorientedEdge(OrientedEdge::new(target, other));
+                edge_valencies.get(&edge).copied().unwrap_or(0) + count > 2
+            });
+
+            if collapses_face || creates_non_manifold_edge {
+                rejected_count += 1;
+                continue;
+            }
+
+            for &(other, count) in &moved_edges {
+                edge_valencies.remove(&UnorientedEdge(OrientedEdge::new(source, other)));
+                *edge_valencies
+                    .entry(UnorientedEdge(OrientedEdge::new(target, other)))
+                    .or_insert(0) += count;
+            }
+
+            let source_faces = mem::replace(&mut vertex_faces[cast_usize(source)], Vec::new());
+            for &face_index in &source_faces {
+                let (v1, v2, v3) = &mut faces[face_index].vertices;
+                for vertex in ArrayVec::from([v1, v2, v3]) {
+                    if *vertex == source {
+                        *vertex = target;
+                    }
+                }
+            }
+            vertex_faces[cast_usize(target)].extend(source_faces);
+            merged_into[cast_usize(source)] = target;
+
+            position_sum += vertices[cast_usize(source)].coords;
+            merged_count += 1;
+        }
+
+        vertices[cast_usize(target)] = Point3::from(position_sum / merged_count as f32);
+    }
+
+    if faces.is_empty() {
+        None
+    } else {
+        let orphan_removal_map = orphan_removal_vertex_map(
+            &faces.iter().copied().map(Face::from).collect::<Vec<_>>(),
+            vertices.len(),
+        );
+        let vertex_map: Vec<_> = merged_into
+            .iter()
+            .map(|target| orphan_removal_map[cast_usize(*target)])
+            .collect();
+
+        // The faces are merged in place, so their corners keep the
+        // order and the normals of the original faces
+        let mut welded = Mesh::from_triangle_faces_with_vertices_and_corner_normals_remove_orphans(
+            faces
+                .iter()
+                .enumerate()
+                .map(|(face_index, face)| (face.vertices, mesh.corner_normals(face_index))),
+            vertices,
+        );
+        welded.remap_vertex_data_from(mesh, &vertex_map);
+        welded.copy_face_materials_from(mesh);
+
+        Some((welded, rejected_count))
+    }
+}
+
+/// Assigns the `material` to faces of the mesh geometry and returns
+/// the mesh with the new face materials. Without a `face_index`, all
+/// faces get the material. Otherwise only the face does, or with
+/// `connected_patch`, all the faces connected to it over shared
+/// edges. The other faces keep their materials.
+///
+/// Returns `None` if the face index is out of bounds.
+pub fn assign_face_material(
+    mesh: &Mesh,
+    material: u32,
+    face_index: Option<usize>,
+    connected_patch: bool,
+) -> Option<Mesh> {
+    let face_count = mesh.faces().len();
+    let mut materials = mesh
+        .face_materials()
+        .map_or_else(|| vec![0; face_count], <[u32]>::to_vec);
+
+    match face_index {
+        None => {
+            for face_material in &mut materials {
+                *face_material = material;
+            }
+        }
+        Some(face_index) if face_index >= face_count => return None,
+        Some(face_index) if connected_patch => {
+            let edge_to_face_topology = topology::compute_edge_to_face_topology(mesh);
+            let face_to_face =
+                topology::compute_face_to_face_topology(mesh, &edge_to_face_topology);
+
+            let mut discovered = vec![false; face_count];
+            discovered[face_index] = true;
+            let mut index_stack = vec![face_index];
+            while let Some(current_face_index) = index_stack.pop() {
+                materials[current_face_index] = material;
+                for neighbor_index in &face_to_face[current_face_index] {
+                    let neighbor_index = cast_usize(*neighbor_index);
+                    if !discovered[neighbor_index] {
+                        discovered[neighbor_index] = true;
+                        index_stack.push(neighbor_index);
+                    }
+                }
+            }
+        }
+        Some(face_index) => materials[face_index] = material,
+    }
+
+    let mut assigned = mesh.clone();
+    assigned.set_face_materials(materials);
+
+    Some(assigned)
+}
+
+/// Crawls the mesh geometry to find continuous patches. Returns a
+/// vector mesh patches.
+pub fn disjoint_mesh(mesh: &Mesh) -> Vec<Mesh> {
+    let edge_to_face_topology = topology::compute_edge_to_face_topology(&mesh);
+    let face_to_face = topology::compute_face_to_face_topology(mesh, &edge_to_face_topology);
+    let mut available_face_indices: HashSet<u32> = (0..cast_u32(mesh.faces().len())).collect();
+    let mut patches: Vec<Mesh> = Vec::new();
+    let mut index_stack: Vec<u32> = Vec::new();
+    let mut connected_face_indices = HashSet::default();
+
+    while let Some(start_face_index) = available_face_indices.iter().copied().next() {
+        available_face_indices.remove(&start_face_index);
+        index_stack.push(start_face_index);
+        connected_face_indices.clear();
+
+        while let Some(current_face_index) = index_stack.pop() {
+            if connected_face_indices.insert(current_face_index) {
+                for neighbor_index in &face_to_face[cast_usize(current_face_index)] {
+                    if available_face_indices.contains(neighbor_index) {
+                        index_stack.push(*neighbor_index);
+                        available_face_indices.remove(neighbor_index);
+                    }
+                }
+            }
+        }
+
+        let patch_face_indices: Vec<usize> = connected_face_indices
+            .iter()
+            .map(|face_index| cast_usize(*face_index))
+            .collect();
+        let mut patch = extract_faces(mesh, &patch_face_indices);
+        if let Some(materials) = mesh.face_materials() {
+            patch.set_face_materials(
+                patch_face_indices
+                    .iter()
+                    .map(|face_index| materials[*face_index])
+                    .collect(),
+            );
+        }
+
+        patches.push(patch);
+    }
+
+    patches
+}
+
+/// Creates a mesh of the faces at `face_indices` of the mesh with the
+/// normals of their corners, without the vertices of the other faces.
+/// Vertex attributes and colors are kept.
+fn extract_faces(mesh: &Mesh, face_indices: &[usize]) -> Mesh {
+    let faces: Vec<Face> = face_indices
+        .iter()
+        .map(|face_index| mesh.faces()[*face_index])
+        .collect();
+    let vertex_map = orphan_removal_vertex_map(&faces, mesh.vertices().len());
+    let vertex_normals = VertexNormals::from_face_corner_normals(
+        face_indices.iter().map(|face_index| {
+            (
+                mesh.faces()[*face_index],
+                mesh.face_corner_normals(*face_index),
+            )
+        }),
+        mesh.vertices().len(),
+    );
+
+    let mut extracted = Mesh::from_faces_with_vertices_and_vertex_normals_remove_orphans(
+        faces,
+        mesh.vertices().iter().copied(),
+        vertex_normals,
+    );
+    extracted.remap_vertex_data_from(mesh, &vertex_map);
+
+    extracted
+}
+
+/// Joins multiple mesh geometries into one.
+///
+/// Concatenates vertex and normal slices, while keeping the first mesh's
+/// element indices intact and all other meshes' indices offset by the number of
+/// of the respective elements already joined into the joint mesh. Reuses first
+/// mesh's faces and recomputes the other mesh's faces to match new indices of
+/// its vertices.
+///
+/// Vertex attributes present in all the meshes are joined too, as are
+/// vertex colors, if all the meshes have them. Face materials are
+/// joined if any of the meshes has them, the faces of the other meshes
+/// getting the material 0.
+pub fn join_multiple_meshes<'a, I>(meshes: I) -> Mesh
+where
+    I: IntoIterator<Item = &'a Mesh>,
+{
+    let meshes: Vec<&Mesh> = meshes.into_iter().collect();
+    let mut vertices: Vec<Point3<f32>> = Vec::new();
+    let mut normals = VertexNormals::from_vertex_normals(iter::empty());
+    let mut faces: Vec<Face> = Vec::new();
+
+    for mesh in &meshes {
+        let vertex_offset_u32 = cast_u32(vertices.len());
+
+        vertices.extend_from_slice(mesh.vertices());
+        normals.append(mesh.vertex_normals(), faces.len());
+
+        if vertex_offset_u32 == 0 {
+            faces.extend_from_slice(mesh.faces());
+        } else {
+            for face in mesh.faces() {
+                faces.push(face.map_vertices(|vertex_index| vertex_index + vertex_offset_u32));
+            }
+        }
+    }
+
+    let mut joined = Mesh::from_faces_with_vertices_and_vertex_normals(faces, vertices, normals);
+
+    if let Some((first, rest)) = meshes.split_first() {
+        for attribute in first.vertex_attributes() {
+            let attributes: Option<Vec<_>> = rest
+                .iter()
+                .map(|mesh| mesh.vertex_attribute(attribute.name()))
+                .collect();
+            if let Some(attributes) = attributes {
+                let values = iter::once(attribute)
+                    .chain(attributes)
+                    .flat_map(|attribute| attribute.values().iter().copied())
+                    .collect();
+                joined.set_vertex_attribute(attribute.name(), values);
+            }
+        }
+
+        let colors: Option<Vec<_>> = meshes.iter().map(|mesh| mesh.vertex_colors()).collect();
+        if let Some(colors) = colors {
+            joined.set_vertex_colors(colors.concat());
+        }
+    }
+
+    if meshes.iter().any(|mesh| mesh.face_materials().is_some()) {
+        let materials = meshes
+            .iter()
+            .flat_map(|mesh| match mesh.face_materials() {
+                Some(materials) => materials.to_vec(),
+                None => vec![0; mesh.faces().len()],
+            })
+            .collect();
+        joined.set_face_materials(materials);
+    }
+
+    joined
+}
+
+/// Transfers all per-vertex attributes from the `source` mesh onto
+/// the vertices of the `target` mesh.
+///
+/// For each target vertex, the closest point on the source mesh
+/// surface is found and the attribute values of the source triangle
+/// vertices are interpolated with the barycentric coordinates of that
+/// point. Attributes already present on the target mesh with the
+/// same names are replaced.
+///
+/// Returns `None` if the source mesh has no faces.
+pub fn transfer_vertex_attributes(source: &Mesh, target: &Mesh) -> Option<Mesh> {
+    if source.faces().is_empty() {
+        return None;
+    }
+
+    let source_bvh = Bvh::from_mesh(source);
+    let closest_face_coords: Vec<(TriangleFace, Point3<f32>)> = target
+        .vertices()
+        .iter()
+        .map(|target_vertex| {
+            let closest = source_bvh.closest_point(target_vertex)?;
+
+            Some((closest.triangle_face, closest.barycentric_coords))
+        })
+        .collect::<Option<_>>()?;
+
+    let mut transferred = target.clone();
+    for attribute in source.vertex_attributes() {
+        let source_values = attribute.values();
+        let values = closest_face_coords
+            .iter()
+            .map(|(face, coords)| {
+                let (v0, v1, v2) = face.vertices;
+                coords.x * source_values[cast_usize(v0)]
+                    + coords.y * source_values[cast_usize(v1)]
+                    + coords.z * source_values[cast_usize(v2)]
+            })
+            .collect();
+
+        transferred.set_vertex_attribute(attribute.name(), values);
+    }
+
+    Some(transferred)
+}
+
+/// Reduces the number of faces of the mesh to at most `face_ratio` of
+/// the original count, if possible, by clustering vertices in a
+/// regular grid and merging each cluster into a single averaged vertex.
+/// Faces collapsed into an edge or a point are removed.
+///
+/// The finest grid producing few enough faces is found by bisecting
+/// the grid resolution. Vertex clustering is fast and robust, but it
+/// does not preserve topology - small holes may close and thin parts
+/// may merge. Normals are recomputed and vertex attributes and colors
+/// are not kept.
+///
+/// Returns `None` if no faces remain. The mesh **must** be triangulated.
+pub fn decimate(mesh: &Mesh, face_ratio: f32) -> Option<Mesh> {
+    const MAX_RESOLUTION: u32 = 4096;
+
+    if face_ratio >= 1.0 {
+        return Some(mesh.clone());
+    }
+
+    let target_face_count = (mesh.faces().len() as f32 * face_ratio.max(0.0)).round() as usize;
+    let bounding_box = mesh.bounding_box();
+    let diagonal = bounding_box.diagonal();
+    let extent = diagonal.x.max(diagonal.y).max(diagonal.z);
+    if extent <= 0.0 || target_face_count == 0 {
+        return None;
+    }
+
+    let cluster = |resolution: u32| {
+        let cell_size = extent / resolution as f32;
+        cluster_vertices(mesh, &bounding_box.minimum_point(), cell_size)
+    };
+
+    let mut coarsest = None;
+    let mut best = None;
+    let mut low = 1;
+    let mut high = MAX_RESOLUTION;
+    while low <= high {
+        let resolution = low + (high - low) / 2;
+        let (faces, vertices) = cluster(resolution);
+
+        if faces.len() <= target_face_count {
+            best = Some((faces, vertices));
+            low = resolution + 1;
+        } else {
+            if resolution == 1 {
+                coarsest = Some((faces, vertices));
+            }
+            high = resolution - 1;
+        }
+    }
+
+    // Even the coarsest grid may produce more faces than requested
+    let (faces, vertices) = best.or(coarsest)?;
+    if faces.is_empty() {
+        return None;
+    }
+
+    Some(
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals_remove_orphans(
+            faces,
+            vertices,
+            NormalStrategy::Smooth,
+        ),
+    )
+}
+
+type ClusteredGeometry = (Vec<(u32, u32, u32)>, Vec<Point3<f32>>);
+
+/// Merges the vertices of the mesh falling into the same grid cell and
+/// returns the resulting non-degenerate, unique faces together with
+/// the averaged vertices.
+fn cluster_vertices(mesh: &Mesh, origin: &Point3<f32>, cell_size: f32) -> ClusteredGeometry {
+    let mut cell_cluster_indices: HashMap<(i64, i64, i64), u32> = HashMap::default();
+    let mut cluster_sums: Vec<(Vector3<f32>, u32)> = Vec::new();
+    let vertex_cluster_indices: Vec<u32> = mesh
+        .vertices()
+        .iter()
+        .map(|vertex| {
+            let cell = (vertex - origin) / cell_size;
+            let key = (
+                cell.x.floor() as i64,
+                cell.y.floor() as i64,
+                cell.z.floor() as i64,
+            );
+
+            let cluster_index = *cell_cluster_indices.entry(key).or_insert_with(|| {
+                cluster_sums.push((Vector3::zeros(), 0));
+                cast_u32(cluster_sums.len() - 1)
+            });
+
+            let (sum, count) = &mut cluster_sums[cast_usize(cluster_index)];
+            *sum += vertex.coords;
+            *count += 1;
+
+            cluster_index
+        })
+        .collect();
+
+    let mut face_keys = HashSet::default();
+    let faces: Vec<_> = mesh
+        .triangle_faces_iter()
+        .filter_map(|triangle_face| {
+            let (v1, v2, v3) = triangle_face.vertices;
+            let face = (
+                vertex_cluster_indices[cast_usize(v1)],
+                vertex_cluster_indices[cast_usize(v2)],
+                vertex_cluster_indices[cast_usize(v3)],
+            );
+
+            if face.0 == face.1 || face.0 == face.2 || face.1 == face.2 {
+                return None;
+            }
+
+            // Faces collapsed onto the same vertices are kept
+            // only once, regardless of their winding
+            let mut key = [face.0, face.1, face.2];
+            key.sort();
+            if face_keys.insert(key) {
+                Some(face)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let vertices = cluster_sums
+        .iter()
+        .map(|(sum, count)| Point3::from(sum / *count as f32))
+        .collect();
+
+    (faces, vertices)
+}
+
+/// Number of relaxation iterations fairing the vertices inserted by a
+/// smooth hole fill.
+const HOLE_FAIRING_ITERATIONS: u32 = 64;
+
+/// Fills the holes of the mesh bounded by closed loops of at most
+/// `max_hole_edges` border edges. Returns the filled mesh and the
+/// number of filled holes.
+///
+/// Each hole is triangulated by ear clipping its boundary projected
+/// onto the plane best fitting it. If `smooth` is set, the hole is
+/// instead filled with concentric rings of new vertices, which are
+/// relaxed into a membrane spanning the boundary. The fill faces are
+/// wound consistently with the faces around the hole.
+///
+/// The mesh should be welded, otherwise faces sharing only vertex
+/// positions are separated by holes too. Vertex attributes and colors
+/// are not kept. The mesh **must** be triangulated.
+pub fn fill_holes(mesh: &Mesh, max_hole_edges: usize, smooth: bool) -> (Mesh, usize) {
+    let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+    let edge_sharing = analysis::edge_sharing(&oriented_edges);
+    let border_edges: HashSet<OrientedEdge> = analysis::border_edges(&edge_sharing).collect();
+
+    let mut vertices = mesh.vertices().to_vec();
+    let mut faces: Vec<CornerNormalFace> = mesh
+        .triangle_faces_iter()
+        .enumerate()
+        .map(|(face_index, f)| (f.vertices, mesh.corner_normals(face_index)))
+        .collect();
+    let mut filled_hole_count = 0;
+
+    for boundary in border_loops(&border_edges, max_hole_edges) {
+        let (fill_vertices, fill_faces) = if smooth {
+            fair_fill(&vertices, &boundary)
+        } else {
+            (Vec::new(), ear_clip(&vertices, &boundary))
+        };
+        vertices.extend(fill_vertices);
+
+        // Each vertex of the fill gets a normal averaged from the fill
+        // faces around it, so the fill does not change the shading of
+        // the faces around the hole
+        let mut fill_normals: HashMap<u32, Vector3<f32>> = HashMap::default();
+        for &(v1, v2, v3) in &fill_faces {
+            let face_normal = (vertices[cast_usize(v2)] - vertices[cast_usize(v1)])
+                .cross(&(vertices[cast_usize(v3)] - vertices[cast_usize(v1)]));
+            for vertex_index in &[v1, v2, v3] {
+                *fill_normals
+                    .entry(*vertex_index)
+                    .or_insert_with(Vector3::zeros) += face_normal;
+            }
+        }
+
+        faces.extend(fill_faces.iter().map(|&(v1, v2, v3)| {
+            (
+                (v1, v2, v3),
+                [fill_normals[&v1], fill_normals[&v2], fill_normals[&v3]],
+            )
+        }));
+
+        filled_hole_count += 1;
+    }
+
+    (
+        Mesh::from_triangle_faces_with_vertices_and_corner_normals(faces, vertices),
+        filled_hole_count,
+    )
+}
+
+/// Joins the border edges into closed loops of at most `max_edges`
+/// edges. The vertices of each loop run against the border edges, so
+/// that faces filling the loop are wound consistently with the faces
+/// around it.
+fn border_loops(border_edges: &HashSet<OrientedEdge>, max_edges: usize) -> Vec<Vec<u32>> {
+    analysis::edge_chains(border_edges.iter().map(|edge| edge.to_unoriented()))
+        .into_iter()
+        .filter_map(|chain| {
+            let edge_count = chain.len() - 1;
+            if chain.first() != chain.last() || edge_count < 3 || edge_count > max_edges {
+                return None;
+            }
+
+            let mut boundary = chain[..edge_count].to_vec();
+            if border_edges.contains(&OrientedEdge::new(boundary[0], boundary[1])) {
+                boundary.reverse();
+            }
+
+            Some(boundary)
+        })
+        .collect()
+}
+
+/// Cuts the mesh by the plane given by its origin and normal and keeps
+/// the part behind it, i.e. the normal points towards the part cut
+/// away. Faces crossing the plane are re-triangulated, with normals
+/// interpolated along the cut.
+///
+/// If `cap` is set, the closed outlines of the cut are filled with
+/// planar faces, so that cutting a watertight mesh results in a
+/// watertight mesh. Nested outlines, e.g. of a cut pipe, are capped
+/// separately and their caps overlap. Vertex attributes and colors are
+/// not kept.
+///
+/// Returns `None` if the whole mesh is cut away. The mesh **must** be
+/// triangulated.
+///
+/// # Panics
+/// Panics if the normal is a zero vector.
+pub fn cut_by_plane(
+    mesh: &Mesh,
+    plane_origin: &Point3<f32>,
+    plane_normal: &Vector3<f32>,
+    cap: bool,
+) -> Option<Mesh> {
+    let plane = Plane::from_origin_and_normal(plane_origin, plane_normal);
+    let plane_origin = *plane_origin;
+    let plane_normal = plane_normal.normalize();
+    let distances: Vec<f32> = mesh
+        .vertices()
+        .iter()
+        .map(|vertex| (vertex - plane_origin).dot(&plane_normal))
+        .collect();
+
+    // The sides of the vertices are decided exactly, so that vertices
+    // on the plane are recognized and each face is classified
+    // consistently with its neighbors. The rounded distances are only
+    // used to place the new vertices. The plane vectors don't
+    // necessarily span it counterclockwise around the given normal, so
+    // the points are ordered for the orientation test to agree with it.
+    let (plane_point_x, plane_point_y) = if plane.normal().dot(&plane_normal) > 0.0 {
+        (
+            plane_origin + plane.x_vector(),
+            plane_origin + plane.y_vector(),
+        )
+    } else {
+        (
+            plane_origin + plane.y_vector(),
+            plane_origin + plane.x_vector(),
+        )
+    };
+    let sides: Vec<f64> = mesh
+        .vertices()
+        .iter()
+        .map(|vertex| -predicates::orient3d(&plane_origin, &plane_point_x, &plane_point_y, vertex))
+        .collect();
+
+    let mut vertices = mesh.vertices().to_vec();
+    let mut on_plane: Vec<bool> = sides.iter().map(|side| *side == 0.0).collect();
+    let mut faces: Vec<CornerNormalFace> = Vec::with_capacity(mesh.faces().len());
+
+    // Each edge crossing the plane gets a single new vertex shared by
+    // the faces around it, so that the cut stays welded
+    let mut edge_vertices: HashMap<UnorientedEdge, u32> = HashMap::default();
+
+    for (face_index, face) in mesh.triangle_faces_iter().enumerate() {
+        let [n1, n2, n3] = mesh.corner_normals(face_index);
+        let corners = [
+            (face.vertices.0, n1),
+            (face.vertices.1, n2),
+            (face.vertices.2, n3),
+        ];
+        let side = |vertex_index: u32| sides[cast_usize(vertex_index)];
+
+        if corners.iter().all(|(v, _)| side(*v) <= 0.0) {
+            faces.push((face.vertices, [n1, n2, n3]));
+            continue;
+        }
+        if corners.iter().all(|(v, _)| side(*v) >= 0.0) {
+            continue;
+        }
+
+        // Clip the triangle by the plane, resulting in a triangle or
+        // a quad of (vertex_index, normal) corners
+        let mut polygon: ArrayVec<[(u32, Vector3<f32>); 4]> = ArrayVec::new();
+        for i in 0..3 {
+            let (v1, n1) = corners[i];
+            let (v2, n2) = corners[(i + 1) % 3];
+            let (s1, s2) = (side(v1), side(v2));
+
+            if s1 <= 0.0 {
+                polygon.push((v1, n1));
+            }
+            if (s1 < 0.0 && s2 > 0.0) || (s1 > 0.0 && s2 < 0.0) {
+                // Rounding may put the crossing slightly outside the
+                // edge, or make it undefined for tiny distances
+                let (d1, d2) = (distances[cast_usize(v1)], distances[cast_usize(v2)]);
+                let t = if (d1 - d2).abs() <= f32::EPSILON {
+                    0.5
+                } else {
+                    (d1 / (d1 - d2)).max(0.0).min(1.0)
+                };
+                let vertex_index = *edge_vertices
+                    .entry(UnorientedEdge(OrientedEdge::new(v1, v2)))
+                    .or_insert_with(|| {
+                        let p1 = vertices[cast_usize(v1)];
+                        let p2 = vertices[cast_usize(v2)];
+                        vertices.push(p1 + (p2 - p1) * t);
+                        on_plane.push(true);
+                        cast_u32(vertices.len() - 1)
+                    });
+
+                polygon.push((vertex_index, n1 * (1.0 - t) + n2 * t));
+            }
+        }
+
+        if polygon.len() >= 3 {
+            for i in 1..polygon.len() - 1 {
+                let (v1, n1) = polygon[0];
+                let (v2, n2) = polygon[i];
+                let (v3, n3) = polygon[i + 1];
+                faces.push(((v1, v2, v3), [n1, n2, n3]));
+            }
+        }
+    }
+
+    if cap {
+        let mut oriented_edges: Vec<OrientedEdge> = Vec::with_capacity(faces.len() * 3);
+        for (face_vertices, _) in &faces {
+            oriented_edges
+                .extend_from_slice(&TriangleFace::from(*face_vertices).to_oriented_edges());
+        }
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        let cut_edges: HashSet<OrientedEdge> = analysis::border_edges(&edge_sharing)
+            .filter(|edge| {
+                let (v1, v2) = edge.vertices;
+                on_plane[cast_usize(v1)] && on_plane[cast_usize(v2)]
+            })
+            .collect();
+
+        for boundary in border_loops(&cut_edges, usize::max_value()) {
+            for face_vertices in ear_clip(&vertices, &boundary) {
+                faces.push((face_vertices, [plane_normal; 3]));
+            }
+        }
+    }
+
+    if faces.is_empty() {
+        None
+    } else {
+        Some(
+            Mesh::from_triangle_faces_with_vertices_and_corner_normals_remove_orphans(
+                faces, vertices,
+            ),
+        )
+    }
+}
+
+/// Crops the mesh to the axis aligned box by cutting it by the planes
+/// of the box sides. If `cap` is set, the cuts are capped, see
+/// `cut_by_plane`.
+///
+/// Returns `None` if no part of the mesh is inside the box. The mesh
+/// **must** be triangulated.
+pub fn crop_to_box(mesh: &Mesh, bounding_box: &BoundingBox<f32>, cap: bool) -> Option<Mesh> {
+    let minimum_point = bounding_box.minimum_point();
+    let maximum_point = bounding_box.maximum_point();
+    let planes = [
+        (maximum_point, Vector3::x()),
+        (maximum_point, Vector3::y()),
+        (maximum_point, Vector3::z()),
+        (minimum_point, -Vector3::x()),
+        (minimum_point, -Vector3::y()),
+        (minimum_point, -Vector3::z()),
+    ];
+
+    let mut cropped = cut_by_plane(mesh, &planes[0].0, &planes[0].1, cap)?;
+    for (origin, normal) in &planes[1..] {
+        cropped = cut_by_plane(&cropped, origin, normal, cap)?;
+    }
+
+    Some(cropped)
+}
+
+/// Removes the duplicate and internal faces found by
+/// `analysis::find_duplicate_faces`, keeping one face of each set of
+/// duplicates. Returns `None` if no faces remain.
+pub fn remove_duplicate_faces(
+    mesh: &Mesh,
+    duplicate_faces: &analysis::DuplicateFaces,
+) -> Option<Mesh> {
+    let removed_face_indices: HashSet<usize> = duplicate_faces
+        .duplicate_face_indices
+        .iter()
+        .chain(&duplicate_faces.internal_face_indices)
+        .copied()
+        .collect();
+    if removed_face_indices.len() == mesh.faces().len() {
+        return None;
+    }
+
+    let kept_face_indices: Vec<usize> = (0..mesh.faces().len())
+        .filter(|face_index| !removed_face_indices.contains(face_index))
+        .collect();
+
+    Some(extract_faces(mesh, &kept_face_indices))
+}
+
+/// Faces with the shortest edge shorter than this fraction of the
+/// longest edge are needles, which are removed by collapsing the
+/// shortest edge. Other degenerate faces are caps, which are removed by
+/// flipping the longest edge.
+const NEEDLE_EDGE_RATIO: f32 = 0.2;
+
+/// Maximum number of passes over the mesh removing degenerate faces.
+const DEGENERATE_FACE_PASSES: u32 = 16;
+
+/// Removes zero-area and needle (sliver) faces from the mesh. Returns
+/// the repaired mesh and the number of degenerate faces that could not
+/// be removed, or `None` if no faces remain.
+///
+/// A face is degenerate if its area is at most `area_epsilon` or if the
+/// ratio of its longest edge squared to twice its area exceeds
+/// `aspect_limit`. Needles get their shortest edge collapsed, caps get
+/// their longest edge flipped with the neighboring face and zero-area
+/// caps on the mesh border are dropped. Changes which would fold the
+/// surface or make the mesh non-manifold are skipped.
+///
+/// The mesh should be welded, otherwise faces sharing only vertex
+/// positions are not considered neighbors. The mesh **must** be
+/// triangulated.
+pub fn remove_degenerate_faces(
+    mesh: &Mesh,
+    area_epsilon: f32,
+    aspect_limit: f32,
+) -> Option<(Mesh, usize)> {
+    let vertices = mesh.vertices();
+    let is_degenerate = |corners: &FaceCorners| {
+        let (area, aspect) = triangle_area_and_aspect(&face_positions(vertices, corners));
+        area <= area_epsilon || aspect > aspect_limit
+    };
+
+    // Each face is kept as its corners of vertex indices and normals
+    let mut faces: Vec<Option<FaceCorners>> = mesh
+        .triangle_faces_iter()
+        .enumerate()
+        .map(|(face_index, f)| {
+            let [n1, n2, n3] = mesh.corner_normals(face_index);
+            Some([(f.vertices.0, n1), (f.vertices.1, n2), (f.vertices.2, n3)])
+        })
+        .collect();
+
+    for _ in 0..DEGENERATE_FACE_PASSES {
+        let mut edge_faces: HashMap<(u32, u32), usize> = HashMap::default();
+        let mut vertex_faces: HashMap<u32, Vec<usize>> = HashMap::default();
+        for (face_index, corners) in faces.iter().enumerate() {
+            if let Some(corners) = corners {
+                for i in 0..3 {
+                    edge_faces.insert((corners[i].0, corners[(i + 1) % 3].0), face_index);
+                    vertex_faces
+                        .entry(corners[i].0)
+                        .or_insert_with(Vec::new)
+                        .push(face_index);
+                }
+            }
+        }
+
+        let mut border_vertices: HashSet<u32> = HashSet::default();
+        for &(from, to) in edge_faces.keys() {
+            if !edge_faces.contains_key(&(to, from)) {
+                border_vertices.insert(from);
+                border_vertices.insert(to);
+            }
+        }
+
+        // The adjacency above is not updated during the pass, therefore
+        // the vertices of every changed face are locked until the next
+        // pass
+        let mut locked_vertices: HashSet<u32> = HashSet::default();
+
+        for face_index in 0..faces.len() {
+            let corners = match faces[face_index] {
+                Some(corners) => corners,
+                None => continue,
+            };
+            if corners
+                .iter()
+                .any(|(vertex_index, _)| locked_vertices.contains(vertex_index))
+                || !is_degenerate(&corners)
+            {
+                continue;
+            }
+
+            let positions = face_positions(vertices, &corners);
+            let edge_lengths = [
+                (positions[1] - positions[0]).norm(),
+                (positions[2] - positions[1]).norm(),
+                (positions[0] - positions[2]).norm(),
+            ];
+            let shortest = (1..3).fold(0, |shortest, i| {
+                if edge_lengths[i] < edge_lengths[shortest] {
+                    i
+                } else {
+                    shortest
+                }
+            });
+            let longest = (1..3).fold(0, |longest, i| {
+                if edge_lengths[i] > edge_lengths[longest] {
+                    i
+                } else {
+                    longest
+                }
+            });
+
+            let mut changed_vertices =
+                if edge_lengths[shortest] <= NEEDLE_EDGE_RATIO * edge_lengths[longest] {
+                    collapse_edge(
+                        vertices,
+                        &mut faces,
+                        &edge_faces,
+                        &vertex_faces,
+                        &border_vertices,
+                        (corners[shortest].0, corners[(shortest + 1) % 3].0),
+                    )
+                } else {
+                    flip_edge(vertices, &mut faces, &edge_faces, face_index, longest)
+                };
+
+            if changed_vertices.is_none() {
+                let (area, _) = triangle_area_and_aspect(&positions);
+                let from = corners[longest].0;
+                let to = corners[(longest + 1) % 3].0;
+                let opposite = corners[(longest + 2) % 3].0;
+                if area <= area_epsilon
+                    && !edge_faces.contains_key(&(to, from))
+                    && !border_vertices.contains(&opposite)
+                {
+                    faces[face_index] = None;
+                    changed_vertices = Some(vec![from, to, opposite]);
+                }
+            }
+
+            if let Some(changed_vertices) = changed_vertices {
+                locked_vertices.extend(changed_vertices);
+            }
+        }
+
+        if locked_vertices.is_empty() {
+            break;
+        }
+    }
+
+    if faces.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let remaining_count = faces
+        .iter()
+        .flatten()
+        .filter(|corners| is_degenerate(*corners))
+        .count();
+    let faces: Vec<CornerNormalFace> = faces
+        .iter()
+        .flatten()
+        .map(|corners| {
+            (
+                (corners[0].0, corners[1].0, corners[2].0),
+                [corners[0].1, corners[1].1, corners[2].1],
+            )
+        })
+        .collect();
+    let vertex_map = orphan_removal_vertex_map(
+        &faces
+            .iter()
+            .map(|(face_vertices, _)| {
+                Face::from(TriangleFace {
+                    vertices: *face_vertices,
+                })
+            })
+            .collect::<Vec<_>>(),
+        vertices.len(),
+    );
+
+    let mut repaired = Mesh::from_triangle_faces_with_vertices_and_corner_normals_remove_orphans(
+        faces,
+        vertices.iter().copied(),
+    );
+    repaired.remap_vertex_data_from(mesh, &vertex_map);
+
+    Some((repaired, remaining_count))
+}
+
+/// Collapses the `edge` of a degenerate face into one of its vertices,
+/// keeping the vertex on the mesh border, if any. Returns the vertices
+/// of the changed faces, or `None` if the collapse would fold the
+/// surface or make the mesh non-manifold.
+fn collapse_edge(
+    vertices: &[Point3<f32>],
+    faces: &mut [Option<FaceCorners>],
+    edge_faces: &HashMap<(u32, u32), usize>,
+    vertex_faces: &HashMap<u32, Vec<usize>>,
+    border_vertices: &HashSet<u32>,
+    (from, to): (u32, u32),
+) -> Option<Vec<u32>> {
+    let is_interior_edge =
+        edge_faces.contains_key(&(from, to)) && edge_faces.contains_key(&(to, from));
+    let (kept, removed) = match (
+        border_vertices.contains(&from),
+        border_vertices.contains(&to),
+    ) {
+        (true, true) if is_interior_edge => return None,
+        (false, true) => (to, from),
+        _ => (from, to),
+    };
+
+    let neighbors = |vertex_index: u32| {
+        let mut neighbors = HashSet::default();
+        for face_index in &vertex_faces[&vertex_index] {
+            if let Some(corners) = faces[*face_index] {
+                neighbors.extend(
+                    corners
+                        .iter()
+                        .map(|(neighbor_index, _)| *neighbor_index)
+                        .filter(|neighbor_index| *neighbor_index != vertex_index),
+                );
+            }
+        }
+        neighbors
+    };
+
+    let shared_faces: Vec<usize> = vertex_faces[&kept]
+        .iter()
+        .copied()
+        .filter(|face_index| {
+            faces[*face_index].map_or(false, |corners| {
+                corners
+                    .iter()
+                    .any(|(vertex_index, _)| *vertex_index == removed)
+            })
+        })
+        .collect();
+
+    // The link condition: the only vertices neighboring both ends of the
+    // edge are the ones opposite to it, otherwise the collapse pinches
+    // the surface
+    let kept_neighbors = neighbors(kept);
+    let removed_neighbors = neighbors(removed);
+    if kept_neighbors.intersection(&removed_neighbors).count() != shared_faces.len() {
+        return None;
+    }
+
+    let kept_position = vertices[cast_usize(kept)];
+    for face_index in &vertex_faces[&removed] {
+        if shared_faces.contains(face_index) {
+            continue;
+        }
+        if let Some(corners) = faces[*face_index] {
+            let before = face_positions(vertices, &corners);
+            let mut after = before;
+            for (position, corner) in after.iter_mut().zip(corners.iter()) {
+                if corner.0 == removed {
+                    *position = kept_position;
+                }
+            }
+
+            if triangle_cross(&before).dot(&triangle_cross(&after)) < 0.0 {
+                return None;
+            }
+        }
+    }
+
+    for face_index in &shared_faces {
+        faces[*face_index] = None;
+    }
+    // The corners keep their normals, so that the shading of flat faces
+    // does not change
+    for face_index in &vertex_faces[&removed] {
+        if let Some(corners) = &mut faces[*face_index] {
+            for corner in corners.iter_mut() {
+                if corner.0 == removed {
+                    corner.0 = kept;
+                }
+            }
+        }
+    }
+
+    let mut changed_vertices = vec![kept, removed];
+    changed_vertices.extend(kept_neighbors);
+    changed_vertices.extend(removed_neighbors);
+
+    Some(changed_vertices)
+}
+
+/// Flips the edge starting at the `edge_index` corner of a degenerate
+/// face with the neighboring face across it. Returns the vertices of
+/// the changed faces, or `None` if the flip would fold the surface,
+/// duplicate an edge or not improve the worse of the two faces.
+fn flip_edge(
+    vertices: &[Point3<f32>],
+    faces: &mut [Option<FaceCorners>],
+    edge_faces: &HashMap<(u32, u32), usize>,
+    face_index: usize,
+    edge_index: usize,
+) -> Option<Vec<u32>> {
+    let corners = faces[face_index]?;
+    let from = corners[edge_index];
+    let to = corners[(edge_index + 1) % 3];
+    let opposite = corners[(edge_index + 2) % 3];
+
+    let neighbor_index = *edge_faces.get(&(to.0, from.0))?;
+    let neighbor = faces[neighbor_index]?;
+    let neighbor_opposite = neighbor
+        .iter()
+        .copied()
+        .find(|(vertex_index, _)| *vertex_index != from.0 && *vertex_index != to.0)?;
+    if neighbor_opposite.0 == opposite.0
+        || edge_faces.contains_key(&(opposite.0, neighbor_opposite.0))
+        || edge_faces.contains_key(&(neighbor_opposite.0, opposite.0))
+    {
+        return None;
+    }
+
+    let flipped = [
+        [opposite, from, neighbor_opposite],
+        [opposite, neighbor_opposite, to],
+    ];
+    let worst_aspect = |faces: &[FaceCorners]| {
+        faces
+            .iter()
+            .map(|corners| triangle_area_and_aspect(&face_positions(vertices, corners)).1)
+            .fold(
+                0.0,
+                |worst, aspect| if aspect > worst { aspect } else { worst },
+            )
+    };
+    let quad_normal = triangle_cross(&face_positions(vertices, &corners))
+        + triangle_cross(&face_positions(vertices, &neighbor));
+    if flipped
+        .iter()
+        .any(|corners| triangle_cross(&face_positions(vertices, corners)).dot(&quad_normal) <= 0.0)
+        || worst_aspect(&flipped) >= worst_aspect(&[corners, neighbor])
+    {
+        return None;
+    }
+
+    faces[face_index] = Some(flipped[0]);
+    faces[neighbor_index] = Some(flipped[1]);
+
+    Some(vec![opposite.0, from.0, to.0, neighbor_opposite.0])
+}
+
+fn face_positions(vertices: &[Point3<f32>], corners: &FaceCorners) -> [Point3<f32>; 3] {
+    [
+        vertices[cast_usize(corners[0].0)],
+        vertices[cast_usize(corners[1].0)],
+        vertices[cast_usize(corners[2].0)],
+    ]
+}
+
+fn triangle_cross(positions: &[Point3<f32>; 3]) -> Vector3<f32> {
+    (positions[1] - positions[0]).cross(&(positions[2] - positions[0]))
+}
+
+/// Computes the area of the triangle and the ratio of its longest edge
+/// squared to twice its area, which is infinite for zero-area
+/// triangles.
+fn triangle_area_and_aspect(positions: &[Point3<f32>; 3]) -> (f32, f32) {
+    let double_area = triangle_cross(positions).norm();
+    let longest_squared = (0..3)
+        .map(|i| (positions[(i + 1) % 3] - positions[i]).norm_squared())
+        .fold(
+            0.0,
+            |longest, length| if length > longest { length } else { longest },
+        );
+
+    let aspect = if double_area > 0.0 {
+        longest_squared / double_area
+    } else {
+        f32::INFINITY
+    };
+
+    (double_area / 2.0, aspect)
+}
+
+/// Triangulates the polygon with the `boundary` vertices by clipping
+/// its ears in the plane best fitting it. The triangles are wound the
+/// same way as the boundary.
+fn ear_clip(vertices: &[Point3<f32>], boundary: &[u32]) -> Vec<(u32, u32, u32)> {
+    let points: Vec<Point3<f32>> = boundary
+        .iter()
+        .map(|vertex_index| vertices[cast_usize(*vertex_index)])
+        .collect();
+
+    // Newell's method gives a normal the boundary winds around
+    // counter-clockwise, even for non-planar and concave polygons
+    let mut normal = Vector3::<f32>::zeros();
+    for (i, point) in points.iter().enumerate() {
+        let next = points[(i + 1) % points.len()];
+        normal.x += (point.y - next.y) * (point.z + next.z);
+        normal.y += (point.z - next.z) * (point.x + next.x);
+        normal.z += (point.x - next.x) * (point.y + next.y);
+    }
+    if normal.norm_squared() == 0.0 {
+        normal = Vector3::z();
+    }
+    let normal = normal.normalize();
+    let x_axis = if normal.x.abs() < 0.9 {
+        Vector3::x().cross(&normal).normalize()
+    } else {
+        Vector3::y().cross(&normal).normalize()
+    };
+    let y_axis = normal.cross(&x_axis);
+
+    let projected: Vec<Point2<f32>> = points
+        .iter()
+        .map(|point| Point2::new(point.coords.dot(&x_axis), point.coords.dot(&y_axis)))
+        .collect();
+    // The projection is rounded, but the predicates are exact for the
+    // projected points, so the ear tests never contradict each other
+    let cross = |first: usize, second: usize, third: usize| {
+        predicates::orient2d(projected[first], projected[second], projected[third])
+    };
+
+    let mut remaining: Vec<usize> = (0..boundary.len()).collect();
+    let mut triangles = Vec::with_capacity(boundary.len() - 2);
+    while remaining.len() > 3 {
+        let len = remaining.len();
+        let is_ear = |i: usize| {
+            let (previous, current, next) = (
+                remaining[(i + len - 1) % len],
+                remaining[i],
+                remaining[(i + 1) % len],
+            );
+            cross(previous, current, next) > 0.0
+                && remaining.iter().all(|&other| {
+                    other == previous
+                        || other == current
+                        || other == next
+                        || cross(previous, current, other) < 0.0
+                        || cross(current, next, other) < 0.0
+                        || cross(next, previous, other) < 0.0
+                })
+        };
+
+        // Degenerate boundaries may have no proper ear, in which case
+        // the triangulation continues with the first vertex anyway
+        let ear = (0..len).find(|i| is_ear(*i)).unwrap_or(0);
+        triangles.push((
+            boundary[remaining[(ear + len - 1) % len]],
+            boundary[remaining[ear]],
+            boundary[remaining[(ear + 1) % len]],
+        ));
+        remaining.remove(ear);
+    }
+    triangles.push((
+        boundary[remaining[0]],
+        boundary[remaining[1]],
+        boundary[remaining[2]],
+    ));
+
+    triangles
+}
+
+type FillGeometry = (Vec<Point3<f32>>, Vec<(u32, u32, u32)>);
+
+/// Fills the polygon with the `boundary` vertices with concentric rings
+/// of new vertices and a center vertex, relaxed into a membrane. Returns
+/// the new vertices, indexed after the existing `vertices`, and the
+/// faces, wound the same way as the boundary.
+fn fair_fill(vertices: &[Point3<f32>], boundary: &[u32]) -> FillGeometry {
+    let boundary_len = boundary.len();
+    let ring_count = boundary_len / 6;
+    let first_index = cast_u32(vertices.len());
+
+    let boundary_points: Vec<Point3<f32>> = boundary
+        .iter()
+        .map(|vertex_index| vertices[cast_usize(*vertex_index)])
+        .collect();
+    let centroid = Point3::from(
+        boundary_points
+            .iter()
+            .fold(Vector3::zeros(), |sum, point| sum + point.coords)
+            / boundary_len as f32,
+    );
+
+    // Rings of new vertices are initially placed between the boundary
+    // and its centroid
+    let mut fill_vertices = Vec::with_capacity(ring_count * boundary_len + 1);
+    for ring in 1..=ring_count {
+        let weight = ring as f32 / (ring_count + 1) as f32;
+        fill_vertices.extend(
+            boundary_points
+                .iter()
+                .map(|point| point + (centroid - point) * weight),
+        );
+    }
+    fill_vertices.push(centroid);
+
+    let ring_vertex = |ring: usize, i: usize| {
+        if ring == 0 {
+            boundary[i % boundary_len]
+        } else {
+            first_index + cast_u32((ring - 1) * boundary_len + i % boundary_len)
+        }
+    };
+    let center = first_index + cast_u32(ring_count * boundary_len);
+
+    let mut faces = Vec::with_capacity((2 * ring_count + 1) * boundary_len);
+    for ring in 0..ring_count {
+        for i in 0..boundary_len {
+            let (outer1, outer2) = (ring_vertex(ring, i), ring_vertex(ring, i + 1));
+            let (inner1, inner2) = (ring_vertex(ring + 1, i), ring_vertex(ring + 1, i + 1));
+            faces.push((outer1, outer2, inner2));
+            faces.push((outer1, inner2, inner1));
+        }
+    }
+    for i in 0..boundary_len {
+        faces.push((
+            ring_vertex(ring_count, i),
+            ring_vertex(ring_count, i + 1),
+            center,
+        ));
+    }
+
+    // Each new vertex is repeatedly moved to the average of its
+    // neighbors, while the boundary stays in place
+    let mut neighbors: HashMap<u32, HashSet<u32>> = HashMap::default();
+    for &(v1, v2, v3) in &faces {
+        for &(from, to) in &[(v1, v2), (v2, v3), (v3, v1)] {
+            neighbors.entry(from).or_default().insert(to);
+            neighbors.entry(to).or_default().insert(from);
+        }
+    }
+    let position = |fill_vertices: &[Point3<f32>], vertex_index: u32| {
+        if vertex_index >= first_index {
+            fill_vertices[cast_usize(vertex_index - first_index)]
+        } else {
+            vertices[cast_usize(vertex_index)]
+        }
+    };
+    for _ in 0..HOLE_FAIRING_ITERATIONS {
+        let relaxed: Vec<Point3<f32>> = (0..fill_vertices.len())
+            .map(|i| {
+                let vertex_neighbors = &neighbors[&(first_index + cast_u32(i))];
+                let sum = vertex_neighbors
+                    .iter()
+                    .fold(Vector3::zeros(), |sum, neighbor| {
+                        sum + position(&fill_vertices, *neighbor).coords
+                    });
+                Point3::from(sum / vertex_neighbors.len() as f32)
+            })
+            .collect();
+        fill_vertices = relaxed;
+    }
+
+    (fill_vertices, faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Rotation3, Vector2};
+
+    use crate::geometry;
+    use crate::mesh::{analysis, primitive};
+    use crate::plane::Plane;
+
+    use super::*;
+
+    fn welded_tessellated_triangle_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+
+        let vertex_normals = vec![
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ];
+
+        let faces = vec![(0, 1, 3), (1, 4, 3), (1, 2, 4), (3, 4, 5)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn tessellated_triangle_mesh_for_welding() -> Mesh {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0), //0, 0
+            Point3::new(0.0, -2.0, 0.0),  //1, 1
+            Point3::new(-1.0, 0.0, 0.0),  //3, 2
+            Point3::new(0.0, -2.0, 0.0),  //1, 3
+            Point3::new(2.0, -2.0, 0.0),  //2, 4
+            Point3::new(1.0, 0.0, 0.0),   //4, 5
+            Point3::new(1.0, 0.0, 0.0),   //4, 6
+            Point3::new(-1.0, 0.0, 0.0),  //3, 7
+            Point3::new(0.0, -2.0, 0.0),  //1, 8
+            Point3::new(-1.0, 0.0, 0.0),  //3, 9
+            Point3::new(1.0, 0.0, 0.0),   //4, 10
+            Point3::new(0.0, 2.0, 0.0),   //5, 11
+        ];
+
+        let vertex_normals = vec![Vector3::new(0.0, 0.0, 1.0); vertices.len()];
+
+        let faces = vec![(0, 1, 2), (3, 4, 5), (6, 7, 8), (9, 10, 11)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn tessellated_triangle_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+
+        let vertex_normals = vec![Vector3::new(0.0, 0.0, 1.0); vertices.len()];
+
+        let faces = vec![(0, 3, 1), (1, 3, 4), (1, 4, 2), (3, 5, 4)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn tessellated_triangle_with_island_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            Point3::new(-1.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 2.0, 1.0),
+        ];
+
+        let vertex_normals = vec![Vector3::new(0.0, 0.0, 1.0); vertices.len()];
+
+        let faces = vec![(0, 3, 1), (1, 3, 4), (1, 4, 2), (3, 5, 4), (6, 7, 8)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn flipped_tessellated_triangle_with_island_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            Point3::new(-1.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 2.0, 1.0),
+        ];
+
+        let vertex_normals = vec![Vector3::new(0.0, 0.0, 1.0); vertices.len()];
+
+        let faces = vec![
+            (0, 3, 1),
+            (1, 3, 4),
+            (2, 4, 1), // flipped
+            (3, 5, 4),
+            (6, 7, 8),
+        ];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    fn triangular_island_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(-1.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 2.0, 1.0),
+        ];
+
+        let vertex_normals = vec![Vector3::new(0.0, 0.0, 1.0); vertices.len()];
+
+        let faces = vec![(0, 1, 2)];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    pub fn open_box_sharp_mesh() -> Mesh {
+        let vertex_positions = vec![
+            // back
+            Point3::new(-1.0, 1.0, -1.0), //0
+            Point3::new(-1.0, 1.0, 1.0),  //1
+            Point3::new(1.0, 1.0, 1.0),   //2
+            Point3::new(1.0, 1.0, -1.0),  //3
+            // front
+            Point3::new(-1.0, -1.0, -1.0), //4
+            Point3::new(1.0, -1.0, -1.0),  //5
+            Point3::new(1.0, -1.0, 1.0),   //6
+            Point3::new(-1.0, -1.0, 1.0),  //7
+            // top
+            Point3::new(-1.0, 1.0, 1.0),  //8
+            Point3::new(-1.0, -1.0, 1.0), //9
+            Point3::new(1.0, -1.0, 1.0),  //10
+            Point3::new(1.0, 1.0, 1.0),   //11
+            // bottom
+            Point3::new(-1.0, 1.0, -1.0),  //12
+            Point3::new(1.0, 1.0, -1.0),   //13
+            Point3::new(1.0, -1.0, -1.0),  //14
+            Point3::new(-1.0, -1.0, -1.0), //15
+            // right
+            Point3::new(1.0, 1.0, -1.0),  //16
+            Point3::new(1.0, 1.0, 1.0),   //17
+            Point3::new(1.0, -1.0, 1.0),  //18
+            Point3::new(1.0, -1.0, -1.0), //19
+            // left
+            Point3::new(-1.0, 1.0, -1.0),  //20
+            Point3::new(-1.0, -1.0, -1.0), //21
+            Point3::new(-1.0, -1.0, 1.0),  //22
+            Point3::new(-1.0, 1.0, 1.0),   //23
+        ];
+
+        let vertex_normals = vec![
+            // back
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            // front
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            // top
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            // bottom
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            // right
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            // left
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+        ];
+
+        let faces = vec![
+            // back
+            (0, 1, 2),
+            (2, 3, 0),
+            // front
+            (4, 5, 6),
+            (6, 7, 4),
+            // top
+            (8, 9, 10),
+            (10, 11, 8),
+            // bottom
+            (12, 13, 14),
+            (14, 15, 12),
+            // right
+            (16, 17, 18),
+            (18, 19, 16),
+            // left
+            (20, 21, 22),
+            (22, 23, 20),
+        ];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertex_positions,
+            VertexNormals::from_vertex_normals(vertex_normals),
+        )
+    }
+
+    pub fn welded_box_smooth_mesh() -> Mesh {
+        let vertices = vec![
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, -1.0, 1.0),
+            Point3::new(-1.0, 1.0, 1.0),
+            Point3::new(1.0, 1.0, -1.0),
+            Point3::new(-1.0, 1.0, -1.0),
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, -1.0, 1.0),
+            Point3::new(1.0, -1.0, -1.0),
+        ];
+
+        let normals = vec![
+            Vector3::new(0.25, 0.5, 0.25),
+            Vector3::new(-0.5, -0.25, 0.25),
+            Vector3::new(-0.25, 0.25, 0.5),
+            Vector3::new(0.5, 0.25, -0.25),
+            Vector3::new(-0.33333334, 0.33333334, -0.33333334),
+            Vector3::new(-0.25, -0.5, -0.25),
+            Vector3::new(0.33333334, -0.33333334, 0.33333334),
+            Vector3::new(0.25, -0.25, -0.5),
+        ];
+
+        let faces = vec![
+            (0, 4, 2),
+            (0, 3, 4),
+            (5, 7, 6),
+            (1, 5, 6),
+            (1, 6, 2),
+            (0, 2, 6),
+            (3, 7, 4),
+            (4, 7, 5),
+            (0, 6, 3),
+            (3, 6, 7),
+            (1, 4, 5),
+            (1, 2, 4),
+        ];
+
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(normals),
+        )
+    }
+
+    #[test]
+    fn test_disjoint_mesh_returns_similar_for_tessellated_triangle() {
+        let mesh = tessellated_triangle_mesh();
+
+        let computed_meshes = disjoint_mesh(&mesh);
+
+        assert_eq!(computed_meshes.len(), 1);
+
+        assert!(analysis::are_similar(&computed_meshes[0], &mesh));
+    }
+
+    #[test]
+    fn test_disjoint_mesh_returns_similar_for_box() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let computed_meshes = disjoint_mesh(&mesh);
+
+        assert_eq!(computed_meshes.len(), 1);
+        assert!(analysis::are_similar(&mesh, &computed_meshes[0]));
+    }
+
+    #[test]
+    fn test_disjoint_mesh_returns_similar_for_tessellated_triangle_with_island() {
+        let mesh = tessellated_triangle_with_island_mesh();
+        let mesh_triangle_correct = tessellated_triangle_mesh();
+        let mesh_island_correct = triangular_island_mesh();
+
+        let computed_meshes = disjoint_mesh(&mesh);
+
+        assert_eq!(computed_meshes.len(), 2);
+
+        if analysis::are_similar(&computed_meshes[0], &mesh_triangle_correct) {
+            assert!(analysis::are_similar(
+                &computed_meshes[1],
+                &mesh_island_correct
+            ));
+        } else {
+            assert!(analysis::are_similar(
+                &computed_meshes[1],
+                &mesh_triangle_correct
+            ));
+            assert!(analysis::are_similar(
+                &computed_meshes[0],
+                &mesh_island_correct
+            ));
+        }
+    }
+
+    #[test]
+    fn test_revert_mesh_faces() {
+        let plane = Plane::from_origin_and_normal(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+        );
+        let plane_mesh = primitive::create_mesh_plane(plane, Vector2::new(2.0, 2.0));
+        let plane_reverted = revert_mesh_faces(&plane_mesh);
+
+        let expected_reverted_faces = vec![
+            Face::from(TriangleFace::new(2, 1, 0)),
+            Face::from(TriangleFace::new(0, 3, 2)),
+        ];
+
+        assert_eq!(plane_reverted.faces(), expected_reverted_faces.as_slice());
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_once_does_not_equal_original() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let mesh_reverted = revert_mesh_faces(&mesh);
+
+        assert_ne!(mesh, mesh_reverted);
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_twice_does_equal_original() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let mesh_twice_reverted = revert_mesh_faces(&revert_mesh_faces(&mesh));
+
+        assert_eq!(mesh, mesh_twice_reverted);
+    }
+
+    #[test]
+    fn test_transform_mesh_moves_vertices_rotates_normals_keeps_attributes() {
+        let mut mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let values: Vec<f32> = (0..mesh.vertices().len()).map(|i| i as f32).collect();
+        mesh.set_vertex_attribute("index", values.clone());
+        let transform = Isometry3::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::z() * f32::consts::FRAC_PI_2,
+        );
+
+        let transformed_mesh = transform_mesh(&mesh, &transform);
+
+        for (vertex, transformed_vertex) in mesh.vertices().iter().zip(transformed_mesh.vertices())
+        {
+            assert!(transformed_vertex.coords.relative_eq(
+                &(transform * vertex).coords,
+                0.0001,
+                0.0001,
+            ));
+        }
+        let normals = mesh.vertex_normals().vertex_normals();
+        let transformed_normals = transformed_mesh.vertex_normals().vertex_normals();
+        for (normal, transformed_normal) in normals.iter().zip(transformed_normals) {
+            assert!(transformed_normal.relative_eq(&(transform * normal), 0.0001, 0.0001));
+        }
+        assert_eq!(transformed_mesh.faces(), mesh.faces());
+        assert_eq!(
+            transformed_mesh.vertex_attribute("index").unwrap().values(),
+            &values[..],
+        );
+    }
+
+    #[test]
+    fn test_transform_mesh_by_matrix_keeps_normals_perpendicular_and_faces_outward() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            8,
+            8,
+            NormalStrategy::Smooth,
+        );
+        // Mirrors the sphere along X and stretches it into an ellipsoid
+        let transform = Matrix4::new_nonuniform_scaling(&Vector3::new(-2.0, 1.0, 1.0));
+
+        let transformed_mesh = transform_mesh_by_matrix(&mesh, &transform);
+
+        for (face_index, face) in transformed_mesh.triangle_faces_iter().enumerate() {
+            let (v1, v2, v3) = face.vertices;
+            let vertices = transformed_mesh.vertices();
+            let p1 = vertices[cast_usize(v1)];
+            let face_normal =
+                (vertices[cast_usize(v2)] - p1).cross(&(vertices[cast_usize(v3)] - p1));
+            let normal = transformed_mesh
+                .vertex_normals()
+                .corner_normal(face_index, v1);
+            // The smooth normals of the sphere are averaged face normals,
+            // so they are compared with the original normals transformed
+            // by the inverse transpose, which keeps them perpendicular to
+            // the surface
+            let original_normal = mesh.vertex_normals().corner_normal(face_index, v1);
+            let expected_normal = Vector3::new(
+                -original_normal.x / 2.0,
+                original_normal.y,
+                original_normal.z,
+            )
+            .normalize();
+
+            assert!(face_normal.dot(&normal) > 0.0);
+            assert!(normal.relative_eq(&expected_normal, 0.0001, 0.0001));
+        }
+    }
+
+    #[test]
+    fn test_orient_mesh_to_ground_puts_largest_face_of_box_on_ground() {
+        let mesh = primitive::create_box(
+            Point3::new(1.0, 2.0, 3.0),
+            Rotation3::from_euler_angles(0.3, 0.2, 0.1),
+            Vector3::new(4.0, 2.0, 1.0),
+        );
+
+        let oriented_mesh = orient_mesh_to_ground(&mesh);
+        let bounding_box = oriented_mesh.bounding_box();
+
+        assert!(bounding_box.minimum_point().coords.relative_eq(
+            &Vector3::new(-2.0, -1.0, 0.0),
+            0.0001,
+            0.0001
+        ));
+        assert!(bounding_box.maximum_point().coords.relative_eq(
+            &Vector3::new(2.0, 1.0, 1.0),
+            0.0001,
+            0.0001
+        ));
+    }
+
+    #[test]
+    fn test_orient_mesh_to_ground_puts_heavier_side_down() {
+        // An upside down pyramid with the apex below a wide base
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(2.0, 2.0, 0.0),
+            Point3::new(-2.0, 2.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+        ];
+        let faces = vec![
+            (0, 1, 2),
+            (0, 2, 3),
+            (1, 0, 4),
+            (2, 1, 4),
+            (3, 2, 4),
+            (0, 3, 4),
+        ];
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let oriented_mesh = orient_mesh_to_ground(&mesh);
+        let centroid_height = oriented_mesh
+            .vertices()
+            .iter()
+            .map(|vertex| vertex.z)
+            .sum::<f32>()
+            / oriented_mesh.vertices().len() as f32;
+
+        assert!(centroid_height < oriented_mesh.bounding_box().center().z);
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_at_single_face_fixes_flipped_face() {
+        let mesh = welded_tessellated_triangle_mesh();
+        let flipped = revert_mesh_faces_at(&mesh, 1, false).unwrap();
+        assert_ne!(mesh, flipped);
+
+        let fixed = revert_mesh_faces_at(&flipped, 1, false).unwrap();
+        assert_eq!(mesh, fixed);
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_at_connected_patch_reverts_consistent_faces() {
+        let mesh = welded_tessellated_triangle_mesh();
+        let reverted = revert_mesh_faces_at(&mesh, 0, true).unwrap();
+
+        assert_eq!(reverted, revert_mesh_faces(&mesh));
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_at_connected_patch_stops_at_inconsistent_edges() {
+        let mesh = welded_tessellated_triangle_mesh();
+        let flipped = revert_mesh_faces_at(&mesh, 1, false).unwrap();
+        let reverted = revert_mesh_faces_at(&flipped, 0, true).unwrap();
+
+        let expected_faces = vec![
+            Face::from(TriangleFace::new(3, 1, 0)),
+            Face::from(TriangleFace::new(3, 4, 1)),
+            Face::from(TriangleFace::new(1, 2, 4)),
+            Face::from(TriangleFace::new(3, 4, 5)),
+        ];
+
+        assert_eq!(reverted.faces(), expected_faces.as_slice());
+    }
+
+    #[test]
+    fn test_revert_mesh_faces_at_out_of_bounds_returns_none() {
+        let mesh = welded_tessellated_triangle_mesh();
+        assert!(revert_mesh_faces_at(&mesh, 4, true).is_none());
+    }
+
+    #[test]
+    fn test_synchronize_mesh_winding() {
+        let mesh = flipped_tessellated_triangle_with_island_mesh();
+        let mesh_with_synced_winding_expected = tessellated_triangle_with_island_mesh();
+
+        let e2f = topology::compute_edge_to_face_topology(&mesh);
+        let f2f = topology::compute_face_to_face_topology(&mesh, &e2f);
+        let mesh_with_synced_winding = synchronize_mesh_winding(&mesh, &f2f);
+
+        // Can't use Eq here, because the algorithm can produce faces
+        // in a different order than in the original
+        assert!(analysis::are_similar(
+            &mesh_with_synced_winding,
+            &mesh_with_synced_winding_expected,
+        ));
+    }
+
+    #[test]
+    fn test_synchronize_mesh_winding_for_sphere() {
+        let sphere = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            10,
+            10,
+            NormalStrategy::Sharp,
+        );
+        let sphere_faces_one_flipped =
+            sphere
+                .faces()
+                .iter()
+                .enumerate()
+                .map(|(i, f)| if i == 5 { f.to_reverted() } else { *f });
+
+        let sphere_with_faces_one_flipped = Mesh::from_faces_with_vertices_and_vertex_normals(
+            sphere_faces_one_flipped,
+            sphere.vertices().iter().copied(),
+            sphere.vertex_normals().clone(),
+        );
+
+        let e2f = topology::compute_edge_to_face_topology(&sphere_with_faces_one_flipped);
+        let f2f = topology::compute_face_to_face_topology(&sphere_with_faces_one_flipped, &e2f);
+
+        let sphere_with_synced_winding =
+            synchronize_mesh_winding(&sphere_with_faces_one_flipped, &f2f);
+
+        // Can't use Eq here, because the algorithm can produce faces
+        // in a different order than in the original
+        assert!(!analysis::are_similar(
+            &sphere,
+            &sphere_with_faces_one_flipped,
+        ));
+        assert!(analysis::are_similar(&sphere, &sphere_with_synced_winding,));
+    }
+
+    #[test]
+    fn test_weld_tessellated_triangle() {
+        let mesh = tessellated_triangle_mesh_for_welding();
+        let mesh_after_welding_correct = welded_tessellated_triangle_mesh();
+
+        let mesh_after_welding = weld(&mesh, 0.1).expect("Welding failed");
+
+        assert!(analysis::are_similar(
+            &mesh_after_welding_correct,
+            &mesh_after_welding
+        ));
+    }
+
+    #[test]
+    fn test_weld_averages_vertex_attributes() {
+        let mut mesh = tessellated_triangle_mesh_for_welding();
+        let values = mesh.vertices().iter().map(|v| v.x).collect();
+        mesh.set_vertex_attribute("x", values);
+
+        let mesh_after_welding = weld(&mesh, 0.1).expect("Welding failed");
+        let welded_values = mesh_after_welding
+            .vertex_attribute("x")
+            .expect("Attribute must be kept")
+            .values();
+
+        assert_eq!(welded_values.len(), mesh_after_welding.vertices().len());
+        for (value, vertex) in welded_values.iter().zip(mesh_after_welding.vertices()) {
+            assert!(approx::relative_eq!(*value, vertex.x, epsilon = 0.0001));
+        }
+    }
+
+    #[test]
+    fn test_weld_box_sharp_same_len() {
+        let mesh = open_box_sharp_mesh();
+        let mesh_after_welding_correct = welded_box_smooth_mesh();
+
+        let mesh_after_welding = weld(&mesh, 0.1).expect("Welding failed");
+
+        assert!(analysis::are_similar(
+            &mesh_after_welding_correct,
+            &mesh_after_welding
+        ));
+    }
+
+    #[test]
+    fn test_weld_borders_box_sharp_closes_box() {
+        let mesh = open_box_sharp_mesh();
+
+        let (mesh_after_welding, rejected_count) =
+            weld_borders(&mesh, 0.1).expect("Welding failed");
+
+        assert_eq!(rejected_count, 0);
+        assert_eq!(mesh_after_welding.vertices().len(), 8);
+        assert_eq!(mesh_after_welding.faces().len(), 12);
+        assert!(is_watertight_and_orientable(&mesh_after_welding));
+    }
+
+    #[test]
+    fn test_weld_borders_rejects_non_manifold_edges() {
+        // Three triangles meeting at the same edge
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2), (3, 4, 5), (6, 7, 8)],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.5, 1.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(0.5, -1.0, 0.0),
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.5, 0.0, 1.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let (mesh_after_welding, rejected_count) =
+            weld_borders(&mesh, 0.1).expect("Welding failed");
+
+        let oriented_edges: Vec<OrientedEdge> = mesh_after_welding.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        assert_eq!(rejected_count, 1);
+        assert_eq!(mesh_after_welding.faces().len(), 3);
+        assert_eq!(mesh_after_welding.vertices().len(), 6);
+        assert_eq!(analysis::non_manifold_edges(&edge_sharing).count(), 0);
+    }
+
+    #[test]
+    fn test_join_multiple_meshes_returns_tessellated_triangle_with_island() {
+        let multiple_meshes = vec![tessellated_triangle_mesh(), triangular_island_mesh()];
+
+        let mesh_correct = tessellated_triangle_with_island_mesh();
+        let mesh_computed = join_multiple_meshes(&multiple_meshes);
+
+        assert_eq!(&mesh_correct, &mesh_computed);
+    }
+
+    #[test]
+    fn test_join_multiple_meshes_keeps_common_vertex_attributes() {
+        let mut triangle = tessellated_triangle_mesh();
+        let mut island = triangular_island_mesh();
+        triangle.set_vertex_attribute("common", vec![1.0; triangle.vertices().len()]);
+        triangle.set_vertex_attribute("partial", vec![2.0; triangle.vertices().len()]);
+        island.set_vertex_attribute("common", vec![3.0; island.vertices().len()]);
+
+        let joined = join_multiple_meshes(&[triangle.clone(), island.clone()]);
+        let common_values = joined
+            .vertex_attribute("common")
+            .expect("Common attribute must be kept")
+            .values();
+
+        assert!(joined.vertex_attribute("partial").is_none());
+        assert_eq!(
+            &common_values[..triangle.vertices().len()],
+            &vec![1.0; triangle.vertices().len()][..],
+        );
+        assert_eq!(
+            &common_values[triangle.vertices().len()..],
+            &vec![3.0; island.vertices().len()][..],
+        );
+    }
+
+    #[test]
+    fn test_disjoint_mesh_keeps_vertex_attributes_of_patches() {
+        let mut mesh = tessellated_triangle_with_island_mesh();
+        let values = mesh.vertices().iter().map(|v| v.x + v.y).collect();
+        mesh.set_vertex_attribute("sum", values);
+
+        let computed_meshes = disjoint_mesh(&mesh);
+
+        assert_eq!(computed_meshes.len(), 2);
+        for patch in &computed_meshes {
+            let patch_values = patch
+                .vertex_attribute("sum")
+                .expect("Attribute must be kept")
+                .values();
+            assert_eq!(patch_values.len(), patch.vertices().len());
+            for (value, vertex) in patch_values.iter().zip(patch.vertices()) {
+                assert_eq!(*value, vertex.x + vertex.y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_assign_face_material_to_connected_patch_keeps_it_through_join_and_disjoint() {
+        let mesh = tessellated_triangle_with_island_mesh();
+        let island_face_index = mesh.faces().len() - 1;
+
+        let assigned = assign_face_material(&mesh, 2, Some(island_face_index), true)
+            .expect("Face must be in bounds");
+        let materials = assigned.face_materials().expect("Materials must be set");
+        assert_eq!(
+            materials.iter().filter(|material| **material == 2).count(),
+            1
+        );
+        assert_eq!(materials[island_face_index], 2);
+
+        let patches = disjoint_mesh(&assigned);
+        let mut patch_materials: Vec<Vec<u32>> = patches
+            .iter()
+            .map(|patch| {
+                patch
+                    .face_materials()
+                    .expect("Materials must be kept")
+                    .to_vec()
+            })
+            .collect();
+        patch_materials.sort();
+        assert_eq!(
+            patch_materials,
+            vec![vec![0; mesh.faces().len() - 1], vec![2]],
+        );
+
+        let joined = join_multiple_meshes(&[assigned, triangular_island_mesh()]);
+        let joined_materials = joined.face_materials().expect("Materials must be joined");
+        assert_eq!(joined_materials.len(), joined.faces().len());
+        assert_eq!(joined_materials[island_face_index], 2);
+        assert_eq!(joined_materials[joined.faces().len() - 1], 0);
+    }
+
+    #[test]
+    fn test_mirror_mesh_reflects_vertices_and_keeps_orientation() {
+        let mesh = tessellated_triangle_mesh();
+        let plane = Plane::from_origin_and_normal(
+            &Point3::new(1.0, 0.0, 0.0),
+            &Vector3::new(-1.0, 0.0, 0.0),
+        );
+
+        let mirrored = mirror_mesh(&mesh, &plane);
+
+        for (mirrored_vertex, vertex) in mirrored.vertices().iter().zip(mesh.vertices()) {
+            assert!(approx::relative_eq!(
+                *mirrored_vertex,
+                Point3::new(2.0 - vertex.x, vertex.y, vertex.z),
+                epsilon = 0.0001,
+            ));
+        }
+
+        for (mirrored_face, face) in mirrored.faces().iter().zip(mesh.faces()) {
+            assert_eq!(*mirrored_face, face.to_reverted());
+        }
+    }
+
+    #[test]
+    fn test_assign_face_material_out_of_bounds_returns_none() {
+        let mesh = tessellated_triangle_mesh();
+
+        assert!(assign_face_material(&mesh, 1, Some(mesh.faces().len()), false).is_none());
+    }
+
+    #[test]
+    fn test_transfer_vertex_attributes_interpolates_values_from_closest_faces() {
+        let mut source = welded_tessellated_triangle_mesh();
+        let source_values = source.vertices().iter().map(|v| v.x).collect();
+        source.set_vertex_attribute("x", source_values);
+
+        let target = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2)],
+            vec![
+                Point3::new(-1.0, -1.0, 1.0),
+                Point3::new(1.0, -1.0, 1.0),
+                Point3::new(0.0, 0.0, -1.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let transferred =
+            transfer_vertex_attributes(&source, &target).expect("Source mesh must not be empty");
+        let values = transferred
+            .vertex_attribute("x")
+            .expect("Attribute must be transferred")
+            .values();
+
+        assert_eq!(transferred.vertices(), target.vertices());
+        assert!(approx::relative_eq!(values[0], -1.0, epsilon = 0.0001));
+        assert!(approx::relative_eq!(values[1], 1.0, epsilon = 0.0001));
+        assert!(approx::relative_eq!(values[2], 0.0, epsilon = 0.0001));
+    }
+
+    #[test]
+    fn test_decimate_reduces_face_count() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            32,
+            32,
+            NormalStrategy::Smooth,
+        );
+        let face_count = mesh.faces().len();
+
+        let decimated = decimate(&mesh, 0.25).expect("Decimated mesh must not be empty");
+        assert!(decimated.faces().len() <= face_count / 4);
+        assert!(decimated.faces().len() > face_count / 16);
+
+        let full = decimate(&mesh, 1.0).expect("Decimated mesh must not be empty");
+        assert_eq!(full, mesh);
+    }
+
+    #[test]
+    fn test_decimate_keeps_bounds() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(2.0, 2.0, 2.0),
+            16,
+            16,
+            NormalStrategy::Smooth,
+        );
+
+        let decimated = decimate(&mesh, 0.5).expect("Decimated mesh must not be empty");
+        let original_diagonal = mesh.bounding_box().diagonal();
+        let decimated_diagonal = decimated.bounding_box().diagonal();
+        assert!((original_diagonal - decimated_diagonal).norm() < 1.0);
+    }
+
+    fn open_box() -> Mesh {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        // Leave out the top faces
+        let faces = mesh
+            .triangle_faces_iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 4 && *index != 5)
+            .map(|(index, face)| (face.vertices, mesh.corner_normals(index)));
+
+        Mesh::from_triangle_faces_with_vertices_and_corner_normals(faces, mesh.vertices().to_vec())
+    }
+
+    fn is_watertight_and_orientable(mesh: &Mesh) -> bool {
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+
+        analysis::is_mesh_watertight(&edge_sharing) && analysis::is_mesh_orientable(&edge_sharing)
+    }
+
+    #[test]
+    fn test_fill_holes_closes_open_box() {
+        let (filled, filled_hole_count) = fill_holes(&open_box(), 100, false);
+
+        assert_eq!(filled_hole_count, 1);
+        assert_eq!(filled.faces().len(), 12);
+        assert_eq!(filled.vertices().len(), 8);
+        assert!(is_watertight_and_orientable(&filled));
+    }
+
+    #[test]
+    fn test_fill_holes_skips_holes_larger_than_max() {
+        let mesh = open_box();
+        let (filled, filled_hole_count) = fill_holes(&mesh, 3, false);
+
+        assert_eq!(filled_hole_count, 0);
+        assert_eq!(filled.faces(), mesh.faces());
+    }
+
+    #[test]
+    fn test_fill_holes_smooth_closes_sphere_cap() {
+        let sphere = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            16,
+            16,
+            NormalStrategy::Smooth,
+        );
+        let (pole_index, _) = sphere
+            .vertices()
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.z.partial_cmp(&b.z).unwrap())
+            .unwrap();
+        let faces = sphere
+            .faces()
+            .iter()
+            .filter(|face| !face.contains_vertex(cast_u32(pole_index)))
+            .copied();
+        let mesh = Mesh::from_faces_with_vertices_and_vertex_normals(
+            faces,
+            sphere.vertices().to_vec(),
+            sphere.vertex_normals().clone(),
+        );
+
+        let (filled, filled_hole_count) = fill_holes(&mesh, 100, true);
+
+        assert_eq!(filled_hole_count, 1);
+        assert!(filled.vertices().len() > sphere.vertices().len());
+        assert!(is_watertight_and_orientable(&filled));
+        assert!(filled.bounding_box().maximum_point().z <= 1.0);
+    }
+
+    #[test]
+    fn test_cut_by_plane_with_cap_keeps_box_watertight() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let cut = cut_by_plane(&mesh, &Point3::new(0.0, 0.0, 0.2), &Vector3::z(), true)
+            .expect("Cut mesh must not be empty");
+
+        assert!(is_watertight_and_orientable(&cut));
+        let bounding_box = cut.bounding_box();
+        assert!((bounding_box.maximum_point().z - 0.2).abs() < 0.0001);
+        assert!((bounding_box.minimum_point().z + 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_cut_by_plane_without_cap_leaves_cut_open() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let cut = cut_by_plane(
+            &mesh,
+            &Point3::origin(),
+            &Vector3::new(1.0, 1.0, 0.0),
+            false,
+        )
+        .expect("Cut mesh must not be empty");
+
+        let oriented_edges: Vec<OrientedEdge> = cut.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        assert!(!analysis::is_mesh_watertight(&edge_sharing));
+        assert!(analysis::is_mesh_orientable(&edge_sharing));
+    }
+
+    #[test]
+    fn test_cut_by_plane_returns_none_when_everything_is_cut_away() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let cut = cut_by_plane(&mesh, &Point3::new(0.0, 0.0, -1.0), &Vector3::z(), true);
+
+        assert!(cut.is_none());
+    }
+
+    #[test]
+    fn test_crop_to_box_keeps_sphere_inside_box() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            16,
+            16,
+            NormalStrategy::Smooth,
+        );
+        let bounding_box =
+            BoundingBox::new(&Point3::new(-0.3, -0.3, -0.3), &Point3::new(0.3, 0.3, 2.0));
+
+        let cropped =
+            crop_to_box(&mesh, &bounding_box, true).expect("Cropped mesh must not be empty");
+
+        assert!(is_watertight_and_orientable(&cropped));
+        let cropped_bounding_box = cropped.bounding_box();
+        assert!(cropped_bounding_box.minimum_point().x >= -0.3001);
+        assert!(cropped_bounding_box.maximum_point().y <= 0.3001);
+        assert!(cropped_bounding_box.minimum_point().z >= -0.3001);
+    }
+
+    #[test]
+    fn test_remove_degenerate_faces_collapses_needles() {
+        let sphere = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            8,
+            8,
+            NormalStrategy::Smooth,
+        );
+        let face = sphere.triangle_face(sphere.faces().len() / 2);
+        let (moved_index, target_index, _) = face.vertices;
+        let mut vertices = sphere.vertices().to_vec();
+        let target = vertices[cast_usize(target_index)];
+        let direction = (vertices[cast_usize(moved_index)] - target).normalize();
+        vertices[cast_usize(moved_index)] = target + direction * 0.0001;
+        let mesh = Mesh::from_faces_with_vertices_and_vertex_normals(
+            sphere.faces().to_vec(),
+            vertices,
+            sphere.vertex_normals().clone(),
+        );
+
+        let (repaired, remaining_count) = remove_degenerate_faces(&mesh, 0.000_001, 20.0)
+            .expect("Failed to remove degenerate faces");
+
+        assert_eq!(remaining_count, 0);
+        assert_eq!(repaired.vertices().len(), sphere.vertices().len() - 1);
+        assert_eq!(repaired.faces().len(), sphere.faces().len() - 2);
+        assert!(is_watertight_and_orientable(&repaired));
+    }
+
+    #[test]
+    fn test_remove_degenerate_faces_flips_caps() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0001, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+        ];
+        let normals = vec![Vector3::z(), Vector3::z(), Vector3::z(), Vector3::z()];
+        let faces = vec![(0, 1, 2), (1, 0, 3)];
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(normals),
+        );
+
+        let (repaired, remaining_count) = remove_degenerate_faces(&mesh, 0.000_001, 10.0)
+            .expect("Failed to remove degenerate faces");
+
+        assert_eq!(remaining_count, 0);
+        assert_eq!(repaired.faces().len(), 2);
+        assert_eq!(repaired.vertices().len(), 4);
+        let oriented_edges: Vec<OrientedEdge> = repaired.oriented_edges_iter().collect();
+        assert!(analysis::is_mesh_orientable(&analysis::edge_sharing(
+            &oriented_edges
+        )));
+    }
+
+    #[test]
+    fn test_remove_degenerate_faces_returns_none_if_all_faces_are_degenerate() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.01, 0.0, 0.0),
+        ];
+        let normals = vec![Vector3::z(), Vector3::z(), Vector3::z()];
+        let faces = vec![(0, 1, 2)];
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            faces,
+            vertices,
+            VertexNormals::from_vertex_normals(normals),
+        );
+
+        assert!(remove_degenerate_faces(&mesh, 0.000_001, 20.0).is_none());
+    }
+
+    #[test]
+    fn test_recompute_normals_keeps_box_edges_sharp() {
+        let mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let recomputed = recompute_normals(&mesh, f32::consts::PI / 6.0);
+
+        assert!(recomputed.vertex_normals().has_overrides());
+        for (face_index, face) in recomputed.triangle_faces_iter().enumerate() {
+            let (v1, v2, v3) = face.vertices;
+            let face_normal = geometry::compute_triangle_normal(
+                &recomputed.vertices()[cast_usize(v1)],
+                &recomputed.vertices()[cast_usize(v2)],
+                &recomputed.vertices()[cast_usize(v3)],
+            );
+            for normal in &recomputed.corner_normals(face_index) {
+                assert!(normal.dot(&face_normal) > 0.9999);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recompute_normals_smooths_below_crease_angle() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+            16,
+            16,
+            NormalStrategy::Sharp,
+        );
+
+        let recomputed = recompute_normals(&mesh, f32::consts::PI / 3.0);
+
+        assert!(!recomputed.vertex_normals().has_overrides());
+        for (face_index, face) in recomputed.triangle_faces_iter().enumerate() {
+            let (v1, v2, v3) = face.vertices;
+            let [n1, n2, n3] = recomputed.corner_normals(face_index);
+            for (vertex_index, normal) in &[(v1, n1), (v2, n2), (v3, n3)] {
+                let direction = recomputed.vertices()[cast_usize(*vertex_index)]
+                    .coords
+                    .normalize();
+                assert!(direction.dot(normal) > 0.99);
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_duplicate_faces_joins_tetrahedra_sharing_face() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.2, 0.2, 1.0),
+            Point3::new(0.2, 0.2, -1.0),
+        ];
+        let faces = vec![
+            // Upper tetrahedron
+            (0, 2, 1),
+            (0, 1, 3),
+            (1, 2, 3),
+            (2, 0, 3),
+            // Lower tetrahedron
+            (0, 1, 2),
+            (1, 0, 4),
+            (2, 1, 4),
+            (0, 2, 4),
+        ];
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let duplicate_faces = analysis::find_duplicate_faces(&mesh);
+        let joined =
+            remove_duplicate_faces(&mesh, &duplicate_faces).expect("Joined mesh must not be empty");
+
+        assert_eq!(duplicate_faces.internal_face_indices.len(), 2);
+        assert_eq!(joined.faces().len(), 6);
+        assert!(is_watertight_and_orientable(&joined));
+    }
+}
@@ -0,0 +1,302 @@
+use smallvec::SmallVec;
+
+use crate::collections::HashMap;
+use crate::convert::{cast_u32, cast_usize};
+
+use super::{Face, Mesh, UnorientedEdge};
+
+// FIXME: Ideally, we'd also create a wrapper struct that casts the indices
+// to/from u32 as necessary.
+//
+// FIXME: @Optimization Analyze where this threshold is overly benevolent and
+// define different thresholds for different topologies.
+
+/// The number of relations/neighbors a `SmallVec` is allowed to
+/// contain before it spills into heap. Implementation detail.
+pub const MAX_INLINE_NEIGHBOR_COUNT: usize = 8;
+
+/// Computes topological relations of mesh vertex -> faces. A vertex is related
+/// to a face if and only if the face contains the respective vertex.
+///
+/// Output: The index represents a vertex index, the value is a list of faces
+/// containing the respective vertex.
+pub fn compute_vertex_to_face_topology(
+    mesh: &Mesh,
+) -> Vec<SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]>> {
+    compute_vertex_to_face_topology_from_components(mesh.faces(), cast_u32(mesh.vertices().len()))
+}
+
+/// Computes topological relations of mesh vertex -> faces from standalone mesh
+/// components: faces and vertex count. A vertex is related to a face if and
+/// only if the face contains the respective vertex.
+///
+/// Output: The index represents a vertex index, the value is a list of faces
+/// containing the respective vertex.
+pub fn compute_vertex_to_face_topology_from_components(
+    faces: &[Face],
+    vertex_count: u32,
+) -> Vec<SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]>> {
+    let mut v2f: Vec<SmallVec<[u32; 8]>> = vec![SmallVec::new(); cast_usize(vertex_count)];
+
+    for (face_index, face) in faces.iter().enumerate() {
+        let face_index_u32 = cast_u32(face_index);
+
+        for from_vertex in face.vertices() {
+            if !v2f[cast_usize(from_vertex)].contains(&face_index_u32) {
+                v2f[cast_usize(from_vertex)].push(face_index_u32);
+            }
+        }
+    }
+
+    v2f
+}
+
+/// Topological relations of mesh edge -> faces. The key is an unoriented
+/// edge, the value is a list of faces containing it, in the order of their
+/// indices. Border edges are contained in one face, manifold edges in two.
+pub type EdgeToFaceTopology = HashMap<UnorientedEdge, SmallVec<[u32; 2]>>;
+
+/// Computes topological relations of mesh edge -> faces. An edge is related to
+/// a face if and only if the face contains the respective unoriented edge.
+///
+/// The result can be computed once and reused for all the other topologies and
+/// operations that need to find faces by their edges.
+pub fn compute_edge_to_face_topology(mesh: &Mesh) -> EdgeToFaceTopology {
+    let mut e2f: EdgeToFaceTopology = HashMap::default();
+    e2f.reserve(mesh.faces().len() * 3 / 2);
+
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        let face_index_u32 = cast_u32(face_index);
+
+        for edge in face.to_unoriented_edges() {
+            let faces = e2f.entry(edge).or_insert_with(SmallVec::new);
+            // A face can only contain an edge twice if it is
+            // degenerate
+            if !faces.contains(&face_index_u32) {
+                faces.push(face_index_u32);
+            }
+        }
+    }
+
+    e2f
+}
+
+/// Computes topological relations (neighborhood) of mesh face -> faces. Two
+/// faces are neighbors if and only if they they share an unoriented edge.
+///
+/// The neighbors are found by looking up the edges of each face in `e2f`, which
+/// has to be computed for the same mesh, so the computation is linear in the
+/// number of faces, regardless of vertex valency.
+///
+/// Output: The index represents a face index, the value is a list of faces
+/// neighboring with the respective face.
+///
+/// # Panics
+/// Panics if `e2f` doesn't contain an edge of the mesh.
+pub fn compute_face_to_face_topology(
+    mesh: &Mesh,
+    e2f: &EdgeToFaceTopology,
+) -> Vec<SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]>> {
+    mesh.faces()
+        .iter()
+        .enumerate()
+        .map(|(face_index, face)| {
+            let face_index_u32 = cast_u32(face_index);
+            let edges = face.to_unoriented_edges();
+            let (first_edge, last_edge) = (edges[0], edges[edges.len() - 1]);
+
+            // The neighbors sharing the first vertex come first, ordered
+            // by their indices, then the neighbors across the other edges.
+            // Some operations, such as the winding synchronization, visit
+            // the faces in this order.
+            let mut neighbors: SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]> = e2f[&first_edge]
+                .iter()
+                .chain(&e2f[&last_edge])
+                .copied()
+                .filter(|neighbor| *neighbor != face_index_u32)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+
+            for edge in &edges[1..edges.len() - 1] {
+                for neighbor in &e2f[edge] {
+                    if *neighbor != face_index_u32 && !neighbors.contains(neighbor) {
+                        neighbors.push(*neighbor);
+                    }
+                }
+            }
+
+            neighbors
+        })
+        .collect()
+}
+
+/// Computes topological relations (connections) of mesh vertex -> vertices. Two
+/// vertices are neighbors if and only if they they are end points of an edge.
+///
+/// Output: The index represents a vertex index, the value is a list of vertices
+/// connected to the respective vertex.
+pub fn compute_vertex_to_vertex_topology(
+    mesh: &Mesh,
+) -> Vec<SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]>> {
+    let mut v2v = vec![SmallVec::new(); mesh.vertices().len()];
+
+    for face in mesh.faces() {
+        // The neighbors of a vertex are the next and the previous vertex
+        // of each face, never the opposite vertex of a quad
+        let vertex_indices = face.vertices();
+        let vertex_count = vertex_indices.len();
+        for i in 0..vertex_count {
+            let neighbor_candidate1 = vertex_indices[(i + 1) % vertex_count];
+            let neighbor_candidate2 = vertex_indices[(i + vertex_count - 1) % vertex_count];
+
+            let neighbor_vertices = &mut v2v[cast_usize(vertex_indices[i])];
+            if !neighbor_vertices.contains(&neighbor_candidate1) {
+                neighbor_vertices.push(neighbor_candidate1)
+            }
+            if !neighbor_vertices.contains(&neighbor_candidate2) {
+                neighbor_vertices.push(neighbor_candidate2)
+            }
+        }
+    }
+
+    v2v
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Point3, Rotation3, Vector3};
+    use smallvec::smallvec;
+
+    use crate::mesh::{primitive, NormalStrategy, OrientedEdge};
+
+    use super::*;
+
+    fn tessellated_triangle() -> (Vec<(u32, u32, u32)>, Vec<Point3<f32>>) {
+        let vertices = vec![
+            Point3::new(-2.0, -2.0, 0.0),
+            Point3::new(0.0, -2.0, 0.0),
+            Point3::new(2.0, -2.0, 0.0),
+            Point3::new(-1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+
+        let faces = vec![(0, 3, 1), (1, 3, 4), (1, 4, 2), (3, 5, 4)];
+
+        (faces, vertices)
+    }
+
+    // FIXME: make proptests on all topologies verifying that self is
+    // not included in neighbors, similar to what
+    // test_face_to_face_topology_does_not_include_self_in_neighbors
+    // does
+
+    #[test]
+    fn test_compute_face_to_face_topology_does_not_include_self_in_neighbors() {
+        let (faces, vertices) = tessellated_triangle();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+        let edge_to_face_topology = compute_edge_to_face_topology(&mesh);
+        let face_to_face_topology_computed =
+            compute_face_to_face_topology(&mesh, &edge_to_face_topology);
+
+        for (self_index, neighbor_indices) in face_to_face_topology_computed.iter().enumerate() {
+            assert!(!neighbor_indices.contains(&cast_u32(self_index)));
+        }
+    }
+
+    #[test]
+    fn test_compute_face_to_face_topology_from_tessellated_triangle() {
+        let (faces, vertices) = tessellated_triangle();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+        let face_to_face_topology_correct: Vec<SmallVec<[u32; MAX_INLINE_NEIGHBOR_COUNT]>> =
+            vec![smallvec![1], smallvec![0, 2, 3], smallvec![1], smallvec![1]];
+
+        let edge_to_face_topology = compute_edge_to_face_topology(&mesh);
+        let face_to_face_topology_computed =
+            compute_face_to_face_topology(&mesh, &edge_to_face_topology);
+
+        assert_eq!(
+            face_to_face_topology_computed,
+            face_to_face_topology_correct,
+        );
+    }
+
+    #[test]
+    fn test_compute_edge_to_face_topology_from_tessellated_triangle() {
+        let (faces, vertices) = tessellated_triangle();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let edge_to_face_topology = compute_edge_to_face_topology(&mesh);
+
+        assert_eq!(edge_to_face_topology.len(), 9);
+        assert_eq!(
+            edge_to_face_topology[&UnorientedEdge(OrientedEdge::new(0, 3))].as_slice(),
+            &[0],
+        );
+        assert_eq!(
+            edge_to_face_topology[&UnorientedEdge(OrientedEdge::new(1, 3))].as_slice(),
+            &[0, 1],
+        );
+        assert_eq!(
+            edge_to_face_topology[&UnorientedEdge(OrientedEdge::new(4, 3))].as_slice(),
+            &[1, 3],
+        );
+    }
+
+    #[test]
+    fn test_compute_face_to_face_topology_for_uv_sphere_has_three_neighbors_per_face() {
+        let mesh = primitive::create_uv_sphere(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+            8,
+            64,
+            NormalStrategy::Sharp,
+        );
+
+        let edge_to_face_topology = compute_edge_to_face_topology(&mesh);
+        let face_to_face_topology_computed =
+            compute_face_to_face_topology(&mesh, &edge_to_face_topology);
+
+        for neighbor_indices in &face_to_face_topology_computed {
+            assert_eq!(neighbor_indices.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_compute_vertex_to_vertex_topology_from_tessellated_triangle() {
+        let (faces, vertices) = tessellated_triangle();
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            faces.clone(),
+            vertices.clone(),
+            NormalStrategy::Sharp,
+        );
+
+        let vertex_to_vertex_topology_computed = compute_vertex_to_vertex_topology(&mesh);
+
+        let two_neighbors_count = vertex_to_vertex_topology_computed
+            .iter()
+            .filter(|to| to.len() == 2)
+            .count();
+        let four_neighbors_count = vertex_to_vertex_topology_computed
+            .iter()
+            .filter(|to| to.len() == 4)
+            .count();
+
+        assert_eq!(two_neighbors_count, 3);
+        assert_eq!(four_neighbors_count, 3);
+    }
+}
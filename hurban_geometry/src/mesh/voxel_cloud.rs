@@ -1,13 +1,18 @@
-use std::collections::VecDeque;
-
 use nalgebra::{Point3, Vector2, Vector3};
+use rayon::prelude::*;
 
 use crate::bounding_box::BoundingBox;
+use crate::collections::{HashMap, HashSet};
 use crate::convert::{cast_i32, cast_u32, cast_usize, clamp_cast_i32_to_u32};
 use crate::geometry;
 use crate::plane::Plane;
 
-use super::{primitive, tools, Face, Mesh};
+use super::{primitive, tools, Mesh, NormalStrategy};
+
+/// The minimum number of voxels (or faces) processed by a single task
+/// of the parallel voxel cloud operations. Smaller chunks don't pay off
+/// the cost of scheduling them.
+const PARALLEL_CHUNK_LEN: usize = 4096;
 
 /// Voxel cloud is an abstract representation of points in a block of
 /// space. The block is delimited by its beginning and its dimensions, both in
@@ -97,9 +102,20 @@ impl VoxelCloud {
             .x
             .min(voxel_dimensions.y.min(voxel_dimensions.z));
 
-        for face in mesh.faces() {
-            match face {
-                Face::Triangle(f) => {
+        // The faces are rasterized in parallel, each task collecting the
+        // distinct indices of the voxels its points fall into. Neighboring
+        // points mostly fall into the same voxels, so the sets stay much
+        // smaller than the number of points. Turning the voxels on
+        // afterwards is order-independent, so the result matches the
+        // sequential rasterization.
+        let block_start = voxel_cloud.block_start;
+        let block_dimensions = voxel_cloud.block_dimensions;
+        let occupied_voxel_indices = mesh
+            .faces()
+            .par_iter()
+            .with_min_len(PARALLEL_CHUNK_LEN)
+            .fold(HashSet::default, |mut occupied_voxel_indices, face| {
+                for f in face.to_triangles() {
                     let point_a = &mesh.vertices()[cast_usize(f.vertices.0)];
                     let point_b = &mesh.vertices()[cast_usize(f.vertices.1)];
                     let point_c = &mesh.vertices()[cast_usize(f.vertices.2)];
@@ -129,13 +145,40 @@ impl VoxelCloud {
                                     &point_b,
                                     &point_c,
                                 );
-                                // and set a voxel containing the point to be on
-                                voxel_cloud.set_voxel_at_cartesian_coords(&cartesian, true);
+                                // and remember the voxel containing the point
+                                let voxel_coords = cartesian_to_absolute_voxel_coords(
+                                    &cartesian,
+                                    voxel_dimensions,
+                                );
+                                let index =
+                                    absolute_three_dimensional_coordinate_to_one_dimensional(
+                                        &voxel_coords,
+                                        &block_start,
+                                        &block_dimensions,
+                                    )
+                                    .expect("Coordinates out of bounds");
+                                occupied_voxel_indices.insert(index);
                             }
                         }
                     }
                 }
-            }
+
+                occupied_voxel_indices
+            })
+            .reduce(HashSet::default, |occupied_voxel_indices, other| {
+                // Merge the smaller set into the larger one
+                let (mut larger, smaller) = if occupied_voxel_indices.len() >= other.len() {
+                    (occupied_voxel_indices, other)
+                } else {
+                    (other, occupied_voxel_indices)
+                };
+                larger.extend(smaller);
+                larger
+            });
+
+        // Set the voxels containing the points to be on
+        for index in occupied_voxel_indices {
+            voxel_cloud.voxel_map[index] = true;
         }
 
         voxel_cloud
@@ -158,13 +201,11 @@ impl VoxelCloud {
     }
 
     /// Returns single voxel dimensions in model space units.
-    #[allow(dead_code)]
     pub fn voxel_dimensions(&self) -> Vector3<f32> {
         self.voxel_dimensions
     }
 
     /// Checks if the voxel cloud contains any voxel / volume
-    #[allow(dead_code)]
     pub fn contains_voxels(&self) -> bool {
         self.voxel_map.iter().any(|v| *v)
     }
@@ -190,56 +231,59 @@ impl VoxelCloud {
         let grown_block_start = self.block_start - Vector3::new(1, 1, 1);
         let grown_block_dimensions = self.block_dimensions + Vector3::new(2, 2, 2);
 
-        let original_voxel_map = self.voxel_map.clone();
-        let original_block_start = self.block_start;
-        let original_block_dimensions = self.block_dimensions;
-
-        self.block_start = grown_block_start;
-        self.block_dimensions = grown_block_dimensions;
-
         let grown_voxel_map_len = cast_usize(
             grown_block_dimensions.x * grown_block_dimensions.y * grown_block_dimensions.z,
         );
 
-        // The original voxel map is shorter, therefore wipe it before resizing.
-        self.fill_with(false);
+        // A grown voxel is on, if it or any of its neighbors was on in the
+        // original voxel cloud. Looking at the neighbors of each grown voxel,
+        // instead of turning on the neighbors of each original voxel, lets
+        // the grown voxels be computed independently in parallel.
+        let original_voxel_map = &self.voxel_map;
+        let original_block_start = self.block_start;
+        let original_block_dimensions = self.block_dimensions;
 
-        self.voxel_map.resize(grown_voxel_map_len, false);
+        let mut grown_voxel_map = vec![false; grown_voxel_map_len];
+        grown_voxel_map
+            .par_iter_mut()
+            .enumerate()
+            .with_min_len(PARALLEL_CHUNK_LEN)
+            .for_each(|(grown_index, grown_voxel)| {
+                let absolute_coords = one_dimensional_to_absolute_three_dimensional_coordinate(
+                    grown_index,
+                    &grown_block_start,
+                    &grown_block_dimensions,
+                )
+                .expect("Index out of bounds");
 
-        for grown_index in 0..self.voxel_map.len() {
-            let absolute_coords = one_dimensional_to_absolute_three_dimensional_coordinate(
-                grown_index,
-                &grown_block_start,
-                &grown_block_dimensions,
-            )
-            .expect("Index out of bounds");
-
-            if let Some(original_index) = absolute_three_dimensional_coordinate_to_one_dimensional(
-                &absolute_coords,
-                &original_block_start,
-                &original_block_dimensions,
-            ) {
-                if original_voxel_map[original_index] {
-                    // set self an also its neighbors to be on
-                    for neighbor_offset in &neighbor_offsets {
-                        self.set_voxel_at_absolute_coords(
-                            &(absolute_coords + neighbor_offset),
-                            true,
-                        );
-                    }
-                }
-            }
-        }
+                *grown_voxel = neighbor_offsets.iter().any(|neighbor_offset| {
+                    absolute_three_dimensional_coordinate_to_one_dimensional(
+                        &(absolute_coords + neighbor_offset),
+                        &original_block_start,
+                        &original_block_dimensions,
+                    )
+                    .map_or(false, |original_index| original_voxel_map[original_index])
+                });
+            });
+
+        self.block_start = grown_block_start;
+        self.block_dimensions = grown_block_dimensions;
+        self.voxel_map = grown_voxel_map;
     }
 
     /// Computes boolean intersection (logical AND operation) of the current and
     /// another Voxel cloud. The current Voxel cloud will be mutated and resized
     /// to the size and position of an intersection of the two Voxel clouds'
     /// volumes.
+    ///
+    /// The other Voxel cloud may have different voxel dimensions, in which
+    /// case it is resampled to the voxels of the current Voxel cloud.
     pub fn boolean_intersection(&mut self, other: &VoxelCloud) {
         // Find volume common to both voxel clouds.
         if let Some(self_volume_bounding_box) = self.volume_bounding_box() {
-            if let Some(other_volume_bounding_box) = other.volume_bounding_box() {
+            if let Some(other_volume_bounding_box) =
+                other.volume_bounding_box_in_grid(&self.voxel_dimensions)
+            {
                 if let Some(bounding_box) = BoundingBox::intersection(
                     [self_volume_bounding_box, other_volume_bounding_box]
                         .iter()
@@ -290,11 +334,17 @@ impl VoxelCloud {
     /// current Voxel cloud will be mutated and resized to contain both input
     /// Voxel clouds' volumes.
     ///
+    /// The other Voxel cloud may have different voxel dimensions, in which
+    /// case it is resampled to the voxels of the current Voxel cloud.
+    ///
     /// # Warning
     /// If the input Voxel clouds are far apart, the resulting voxel cloud may
     /// be huge.
     pub fn boolean_union(&mut self, other: &VoxelCloud) {
-        let bounding_boxes = [self.volume_bounding_box(), other.volume_bounding_box()];
+        let bounding_boxes = [
+            self.volume_bounding_box(),
+            other.volume_bounding_box_in_grid(&self.voxel_dimensions),
+        ];
 
         let valid_bounding_boxes_iter = bounding_boxes.iter().filter_map(|b| *b);
         if let Some(bounding_box) = BoundingBox::union(valid_bounding_boxes_iter) {
@@ -337,6 +387,9 @@ impl VoxelCloud {
     /// Voxel cloud. The current Voxel cloud will be modified so that voxels,
     /// that are on in both Voxel clouds will be turned off, while the rest
     /// remains intact.
+    ///
+    /// The other Voxel cloud may have different voxel dimensions, in which
+    /// case it is resampled to the voxels of the current Voxel cloud.
     pub fn boolean_difference(&mut self, other: &VoxelCloud) {
         // Iterate through the target voxel cloud
         for i in 0..self.voxel_map.len() {
@@ -389,7 +442,6 @@ impl VoxelCloud {
 
     /// Sets the state of a voxel defined in voxel coordinates relative to the
     /// voxel block start.
-    #[allow(dead_code)]
     pub fn set_voxel_at_relative_coords(&mut self, relative_coords: &Point3<i32>, state: bool) {
         let index = relative_three_dimensional_coordinate_to_one_dimensional(
             relative_coords,
@@ -652,9 +704,138 @@ impl VoxelCloud {
         tools::weld(&joined_voxel_mesh, (min_voxel_dimension as f32) / 4.0)
     }
 
+    /// Computes a smooth triangulated welded mesh enclosing the volume
+    /// voxels of the current state of the voxel cloud.
+    ///
+    /// The mesh is extracted with naive surface nets, a dual method similar
+    /// to marching cubes: there is one mesh vertex for each cell between 8
+    /// neighboring voxel centers on the boundary of the volume, placed in
+    /// the average of the cell's crossing points, and one quad for each pair
+    /// of neighboring volume and void voxels. Like `to_mesh`, voxels
+    /// touching only diagonally may produce non-manifold geometry.
+    pub fn to_smooth_mesh(&self) -> Option<Mesh> {
+        if !self.contains_voxels() {
+            return None;
+        }
+
+        let axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+        let block_start = self.block_start;
+        let block_end = self.block_end();
+
+        let mut vertices: Vec<Point3<f32>> = Vec::new();
+        let mut cell_vertex_indices: HashMap<Point3<i32>, u32> = HashMap::default();
+        let mut faces: Vec<(u32, u32, u32)> = Vec::new();
+
+        // Voxels just outside the block are void, therefore the iteration
+        // starts one voxel before the block to find its boundary there.
+        for x in block_start.x - 1..=block_end.x {
+            for y in block_start.y - 1..=block_end.y {
+                for z in block_start.z - 1..=block_end.z {
+                    let voxel = Point3::new(x, y, z);
+                    let voxel_state = self.voxel_state_at_absolute_coords(&voxel);
+
+                    for (axis_index, axis) in axes.iter().enumerate() {
+                        let neighbor_state = self.voxel_state_at_absolute_coords(&(voxel + axis));
+                        if voxel_state == neighbor_state {
+                            continue;
+                        }
+
+                        // The 4 cells around the edge between the voxels,
+                        // counter-clockwise when looking against the axis
+                        let u = axes[(axis_index + 1) % 3];
+                        let v = axes[(axis_index + 2) % 3];
+                        let cells = [voxel - u - v, voxel - v, voxel, voxel - u];
+
+                        let mut quad = [0; 4];
+                        for (quad_vertex, cell) in quad.iter_mut().zip(cells.iter()) {
+                            *quad_vertex = *cell_vertex_indices.entry(*cell).or_insert_with(|| {
+                                vertices.push(self.surface_nets_cell_vertex(cell));
+                                cast_u32(vertices.len() - 1)
+                            });
+                        }
+
+                        // The quad faces along the axis, which has to point
+                        // from the volume to the void.
+                        if voxel_state {
+                            faces.push((quad[0], quad[1], quad[2]));
+                            faces.push((quad[0], quad[2], quad[3]));
+                        } else {
+                            faces.push((quad[2], quad[1], quad[0]));
+                            faces.push((quad[3], quad[2], quad[0]));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(
+            Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+                faces,
+                vertices,
+                NormalStrategy::Smooth,
+            ),
+        )
+    }
+
+    /// Returns whether there is a volume voxel at the absolute coordinates,
+    /// treating voxels outside of the block as void.
+    fn voxel_state_at_absolute_coords(&self, absolute_coords: &Point3<i32>) -> bool {
+        self.voxel_at_absolute_coords(absolute_coords)
+            .unwrap_or(false)
+    }
+
+    /// Computes the surface nets vertex of the cell spanning between the
+    /// centers of voxels from `cell_start` to `cell_start + (1, 1, 1)`. The
+    /// vertex is in the average of the midpoints of the cell edges connecting
+    /// a volume and a void voxel.
+    fn surface_nets_cell_vertex(&self, cell_start: &Point3<i32>) -> Point3<f32> {
+        let mut crossing_sum = Vector3::zeros();
+        let mut crossing_count = 0;
+
+        for corner_index in 0..8 {
+            let corner = Vector3::new(corner_index & 1, (corner_index >> 1) & 1, corner_index >> 2);
+            let corner_state = self.voxel_state_at_absolute_coords(&(cell_start + corner));
+
+            // Visit each edge once, from its corner with the lower
+            // coordinate along the edge axis
+            for axis_index in 0..3 {
+                if corner[axis_index] == 1 {
+                    continue;
+                }
+
+                let mut other_corner = corner;
+                other_corner[axis_index] = 1;
+                let other_corner_state =
+                    self.voxel_state_at_absolute_coords(&(cell_start + other_corner));
+
+                if corner_state != other_corner_state {
+                    let edge_sum = corner + other_corner;
+                    let midpoint = Vector3::new(
+                        edge_sum.x as f32 / 2.0,
+                        edge_sum.y as f32 / 2.0,
+                        edge_sum.z as f32 / 2.0,
+                    );
+                    crossing_sum += midpoint;
+                    crossing_count += 1;
+                }
+            }
+        }
+
+        assert!(
+            crossing_count > 0,
+            "Surface nets vertex requested for a cell not on the boundary"
+        );
+        let position = Vector3::new(
+            cell_start.x as f32,
+            cell_start.y as f32,
+            cell_start.z as f32,
+        ) + crossing_sum / crossing_count as f32;
+
+        Point3::from(position.component_mul(&self.voxel_dimensions))
+    }
+
     /// Returns the bounding box of this voxel cloud in world space cartesian
     /// units.
-    #[allow(dead_code)]
     pub fn bounding_box_cartesian(&self) -> BoundingBox<f32> {
         let voxel_dimensions = self.voxel_dimensions;
         let block_start = self.block_start;
@@ -674,14 +855,12 @@ impl VoxelCloud {
     }
 
     /// Returns the bounding box of this voxel cloud in voxel units.
-    #[allow(dead_code)]
     pub fn bounding_box(&self) -> BoundingBox<i32> {
         BoundingBox::new(&self.block_start, &self.block_end())
     }
 
     /// Returns the bounding box of the mesh produced by `VoxelCloud::to_mesh`
     /// for this voxel cloud in world space cartesian units.
-    #[allow(dead_code)]
     pub fn mesh_bounding_box_cartesian(&self) -> Option<BoundingBox<f32>> {
         let voxel_dimensions = self.voxel_dimensions;
         self.compute_volume_boundaries()
@@ -706,7 +885,6 @@ impl VoxelCloud {
 
     /// Returns the bounding box in world space cartesian units of the current
     /// voxel cloud after shrinking to fit just the nonempty voxels.
-    #[allow(dead_code)]
     pub fn volume_bounding_box_cartesian(&self) -> Option<BoundingBox<f32>> {
         let voxel_dimensions = self.voxel_dimensions;
         self.compute_volume_boundaries()
@@ -741,6 +919,41 @@ impl VoxelCloud {
             })
     }
 
+    /// Returns the bounding box of the nonempty voxels in the voxel units of
+    /// another grid with (possibly) different voxel dimensions. The bounding
+    /// box contains all voxels of the other grid, whose centers lie in the
+    /// nonempty voxels of this voxel cloud. For the same voxel dimensions,
+    /// this is the same as `volume_bounding_box`.
+    fn volume_bounding_box_in_grid(
+        &self,
+        voxel_dimensions: &Vector3<f32>,
+    ) -> Option<BoundingBox<i32>> {
+        // Voxel at absolute coordinate `i` spans from `i - 0.5` to
+        // `i + 0.5` voxels, so the other grid's voxel `j` has its
+        // center in this voxel cloud's volume for `start - 0.5 <= j *
+        // ratio < end - 0.5`.
+        let ratio = self.voxel_dimensions.component_div(voxel_dimensions);
+        let to_grid = |coord: i32, ratio: f32| ((coord as f32 - 0.5) * ratio).ceil() as i32;
+
+        self.volume_bounding_box().map(|bounding_box| {
+            let start = bounding_box.minimum_point();
+            let end = bounding_box.maximum_point();
+
+            BoundingBox::new(
+                &Point3::new(
+                    to_grid(start.x, ratio.x),
+                    to_grid(start.y, ratio.y),
+                    to_grid(start.z, ratio.z),
+                ),
+                &Point3::new(
+                    to_grid(end.x, ratio.x),
+                    to_grid(end.y, ratio.y),
+                    to_grid(end.z, ratio.z),
+                ),
+            )
+        })
+    }
+
     /// Fill hollow volumes in voxel cloud. The original voxel cloud will be
     /// mutated. The method flood-fills the outer space with void voxels,
     /// leaving everything inside volume voxels filled.
@@ -759,66 +972,91 @@ impl VoxelCloud {
             Vector3::new(0, 0, 1),
         ];
 
-        // Contains indices into the voxel map
-        let mut queue_to_process: VecDeque<usize> = VecDeque::new();
-        // Matches the voxel map length
-        let mut discovered = vec![false; self.voxel_map.len()];
+        let block_dimensions = self.block_dimensions;
 
         // Scan for void voxels at the boundaries of the voxel cloud. For
         // optimization and readability reasons this scans the entire voxel
         // cloud and filters out coordinates inside the voxel cloud block.
-        for (one_dimensional, voxel) in self.voxel_map.iter().enumerate() {
-            let coord = one_dimensional_to_relative_three_dimensional_coordinate(
-                one_dimensional,
-                &self.block_dimensions,
-            )
-            .expect("Coord out of bounds");
-            // If any of these is true, the coordinate is at the boundary of the
-            // voxel cloud block
-            if coord.x == 0
-                || coord.y == 0
-                || coord.z == 0
-                || coord.x == cast_i32(self.block_dimensions.x) - 1
-                || coord.y == cast_i32(self.block_dimensions.y) - 1
-                || coord.z == cast_i32(self.block_dimensions.z) - 1
-            {
-                // If the voxel is void and hasn't been discovered yet
-                if !voxel && !discovered[one_dimensional] {
-                    // put it into the processing queue
-                    queue_to_process.push_back(one_dimensional);
-                    // and mark it discovered.
-                    discovered[one_dimensional] = true;
-                }
-            }
+        let mut frontier: Vec<usize> = self
+            .voxel_map
+            .par_iter()
+            .enumerate()
+            .with_min_len(PARALLEL_CHUNK_LEN)
+            .filter(|(one_dimensional, voxel)| {
+                let coord = one_dimensional_to_relative_three_dimensional_coordinate(
+                    *one_dimensional,
+                    &block_dimensions,
+                )
+                .expect("Coord out of bounds");
+                // If any of these is true, the coordinate is at the boundary of
+                // the voxel cloud block
+                let at_boundary = coord.x == 0
+                    || coord.y == 0
+                    || coord.z == 0
+                    || coord.x == cast_i32(block_dimensions.x) - 1
+                    || coord.y == cast_i32(block_dimensions.y) - 1
+                    || coord.z == cast_i32(block_dimensions.z) - 1;
+
+                at_boundary && !**voxel
+            })
+            .map(|(one_dimensional, _)| one_dimensional)
+            .collect();
+
+        // Matches the voxel map length
+        let mut discovered = vec![false; self.voxel_map.len()];
+        for one_dimensional in &frontier {
+            discovered[*one_dimensional] = true;
         }
 
-        // Process the queue
-        while let Some(one_dimensional) = queue_to_process.pop_front() {
-            // Calculate the relative coord of the currently processed voxel.
-            // Will be needed to calculate its neighbors.
-            let coord = one_dimensional_to_relative_three_dimensional_coordinate(
-                one_dimensional,
-                &self.block_dimensions,
-            )
-            .expect("Coord out of bounds");
-            // Check all the neighbors
-            for neighbor_offset in &neighbor_offsets {
-                let neighbor_coord = coord + neighbor_offset;
-                // If the neighbor exists (is not out of bounds)
-                if let Some(false) = self.voxel_at_relative_coords(&neighbor_coord) {
-                    let neighbor_one_dimensional =
-                        relative_three_dimensional_coordinate_to_one_dimensional(
-                            &neighbor_coord,
-                            &self.block_dimensions,
-                        )
-                        .expect("Coord out of bounds");
-                    // Check if it is void and hasn't been discovered yet
-                    if !discovered[neighbor_one_dimensional] {
-                        // Put it to the processing queue
-                        queue_to_process.push_back(neighbor_one_dimensional);
-                        // and mark it discovered.
-                        discovered[neighbor_one_dimensional] = true;
+        // Flood fill the void one layer at a time. The void neighbors of the
+        // current layer are found in parallel, then the undiscovered ones
+        // become the next layer. The discovered voxels are the same as if
+        // the voxels were discovered one by one, only in a different order.
+        while !frontier.is_empty() {
+            let neighbors = frontier
+                .par_iter()
+                .with_min_len(PARALLEL_CHUNK_LEN)
+                .fold(Vec::new, |mut neighbors, one_dimensional| {
+                    // Calculate the relative coord of the currently processed
+                    // voxel. Will be needed to calculate its neighbors.
+                    let coord = one_dimensional_to_relative_three_dimensional_coordinate(
+                        *one_dimensional,
+                        &block_dimensions,
+                    )
+                    .expect("Coord out of bounds");
+                    // Check all the neighbors
+                    for neighbor_offset in &neighbor_offsets {
+                        let neighbor_coord = coord + neighbor_offset;
+                        // If the neighbor exists (is not out of bounds) and is
+                        // void
+                        if let Some(false) = self.voxel_at_relative_coords(&neighbor_coord) {
+                            let neighbor_one_dimensional =
+                                relative_three_dimensional_coordinate_to_one_dimensional(
+                                    &neighbor_coord,
+                                    &block_dimensions,
+                                )
+                                .expect("Coord out of bounds");
+                            // and hasn't been discovered yet
+                            if !discovered[neighbor_one_dimensional] {
+                                neighbors.push(neighbor_one_dimensional);
+                            }
+                        }
                     }
+
+                    neighbors
+                })
+                .reduce(Vec::new, |mut neighbors, other| {
+                    neighbors.extend(other);
+                    neighbors
+                });
+
+            // Multiple voxels of the layer can share a neighbor, so only keep
+            // the first occurrence and mark it discovered.
+            frontier.clear();
+            for neighbor_one_dimensional in neighbors {
+                if !discovered[neighbor_one_dimensional] {
+                    discovered[neighbor_one_dimensional] = true;
+                    frontier.push(neighbor_one_dimensional);
                 }
             }
         }
@@ -826,9 +1064,11 @@ impl VoxelCloud {
         // All the discovered voxels were empty and connected with the voxel
         // cloud boundaries. Therefore they are part of the outer void space and
         // everything else is a filled volume.
-        for (i, v) in self.voxel_map.iter_mut().enumerate() {
-            *v = !discovered[i];
-        }
+        self.voxel_map
+            .par_iter_mut()
+            .zip(discovered.par_iter())
+            .with_min_len(PARALLEL_CHUNK_LEN)
+            .for_each(|(voxel, discovered)| *voxel = !discovered);
     }
 
     /// Computes boundaries of volumes contained in voxel cloud. Returns tuple
@@ -1261,6 +1501,81 @@ mod tests {
         assert_eq!(vc_a, vc_correct);
     }
 
+    #[test]
+    fn test_voxel_cloud_boolean_intersection_with_different_voxel_dimensions() {
+        let mut vc_a = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(6, 2, 2),
+            &Vector3::new(0.5, 0.5, 0.5),
+        );
+        let mut vc_b = VoxelCloud::new(
+            &Point3::new(2, 0, 0),
+            &Vector3::new(1, 1, 1),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        vc_a.fill_with(true);
+        vc_b.fill_with(true);
+
+        vc_a.boolean_intersection(&vc_b);
+
+        assert_eq!(
+            vc_a.volume_bounding_box(),
+            Some(BoundingBox::new(
+                &Point3::new(3, 0, 0),
+                &Point3::new(5, 1, 1)
+            ))
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(3, 0, 0)),
+            Some(true)
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(4, 0, 0)),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_voxel_cloud_boolean_union_with_different_voxel_dimensions() {
+        let mut vc_a = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(2, 2, 2),
+            &Vector3::new(0.5, 0.5, 0.5),
+        );
+        let mut vc_b = VoxelCloud::new(
+            &Point3::new(2, 0, 0),
+            &Vector3::new(1, 1, 1),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        vc_a.fill_with(true);
+        vc_b.fill_with(true);
+
+        vc_a.boolean_union(&vc_b);
+
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(0, 0, 0)),
+            Some(true)
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(2, 0, 0)),
+            Some(false)
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(3, 0, 0)),
+            Some(true)
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(4, 0, 0)),
+            Some(true)
+        );
+        assert_eq!(
+            vc_a.voxel_at_absolute_coords(&Point3::new(4, 1, 0)),
+            Some(false)
+        );
+    }
+
     #[test]
     fn test_voxel_cloud_get_set_for_single_voxel() {
         let mut voxel_cloud = VoxelCloud::new(
@@ -1290,13 +1605,71 @@ mod tests {
 
         let voxel_mesh = voxel_cloud.to_mesh().unwrap();
 
-        let v2f = topology::compute_vertex_to_face_topology(&voxel_mesh);
-        let f2f = topology::compute_face_to_face_topology(&voxel_mesh, &v2f);
+        let e2f = topology::compute_edge_to_face_topology(&voxel_mesh);
+        let f2f = topology::compute_face_to_face_topology(&voxel_mesh, &e2f);
         let voxel_mesh_synced = tools::synchronize_mesh_winding(&voxel_mesh, &f2f);
 
         assert!(analysis::are_similar(&voxel_mesh, &voxel_mesh_synced));
     }
 
+    #[test]
+    fn test_voxel_cloud_single_voxel_to_smooth_mesh_produces_closed_outward_mesh() {
+        let mut voxel_cloud = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(1, 1, 1),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+        voxel_cloud.set_voxel_at_relative_coords(&Point3::new(0, 0, 0), true);
+
+        let voxel_mesh = voxel_cloud.to_smooth_mesh().unwrap();
+        let oriented_edges: Vec<_> = voxel_mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+
+        assert_eq!(voxel_mesh.vertices().len(), 8);
+        assert_eq!(voxel_mesh.faces().len(), 12);
+        assert!(analysis::is_mesh_watertight(&edge_sharing));
+        assert!(analysis::signed_volume(&voxel_mesh) > 0.0);
+    }
+
+    #[test]
+    fn test_voxel_cloud_to_smooth_mesh_fits_in_voxel_mesh_bounds() {
+        let mut voxel_cloud = VoxelCloud::new(
+            &Point3::new(-2, 0, 1),
+            &Vector3::new(3, 2, 2),
+            &Vector3::new(0.5, 1.0, 2.0),
+        );
+        voxel_cloud.fill_with(true);
+
+        let voxel_mesh = voxel_cloud.to_mesh().unwrap();
+        let smooth_mesh = voxel_cloud.to_smooth_mesh().unwrap();
+        let oriented_edges: Vec<_> = smooth_mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+
+        let voxel_bounding_box = voxel_mesh.bounding_box();
+        let smooth_bounding_box = smooth_mesh.bounding_box();
+        assert!(analysis::is_mesh_watertight(&edge_sharing));
+        assert!(analysis::signed_volume(&smooth_mesh) > 0.0);
+        assert_eq!(
+            smooth_bounding_box.minimum_point(),
+            voxel_bounding_box.minimum_point()
+        );
+        assert_eq!(
+            smooth_bounding_box.maximum_point(),
+            voxel_bounding_box.maximum_point()
+        );
+    }
+
+    #[test]
+    fn test_voxel_cloud_empty_to_smooth_mesh_returns_none() {
+        let voxel_cloud = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(2, 2, 2),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        assert!(voxel_cloud.to_smooth_mesh().is_none());
+    }
+
     #[test]
     fn test_voxel_cloud_resize_zero_to_nonzero_all_false() {
         let mut voxel_cloud = VoxelCloud::new(
@@ -1447,4 +1820,58 @@ mod tests {
         assert_eq!(voxel_cloud.block_dimensions, Vector3::new(0, 0, 0));
         assert_eq!(voxel_cloud.voxel_map.len(), 0);
     }
+
+    #[test]
+    fn test_voxel_cloud_grow_volume_turns_on_neighbors_of_single_voxel() {
+        let mut voxel_cloud = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(1, 1, 1),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+        voxel_cloud.set_voxel_at_relative_coords(&Point3::new(0, 0, 0), true);
+        voxel_cloud.grow_volume();
+
+        assert_eq!(voxel_cloud.block_start, Point3::new(-1, -1, -1));
+        assert_eq!(voxel_cloud.block_dimensions, Vector3::new(3, 3, 3));
+        for (index, voxel) in voxel_cloud.voxel_map.iter().enumerate() {
+            let absolute_coords = one_dimensional_to_absolute_three_dimensional_coordinate(
+                index,
+                &voxel_cloud.block_start,
+                &voxel_cloud.block_dimensions,
+            )
+            .unwrap();
+            let manhattan_distance =
+                absolute_coords.x.abs() + absolute_coords.y.abs() + absolute_coords.z.abs();
+            assert_eq!(*voxel, manhattan_distance <= 1);
+        }
+    }
+
+    #[test]
+    fn test_voxel_cloud_fill_volumes_fills_hollow_cube() {
+        let mut voxel_cloud = VoxelCloud::new(
+            &Point3::origin(),
+            &Vector3::new(5, 5, 5),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+        for x in 1..4 {
+            for y in 1..4 {
+                for z in 1..4 {
+                    if x != 2 || y != 2 || z != 2 {
+                        voxel_cloud.set_voxel_at_relative_coords(&Point3::new(x, y, z), true);
+                    }
+                }
+            }
+        }
+        voxel_cloud.fill_volumes();
+
+        for (index, voxel) in voxel_cloud.voxel_map.iter().enumerate() {
+            let coords = one_dimensional_to_relative_three_dimensional_coordinate(
+                index,
+                &Vector3::new(5, 5, 5),
+            )
+            .unwrap();
+            let inside = coords.iter().all(|coord| *coord >= 1 && *coord <= 3);
+            assert_eq!(*voxel, inside);
+        }
+    }
 }
@@ -88,7 +88,6 @@ impl Plane {
     /// correct.
     ///
     /// https://www.ilikebigbits.com/2017_09_25_plane_from_points_2.html
-    #[allow(dead_code)]
     pub fn fit(points: &[Point3<f32>]) -> Option<Plane> {
         let n = points.len();
         // Not enough points to compute a plane.
@@ -170,12 +169,10 @@ impl Plane {
         self.x_vector.cross(&self.y_vector)
     }
 
-    #[allow(dead_code)]
     pub fn x_vector(&self) -> Vector3<f32> {
         self.x_vector
     }
 
-    #[allow(dead_code)]
     pub fn y_vector(&self) -> Vector3<f32> {
         self.y_vector
     }
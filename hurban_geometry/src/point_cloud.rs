@@ -0,0 +1,22 @@
+use nalgebra::Point3;
+
+/// An unordered set of points, e.g. the vertices of a mesh without
+/// its faces.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PointCloud {
+    points: Vec<Point3<f32>>,
+}
+
+impl PointCloud {
+    pub fn new(points: Vec<Point3<f32>>) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[Point3<f32>] {
+        &self.points
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
@@ -0,0 +1,2637 @@
+use std::collections::{HashMap, HashSet};
+use std::f32;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nalgebra::{Matrix4, Point3, Vector3};
+
+use crate::bounding_box::BoundingBox;
+use crate::camera::{Camera, CameraOptions, CameraProjection, CameraRotationMode};
+use crate::camera_path::{CameraKeyframe, CameraPath};
+use crate::color_ramp::{self, ColorRamp};
+use crate::convert::{cast_u32, cast_u8_color_to_f64, cast_usize};
+use crate::curve::Curve;
+use crate::gizmo::{Gizmo, GizmoHandle, GizmoMode, TransformParams};
+use crate::history::{EvaluationHistory, HistoryEntryId};
+use crate::input::{InputManager, InputState, ViewportClick};
+use crate::interpreter::{ast, Ty, Value, VarIdent};
+use crate::interpreter_funcs;
+use crate::ipc_server::IpcServer;
+use crate::live_view::{CameraCommand, LiveViewServer};
+use crate::locator::Locator;
+use crate::logger;
+use crate::mesh::analysis::{self, FaceChange};
+use crate::mesh::bvh::Bvh;
+use crate::mesh::{Face, Mesh, NormalStrategy, QuadFace, TriangleFace};
+use crate::point_cloud::PointCloud;
+use crate::renderer::{
+    Background, DebugView, DrawMeshMode, GpuBackend, GpuCurve, GpuCurveId, GpuMeshId,
+    GpuMeshTopology, GpuPoints, GpuPointsId, GroundShadow, Material, MeshShading, Msaa,
+    Options as RendererOptions, PostProcessing, PresentMode, RenderPass, Renderer, Section,
+    SectionFill,
+};
+use crate::scene::Scene;
+use crate::session::{PollInterpreterResponseNotification, Session};
+use crate::sweep::SweepCoordinator;
+use crate::ui::{CameraPathCommand, LocatorMarker, Theme, Ui, UiFrame};
+
+const CAMERA_INTERPOLATION_DURATION: Duration = Duration::from_millis(1000);
+/// How much later than the last keyframe of the camera path new
+/// keyframes are added, in seconds.
+const CAMERA_PATH_KEYFRAME_INTERVAL: f32 = 2.0;
+const HISTORY_MAX_ENTRIES_PER_VAR: usize = 8;
+const HISTORY_MEMORY_BUDGET_BYTES: usize = 512 * 1024 * 1024;
+const LIVE_VIEW_FRAME_INTERVAL: Duration = Duration::from_millis(200);
+/// How fast the camera orbits in presentation mode, in the camera's
+/// rotation units per second.
+const PRESENTATION_AUTO_ORBIT_SPEED: f32 = 40.0;
+/// The color blended over the mesh selected in the viewport.
+const SELECTION_TINT: [f32; 3] = [1.0, 0.6, 0.1];
+/// The colors of the faces added, moved and removed since the previous
+/// result, and of the unchanged faces, in the "What changed" view.
+const CHANGE_ADDED_COLOR: [f32; 3] = [0.2, 0.8, 0.2];
+const CHANGE_MOVED_COLOR: [f32; 3] = [0.95, 0.8, 0.1];
+const CHANGE_REMOVED_COLOR: [f32; 3] = [0.9, 0.15, 0.1];
+const CHANGE_UNCHANGED_COLOR: [f32; 3] = [0.7, 0.7, 0.7];
+/// How far, relative to the size of the compared meshes, faces can be
+/// from each other to still count as unchanged.
+const CHANGE_RELATIVE_TOLERANCE: f32 = 0.0001;
+/// The length of the locator axes relative to the radius of the sphere
+/// visible by the camera, so that they keep their size on the screen.
+const LOCATOR_RELATIVE_SIZE: f32 = 0.08;
+/// The material of the earlier pipeline stages drawn behind the final
+/// result.
+const STAGE_GHOST_MATERIAL: Material = Material {
+    base_color: [0.8, 0.8, 0.8],
+    roughness: 0.5,
+    opacity: 0.25,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// What theme to use.
+    pub theme: Theme,
+    /// Whether to open a fullscreen window.
+    pub fullscreen: bool,
+    /// Which multi-sampling setting to use.
+    pub msaa: Msaa,
+    /// Whether to run with VSync or not.
+    pub present_mode: PresentMode,
+    /// Whether to select an explicit gpu backend for the renderer to use.
+    pub gpu_backend: Option<GpuBackend>,
+    /// Logging level for the editor.
+    pub app_log_level: Option<logger::LogLevel>,
+    /// Logging level for external libraries.
+    pub lib_log_level: Option<logger::LogLevel>,
+    /// Whether to accept commands from external tools on a local TCP
+    /// port.
+    pub ipc_port: Option<u16>,
+    /// The token external tools authenticate with. Required if the
+    /// IPC port is set.
+    pub ipc_token: Option<String>,
+    /// Whether to stream the viewport to browsers on a TCP port.
+    pub live_view_port: Option<u16>,
+    /// The address of the network interface the live view server
+    /// listens on.
+    pub live_view_address: IpAddr,
+    /// Origins of pages allowed to connect to the live view in
+    /// addition to localhost and the pages it serves itself.
+    pub live_view_allowed_origins: Vec<String>,
+    /// Whether to distribute parameter sweeps to headless workers
+    /// connecting to a TCP port.
+    pub sweep_coordinator_port: Option<u16>,
+    /// The address of the network interface the sweep coordinator
+    /// listens on.
+    pub sweep_coordinator_address: IpAddr,
+    /// Whether to spill intermediate meshes to disk once they exceed
+    /// this many bytes of memory.
+    pub spill_memory_budget_bytes: Option<usize>,
+}
+
+/// A unique identifier assigned to a value or subvalue for purposes
+/// of displaying in the viewport.
+///
+/// Since we support value arrays, there can be multiple geometries
+/// contained in a single value that all need to be treated separately
+/// for purposes of scene geometry analysis and rendering.
+///
+/// For simple values, the path is always `(var_ident, 0)`. For array
+/// element values, the path is `(var_ident, array_index)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ValuePath(VarIdent, usize);
+
+/// Identifies a mesh of a remembered result in the evaluation history
+/// for purposes of displaying in the viewport, as `(entry_id, index)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct HistoryPath(HistoryEntryId, usize);
+
+/// Display settings of the viewport, controlled from the UI.
+#[derive(Debug, Clone, PartialEq)]
+struct ViewportSettings {
+    draw_mesh_mode: DrawMeshMode,
+    show_previous_result: bool,
+    /// Whether the scene meshes are replaced by their faces colored by
+    /// how they changed since the previous result.
+    show_changes: bool,
+    /// Whether the results of earlier operations of the pipeline are
+    /// drawn as transparent ghosts behind the final result.
+    show_pipeline_stages: bool,
+    compare_with_pinned: bool,
+    comparison_split: f32,
+    /// Whether the camera orbits around the scene in presentation
+    /// mode.
+    auto_orbit: bool,
+    /// How far the scene meshes are moved away from the center of the
+    /// scene, relative to their distance from it. Zero shows the
+    /// meshes in place.
+    exploded_view_factor: f32,
+    background: Background,
+    /// The Radiance HDR image shown by the environment background.
+    background_environment_path: Option<String>,
+    /// Whether the scene casts a shadow on a ground plane below
+    /// it. Captured frames then have transparent background, so that
+    /// only the meshes and the shadow are in them.
+    ground_shadow: bool,
+    /// Whether a grid is drawn on the ground plane. Only shown in the
+    /// window, not in saved images or the live view.
+    ground_grid: bool,
+    /// Whether the world axes are drawn in the corner of the
+    /// viewport. Only shown in the window, like the grid.
+    axes: bool,
+    /// Whether the edges and vertices of scene meshes are drawn over
+    /// them, with border and non-manifold edges highlighted.
+    topology: bool,
+    /// Whether the `post_processing` is applied to captured frames.
+    post_processing_enabled: bool,
+    post_processing: PostProcessing,
+    /// Whether the scene is cut by the `section`.
+    section_enabled: bool,
+    section: Section,
+    /// Whether the scene meshes are colored by the values of their
+    /// vertex attribute named `attribute_colors_name`, mapped through
+    /// the `attribute_colors_ramp`.
+    attribute_colors_enabled: bool,
+    attribute_colors_name: Option<String>,
+    attribute_colors_ramp: ColorRamp,
+    /// Whether clicking a face in the viewport assigns it the
+    /// `assigned_material`, instead of selecting its mesh.
+    assign_material_on_click: bool,
+    assigned_material: u32,
+    /// Whether clicking a mesh in the viewport adds a locator on its
+    /// surface, instead of selecting it.
+    place_locator_on_click: bool,
+    /// Which parameters of the Transform of the selected mesh the
+    /// viewport gizmo edits.
+    gizmo_mode: GizmoMode,
+    camera_rotation_mode: CameraRotationMode,
+    camera_projection: CameraProjection,
+    /// The shading of scene meshes without their own shading.
+    mesh_shading: MeshShading,
+    /// The shading of individual scene meshes, set while they were
+    /// selected.
+    mesh_shading_overrides: HashMap<ValuePath, MeshShading>,
+    /// The material of scene meshes without their own material.
+    mesh_material: Material,
+    /// The material of individual scene meshes, e.g. to tell apart
+    /// the outputs of the pipeline by color.
+    mesh_material_overrides: HashMap<ValuePath, Material>,
+    debug_view: DebugView,
+    /// The multisampling level the renderer runs with, changed
+    /// without restarting.
+    msaa: Msaa,
+    /// How many times larger than the window saved images are.
+    capture_scale: u32,
+    /// Where to save an image of the viewport after the next frame is
+    /// rendered.
+    capture_path: Option<String>,
+    camera_path: CameraPath,
+    /// The frame rate camera paths are rendered with.
+    camera_path_fps: u32,
+    /// What to do with the camera path after the UI is drawn.
+    camera_path_command: Option<CameraPathCommand>,
+}
+
+/// Initialize the window and run in infinite loop.
+///
+/// Will continue running until a close request is received from the
+/// created window.
+pub fn init_and_run(options: Options) -> ! {
+    logger::init(options.app_log_level, options.lib_log_level);
+
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = if options.fullscreen {
+        let monitor = event_loop.primary_monitor();
+
+        // Exclusive fullscreen on macOS has 2 problems:
+        // - winit does not report correct DPI once the window
+        //   switches to fullscreen,
+        // - wgpu on vulkan on metal can not allocate a large enough
+        //   backbuffer.
+        //
+        // Neither of these happen on borderless fullscreen on macOS.
+        // FIXME: Fix these issues in winit and wgpu.
+        #[cfg(target_os = "macos")]
+        {
+            log::info!("Running in fullscreen mode on macOS, opening borderless fullscreen");
+            winit::window::WindowBuilder::new()
+                .with_title("H.U.R.B.A.N. Selector")
+                .with_fullscreen(Some(winit::window::Fullscreen::Borderless(monitor)))
+                .build(&event_loop)
+                .expect("Failed to create window")
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            log::info!("Running in fullscreen mode, looking for exclusive fullscreen video modes");
+            if let Some(video_mode) = monitor.video_modes().next() {
+                log::info!(
+                    "Found video mode: {}, opening exclusive fullscreen",
+                    video_mode,
+                );
+                winit::window::WindowBuilder::new()
+                    .with_title("H.U.R.B.A.N. Selector")
+                    .with_fullscreen(Some(winit::window::Fullscreen::Exclusive(video_mode)))
+                    .build(&event_loop)
+                    .expect("Failed to create window")
+            } else {
+                log::info!("Didn't find compatible video mode, opening borderless fullscreen");
+                winit::window::WindowBuilder::new()
+                    .with_title("H.U.R.B.A.N. Selector")
+                    .with_fullscreen(Some(winit::window::Fullscreen::Borderless(monitor)))
+                    .build(&event_loop)
+                    .expect("Failed to create window")
+            }
+        }
+    } else {
+        log::info!("Running in windowed mode");
+        winit::window::WindowBuilder::new()
+            .with_title("H.U.R.B.A.N. Selector")
+            .with_inner_size(winit::dpi::LogicalSize::new(1280.0, 720.0))
+            .build(&event_loop)
+            .expect("Failed to create window")
+    };
+
+    let window_size = window.inner_size().to_physical(window.hidpi_factor());
+
+    let mut session = Session::new(options.spill_memory_budget_bytes);
+    let ipc_server = options.ipc_port.map(|port| {
+        let token = options
+            .ipc_token
+            .clone()
+            .expect("IPC server requires a token");
+        IpcServer::new(port, token)
+            .unwrap_or_else(|err| panic!("Failed to start IPC server on port {}: {}", port, err))
+    });
+    let mut sweep_coordinator = options.sweep_coordinator_port.map(|port| {
+        SweepCoordinator::new(options.sweep_coordinator_address, port).unwrap_or_else(|err| {
+            panic!(
+                "Failed to start sweep coordinator on port {}: {}",
+                port, err
+            )
+        })
+    });
+    let mut live_view_server = options.live_view_port.map(|port| {
+        LiveViewServer::new(
+            options.live_view_address,
+            port,
+            options.live_view_allowed_origins.clone(),
+            LIVE_VIEW_FRAME_INTERVAL,
+        )
+        .unwrap_or_else(|err| panic!("Failed to start live view server on port {}: {}", port, err))
+    });
+    let mut input_manager = InputManager::new();
+    let mut ui = Ui::new(&window, options.theme);
+
+    let mut camera = Camera::new(
+        window_size,
+        5.0,
+        45f32.to_radians(),
+        60f32.to_radians(),
+        CameraOptions {
+            radius_min: 1.0,
+            radius_max: 10000.0,
+            polar_angle_distance_min: 1f32.to_radians(),
+            speed_pan: 10.0,
+            speed_rotate: 0.005,
+            speed_zoom: 0.01,
+            speed_zoom_step: 1.0,
+            fovy: 45f32.to_radians(),
+            znear: 0.01,
+            zfar: 1000.0,
+        },
+    );
+
+    let clear_color = match options.theme {
+        Theme::Dark => [0.1, 0.1, 0.1, 1.0],
+        Theme::Funky => cast_u8_color_to_f64([0xea, 0xe7, 0xe1, 0xff]),
+    };
+    let mut viewport_settings = ViewportSettings {
+        draw_mesh_mode: DrawMeshMode::Shaded,
+        show_previous_result: false,
+        show_changes: false,
+        show_pipeline_stages: false,
+        compare_with_pinned: false,
+        comparison_split: 0.5,
+        auto_orbit: false,
+        exploded_view_factor: 0.0,
+        background: Background::Solid {
+            color: [
+                clear_color[0] as f32,
+                clear_color[1] as f32,
+                clear_color[2] as f32,
+            ],
+        },
+        background_environment_path: None,
+        ground_shadow: false,
+        ground_grid: true,
+        axes: true,
+        topology: false,
+        post_processing_enabled: false,
+        post_processing: PostProcessing::default(),
+        section_enabled: false,
+        section: Section {
+            origin: Point3::origin(),
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            fill: SectionFill::Hatched,
+            color: [0.6, 0.6, 0.6],
+            hatch_spacing: 0.1,
+        },
+        attribute_colors_enabled: false,
+        attribute_colors_name: None,
+        attribute_colors_ramp: ColorRamp::Viridis,
+        assign_material_on_click: false,
+        assigned_material: 1,
+        place_locator_on_click: false,
+        gizmo_mode: GizmoMode::Translate,
+        camera_rotation_mode: CameraRotationMode::Turntable,
+        camera_projection: CameraProjection::Perspective,
+        mesh_shading: MeshShading::default(),
+        mesh_shading_overrides: HashMap::new(),
+        mesh_material: Material::default(),
+        mesh_material_overrides: HashMap::new(),
+        debug_view: DebugView::Off,
+        msaa: options.msaa,
+        capture_scale: 2,
+        capture_path: None,
+        camera_path: CameraPath::new(),
+        camera_path_fps: 30,
+        camera_path_command: None,
+    };
+    let mut presentation_mode = false;
+    let mut renderer = Renderer::new(
+        &window,
+        &camera.projection_matrix(),
+        &camera.view_matrix(),
+        ui.fonts(),
+        RendererOptions {
+            clear_color,
+            background: viewport_settings.background,
+            // FIXME: @Correctness Msaa X4 is the only value currently
+            // working on all devices we tried. Once msaa capabilities
+            // are queryable with wgpu `Limits`, we should have a
+            // chain of options the renderer tries before giving up,
+            // and this field should be renamed to `desired_msaa`.
+            msaa: viewport_settings.msaa,
+            present_mode: options.present_mode,
+            gpu_backend: options.gpu_backend,
+        },
+    );
+
+    let mut scene_meshes: HashMap<ValuePath, Arc<Mesh>> = HashMap::new();
+    let mut scene_locators: HashMap<VarIdent, Arc<Locator>> = HashMap::new();
+    let mut scene_curves: HashMap<VarIdent, Arc<Curve>> = HashMap::new();
+    let mut scene_points: HashMap<VarIdent, Arc<PointCloud>> = HashMap::new();
+    let mut ground_shadow_tracker = GroundShadowTracker::default();
+    let mut exploded_view_tracker = ExplodedViewTracker::default();
+    let mut attribute_color_tracker = AttributeColorTracker::default();
+    let mut change_tracker = ChangeTracker::default();
+    let mut scene_picker = ScenePicker::default();
+    let mut selection_tracker = SelectionTracker::default();
+    let mut shading_tracker = ShadingTracker::default();
+    let mut topology_tracker = TopologyTracker::default();
+    let mut curve_tracker: GpuValueTracker<Curve, GpuCurveId> = GpuValueTracker::default();
+    let mut points_tracker: GpuValueTracker<PointCloud, GpuPointsId> = GpuValueTracker::default();
+    let mut gizmo_tracker = GizmoTracker::default();
+    let mut scene: Scene<ValuePath> = Scene::default();
+
+    let mut history =
+        EvaluationHistory::new(HISTORY_MAX_ENTRIES_PER_VAR, HISTORY_MEMORY_BUDGET_BYTES);
+    let mut pinned_scene: Scene<HistoryPath> = Scene::default();
+    let mut previous_scene: Scene<HistoryPath> = Scene::default();
+    let mut stage_scene: Scene<ValuePath> = Scene::default();
+
+    let cubic_bezier = math::CubicBezierEasing::new([0.7, 0.0], [0.3, 1.0]);
+
+    let time_start = Instant::now();
+    let mut time = time_start;
+
+    let mut camera_interpolation: Option<CameraInterpolation> = None;
+    let mut camera_path_playback: Option<CameraPathPlayback> = None;
+
+    // Since input manager needs to process events separately after imgui
+    // handles them, this buffer with copies of events is needed.
+    let mut input_events: Vec<winit::event::Event<_>> = Vec::with_capacity(16);
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+
+        match event {
+            winit::event::Event::EventsCleared => {
+                let (duration_last_frame, _duration_running) = {
+                    let now = Instant::now();
+                    let duration_last_frame = now.duration_since(time);
+                    let duration_running = now.duration_since(time_start);
+                    time = now;
+
+                    (duration_last_frame, duration_running)
+                };
+
+                ui.set_delta_time(duration_last_frame.as_secs_f32());
+                update_hidpi_factor(&window, &mut ui, &mut renderer, &mut camera);
+
+                let ui_frame = ui.prepare_frame(&window);
+                input_manager.start_frame();
+
+                for event in input_events.drain(..) {
+                    input_manager.process_event(
+                        &event,
+                        ui_frame.want_capture_keyboard(),
+                        ui_frame.want_capture_mouse(),
+                    );
+                }
+
+                let input_state = input_manager.input_state();
+                let cursor_position = input_manager.cursor_position();
+                let window_size = {
+                    let logical_size = window.inner_size();
+                    [logical_size.width as f32, logical_size.height as f32]
+                };
+
+                // The gizmo is only available while the UI is shown
+                let gizmo_origin = if presentation_mode {
+                    None
+                } else {
+                    selected_gizmo_origin(
+                        &session,
+                        selection_tracker.selected_path(),
+                        &scene_meshes,
+                        &exploded_view_tracker,
+                    )
+                };
+                let gizmo_dragging = gizmo_tracker.update(
+                    &camera,
+                    window_size,
+                    cursor_position,
+                    viewport_settings.gizmo_mode,
+                    gizmo_origin,
+                    input_state,
+                    &mut session,
+                    &mut selection_tracker,
+                );
+
+                let [pan_ground_x, pan_ground_y] = input_state.camera_pan_ground;
+                let [pan_screen_x, pan_screen_y] = input_state.camera_pan_screen;
+                let [rotate_x, rotate_y] = input_state.camera_rotate;
+
+                camera.pan_ground(pan_ground_x, pan_ground_y);
+                camera.pan_screen(pan_screen_x, pan_screen_y);
+                // Dragging the gizmo must not rotate the camera
+                if !gizmo_dragging {
+                    camera.rotate(rotate_x, rotate_y);
+                }
+                camera.zoom(input_state.camera_zoom);
+                camera.zoom_step(input_state.camera_zoom_steps);
+                if let Some(camera_view) = input_state.camera_view {
+                    camera.set_view(camera_view);
+                }
+                if input_state.camera_projection_toggled {
+                    viewport_settings.camera_projection = match viewport_settings.camera_projection
+                    {
+                        CameraProjection::Perspective => CameraProjection::Orthographic,
+                        CameraProjection::Orthographic => CameraProjection::Perspective,
+                    };
+                }
+
+                let live_view_reset_viewport =
+                    live_view_server.as_ref().map_or(false, |live_view_server| {
+                        apply_camera_commands(&mut camera, live_view_server.poll_camera_commands())
+                    });
+
+                if input_state.presentation_mode_toggled {
+                    presentation_mode = !presentation_mode;
+                    set_presentation_mode(&window, presentation_mode, options.fullscreen);
+                }
+
+                // Presentation mode only shows the viewport
+                let ui_reset_viewport = if presentation_mode {
+                    if viewport_settings.auto_orbit {
+                        camera.rotate(
+                            PRESENTATION_AUTO_ORBIT_SPEED * duration_last_frame.as_secs_f32(),
+                            0.0,
+                        );
+                    }
+
+                    false
+                } else {
+                    // Drawn first, so that the gizmo and locators are
+                    // behind the panels
+                    ui_frame.draw_locators(&locator_markers(&camera, window_size, &scene_locators));
+                    ui_frame.draw_gizmo(
+                        viewport_settings.gizmo_mode,
+                        &gizmo_tracker.handles(
+                            &camera,
+                            window_size,
+                            cursor_position,
+                            viewport_settings.gizmo_mode,
+                            gizmo_origin,
+                        ),
+                    );
+                    draw_panels(
+                        &ui_frame,
+                        &mut session,
+                        &mut history,
+                        &mut renderer,
+                        &scene_meshes,
+                        &scene_locators,
+                        sweep_coordinator.as_mut(),
+                        &mut viewport_settings,
+                        selection_tracker.selected_path(),
+                        camera_path_playback.is_some(),
+                    )
+                };
+                camera.set_rotation_mode(viewport_settings.camera_rotation_mode);
+                camera.set_projection(viewport_settings.camera_projection);
+
+                // Releasing the gizmo close to where it was grabbed is
+                // not a click
+                let viewport_click = input_state.viewport_click.filter(|_| !gizmo_dragging);
+                if let Some(click) = viewport_click {
+                    let picked = pick_clicked_face(
+                        &camera,
+                        window.inner_size(),
+                        click,
+                        &mut scene_picker,
+                        &scene_meshes,
+                        &exploded_view_tracker,
+                    );
+
+                    // While the face orientation is displayed, clicks
+                    // fix it, while assigning materials or placing
+                    // locators, they do that, otherwise they select
+                    // meshes
+                    if viewport_settings.draw_mesh_mode == DrawMeshMode::FaceOrientation {
+                        if let Some((path, face_index, _)) = picked {
+                            revert_clicked_face(&mut session, path, face_index, click.shift);
+                        }
+                    } else if viewport_settings.assign_material_on_click {
+                        if let Some((path, face_index, _)) = picked {
+                            assign_clicked_face_material(
+                                &mut session,
+                                path,
+                                face_index,
+                                viewport_settings.assigned_material,
+                                click.shift,
+                            );
+                        }
+                    } else if viewport_settings.place_locator_on_click {
+                        if let Some((path, face_index, position)) = picked {
+                            place_clicked_locator(
+                                &mut session,
+                                &scene_meshes[&path],
+                                face_index,
+                                position,
+                            );
+                        }
+                    } else {
+                        selection_tracker.select(picked.map(|(path, _, _)| path));
+                    }
+                }
+
+                if input_state.camera_reset_viewport
+                    || ui_reset_viewport
+                    || live_view_reset_viewport
+                {
+                    camera_interpolation = Some(CameraInterpolation::new(
+                        &camera,
+                        scene_meshes.values().map(Arc::as_ref),
+                        time,
+                    ));
+                }
+
+                if input_state.close_requested {
+                    *control_flow = winit::event_loop::ControlFlow::Exit;
+                }
+
+                if let Some(logical_size) = input_state.window_resized {
+                    let physical_size = logical_size.to_physical(window.hidpi_factor());
+                    log::debug!(
+                        "Window resized to new size: logical {}x{}, physical {}x{}",
+                        logical_size.width,
+                        logical_size.height,
+                        physical_size.width,
+                        physical_size.height,
+                    );
+
+                    camera.set_window_size(physical_size);
+                    renderer.set_window_size(physical_size);
+                }
+
+                session.poll_interpreter_response(|callback_value| match callback_value {
+                    PollInterpreterResponseNotification::Add(var_ident, value) => match value {
+                        Value::Mesh(mesh) => {
+                            history.record(var_ident, vec![Arc::clone(&mesh)]);
+                            scene_meshes.insert(ValuePath(var_ident, 0), mesh);
+                        }
+                        Value::MeshArray(mesh_array) => {
+                            history.record(var_ident, mesh_array.iter_refcounted().collect());
+
+                            for (index, mesh) in mesh_array.iter_refcounted().enumerate() {
+                                scene_meshes.insert(ValuePath(var_ident, index), mesh);
+                            }
+                        }
+                        Value::Locator(locator) => {
+                            scene_locators.insert(var_ident, locator);
+                        }
+                        Value::Curve(curve) => {
+                            scene_curves.insert(var_ident, curve);
+                        }
+                        Value::Points(points) => {
+                            scene_points.insert(var_ident, points);
+                        }
+                        _ => (/* Ignore other values, we don't display them in the viewport */),
+                    },
+                    PollInterpreterResponseNotification::Remove(var_ident, value) => match value {
+                        Value::Mesh(_) => {
+                            scene_meshes.remove(&ValuePath(var_ident, 0));
+                        }
+                        Value::MeshArray(mesh_array) => {
+                            for index in 0..mesh_array.len() {
+                                scene_meshes.remove(&ValuePath(var_ident, cast_usize(index)));
+                            }
+                        }
+                        Value::Locator(_) => {
+                            scene_locators.remove(&var_ident);
+                        }
+                        Value::Curve(_) => {
+                            scene_curves.remove(&var_ident);
+                        }
+                        Value::Points(_) => {
+                            scene_points.remove(&var_ident);
+                        }
+                        _ => (/* Ignore other values, we don't display them in the viewport */),
+                    },
+                });
+
+                if let Some(ipc_server) = &ipc_server {
+                    ipc_server.poll(&mut session, |var| {
+                        let mut meshes: Vec<_> = scene_meshes
+                            .iter()
+                            .filter(|(path, _)| var.map_or(true, |var| path.0 == var))
+                            .map(|(path, mesh)| (path, Arc::clone(mesh)))
+                            .collect();
+                        meshes.sort_by_key(|(path, _)| ((path.0).0, path.1));
+
+                        meshes
+                            .into_iter()
+                            .map(|(path, mesh)| (path.0, mesh))
+                            .collect()
+                    });
+                }
+
+                let attribute_colors = if viewport_settings.attribute_colors_enabled {
+                    viewport_settings
+                        .attribute_colors_name
+                        .as_ref()
+                        .map(|name| (name.as_str(), viewport_settings.attribute_colors_ramp))
+                } else {
+                    None
+                };
+                scene.update(
+                    &mut renderer,
+                    change_tracker.update(
+                        viewport_settings.show_changes,
+                        &history,
+                        attribute_color_tracker.update(attribute_colors, &scene_meshes),
+                    ),
+                );
+                pinned_scene.update(&mut renderer, &pinned_meshes(&history));
+                previous_scene.update(
+                    &mut renderer,
+                    &previous_meshes(
+                        &history,
+                        &scene_meshes,
+                        viewport_settings.show_previous_result,
+                    ),
+                );
+                let stages_changed = stage_scene.update(
+                    &mut renderer,
+                    &stage_meshes(&session, viewport_settings.show_pipeline_stages),
+                );
+                if stages_changed {
+                    for gpu_mesh_id in stage_scene.gpu_mesh_ids().values() {
+                        renderer.set_scene_mesh_material(*gpu_mesh_id, STAGE_GHOST_MATERIAL);
+                    }
+                }
+
+                if let Some(interp) = camera_interpolation {
+                    if interp.target_time > time {
+                        let (sphere_origin, sphere_radius) = interp.update(time, &cubic_bezier);
+                        camera.zoom_to_fit_visible_sphere(sphere_origin, sphere_radius);
+                    } else {
+                        camera
+                            .zoom_to_fit_visible_sphere(interp.target_origin, interp.target_radius);
+                        camera_interpolation = None;
+                    }
+                }
+
+                if let Some(command) = viewport_settings.camera_path_command.take() {
+                    camera_path_playback = handle_camera_path_command(
+                        command,
+                        &mut viewport_settings.camera_path,
+                        viewport_settings.camera_path_fps,
+                        &camera,
+                        &scene_locators,
+                        camera_path_playback.take(),
+                    );
+                }
+                // The camera path overrides the camera, including its
+                // interpolation
+                if let Some(playback) = &camera_path_playback {
+                    if let Some((eye, target)) =
+                        viewport_settings.camera_path.sample(playback.time())
+                    {
+                        camera.look_at(&eye, &target);
+                    }
+                }
+
+                let imgui_draw_data = ui_frame.render(&window);
+
+                // Camera matrices have to be uploaded when either window
+                // resizes or the camera moves. We do it every frame for
+                // simplicity.
+                // FIXME: @Optimization Update camera matrices within
+                // the same command encoder.
+                renderer.set_camera_matrices(&camera.projection_matrix(), &camera.view_matrix());
+                let comparison_split =
+                    if viewport_settings.compare_with_pinned && !presentation_mode {
+                        Some(viewport_settings.comparison_split)
+                    } else {
+                        None
+                    };
+
+                ground_shadow_tracker.update(
+                    &mut renderer,
+                    viewport_settings.ground_shadow,
+                    &scene_meshes,
+                );
+                exploded_view_tracker.update(
+                    &mut renderer,
+                    viewport_settings.exploded_view_factor,
+                    &scene_meshes,
+                    scene.gpu_mesh_ids(),
+                );
+                selection_tracker.update(&mut renderer, scene.gpu_mesh_ids());
+                shading_tracker.update(
+                    &mut renderer,
+                    viewport_settings.mesh_shading,
+                    &viewport_settings.mesh_shading_overrides,
+                    viewport_settings.mesh_material,
+                    &viewport_settings.mesh_material_overrides,
+                    scene.gpu_mesh_ids(),
+                );
+                topology_tracker.update(
+                    &mut renderer,
+                    viewport_settings.topology,
+                    &scene_meshes,
+                    scene.gpu_mesh_ids(),
+                );
+                curve_tracker.update(
+                    &mut renderer,
+                    &scene_curves,
+                    |renderer, curve| renderer.add_scene_curve(&GpuCurve::from_curve(curve)),
+                    Renderer::remove_scene_curve,
+                );
+                points_tracker.update(
+                    &mut renderer,
+                    &scene_points,
+                    |renderer, points| {
+                        renderer.add_scene_points(&GpuPoints::from_point_cloud(points))
+                    },
+                    Renderer::remove_scene_points,
+                );
+                render_frame(
+                    &mut renderer,
+                    imgui_draw_data,
+                    viewport_settings.ground_grid,
+                    viewport_settings.axes,
+                    viewport_settings.draw_mesh_mode,
+                    comparison_split,
+                    scene.gpu_mesh_ids(),
+                    pinned_scene.gpu_mesh_ids(),
+                    previous_scene.gpu_mesh_ids(),
+                    stage_scene.gpu_mesh_ids(),
+                    curve_tracker.gpu_ids(),
+                    points_tracker.gpu_ids(),
+                );
+
+                let post_processing = if viewport_settings.post_processing_enabled {
+                    Some(viewport_settings.post_processing)
+                } else {
+                    None
+                };
+
+                // Saved images, like the live view, only show the
+                // viewport, without the UI
+                let image_path = viewport_settings.capture_path.take();
+                let frame_path = camera_path_playback
+                    .as_ref()
+                    .and_then(CameraPathPlayback::frame_path);
+                let capture_viewport = |renderer: &mut Renderer, path: &str| {
+                    renderer.capture_frame(
+                        path,
+                        post_processing,
+                        viewport_settings.capture_scale,
+                        |render_pass| {
+                            draw_scene(
+                                render_pass,
+                                viewport_settings.ground_shadow,
+                                viewport_settings.draw_mesh_mode,
+                                comparison_split,
+                                scene.gpu_mesh_ids(),
+                                pinned_scene.gpu_mesh_ids(),
+                                previous_scene.gpu_mesh_ids(),
+                                stage_scene.gpu_mesh_ids(),
+                                curve_tracker.gpu_ids(),
+                                points_tracker.gpu_ids(),
+                            );
+                        },
+                    )
+                };
+                if let Some(path) = image_path {
+                    match capture_viewport(&mut renderer, &path) {
+                        Ok(frame) => {
+                            log::info!("Saved {}x{} image to {}", frame.width, frame.height, path,)
+                        }
+                        Err(err) => log::error!("Failed to save image to {}: {}", path, err),
+                    }
+                }
+                let frame_captured = match frame_path {
+                    Some(path) => match capture_viewport(&mut renderer, &path) {
+                        Ok(_) => true,
+                        Err(err) => {
+                            log::error!("Failed to render camera path frame to {}: {}", path, err);
+                            false
+                        }
+                    },
+                    None => true,
+                };
+
+                let camera_path_finished = match &mut camera_path_playback {
+                    Some(playback) => {
+                        !frame_captured
+                            || playback.advance(
+                                duration_last_frame.as_secs_f32(),
+                                viewport_settings.camera_path.end_time(),
+                            )
+                    }
+                    None => false,
+                };
+                if camera_path_finished {
+                    if let Some(CameraPathPlayback::Render {
+                        dir, frame_times, ..
+                    }) = &camera_path_playback
+                    {
+                        if frame_captured {
+                            log::info!(
+                                "Rendered {} frames of the camera path to {}",
+                                frame_times.len(),
+                                dir.display(),
+                            );
+                        }
+                    }
+                    camera_path_playback = None;
+                }
+
+                // Live view clients only see the viewport, without the UI
+                if let Some(live_view_server) = &mut live_view_server {
+                    if live_view_server.wants_frame(time) && !renderer.is_paused() {
+                        let mut capture_render_pass =
+                            renderer.begin_capture_render_pass(post_processing, 1);
+                        draw_scene(
+                            &mut capture_render_pass,
+                            viewport_settings.ground_shadow,
+                            viewport_settings.draw_mesh_mode,
+                            comparison_split,
+                            scene.gpu_mesh_ids(),
+                            pinned_scene.gpu_mesh_ids(),
+                            previous_scene.gpu_mesh_ids(),
+                            stage_scene.gpu_mesh_ids(),
+                            curve_tracker.gpu_ids(),
+                            points_tracker.gpu_ids(),
+                        );
+                        capture_render_pass.submit();
+
+                        if let Some(frame) = renderer.read_captured_frame() {
+                            live_view_server.send_frame(frame, time);
+                        }
+                    }
+                }
+            }
+
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::RedrawRequested,
+                ..
+            } => {
+                // We don't answer redraw requests and instead draw in
+                // the "events cleared" for 2 reasons:
+                //
+                // 1) Doing it with VSync is challenging - redrawing
+                //    at the OS's whim while knowing that each redraw
+                //    will block until the monitor flips sounds
+                //    dangerous. We could still redraw here only when
+                //    we were running without VSync, but...
+                //
+                // 2) ImGui produces a draw list with `render()`. The
+                //    drawlist shares the lifetime of the `Ui` frame
+                //    context, which is dropped at the end of "events
+                //    cleared". We could copy the draw list and stash
+                //    it for our subsequent handling of redraw
+                //    requests, but it contains raw pointers to the
+                //    `Ui` frame context which I am not sure are alive
+                //    (or even contain correct data) by the time we
+                //    get here. We could also try to prolong the
+                //    lifetime of the `Ui` by not dropping it, but
+                //    this is Rust...
+            }
+
+            winit::event::Event::WindowEvent { .. } => {
+                ui.handle_event(&window, &event);
+                input_events.push(event.clone());
+            }
+
+            _ => (),
+        }
+    });
+}
+
+/// Draws the sweep window, submits the started sweeps and saves the
+/// results returned by the workers as variants.
+fn update_sweep(
+    ui_frame: &UiFrame,
+    sweep_coordinator: &mut SweepCoordinator,
+    session: &mut Session,
+) {
+    if let Some(jobs) = ui_frame.draw_sweep_window(session, sweep_coordinator.status()) {
+        sweep_coordinator.submit(jobs);
+    }
+
+    for sweep_result in sweep_coordinator.poll_results() {
+        match sweep_result.result {
+            Ok(meshes) => {
+                session.add_variant_with_results(sweep_result.name, sweep_result.prog, meshes);
+            }
+            Err(err) => log::error!("Sweep job {} failed: {}", sweep_result.name, err),
+        }
+    }
+}
+
+/// Applies camera commands of live view clients. Returns whether a
+/// viewport reset was requested.
+fn apply_camera_commands(camera: &mut Camera, camera_commands: Vec<CameraCommand>) -> bool {
+    let mut reset_viewport = false;
+    for camera_command in camera_commands {
+        // Pointer movement is interpreted the same way as by the
+        // input manager
+        match camera_command {
+            CameraCommand::Rotate { x, y } => camera.rotate(-x, -y),
+            CameraCommand::Pan { x, y } => camera.pan_screen(x, -y),
+            CameraCommand::Zoom { delta } => camera.zoom(delta),
+            CameraCommand::ResetViewport => reset_viewport = true,
+        }
+    }
+
+    reset_viewport
+}
+
+/// Records drawing of the current results and the ghosted previous
+/// and pinned results. If comparing with pinned results, current
+/// results are drawn left of the `comparison_split` and pinned
+/// results right of it.
+/// Switches the window to borderless fullscreen when entering the
+/// presentation mode. Leaving it restores the window, unless the
+/// editor runs in fullscreen.
+fn set_presentation_mode(window: &winit::window::Window, enabled: bool, fullscreen: bool) {
+    if enabled {
+        log::info!("Entering presentation mode");
+        window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(
+            window.current_monitor(),
+        )));
+    } else {
+        log::info!("Leaving presentation mode");
+        if !fullscreen {
+            window.set_fullscreen(None);
+        }
+    }
+}
+
+/// Draws all UI panels and handles their results. Returns whether
+/// resetting the viewport was requested.
+#[allow(clippy::too_many_arguments)]
+fn draw_panels(
+    ui_frame: &UiFrame,
+    session: &mut Session,
+    history: &mut EvaluationHistory,
+    renderer: &mut Renderer,
+    scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+    scene_locators: &HashMap<VarIdent, Arc<Locator>>,
+    sweep_coordinator: Option<&mut SweepCoordinator>,
+    viewport_settings: &mut ViewportSettings,
+    selected_path: Option<ValuePath>,
+    camera_path_playing: bool,
+) -> bool {
+    let previous_background = viewport_settings.background;
+    let previous_environment_path = viewport_settings.background_environment_path.clone();
+    let previous_section = (viewport_settings.section_enabled, viewport_settings.section);
+    // With a mesh selected, the shading settings only edit its shading
+    // and material
+    let mut mesh_shading = selected_path
+        .and_then(|path| viewport_settings.mesh_shading_overrides.get(&path))
+        .copied()
+        .unwrap_or(viewport_settings.mesh_shading);
+    let previous_mesh_shading = mesh_shading;
+    let mut mesh_material = selected_path
+        .and_then(|path| viewport_settings.mesh_material_overrides.get(&path))
+        .copied()
+        .unwrap_or(viewport_settings.mesh_material);
+    let previous_mesh_material = mesh_material;
+    let previous_debug_view = viewport_settings.debug_view;
+    let previous_msaa = viewport_settings.msaa;
+    let mut attribute_names: Vec<String> = scene_meshes
+        .values()
+        .flat_map(|mesh| mesh.vertex_attributes())
+        .map(|attribute| attribute.name().to_string())
+        .collect();
+    attribute_names.sort();
+    attribute_names.dedup();
+    let locator_names: Vec<String> = sorted_locators(scene_locators)
+        .iter()
+        .map(|locator| locator.name().to_string())
+        .collect();
+    let reset_viewport = ui_frame.draw_viewport_settings_window(
+        &mut viewport_settings.draw_mesh_mode,
+        &mut viewport_settings.show_previous_result,
+        &mut viewport_settings.show_changes,
+        &mut viewport_settings.show_pipeline_stages,
+        &mut viewport_settings.compare_with_pinned,
+        &mut viewport_settings.auto_orbit,
+        &mut viewport_settings.exploded_view_factor,
+        &mut viewport_settings.background,
+        &mut viewport_settings.background_environment_path,
+        &mut viewport_settings.ground_shadow,
+        &mut viewport_settings.ground_grid,
+        &mut viewport_settings.axes,
+        &mut viewport_settings.topology,
+        &mut viewport_settings.post_processing_enabled,
+        &mut viewport_settings.post_processing,
+        &mut viewport_settings.section_enabled,
+        &mut viewport_settings.section,
+        &mut viewport_settings.attribute_colors_enabled,
+        &mut viewport_settings.attribute_colors_name,
+        &mut viewport_settings.attribute_colors_ramp,
+        &mut viewport_settings.assign_material_on_click,
+        &mut viewport_settings.assigned_material,
+        &mut viewport_settings.place_locator_on_click,
+        &attribute_names,
+        &mut viewport_settings.gizmo_mode,
+        &mut viewport_settings.camera_rotation_mode,
+        &mut viewport_settings.camera_projection,
+        &mut mesh_shading,
+        &mut mesh_material,
+        selected_path.is_some(),
+        &mut viewport_settings.debug_view,
+        &mut viewport_settings.msaa,
+        &mut viewport_settings.capture_scale,
+        &mut viewport_settings.capture_path,
+        &mut viewport_settings.camera_path,
+        &mut viewport_settings.camera_path_fps,
+        &locator_names,
+        camera_path_playing,
+        &mut viewport_settings.camera_path_command,
+    );
+    if mesh_shading != previous_mesh_shading {
+        match selected_path {
+            Some(path) => {
+                viewport_settings
+                    .mesh_shading_overrides
+                    .insert(path, mesh_shading);
+            }
+            None => viewport_settings.mesh_shading = mesh_shading,
+        }
+    }
+    if mesh_material != previous_mesh_material {
+        match selected_path {
+            Some(path) => {
+                viewport_settings
+                    .mesh_material_overrides
+                    .insert(path, mesh_material);
+            }
+            None => viewport_settings.mesh_material = mesh_material,
+        }
+    }
+    if viewport_settings.debug_view != previous_debug_view {
+        renderer.set_debug_view(viewport_settings.debug_view);
+    }
+    if viewport_settings.msaa != previous_msaa {
+        renderer.set_options(RendererOptions {
+            msaa: viewport_settings.msaa,
+            ..renderer.options().clone()
+        });
+    }
+    if viewport_settings.background_environment_path != previous_environment_path {
+        load_background_environment(renderer, viewport_settings, previous_background);
+    }
+    if viewport_settings.background != previous_background {
+        renderer.set_background(viewport_settings.background);
+    }
+    if (viewport_settings.section_enabled, viewport_settings.section) != previous_section {
+        renderer.set_section(if viewport_settings.section_enabled {
+            Some(viewport_settings.section)
+        } else {
+            None
+        });
+    }
+    if viewport_settings.compare_with_pinned {
+        ui_frame.draw_comparison_splitter(&mut viewport_settings.comparison_split);
+    }
+    ui_frame.draw_pipeline_window(session);
+    ui_frame.draw_operations_window(session);
+    ui_frame.draw_variants_window(session, &sorted_meshes(scene_meshes));
+    ui_frame.draw_optimizer_window(session);
+    ui_frame.draw_pareto_window(session);
+    ui_frame.draw_plot_window(session);
+    if let Some(sweep_coordinator) = sweep_coordinator {
+        update_sweep(ui_frame, sweep_coordinator, session);
+    }
+
+    let mut inspected_meshes: Vec<_> = scene_meshes
+        .iter()
+        .map(|(path, mesh)| (path.0, path.1, Arc::clone(mesh)))
+        .collect();
+    inspected_meshes.sort_by_key(|(var_ident, index, _)| (var_ident.0, *index));
+    ui_frame.draw_inspector_window(
+        session,
+        &inspected_meshes,
+        selected_path.map(|ValuePath(var_ident, index)| (var_ident, index)),
+    );
+
+    if let Some((entry_id, pinned)) = ui_frame.draw_history_window(session, history) {
+        history.set_pinned(entry_id, pinned);
+    }
+
+    reset_viewport
+}
+
+/// Loads the environment image at the path in the `viewport_settings`
+/// to the renderer. If it can't be loaded, the path is cleared and
+/// the `previous_background` is restored.
+fn load_background_environment(
+    renderer: &mut Renderer,
+    viewport_settings: &mut ViewportSettings,
+    previous_background: Background,
+) {
+    let path = match &viewport_settings.background_environment_path {
+        Some(path) => path,
+        None => return,
+    };
+
+    match importer::import_hdr_environment(path) {
+        Ok(image) => {
+            log::info!("Loaded background environment {}", path);
+            renderer.set_background_environment(image.width, image.height, &image.data);
+        }
+        Err(err) => {
+            log::error!("Failed to load background environment {}: {}", path, err);
+            viewport_settings.background_environment_path = None;
+            viewport_settings.background = previous_background;
+        }
+    }
+}
+
+/// Checks whether the window's hidpi factor changed, e.g. after it was
+/// moved to a monitor with different DPI. If so, UI fonts are rebuilt
+/// at the new scale and the screen textures are resized, as the
+/// physical size of the window changes with the factor.
+fn update_hidpi_factor(
+    window: &winit::window::Window,
+    ui: &mut Ui,
+    renderer: &mut Renderer,
+    camera: &mut Camera,
+) {
+    let hidpi_factor = window.hidpi_factor();
+    if ui.set_hidpi_factor(hidpi_factor) {
+        renderer.set_ui_font_atlas(ui.fonts());
+
+        let physical_size = window.inner_size().to_physical(hidpi_factor);
+        camera.set_window_size(physical_size);
+        renderer.set_window_size(physical_size);
+    }
+}
+
+/// Returns the meshes of the pinned results in the evaluation history.
+fn pinned_meshes(history: &EvaluationHistory) -> HashMap<HistoryPath, Arc<Mesh>> {
+    history
+        .pinned_entries()
+        .flat_map(|entry| {
+            entry
+                .meshes()
+                .iter()
+                .enumerate()
+                .map(move |(index, mesh)| (HistoryPath(entry.id(), index), Arc::clone(mesh)))
+        })
+        .collect()
+}
+
+/// Returns the meshes of the results evaluated before the most recent
+/// ones, for the variables displayed in the scene. These are displayed
+/// ghosted, if `enabled`.
+fn previous_meshes(
+    history: &EvaluationHistory,
+    scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+    enabled: bool,
+) -> HashMap<HistoryPath, Arc<Mesh>> {
+    let mut meshes = HashMap::new();
+    if enabled {
+        for ValuePath(var_ident, _) in scene_meshes.keys() {
+            if let Some(entry) = history.previous_entry(*var_ident) {
+                for (index, mesh) in entry.meshes().iter().enumerate() {
+                    meshes.insert(HistoryPath(entry.id(), index), Arc::clone(mesh));
+                }
+            }
+        }
+    }
+
+    meshes
+}
+
+/// Returns the meshes of the values used by other operations, i.e. the
+/// results of the earlier stages of the pipeline, if they are shown.
+fn stage_meshes(session: &Session, enabled: bool) -> HashMap<ValuePath, Arc<Mesh>> {
+    let mut meshes = HashMap::new();
+    if enabled {
+        for (var_ident, value) in session.used_values() {
+            match value {
+                Value::Mesh(mesh) => {
+                    meshes.insert(ValuePath(*var_ident, 0), Arc::clone(mesh));
+                }
+                Value::MeshArray(mesh_array) => {
+                    for (index, mesh) in mesh_array.iter_refcounted().enumerate() {
+                        meshes.insert(ValuePath(*var_ident, index), mesh);
+                    }
+                }
+                _ => (/* Ignore other values, we don't display them in the viewport */),
+            }
+        }
+    }
+
+    meshes
+}
+
+/// Returns the scene meshes ordered by their variables and indices.
+fn sorted_meshes(scene_meshes: &HashMap<ValuePath, Arc<Mesh>>) -> Vec<Arc<Mesh>> {
+    let mut meshes: Vec<_> = scene_meshes.iter().collect();
+    meshes.sort_by_key(|(path, _)| ((path.0).0, path.1));
+
+    meshes
+        .into_iter()
+        .map(|(_, mesh)| Arc::clone(mesh))
+        .collect()
+}
+
+/// Draws the scene, the viewport guides and the UI to the window. The
+/// frame is skipped, if rendering is paused or the renderer fails to
+/// start rendering it.
+#[allow(clippy::too_many_arguments)]
+fn render_frame(
+    renderer: &mut Renderer,
+    imgui_draw_data: &imgui::DrawData,
+    ground_grid: bool,
+    axes: bool,
+    draw_mesh_mode: DrawMeshMode,
+    comparison_split: Option<f32>,
+    scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    pinned_gpu_mesh_ids: &HashMap<HistoryPath, GpuMeshId>,
+    previous_gpu_mesh_ids: &HashMap<HistoryPath, GpuMeshId>,
+    stage_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    scene_gpu_curve_ids: &HashMap<VarIdent, GpuCurveId>,
+    scene_gpu_points_ids: &HashMap<VarIdent, GpuPointsId>,
+) {
+    if renderer.is_paused() {
+        return;
+    }
+
+    match renderer.begin_render_pass() {
+        Ok(mut render_pass) => {
+            draw_scene(
+                &mut render_pass,
+                false,
+                draw_mesh_mode,
+                comparison_split,
+                scene_gpu_mesh_ids,
+                pinned_gpu_mesh_ids,
+                previous_gpu_mesh_ids,
+                stage_gpu_mesh_ids,
+                scene_gpu_curve_ids,
+                scene_gpu_points_ids,
+            );
+            if ground_grid {
+                render_pass.draw_grid();
+            }
+            if axes {
+                render_pass.draw_axes();
+            }
+            render_pass.draw_ui(imgui_draw_data);
+
+            render_pass.submit();
+        }
+        Err(err) => log::warn!("Skipping frame: {}", err),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_scene(
+    render_pass: &mut RenderPass,
+    transparent_background: bool,
+    draw_mesh_mode: DrawMeshMode,
+    comparison_split: Option<f32>,
+    scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    pinned_gpu_mesh_ids: &HashMap<HistoryPath, GpuMeshId>,
+    previous_gpu_mesh_ids: &HashMap<HistoryPath, GpuMeshId>,
+    stage_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    scene_gpu_curve_ids: &HashMap<VarIdent, GpuCurveId>,
+    scene_gpu_points_ids: &HashMap<VarIdent, GpuPointsId>,
+) {
+    if transparent_background {
+        render_pass.clear_transparent();
+    } else {
+        render_pass.draw_background();
+    }
+
+    if let Some(split) = comparison_split {
+        // Current results (A) are drawn left of the splitter, pinned
+        // results (B) right of it
+        render_pass.draw_mesh_clipped(
+            scene_gpu_mesh_ids.values(),
+            draw_mesh_mode,
+            [0.0, 0.0, split, 1.0],
+        );
+        render_pass.draw_mesh_clipped(
+            previous_gpu_mesh_ids.values(),
+            DrawMeshMode::Ghosted,
+            [0.0, 0.0, split, 1.0],
+        );
+        render_pass.draw_mesh_clipped(
+            pinned_gpu_mesh_ids.values(),
+            draw_mesh_mode,
+            [split, 0.0, 1.0 - split, 1.0],
+        );
+        render_pass.draw_topology_clipped(scene_gpu_mesh_ids.values(), [0.0, 0.0, split, 1.0]);
+        render_pass.draw_curves_and_points_clipped(
+            scene_gpu_curve_ids.values(),
+            scene_gpu_points_ids.values(),
+            [0.0, 0.0, split, 1.0],
+        );
+        // The stages are translucent, so they have to come after
+        // everything opaque they could be in front of
+        render_pass.draw_mesh_clipped(
+            stage_gpu_mesh_ids.values(),
+            DrawMeshMode::Shaded,
+            [0.0, 0.0, split, 1.0],
+        );
+    } else {
+        render_pass.draw_mesh(scene_gpu_mesh_ids.values(), draw_mesh_mode);
+        render_pass.draw_mesh(
+            pinned_gpu_mesh_ids
+                .values()
+                .chain(previous_gpu_mesh_ids.values()),
+            DrawMeshMode::Ghosted,
+        );
+        render_pass.draw_topology(scene_gpu_mesh_ids.values());
+        render_pass
+            .draw_curves_and_points(scene_gpu_curve_ids.values(), scene_gpu_points_ids.values());
+        // The stages are translucent, so they have to come after
+        // everything opaque they could be in front of
+        render_pass.draw_mesh(stage_gpu_mesh_ids.values(), DrawMeshMode::Shaded);
+    }
+
+    render_pass.draw_ground_shadow();
+}
+
+/// Keeps the ground shadow of the renderer in sync with the scene
+/// meshes. The shadow is only recomputed when the meshes change.
+#[derive(Debug, Default)]
+struct GroundShadowTracker {
+    /// The meshes casting the current shadow, or `None` if there is
+    /// no ground shadow.
+    meshes: Option<Vec<Arc<Mesh>>>,
+}
+
+impl GroundShadowTracker {
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        enabled: bool,
+        scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+    ) {
+        if !enabled {
+            if self.meshes.take().is_some() {
+                renderer.set_ground_shadow(None);
+            }
+            return;
+        }
+
+        let meshes = sorted_meshes(scene_meshes);
+        let changed = match &self.meshes {
+            Some(previous_meshes) => !same_meshes(previous_meshes, &meshes),
+            None => true,
+        };
+
+        if changed {
+            let ground_shadow = GroundShadow::from_meshes(meshes.iter().map(Arc::as_ref));
+            renderer.set_ground_shadow(ground_shadow.as_ref());
+            self.meshes = Some(meshes);
+        }
+    }
+}
+
+/// Keeps the scene meshes moved apart from the center of the scene,
+/// so that assemblies of separate meshes, e.g. the islands of a
+/// disjoint mesh, are easier to see. The transforms are only updated
+/// when the meshes or the factor change.
+#[derive(Debug, Default)]
+struct ExplodedViewTracker {
+    factor: f32,
+    meshes: Vec<Arc<Mesh>>,
+    offsets: HashMap<ValuePath, Vector3<f32>>,
+}
+
+impl ExplodedViewTracker {
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        factor: f32,
+        scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+        scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    ) {
+        let meshes = sorted_meshes(scene_meshes);
+        if (factor - self.factor).abs() < f32::EPSILON && same_meshes(&self.meshes, &meshes) {
+            return;
+        }
+
+        let scene_center = BoundingBox::union(meshes.iter().map(|mesh| mesh.bounding_box()))
+            .map_or_else(Point3::origin, |bounding_box| bounding_box.center());
+
+        self.offsets.clear();
+        for (path, mesh) in scene_meshes {
+            let offset = (mesh.bounding_box().center() - scene_center) * factor;
+            if let Some(gpu_mesh_id) = scene_gpu_mesh_ids.get(path) {
+                renderer.set_scene_mesh_transform(*gpu_mesh_id, &Matrix4::new_translation(&offset));
+            }
+            self.offsets.insert(*path, offset);
+        }
+
+        self.factor = factor;
+        self.meshes = meshes;
+    }
+
+    /// Returns how far the scene mesh is currently moved from its
+    /// original position.
+    fn offset(&self, path: ValuePath) -> Vector3<f32> {
+        self.offsets
+            .get(&path)
+            .copied()
+            .unwrap_or_else(Vector3::zeros)
+    }
+}
+
+/// Keeps copies of the scene meshes colored by one of their vertex
+/// attributes. The values are mapped through the color ramp from the
+/// range of the attribute over all the meshes, so that the meshes can
+/// be compared by color. The colors are only recomputed when the
+/// meshes or the settings change.
+#[derive(Debug, Default)]
+struct AttributeColorTracker {
+    /// The attribute name and the ramp of the current colors, or
+    /// `None` if the meshes are not colored.
+    settings: Option<(String, ColorRamp)>,
+    meshes: Vec<Arc<Mesh>>,
+    colored_meshes: HashMap<ValuePath, Arc<Mesh>>,
+}
+
+impl AttributeColorTracker {
+    /// Returns the meshes to display in the scene. These are the
+    /// `scene_meshes`, with the meshes having the attribute replaced by
+    /// their colored copies.
+    fn update<'a>(
+        &'a mut self,
+        settings: Option<(&str, ColorRamp)>,
+        scene_meshes: &'a HashMap<ValuePath, Arc<Mesh>>,
+    ) -> &'a HashMap<ValuePath, Arc<Mesh>> {
+        let (attribute_name, ramp) = match settings {
+            Some(settings) => settings,
+            None => {
+                if self.settings.take().is_some() {
+                    self.meshes.clear();
+                    self.colored_meshes.clear();
+                }
+                return scene_meshes;
+            }
+        };
+
+        let meshes = sorted_meshes(scene_meshes);
+        let changed = match &self.settings {
+            Some((previous_name, previous_ramp)) => {
+                previous_name != attribute_name
+                    || *previous_ramp != ramp
+                    || !same_meshes(&self.meshes, &meshes)
+            }
+            None => true,
+        };
+
+        if changed {
+            let range = scene_meshes
+                .values()
+                .filter_map(|mesh| mesh.vertex_attribute(attribute_name))
+                .filter_map(|attribute| color_ramp::compute_range(attribute.values()))
+                .fold(None, |range, (min, max)| match range {
+                    Some((range_min, range_max)) => {
+                        Some((f32::min(range_min, min), f32::max(range_max, max)))
+                    }
+                    None => Some((min, max)),
+                });
+
+            self.colored_meshes = scene_meshes
+                .iter()
+                .map(|(path, mesh)| {
+                    let colored_mesh = match (mesh.vertex_attribute(attribute_name), range) {
+                        (Some(attribute), Some((min, max))) => {
+                            let mut colored_mesh = Mesh::clone(mesh);
+                            colored_mesh.set_vertex_colors(color_ramp::remap_to_colors(
+                                attribute.values(),
+                                ramp,
+                                min,
+                                max,
+                            ));
+                            Arc::new(colored_mesh)
+                        }
+                        _ => Arc::clone(mesh),
+                    };
+                    (*path, colored_mesh)
+                })
+                .collect();
+            self.settings = Some((attribute_name.to_string(), ramp));
+            self.meshes = meshes;
+        }
+
+        &self.colored_meshes
+    }
+}
+
+/// Keeps the scene meshes replaced by the "What changed" view of them,
+/// where faces added, moved and removed since the previous result of
+/// the same variable are highlighted. Meshes without a previous result
+/// are kept as they are. The views are only rebuilt when the meshes
+/// change.
+#[derive(Debug, Default)]
+struct ChangeTracker {
+    /// The compared previous and current mesh and the view of their
+    /// changes for each scene mesh.
+    views: HashMap<ValuePath, (Option<Arc<Mesh>>, Arc<Mesh>, Arc<Mesh>)>,
+    meshes: HashMap<ValuePath, Arc<Mesh>>,
+}
+
+impl ChangeTracker {
+    /// Returns the meshes to display in the scene. These are the
+    /// `scene_meshes`, if the view is not `enabled`.
+    fn update<'a>(
+        &'a mut self,
+        enabled: bool,
+        history: &EvaluationHistory,
+        scene_meshes: &'a HashMap<ValuePath, Arc<Mesh>>,
+    ) -> &'a HashMap<ValuePath, Arc<Mesh>> {
+        if !enabled {
+            self.views.clear();
+            self.meshes.clear();
+            return scene_meshes;
+        }
+
+        self.views.retain(|path, _| scene_meshes.contains_key(path));
+        self.meshes.clear();
+        for (path, mesh) in scene_meshes {
+            let ValuePath(var_ident, index) = *path;
+            let previous_mesh = history
+                .previous_entry(var_ident)
+                .and_then(|entry| entry.meshes().get(index))
+                .cloned();
+
+            let up_to_date = self
+                .views
+                .get(path)
+                .map_or(false, |(previous, current, _)| {
+                    Arc::ptr_eq(current, mesh)
+                        && match (previous, &previous_mesh) {
+                            (Some(previous), Some(previous_mesh)) => {
+                                Arc::ptr_eq(previous, previous_mesh)
+                            }
+                            (None, None) => true,
+                            _ => false,
+                        }
+                });
+
+            if !up_to_date {
+                let view = match &previous_mesh {
+                    Some(previous_mesh) => Arc::new(change_view_mesh(previous_mesh, mesh)),
+                    None => Arc::clone(mesh),
+                };
+                self.views
+                    .insert(*path, (previous_mesh, Arc::clone(mesh), view));
+            }
+
+            let (_, _, view) = &self.views[path];
+            self.meshes.insert(*path, Arc::clone(view));
+        }
+
+        &self.meshes
+    }
+}
+
+/// Creates a mesh with the faces of the `current` mesh colored by how
+/// they changed since the `previous` mesh, together with the removed
+/// faces of the previous mesh. The faces don't share vertices, so that
+/// each of them can have its own color.
+fn change_view_mesh(previous: &Mesh, current: &Mesh) -> Mesh {
+    let tolerance = BoundingBox::union(vec![previous.bounding_box(), current.bounding_box()])
+        .map_or(0.0, |bounding_box| bounding_box.diagonal().norm())
+        * CHANGE_RELATIVE_TOLERANCE;
+    let diff = analysis::diff_faces(previous, current, tolerance);
+
+    let changed_faces = current
+        .faces()
+        .iter()
+        .zip(&diff.current_faces)
+        .map(|(face, change)| {
+            let color = match change {
+                FaceChange::Unchanged => CHANGE_UNCHANGED_COLOR,
+                FaceChange::Moved => CHANGE_MOVED_COLOR,
+                FaceChange::Added => CHANGE_ADDED_COLOR,
+            };
+            (current, face, color)
+        });
+    let removed_faces = diff.removed_faces.iter().map(|face_index| {
+        (
+            previous,
+            &previous.faces()[*face_index],
+            CHANGE_REMOVED_COLOR,
+        )
+    });
+
+    let mut faces = Vec::new();
+    let mut vertices = Vec::new();
+    let mut colors = Vec::new();
+    for (mesh, face, color) in changed_faces.chain(removed_faces) {
+        let first_vertex = cast_u32(vertices.len());
+        for vertex_index in &face.vertices() {
+            vertices.push(mesh.vertices()[cast_usize(*vertex_index)]);
+            colors.push(color);
+        }
+        faces.push(match face {
+            Face::Triangle(_) => Face::from(TriangleFace::new(
+                first_vertex,
+                first_vertex + 1,
+                first_vertex + 2,
+            )),
+            Face::Quad(_) => Face::from(QuadFace::new(
+                first_vertex,
+                first_vertex + 1,
+                first_vertex + 2,
+                first_vertex + 3,
+            )),
+        });
+    }
+
+    let mut view =
+        Mesh::from_faces_with_vertices_and_computed_normals(faces, vertices, NormalStrategy::Sharp);
+    view.set_vertex_colors(colors);
+
+    view
+}
+
+/// Keeps bounding volume hierarchies of the scene meshes for picking
+/// them with the cursor. A hierarchy is only built on the first pick
+/// after its mesh changes.
+#[derive(Debug, Default)]
+struct ScenePicker {
+    bvhs: HashMap<ValuePath, (Arc<Mesh>, Bvh)>,
+}
+
+impl ScenePicker {
+    /// Finds the closest face of the scene meshes hit by the ray,
+    /// including the offsets of the exploded view. Returns the path
+    /// of the hit mesh, the index of the hit face and the hit point
+    /// in the space of the mesh, i.e. without the exploded view
+    /// offset.
+    fn pick(
+        &mut self,
+        ray_origin: &Point3<f32>,
+        ray_direction: &Vector3<f32>,
+        scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+        exploded_view_tracker: &ExplodedViewTracker,
+    ) -> Option<(ValuePath, usize, Point3<f32>)> {
+        self.bvhs
+            .retain(|path, (bvh_mesh, _)| match scene_meshes.get(path) {
+                Some(mesh) => Arc::ptr_eq(mesh, bvh_mesh),
+                None => false,
+            });
+
+        let mut closest: Option<(f32, ValuePath, usize, Point3<f32>)> = None;
+        for (path, mesh) in scene_meshes {
+            let (_, bvh) = self
+                .bvhs
+                .entry(*path)
+                .or_insert_with(|| (Arc::clone(mesh), Bvh::from_mesh(mesh)));
+
+            let mesh_ray_origin = ray_origin - exploded_view_tracker.offset(*path);
+            if let Some(hit) = bvh.ray_intersection(&mesh_ray_origin, ray_direction) {
+                let closer = closest.map_or(true, |(closest_distance, _, _, _)| {
+                    hit.distance < closest_distance
+                });
+                if closer {
+                    let position = mesh_ray_origin + ray_direction * hit.distance;
+                    closest = Some((hit.distance, *path, hit.face_index, position));
+                }
+            }
+        }
+
+        closest.map(|(_, path, face_index, position)| (path, face_index, position))
+    }
+}
+
+/// Keeps the mesh selected in the viewport highlighted. The selection
+/// is kept by value path, so it survives re-evaluation of the
+/// selected value.
+#[derive(Debug, Default)]
+struct SelectionTracker {
+    path: Option<ValuePath>,
+    highlighted_gpu_mesh_id: Option<GpuMeshId>,
+}
+
+impl SelectionTracker {
+    fn selected_path(&self) -> Option<ValuePath> {
+        self.path
+    }
+
+    fn select(&mut self, path: Option<ValuePath>) {
+        if path != self.path {
+            match path {
+                Some(ValuePath(var_ident, index)) => {
+                    log::info!("Selected mesh {} of {}", index, var_ident)
+                }
+                None => log::info!("Cleared selection"),
+            }
+            self.path = path;
+        }
+    }
+
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    ) {
+        let gpu_mesh_id = self
+            .path
+            .and_then(|path| scene_gpu_mesh_ids.get(&path).copied());
+        if gpu_mesh_id == self.highlighted_gpu_mesh_id {
+            return;
+        }
+
+        // The previously highlighted mesh may have already been
+        // removed by re-evaluation
+        if let Some(highlighted_gpu_mesh_id) = self.highlighted_gpu_mesh_id {
+            if scene_gpu_mesh_ids
+                .values()
+                .any(|id| *id == highlighted_gpu_mesh_id)
+            {
+                renderer.set_scene_mesh_tint(highlighted_gpu_mesh_id, None);
+            }
+        }
+        if let Some(gpu_mesh_id) = gpu_mesh_id {
+            renderer.set_scene_mesh_tint(gpu_mesh_id, Some(SELECTION_TINT));
+        }
+
+        self.highlighted_gpu_mesh_id = gpu_mesh_id;
+    }
+}
+
+/// Keeps the shading and material of scene meshes in sync with the
+/// viewport settings. Only meshes whose shading or material differs
+/// from what was last set on them are updated.
+#[derive(Debug, Default)]
+struct ShadingTracker {
+    shadings: HashMap<GpuMeshId, MeshShading>,
+    materials: HashMap<GpuMeshId, Material>,
+}
+
+impl ShadingTracker {
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        default_shading: MeshShading,
+        shading_overrides: &HashMap<ValuePath, MeshShading>,
+        default_material: Material,
+        material_overrides: &HashMap<ValuePath, Material>,
+        scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    ) {
+        // Meshes removed by re-evaluation are forgotten
+        self.shadings
+            .retain(|gpu_mesh_id, _| scene_gpu_mesh_ids.values().any(|id| id == gpu_mesh_id));
+        self.materials
+            .retain(|gpu_mesh_id, _| scene_gpu_mesh_ids.values().any(|id| id == gpu_mesh_id));
+
+        for (path, gpu_mesh_id) in scene_gpu_mesh_ids {
+            let shading = shading_overrides
+                .get(path)
+                .copied()
+                .unwrap_or(default_shading);
+            // New meshes start with the default renderer shading
+            let current_shading = self.shadings.get(gpu_mesh_id).copied().unwrap_or_default();
+            if shading != current_shading {
+                renderer.set_scene_mesh_shading(*gpu_mesh_id, shading);
+                self.shadings.insert(*gpu_mesh_id, shading);
+            }
+
+            let material = material_overrides
+                .get(path)
+                .copied()
+                .unwrap_or(default_material);
+            let current_material = self.materials.get(gpu_mesh_id).copied().unwrap_or_default();
+            if material != current_material {
+                renderer.set_scene_mesh_material(*gpu_mesh_id, material);
+                self.materials.insert(*gpu_mesh_id, material);
+            }
+        }
+    }
+}
+
+/// Keeps the topology overlay of scene meshes in sync with the
+/// viewport settings. The topology of a mesh is only analyzed while
+/// the overlay is enabled, once per mesh.
+#[derive(Debug, Default)]
+struct TopologyTracker {
+    gpu_mesh_ids: HashSet<GpuMeshId>,
+}
+
+impl TopologyTracker {
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        enabled: bool,
+        scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+        scene_gpu_mesh_ids: &HashMap<ValuePath, GpuMeshId>,
+    ) {
+        // Meshes removed by re-evaluation take their topology with them
+        self.gpu_mesh_ids
+            .retain(|gpu_mesh_id| scene_gpu_mesh_ids.values().any(|id| id == gpu_mesh_id));
+
+        if !enabled {
+            for gpu_mesh_id in self.gpu_mesh_ids.drain() {
+                renderer.set_scene_mesh_topology(gpu_mesh_id, None);
+            }
+            return;
+        }
+
+        for (path, gpu_mesh_id) in scene_gpu_mesh_ids {
+            if self.gpu_mesh_ids.contains(gpu_mesh_id) {
+                continue;
+            }
+            if let Some(mesh) = scene_meshes.get(path) {
+                let topology = GpuMeshTopology::from_mesh(mesh);
+                renderer.set_scene_mesh_topology(*gpu_mesh_id, Some(&topology));
+                self.gpu_mesh_ids.insert(*gpu_mesh_id);
+            }
+        }
+    }
+}
+
+/// Keeps the values uploaded on the GPU, like curves or points, in
+/// sync with the values of the scene. A value is only re-uploaded when
+/// it changes.
+#[derive(Debug)]
+struct GpuValueTracker<T, I> {
+    values: HashMap<VarIdent, Arc<T>>,
+    gpu_ids: HashMap<VarIdent, I>,
+}
+
+impl<T, I> Default for GpuValueTracker<T, I> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            gpu_ids: HashMap::new(),
+        }
+    }
+}
+
+impl<T, I> GpuValueTracker<T, I> {
+    fn update<A, R>(
+        &mut self,
+        renderer: &mut Renderer,
+        scene_values: &HashMap<VarIdent, Arc<T>>,
+        mut add: A,
+        mut remove: R,
+    ) where
+        A: FnMut(&mut Renderer, &T) -> I,
+        R: FnMut(&mut Renderer, I),
+    {
+        let changed_or_removed: Vec<VarIdent> = self
+            .values
+            .iter()
+            .filter(|(var_ident, value)| {
+                scene_values
+                    .get(*var_ident)
+                    .map_or(true, |scene_value| !Arc::ptr_eq(scene_value, *value))
+            })
+            .map(|(var_ident, _)| *var_ident)
+            .collect();
+
+        for var_ident in changed_or_removed {
+            self.values.remove(&var_ident);
+            if let Some(gpu_id) = self.gpu_ids.remove(&var_ident) {
+                remove(renderer, gpu_id);
+            }
+        }
+
+        for (var_ident, value) in scene_values {
+            if !self.values.contains_key(var_ident) {
+                let gpu_id = add(renderer, value.as_ref());
+                self.values.insert(*var_ident, Arc::clone(value));
+                self.gpu_ids.insert(*var_ident, gpu_id);
+            }
+        }
+    }
+
+    fn gpu_ids(&self) -> &HashMap<VarIdent, I> {
+        &self.gpu_ids
+    }
+}
+
+/// What the viewport gizmo edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GizmoTarget {
+    /// The Transform statement at the index.
+    Transform(usize),
+    /// A single mesh not produced by a Transform. A Transform of it is
+    /// added to the program once the gizmo is dragged.
+    Mesh(ValuePath),
+}
+
+/// Lets the viewport gizmo edit the parameters of the Transform
+/// producing the selected mesh.
+#[derive(Debug, Default)]
+struct GizmoTracker {
+    gizmo: Gizmo,
+    target: Option<GizmoTarget>,
+}
+
+impl GizmoTracker {
+    /// Grabs, drags and releases the gizmo at the `origin` and edits
+    /// the program accordingly. Returns whether the gizmo was dragged
+    /// this frame, in which case the mouse input is taken by the
+    /// gizmo.
+    #[allow(clippy::too_many_arguments)]
+    fn update(
+        &mut self,
+        camera: &Camera,
+        window_size: [f32; 2],
+        cursor_position: [f32; 2],
+        mode: GizmoMode,
+        origin: Option<Point3<f32>>,
+        input_state: &InputState,
+        session: &mut Session,
+        selection_tracker: &mut SelectionTracker,
+    ) -> bool {
+        if window_size[0] <= 0.0 || window_size[1] <= 0.0 {
+            return self.gizmo.is_dragging();
+        }
+
+        if let (Some(press), Some(origin), Some(path)) = (
+            input_state.viewport_press,
+            origin,
+            selection_tracker.selected_path(),
+        ) {
+            let target = gizmo_target(session, path);
+            let params = match target {
+                Some(GizmoTarget::Transform(stmt_index)) => {
+                    transform_params_at(session, stmt_index)
+                }
+                Some(GizmoTarget::Mesh(_)) => Some(TransformParams::default()),
+                None => None,
+            };
+
+            if let Some(params) = params {
+                if self
+                    .gizmo
+                    .start_drag(camera, window_size, mode, &origin, press, params)
+                {
+                    self.target = target;
+                }
+            }
+        }
+
+        let dragging = self.gizmo.is_dragging();
+        if dragging {
+            let params = self.gizmo.drag(camera, window_size, cursor_position);
+            // Edits are only submitted once the interpreter is done
+            // with the previous ones. The drag is relative to where it
+            // started, so no movement is lost.
+            if let (Some(params), false) = (params, session.interpreter_busy()) {
+                match self.target {
+                    Some(GizmoTarget::Transform(stmt_index)) => {
+                        set_transform_params(session, stmt_index, params);
+                    }
+                    Some(GizmoTarget::Mesh(path)) => {
+                        if params != TransformParams::default() {
+                            let stmt_index = add_transform(session, path, params);
+                            let ValuePath(_, index) = path;
+                            let var_ident = match &session.stmts()[stmt_index] {
+                                ast::Stmt::VarDecl(var_decl) => var_decl.ident(),
+                            };
+                            selection_tracker.select(Some(ValuePath(var_ident, index)));
+                            self.target = Some(GizmoTarget::Transform(stmt_index));
+                        }
+                    }
+                    None => (),
+                }
+            }
+
+            if input_state.lmb_released {
+                self.gizmo.end_drag();
+                self.target = None;
+            }
+        }
+
+        dragging
+    }
+
+    fn handles(
+        &self,
+        camera: &Camera,
+        window_size: [f32; 2],
+        cursor_position: [f32; 2],
+        mode: GizmoMode,
+        origin: Option<Point3<f32>>,
+    ) -> Vec<GizmoHandle> {
+        if window_size[0] <= 0.0 || window_size[1] <= 0.0 {
+            return Vec::new();
+        }
+
+        // The edited mesh may be missing while it is re-evaluated,
+        // but the gizmo stays where the drag started
+        let origin = match origin {
+            Some(origin) => origin,
+            None if self.gizmo.is_dragging() => Point3::origin(),
+            None => return Vec::new(),
+        };
+
+        self.gizmo
+            .handles(camera, window_size, mode, &origin, cursor_position)
+    }
+}
+
+/// Returns what the gizmo edits for the selected value, if it can be
+/// edited.
+fn gizmo_target(session: &Session, path: ValuePath) -> Option<GizmoTarget> {
+    let ValuePath(var_ident, _) = path;
+    let stmt_index = session.stmts().iter().position(|stmt| match stmt {
+        ast::Stmt::VarDecl(var_decl) => var_decl.ident() == var_ident,
+    })?;
+
+    if transform_params_at(session, stmt_index).is_some() {
+        return Some(GizmoTarget::Transform(stmt_index));
+    }
+
+    // Only single meshes can be transformed, not groups
+    let is_mesh_var = session
+        .visible_vars_at_stmt(session.stmts().len(), Ty::Mesh)
+        .any(|visible_var_ident| visible_var_ident == var_ident);
+    if is_mesh_var {
+        Some(GizmoTarget::Mesh(path))
+    } else {
+        None
+    }
+}
+
+/// Returns where the gizmo is shown for the selected mesh: at the
+/// center of its bounding box, as displayed in the viewport.
+fn selected_gizmo_origin(
+    session: &Session,
+    selected_path: Option<ValuePath>,
+    scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+    exploded_view_tracker: &ExplodedViewTracker,
+) -> Option<Point3<f32>> {
+    let path = selected_path?;
+    gizmo_target(session, path)?;
+
+    let mesh = scene_meshes.get(&path)?;
+    Some(mesh.bounding_box().center() + exploded_view_tracker.offset(path))
+}
+
+/// Returns the parameters of the Transform statement at the index, if
+/// it is one and the parameters are literals not bound to constants.
+fn transform_params_at(session: &Session, stmt_index: usize) -> Option<TransformParams> {
+    const TRANSLATE_ARG_INDEX: usize = 1;
+    const ROTATE_ARG_INDEX: usize = 2;
+    const SCALE_ARG_INDEX: usize = 3;
+
+    let init_expr = match session.stmts().get(stmt_index)? {
+        ast::Stmt::VarDecl(var_decl) => var_decl.init_expr(),
+    };
+    if init_expr.ident() != interpreter_funcs::FUNC_ID_TRANSFORM {
+        return None;
+    }
+
+    let float3_arg = |arg_index: usize| {
+        if session.param_constant(stmt_index, arg_index).is_some() {
+            return None;
+        }
+        match init_expr.args().get(arg_index) {
+            Some(ast::Expr::Lit(ast::LitExpr::Float3(value))) => Some(*value),
+            _ => None,
+        }
+    };
+
+    Some(TransformParams {
+        translate: float3_arg(TRANSLATE_ARG_INDEX)?,
+        rotate: float3_arg(ROTATE_ARG_INDEX)?,
+        scale: float3_arg(SCALE_ARG_INDEX)?,
+    })
+}
+
+/// Sets the parameters of the Transform statement at the index, if
+/// they changed.
+fn set_transform_params(session: &mut Session, stmt_index: usize, params: TransformParams) {
+    match transform_params_at(session, stmt_index) {
+        Some(current_params) if current_params != params => (),
+        _ => return,
+    }
+
+    let stmt = match &session.stmts()[stmt_index] {
+        ast::Stmt::VarDecl(var_decl) => {
+            let init_expr = var_decl
+                .init_expr()
+                .clone_with_arg_at(1, ast::Expr::Lit(ast::LitExpr::Float3(params.translate)))
+                .clone_with_arg_at(2, ast::Expr::Lit(ast::LitExpr::Float3(params.rotate)))
+                .clone_with_arg_at(3, ast::Expr::Lit(ast::LitExpr::Float3(params.scale)));
+
+            ast::Stmt::VarDecl(var_decl.clone_with_init_expr(init_expr))
+        }
+    };
+    session.set_prog_stmt_at(stmt_index, stmt);
+    interpret_unless_busy(session);
+}
+
+/// Adds a Transform of the mesh at the `path` to the program. Returns
+/// the index of the added statement.
+fn add_transform(session: &mut Session, path: ValuePath, params: TransformParams) -> usize {
+    let ValuePath(var_ident, _) = path;
+    log::info!("Adding a Transform of {}", var_ident);
+
+    let args = vec![
+        ast::Expr::Var(ast::VarExpr::new(var_ident)),
+        ast::Expr::Lit(ast::LitExpr::Float3(params.translate)),
+        ast::Expr::Lit(ast::LitExpr::Float3(params.rotate)),
+        ast::Expr::Lit(ast::LitExpr::Float3(params.scale)),
+        ast::Expr::Lit(ast::LitExpr::Boolean(true)),
+    ];
+    let init_expr = ast::CallExpr::new(interpreter_funcs::FUNC_ID_TRANSFORM, args);
+    push_stmt_and_interpret(session, init_expr);
+
+    session.stmts().len() - 1
+}
+
+/// Adds a statement calling `init_expr` to the end of the program and
+/// runs the program.
+fn push_stmt_and_interpret(session: &mut Session, init_expr: ast::CallExpr) {
+    let stmt = ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+        session.next_free_var_ident(),
+        init_expr,
+    ));
+
+    session.push_prog_stmt(stmt);
+    interpret_unless_busy(session);
+}
+
+/// Runs the program after it was edited from the viewport.
+fn interpret_unless_busy(session: &mut Session) {
+    // Editing the program may have already started the interpreter,
+    // if running it automatically is enabled
+    if !session.interpreter_busy() {
+        session.interpret();
+    }
+}
+
+/// Casts a ray from the camera through the clicked point and finds
+/// the closest hit face of the scene meshes.
+fn pick_clicked_face(
+    camera: &Camera,
+    window_size: winit::dpi::LogicalSize,
+    click: ViewportClick,
+    scene_picker: &mut ScenePicker,
+    scene_meshes: &HashMap<ValuePath, Arc<Mesh>>,
+    exploded_view_tracker: &ExplodedViewTracker,
+) -> Option<(ValuePath, usize, Point3<f32>)> {
+    if window_size.width <= 0.0 || window_size.height <= 0.0 {
+        return None;
+    }
+
+    let [x, y] = click.position;
+    let screen_position = [x / window_size.width as f32, y / window_size.height as f32];
+    let (ray_origin, ray_direction) = camera.ray(screen_position);
+
+    scene_picker.pick(
+        &ray_origin,
+        &ray_direction,
+        scene_meshes,
+        exploded_view_tracker,
+    )
+}
+
+/// Reverts the clicked face of a scene mesh by adding a "Revert
+/// Face" operation to the program. Clicking with shift reverts the
+/// whole consistently wound patch around the face.
+fn revert_clicked_face(session: &mut Session, path: ValuePath, face_index: usize, shift: bool) {
+    let ValuePath(var_ident, _) = path;
+
+    if session.interpreter_busy() {
+        log::warn!("Can not revert faces while the pipeline is running");
+        return;
+    }
+
+    let is_mesh_var = session
+        .visible_vars_at_stmt(session.stmts().len(), Ty::Mesh)
+        .any(|visible_var_ident| visible_var_ident == var_ident);
+    if !is_mesh_var {
+        log::warn!("Faces can only be reverted on single meshes, not on groups");
+        return;
+    }
+
+    log::info!(
+        "Reverting {} {} of {}",
+        if shift { "patch around face" } else { "face" },
+        face_index,
+        var_ident,
+    );
+
+    let args = vec![
+        ast::Expr::Var(ast::VarExpr::new(var_ident)),
+        ast::Expr::Lit(ast::LitExpr::Uint(cast_u32(face_index))),
+        ast::Expr::Lit(ast::LitExpr::Boolean(shift)),
+    ];
+    let init_expr = ast::CallExpr::new(interpreter_funcs::FUNC_ID_REVERT_FACE, args);
+    push_stmt_and_interpret(session, init_expr);
+}
+
+/// Assigns the material to the clicked face of a scene mesh by adding
+/// an "Assign Material" operation to the program. Clicking with shift
+/// assigns it to the whole connected patch around the face.
+fn assign_clicked_face_material(
+    session: &mut Session,
+    path: ValuePath,
+    face_index: usize,
+    material: u32,
+    shift: bool,
+) {
+    let ValuePath(var_ident, _) = path;
+
+    if session.interpreter_busy() {
+        log::warn!("Can not assign materials while the pipeline is running");
+        return;
+    }
+
+    let is_mesh_var = session
+        .visible_vars_at_stmt(session.stmts().len(), Ty::Mesh)
+        .any(|visible_var_ident| visible_var_ident == var_ident);
+    if !is_mesh_var {
+        log::warn!("Materials can only be assigned on single meshes, not on groups");
+        return;
+    }
+
+    log::info!(
+        "Assigning material {} to {} {} of {}",
+        material,
+        if shift { "patch around face" } else { "face" },
+        face_index,
+        var_ident,
+    );
+
+    let args = vec![
+        ast::Expr::Var(ast::VarExpr::new(var_ident)),
+        ast::Expr::Lit(ast::LitExpr::Uint(material)),
+        ast::Expr::Lit(ast::LitExpr::Boolean(false)),
+        ast::Expr::Lit(ast::LitExpr::Uint(cast_u32(face_index))),
+        ast::Expr::Lit(ast::LitExpr::Boolean(shift)),
+    ];
+    let init_expr = ast::CallExpr::new(interpreter_funcs::FUNC_ID_ASSIGN_MATERIAL, args);
+    push_stmt_and_interpret(session, init_expr);
+}
+
+/// Adds a locator on the clicked face of a scene mesh by adding a
+/// "Create Locator" operation to the program. The Z axis of the
+/// locator is the normal of the face.
+fn place_clicked_locator(
+    session: &mut Session,
+    mesh: &Mesh,
+    face_index: usize,
+    position: Point3<f32>,
+) {
+    if session.interpreter_busy() {
+        log::warn!("Can not place locators while the pipeline is running");
+        return;
+    }
+
+    let vertices = mesh.vertices();
+    let face_cross = match mesh.faces()[face_index] {
+        Face::Triangle(triangle_face) => {
+            let (v1, v2, v3) = triangle_face.vertices;
+            let p1 = vertices[cast_usize(v1)];
+            (vertices[cast_usize(v2)] - p1).cross(&(vertices[cast_usize(v3)] - p1))
+        }
+        Face::Quad(quad_face) => {
+            // The cross product of the diagonals is perpendicular to
+            // both halves of a planar quad
+            let (v1, v2, v3, v4) = quad_face.vertices;
+            (vertices[cast_usize(v3)] - vertices[cast_usize(v1)])
+                .cross(&(vertices[cast_usize(v4)] - vertices[cast_usize(v2)]))
+        }
+    };
+
+    // Degenerate faces have no normal, the locator then stays upright
+    let normal = face_cross
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::z);
+    let (roll, pitch, yaw) = Locator::from_origin_and_normal("", position, &normal)
+        .rotation()
+        .euler_angles();
+
+    let locator_count = session
+        .visible_vars_at_stmt(session.stmts().len(), Ty::Locator)
+        .count();
+    let name = format!("Locator {}", locator_count + 1);
+
+    log::info!(
+        "Placing {} at [{}, {}, {}]",
+        name,
+        position.x,
+        position.y,
+        position.z,
+    );
+
+    let args = vec![
+        ast::Expr::Lit(ast::LitExpr::String(Arc::new(name))),
+        ast::Expr::Lit(ast::LitExpr::Float3([position.x, position.y, position.z])),
+        ast::Expr::Lit(ast::LitExpr::Float3([
+            roll.to_degrees(),
+            pitch.to_degrees(),
+            yaw.to_degrees(),
+        ])),
+    ];
+    let init_expr = ast::CallExpr::new(interpreter_funcs::FUNC_ID_CREATE_LOCATOR, args);
+    push_stmt_and_interpret(session, init_expr);
+}
+
+/// Projects the scene locators to the screen for drawing, ordered by
+/// their variables.
+fn locator_markers(
+    camera: &Camera,
+    window_size: [f32; 2],
+    scene_locators: &HashMap<VarIdent, Arc<Locator>>,
+) -> Vec<LocatorMarker> {
+    let [width, height] = window_size;
+    if width <= 0.0 || height <= 0.0 {
+        return Vec::new();
+    }
+
+    let (_, visible_radius) = camera.visible_sphere();
+    let size = visible_radius * LOCATOR_RELATIVE_SIZE;
+    let to_pixels =
+        |point: &Point3<f32>| camera.project(point).map(|[x, y]| [x * width, y * height]);
+
+    sorted_locators(scene_locators)
+        .into_iter()
+        .filter_map(|locator| {
+            let origin = locator.origin();
+            let axis_end = |axis: Vector3<f32>| to_pixels(&(origin + axis * size));
+
+            Some(LocatorMarker {
+                name: locator.name().to_string(),
+                origin: to_pixels(&origin)?,
+                axis_ends: [
+                    axis_end(locator.x_axis()),
+                    axis_end(locator.y_axis()),
+                    axis_end(locator.z_axis()),
+                ],
+            })
+        })
+        .collect()
+}
+
+/// The scene locators ordered by their variables, the same way the UI
+/// lists them.
+fn sorted_locators(scene_locators: &HashMap<VarIdent, Arc<Locator>>) -> Vec<&Arc<Locator>> {
+    let mut locators: Vec<_> = scene_locators.iter().collect();
+    locators.sort_by_key(|(var_ident, _)| var_ident.0);
+
+    locators.into_iter().map(|(_, locator)| locator).collect()
+}
+
+/// Carries out a request of the camera path settings. Returns the
+/// playback to continue with.
+fn handle_camera_path_command(
+    command: CameraPathCommand,
+    camera_path: &mut CameraPath,
+    fps: u32,
+    camera: &Camera,
+    scene_locators: &HashMap<VarIdent, Arc<Locator>>,
+    playback: Option<CameraPathPlayback>,
+) -> Option<CameraPathPlayback> {
+    let next_keyframe_time = if camera_path.keyframes().is_empty() {
+        0.0
+    } else {
+        camera_path.end_time() + CAMERA_PATH_KEYFRAME_INTERVAL
+    };
+
+    match command {
+        CameraPathCommand::AddKeyframe => {
+            camera_path.add_keyframe(CameraKeyframe {
+                name: format!("View {}", camera_path.keyframes().len() + 1),
+                time: next_keyframe_time,
+                eye: camera.eye(),
+                target: camera.origin(),
+            });
+
+            playback
+        }
+        CameraPathCommand::AddKeyframeFromLocator(locator_index) => {
+            // The locator could have been removed since the UI listed it
+            if let Some(locator) = sorted_locators(scene_locators).get(locator_index) {
+                let distance = (camera.eye() - camera.origin()).norm();
+                camera_path.add_keyframe(CameraKeyframe::from_locator(
+                    locator,
+                    next_keyframe_time,
+                    distance,
+                ));
+            }
+
+            playback
+        }
+        CameraPathCommand::Preview => Some(CameraPathPlayback::Preview {
+            time: camera_path.start_time(),
+        }),
+        CameraPathCommand::Render(dir) => {
+            let frame_times = camera_path.frame_times(fps);
+            if frame_times.is_empty() {
+                log::warn!("The camera path has no keyframes to render");
+                return None;
+            }
+
+            log::info!(
+                "Rendering {} frames of the camera path to {}",
+                frame_times.len(),
+                dir,
+            );
+
+            Some(CameraPathPlayback::Render {
+                dir: PathBuf::from(dir),
+                frame_times,
+                frame_index: 0,
+            })
+        }
+        CameraPathCommand::Stop => None,
+    }
+}
+
+/// Moves the camera along the camera path, either in real time as a
+/// preview, or frame by frame while rendering it to images.
+#[derive(Debug, Clone)]
+enum CameraPathPlayback {
+    Preview {
+        time: f32,
+    },
+    Render {
+        dir: PathBuf,
+        frame_times: Vec<f32>,
+        frame_index: usize,
+    },
+}
+
+impl CameraPathPlayback {
+    /// The time of the camera path shown by the current frame.
+    fn time(&self) -> f32 {
+        match self {
+            CameraPathPlayback::Preview { time } => *time,
+            CameraPathPlayback::Render {
+                frame_times,
+                frame_index,
+                ..
+            } => frame_times[*frame_index],
+        }
+    }
+
+    /// Where to save the image of the current frame, if rendering.
+    fn frame_path(&self) -> Option<String> {
+        match self {
+            CameraPathPlayback::Preview { .. } => None,
+            CameraPathPlayback::Render {
+                dir, frame_index, ..
+            } => {
+                let path = dir.join(format!("frame_{:05}.png", frame_index));
+                Some(path.to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Moves to the next frame. Returns whether the playback reached
+    /// the end of the camera path.
+    fn advance(&mut self, duration_last_frame: f32, end_time: f32) -> bool {
+        match self {
+            CameraPathPlayback::Preview { time } => {
+                *time += duration_last_frame;
+                *time > end_time
+            }
+            CameraPathPlayback::Render {
+                frame_times,
+                frame_index,
+                ..
+            } => {
+                *frame_index += 1;
+                *frame_index >= frame_times.len()
+            }
+        }
+    }
+}
+
+/// Returns whether both lists contain the same meshes in the same
+/// order, without comparing their geometry.
+fn same_meshes(meshes: &[Arc<Mesh>], other_meshes: &[Arc<Mesh>]) -> bool {
+    meshes.len() == other_meshes.len()
+        && meshes
+            .iter()
+            .zip(other_meshes)
+            .all(|(mesh, other_mesh)| Arc::ptr_eq(mesh, other_mesh))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CameraInterpolation {
+    source_origin: Point3<f32>,
+    source_radius: f32,
+    target_origin: Point3<f32>,
+    target_radius: f32,
+    target_time: Instant,
+}
+
+impl CameraInterpolation {
+    fn new<'a, I>(camera: &Camera, scene_meshes: I, time: Instant) -> Self
+    where
+        I: Iterator<Item = &'a Mesh>,
+    {
+        let (source_origin, source_radius) = camera.visible_sphere();
+        let bounding_box_iter = scene_meshes.map(|mesh| mesh.bounding_box());
+
+        let (target_origin, target_radius) = match BoundingBox::union(bounding_box_iter) {
+            Some(bounding_box) => (bounding_box.center(), bounding_box.diagonal().norm() / 2.0),
+            None => (Point3::origin(), 1.0),
+        };
+
+        CameraInterpolation {
+            source_origin,
+            source_radius,
+            target_origin,
+            target_radius,
+            target_time: time + CAMERA_INTERPOLATION_DURATION,
+        }
+    }
+
+    fn update(&self, time: Instant, easing: &math::CubicBezierEasing) -> (Point3<f32>, f32) {
+        let duration_left = self.target_time.duration_since(time).as_secs_f32();
+        let whole_duration = CAMERA_INTERPOLATION_DURATION.as_secs_f32();
+        let t = easing.apply(1.0 - duration_left / whole_duration);
+
+        let sphere_origin = Point3::from(
+            self.source_origin
+                .coords
+                .lerp(&self.target_origin.coords, t),
+        );
+        let sphere_radius = math::lerp(self.source_radius, self.target_radius, t);
+
+        (sphere_origin, sphere_radius)
+    }
+}
@@ -1,13 +1,41 @@
 use std::cmp::Ordering;
 use std::f32;
 
-use nalgebra::{Matrix4, Point3, Rotation3, Vector3};
+use nalgebra::{Matrix4, Point3, Rotation3, UnitQuaternion, Vector3};
 
 use crate::math::clamp;
 
 const TWO_PI: f32 = f32::consts::PI * 2.0;
 const ZOOM_SPEED_BASE: f32 = 0.95;
 
+/// How dragging rotates the camera around its origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraRotationMode {
+    /// Orbits around the vertical axis, keeping the horizon level.
+    Turntable,
+    /// Rotates freely around the screen axes, e.g. also upside down.
+    Arcball,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraProjection {
+    Perspective,
+    /// Parallel projection, showing the same area as the perspective
+    /// projection does at the camera origin.
+    Orthographic,
+}
+
+/// Views looking at the camera origin along the scene axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraView {
+    /// Looking along the Y axis.
+    Front,
+    /// Looking against the X axis.
+    Right,
+    /// Looking down the Z axis.
+    Top,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CameraOptions {
     pub radius_max: f32,
@@ -30,6 +58,11 @@ pub struct Camera {
     polar_angle: f32,
     origin: Point3<f32>,
     up: Vector3<f32>,
+    /// Rotation of the camera in the arcball mode, mapping the view
+    /// space axes to the world space.
+    orientation: UnitQuaternion<f32>,
+    rotation_mode: CameraRotationMode,
+    projection: CameraProjection,
     options: CameraOptions,
 }
 
@@ -41,8 +74,8 @@ impl Camera {
         polar_angle: f32,
         options: CameraOptions,
     ) -> Camera {
-        Camera {
-            aspect_ratio: (window_size.width / window_size.height) as f32,
+        let mut camera = Camera {
+            aspect_ratio: aspect_ratio(window_size).unwrap_or(1.0),
             radius: clamp(radius, options.radius_min, options.radius_max),
             azimuthal_angle: azimuthal_angle % TWO_PI,
             polar_angle: clamp(
@@ -52,12 +85,96 @@ impl Camera {
             ),
             origin: Point3::origin(),
             up: Vector3::z(),
+            orientation: UnitQuaternion::identity(),
+            rotation_mode: CameraRotationMode::Turntable,
+            projection: CameraProjection::Perspective,
             options,
-        }
+        };
+        camera.orientation = camera.turntable_orientation();
+
+        camera
     }
 
+    /// Updates the aspect ratio. Zero sized windows, e.g. minimized
+    /// ones, keep the previous aspect ratio.
     pub fn set_window_size(&mut self, window_size: winit::dpi::PhysicalSize) {
-        self.aspect_ratio = (window_size.width / window_size.height) as f32;
+        if let Some(aspect_ratio) = aspect_ratio(window_size) {
+            self.aspect_ratio = aspect_ratio;
+        }
+    }
+
+    /// Switches the rotation mode, keeping the view direction. The
+    /// turntable mode levels the horizon.
+    pub fn set_rotation_mode(&mut self, rotation_mode: CameraRotationMode) {
+        if rotation_mode == self.rotation_mode {
+            return;
+        }
+
+        match rotation_mode {
+            CameraRotationMode::Turntable => {
+                let direction = self.orientation * Vector3::z();
+                let up = self.orientation * Vector3::y();
+                self.set_turntable_angles(&direction, &up);
+            }
+            CameraRotationMode::Arcball => {
+                self.orientation = self.turntable_orientation();
+            }
+        }
+        self.rotation_mode = rotation_mode;
+    }
+
+    pub fn set_projection(&mut self, projection: CameraProjection) {
+        self.projection = projection;
+    }
+
+    /// Rotates the camera to look at its origin along the axes of the
+    /// `view`. The turntable mode can not look straight down, so it
+    /// looks down the Z axis as steeply as the options allow.
+    pub fn set_view(&mut self, view: CameraView) {
+        let (direction, up) = match view {
+            CameraView::Front => (-Vector3::y(), Vector3::z()),
+            CameraView::Right => (Vector3::x(), Vector3::z()),
+            CameraView::Top => (Vector3::z(), Vector3::y()),
+        };
+
+        self.orientation = UnitQuaternion::face_towards(&direction, &up);
+        self.set_turntable_angles(&direction, &up);
+    }
+
+    /// Moves the camera to look from the `eye` at the `target`, e.g.
+    /// to follow a camera path. The distance between them is limited
+    /// by the camera options and the turntable mode can not look
+    /// straight down or up. Does nothing if both points are the same.
+    pub fn look_at(&mut self, eye: &Point3<f32>, target: &Point3<f32>) {
+        let offset = eye - target;
+        let direction = match offset.try_normalize(f32::EPSILON) {
+            Some(direction) => direction,
+            None => return,
+        };
+        // Looking along the up vector, the view is rolled the same way
+        // as the top view
+        let up = if direction.cross(&self.up).norm() > f32::EPSILON {
+            self.up
+        } else {
+            Vector3::y()
+        };
+
+        self.origin = *target;
+        self.radius = clamp(
+            offset.norm(),
+            self.options.radius_min,
+            self.options.radius_max,
+        );
+        self.orientation = UnitQuaternion::face_towards(&direction, &up);
+        self.set_turntable_angles(&direction, &up);
+    }
+
+    pub fn origin(&self) -> Point3<f32> {
+        self.origin
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        self.compute_eye()
     }
 
     #[allow(dead_code)]
@@ -69,12 +186,17 @@ impl Camera {
         let pan_factor = self.options.speed_pan * self.radius / self.options.radius_max;
         let ground_translation = Vector3::new(dx, dy, 0.0) * pan_factor;
         let camera_rotation =
-            Rotation3::new(Vector3::z() * (self.azimuthal_angle - f32::consts::FRAC_PI_2));
+            Rotation3::new(Vector3::z() * (self.view_azimuthal_angle() - f32::consts::FRAC_PI_2));
         self.origin += camera_rotation * ground_translation;
     }
 
     pub fn pan_screen(&mut self, dx: f32, dy: f32) {
         let pan_factor = self.options.speed_pan * self.radius / self.options.radius_max;
+        if self.rotation_mode == CameraRotationMode::Arcball {
+            self.origin += self.orientation * Vector3::new(-dx, -dy, 0.0) * pan_factor;
+            return;
+        }
+
         let ground_translation = Vector3::new(dx, dy, 0.0) * pan_factor;
         let camera_rotation =
             Rotation3::new(Vector3::z() * (self.azimuthal_angle - f32::consts::FRAC_PI_2));
@@ -94,6 +216,19 @@ impl Camera {
         let dtheta = dtheta * self.options.speed_rotate;
         let dphi = dphi * self.options.speed_rotate;
 
+        if self.rotation_mode == CameraRotationMode::Arcball {
+            // Rotating the view space around its up axis moves the eye
+            // right, the same as increasing the azimuthal angle, and
+            // around its right axis moves the eye down, the same as
+            // increasing the polar angle
+            self.orientation = self.orientation
+                * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), dtheta)
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), dphi);
+            // Repeated rotations could otherwise drift from unit length
+            self.orientation.renormalize();
+            return;
+        }
+
         self.azimuthal_angle = (self.azimuthal_angle + dtheta) % TWO_PI;
         self.polar_angle = clamp(
             self.polar_angle + dphi,
@@ -165,19 +300,81 @@ impl Camera {
 
     pub fn view_matrix(&self) -> Matrix4<f32> {
         let eye = self.compute_eye();
-        Matrix4::look_at_rh(&eye, &self.origin, &self.up)
+        let up = match self.rotation_mode {
+            CameraRotationMode::Turntable => self.up,
+            CameraRotationMode::Arcball => self.orientation * Vector3::y(),
+        };
+
+        Matrix4::look_at_rh(&eye, &self.origin, &up)
     }
 
     pub fn projection_matrix(&self) -> Matrix4<f32> {
-        Matrix4::new_perspective(
-            self.aspect_ratio,
-            self.options.fovy,
-            self.options.znear,
-            self.options.zfar,
-        )
+        match self.projection {
+            CameraProjection::Perspective => Matrix4::new_perspective(
+                self.aspect_ratio,
+                self.options.fovy,
+                self.options.znear,
+                self.options.zfar,
+            ),
+            CameraProjection::Orthographic => {
+                let half_height = self.radius * (self.options.fovy / 2.0).tan();
+                let half_width = half_height * self.aspect_ratio;
+
+                // Zooming in moves the eye into the scene, therefore
+                // the near plane is behind the eye, so that the
+                // geometry between it and the origin stays visible
+                Matrix4::new_orthographic(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    -self.options.zfar,
+                    self.options.zfar,
+                )
+            }
+        }
+    }
+
+    /// Computes the ray going from the camera through a point on the
+    /// screen, e.g. under the cursor. The screen position is relative
+    /// to the window size, with `[0, 0]` in the top left and `[1, 1]`
+    /// in the bottom right corner. Returns ray origin and normalized
+    /// direction.
+    pub fn ray(&self, screen_position: [f32; 2]) -> (Point3<f32>, Vector3<f32>) {
+        let [x, y] = screen_position;
+        let ndc_x = 2.0 * x - 1.0;
+        let ndc_y = 1.0 - 2.0 * y;
+
+        let view_projection = self.projection_matrix() * self.view_matrix();
+        let inverse_view_projection = view_projection
+            .try_inverse()
+            .expect("View projection matrix must be invertible");
+
+        let near = inverse_view_projection.transform_point(&Point3::new(ndc_x, ndc_y, -1.0));
+        let far = inverse_view_projection.transform_point(&Point3::new(ndc_x, ndc_y, 1.0));
+
+        (near, (far - near).normalize())
+    }
+
+    /// Projects a point to the screen. The screen position is
+    /// relative to the window size, like in `ray`. Returns `None` for
+    /// points behind the perspective camera.
+    pub fn project(&self, point: &Point3<f32>) -> Option<[f32; 2]> {
+        // The camera looks down the negative Z axis of the view space
+        let view_point = self.view_matrix().transform_point(point);
+        if self.projection == CameraProjection::Perspective && view_point.z >= 0.0 {
+            return None;
+        }
+
+        let ndc = self.projection_matrix().transform_point(&view_point);
+        Some([(ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0])
     }
 
     fn compute_eye(&self) -> Point3<f32> {
+        if self.rotation_mode == CameraRotationMode::Arcball {
+            return self.origin + self.orientation * Vector3::z() * self.radius;
+        }
+
         let x = self.radius * self.azimuthal_angle.cos() * self.polar_angle.sin();
         let y = self.radius * self.azimuthal_angle.sin() * self.polar_angle.sin();
         let z = self.radius * self.polar_angle.cos();
@@ -185,6 +382,40 @@ impl Camera {
         self.origin + Vector3::new(x, y, z)
     }
 
+    /// The arcball orientation looking the same way as the turntable
+    /// angles.
+    fn turntable_orientation(&self) -> UnitQuaternion<f32> {
+        let direction = Vector3::new(
+            self.azimuthal_angle.cos() * self.polar_angle.sin(),
+            self.azimuthal_angle.sin() * self.polar_angle.sin(),
+            self.polar_angle.cos(),
+        );
+
+        UnitQuaternion::face_towards(&direction, &self.up)
+    }
+
+    /// Sets the turntable angles to look against `direction`, the
+    /// direction from the origin to the eye.
+    fn set_turntable_angles(&mut self, direction: &Vector3<f32>, up: &Vector3<f32>) {
+        self.azimuthal_angle = azimuthal_angle(direction, up) % TWO_PI;
+        self.polar_angle = clamp(
+            clamp(direction.z, -1.0, 1.0).acos(),
+            self.options.polar_angle_distance_min,
+            f32::consts::PI - self.options.polar_angle_distance_min,
+        );
+    }
+
+    /// The azimuthal angle of the eye, also in the arcball mode.
+    fn view_azimuthal_angle(&self) -> f32 {
+        match self.rotation_mode {
+            CameraRotationMode::Turntable => self.azimuthal_angle,
+            CameraRotationMode::Arcball => azimuthal_angle(
+                &(self.orientation * Vector3::z()),
+                &(self.orientation * Vector3::y()),
+            ),
+        }
+    }
+
     fn compute_visible_sphere_alpha(&self) -> f32 {
         let fovy = self.options.fovy;
         let fovx = fovy * self.aspect_ratio;
@@ -193,3 +424,27 @@ impl Camera {
         fov / 2.0
     }
 }
+
+/// Returns the azimuthal angle of the `direction`. If it is vertical,
+/// the angle is taken from the `up` vector of the view instead, as if
+/// the direction was tilted slightly away from it.
+fn azimuthal_angle(direction: &Vector3<f32>, up: &Vector3<f32>) -> f32 {
+    let horizontal = if direction.x.abs() + direction.y.abs() > f32::EPSILON {
+        *direction
+    } else if direction.z > 0.0 {
+        -up
+    } else {
+        *up
+    };
+
+    horizontal.y.atan2(horizontal.x)
+}
+
+/// Returns the aspect ratio of the window, unless it has zero size.
+fn aspect_ratio(window_size: winit::dpi::PhysicalSize) -> Option<f32> {
+    if window_size.width > 0.0 && window_size.height > 0.0 {
+        Some((window_size.width / window_size.height) as f32)
+    } else {
+        None
+    }
+}
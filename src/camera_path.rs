@@ -0,0 +1,401 @@
+use std::error;
+use std::f32;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use nalgebra::Point3;
+
+use crate::locator::Locator;
+use crate::platform::{FileSystem, PlatformFileSystem};
+
+/// Version of the camera path file format. Bumped on incompatible
+/// changes.
+pub const CAMERA_PATH_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum CameraPathError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    InvalidKeyframeTime(usize),
+}
+
+impl fmt::Display for CameraPathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CameraPathError::Io(err) => write!(f, "Failed to access the camera path file: {}", err),
+            CameraPathError::Parse(err) => {
+                write!(f, "Failed to parse the camera path file: {}", err)
+            }
+            CameraPathError::UnsupportedVersion(version) => write!(
+                f,
+                "Camera path file version {} is not supported (expected {})",
+                version, CAMERA_PATH_VERSION,
+            ),
+            CameraPathError::InvalidKeyframeTime(index) => write!(
+                f,
+                "Keyframe {} has an invalid time or is earlier than the preceding keyframe",
+                index + 1,
+            ),
+        }
+    }
+}
+
+impl error::Error for CameraPathError {}
+
+impl From<io::Error> for CameraPathError {
+    fn from(err: io::Error) -> Self {
+        CameraPathError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CameraPathError {
+    fn from(err: serde_json::Error) -> Self {
+        CameraPathError::Parse(err)
+    }
+}
+
+/// A camera looking from the `eye` at the `target` at the `time` of a
+/// camera path, in seconds.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraKeyframe {
+    pub name: String,
+    pub time: f32,
+    pub eye: Point3<f32>,
+    pub target: Point3<f32>,
+}
+
+impl CameraKeyframe {
+    /// Creates a keyframe looking at the locator against its Z axis
+    /// from `distance`, e.g. at a locator placed on a mesh surface,
+    /// whose Z axis is the surface normal.
+    pub fn from_locator(locator: &Locator, time: f32, distance: f32) -> Self {
+        let target = locator.origin();
+
+        Self {
+            name: locator.name().to_string(),
+            time,
+            eye: target + locator.z_axis() * distance,
+            target,
+        }
+    }
+}
+
+/// A camera flight through keyframes ordered by time. Saved as JSON
+/// separately from projects, so that the same shots can be reused
+/// across projects and their variants.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraPath {
+    pub version: u32,
+    keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        Self {
+            version: CAMERA_PATH_VERSION,
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    /// Mutable access to the keyframes, e.g. to edit them in the UI.
+    /// Call `sort_keyframes` after changing their times.
+    pub fn keyframes_mut(&mut self) -> &mut Vec<CameraKeyframe> {
+        &mut self.keyframes
+    }
+
+    /// Inserts the keyframe after all keyframes with the same or
+    /// earlier time.
+    pub fn add_keyframe(&mut self, keyframe: CameraKeyframe) {
+        let index = self
+            .keyframes
+            .iter()
+            .position(|existing| existing.time > keyframe.time)
+            .unwrap_or_else(|| self.keyframes.len());
+        self.keyframes.insert(index, keyframe);
+    }
+
+    /// Restores the order of the keyframes by time. Keyframes with the
+    /// same time keep their order.
+    pub fn sort_keyframes(&mut self) {
+        self.keyframes.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .expect("Keyframe times must not be NaN")
+        });
+    }
+
+    /// The time of the first keyframe, or zero for empty paths.
+    pub fn start_time(&self) -> f32 {
+        self.keyframes.first().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// The time of the last keyframe, or zero for empty paths.
+    pub fn end_time(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Computes the eye and target of the camera at `time`. The camera
+    /// moves along Catmull-Rom splines through the keyframes and stays
+    /// at the first or last keyframe outside of the path. Returns
+    /// `None` for empty paths.
+    pub fn sample(&self, time: f32) -> Option<(Point3<f32>, Point3<f32>)> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some((first.eye, first.target));
+        }
+        if time >= last.time {
+            return Some((last.eye, last.target));
+        }
+
+        // The first keyframe is not later than the time, therefore at
+        // least one keyframe precedes the segment end
+        let end = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time > time)
+            .expect("A keyframe must be later than the time inside the path");
+        let start = end - 1;
+        let before = start.saturating_sub(1);
+        let after = (end + 1).min(self.keyframes.len() - 1);
+
+        let keyframe = |index: usize| &self.keyframes[index];
+        let segment_duration = keyframe(end).time - keyframe(start).time;
+        let t = if segment_duration > f32::EPSILON {
+            (time - keyframe(start).time) / segment_duration
+        } else {
+            1.0
+        };
+
+        let eye = catmull_rom(
+            &keyframe(before).eye,
+            &keyframe(start).eye,
+            &keyframe(end).eye,
+            &keyframe(after).eye,
+            t,
+        );
+        let target = catmull_rom(
+            &keyframe(before).target,
+            &keyframe(start).target,
+            &keyframe(end).target,
+            &keyframe(after).target,
+            t,
+        );
+
+        Some((eye, target))
+    }
+
+    /// The times of the frames of the path rendered at `fps` frames
+    /// per second, from the first to the last keyframe.
+    pub fn frame_times(&self, fps: u32) -> Vec<f32> {
+        if self.keyframes.is_empty() {
+            return Vec::new();
+        }
+        let start_time = self.start_time();
+        if fps == 0 {
+            return vec![start_time];
+        }
+
+        let duration = self.end_time() - start_time;
+        let frame_count = (duration * fps as f32).floor() as usize + 1;
+
+        (0..frame_count)
+            .map(|frame_index| start_time + frame_index as f32 / fps as f32)
+            .collect()
+    }
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the camera path to a JSON file at `path`.
+pub fn save_camera_path<P: AsRef<Path>>(
+    path: P,
+    camera_path: &CameraPath,
+) -> Result<(), CameraPathError> {
+    let contents = serde_json::to_vec_pretty(camera_path)?;
+    PlatformFileSystem.write(path.as_ref(), &contents)?;
+
+    Ok(())
+}
+
+/// Reads a camera path from a JSON file at `path` and checks that its
+/// keyframes are ordered by time.
+pub fn load_camera_path<P: AsRef<Path>>(path: P) -> Result<CameraPath, CameraPathError> {
+    let contents = PlatformFileSystem.read(path.as_ref())?;
+    let camera_path: CameraPath = serde_json::from_slice(&contents)?;
+
+    if camera_path.version != CAMERA_PATH_VERSION {
+        return Err(CameraPathError::UnsupportedVersion(camera_path.version));
+    }
+
+    let mut previous_time = f32::NEG_INFINITY;
+    for (index, keyframe) in camera_path.keyframes.iter().enumerate() {
+        if !keyframe.time.is_finite() || keyframe.time < previous_time {
+            return Err(CameraPathError::InvalidKeyframeTime(index));
+        }
+        previous_time = keyframe.time;
+    }
+
+    Ok(camera_path)
+}
+
+/// Interpolates between `p1` and `p2` on a uniform Catmull-Rom spline,
+/// which also passes through `p0` and `p3`.
+fn catmull_rom(
+    p0: &Point3<f32>,
+    p1: &Point3<f32>,
+    p2: &Point3<f32>,
+    p3: &Point3<f32>,
+    t: f32,
+) -> Point3<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let coords = (p1.coords * 2.0
+        + (p2.coords - p0.coords) * t
+        + (p0.coords * 2.0 - p1.coords * 5.0 + p2.coords * 4.0 - p3.coords) * t2
+        + (p1.coords * 3.0 - p0.coords - p2.coords * 3.0 + p3.coords) * t3)
+        * 0.5;
+
+    Point3::from(coords)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use nalgebra::Vector3;
+
+    use super::*;
+
+    fn keyframe(name: &str, time: f32, eye: Point3<f32>) -> CameraKeyframe {
+        CameraKeyframe {
+            name: name.to_string(),
+            time,
+            eye,
+            target: Point3::origin(),
+        }
+    }
+
+    fn create_camera_path() -> CameraPath {
+        let mut camera_path = CameraPath::new();
+        camera_path.add_keyframe(keyframe("Front", 0.0, Point3::new(0.0, -10.0, 2.0)));
+        camera_path.add_keyframe(keyframe("Top", 2.0, Point3::new(0.0, 0.0, 10.0)));
+        camera_path.add_keyframe(keyframe("Right", 1.0, Point3::new(10.0, 0.0, 2.0)));
+
+        camera_path
+    }
+
+    #[test]
+    fn test_camera_path_add_keyframe_keeps_time_order() {
+        let camera_path = create_camera_path();
+
+        let names: Vec<_> = camera_path
+            .keyframes()
+            .iter()
+            .map(|keyframe| keyframe.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Front", "Right", "Top"]);
+        assert_eq!(camera_path.end_time(), 2.0);
+    }
+
+    #[test]
+    fn test_camera_path_sample_passes_through_keyframes() {
+        let camera_path = create_camera_path();
+
+        for keyframe in camera_path.keyframes() {
+            let (eye, target) = camera_path
+                .sample(keyframe.time)
+                .expect("Failed to sample camera path");
+            assert!(approx::relative_eq!(eye, keyframe.eye, epsilon = 0.0001));
+            assert_eq!(target, keyframe.target);
+        }
+
+        let (before_eye, _) = camera_path.sample(-1.0).expect("Failed to sample");
+        let (after_eye, _) = camera_path.sample(3.0).expect("Failed to sample");
+        assert_eq!(before_eye, Point3::new(0.0, -10.0, 2.0));
+        assert_eq!(after_eye, Point3::new(0.0, 0.0, 10.0));
+
+        // Between the keyframes, the camera keeps moving
+        let (halfway_eye, _) = camera_path.sample(0.5).expect("Failed to sample");
+        assert!(halfway_eye.x > 0.0 && halfway_eye.x < 10.0);
+        assert!(halfway_eye.y > -10.0 && halfway_eye.y < 0.0);
+
+        assert_eq!(CameraPath::new().sample(0.0), None);
+    }
+
+    #[test]
+    fn test_camera_path_frame_times_cover_path() {
+        let camera_path = create_camera_path();
+
+        let frame_times = camera_path.frame_times(4);
+
+        assert_eq!(frame_times.len(), 9);
+        assert_eq!(frame_times[0], 0.0);
+        assert_eq!(frame_times[1], 0.25);
+        assert_eq!(frame_times[8], 2.0);
+        assert!(CameraPath::new().frame_times(4).is_empty());
+    }
+
+    #[test]
+    fn test_camera_keyframe_from_locator_looks_against_z_axis() {
+        let locator = Locator::from_origin_and_normal(
+            "Entrance",
+            Point3::new(1.0, 2.0, 0.0),
+            &Vector3::new(1.0, 0.0, 0.0),
+        );
+
+        let keyframe = CameraKeyframe::from_locator(&locator, 1.5, 4.0);
+
+        assert_eq!(keyframe.name, "Entrance");
+        assert_eq!(keyframe.time, 1.5);
+        assert_eq!(keyframe.target, Point3::new(1.0, 2.0, 0.0));
+        assert!(approx::relative_eq!(
+            keyframe.eye,
+            Point3::new(5.0, 2.0, 0.0),
+            epsilon = 0.0001,
+        ));
+    }
+
+    #[test]
+    fn test_camera_path_save_load_roundtrip() {
+        let camera_path = create_camera_path();
+        let path = env::temp_dir().join("hurban_selector_test_camera_path_roundtrip.json");
+
+        save_camera_path(&path, &camera_path).expect("Failed to save camera path");
+        let loaded_camera_path = load_camera_path(&path);
+        fs::remove_file(&path).expect("Failed to remove camera path file");
+
+        assert_eq!(
+            loaded_camera_path.expect("Failed to load camera path"),
+            camera_path,
+        );
+    }
+
+    #[test]
+    fn test_load_camera_path_rejects_unordered_keyframes() {
+        let path = env::temp_dir().join("hurban_selector_test_camera_path_unordered.json");
+        let mut camera_path = create_camera_path();
+        camera_path.keyframes_mut().reverse();
+
+        save_camera_path(&path, &camera_path).expect("Failed to save camera path");
+        let result = load_camera_path(&path);
+        fs::remove_file(&path).expect("Failed to remove camera path file");
+
+        assert!(match result {
+            Err(CameraPathError::InvalidKeyframeTime(1)) => true,
+            _ => false,
+        });
+    }
+}
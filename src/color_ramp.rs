@@ -0,0 +1,167 @@
+use std::f32;
+
+use crate::convert::cast_usize;
+use crate::math;
+
+/// A gradient mapping normalized scalar values to colors.
+///
+/// Used to visualize per-vertex scalar data, such as results of mesh
+/// analyses. The ramps are defined as piecewise linear gradients
+/// between a few color stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRamp {
+    Grayscale,
+    Heat,
+    Rainbow,
+    Viridis,
+    Diverging,
+}
+
+impl ColorRamp {
+    /// All available ramps in the order of their indices.
+    pub const ALL: [ColorRamp; 5] = [
+        ColorRamp::Grayscale,
+        ColorRamp::Heat,
+        ColorRamp::Rainbow,
+        ColorRamp::Viridis,
+        ColorRamp::Diverging,
+    ];
+
+    /// Names of all available ramps in the order of their indices.
+    pub const NAMES: [&'static str; 5] = ["Grayscale", "Heat", "Rainbow", "Viridis", "Diverging"];
+
+    /// Returns the ramp with the `index` in `ColorRamp::ALL`, if any.
+    pub fn from_index(index: u32) -> Option<ColorRamp> {
+        Self::ALL.get(cast_usize(index)).copied()
+    }
+
+    /// Samples the ramp at `t`. Values of `t` outside of the `0..1`
+    /// range are clamped.
+    pub fn sample(self, t: f32) -> [f32; 3] {
+        let stops = self.stops();
+        let t = math::clamp(t, 0.0, 1.0);
+
+        let segment_count = stops.len() - 1;
+        let position = t * segment_count as f32;
+        let segment = usize::min(position as usize, segment_count - 1);
+        let weight = position - segment as f32;
+
+        let from = stops[segment];
+        let to = stops[segment + 1];
+
+        [
+            math::lerp(from[0], to[0], weight),
+            math::lerp(from[1], to[1], weight),
+            math::lerp(from[2], to[2], weight),
+        ]
+    }
+
+    fn stops(self) -> &'static [[f32; 3]] {
+        match self {
+            ColorRamp::Grayscale => &[[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]],
+            ColorRamp::Heat => &[
+                [0.0, 0.0, 0.0],
+                [0.9, 0.0, 0.0],
+                [1.0, 0.9, 0.0],
+                [1.0, 1.0, 1.0],
+            ],
+            ColorRamp::Rainbow => &[
+                [0.0, 0.0, 1.0],
+                [0.0, 1.0, 1.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0],
+            ],
+            ColorRamp::Viridis => &[
+                [0.267, 0.005, 0.329],
+                [0.231, 0.322, 0.545],
+                [0.129, 0.569, 0.549],
+                [0.369, 0.788, 0.384],
+                [0.993, 0.906, 0.144],
+            ],
+            ColorRamp::Diverging => &[[0.0, 0.2, 0.8], [1.0, 1.0, 1.0], [0.8, 0.0, 0.1]],
+        }
+    }
+}
+
+/// Computes the range of finite `values`. Returns `None` if there
+/// are no finite values.
+pub fn compute_range(values: &[f32]) -> Option<(f32, f32)> {
+    values
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite())
+        .fold(None, |range, value| match range {
+            Some((min, max)) => Some((f32::min(min, value), f32::max(max, value))),
+            None => Some((value, value)),
+        })
+}
+
+/// Maps `values` to colors by normalizing them from the `min..max`
+/// range and sampling the `ramp`. If the range is empty, all values
+/// are mapped to the start of the ramp.
+pub fn remap_to_colors(values: &[f32], ramp: ColorRamp, min: f32, max: f32) -> Vec<[f32; 3]> {
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            if range > 0.0 {
+                ramp.sample((value - min) / range)
+            } else {
+                ramp.sample(0.0)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_ramp_sample_returns_stops_at_ends() {
+        for ramp in &ColorRamp::ALL {
+            let stops = ramp.stops();
+
+            let start = ramp.sample(0.0);
+            let end = ramp.sample(1.0);
+            let last_stop = stops[stops.len() - 1];
+
+            assert_eq!(start, stops[0]);
+            for i in 0..3 {
+                assert!(approx::relative_eq!(end[i], last_stop[i]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_color_ramp_sample_clamps_out_of_range_values() {
+        assert_eq!(ColorRamp::Grayscale.sample(-1.0), [0.0, 0.0, 0.0]);
+        assert_eq!(ColorRamp::Grayscale.sample(2.0), [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_color_ramp_sample_interpolates_between_stops() {
+        assert_eq!(ColorRamp::Grayscale.sample(0.25), [0.25, 0.25, 0.25]);
+        assert_eq!(ColorRamp::Rainbow.sample(0.125), [0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_compute_range_skips_non_finite_values() {
+        let values = [f32::NAN, 2.0, -1.0, f32::INFINITY, 0.5];
+
+        assert_eq!(compute_range(&values), Some((-1.0, 2.0)));
+        assert_eq!(compute_range(&[f32::NAN]), None);
+    }
+
+    #[test]
+    fn test_remap_to_colors_normalizes_values() {
+        let colors = remap_to_colors(&[1.0, 2.0, 3.0], ColorRamp::Grayscale, 1.0, 3.0);
+
+        assert_eq!(
+            colors,
+            vec![[0.0, 0.0, 0.0], [0.5, 0.5, 0.5], [1.0, 1.0, 1.0]],
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use crate::convert::clamp_cast_i32_to_u32;
+use crate::interpreter::ast::{Expr, FuncIdent, LitExpr, Stmt};
+use crate::interpreter::{Func, ParamRefinement};
+
+/// A named project-level value, e.g. `voxel_size` or `floor_height`.
+///
+/// Numeric operation parameters can be bound to a constant, so that
+/// changing its value updates all of them at once.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Constant {
+    pub name: String,
+    pub value: f32,
+}
+
+/// Binds the argument at `arg_index` of the statement at `stmt_index`
+/// to the constant named `constant_name`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConstantBinding {
+    pub stmt_index: usize,
+    pub arg_index: usize,
+    pub constant_name: String,
+}
+
+/// Returns the literal a parameter with the refinement gets, when
+/// bound to a constant with the value. Vector parameters get the value
+/// in all of their components. The value is clamped to the parameter's
+/// bounds.
+///
+/// Returns `None`, if the parameter can't be bound to a constant,
+/// because it isn't numeric.
+pub fn constant_lit(refinement: &ParamRefinement, value: f32) -> Option<LitExpr> {
+    match refinement {
+        ParamRefinement::Int(int_refinement) => {
+            Some(LitExpr::Int(int_refinement.clamp(value.round() as i32)))
+        }
+        ParamRefinement::Uint(uint_refinement) => Some(LitExpr::Uint(
+            uint_refinement.clamp(clamp_cast_i32_to_u32(value.round() as i32)),
+        )),
+        ParamRefinement::Float(float_refinement) => {
+            Some(LitExpr::Float(float_refinement.clamp(value)))
+        }
+        ParamRefinement::Float2(float2_refinement) => {
+            Some(LitExpr::Float2(float2_refinement.clamp([value; 2])))
+        }
+        ParamRefinement::Float3(float3_refinement) => {
+            Some(LitExpr::Float3(float3_refinement.clamp([value; 3])))
+        }
+        ParamRefinement::Boolean(_)
+        | ParamRefinement::Enum(_)
+        | ParamRefinement::String(_)
+        | ParamRefinement::Mesh
+        | ParamRefinement::MeshArray
+        | ParamRefinement::Locator
+        | ParamRefinement::Curve
+        | ParamRefinement::EmbeddedMesh
+        | ParamRefinement::EmbeddedCurve => None,
+    }
+}
+
+/// Returns whether the binding refers to a literal numeric argument of
+/// one of the statements.
+pub fn binding_fits_stmts(
+    binding: &ConstantBinding,
+    stmts: &[Stmt],
+    function_table: &BTreeMap<FuncIdent, Box<dyn Func>>,
+) -> bool {
+    let var_decl = match stmts.get(binding.stmt_index) {
+        Some(Stmt::VarDecl(var_decl)) => var_decl,
+        None => return false,
+    };
+    let init_expr = var_decl.init_expr();
+    let func = match function_table.get(&init_expr.ident()) {
+        Some(func) => func,
+        None => return false,
+    };
+
+    match (
+        func.param_info().get(binding.arg_index),
+        init_expr.args().get(binding.arg_index),
+    ) {
+        (Some(param_info), Some(Expr::Lit(_))) => {
+            constant_lit(&param_info.refinement, 0.0).is_some()
+        }
+        _ => false,
+    }
+}
+
+/// Returns the statement with its argument at `arg_index` set to the
+/// constant value.
+///
+/// # Panics
+/// Panics if the argument is not numeric or the statement calls a
+/// func missing from the `function_table`.
+#[cfg(feature = "gui")]
+pub fn bind_stmt_arg(
+    stmt: &Stmt,
+    arg_index: usize,
+    value: f32,
+    function_table: &BTreeMap<FuncIdent, Box<dyn Func>>,
+) -> Stmt {
+    let Stmt::VarDecl(var_decl) = stmt;
+    let init_expr = var_decl.init_expr();
+    let refinement = &function_table[&init_expr.ident()].param_info()[arg_index].refinement;
+    let lit = constant_lit(refinement, value).expect("Only numeric params can be bound");
+
+    Stmt::VarDecl(
+        var_decl.clone_with_init_expr(init_expr.clone_with_arg_at(arg_index, Expr::Lit(lit))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{BooleanParamRefinement, Float3ParamRefinement, UintParamRefinement};
+
+    use super::*;
+
+    #[test]
+    fn test_constant_lit_splats_value_into_vector_params() {
+        let refinement = ParamRefinement::Float3(Float3ParamRefinement::default());
+
+        assert_eq!(
+            constant_lit(&refinement, 0.5),
+            Some(LitExpr::Float3([0.5, 0.5, 0.5])),
+        );
+    }
+
+    #[test]
+    fn test_constant_lit_rounds_and_clamps_integer_params() {
+        let refinement = ParamRefinement::Uint(UintParamRefinement {
+            default_value: None,
+            min_value: Some(1),
+            max_value: Some(10),
+        });
+
+        assert_eq!(constant_lit(&refinement, 3.6), Some(LitExpr::Uint(4)));
+        assert_eq!(constant_lit(&refinement, -2.0), Some(LitExpr::Uint(1)));
+        assert_eq!(constant_lit(&refinement, 25.0), Some(LitExpr::Uint(10)));
+    }
+
+    #[test]
+    fn test_constant_lit_rejects_non_numeric_params() {
+        let refinement = ParamRefinement::Boolean(BooleanParamRefinement::default());
+
+        assert_eq!(constant_lit(&refinement, 1.0), None);
+    }
+}
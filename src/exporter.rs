@@ -0,0 +1,1244 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error;
+use std::f32;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[cfg(any(test, feature = "gui"))]
+use nalgebra::Matrix4;
+use nalgebra::Vector3;
+
+use crate::convert::{cast_u32, cast_usize};
+use crate::mesh::{Face, Mesh};
+use crate::platform::{FileSystem, PlatformFileSystem};
+
+#[derive(Debug, PartialEq)]
+pub enum ExporterError {
+    InvalidPath,
+    PermissionDenied,
+    Other,
+}
+
+impl fmt::Display for ExporterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExporterError::InvalidPath => {
+                write!(f, "The path is not valid or its directory does not exist.")
+            }
+            ExporterError::PermissionDenied => write!(f, "Permission denied."),
+            ExporterError::Other => write!(f, "Unexpected error happened."),
+        }
+    }
+}
+
+impl error::Error for ExporterError {}
+
+impl From<io::Error> for ExporterError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => ExporterError::InvalidPath,
+            io::ErrorKind::PermissionDenied => ExporterError::PermissionDenied,
+            _ => ExporterError::Other,
+        }
+    }
+}
+
+/// Writes vertex positions of the `mesh` together with the values of
+/// its vertex attributes named in `attribute_names` to a CSV file at
+/// `path`. There is one row per vertex, preceded by a header row.
+///
+/// # Panics
+/// Panics if the mesh does not contain an attribute named in
+/// `attribute_names`.
+pub fn export_attributes_csv<P: AsRef<Path>>(
+    path: P,
+    mesh: &Mesh,
+    attribute_names: &[&str],
+) -> Result<(), ExporterError> {
+    let mut writer = PlatformFileSystem.create(path.as_ref())?;
+    write_attributes_csv(&mut writer, mesh, attribute_names)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes vertex positions of the `mesh` together with the values of
+/// its vertex attributes named in `attribute_names` as CSV to the
+/// `writer`.
+///
+/// # Panics
+/// Panics if the mesh does not contain an attribute named in
+/// `attribute_names`.
+pub fn write_attributes_csv<W: Write>(
+    writer: &mut W,
+    mesh: &Mesh,
+    attribute_names: &[&str],
+) -> io::Result<()> {
+    let attributes: Vec<_> = attribute_names
+        .iter()
+        .map(|name| {
+            mesh.vertex_attribute(name)
+                .expect("Mesh must contain the exported attribute")
+        })
+        .collect();
+
+    write!(writer, "x,y,z")?;
+    for attribute in &attributes {
+        write!(writer, ",{}", escape_csv_field(attribute.name()))?;
+    }
+    writeln!(writer)?;
+
+    for (vertex_index, vertex) in mesh.vertices().iter().enumerate() {
+        write!(writer, "{},{},{}", vertex.x, vertex.y, vertex.z)?;
+        for attribute in &attributes {
+            write!(writer, ",{}", attribute.values()[vertex_index])?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `mesh` to a Wavefront OBJ file at `path`. See
+/// `write_obj`.
+///
+/// The mesh is written to the file as it is serialized, so exporting
+/// even very large meshes doesn't need more memory.
+pub fn export_obj<P: AsRef<Path>>(path: P, mesh: &Mesh) -> Result<(), ExporterError> {
+    let mut writer = PlatformFileSystem.create(path.as_ref())?;
+    write_obj(&mut writer, mesh)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes vertices, normals and faces of the `mesh` as Wavefront OBJ
+/// to the `writer`. Coordinates are written as they are, without
+/// converting from our Z-up coordinate space, the same as they are
+/// read by the importer.
+///
+/// If the mesh has face materials, each run of faces with the same
+/// material is preceded by a `usemtl material_N` statement, so that
+/// the faces are imported as submeshes by other applications.
+pub fn write_obj<W: Write>(writer: &mut W, mesh: &Mesh) -> io::Result<()> {
+    writeln!(writer, "# Exported from H.U.R.B.A.N. Selector")?;
+
+    for vertex in mesh.vertices() {
+        writeln!(writer, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+    }
+    let (normal_indices, normals) = mesh.vertex_normals().to_indexed_normals(mesh.faces());
+    for normal in &normals {
+        writeln!(writer, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+    }
+
+    // OBJ indices start at 1
+    let mut current_material = None;
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        if let Some(materials) = mesh.face_materials() {
+            let material = materials[face_index];
+            if current_material != Some(material) {
+                writeln!(writer, "usemtl {}", material_name(material))?;
+                current_material = Some(material);
+            }
+        }
+
+        write!(writer, "f")?;
+        for (vertex_index, normal_index) in face.vertices().iter().zip(&normal_indices[face_index])
+        {
+            write!(writer, " {}//{}", vertex_index + 1, normal_index + 1)?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `mesh` to a binary STL file at `path`. See `write_stl`.
+///
+/// Like `export_obj`, the mesh is written to the file as it is
+/// serialized.
+pub fn export_stl<P: AsRef<Path>>(path: P, mesh: &Mesh) -> Result<(), ExporterError> {
+    let mut writer = PlatformFileSystem.create(path.as_ref())?;
+    write_stl(&mut writer, mesh)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the faces of the `mesh` as binary STL to the `writer`. STL
+/// has no shared vertices or vertex normals, so each face is written
+/// with its own copies of its vertices and a normal computed from
+/// them. STL only stores triangles, so quads are written as their two
+/// triangles. Coordinates are written as they are, the same as in
+/// `write_obj`.
+pub fn write_stl<W: Write>(writer: &mut W, mesh: &Mesh) -> io::Result<()> {
+    const STL_HEADER_LEN: usize = 80;
+
+    let mut header = [0u8; STL_HEADER_LEN];
+    let header_text = b"Exported from H.U.R.B.A.N. Selector";
+    header[..header_text.len()].copy_from_slice(header_text);
+    writer.write_all(&header)?;
+    let triangle_count: usize = mesh
+        .faces()
+        .iter()
+        .map(|face| face.to_triangles().len())
+        .sum();
+    writer.write_all(&cast_u32(triangle_count).to_le_bytes())?;
+
+    let vertices = mesh.vertices();
+    for triangle_face in mesh.faces().iter().flat_map(Face::to_triangles) {
+        let (v1, v2, v3) = triangle_face.vertices;
+        let p1 = vertices[cast_usize(v1)];
+        let p2 = vertices[cast_usize(v2)];
+        let p3 = vertices[cast_usize(v3)];
+
+        // Degenerate faces get a zero normal, which STL readers
+        // recompute from the vertices
+        let normal = (p2 - p1)
+            .cross(&(p3 - p1))
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::zeros);
+
+        for component in normal
+            .iter()
+            .chain(p1.coords.iter())
+            .chain(p2.coords.iter())
+            .chain(p3.coords.iter())
+        {
+            writer.write_all(&component.to_bits().to_le_bytes())?;
+        }
+        // Attribute byte count, unused
+        writer.write_all(&0u16.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Writes the named meshes to a binary glTF (.glb) file at `path`,
+/// each mesh as a separate node. See `write_glb`.
+pub fn export_glb<P: AsRef<Path>>(
+    path: P,
+    meshes: &[(String, &Mesh)],
+) -> Result<(), ExporterError> {
+    let mut writer = PlatformFileSystem.create(path.as_ref())?;
+    write_glb(&mut writer, meshes)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the named meshes as binary glTF (.glb) to the `writer`. Each
+/// mesh becomes a node of the scene with the mesh's name, so that
+/// e.g. levels of detail can be named after the conventions of the
+/// target engine.
+///
+/// Vertices are converted from our Z-up to the Y-up coordinate space
+/// of glTF. Vertices are duplicated for each distinct normal they are
+/// used with, as glTF only supports a single index per vertex. Vertex
+/// colors are written, if present.
+///
+/// Faces of meshes with face materials are written as one primitive
+/// per material, referencing a glTF material named `material_N`. The
+/// primitives share the vertex data of their mesh.
+pub fn write_glb<W: Write>(writer: &mut W, meshes: &[(String, &Mesh)]) -> io::Result<()> {
+    const MODE_TRIANGLES: u32 = 4;
+
+    // Materials are shared by all meshes, so that the same material
+    // index means the same glTF material across the whole file
+    let material_indices: BTreeSet<u32> = meshes
+        .iter()
+        .filter_map(|(_, mesh)| mesh.face_materials())
+        .flat_map(|materials| materials.iter().copied())
+        .collect();
+    let gltf_material_indices: HashMap<u32, usize> = material_indices
+        .iter()
+        .enumerate()
+        .map(|(gltf_material_index, material)| (*material, gltf_material_index))
+        .collect();
+
+    let mut builder = GlbBuilder::default();
+    let mut gltf_meshes = Vec::new();
+    let mut nodes = Vec::new();
+
+    for (name, mesh) in meshes {
+        let (positions, normals, colors, material_indices) = glb_vertex_data(mesh);
+
+        let mut attributes = serde_json::Map::new();
+        let position_accessor = builder.push_vec3_accessor(&positions, true);
+        attributes.insert("POSITION".to_string(), position_accessor.into());
+        let normal_accessor = builder.push_vec3_accessor(&normals, false);
+        attributes.insert("NORMAL".to_string(), normal_accessor.into());
+        if let Some(colors) = colors {
+            let color_accessor = builder.push_vec3_accessor(&colors, false);
+            attributes.insert("COLOR_0".to_string(), color_accessor.into());
+        }
+
+        let primitives: Vec<serde_json::Value> = material_indices
+            .iter()
+            .map(|(material, indices)| {
+                let mut primitive = serde_json::json!({
+                    "attributes": attributes,
+                    "indices": builder.push_index_accessor(indices),
+                    "mode": MODE_TRIANGLES,
+                });
+                if let Some(material) = material {
+                    primitive["material"] = gltf_material_indices[material].into();
+                }
+
+                primitive
+            })
+            .collect();
+
+        gltf_meshes.push(serde_json::json!({
+            "name": name,
+            "primitives": primitives,
+        }));
+        nodes.push(serde_json::json!({
+            "name": name,
+            "mesh": gltf_meshes.len() - 1,
+        }));
+    }
+
+    let GlbBuilder {
+        mut buffer,
+        buffer_views,
+        accessors,
+    } = builder;
+
+    let node_indices: Vec<usize> = (0..nodes.len()).collect();
+    let mut json = serde_json::json!({
+        "asset": {
+            "version": "2.0",
+            "generator": "H.U.R.B.A.N. Selector",
+        },
+        "scene": 0,
+        "scenes": [{ "nodes": node_indices }],
+        "nodes": nodes,
+        "meshes": gltf_meshes,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "byteLength": buffer.len() }],
+    });
+    if !material_indices.is_empty() {
+        let materials: Vec<serde_json::Value> = material_indices
+            .iter()
+            .map(|material| serde_json::json!({ "name": material_name(*material) }))
+            .collect();
+        json["materials"] = materials.into();
+    }
+
+    let mut json_chunk =
+        serde_json::to_vec(&json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    // Chunks must be aligned to 4 bytes. JSON is padded with spaces,
+    // binary data with zeros.
+    while json_chunk.len() % 4 != 0 {
+        json_chunk.push(b' ');
+    }
+    while buffer.len() % 4 != 0 {
+        buffer.push(0);
+    }
+
+    const GLB_MAGIC: u32 = 0x4654_6c67;
+    const GLB_VERSION: u32 = 2;
+    const CHUNK_TYPE_JSON: u32 = 0x4e4f_534a;
+    const CHUNK_TYPE_BIN: u32 = 0x004e_4942;
+    const HEADER_LEN: usize = 12;
+    const CHUNK_HEADER_LEN: usize = 8;
+
+    let total_len = HEADER_LEN + 2 * CHUNK_HEADER_LEN + json_chunk.len() + buffer.len();
+
+    writer.write_all(&GLB_MAGIC.to_le_bytes())?;
+    writer.write_all(&GLB_VERSION.to_le_bytes())?;
+    writer.write_all(&cast_u32(total_len).to_le_bytes())?;
+
+    writer.write_all(&cast_u32(json_chunk.len()).to_le_bytes())?;
+    writer.write_all(&CHUNK_TYPE_JSON.to_le_bytes())?;
+    writer.write_all(&json_chunk)?;
+
+    writer.write_all(&cast_u32(buffer.len()).to_le_bytes())?;
+    writer.write_all(&CHUNK_TYPE_BIN.to_le_bytes())?;
+    writer.write_all(&buffer)?;
+
+    Ok(())
+}
+
+/// Collects the binary data of a glTF file together with the buffer
+/// views and accessors describing it.
+#[derive(Default)]
+struct GlbBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<serde_json::Value>,
+    accessors: Vec<serde_json::Value>,
+}
+
+impl GlbBuilder {
+    const TARGET_ARRAY_BUFFER: u32 = 34962;
+    const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const COMPONENT_TYPE_UNSIGNED_INT: u32 = 5125;
+    const COMPONENT_TYPE_FLOAT: u32 = 5126;
+
+    /// Appends the data as a new buffer view and returns its index.
+    fn push_buffer_view(&mut self, data: &[u8], target: u32) -> usize {
+        self.buffer_views.push(serde_json::json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": data.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(data);
+
+        self.buffer_views.len() - 1
+    }
+
+    /// Appends the vertex data and returns the index of its accessor.
+    /// Bounds are required for positions.
+    fn push_vec3_accessor(&mut self, data: &[[f32; 3]], with_bounds: bool) -> usize {
+        let bytes: Vec<u8> = data
+            .iter()
+            .flat_map(|value| value.iter())
+            .flat_map(|component| component.to_bits().to_le_bytes().to_vec())
+            .collect();
+        let buffer_view = self.push_buffer_view(&bytes, Self::TARGET_ARRAY_BUFFER);
+
+        let mut accessor = serde_json::json!({
+            "bufferView": buffer_view,
+            "componentType": Self::COMPONENT_TYPE_FLOAT,
+            "count": data.len(),
+            "type": "VEC3",
+        });
+
+        if with_bounds {
+            let mut min = [f32::INFINITY; 3];
+            let mut max = [f32::NEG_INFINITY; 3];
+            for value in data {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(value[axis]);
+                    max[axis] = max[axis].max(value[axis]);
+                }
+            }
+
+            accessor["min"] = serde_json::json!(min);
+            accessor["max"] = serde_json::json!(max);
+        }
+
+        self.accessors.push(accessor);
+
+        self.accessors.len() - 1
+    }
+
+    /// Appends the triangle indices and returns the index of their
+    /// accessor.
+    fn push_index_accessor(&mut self, indices: &[u32]) -> usize {
+        let bytes: Vec<u8> = indices
+            .iter()
+            .flat_map(|index| index.to_le_bytes().to_vec())
+            .collect();
+        let buffer_view = self.push_buffer_view(&bytes, Self::TARGET_ELEMENT_ARRAY_BUFFER);
+
+        self.accessors.push(serde_json::json!({
+            "bufferView": buffer_view,
+            "componentType": Self::COMPONENT_TYPE_UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+
+        self.accessors.len() - 1
+    }
+}
+
+type GlbVertexData = (
+    Vec<[f32; 3]>,
+    Vec<[f32; 3]>,
+    Option<Vec<[f32; 3]>>,
+    BTreeMap<Option<u32>, Vec<u32>>,
+);
+
+/// Converts the mesh to vertex data with a single index per vertex,
+/// as required by glTF, returning positions, normals, colors and
+/// triangle indices grouped by face material. Meshes without face
+/// materials have all their indices in a single `None` group.
+fn glb_vertex_data(mesh: &Mesh) -> GlbVertexData {
+    let z_up_to_y_up = |v: [f32; 3]| [v[0], v[2], -v[1]];
+
+    // Vertices are only duplicated for face corners overriding the
+    // vertex normal, e.g. along sharp edges
+    let vertex_normals = mesh.vertex_normals();
+    let mut vertex_indices: HashMap<(u32, Option<[u32; 3]>), u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = mesh.vertex_colors().map(|_| Vec::new());
+    let mut material_indices: BTreeMap<Option<u32>, Vec<u32>> = BTreeMap::new();
+
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        let material = mesh.face_materials().map(|materials| materials[face_index]);
+        let indices = material_indices.entry(material).or_insert_with(Vec::new);
+
+        // glTF only stores triangles, the triangles of a quad keep the
+        // normals of its corners
+        for triangle_face in face.to_triangles() {
+            let (v1, v2, v3) = triangle_face.vertices;
+            for &vertex_index in &[v1, v2, v3] {
+                let corner_override = vertex_normals.corner_override(face_index, vertex_index);
+                let key = (
+                    vertex_index,
+                    corner_override
+                        .map(|normal| [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()]),
+                );
+                let index = *vertex_indices.entry(key).or_insert_with(|| {
+                    let vertex = mesh.vertices()[cast_usize(vertex_index)];
+                    let normal = corner_override
+                        .unwrap_or_else(|| vertex_normals.vertex_normal(vertex_index));
+                    positions.push(z_up_to_y_up([vertex.x, vertex.y, vertex.z]));
+                    normals.push(z_up_to_y_up([normal.x, normal.y, normal.z]));
+                    if let (Some(colors), Some(vertex_colors)) =
+                        (colors.as_mut(), mesh.vertex_colors())
+                    {
+                        colors.push(vertex_colors[cast_usize(vertex_index)]);
+                    }
+
+                    cast_u32(positions.len() - 1)
+                });
+
+                indices.push(index);
+            }
+        }
+    }
+
+    (positions, normals, colors, material_indices)
+}
+
+/// The name of the material with the index in exported files.
+fn material_name(material: u32) -> String {
+    format!("material_{}", material)
+}
+
+/// A mesh exported to USD, placed in the scene with a transform.
+#[cfg(any(test, feature = "gui"))]
+#[derive(Debug, Clone)]
+pub struct UsdMesh<'a> {
+    pub name: String,
+    pub mesh: &'a Mesh,
+    pub transform: Matrix4<f32>,
+}
+
+/// Writes the meshes to a USDZ package at `path`, which can be viewed
+/// in AR on iOS devices. See `write_usdz`.
+#[cfg(feature = "gui")]
+pub fn export_usdz<P: AsRef<Path>>(path: P, meshes: &[UsdMesh]) -> Result<(), ExporterError> {
+    let mut writer = PlatformFileSystem.create(path.as_ref())?;
+    write_usdz(&mut writer, meshes)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the meshes as a USDZ package to the `writer`.
+///
+/// The package is an uncompressed zip archive containing a single
+/// ASCII USD layer written by `write_usda`, with its data aligned to
+/// 64 bytes as required by the USDZ specification.
+///
+/// The checksum and length of the layer precede it in the archive, so
+/// the layer is written twice: first only to compute them, then to the
+/// `writer`. Unlike building the layer in memory, this keeps exporting
+/// large meshes from needing more memory.
+#[cfg(any(test, feature = "gui"))]
+pub fn write_usdz<W: Write>(writer: &mut W, meshes: &[UsdMesh]) -> io::Result<()> {
+    const FILE_NAME: &[u8] = b"scene.usda";
+    const ALIGNMENT: usize = 64;
+    const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+    const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+    const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+    const LOCAL_HEADER_LEN: usize = 30;
+    const CENTRAL_HEADER_LEN: usize = 46;
+    const PADDING_EXTRA_FIELD_ID: u16 = 0x1986;
+    const VERSION: u16 = 20;
+    // 1980-01-01, the earliest date representable in zip archives
+    const DOS_DATE: u16 = 0x21;
+
+    let mut checksum_writer = ChecksumWriter::default();
+    write_usda(&mut checksum_writer, meshes)?;
+    let ChecksumWriter {
+        hasher,
+        len: usda_len,
+    } = checksum_writer;
+    let crc = hasher.finalize();
+
+    // The extra field pads the local header, so that the file data
+    // starts at an aligned offset
+    let unpadded_len = LOCAL_HEADER_LEN + FILE_NAME.len() + 4;
+    let padding_len = (ALIGNMENT - unpadded_len % ALIGNMENT) % ALIGNMENT;
+    let extra_field_len = 4 + padding_len;
+    let local_header_len = unpadded_len + padding_len;
+    debug_assert_eq!(local_header_len % ALIGNMENT, 0);
+
+    let write_u16 = |writer: &mut W, value: u16| writer.write_all(&value.to_le_bytes());
+    let write_u32 = |writer: &mut W, value: u32| writer.write_all(&value.to_le_bytes());
+
+    write_u32(writer, LOCAL_HEADER_SIGNATURE)?;
+    write_u16(writer, VERSION)?;
+    write_u16(writer, 0)?; // Flags
+    write_u16(writer, 0)?; // Stored, no compression
+    write_u16(writer, 0)?; // Time
+    write_u16(writer, DOS_DATE)?;
+    write_u32(writer, crc)?;
+    write_u32(writer, cast_u32(usda_len))?; // Compressed size
+    write_u32(writer, cast_u32(usda_len))?; // Uncompressed size
+    write_u16(writer, FILE_NAME.len() as u16)?;
+    write_u16(writer, extra_field_len as u16)?;
+    writer.write_all(FILE_NAME)?;
+    write_u16(writer, PADDING_EXTRA_FIELD_ID)?;
+    write_u16(writer, padding_len as u16)?;
+    writer.write_all(&[0; ALIGNMENT][..padding_len])?;
+    write_usda(writer, meshes)?;
+
+    let central_directory_offset = local_header_len + usda_len;
+    write_u32(writer, CENTRAL_HEADER_SIGNATURE)?;
+    write_u16(writer, VERSION)?; // Version made by
+    write_u16(writer, VERSION)?; // Version needed to extract
+    write_u16(writer, 0)?; // Flags
+    write_u16(writer, 0)?; // Stored, no compression
+    write_u16(writer, 0)?; // Time
+    write_u16(writer, DOS_DATE)?;
+    write_u32(writer, crc)?;
+    write_u32(writer, cast_u32(usda_len))?; // Compressed size
+    write_u32(writer, cast_u32(usda_len))?; // Uncompressed size
+    write_u16(writer, FILE_NAME.len() as u16)?;
+    write_u16(writer, 0)?; // Extra field length
+    write_u16(writer, 0)?; // Comment length
+    write_u16(writer, 0)?; // Disk number
+    write_u16(writer, 0)?; // Internal attributes
+    write_u32(writer, 0)?; // External attributes
+    write_u32(writer, 0)?; // Offset of the local header
+    writer.write_all(FILE_NAME)?;
+    let central_directory_len = CENTRAL_HEADER_LEN + FILE_NAME.len();
+
+    write_u32(writer, END_OF_CENTRAL_DIRECTORY_SIGNATURE)?;
+    write_u16(writer, 0)?; // Disk number
+    write_u16(writer, 0)?; // Disk with the central directory
+    write_u16(writer, 1)?; // Entries on this disk
+    write_u16(writer, 1)?; // Entries in total
+    write_u32(writer, cast_u32(central_directory_len))?;
+    write_u32(writer, cast_u32(central_directory_offset))?;
+    write_u16(writer, 0)?; // Comment length
+
+    Ok(())
+}
+
+/// A writer discarding the written data, which only keeps its length
+/// and checksum.
+#[cfg(any(test, feature = "gui"))]
+#[derive(Default)]
+struct ChecksumWriter {
+    hasher: crc32fast::Hasher,
+    len: usize,
+}
+
+#[cfg(any(test, feature = "gui"))]
+impl Write for ChecksumWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        self.len += buf.len();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes the meshes as an ASCII USD layer to the `writer`.
+///
+/// Each mesh is placed under its own transform. Vertex colors are
+/// written as the display color primvar and shown by a shared preview
+/// material. The root transform converts our Z-up coordinate space to
+/// Y-up, which is what AR viewers expect.
+#[cfg(any(test, feature = "gui"))]
+pub fn write_usda<W: Write>(writer: &mut W, meshes: &[UsdMesh]) -> io::Result<()> {
+    writeln!(writer, "#usda 1.0")?;
+    writeln!(writer, "(")?;
+    writeln!(writer, "    defaultPrim = \"Root\"")?;
+    writeln!(writer, "    metersPerUnit = 1")?;
+    writeln!(writer, "    upAxis = \"Y\"")?;
+    writeln!(writer, ")")?;
+    writeln!(writer)?;
+    writeln!(writer, "def Xform \"Root\"")?;
+    writeln!(writer, "{{")?;
+    writeln!(
+        writer,
+        "    matrix4d xformOp:transform = ( (1, 0, 0, 0), (0, 0, -1, 0), (0, 1, 0, 0), (0, 0, 0, 1) )",
+    )?;
+    writeln!(
+        writer,
+        "    uniform token[] xformOpOrder = [\"xformOp:transform\"]",
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "    def Scope \"Materials\"")?;
+    writeln!(writer, "    {{")?;
+    writeln!(writer, "        def Material \"DisplayColor\"")?;
+    writeln!(writer, "        {{")?;
+    writeln!(
+        writer,
+        "            token outputs:surface.connect = </Root/Materials/DisplayColor/Surface.outputs:surface>",
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "            def Shader \"Surface\"")?;
+    writeln!(writer, "            {{")?;
+    writeln!(
+        writer,
+        "                uniform token info:id = \"UsdPreviewSurface\"",
+    )?;
+    writeln!(
+        writer,
+        "                color3f inputs:diffuseColor.connect = </Root/Materials/DisplayColor/Color.outputs:result>",
+    )?;
+    writeln!(writer, "                float inputs:roughness = 0.8")?;
+    writeln!(writer, "                token outputs:surface")?;
+    writeln!(writer, "            }}")?;
+    writeln!(writer)?;
+    writeln!(writer, "            def Shader \"Color\"")?;
+    writeln!(writer, "            {{")?;
+    writeln!(
+        writer,
+        "                uniform token info:id = \"UsdPrimvarReader_float3\"",
+    )?;
+    writeln!(
+        writer,
+        "                string inputs:varname = \"displayColor\""
+    )?;
+    writeln!(
+        writer,
+        "                float3 inputs:fallback = (0.8, 0.8, 0.8)"
+    )?;
+    writeln!(writer, "                float3 outputs:result")?;
+    writeln!(writer, "            }}")?;
+    writeln!(writer, "        }}")?;
+    writeln!(writer, "    }}")?;
+
+    for (mesh_index, usd_mesh) in meshes.iter().enumerate() {
+        writeln!(writer)?;
+        write_usda_mesh(writer, mesh_index, usd_mesh)?;
+    }
+
+    writeln!(writer, "}}")
+}
+
+#[cfg(any(test, feature = "gui"))]
+fn write_usda_mesh<W: Write>(
+    writer: &mut W,
+    mesh_index: usize,
+    usd_mesh: &UsdMesh,
+) -> io::Result<()> {
+    let mesh = usd_mesh.mesh;
+    let transform = &usd_mesh.transform;
+
+    let join = |values: Vec<String>| values.join(", ");
+    let vec3 = |x: f32, y: f32, z: f32| format!("({}, {}, {})", x, y, z);
+
+    // USD uses row vectors, so the rows of the USD matrix are the
+    // columns of ours
+    let rows: Vec<_> = (0..4)
+        .map(|column| {
+            let column = transform.column(column);
+            format!(
+                "({}, {}, {}, {})",
+                column[0], column[1], column[2], column[3]
+            )
+        })
+        .collect();
+
+    let mut face_vertex_counts = Vec::with_capacity(mesh.faces().len());
+    let mut face_vertex_indices = Vec::with_capacity(mesh.faces().len() * 3);
+    let mut face_normals = Vec::with_capacity(mesh.faces().len() * 3);
+    for (face_index, face) in mesh.faces().iter().enumerate() {
+        let vertices = face.vertices();
+        face_vertex_counts.push(vertices.len().to_string());
+        for (vertex_index, normal) in vertices.iter().zip(&mesh.face_corner_normals(face_index)) {
+            face_vertex_indices.push(vertex_index.to_string());
+            face_normals.push(vec3(normal.x, normal.y, normal.z));
+        }
+    }
+
+    let points: Vec<_> = mesh
+        .vertices()
+        .iter()
+        .map(|vertex| vec3(vertex.x, vertex.y, vertex.z))
+        .collect();
+
+    writeln!(
+        writer,
+        "    def Xform \"{}\"",
+        usd_identifier(&usd_mesh.name, mesh_index),
+    )?;
+    writeln!(writer, "    {{")?;
+    writeln!(
+        writer,
+        "        matrix4d xformOp:transform = ( {} )",
+        join(rows),
+    )?;
+    writeln!(
+        writer,
+        "        uniform token[] xformOpOrder = [\"xformOp:transform\"]",
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "        def Mesh \"Geometry\" (")?;
+    writeln!(
+        writer,
+        "            prepend apiSchemas = [\"MaterialBindingAPI\"]"
+    )?;
+    writeln!(writer, "        )")?;
+    writeln!(writer, "        {{")?;
+    writeln!(
+        writer,
+        "            int[] faceVertexCounts = [{}]",
+        join(face_vertex_counts),
+    )?;
+    writeln!(
+        writer,
+        "            int[] faceVertexIndices = [{}]",
+        join(face_vertex_indices),
+    )?;
+    writeln!(writer, "            point3f[] points = [{}]", join(points))?;
+    writeln!(
+        writer,
+        "            normal3f[] normals = [{}] (",
+        join(face_normals),
+    )?;
+    writeln!(writer, "                interpolation = \"faceVarying\"")?;
+    writeln!(writer, "            )")?;
+
+    match mesh.vertex_colors() {
+        Some(vertex_colors) => {
+            let colors: Vec<_> = vertex_colors
+                .iter()
+                .map(|color| vec3(color[0], color[1], color[2]))
+                .collect();
+            writeln!(
+                writer,
+                "            color3f[] primvars:displayColor = [{}] (",
+                join(colors),
+            )?;
+            writeln!(writer, "                interpolation = \"vertex\"")?;
+            writeln!(writer, "            )")?;
+        }
+        None => {
+            writeln!(
+                writer,
+                "            color3f[] primvars:displayColor = [(0.8, 0.8, 0.8)] (",
+            )?;
+            writeln!(writer, "                interpolation = \"constant\"")?;
+            writeln!(writer, "            )")?;
+        }
+    }
+
+    writeln!(
+        writer,
+        "            rel material:binding = </Root/Materials/DisplayColor>",
+    )?;
+    writeln!(
+        writer,
+        "            uniform token subdivisionScheme = \"none\""
+    )?;
+    writeln!(writer, "        }}")?;
+    writeln!(writer, "    }}")
+}
+
+/// Converts the name into a valid USD prim identifier, which is unique
+/// thanks to the appended index.
+#[cfg(any(test, feature = "gui"))]
+fn usd_identifier(name: &str, index: usize) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    format!("_{}_{}", index, sanitized)
+}
+
+/// Quotes the field if it contains characters with special meaning
+/// in CSV.
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use nalgebra::Point3;
+
+    use crate::importer;
+    use crate::mesh::{NormalStrategy, QuadFace, TriangleFace};
+
+    use super::*;
+
+    fn triangle_mesh_with_attributes() -> Mesh {
+        let mut mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2)],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.5),
+            ],
+            NormalStrategy::Sharp,
+        );
+        mesh.set_vertex_attribute("thickness", vec![0.5, 1.0, 1.5]);
+        mesh.set_vertex_attribute("sunlight, hours", vec![2.0, 4.0, 8.0]);
+
+        mesh
+    }
+
+    fn two_material_mesh() -> Mesh {
+        Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2), (1, 3, 2), (2, 3, 4)],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 2.0, 0.0),
+            ],
+            NormalStrategy::Sharp,
+        )
+    }
+
+    #[test]
+    fn test_write_attributes_csv() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_attributes_csv(&mut buffer, &mesh, &["sunlight, hours", "thickness"])
+            .expect("Failed to write CSV");
+
+        assert_eq!(
+            String::from_utf8(buffer).expect("CSV must be valid UTF-8"),
+            "x,y,z,\"sunlight, hours\",thickness\n0,0,0,2,0.5\n1,0,0,4,1\n0,1,0.5,8,1.5\n",
+        );
+    }
+
+    #[test]
+    fn test_write_attributes_csv_without_attributes() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_attributes_csv(&mut buffer, &mesh, &[]).expect("Failed to write CSV");
+
+        assert_eq!(
+            String::from_utf8(buffer).expect("CSV must be valid UTF-8"),
+            "x,y,z\n0,0,0\n1,0,0\n0,1,0.5\n",
+        );
+    }
+
+    #[test]
+    #[should_panic = "Mesh must contain the exported attribute"]
+    fn test_write_attributes_csv_panics_on_missing_attribute() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        let _ = write_attributes_csv(&mut buffer, &mesh, &["curvature"]);
+    }
+
+    #[test]
+    fn test_write_obj() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_obj(&mut buffer, &mesh).expect("Failed to write OBJ");
+
+        let obj = String::from_utf8(buffer).expect("OBJ must be valid UTF-8");
+        let lines: Vec<_> = obj.lines().filter(|line| !line.starts_with('#')).collect();
+        let normal = mesh.vertex_normals().vertex_normal(0);
+        let normal_line = format!("vn {} {} {}", normal.x, normal.y, normal.z);
+        assert_eq!(
+            lines,
+            vec![
+                "v 0 0 0",
+                "v 1 0 0",
+                "v 0 1 0.5",
+                &normal_line,
+                &normal_line,
+                &normal_line,
+                "f 1//1 2//2 3//3",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_write_obj_writes_materials_before_runs_of_faces() {
+        let mut mesh = two_material_mesh();
+        mesh.set_face_materials(vec![1, 1, 0]);
+
+        let mut buffer = Vec::new();
+        write_obj(&mut buffer, &mesh).expect("Failed to write OBJ");
+
+        let obj = String::from_utf8(buffer).expect("OBJ must be valid UTF-8");
+        let lines: Vec<_> = obj
+            .lines()
+            .filter(|line| line.starts_with("usemtl") || line.starts_with('f'))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "usemtl material_1",
+                "f 1//1 2//2 3//3",
+                "f 2//2 4//4 3//3",
+                "usemtl material_0",
+                "f 3//3 4//4 5//5",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_write_obj_roundtrips_through_importer() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_obj(&mut buffer, &mesh).expect("Failed to write OBJ");
+
+        let tobj_models =
+            importer::obj_buf_into_tobj(&mut &buffer[..]).expect("Failed to parse OBJ");
+        let models = importer::tobj_to_internal(tobj_models.0);
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].mesh.faces().len(), mesh.faces().len());
+        assert_eq!(models[0].mesh.vertices(), mesh.vertices());
+    }
+
+    #[test]
+    fn test_write_obj_keeps_quads_through_importer() {
+        let mesh = Mesh::from_faces_with_vertices_and_computed_normals(
+            vec![
+                Face::from(QuadFace::new(0, 1, 2, 3)),
+                Face::from(TriangleFace::new(1, 4, 2)),
+            ],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+                Point3::new(2.0, 0.0, 0.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let mut buffer = Vec::new();
+        write_obj(&mut buffer, &mesh).expect("Failed to write OBJ");
+
+        let obj = String::from_utf8(buffer.clone()).expect("OBJ must be valid UTF-8");
+        let face_lines: Vec<_> = obj.lines().filter(|line| line.starts_with('f')).collect();
+        assert_eq!(
+            face_lines,
+            vec!["f 1//1 2//2 3//3 4//4", "f 2//2 5//5 3//3"]
+        );
+
+        let tobj_models =
+            importer::obj_buf_into_tobj(&mut &buffer[..]).expect("Failed to parse OBJ");
+        let face_sizes = importer::decode_obj_face_sizes(&buffer);
+        let models = importer::tobj_to_internal_with_face_sizes(tobj_models.0, &face_sizes);
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].mesh.faces(), mesh.faces());
+        assert_eq!(models[0].mesh.vertices(), mesh.vertices());
+    }
+
+    #[test]
+    fn test_write_stl() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_stl(&mut buffer, &mesh).expect("Failed to write STL");
+
+        assert_eq!(buffer.len(), 80 + 4 + 50);
+        assert_eq!(&buffer[80..84], &1u32.to_le_bytes());
+
+        let normal = Vector3::new(0.0, -0.5, 1.0).normalize();
+        let expected_floats: Vec<f32> = normal
+            .iter()
+            .copied()
+            .chain(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.5])
+            .collect();
+        let expected_bytes: Vec<u8> = expected_floats
+            .iter()
+            .flat_map(|component| component.to_bits().to_le_bytes().to_vec())
+            .collect();
+        assert_eq!(&buffer[84..132], &expected_bytes[..]);
+        assert_eq!(&buffer[132..134], &[0, 0]);
+    }
+
+    #[test]
+    fn test_export_stl_writes_the_same_as_write_stl() {
+        let mesh = triangle_mesh_with_attributes();
+        let path = env::temp_dir().join("hurban_selector_test_export.stl");
+
+        let mut buffer = Vec::new();
+        write_stl(&mut buffer, &mesh).expect("Failed to write STL");
+        export_stl(&path, &mesh).expect("Failed to export STL");
+        let contents = fs::read(&path).expect("Failed to read STL file");
+        fs::remove_file(&path).expect("Failed to remove STL file");
+
+        assert_eq!(contents, buffer);
+    }
+
+    #[test]
+    fn test_write_glb_writes_named_nodes() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_glb(
+            &mut buffer,
+            &[
+                ("Box_LOD0".to_string(), &mesh),
+                ("Box_LOD1".to_string(), &mesh),
+            ],
+        )
+        .expect("Failed to write glTF");
+
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([
+                buffer[offset],
+                buffer[offset + 1],
+                buffer[offset + 2],
+                buffer[offset + 3],
+            ]) as usize
+        };
+        assert_eq!(&buffer[0..4], b"glTF");
+        assert_eq!(u32_at(8), buffer.len());
+
+        let json_len = u32_at(12);
+        let json: serde_json::Value =
+            serde_json::from_slice(&buffer[20..20 + json_len]).expect("Failed to parse glTF JSON");
+        assert_eq!(json["nodes"][0]["name"], "Box_LOD0");
+        assert_eq!(json["nodes"][1]["name"], "Box_LOD1");
+        assert_eq!(json["nodes"][1]["mesh"], 1);
+
+        // One triangle with sharp normals has 3 vertices and indices
+        let position_accessor = &json["accessors"][0];
+        assert_eq!(position_accessor["count"], 3);
+        assert_eq!(position_accessor["max"], serde_json::json!([1.0, 0.5, 0.0]));
+        assert_eq!(
+            position_accessor["min"],
+            serde_json::json!([0.0, 0.0, -1.0])
+        );
+        assert_eq!(json["accessors"][2]["count"], 3);
+
+        let bin_len = u32_at(20 + json_len);
+        assert_eq!(json["buffers"][0]["byteLength"], serde_json::json!(bin_len));
+        assert_eq!(28 + json_len + bin_len, buffer.len());
+    }
+
+    #[test]
+    fn test_write_glb_writes_primitive_per_material() {
+        let mut mesh = two_material_mesh();
+        mesh.set_face_materials(vec![3, 1, 3]);
+        let plain_mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_glb(
+            &mut buffer,
+            &[
+                ("Facade".to_string(), &mesh),
+                ("Plain".to_string(), &plain_mesh),
+            ],
+        )
+        .expect("Failed to write glTF");
+
+        let json_len = u32::from_le_bytes([buffer[12], buffer[13], buffer[14], buffer[15]]);
+        let json: serde_json::Value = serde_json::from_slice(&buffer[20..20 + json_len as usize])
+            .expect("Failed to parse glTF JSON");
+
+        assert_eq!(
+            json["materials"],
+            serde_json::json!([{ "name": "material_1" }, { "name": "material_3" }]),
+        );
+
+        let primitives = json["meshes"][0]["primitives"]
+            .as_array()
+            .expect("Primitives must be an array");
+        assert_eq!(primitives.len(), 2);
+        assert_eq!(primitives[0]["material"], 0);
+        assert_eq!(primitives[1]["material"], 1);
+        assert_eq!(primitives[0]["attributes"], primitives[1]["attributes"]);
+
+        let index_count = |primitive: &serde_json::Value| {
+            let accessor_index = primitive["indices"].as_u64().expect("Indices must be set");
+            json["accessors"][accessor_index as usize]["count"].clone()
+        };
+        assert_eq!(index_count(&primitives[0]), 3);
+        assert_eq!(index_count(&primitives[1]), 6);
+
+        let plain_primitives = json["meshes"][1]["primitives"]
+            .as_array()
+            .expect("Primitives must be an array");
+        assert_eq!(plain_primitives.len(), 1);
+        assert!(plain_primitives[0].get("material").is_none());
+    }
+
+    #[test]
+    fn test_write_usdz_aligns_usda_layer() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_usdz(
+            &mut buffer,
+            &[UsdMesh {
+                name: "Variant 1".to_string(),
+                mesh: &mesh,
+                transform: Matrix4::identity(),
+            }],
+        )
+        .expect("Failed to write USDZ");
+
+        let u16_at =
+            |offset: usize| usize::from(u16::from_le_bytes([buffer[offset], buffer[offset + 1]]));
+        assert_eq!(&buffer[0..4], &[0x50, 0x4b, 0x03, 0x04]);
+
+        let name_len = u16_at(26);
+        let extra_field_len = u16_at(28);
+        assert_eq!(&buffer[30..30 + name_len], b"scene.usda");
+
+        let data_offset = 30 + name_len + extra_field_len;
+        assert_eq!(data_offset % 64, 0);
+
+        let mut usda = Vec::new();
+        write_usda(
+            &mut usda,
+            &[UsdMesh {
+                name: "Variant 1".to_string(),
+                mesh: &mesh,
+                transform: Matrix4::identity(),
+            }],
+        )
+        .expect("Failed to write USDA");
+        assert_eq!(&buffer[data_offset..data_offset + usda.len()], &usda[..]);
+    }
+
+    #[test]
+    fn test_write_usda_writes_mesh() {
+        let mesh = triangle_mesh_with_attributes();
+
+        let mut buffer = Vec::new();
+        write_usda(
+            &mut buffer,
+            &[UsdMesh {
+                name: "Variant 1".to_string(),
+                mesh: &mesh,
+                transform: Matrix4::new_translation(&nalgebra::Vector3::new(1.0, 2.0, 3.0)),
+            }],
+        )
+        .expect("Failed to write USDA");
+        let usda = String::from_utf8(buffer).expect("USDA must be valid UTF-8");
+
+        assert!(usda.starts_with("#usda 1.0"));
+        assert!(usda.contains("def Xform \"_0_Variant_1\""));
+        assert!(usda.contains("(1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (1, 2, 3, 1)"));
+        assert!(usda.contains("int[] faceVertexIndices = [0, 1, 2]"));
+        assert!(usda.contains("point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0.5)]"));
+    }
+}
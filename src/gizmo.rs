@@ -0,0 +1,501 @@
+use std::f32;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::camera::Camera;
+
+/// The length of the gizmo handles relative to the radius of the
+/// sphere visible by the camera, so that the gizmo keeps its size on
+/// the screen when zooming.
+const HANDLE_SIZE: f32 = 0.3;
+/// The largest distance in logical pixels of the cursor from a handle
+/// for it to be grabbed.
+const PICK_DISTANCE_MAX: f32 = 8.0;
+const RING_SEGMENT_COUNT: usize = 48;
+/// Scaling the handle below this fraction of its size would flatten
+/// the mesh, or mirror it once the cursor crosses the gizmo origin.
+const SCALE_RATIO_MIN: f32 = 0.01;
+/// Below this, the cursor ray is too parallel with a handle axis or
+/// plane to tell where on it the cursor is.
+const PARALLEL_EPSILON: f32 = 0.0001;
+
+/// Which parameters of a Transform the gizmo edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl GizmoAxis {
+    pub const ALL: [GizmoAxis; 3] = [GizmoAxis::X, GizmoAxis::Y, GizmoAxis::Z];
+
+    fn index(self) -> usize {
+        match self {
+            GizmoAxis::X => 0,
+            GizmoAxis::Y => 1,
+            GizmoAxis::Z => 2,
+        }
+    }
+
+    fn direction(self) -> Vector3<f32> {
+        match self {
+            GizmoAxis::X => Vector3::x(),
+            GizmoAxis::Y => Vector3::y(),
+            GizmoAxis::Z => Vector3::z(),
+        }
+    }
+
+    /// Returns the basis of the plane perpendicular to the axis, such
+    /// that the axis is their cross product.
+    fn plane_basis(self) -> (Vector3<f32>, Vector3<f32>) {
+        match self {
+            GizmoAxis::X => (Vector3::y(), Vector3::z()),
+            GizmoAxis::Y => (Vector3::z(), Vector3::x()),
+            GizmoAxis::Z => (Vector3::x(), Vector3::y()),
+        }
+    }
+}
+
+/// The parameters of a Transform edited by the gizmo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformParams {
+    pub translate: [f32; 3],
+    /// Euler angles in degrees.
+    pub rotate: [f32; 3],
+    pub scale: [f32; 3],
+}
+
+impl Default for TransformParams {
+    fn default() -> Self {
+        Self {
+            translate: [0.0; 3],
+            rotate: [0.0; 3],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+/// A gizmo handle projected to the screen for drawing, as a polyline
+/// in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GizmoHandle {
+    pub axis: GizmoAxis,
+    pub points: Vec<[f32; 2]>,
+    /// Whether the handle is hovered or dragged.
+    pub highlighted: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GizmoDrag {
+    mode: GizmoMode,
+    axis: GizmoAxis,
+    origin: Point3<f32>,
+    size: f32,
+    start_params: TransformParams,
+    /// Where the drag started along the axis, or for rotation the
+    /// angle of the cursor around the axis in the last frame.
+    start_value: f32,
+    /// The rotation accumulated over the drag, so that the handle can
+    /// be turned more than half a turn.
+    rotated_degrees: f32,
+}
+
+/// A viewport gizmo moving, rotating or scaling along the world axes.
+///
+/// The gizmo does not know what it edits. It is started with the
+/// current `TransformParams` and produces new ones while dragged.
+/// Rotation edits the Euler angle of the dragged axis, which matches
+/// the rotation around the world axis only if the other angles are
+/// zero.
+#[derive(Debug, Default)]
+pub struct Gizmo {
+    drag: Option<GizmoDrag>,
+}
+
+impl Gizmo {
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Returns the handles of the gizmo at the `origin` for
+    /// drawing. While dragging, the gizmo stays where the drag
+    /// started.
+    pub fn handles(
+        &self,
+        camera: &Camera,
+        window_size: [f32; 2],
+        mode: GizmoMode,
+        origin: &Point3<f32>,
+        cursor_position: [f32; 2],
+    ) -> Vec<GizmoHandle> {
+        let (mode, origin, size, highlighted_axis) = match &self.drag {
+            Some(drag) => (drag.mode, drag.origin, drag.size, Some(drag.axis)),
+            None => {
+                let size = handle_size(camera);
+                let hovered_axis = pick(camera, window_size, mode, origin, size, cursor_position);
+                (mode, *origin, size, hovered_axis)
+            }
+        };
+
+        GizmoAxis::ALL
+            .iter()
+            .map(|axis| GizmoHandle {
+                axis: *axis,
+                points: project_handle(camera, window_size, mode, &origin, size, *axis),
+                highlighted: highlighted_axis == Some(*axis),
+            })
+            .collect()
+    }
+
+    /// Starts dragging the handle under the cursor, if any. Returns
+    /// whether a handle was grabbed.
+    pub fn start_drag(
+        &mut self,
+        camera: &Camera,
+        window_size: [f32; 2],
+        mode: GizmoMode,
+        origin: &Point3<f32>,
+        cursor_position: [f32; 2],
+        params: TransformParams,
+    ) -> bool {
+        let size = handle_size(camera);
+        let axis = match pick(camera, window_size, mode, origin, size, cursor_position) {
+            Some(axis) => axis,
+            None => return false,
+        };
+
+        let (ray_origin, ray_direction) = cursor_ray(camera, window_size, cursor_position);
+        let start_value = match drag_value(mode, origin, axis, &ray_origin, &ray_direction) {
+            Some(start_value) => start_value,
+            None => return false,
+        };
+
+        self.drag = Some(GizmoDrag {
+            mode,
+            axis,
+            origin: *origin,
+            size,
+            start_params: params,
+            start_value,
+            rotated_degrees: 0.0,
+        });
+
+        true
+    }
+
+    /// Returns the parameters edited by the drag to the current cursor
+    /// position, or `None` if not dragging or the cursor position
+    /// can't be projected on the dragged handle.
+    pub fn drag(
+        &mut self,
+        camera: &Camera,
+        window_size: [f32; 2],
+        cursor_position: [f32; 2],
+    ) -> Option<TransformParams> {
+        let drag = self.drag.as_mut()?;
+        let (ray_origin, ray_direction) = cursor_ray(camera, window_size, cursor_position);
+        let value = drag_value(
+            drag.mode,
+            &drag.origin,
+            drag.axis,
+            &ray_origin,
+            &ray_direction,
+        )?;
+
+        let index = drag.axis.index();
+        let mut params = drag.start_params;
+        match drag.mode {
+            GizmoMode::Translate => {
+                params.translate[index] += value - drag.start_value;
+            }
+            GizmoMode::Rotate => {
+                let mut delta = value - drag.start_value;
+                if delta > 180.0 {
+                    delta -= 360.0;
+                } else if delta < -180.0 {
+                    delta += 360.0;
+                }
+
+                drag.rotated_degrees += delta;
+                drag.start_value = value;
+                params.rotate[index] += drag.rotated_degrees;
+            }
+            GizmoMode::Scale => {
+                // Grabbing the handle too close to the origin would
+                // make the scale jump with the slightest movement
+                let start_value = if drag.start_value.abs() < drag.size * SCALE_RATIO_MIN {
+                    drag.size
+                } else {
+                    drag.start_value
+                };
+                let ratio = (value / start_value).max(SCALE_RATIO_MIN);
+                params.scale[index] *= ratio;
+            }
+        }
+
+        Some(params)
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag = None;
+    }
+}
+
+fn handle_size(camera: &Camera) -> f32 {
+    let (_, visible_radius) = camera.visible_sphere();
+    visible_radius * HANDLE_SIZE
+}
+
+fn cursor_ray(
+    camera: &Camera,
+    window_size: [f32; 2],
+    cursor_position: [f32; 2],
+) -> (Point3<f32>, Vector3<f32>) {
+    let [width, height] = window_size;
+    let [x, y] = cursor_position;
+    camera.ray([x / width, y / height])
+}
+
+/// Returns the points of the handle in world space.
+fn handle_points(
+    mode: GizmoMode,
+    origin: &Point3<f32>,
+    size: f32,
+    axis: GizmoAxis,
+) -> Vec<Point3<f32>> {
+    match mode {
+        GizmoMode::Translate | GizmoMode::Scale => vec![*origin, origin + axis.direction() * size],
+        GizmoMode::Rotate => {
+            let (u, v) = axis.plane_basis();
+            (0..=RING_SEGMENT_COUNT)
+                .map(|segment| {
+                    let angle = 2.0 * f32::consts::PI * segment as f32 / RING_SEGMENT_COUNT as f32;
+                    origin + (u * angle.cos() + v * angle.sin()) * size
+                })
+                .collect()
+        }
+    }
+}
+
+/// Projects the handle to logical pixels. Points behind the camera are
+/// left out.
+fn project_handle(
+    camera: &Camera,
+    window_size: [f32; 2],
+    mode: GizmoMode,
+    origin: &Point3<f32>,
+    size: f32,
+    axis: GizmoAxis,
+) -> Vec<[f32; 2]> {
+    let [width, height] = window_size;
+    handle_points(mode, origin, size, axis)
+        .iter()
+        .filter_map(|point| camera.project(point))
+        .map(|[x, y]| [x * width, y * height])
+        .collect()
+}
+
+/// Finds the handle closest to the cursor on the screen, if it is close
+/// enough.
+fn pick(
+    camera: &Camera,
+    window_size: [f32; 2],
+    mode: GizmoMode,
+    origin: &Point3<f32>,
+    size: f32,
+    cursor_position: [f32; 2],
+) -> Option<GizmoAxis> {
+    let mut closest: Option<(f32, GizmoAxis)> = None;
+    for axis in GizmoAxis::ALL.iter() {
+        let points = project_handle(camera, window_size, mode, origin, size, *axis);
+        for segment in points.windows(2) {
+            let distance = segment_distance(cursor_position, segment[0], segment[1]);
+            let closer = closest.map_or(true, |(closest_distance, _)| distance < closest_distance);
+            if distance <= PICK_DISTANCE_MAX && closer {
+                closest = Some((distance, *axis));
+            }
+        }
+    }
+
+    closest.map(|(_, axis)| axis)
+}
+
+/// Returns the distance of the point from the line segment between `a`
+/// and `b`.
+fn segment_distance(point: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let ap = [point[0] - a[0], point[1] - a[1]];
+    let length_squared = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if length_squared > 0.0 {
+        ((ap[0] * ab[0] + ap[1] * ab[1]) / length_squared)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+
+    (ap[0] - t * ab[0]).hypot(ap[1] - t * ab[1])
+}
+
+/// Returns where on the handle the ray points. For moving and scaling,
+/// this is the position along the axis closest to the ray. For
+/// rotation, this is the angle in degrees around the axis, at which
+/// the ray hits the plane of the ring.
+fn drag_value(
+    mode: GizmoMode,
+    origin: &Point3<f32>,
+    axis: GizmoAxis,
+    ray_origin: &Point3<f32>,
+    ray_direction: &Vector3<f32>,
+) -> Option<f32> {
+    let direction = axis.direction();
+    match mode {
+        GizmoMode::Translate | GizmoMode::Scale => {
+            // The closest points of two lines, simplified for the unit
+            // directions of the axis and the ray
+            let w = origin - ray_origin;
+            let b = direction.dot(ray_direction);
+            let denominator = 1.0 - b * b;
+            if denominator < PARALLEL_EPSILON {
+                return None;
+            }
+
+            Some((b * ray_direction.dot(&w) - direction.dot(&w)) / denominator)
+        }
+        GizmoMode::Rotate => {
+            let denominator = ray_direction.dot(&direction);
+            if denominator.abs() < PARALLEL_EPSILON {
+                return None;
+            }
+
+            let t = (origin - ray_origin).dot(&direction) / denominator;
+            let hit = ray_origin + ray_direction * t;
+            let (u, v) = axis.plane_basis();
+            let relative = hit - origin;
+
+            Some(relative.dot(&v).atan2(relative.dot(&u)).to_degrees())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::CameraOptions;
+
+    use super::*;
+
+    const WINDOW_SIZE: [f32; 2] = [800.0, 600.0];
+
+    fn camera() -> Camera {
+        Camera::new(
+            winit::dpi::PhysicalSize::new(800.0, 600.0),
+            5.0,
+            45f32.to_radians(),
+            60f32.to_radians(),
+            CameraOptions {
+                radius_min: 1.0,
+                radius_max: 100.0,
+                polar_angle_distance_min: 1f32.to_radians(),
+                speed_pan: 10.0,
+                speed_rotate: 0.005,
+                speed_zoom: 0.01,
+                speed_zoom_step: 1.0,
+                fovy: 45f32.to_radians(),
+                znear: 0.01,
+                zfar: 1000.0,
+            },
+        )
+    }
+
+    fn screen_position(camera: &Camera, point: &Point3<f32>) -> [f32; 2] {
+        let [x, y] = camera.project(point).expect("Point must be in front");
+        [x * WINDOW_SIZE[0], y * WINDOW_SIZE[1]]
+    }
+
+    #[test]
+    fn test_gizmo_translate_drag_moves_along_grabbed_axis() {
+        let camera = camera();
+        let origin = Point3::origin();
+        let size = handle_size(&camera);
+        let mut gizmo = Gizmo::default();
+
+        let grab = screen_position(&camera, &Point3::new(size / 2.0, 0.0, 0.0));
+        let grabbed = gizmo.start_drag(
+            &camera,
+            WINDOW_SIZE,
+            GizmoMode::Translate,
+            &origin,
+            grab,
+            TransformParams::default(),
+        );
+        assert!(grabbed);
+
+        let release = screen_position(&camera, &Point3::new(size / 2.0 + 1.0, 0.0, 0.0));
+        let params = gizmo
+            .drag(&camera, WINDOW_SIZE, release)
+            .expect("Drag must produce params");
+
+        assert!((params.translate[0] - 1.0).abs() < 0.001);
+        assert_eq!(params.translate[1], 0.0);
+        assert_eq!(params.translate[2], 0.0);
+        assert_eq!(params.rotate, [0.0; 3]);
+        assert_eq!(params.scale, [1.0; 3]);
+    }
+
+    #[test]
+    fn test_gizmo_rotate_drag_turns_around_grabbed_axis() {
+        let camera = camera();
+        let origin = Point3::origin();
+        let size = handle_size(&camera);
+        let mut gizmo = Gizmo::default();
+
+        // The rings of the other axes cross the Z ring on the X and Y
+        // axes, so it is grabbed between them
+        let diagonal = size * f32::consts::FRAC_1_SQRT_2;
+        let grab = screen_position(&camera, &Point3::new(diagonal, diagonal, 0.0));
+        let grabbed = gizmo.start_drag(
+            &camera,
+            WINDOW_SIZE,
+            GizmoMode::Rotate,
+            &origin,
+            grab,
+            TransformParams::default(),
+        );
+        assert!(grabbed);
+
+        let release = screen_position(&camera, &Point3::new(-diagonal, diagonal, 0.0));
+        let params = gizmo
+            .drag(&camera, WINDOW_SIZE, release)
+            .expect("Drag must produce params");
+
+        assert!((params.rotate[2] - 90.0).abs() < 0.1);
+        assert_eq!(params.rotate[0], 0.0);
+        assert_eq!(params.rotate[1], 0.0);
+    }
+
+    #[test]
+    fn test_gizmo_does_not_grab_away_from_handles() {
+        let camera = camera();
+        let mut gizmo = Gizmo::default();
+
+        let grabbed = gizmo.start_drag(
+            &camera,
+            WINDOW_SIZE,
+            GizmoMode::Translate,
+            &Point3::origin(),
+            [0.0, 0.0],
+            TransformParams::default(),
+        );
+
+        assert!(!grabbed);
+        assert!(!gizmo.is_dragging());
+    }
+}
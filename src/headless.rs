@@ -0,0 +1,383 @@
+use std::error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::exporter::{self, ExporterError};
+use crate::importer::{self, ImporterError};
+use crate::interpreter::{FuncCategory, InterpretError, Interpreter, LogMessageLevel};
+use crate::interpreter_funcs;
+use crate::mesh::{tools, Mesh};
+use crate::project::{self, ProjectError};
+use crate::sweep;
+use crate::units::LengthUnit;
+
+#[derive(Debug)]
+pub enum HeadlessError {
+    Project(ProjectError),
+    Interpret(InterpretError),
+    Import(ImporterError),
+    Export(ExporterError),
+    NoMeshes,
+    WeldFailed,
+    UnsupportedFormat,
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeadlessError::Project(err) => write!(f, "{}", err),
+            HeadlessError::Interpret(err) => write!(f, "Failed to run the pipeline: {}", err),
+            HeadlessError::Import(err) => write!(f, "Failed to import the mesh: {}", err),
+            HeadlessError::Export(err) => write!(f, "Failed to export the result: {}", err),
+            HeadlessError::NoMeshes => write!(f, "The pipeline produced no meshes to export"),
+            HeadlessError::WeldFailed => write!(f, "Welding produced an empty mesh"),
+            HeadlessError::UnsupportedFormat => write!(
+                f,
+                "Unsupported output format, expected a .obj, .stl or .glb path"
+            ),
+        }
+    }
+}
+
+impl error::Error for HeadlessError {}
+
+impl From<ProjectError> for HeadlessError {
+    fn from(err: ProjectError) -> Self {
+        HeadlessError::Project(err)
+    }
+}
+
+impl From<InterpretError> for HeadlessError {
+    fn from(err: InterpretError) -> Self {
+        HeadlessError::Interpret(err)
+    }
+}
+
+impl From<ImporterError> for HeadlessError {
+    fn from(err: ImporterError) -> Self {
+        HeadlessError::Import(err)
+    }
+}
+
+impl From<ExporterError> for HeadlessError {
+    fn from(err: ExporterError) -> Self {
+        HeadlessError::Export(err)
+    }
+}
+
+/// Loads the project at `project_path`, runs its whole pipeline and
+/// exports the meshes of the terminal operations to `output_path`.
+///
+/// A glTF output (`.glb`) holds all the meshes. An OBJ (`.obj`) or STL
+/// (`.stl`) output holds a single mesh, so if there are more, each is
+/// written to its own file with a numbered suffix, e.g.
+/// `result_2.obj`.
+pub fn run<P, Q>(project_path: P, output_path: Q) -> Result<(), HeadlessError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let project_path = project_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    // Fail early, before running the whole pipeline
+    OutputFormat::from_path(output_path).ok_or(HeadlessError::UnsupportedFormat)?;
+
+    let function_table = interpreter_funcs::create_function_table();
+    let project = project::load_project(project_path, &function_table)?;
+    let stmt_count = project.prog.stmts().len();
+    let unit = project.unit;
+
+    log::info!(
+        "Running {} operations of project {}",
+        stmt_count,
+        project_path.display(),
+    );
+
+    let mut interpreter = Interpreter::new(function_table);
+    interpreter.set_progress_callback(|progress| {
+        // Only log when an operation starts, not each progress update
+        // of its func
+        if progress.func_progress == 0.0 {
+            log::info!(
+                "Computing operation {}/{}",
+                progress.stmt_index + 1,
+                progress.stmt_count,
+            );
+        }
+    });
+    interpreter.set_prog(project.prog);
+    let outcome = interpreter.interpret();
+
+    for (index, log_messages) in outcome.log_messages.iter().enumerate() {
+        for log_message in log_messages {
+            match log_message.level {
+                LogMessageLevel::Info => {
+                    log::info!("Operation {}: {}", index + 1, log_message.message)
+                }
+                LogMessageLevel::Warn => {
+                    log::warn!("Operation {}: {}", index + 1, log_message.message)
+                }
+                LogMessageLevel::Error => {
+                    log::error!("Operation {}: {}", index + 1, log_message.message)
+                }
+            }
+        }
+    }
+
+    let interpret_value = outcome.result?;
+    let meshes = sweep::terminal_meshes(&interpret_value.unused_values);
+
+    export_meshes(output_path, &meshes, unit)
+}
+
+/// Exports the meshes to `output_path`, like `run` exports the meshes
+/// of the terminal operations. Meshes exported to formats without
+/// units are scaled from meters to the `unit`.
+pub fn export_meshes<P: AsRef<Path>>(
+    output_path: P,
+    meshes: &[Arc<Mesh>],
+    unit: LengthUnit,
+) -> Result<(), HeadlessError> {
+    let output_path = output_path.as_ref();
+
+    let format = OutputFormat::from_path(output_path).ok_or(HeadlessError::UnsupportedFormat)?;
+    if meshes.is_empty() {
+        return Err(HeadlessError::NoMeshes);
+    }
+
+    match format {
+        OutputFormat::Obj | OutputFormat::Stl => {
+            export_single_mesh_files(output_path, meshes, unit, format)?
+        }
+        OutputFormat::Glb => {
+            let named_meshes: Vec<(String, &Mesh)> = meshes
+                .iter()
+                .enumerate()
+                .map(|(index, mesh)| (format!("Mesh {}", index + 1), &**mesh))
+                .collect();
+            exporter::export_glb(output_path, &named_meshes)?;
+            log::info!(
+                "Exported {} meshes to {}",
+                meshes.len(),
+                output_path.display(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Options of the mesh processing done by `convert`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ConvertOptions {
+    /// Welds vertices closer than the tolerance, if set.
+    pub weld_tolerance: Option<f32>,
+    /// Recomputes the normals with the crease angle (in degrees), if
+    /// set. Runs after welding, so that the welded faces get smooth
+    /// normals.
+    pub crease_angle: Option<f32>,
+}
+
+/// Imports the mesh at `input_path`, processes it as requested by the
+/// `options` and exports it to `output_path` (`.obj`, `.stl` or
+/// `.glb`). All models of the input file are joined into a single
+/// mesh. Coordinates are kept as they are, no units are converted.
+pub fn convert<P, Q>(
+    input_path: P,
+    output_path: Q,
+    options: ConvertOptions,
+) -> Result<(), HeadlessError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let format = OutputFormat::from_path(output_path).ok_or(HeadlessError::UnsupportedFormat)?;
+
+    let mut mesh = importer::import_obj_joined(&input_path.to_string_lossy())?;
+    log::info!(
+        "Imported mesh with {} vertices and {} faces from {}",
+        mesh.vertices().len(),
+        mesh.faces().len(),
+        input_path.display(),
+    );
+
+    if let Some(weld_tolerance) = options.weld_tolerance {
+        // Welding works on triangles, the quads of the mesh are split
+        mesh =
+            tools::weld(&mesh.triangulated(), weld_tolerance).ok_or(HeadlessError::WeldFailed)?;
+        log::info!(
+            "Welded mesh with tolerance {} to {} vertices",
+            weld_tolerance,
+            mesh.vertices().len(),
+        );
+    }
+
+    if let Some(crease_angle) = options.crease_angle {
+        mesh = tools::recompute_normals(&mesh, crease_angle.to_radians());
+        log::info!("Recomputed normals with crease angle {}°", crease_angle);
+    }
+
+    match format {
+        OutputFormat::Obj => exporter::export_obj(output_path, &mesh)?,
+        OutputFormat::Stl => exporter::export_stl(output_path, &mesh)?,
+        OutputFormat::Glb => {
+            let name = input_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| String::from("Mesh"));
+            exporter::export_glb(output_path, &[(name, &mesh)])?;
+        }
+    }
+    log::info!("Exported mesh to {}", output_path.display());
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Obj,
+    Stl,
+    Glb,
+}
+
+impl OutputFormat {
+    /// Guesses the format from the extension of the path.
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+
+        match extension.as_ref().map(String::as_str) {
+            Some("obj") => Some(OutputFormat::Obj),
+            Some("stl") => Some(OutputFormat::Stl),
+            Some("glb") => Some(OutputFormat::Glb),
+            _ => None,
+        }
+    }
+}
+
+/// Describes the operations projects can be built of, grouped by
+/// category. Lists the identifier of each operation and the index and
+/// type of each of its parameters, which projects written or edited
+/// outside the editor, e.g. over IPC, refer to.
+pub fn operation_catalog() -> String {
+    let function_table = interpreter_funcs::create_function_table();
+    let mut catalog = String::new();
+
+    for category in &FuncCategory::ALL {
+        catalog.push_str(&format!("{}\n", category.name()));
+
+        for (func_ident, func) in function_table
+            .iter()
+            .filter(|(_, func)| func.info().category == *category)
+        {
+            let info = func.info();
+            catalog.push_str(&format!(
+                "  {} {} -> {}\n",
+                func_ident.0, info.name, info.return_value_name,
+            ));
+            if !info.tags.is_empty() {
+                catalog.push_str(&format!("      tags: {}\n", info.tags.join(", ")));
+            }
+
+            for (index, param_info) in func.param_info().iter().enumerate() {
+                catalog.push_str(&format!(
+                    "      {}: {} ({}{})\n",
+                    index,
+                    param_info.name,
+                    param_info.refinement.ty(),
+                    if param_info.optional {
+                        ", optional"
+                    } else {
+                        ""
+                    },
+                ));
+            }
+        }
+    }
+
+    catalog
+}
+
+/// Exports the meshes to OBJ or STL files. Neither has a notion of
+/// units, so the meshes are scaled from meters to the project's unit.
+fn export_single_mesh_files(
+    output_path: &Path,
+    meshes: &[Arc<Mesh>],
+    unit: LengthUnit,
+    format: OutputFormat,
+) -> Result<(), ExporterError> {
+    for (index, mesh) in meshes.iter().enumerate() {
+        let path = if meshes.len() == 1 {
+            output_path.to_path_buf()
+        } else {
+            numbered_path(output_path, index + 1)
+        };
+
+        let scaled_mesh = tools::scale_mesh(mesh, unit.meters_to_value(1.0));
+        if format == OutputFormat::Stl {
+            exporter::export_stl(&path, &scaled_mesh)?;
+        } else {
+            exporter::export_obj(&path, &scaled_mesh)?;
+        }
+        log::info!("Exported mesh {} to {}", index + 1, path.display());
+    }
+
+    Ok(())
+}
+
+/// Appends the number to the file stem of the path, keeping its
+/// extension.
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut file_name = format!("{}_{}", stem, number);
+    if let Some(extension) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_from_path_ignores_case() {
+        assert_eq!(
+            OutputFormat::from_path(Path::new("result.STL")),
+            Some(OutputFormat::Stl),
+        );
+        assert_eq!(OutputFormat::from_path(Path::new("result.ply")), None);
+    }
+
+    #[test]
+    fn test_operation_catalog_lists_all_funcs() {
+        let catalog = operation_catalog();
+
+        for func in interpreter_funcs::create_function_table().values() {
+            let line = format!(" {} -> {}", func.info().name, func.info().return_value_name);
+            assert!(catalog.contains(&line), "Missing {}", func.info().name);
+        }
+    }
+
+    #[test]
+    fn test_numbered_path_keeps_directory_and_extension() {
+        let path = Path::new("out").join("result.obj");
+
+        assert_eq!(
+            numbered_path(&path, 2),
+            Path::new("out").join("result_2.obj"),
+        );
+    }
+}
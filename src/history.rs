@@ -0,0 +1,262 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::interpreter::spill::estimate_mesh_size;
+use crate::interpreter::VarIdent;
+use crate::mesh::Mesh;
+
+/// A unique identifier of an evaluation history entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HistoryEntryId(u64);
+
+/// A single evaluated result of a terminal pipeline operation.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    id: HistoryEntryId,
+    meshes: Vec<Arc<Mesh>>,
+    size_bytes: usize,
+    pinned: bool,
+}
+
+impl HistoryEntry {
+    pub fn id(&self) -> HistoryEntryId {
+        self.id
+    }
+
+    pub fn meshes(&self) -> &[Arc<Mesh>] {
+        &self.meshes
+    }
+
+    /// The estimated memory footprint of the entry's geometry.
+    pub fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+/// Remembers the last few evaluated results of terminal pipeline
+/// operations.
+///
+/// At most `max_entries_per_var` results are kept per variable, and
+/// the oldest results are forgotten once the estimated total size of
+/// the remembered geometry exceeds `memory_budget_bytes`. Pinned
+/// results are never forgotten and don't count towards the limits.
+pub struct EvaluationHistory {
+    entries: HashMap<VarIdent, VecDeque<HistoryEntry>>,
+    next_entry_id: u64,
+    max_entries_per_var: usize,
+    memory_budget_bytes: usize,
+}
+
+impl EvaluationHistory {
+    pub fn new(max_entries_per_var: usize, memory_budget_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            next_entry_id: 0,
+            max_entries_per_var,
+            memory_budget_bytes,
+        }
+    }
+
+    /// Records a newly evaluated result of a variable and forgets the
+    /// oldest unpinned results, if the limits are exceeded.
+    pub fn record(&mut self, var_ident: VarIdent, meshes: Vec<Arc<Mesh>>) -> HistoryEntryId {
+        let id = HistoryEntryId(self.next_entry_id);
+        self.next_entry_id += 1;
+
+        let size_bytes = meshes.iter().map(|mesh| estimate_mesh_size(mesh)).sum();
+        let var_entries = self.entries.entry(var_ident).or_insert_with(VecDeque::new);
+        var_entries.push_back(HistoryEntry {
+            id,
+            meshes,
+            size_bytes,
+            pinned: false,
+        });
+
+        // Enforce the per-variable limit first...
+        let unpinned_count = var_entries.iter().filter(|entry| !entry.pinned).count();
+        if unpinned_count > self.max_entries_per_var {
+            let mut excess = unpinned_count - self.max_entries_per_var;
+            var_entries.retain(|entry| {
+                if excess > 0 && !entry.pinned {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        // ... and then the global memory budget, forgetting the
+        // oldest results across all variables.
+        while self.unpinned_size_bytes() > self.memory_budget_bytes {
+            let oldest = self
+                .entries
+                .values()
+                .flat_map(|var_entries| var_entries.iter())
+                .filter(|entry| !entry.pinned)
+                .map(|entry| entry.id.0)
+                .min();
+
+            match oldest {
+                Some(oldest) => self.remove(HistoryEntryId(oldest)),
+                None => break,
+            }
+        }
+
+        id
+    }
+
+    /// Returns the remembered results of a variable, oldest first.
+    pub fn entries(&self, var_ident: VarIdent) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.get(&var_ident).into_iter().flatten()
+    }
+
+    /// Returns the result of a variable evaluated before the most
+    /// recent one, if any.
+    pub fn previous_entry(&self, var_ident: VarIdent) -> Option<&HistoryEntry> {
+        self.entries
+            .get(&var_ident)
+            .and_then(|var_entries| var_entries.iter().rev().nth(1))
+    }
+
+    /// Returns the variables with remembered results.
+    pub fn var_idents(&self) -> impl Iterator<Item = VarIdent> + '_ {
+        self.entries
+            .iter()
+            .filter(|(_, var_entries)| !var_entries.is_empty())
+            .map(|(var_ident, _)| *var_ident)
+    }
+
+    /// Returns the pinned results of all variables.
+    pub fn pinned_entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.values().flatten().filter(|entry| entry.pinned)
+    }
+
+    /// Pins or unpins a remembered result. Returns the result, if it
+    /// still exists.
+    pub fn set_pinned(&mut self, id: HistoryEntryId, pinned: bool) -> Option<&HistoryEntry> {
+        let entry = self
+            .entries
+            .values_mut()
+            .flatten()
+            .find(|entry| entry.id == id)?;
+
+        entry.pinned = pinned;
+
+        Some(entry)
+    }
+
+    fn remove(&mut self, id: HistoryEntryId) {
+        for var_entries in self.entries.values_mut() {
+            var_entries.retain(|entry| entry.id != id);
+        }
+    }
+
+    fn unpinned_size_bytes(&self) -> usize {
+        self.entries
+            .values()
+            .flatten()
+            .filter(|entry| !entry.pinned)
+            .map(|entry| entry.size_bytes)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Point3;
+
+    use crate::mesh::NormalStrategy;
+
+    use super::*;
+
+    fn triangle_mesh() -> Arc<Mesh> {
+        Arc::new(
+            Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+                vec![(0, 1, 2)],
+                vec![
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(1.0, 0.0, 0.0),
+                    Point3::new(0.0, 1.0, 0.0),
+                ],
+                NormalStrategy::Sharp,
+            ),
+        )
+    }
+
+    #[test]
+    fn test_evaluation_history_forgets_oldest_entries_over_count_limit() {
+        let mut history = EvaluationHistory::new(2, usize::max_value());
+
+        let first = history.record(VarIdent(0), vec![triangle_mesh()]);
+        let second = history.record(VarIdent(0), vec![triangle_mesh()]);
+        let third = history.record(VarIdent(0), vec![triangle_mesh()]);
+        history.record(VarIdent(1), vec![triangle_mesh()]);
+
+        let ids: Vec<_> = history
+            .entries(VarIdent(0))
+            .map(|entry| entry.id())
+            .collect();
+        assert_eq!(ids, vec![second, third]);
+        assert!(ids.iter().all(|id| *id != first));
+        assert_eq!(history.entries(VarIdent(1)).count(), 1);
+    }
+
+    #[test]
+    fn test_evaluation_history_keeps_pinned_entries() {
+        let mut history = EvaluationHistory::new(1, usize::max_value());
+
+        let first = history.record(VarIdent(0), vec![triangle_mesh()]);
+        assert!(history.set_pinned(first, true).is_some());
+
+        let second = history.record(VarIdent(0), vec![triangle_mesh()]);
+        let third = history.record(VarIdent(0), vec![triangle_mesh()]);
+
+        let ids: Vec<_> = history
+            .entries(VarIdent(0))
+            .map(|entry| entry.id())
+            .collect();
+        assert_eq!(ids, vec![first, third]);
+        assert!(history.set_pinned(second, true).is_none());
+    }
+
+    #[test]
+    fn test_evaluation_history_previous_entry() {
+        let mut history = EvaluationHistory::new(8, usize::max_value());
+
+        history.record(VarIdent(0), vec![triangle_mesh()]);
+        assert!(history.previous_entry(VarIdent(0)).is_none());
+
+        let second = history.record(VarIdent(0), vec![triangle_mesh()]);
+        history.record(VarIdent(0), vec![triangle_mesh()]);
+
+        let previous = history.previous_entry(VarIdent(0)).map(|entry| entry.id());
+        assert_eq!(previous, Some(second));
+        assert!(history.previous_entry(VarIdent(1)).is_none());
+    }
+
+    #[test]
+    fn test_evaluation_history_forgets_oldest_entries_over_memory_budget() {
+        let mesh_size = estimate_mesh_size(&triangle_mesh());
+        let mut history = EvaluationHistory::new(10, 2 * mesh_size);
+
+        history.record(VarIdent(0), vec![triangle_mesh()]);
+        let second = history.record(VarIdent(1), vec![triangle_mesh()]);
+        let third = history.record(VarIdent(0), vec![triangle_mesh()]);
+
+        let ids_0: Vec<_> = history
+            .entries(VarIdent(0))
+            .map(|entry| entry.id())
+            .collect();
+        let ids_1: Vec<_> = history
+            .entries(VarIdent(1))
+            .map(|entry| entry.id())
+            .collect();
+        assert_eq!(ids_0, vec![third]);
+        assert_eq!(ids_1, vec![second]);
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::error;
 use std::fmt;
-use std::fs;
-use std::io::{self, Read};
+use std::io;
+use std::path::Path;
+use std::str;
 use std::time::SystemTime;
 
 use crc32fast;
@@ -11,7 +13,10 @@ use mockall::{automock, lazy_static, predicate};
 use nalgebra::{Point3, Vector3};
 use tobj;
 
-use crate::mesh::{Mesh, NormalStrategy, TriangleFace};
+use crate::curve::Polyline;
+use crate::mesh::normals::VertexNormals;
+use crate::mesh::{tools, Face, Mesh, NormalStrategy, QuadFace, TriangleFace};
+use crate::platform::{FileSystem, PlatformFileSystem};
 
 #[derive(Debug, PartialEq)]
 pub enum ImporterError {
@@ -25,7 +30,7 @@ impl fmt::Display for ImporterError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ImporterError::FileNotFound => write!(f, "File was not found."),
-            ImporterError::InvalidStructure => write!(f, "The obj file is not valid."),
+            ImporterError::InvalidStructure => write!(f, "The file is not valid."),
             ImporterError::PermissionDenied => write!(f, "Permission denied."),
             ImporterError::Other => write!(f, "Unexpected error happened."),
         }
@@ -125,13 +130,21 @@ impl ObjCache for EndlessCache {
 
 /// `Importer` takes care of importing of obj files and caching of their
 /// internal representations.
-pub struct Importer<C: ObjCache> {
+pub struct Importer<C: ObjCache, F: FileSystem = PlatformFileSystem> {
     cache: C,
+    file_system: F,
 }
 
 impl<C: ObjCache> Importer<C> {
     pub fn new(cache: C) -> Self {
-        Self { cache }
+        Self::with_file_system(cache, PlatformFileSystem)
+    }
+}
+
+impl<C: ObjCache, F: FileSystem> Importer<C, F> {
+    /// Creates an importer reading files from the `file_system`.
+    pub fn with_file_system(cache: C, file_system: F) -> Self {
+        Self { cache, file_system }
     }
 
     /// Tries to import obj file from given `path`. If file was already imported
@@ -140,27 +153,22 @@ impl<C: ObjCache> Importer<C> {
     /// given file contents were already saved. If not, obj file is parsed and
     /// cached.
     pub fn import_obj(&mut self, path: &str) -> ImporterResult {
-        let mut file = fs::File::open(path)?;
-        let file_metadata = file.metadata().expect("Failed to load obj file metadata");
-        let file_modified = file_metadata
-            .modified()
-            .expect("Failed to load modified timestamp of obj file");
+        let file_modified = self.file_system.modified(Path::new(path))?;
 
         let models = match self.cache.get_if_not_modified(path, file_modified) {
             Some(models) => return Ok(models),
             None => {
-                let file_size = file_metadata.len() as usize;
-                // Allocate one extra byte so the buffer doesn't need to grow before the
-                // final `read` call at the end of the file.
-                let mut file_contents = Vec::with_capacity(file_size + 1);
-                file.read_to_end(&mut file_contents)?;
+                // Large files are parsed straight from the mapped
+                // file, without first copying them into memory
+                let file_contents = self.file_system.read_mapped(Path::new(path))?;
                 let checksum = calculate_checksum(&file_contents);
 
                 let models = match self.cache.get_by_checksum(checksum) {
                     Some(models) => models.clone(),
                     None => {
-                        let (tobj_models, _) = obj_buf_into_tobj(&mut file_contents.as_slice())?;
-                        tobj_to_internal(tobj_models)
+                        let (tobj_models, _) = obj_buf_into_tobj(&mut &file_contents[..])?;
+                        let face_sizes = decode_obj_face_sizes(&file_contents);
+                        tobj_to_internal_with_face_sizes(tobj_models, &face_sizes)
                     }
                 };
 
@@ -181,16 +189,148 @@ impl<C: ObjCache> Importer<C> {
     }
 }
 
+/// Reads obj file from given `path`, bypassing any cache, and joins
+/// all of its models into a single mesh.
+pub fn import_obj_joined(path: &str) -> Result<Mesh, ImporterError> {
+    let file_contents = PlatformFileSystem.read_mapped(Path::new(path))?;
+    let (tobj_models, _) = obj_buf_into_tobj(&mut &file_contents[..])?;
+    let face_sizes = decode_obj_face_sizes(&file_contents);
+    let models = tobj_to_internal_with_face_sizes(tobj_models, &face_sizes);
+
+    Ok(tools::join_multiple_meshes(
+        models.iter().map(|model| &model.mesh),
+    ))
+}
+
+/// Reads the line elements (`l`) of obj file from given `path` as
+/// polylines. Faces are ignored.
+pub fn import_obj_polylines(path: &str) -> Result<Vec<Polyline>, ImporterError> {
+    let file_contents = PlatformFileSystem.read(Path::new(path))?;
+    decode_obj_polylines(&file_contents)
+}
+
+/// Decodes the vertices and line elements of obj file, which tobj
+/// skips. Lines that end with the vertex they start with are closed
+/// polylines.
+fn decode_obj_polylines(file_contents: &[u8]) -> Result<Vec<Polyline>, ImporterError> {
+    let file_contents =
+        str::from_utf8(file_contents).map_err(|_| ImporterError::InvalidStructure)?;
+
+    let mut vertices = Vec::new();
+    let mut polylines = Vec::new();
+    for line in file_contents.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let mut position = [0.0; 3];
+                for coordinate in &mut position {
+                    *coordinate = words
+                        .next()
+                        .and_then(|word| word.parse().ok())
+                        .ok_or(ImporterError::InvalidStructure)?;
+                }
+                vertices.push(Point3::new(position[0], position[1], position[2]));
+            }
+            Some("l") => {
+                let mut indices = Vec::new();
+                for word in words {
+                    // The texture coordinates after the slash are ignored
+                    let index: isize = word
+                        .split('/')
+                        .next()
+                        .and_then(|index| index.parse().ok())
+                        .ok_or(ImporterError::InvalidStructure)?;
+                    // Indices are 1-based, or relative to the end of
+                    // the vertices read so far, if negative
+                    let index = if index < 0 {
+                        index
+                            .checked_neg()
+                            .and_then(|offset| usize::try_from(offset).ok())
+                            .and_then(|offset| vertices.len().checked_sub(offset))
+                    } else {
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|index| index.checked_sub(1))
+                    };
+
+                    match index {
+                        Some(index) if index < vertices.len() => indices.push(index),
+                        _ => return Err(ImporterError::InvalidStructure),
+                    }
+                }
+
+                let closed = indices.len() > 2 && indices.first() == indices.last();
+                if closed {
+                    indices.pop();
+                }
+
+                let polyline_vertices = indices.iter().map(|index| vertices[*index]).collect();
+                polylines.push(Polyline::new(polyline_vertices, closed));
+            }
+            _ => (/* Other elements are not part of the polylines */),
+        }
+    }
+
+    Ok(polylines)
+}
+
+/// Decodes the number of vertices of each face element (`f`) of obj
+/// file, in the order of the file. Tobj triangulates all faces, these
+/// sizes are used to join the triangles of quads back together.
+pub fn decode_obj_face_sizes(file_contents: &[u8]) -> Vec<usize> {
+    file_contents
+        .split(|byte| *byte == b'\n')
+        .filter_map(|line| {
+            let mut words = line
+                .split(u8::is_ascii_whitespace)
+                .filter(|word| !word.is_empty());
+            if words.next() == Some(b"f") {
+                Some(words.count())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Converts contents of obj file into tobj representation. Materials are
-/// ignored.
+/// ignored. The contents are parsed line by line, without copying them.
 pub fn obj_buf_into_tobj(file_contents: &mut &[u8]) -> tobj::LoadResult {
     tobj::load_obj_buf(file_contents, |_| Ok((vec![], HashMap::new())))
 }
 
 /// Converts `tobj::Model` vector into vector of internal `Model` representations.
 /// It expects valid `tobj::Model` representation, eg. number of positions
-/// divisible by 3.
+/// divisible by 3. All faces are triangles.
 pub fn tobj_to_internal(tobj_models: Vec<tobj::Model>) -> Vec<Model> {
+    tobj_to_internal_with_face_sizes(tobj_models, &[])
+}
+
+/// Converts `tobj::Model` vector into vector of internal `Model`
+/// representations, like `tobj_to_internal`. The `face_sizes` are the
+/// numbers of vertices of the faces of the obj file, as decoded by
+/// `decode_obj_face_sizes`. The two triangles tobj splits each quad
+/// into are joined back into the quad. If the sizes don't match the
+/// triangles of the models, all faces are kept as triangles.
+pub fn tobj_to_internal_with_face_sizes(
+    tobj_models: Vec<tobj::Model>,
+    face_sizes: &[usize],
+) -> Vec<Model> {
+    let triangle_count: usize = tobj_models
+        .iter()
+        .map(|model| model.mesh.indices.len() / 3)
+        .sum();
+    let face_sizes_triangle_count: usize = face_sizes
+        .iter()
+        .map(|face_size| face_size.saturating_sub(2))
+        .sum();
+    let face_sizes = if face_sizes_triangle_count == triangle_count {
+        face_sizes
+    } else {
+        &[]
+    };
+    let mut face_sizes = face_sizes.iter();
+
     let mut models = Vec::with_capacity(tobj_models.len());
 
     for model in tobj_models {
@@ -214,22 +354,52 @@ pub fn tobj_to_internal(tobj_models: Vec<tobj::Model>) -> Vec<Model> {
             Some(normals)
         };
 
-        let faces_raw: Vec<(u32, u32, u32)> = model
-            .mesh
-            .indices
-            .chunks_exact(3)
-            .map(|chunk| (chunk[0], chunk[1], chunk[2]))
-            .collect();
+        let mut triangles = model.mesh.indices.chunks_exact(3);
+        let mut faces = Vec::with_capacity(triangles.len());
+        while let Some(triangle) = triangles.next() {
+            let (v1, v2, v3) = (triangle[0], triangle[1], triangle[2]);
+            let face_size = face_sizes.next().copied().unwrap_or(3);
+
+            // Tobj splits a quad (v1, v2, v3, v4) into triangles
+            // (v1, v2, v3) and (v1, v3, v4)
+            if face_size == 4 {
+                let second_triangle = triangles.next();
+                match second_triangle {
+                    Some(&[w1, w3, v4])
+                        if w1 == v1 && w3 == v3 && v4 != v1 && v4 != v2 && v4 != v3 =>
+                    {
+                        faces.push(Face::from(QuadFace::new(v1, v2, v3, v4)));
+                    }
+                    _ => {
+                        faces.push(Face::from(TriangleFace::new(v1, v2, v3)));
+                        if let Some(&[w1, w2, w3]) = second_triangle {
+                            faces.push(Face::from(TriangleFace::new(w1, w2, w3)));
+                        }
+                    }
+                }
+            } else {
+                faces.push(Face::from(TriangleFace::new(v1, v2, v3)));
+                // The remaining triangles of larger polygons stay
+                // triangles
+                for _ in 3..face_size {
+                    if let Some(&[w1, w2, w3]) = triangles.next() {
+                        faces.push(Face::from(TriangleFace::new(w1, w2, w3)));
+                    }
+                }
+            }
+        }
 
         let mesh = if let Some(vertex_normals) = vertex_normals {
-            Mesh::from_triangle_faces_with_vertices_and_normals(
-                faces_raw.into_iter().map(TriangleFace::from),
+            // The vertices are indexed together with their normals, so
+            // there is a normal for each vertex
+            Mesh::from_faces_with_vertices_and_vertex_normals(
+                faces,
                 vertex_positions,
-                vertex_normals,
+                VertexNormals::from_vertex_normals(vertex_normals),
             )
         } else {
-            Mesh::from_triangle_faces_with_vertices_and_computed_normals(
-                faces_raw,
+            Mesh::from_faces_with_vertices_and_computed_normals(
+                faces,
                 vertex_positions,
                 NormalStrategy::Sharp,
             )
@@ -244,6 +414,160 @@ pub fn tobj_to_internal(tobj_models: Vec<tobj::Model>) -> Vec<Model> {
     models
 }
 
+/// An environment image decoded to RGBA8 for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Reads a Radiance HDR (.hdr) environment image from given `path`
+/// and tone maps it to RGBA8.
+pub fn import_hdr_environment(path: &str) -> Result<EnvironmentImage, ImporterError> {
+    let file_contents = PlatformFileSystem.read(Path::new(path))?;
+    decode_hdr(&file_contents)
+}
+
+/// Decodes a Radiance HDR image with either flat or run-length
+/// encoded RGBE scanlines. Only the standard `-Y height +X width`
+/// orientation is supported.
+fn decode_hdr(file_contents: &[u8]) -> Result<EnvironmentImage, ImporterError> {
+    let mut lines = file_contents.split(|byte| *byte == b'\n');
+    let mut header_len = 0;
+    let mut next_line = || -> Result<&[u8], ImporterError> {
+        let line = lines.next().ok_or(ImporterError::InvalidStructure)?;
+        header_len += line.len() + 1;
+        Ok(line)
+    };
+
+    let signature = next_line()?;
+    if !signature.starts_with(b"#?RADIANCE") && !signature.starts_with(b"#?RGBE") {
+        return Err(ImporterError::InvalidStructure);
+    }
+
+    loop {
+        let line = next_line()?;
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with(b"FORMAT=") && line != b"FORMAT=32-bit_rle_rgbe" {
+            return Err(ImporterError::InvalidStructure);
+        }
+    }
+
+    let resolution = String::from_utf8_lossy(next_line()?).into_owned();
+    let (width, height) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", height, "+X", width] => (
+            width
+                .parse::<usize>()
+                .map_err(|_| ImporterError::InvalidStructure)?,
+            height
+                .parse::<usize>()
+                .map_err(|_| ImporterError::InvalidStructure)?,
+        ),
+        _ => return Err(ImporterError::InvalidStructure),
+    };
+    if width == 0 || height == 0 {
+        return Err(ImporterError::InvalidStructure);
+    }
+
+    let mut data = &file_contents[header_len.min(file_contents.len())..];
+    let mut rgbe_scanline = vec![[0u8; 4]; width];
+    let mut rgba = Vec::with_capacity(width * height * 4);
+
+    for _ in 0..height {
+        decode_hdr_scanline(&mut data, &mut rgbe_scanline)?;
+        for rgbe in &rgbe_scanline {
+            rgba.extend_from_slice(&tone_map_rgbe(*rgbe));
+        }
+    }
+
+    Ok(EnvironmentImage {
+        width: u32::try_from(width).map_err(|_| ImporterError::InvalidStructure)?,
+        height: u32::try_from(height).map_err(|_| ImporterError::InvalidStructure)?,
+        data: rgba,
+    })
+}
+
+fn decode_hdr_scanline(data: &mut &[u8], scanline: &mut [[u8; 4]]) -> Result<(), ImporterError> {
+    let width = scanline.len();
+    let is_run_length_encoded = (8..0x8000).contains(&width)
+        && data.len() >= 4
+        && data[0] == 2
+        && data[1] == 2
+        && data[2] & 0x80 == 0;
+
+    if !is_run_length_encoded {
+        if data.len() < width * 4 {
+            return Err(ImporterError::InvalidStructure);
+        }
+        for (pixel, rgbe) in scanline.iter_mut().zip(data.chunks_exact(4)) {
+            pixel.copy_from_slice(rgbe);
+        }
+        *data = &data[width * 4..];
+
+        return Ok(());
+    }
+
+    if usize::from(data[2]) << 8 | usize::from(data[3]) != width {
+        return Err(ImporterError::InvalidStructure);
+    }
+    *data = &data[4..];
+
+    // Each channel is encoded separately as a sequence of runs of the
+    // same value and of literal values
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let (&count, rest) = data.split_first().ok_or(ImporterError::InvalidStructure)?;
+            if count > 128 {
+                let count = usize::from(count - 128);
+                let &value = rest.first().ok_or(ImporterError::InvalidStructure)?;
+                if x + count > width {
+                    return Err(ImporterError::InvalidStructure);
+                }
+                for pixel in &mut scanline[x..x + count] {
+                    pixel[channel] = value;
+                }
+                x += count;
+                *data = &rest[1..];
+            } else {
+                let count = usize::from(count);
+                if count == 0 || x + count > width || rest.len() < count {
+                    return Err(ImporterError::InvalidStructure);
+                }
+                for (pixel, value) in scanline[x..x + count].iter_mut().zip(rest) {
+                    pixel[channel] = *value;
+                }
+                x += count;
+                *data = &rest[count..];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts the shared-exponent RGBE pixel to RGBA8 with the Reinhard
+/// operator and gamma correction.
+fn tone_map_rgbe(rgbe: [u8; 4]) -> [u8; 4] {
+    const GAMMA: f32 = 2.2;
+
+    if rgbe[3] == 0 {
+        return [0, 0, 0, 255];
+    }
+
+    let scale = 2f32.powi(i32::from(rgbe[3]) - 136);
+    let tone_map = |mantissa: u8| {
+        let radiance = f32::from(mantissa) * scale;
+        let mapped = (radiance / (1.0 + radiance)).powf(1.0 / GAMMA);
+        (mapped * 255.0).round() as u8
+    };
+
+    [tone_map(rgbe[0]), tone_map(rgbe[1]), tone_map(rgbe[2]), 255]
+}
+
 pub fn calculate_checksum(string: &[u8]) -> u32 {
     let mut hasher = crc32fast::Hasher::new();
 
@@ -253,8 +577,12 @@ pub fn calculate_checksum(string: &[u8]) -> u32 {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+    use std::io::Read;
     use std::time::Duration;
 
+    use crate::platform::{FileContents, MockFileSystem};
+
     use super::*;
 
     fn create_tobj_model(
@@ -296,18 +624,14 @@ mod tests {
             models,
             vec![Model {
                 name: tobj_model.name,
-                mesh: Mesh::from_triangle_faces_with_vertices_and_normals(
-                    vec![TriangleFace::from_same_vertex_and_normal_index(0, 1, 2)],
+                mesh: Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+                    vec![(0, 1, 2)],
                     vec![
                         Point3::new(6.0, 5.0, 4.0),
                         Point3::new(3.0, 2.0, 1.0),
                         Point3::new(0.0, 1.0, 2.0),
                     ],
-                    vec![
-                        Vector3::new(1.0, 0.0, 0.0),
-                        Vector3::new(1.0, 0.0, 0.0),
-                        Vector3::new(1.0, 0.0, 0.0),
-                    ],
+                    VertexNormals::from_vertex_normals(vec![Vector3::new(1.0, 0.0, 0.0); 3]),
                 ),
             }]
         );
@@ -333,40 +657,56 @@ mod tests {
             vec![
                 Model {
                     name: tobj_model_1.name,
-                    mesh: Mesh::from_triangle_faces_with_vertices_and_normals(
-                        vec![TriangleFace::from_same_vertex_and_normal_index(0, 1, 2)],
+                    mesh: Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+                        vec![(0, 1, 2)],
                         vec![
                             Point3::new(6.0, 5.0, 4.0),
                             Point3::new(3.0, 2.0, 1.0),
                             Point3::new(0.0, 1.0, 2.0),
                         ],
-                        vec![
-                            Vector3::new(1.0, 0.0, 0.0),
-                            Vector3::new(1.0, 0.0, 0.0),
-                            Vector3::new(1.0, 0.0, 0.0),
-                        ],
+                        VertexNormals::from_vertex_normals(vec![Vector3::new(1.0, 0.0, 0.0); 3]),
                     ),
                 },
                 Model {
                     name: tobj_model_2.name,
-                    mesh: Mesh::from_triangle_faces_with_vertices_and_normals(
-                        vec![TriangleFace::from_same_vertex_and_normal_index(0, 1, 2)],
+                    mesh: Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+                        vec![(0, 1, 2)],
                         vec![
                             Point3::new(16.0, 15.0, 14.0),
                             Point3::new(13.0, 12.0, 11.0),
                             Point3::new(10.0, 9.0, 8.0),
                         ],
-                        vec![
-                            Vector3::new(1.0, 0.0, 0.0),
-                            Vector3::new(1.0, 0.0, 0.0),
-                            Vector3::new(1.0, 0.0, 0.0),
-                        ],
+                        VertexNormals::from_vertex_normals(vec![Vector3::new(1.0, 0.0, 0.0); 3]),
                     ),
                 },
             ]
         );
     }
 
+    #[test]
+    fn test_tobj_to_internal_with_face_sizes_keeps_quads() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\n\
+            f 1 2 3 4\nf 2 5 3\n";
+        let (tobj_models, _) = obj_buf_into_tobj(&mut &obj[..]).expect("Failed to parse obj");
+        let face_sizes = decode_obj_face_sizes(obj);
+        assert_eq!(face_sizes, vec![4, 3]);
+
+        let models = tobj_to_internal_with_face_sizes(tobj_models.clone(), &face_sizes);
+        assert_eq!(models.len(), 1);
+        assert_eq!(
+            models[0].mesh.faces(),
+            &[
+                Face::from(QuadFace::new(0, 1, 2, 3)),
+                Face::from(TriangleFace::new(1, 4, 2)),
+            ][..]
+        );
+
+        // Sizes not matching the triangles are ignored
+        let models = tobj_to_internal_with_face_sizes(tobj_models, &[4]);
+        assert_eq!(models[0].mesh.faces().len(), 3);
+        assert!(models[0].mesh.is_triangulated());
+    }
+
     #[test]
     fn test_obj_cache_set_caches_new_path_with_metadata() {
         let mut cache = EndlessCache::default();
@@ -546,9 +886,6 @@ mod tests {
     // and they use fixture data. They're kept here to prevent complications
     // with automocks not being present in debug build, as it is built without
     // code marked as `cfg(test)`.
-    //
-    // FIXME: Ideal scenario would be if filesystem was mocked and passed into
-    // this method as well. It'd become proper unit test that way.
 
     fn file_metadata(path: &str) -> FileMetadata {
         let mut file = fs::File::open(path).expect("Failed to open file");
@@ -609,18 +946,14 @@ mod tests {
         lazy_static! {
             static ref MODELS: Vec<Model> = vec![Model {
                 name: "test".to_string(),
-                mesh: Mesh::from_triangle_faces_with_vertices_and_normals(
-                    vec![TriangleFace::from_same_vertex_and_normal_index(0, 1, 2)],
+                mesh: Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+                    vec![(0, 1, 2)],
                     vec![
                         Point3::new(6.0, 5.0, 4.0),
                         Point3::new(3.0, 2.0, 1.0),
                         Point3::new(0.0, 1.0, 2.0),
                     ],
-                    vec![
-                        Vector3::new(1.0, 0.0, 0.0),
-                        Vector3::new(1.0, 0.0, 0.0),
-                        Vector3::new(1.0, 0.0, 0.0),
-                    ],
+                    VertexNormals::from_vertex_normals(vec![Vector3::new(1.0, 0.0, 0.0); 3]),
                 ),
             }];
         }
@@ -651,4 +984,128 @@ mod tests {
             .import_obj(&path)
             .expect("Valid obj should be loaded");
     }
+
+    #[test]
+    fn test_importer_import_obj_reads_unmodified_file_from_file_system_once() {
+        let modified = SystemTime::now();
+        let mut file_system = MockFileSystem::new();
+        file_system
+            .expect_modified()
+            .returning(move |_| Ok(modified))
+            .times(2);
+        file_system
+            .expect_read_mapped()
+            .returning(|_| {
+                Ok(FileContents::Buffered(
+                    b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".to_vec(),
+                ))
+            })
+            .times(1);
+        file_system.expect_write().times(0);
+
+        let mut importer = Importer::with_file_system(EndlessCache::default(), file_system);
+        let models = importer
+            .import_obj("virtual.obj")
+            .expect("Valid obj should be loaded");
+        let cached_models = importer
+            .import_obj("virtual.obj")
+            .expect("Valid obj should be loaded");
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].mesh.faces().len(), 1);
+        assert_eq!(models, cached_models);
+    }
+
+    #[test]
+    fn test_platform_file_system_read_mapped_matches_read() {
+        let path = Path::new("tests/fixtures/valid.obj");
+
+        let mapped = PlatformFileSystem
+            .read_mapped(path)
+            .expect("Failed to map file");
+        let read = fs::read(path).expect("Failed to read file");
+
+        assert_eq!(&mapped[..], read.as_slice());
+    }
+
+    #[test]
+    fn test_decode_obj_polylines_reads_open_and_closed_lines() {
+        let file_contents = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\nl 1 2 3 1\nl -1 -2\n";
+
+        let polylines = decode_obj_polylines(file_contents).expect("Valid obj should be decoded");
+
+        assert_eq!(
+            polylines,
+            vec![
+                Polyline::new(
+                    vec![
+                        Point3::new(0.0, 0.0, 0.0),
+                        Point3::new(1.0, 0.0, 0.0),
+                        Point3::new(1.0, 1.0, 0.0),
+                    ],
+                    true,
+                ),
+                Polyline::new(
+                    vec![Point3::new(1.0, 1.0, 0.0), Point3::new(1.0, 0.0, 0.0)],
+                    false,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_decode_obj_polylines_rejects_out_of_range_index() {
+        let file_contents = b"v 0 0 0\nv 1 0 0\nl 1 3\n";
+
+        assert_eq!(
+            decode_obj_polylines(file_contents),
+            Err(ImporterError::InvalidStructure)
+        );
+    }
+
+    #[test]
+    fn test_decode_hdr_flat_scanlines() {
+        let mut file_contents = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+        // Radiance 1.0 in red, zero elsewhere
+        file_contents.extend_from_slice(&[128, 0, 0, 129, 0, 0, 0, 0]);
+
+        let image = decode_hdr(&file_contents).expect("Valid hdr should be decoded");
+
+        assert_eq!(image.width, 2);
+        assert_eq!(image.height, 1);
+        assert_eq!(image.data, vec![186, 0, 0, 255, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_decode_hdr_run_length_encoded_scanlines() {
+        let mut file_contents = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+        file_contents.extend_from_slice(&[2, 2, 0, 8]);
+        // Red: a run of 8, green: 8 literal values, blue: a run of 8,
+        // exponent: runs of 4 and 4
+        file_contents.extend_from_slice(&[136, 128]);
+        file_contents.extend_from_slice(&[8, 0, 0, 0, 0, 128, 128, 128, 128]);
+        file_contents.extend_from_slice(&[136, 0]);
+        file_contents.extend_from_slice(&[132, 129, 132, 0]);
+
+        let image = decode_hdr(&file_contents).expect("Valid hdr should be decoded");
+
+        assert_eq!(image.width, 8);
+        assert_eq!(image.height, 1);
+        for pixel in image.data.chunks(4).take(4) {
+            assert_eq!(pixel, &[186, 0, 0, 255]);
+        }
+        for pixel in image.data.chunks(4).skip(4) {
+            assert_eq!(pixel, &[0, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn test_decode_hdr_rejects_truncated_file() {
+        let file_contents = b"#?RADIANCE\n\n-Y 2 +X 2\n\x80\x00\x00\x81".to_vec();
+
+        assert_eq!(
+            decode_hdr(&file_contents),
+            Err(ImporterError::InvalidStructure)
+        );
+    }
 }
@@ -1,5 +1,20 @@
 use std::cmp::Ordering;
 
+use crate::camera::CameraView;
+
+/// The largest distance in logical pixels the cursor can move between
+/// pressing and releasing the button for it to still be a click, and
+/// not a camera rotation.
+const CLICK_DISTANCE_MAX: f64 = 4.0;
+
+/// A click into the viewport, not captured by the UI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportClick {
+    /// Logical position of the cursor within the window.
+    pub position: [f32; 2],
+    pub shift: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct InputState {
     pub tmp_submit_prog_and_run: bool,
@@ -9,13 +24,22 @@ pub struct InputState {
     pub camera_zoom: f32,
     pub camera_zoom_steps: i32,
     pub camera_reset_viewport: bool,
+    pub camera_view: Option<CameraView>,
+    pub camera_projection_toggled: bool,
     pub close_requested: bool,
+    pub presentation_mode_toggled: bool,
     pub window_resized: Option<winit::dpi::LogicalSize>,
+    pub viewport_click: Option<ViewportClick>,
+    /// Logical position of the cursor, if the left mouse button was
+    /// pressed in the viewport, not over the UI.
+    pub viewport_press: Option<[f32; 2]>,
+    pub lmb_released: bool,
 }
 
 #[derive(Debug, Default)]
 pub struct InputManager {
     lmb_down: bool,
+    lmb_down_position: Option<(f64, f64)>,
     rmb_down: bool,
     shift_down: bool,
     input_state: InputState,
@@ -27,6 +51,7 @@ impl InputManager {
     pub fn new() -> Self {
         Self {
             lmb_down: false,
+            lmb_down_position: None,
             rmb_down: false,
             shift_down: false,
             input_state: InputState::default(),
@@ -39,6 +64,12 @@ impl InputManager {
         &self.input_state
     }
 
+    /// Logical position of the cursor within the window, as of the last
+    /// time it moved over the viewport.
+    pub fn cursor_position(&self) -> [f32; 2] {
+        [self.window_mouse_x as f32, self.window_mouse_y as f32]
+    }
+
     pub fn start_frame(&mut self) {
         self.input_state = InputState::default();
     }
@@ -86,6 +117,13 @@ impl InputManager {
                         ) => {
                             self.input_state.close_requested = true;
                         }
+                        (
+                            Some(winit::event::VirtualKeyCode::F11),
+                            winit::event::ElementState::Pressed,
+                            &MODIFIERS_NONE,
+                        ) => {
+                            self.input_state.presentation_mode_toggled = true;
+                        }
                         (
                             Some(winit::event::VirtualKeyCode::LShift),
                             winit::event::ElementState::Pressed,
@@ -134,6 +172,34 @@ impl InputManager {
                             ) => {
                                 self.input_state.tmp_submit_prog_and_run = true;
                             }
+                            (
+                                Some(winit::event::VirtualKeyCode::Numpad1),
+                                winit::event::ElementState::Pressed,
+                                &MODIFIERS_NONE,
+                            ) => {
+                                self.input_state.camera_view = Some(CameraView::Front);
+                            }
+                            (
+                                Some(winit::event::VirtualKeyCode::Numpad3),
+                                winit::event::ElementState::Pressed,
+                                &MODIFIERS_NONE,
+                            ) => {
+                                self.input_state.camera_view = Some(CameraView::Right);
+                            }
+                            (
+                                Some(winit::event::VirtualKeyCode::Numpad7),
+                                winit::event::ElementState::Pressed,
+                                &MODIFIERS_NONE,
+                            ) => {
+                                self.input_state.camera_view = Some(CameraView::Top);
+                            }
+                            (
+                                Some(winit::event::VirtualKeyCode::Numpad5),
+                                winit::event::ElementState::Pressed,
+                                &MODIFIERS_NONE,
+                            ) => {
+                                self.input_state.camera_projection_toggled = true;
+                            }
                             _ => (),
                         }
                     }
@@ -143,9 +209,30 @@ impl InputManager {
                     match (state, button) {
                         (winit::event::ElementState::Pressed, winit::event::MouseButton::Left) => {
                             self.lmb_down = true;
+                            if !ui_captured_mouse {
+                                self.lmb_down_position =
+                                    Some((self.window_mouse_x, self.window_mouse_y));
+                                self.input_state.viewport_press = Some(self.cursor_position());
+                            }
                         }
                         (winit::event::ElementState::Released, winit::event::MouseButton::Left) => {
                             self.lmb_down = false;
+                            self.input_state.lmb_released = true;
+                            if let Some((x, y)) = self.lmb_down_position.take() {
+                                let dx = self.window_mouse_x - x;
+                                let dy = self.window_mouse_y - y;
+                                if !ui_captured_mouse
+                                    && dx * dx + dy * dy <= CLICK_DISTANCE_MAX * CLICK_DISTANCE_MAX
+                                {
+                                    self.input_state.viewport_click = Some(ViewportClick {
+                                        position: [
+                                            self.window_mouse_x as f32,
+                                            self.window_mouse_y as f32,
+                                        ],
+                                        shift: self.shift_down,
+                                    });
+                                }
+                            }
                         }
                         (winit::event::ElementState::Pressed, winit::event::MouseButton::Right) => {
                             self.rmb_down = true;
@@ -1,11 +1,16 @@
 use std::fmt;
 use std::sync::Arc;
 
+use crate::curve::Curve;
+use crate::mesh::Mesh;
+
 /// A unique function identifier.
 ///
 /// Has to stay stable for the lifetime of the interpreter and program
 /// using it.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
 pub struct FuncIdent(pub(crate) u64);
 
 impl fmt::Display for FuncIdent {
@@ -18,7 +23,7 @@ impl fmt::Display for FuncIdent {
 ///
 /// Has to stay stable for the lifetime of the interpreter and program
 /// using it.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct VarIdent(pub(crate) u64);
 
 impl fmt::Display for VarIdent {
@@ -28,24 +33,28 @@ impl fmt::Display for VarIdent {
 }
 
 /// A program consisting of a list of statements.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
 pub struct Prog {
     stmts: Vec<Stmt>,
 }
 
 impl Prog {
+    #[cfg(any(test, feature = "gui"))]
     pub fn new(stmts: Vec<Stmt>) -> Self {
         Self { stmts }
     }
 
+    #[cfg(feature = "gui")]
     pub fn push_stmt(&mut self, stmt: Stmt) {
         self.stmts.push(stmt);
     }
 
+    #[cfg(feature = "gui")]
     pub fn pop_stmt(&mut self) {
         self.stmts.pop();
     }
 
+    #[cfg(any(test, feature = "gui"))]
     pub fn set_stmt_at(&mut self, index: usize, stmt: Stmt) {
         self.stmts[index] = stmt;
     }
@@ -69,7 +78,7 @@ impl fmt::Display for Prog {
 ///
 /// Statements describe things to do, like execute code or declare a
 /// variable.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Stmt {
     VarDecl(VarDeclStmt),
 }
@@ -86,7 +95,7 @@ impl fmt::Display for Stmt {
 ///
 /// Declares a variable with a known identifier, and uses provided
 /// initializer expression to produce a value for that variable.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct VarDeclStmt {
     // Note that values for variables can only come from calls, so we
     // use `CallExpr` directly.
@@ -95,10 +104,12 @@ pub struct VarDeclStmt {
 }
 
 impl VarDeclStmt {
+    #[cfg(any(test, feature = "gui"))]
     pub fn new(ident: VarIdent, init_expr: CallExpr) -> Self {
         Self { ident, init_expr }
     }
 
+    #[cfg(feature = "gui")]
     pub fn clone_with_init_expr(&self, init_expr: CallExpr) -> Self {
         Self {
             ident: self.ident,
@@ -124,7 +135,7 @@ impl fmt::Display for VarDeclStmt {
 /// A program expression.
 ///
 /// Expressions evaluate to values.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Expr {
     // Note that `CallExpr` is missing here. That is because it is
     // impossible for the frontend to produce a program where a func
@@ -136,6 +147,7 @@ pub enum Expr {
 }
 
 impl Expr {
+    #[cfg(any(test, feature = "gui"))]
     pub fn unwrap_literal(&self) -> &LitExpr {
         match self {
             Expr::Lit(lit) => lit,
@@ -154,7 +166,7 @@ impl fmt::Display for Expr {
 }
 
 /// An expression that evaluates to a constant, literal value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum LitExpr {
     Nil,
     #[allow(dead_code)]
@@ -166,8 +178,29 @@ pub enum LitExpr {
     Float2([f32; 2]),
     Float3([f32; 3]),
     String(Arc<String>),
+    /// Mesh data embedded directly in the program, so that it does
+    /// not depend on external files. Stored quantized and compressed.
+    Mesh(#[serde(with = "crate::mesh_codec::embedded")] Arc<Mesh>),
+    /// Curve data embedded directly in the program, like `Mesh`.
+    Curve(Arc<Curve>),
 }
 
+#[cfg(any(test, feature = "gui"))]
+impl LitExpr {
+    /// Get the literal value if uint, otherwise panic.
+    ///
+    /// # Panics
+    /// This function panics when literal value is not an uint.
+    pub fn unwrap_uint(&self) -> u32 {
+        match self {
+            LitExpr::Uint(uint) => *uint,
+            _ => panic!("Literal expression not uint"),
+        }
+    }
+}
+
+// Only the editor shows literals in param inputs
+#[cfg(feature = "gui")]
 impl LitExpr {
     /// Get the literal value if boolean, otherwise panic.
     ///
@@ -191,17 +224,6 @@ impl LitExpr {
         }
     }
 
-    /// Get the literal value if uint, otherwise panic.
-    ///
-    /// # Panics
-    /// This function panics when literal value is not an uint.
-    pub fn unwrap_uint(&self) -> u32 {
-        match self {
-            LitExpr::Uint(uint) => *uint,
-            _ => panic!("Literal expression not uint"),
-        }
-    }
-
     /// Get the literal value if float, otherwise panic.
     ///
     /// # Panics
@@ -260,13 +282,25 @@ impl fmt::Display for LitExpr {
                 write!(f, "<float3 [{}, {}, {}]>", float3[0], float3[1], float3[2])
             }
             LitExpr::String(string) => write!(f, "<string {}>", string),
+            LitExpr::Mesh(mesh) => write!(
+                f,
+                "<mesh {} vertices, {} faces>",
+                mesh.vertices().len(),
+                mesh.faces().len(),
+            ),
+            LitExpr::Curve(curve) => write!(
+                f,
+                "<curve {} polylines, {} vertices>",
+                curve.polylines().len(),
+                curve.vertex_count(),
+            ),
         }
     }
 }
 
 /// An expression that evaluates to a value by extracting the value
 /// from a variable.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct VarExpr {
     ident: VarIdent,
 }
@@ -289,17 +323,19 @@ impl fmt::Display for VarExpr {
 }
 
 /// An expression that evaluates to a value by calling a function.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct CallExpr {
     ident: FuncIdent,
     args: Vec<Expr>,
 }
 
 impl CallExpr {
+    #[cfg(any(test, feature = "gui"))]
     pub fn new(ident: FuncIdent, args: Vec<Expr>) -> Self {
         Self { ident, args }
     }
 
+    #[cfg(feature = "gui")]
     pub fn clone_with_arg_at(&self, index: usize, arg: Expr) -> Self {
         let mut new_args = self.args.clone();
         new_args[index] = arg;
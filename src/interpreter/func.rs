@@ -1,6 +1,14 @@
+use std::error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
 use bitflags::bitflags;
 
-use super::{FuncError, LogMessage, Ty, Value};
+#[cfg(feature = "gui")]
+use crate::convert::cast_u32;
+
+use super::{ast, FuncError, LogMessage, Ty, Value};
 
 /// Textual information about the function.
 pub struct FuncInfo {
@@ -8,6 +16,61 @@ pub struct FuncInfo {
     pub name: &'static str,
     /// The name of the function's return value.
     pub return_value_name: &'static str,
+    /// The category the function is listed under in the operation
+    /// library.
+    pub category: FuncCategory,
+    /// Additional search terms for the function in the operation
+    /// library.
+    pub tags: &'static [&'static str],
+}
+
+// Only the editor searches the functions
+#[cfg(feature = "gui")]
+impl FuncInfo {
+    /// Returns whether the function's name or tags contain the
+    /// `query`, ignoring case. An empty query matches every function.
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&query))
+    }
+}
+
+/// A group of related functions in the operation library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FuncCategory {
+    Generate,
+    Transform,
+    Analyze,
+    Voxel,
+    Flow,
+    Export,
+}
+
+impl FuncCategory {
+    /// All the categories in the order they are listed in.
+    pub const ALL: [FuncCategory; 6] = [
+        FuncCategory::Generate,
+        FuncCategory::Transform,
+        FuncCategory::Analyze,
+        FuncCategory::Voxel,
+        FuncCategory::Flow,
+        FuncCategory::Export,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FuncCategory::Generate => "Generate",
+            FuncCategory::Transform => "Transform",
+            FuncCategory::Analyze => "Analyze",
+            FuncCategory::Voxel => "Voxel",
+            FuncCategory::Flow => "Flow",
+            FuncCategory::Export => "Export",
+        }
+    }
 }
 
 bitflags! {
@@ -26,6 +89,11 @@ bitflags! {
         /// A pure func's log messages are not returned twice, if the
         /// func's result has been cached and not invalidated.
         const PURE = 0b_0000_0001;
+
+        /// The function handles meshes with quad faces. Mesh arguments
+        /// of other functions have their quads split into triangles
+        /// before the function is called.
+        const ACCEPTS_QUADS = 0b_0000_0010;
     }
 }
 
@@ -50,17 +118,26 @@ pub struct ParamInfo {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ParamRefinement {
-    #[allow(dead_code)]
     Boolean(BooleanParamRefinement),
     #[allow(dead_code)]
     Int(IntParamRefinement),
     Uint(UintParamRefinement),
+    Enum(EnumParamRefinement),
     Float(FloatParamRefinement),
     Float2(Float2ParamRefinement),
     Float3(Float3ParamRefinement),
     String(StringParamRefinement),
     Mesh,
     MeshArray,
+    /// A locator referenced by a variable, like meshes.
+    Locator,
+    /// A curve referenced by a variable, like meshes.
+    Curve,
+    /// A mesh embedded in the program as a literal, as opposed to a
+    /// mesh referenced by a variable.
+    EmbeddedMesh,
+    /// A curve embedded in the program as a literal.
+    EmbeddedCurve,
 }
 
 impl ParamRefinement {
@@ -69,12 +146,17 @@ impl ParamRefinement {
             Self::Boolean(_) => Ty::Boolean,
             Self::Int(_) => Ty::Int,
             Self::Uint(_) => Ty::Uint,
+            Self::Enum(_) => Ty::Uint,
             Self::Float(_) => Ty::Float,
             Self::Float2(_) => Ty::Float2,
             Self::Float3(_) => Ty::Float3,
             Self::String(_) => Ty::String,
             Self::Mesh => Ty::Mesh,
             Self::MeshArray => Ty::MeshArray,
+            Self::Locator => Ty::Locator,
+            Self::Curve => Ty::Curve,
+            Self::EmbeddedMesh => Ty::Mesh,
+            Self::EmbeddedCurve => Ty::Curve,
         }
     }
 }
@@ -132,11 +214,35 @@ impl UintParamRefinement {
     }
 }
 
+/// Refinement of an unsigned integer parameter selecting one of
+/// several named options. The value is the index of the selected
+/// option in `options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumParamRefinement {
+    pub default_value: u32,
+    pub options: &'static [&'static str],
+}
+
+#[cfg(feature = "gui")]
+impl EnumParamRefinement {
+    pub fn clamp(&self, value: u32) -> u32 {
+        let max = cast_u32(self.options.len()).saturating_sub(1);
+        if value > max {
+            max
+        } else {
+            value
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct FloatParamRefinement {
     pub default_value: Option<f32>,
     pub min_value: Option<f32>,
     pub max_value: Option<f32>,
+    /// Whether the value is a length in meters, which is displayed and
+    /// entered in the project's length unit.
+    pub length: bool,
 }
 
 impl FloatParamRefinement {
@@ -164,6 +270,9 @@ pub struct Float2ParamRefinement {
     pub default_value_y: Option<f32>,
     pub min_value_y: Option<f32>,
     pub max_value_y: Option<f32>,
+    /// Whether the components are lengths in meters. See
+    /// `FloatParamRefinement::length`.
+    pub length: bool,
 }
 
 impl Float2ParamRefinement {
@@ -203,6 +312,9 @@ pub struct Float3ParamRefinement {
     pub default_value_z: Option<f32>,
     pub min_value_z: Option<f32>,
     pub max_value_z: Option<f32>,
+    /// Whether the components are lengths in meters. See
+    /// `FloatParamRefinement::length`.
+    pub length: bool,
 }
 
 impl Float3ParamRefinement {
@@ -245,9 +357,90 @@ impl Float3ParamRefinement {
 pub struct StringParamRefinement {
     pub default_value: &'static str,
     pub file_path: bool,
+    /// Whether the file path is a destination to save to, rather than
+    /// an existing file to open. Only relevant for file paths.
+    pub file_save: bool,
     pub file_ext_filter: Option<(&'static [&'static str], &'static str)>,
 }
 
+/// A section of the program to be evaluated repeatedly before the
+/// function repeating it is called.
+///
+/// The section starts with the variable passed as the
+/// `start_arg`-th argument and ends with the variable passed as the
+/// `end_arg`-th argument. It consists of all statements in between
+/// that depend on the start variable. After each iteration, the
+/// value of the end variable is fed back as the value of the start
+/// variable. The function is called with the values of the last
+/// iteration.
+///
+/// Statements calling a function with a [`repeat_index_arg`] on the
+/// start variable hold the index of the current iteration, so the
+/// section can vary between iterations.
+///
+/// [`repeat_index_arg`]: trait.Func.html#method.repeat_index_arg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub start_arg: usize,
+    pub end_arg: usize,
+    pub iterations: u32,
+}
+
+/// A flag for cancelling a running interpretation from another
+/// thread. Clones share the same flag.
+///
+/// Funcs running for a long time should `check` the token
+/// periodically, e.g. once per iteration, and stop early once it is
+/// cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Returns an error to be propagated from the func, if the token
+    /// is cancelled.
+    pub fn check(&self) -> Result<(), FuncError> {
+        if self.is_cancelled() {
+            Err(FuncError::new(CancelledError))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Only the editor cancels interpretations
+#[cfg(any(test, feature = "gui"))]
+impl CancellationToken {
+    /// Asks the funcs sharing the token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Clears the cancellation, so that the token can be used for the
+    /// next interpretation.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug)]
+struct CancelledError;
+
+impl fmt::Display for CancelledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl error::Error for CancelledError {}
+
 /// An interface describing a function as seen by the interpreter.
 ///
 /// Functions are pieces of callable code. They can receive parameters
@@ -261,6 +454,8 @@ pub trait Func {
         &FuncInfo {
             name: "<Unnamed operation>",
             return_value_name: "<Unnamed value>",
+            category: FuncCategory::Transform,
+            tags: &[],
         }
     }
 
@@ -286,14 +481,65 @@ pub trait Func {
     /// [`Ty`]: ../value/enum.Ty.html
     fn return_ty(&self) -> Ty;
 
+    /// Whether the function needs the value of the argument at
+    /// `arg_index` to produce its result, given the argument
+    /// expressions of the call.
+    ///
+    /// Arguments that are not demanded are not evaluated and are
+    /// passed to [`call`] as [`Nil`]. Statements that only produce
+    /// values for arguments that are not demanded are not evaluated
+    /// at all. This makes lazy functions, such as switches, possible.
+    /// All arguments are demanded by default.
+    ///
+    /// [`call`]: trait.Func.html#tymethod.call
+    /// [`Nil`]: ../value/enum.Ty.html#variant.Nil
+    fn arg_demanded(&self, _arg_index: usize, _args: &[ast::Expr]) -> bool {
+        true
+    }
+
+    /// The section of the program the function repeats, given the
+    /// argument expressions of the call. See [`Repeat`] for more.
+    /// Functions repeat nothing by default.
+    ///
+    /// [`Repeat`]: struct.Repeat.html
+    fn repeat(&self, _args: &[ast::Expr]) -> Option<Repeat> {
+        None
+    }
+
+    /// The argument taking the start of a repeated section, if the
+    /// function returns the index of the section's current iteration
+    /// as a [`Uint`]. Such functions are called only for the first
+    /// iteration and must return `0`. The interpreter assigns the
+    /// index of each subsequent iteration to their variables
+    /// directly. See [`Repeat`] for more.
+    ///
+    /// [`Uint`]: ../value/enum.Value.html#variant.Uint
+    /// [`Repeat`]: struct.Repeat.html
+    fn repeat_index_arg(&self) -> Option<usize> {
+        None
+    }
+
     /// Call the function with arguments and receive the return value.
     ///
     /// A correct implementation's types provided in [`param_info`]
     /// and [`return_ty`] will match the types of values expected in
-    /// and provided by this function.
+    /// and provided by this function. Additionally, the function may
+    /// return [`Nil`] to signal it produced no value. Statements
+    /// that require the value are then skipped.
+    ///
+    /// Long running functions should poll the `cancellation_token`
+    /// and return early with its error once it is cancelled. They can
+    /// also call `report_progress` with the fraction of the work done,
+    /// from 0 to 1, so that they don't appear hung.
     ///
     /// [`param_info`]: trait.Func.html#tymethod.param_info
     /// [`return_ty`]: trait.Func.html#tymethod.return_ty
-    fn call(&mut self, args: &[Value], log: &mut dyn FnMut(LogMessage))
-        -> Result<Value, FuncError>;
+    /// [`Nil`]: ../value/enum.Ty.html#variant.Nil
+    fn call(
+        &mut self,
+        args: &[Value],
+        cancellation_token: &CancellationToken,
+        report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError>;
 }
@@ -4,20 +4,25 @@ use std::collections::hash_map::{Entry, HashMap};
 use std::collections::{BTreeMap, HashSet};
 use std::error;
 use std::fmt;
+use std::io;
 use std::ptr;
 use std::sync::Arc;
 use std::time::Instant;
 
 pub use self::ast::{FuncIdent, VarIdent};
 pub use self::func::{
-    BooleanParamRefinement, Float2ParamRefinement, Float3ParamRefinement, FloatParamRefinement,
-    Func, FuncFlags, FuncInfo, IntParamRefinement, ParamInfo, ParamRefinement,
-    StringParamRefinement, UintParamRefinement,
+    BooleanParamRefinement, CancellationToken, EnumParamRefinement, Float2ParamRefinement,
+    Float3ParamRefinement, FloatParamRefinement, Func, FuncCategory, FuncFlags, FuncInfo,
+    ParamInfo, ParamRefinement, Repeat, StringParamRefinement, UintParamRefinement,
 };
+pub use self::spill::SpillStore;
 pub use self::value::{MeshArrayValue, Ty, Value};
 
+use self::spill::SpilledValue;
+
 pub mod ast;
 pub mod func;
+pub mod spill;
 pub mod value;
 
 /// A name resolution error.
@@ -119,6 +124,18 @@ pub enum RuntimeError {
         call: ast::CallExpr,
         func_error: FuncError,
     },
+    RepeatEndBeforeStart {
+        stmt_index: usize,
+        call: ast::CallExpr,
+    },
+    Cancelled {
+        stmt_index: usize,
+    },
+    SpillReload {
+        stmt_index: usize,
+        var: VarIdent,
+        message: String,
+    },
 }
 
 impl fmt::Display for RuntimeError {
@@ -176,6 +193,22 @@ impl fmt::Display for RuntimeError {
                 func_error,
                 stmt_index,
             ),
+            RuntimeError::RepeatEndBeforeStart { stmt_index, call } => write!(
+                f,
+                "Function {} repeats a section ending before it starts on stmt {}",
+                call.ident(),
+                stmt_index,
+            ),
+            RuntimeError::Cancelled { stmt_index } => write!(f, "Cancelled on stmt {}", stmt_index),
+            RuntimeError::SpillReload {
+                stmt_index,
+                var,
+                message,
+            } => write!(
+                f,
+                "Failed to reload spilled value of {} on stmt {}: {}",
+                var, stmt_index, message,
+            ),
         }
     }
 }
@@ -223,7 +256,6 @@ impl From<RuntimeError> for InterpretError {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogMessageLevel {
     Info,
-    #[allow(dead_code)]
     Warn,
     Error,
 }
@@ -242,7 +274,6 @@ impl LogMessage {
         }
     }
 
-    #[allow(dead_code)]
     pub fn warn<S: Into<Cow<'static, str>>>(message: S) -> Self {
         Self {
             level: LogMessageLevel::Warn,
@@ -284,7 +315,8 @@ pub struct InterpretValue {
     pub last_value: Option<Value>,
 
     /// The variable values that were used as parameters to funcs
-    /// within the executed part of the program.
+    /// within the executed part of the program. Values spilled to
+    /// disk by the interpreter are left out.
     pub used_values: Vec<(VarIdent, Value)>,
 
     /// The variable values that were not used as parameters to funcs
@@ -292,14 +324,55 @@ pub struct InterpretValue {
     pub unused_values: Vec<(VarIdent, Value)>,
 }
 
+/// The progress of an ongoing interpretation. Reported before each
+/// statement, whose value has to be computed, is evaluated and
+/// whenever its func reports progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterpretProgress {
+    /// The index of the statement being evaluated.
+    pub stmt_index: usize,
+
+    /// The number of statements being interpreted.
+    pub stmt_count: usize,
+
+    /// The fraction of the statement's func work done, from 0 to 1.
+    /// Stays 0 for funcs not reporting their progress.
+    pub func_progress: f32,
+}
+
 #[derive(Debug, Clone)]
 struct VarInfo {
     /// The parameters and function call this variable was created
     /// with. Used to verify validity of this variable.
     created_call: ast::CallExpr,
 
-    /// Current value of this variable.
-    value: Value,
+    /// Current value of this variable, possibly spilled to disk.
+    value: VarValue,
+
+    /// The interpretation in which the variable was last demanded.
+    /// Values demanded longest ago are spilled first.
+    last_demanded_interpretation: u64,
+}
+
+impl VarInfo {
+    /// Returns the value of the variable, reloading it from disk if
+    /// it was spilled. Reloaded values stay in memory until spilled
+    /// again.
+    fn load_value(&mut self) -> io::Result<Value> {
+        let value = match &self.value {
+            VarValue::Resident(value) => return Ok(value.clone()),
+            VarValue::Spilled(spilled_value) => spilled_value.load()?,
+        };
+
+        self.value = VarValue::Resident(value.clone());
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum VarValue {
+    Resident(Value),
+    Spilled(Arc<SpilledValue>),
 }
 
 /// Interpreter of a list of statements.
@@ -330,6 +403,31 @@ pub struct Interpreter {
     /// The last epoch for which name resolution succeeded. Initially
     /// 0, since empty program is by default resolved.
     last_resolve_epoch: u64,
+
+    /// Variables that are only re-computed when explicitly requested
+    /// via `invalidate_var`. Until then, their values are held even
+    /// if their definitions or dependencies change.
+    manual_update_vars: HashSet<VarIdent>,
+
+    /// Manually updated variables whose held values would have been
+    /// invalidated by the last interpretation.
+    stale_vars: HashSet<VarIdent>,
+
+    /// Called with the progress of the interpretation, e.g. to report
+    /// which func is currently running to another thread.
+    progress_callback: Option<Box<dyn FnMut(InterpretProgress)>>,
+
+    /// Once cancelled, the interpretation stops before the next func
+    /// call and running funcs are asked to stop early.
+    cancellation_token: CancellationToken,
+
+    /// Where values of variables are spilled to, once they exceed the
+    /// memory budget. Values are always kept in memory, if not set.
+    spill_store: Option<SpillStore>,
+
+    /// The number of interpretations run so far. Tells how recently
+    /// each variable was demanded.
+    interpretation_count: u64,
 }
 
 impl Interpreter {
@@ -341,61 +439,41 @@ impl Interpreter {
             log_messages: Vec::new(),
             epoch: 0,
             last_resolve_epoch: 0,
+            manual_update_vars: HashSet::new(),
+            stale_vars: HashSet::new(),
+            progress_callback: None,
+            cancellation_token: CancellationToken::new(),
+            spill_store: None,
+            interpretation_count: 0,
         }
     }
 
-    #[allow(dead_code)]
-    pub fn prog(&self) -> &ast::Prog {
-        &self.prog
+    /// Sets the callback called with the progress of each following
+    /// interpretation.
+    pub fn set_progress_callback<F>(&mut self, progress_callback: F)
+    where
+        F: FnMut(InterpretProgress) + 'static,
+    {
+        self.progress_callback = Some(Box::new(progress_callback));
     }
 
     pub fn set_prog(&mut self, prog: ast::Prog) {
         self.prog = prog;
 
         self.env.clear();
+        self.manual_update_vars.clear();
+        self.log_messages.clear();
         self.log_messages
             .resize_with(self.prog.stmts().len(), Vec::new);
 
         self.epoch += 1;
     }
 
-    pub fn clear_prog(&mut self) {
-        self.prog = ast::Prog::default();
-
-        self.env.clear();
-        self.log_messages.clear();
-
-        self.epoch += 1;
-    }
-
-    pub fn push_prog_stmt(&mut self, stmt: ast::Stmt) {
-        self.prog.push_stmt(stmt);
-        self.log_messages.push(Vec::new());
-        self.epoch += 1;
-    }
-
-    pub fn pop_prog_stmt(&mut self) {
-        assert!(
-            !self.prog.stmts().is_empty(),
-            "Program must not be empty when popping"
-        );
-
-        self.prog.pop_stmt();
-        self.log_messages.pop();
-        self.epoch += 1;
-    }
-
     #[allow(dead_code)]
     pub fn prog_stmt_at(&self, index: usize) -> Option<&ast::Stmt> {
         self.prog.stmts().get(index)
     }
 
-    pub fn set_prog_stmt_at(&mut self, index: usize, stmt: ast::Stmt) {
-        self.prog.set_stmt_at(index, stmt);
-        self.log_messages[index].clear();
-        self.epoch += 1;
-    }
-
     /// Runs name resolution on the currently set program.
     ///
     /// More concretely, this statically verifies that:
@@ -495,6 +573,7 @@ impl Interpreter {
 
         index = cmp::min(index, self.prog.stmts().len().saturating_sub(1));
 
+        self.interpretation_count += 1;
         self.invalidate();
         for log_messages in &mut self.log_messages {
             log_messages.clear();
@@ -502,14 +581,96 @@ impl Interpreter {
 
         log::debug!("Starting program evaluation with PC: 0");
 
+        let unused_vars = self.compute_unused_vars_up_until(index);
+        let demanded_vars = self.compute_demanded_vars_up_until(index, &unused_vars);
+
         for (stmt_index, stmt) in self.prog.stmts()[0..=index].iter().enumerate() {
-            if let Err(err) = eval_stmt(
+            let ast::Stmt::VarDecl(var_decl) = stmt;
+            if !demanded_vars.contains(&var_decl.ident()) {
+                log::debug!("Skipping evaluation of stmt {}: {}", stmt_index, stmt);
+                self.log_messages[stmt_index]
+                    .push(LogMessage::info(">>> Skipped, the value is not needed"));
+
+                continue;
+            }
+
+            if self.stale_vars.contains(&var_decl.ident()) {
+                self.log_messages[stmt_index].push(LogMessage::warn(
+                    ">>> Holding previous value, manual update pending",
+                ));
+            }
+
+            // Cached values are not computed, so there is no progress
+            // worth reporting for them
+            if !self.env.contains_key(&var_decl.ident()) {
+                if let Some(progress_callback) = &mut self.progress_callback {
+                    progress_callback(InterpretProgress {
+                        stmt_index,
+                        stmt_count: index + 1,
+                        func_progress: 0.0,
+                    });
+                }
+            }
+
+            let progress_callback = &mut self.progress_callback;
+            let report_progress: &mut dyn FnMut(usize, f32) = &mut |stmt_index, func_progress| {
+                if let Some(progress_callback) = progress_callback {
+                    progress_callback(InterpretProgress {
+                        stmt_index,
+                        stmt_count: index + 1,
+                        func_progress,
+                    });
+                }
+            };
+
+            // Funcs repeating a section of the program need the
+            // section evaluated repeatedly before they are called
+            // themselves. Values of the section's first iteration are
+            // restored afterwards, so that they stay cacheable.
+            let repeat = if self.env.contains_key(&var_decl.ident()) {
+                None
+            } else {
+                let init_expr = var_decl.init_expr();
+                self.funcs[&init_expr.ident()].repeat(init_expr.args())
+            };
+
+            let first_iteration = match repeat {
+                Some(repeat) => match eval_repeat(
+                    stmt_index,
+                    var_decl,
+                    repeat,
+                    self.prog.stmts(),
+                    &mut self.funcs,
+                    &mut self.env,
+                    &mut self.log_messages,
+                    &self.cancellation_token,
+                    report_progress,
+                ) {
+                    Ok(first_iteration) => first_iteration,
+                    Err(err) => {
+                        return InterpretOutcome {
+                            result: Err(InterpretError::from(err)),
+                            pc: stmt_index + 1,
+                            log_messages: self.log_messages.clone(),
+                        };
+                    }
+                },
+                None => Vec::new(),
+            };
+
+            let result = eval_stmt(
                 stmt_index,
                 stmt,
                 &mut self.funcs,
                 &mut self.env,
                 &mut self.log_messages,
-            ) {
+                &self.cancellation_token,
+                report_progress,
+            );
+
+            self.env.extend(first_iteration);
+
+            if let Err(err) = result {
                 return InterpretOutcome {
                     result: Err(InterpretError::from(err)),
                     pc: stmt_index + 1,
@@ -520,43 +681,73 @@ impl Interpreter {
 
         log::debug!("Ended program evaluation with PC: {}", index + 1);
 
-        let unused_vars = self.compute_unused_vars_up_until(index);
-        let last_value = match &self.prog.stmts()[index] {
-            ast::Stmt::VarDecl(var_decl) => {
-                let var_ident = var_decl.ident();
-                let var_info = self
-                    .env
-                    .get(&var_ident)
-                    .expect("Value must have been populated or already cached");
-
-                var_info.value.clone()
+        for var_ident in &demanded_vars {
+            if let Some(var_info) = self.env.get_mut(var_ident) {
+                var_info.last_demanded_interpretation = self.interpretation_count;
             }
-        };
+        }
 
+        self.spill_least_recently_demanded(index, &unused_vars);
+
+        let mut last_value = None;
         let mut used_values = Vec::with_capacity(index + 1);
         let mut unused_values = Vec::with_capacity(index + 1);
 
-        for stmt in &self.prog.stmts()[0..=index] {
+        for (stmt_index, stmt) in self.prog.stmts()[0..=index].iter().enumerate() {
             match stmt {
                 ast::Stmt::VarDecl(var_decl) => {
                     let var_ident = var_decl.ident();
-                    let var_info = self
-                        .env
-                        .get(&var_ident)
-                        .expect("Value must have been populated or already cached");
 
-                    if unused_vars.contains(&var_ident) {
-                        unused_values.push((var_ident, var_info.value.clone()));
-                    } else {
-                        used_values.push((var_ident, var_info.value.clone()));
+                    // Vars that were not demanded need not have a
+                    // value
+                    let var_info = match self.env.get_mut(&var_ident) {
+                        Some(var_info) => var_info,
+                        None => continue,
+                    };
+
+                    // The last value and unused values are going to be
+                    // displayed, so they are reloaded, if they were
+                    // spilled by a previous interpretation
+                    if stmt_index == index || unused_vars.contains(&var_ident) {
+                        let value = match var_info.load_value() {
+                            Ok(value) => value,
+                            Err(err) => {
+                                self.env.remove(&var_ident);
+                                return InterpretOutcome {
+                                    result: Err(InterpretError::from(RuntimeError::SpillReload {
+                                        stmt_index,
+                                        var: var_ident,
+                                        message: err.to_string(),
+                                    })),
+                                    pc: index + 1,
+                                    log_messages: self.log_messages.clone(),
+                                };
+                            }
+                        };
+
+                        if stmt_index == index {
+                            last_value = Some(value.clone());
+                        }
+                        if unused_vars.contains(&var_ident) {
+                            unused_values.push((var_ident, value));
+                        } else {
+                            used_values.push((var_ident, value));
+                        }
+                    } else if let VarValue::Resident(value) = &var_info.value {
+                        used_values.push((var_ident, value.clone()));
                     }
                 }
             }
         }
 
+        assert!(
+            last_value.is_some(),
+            "Value must have been populated or already cached",
+        );
+
         InterpretOutcome {
             result: Ok(InterpretValue {
-                last_value: Some(last_value),
+                last_value,
                 used_values,
                 unused_values,
             }),
@@ -565,6 +756,80 @@ impl Interpreter {
         }
     }
 
+    /// Spills mesh values of the least recently demanded variables to
+    /// disk, until the values kept in memory fit the memory budget of
+    /// the spill store. The value of the index-th statement and the
+    /// unused values are about to be displayed and are never spilled.
+    ///
+    /// Values that fail to be spilled (e.g. because the disk is full)
+    /// are kept in memory.
+    fn spill_least_recently_demanded(&mut self, index: usize, unused_vars: &HashSet<VarIdent>) {
+        let spill_store = match &mut self.spill_store {
+            Some(spill_store) => spill_store,
+            None => return,
+        };
+
+        let mut resident_size_bytes = 0;
+        let mut spill_candidates = Vec::new();
+
+        for (stmt_index, stmt) in self.prog.stmts().iter().enumerate() {
+            let ast::Stmt::VarDecl(var_decl) = stmt;
+            let var_ident = var_decl.ident();
+
+            if let Some(var_info) = self.env.get(&var_ident) {
+                if let VarValue::Resident(value) = &var_info.value {
+                    let size_bytes = spill::estimate_value_size(value);
+                    resident_size_bytes += size_bytes;
+
+                    if size_bytes > 0 && stmt_index != index && !unused_vars.contains(&var_ident) {
+                        spill_candidates.push((
+                            var_info.last_demanded_interpretation,
+                            stmt_index,
+                            var_ident,
+                            size_bytes,
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Of the vars demanded in the same interpretation, the ones
+        // declared earlier are further from what is being worked on
+        spill_candidates.sort_by_key(|(last_demanded_interpretation, stmt_index, _, _)| {
+            (*last_demanded_interpretation, *stmt_index)
+        });
+
+        for (_, _, var_ident, size_bytes) in spill_candidates {
+            if resident_size_bytes <= spill_store.memory_budget_bytes() {
+                break;
+            }
+
+            let var_info = self
+                .env
+                .get_mut(&var_ident)
+                .expect("Spill candidate must have a value");
+            let spill_result = match &var_info.value {
+                VarValue::Resident(value) => spill_store.spill(value),
+                VarValue::Spilled(_) => unreachable!("Spill candidate must be resident"),
+            };
+
+            match spill_result {
+                Ok(spilled_value) => {
+                    log::debug!("Spilled {} ({} bytes) to disk", var_ident, size_bytes);
+                    var_info.value = VarValue::Spilled(Arc::new(spilled_value));
+                    resident_size_bytes -= size_bytes;
+                }
+                Err(err) => {
+                    log::warn!(
+                        "Failed to spill {}, keeping it in memory: {}",
+                        var_ident,
+                        err
+                    );
+                }
+            }
+        }
+    }
+
     /// Computes a set of variable identifiers that would be unused,
     /// if the current program were only interpreted up to index-th
     /// statement.
@@ -594,6 +859,51 @@ impl Interpreter {
         unused_vars
     }
 
+    /// Computes a set of variable identifiers whose values are needed,
+    /// if the current program were only interpreted up to index-th
+    /// statement.
+    ///
+    /// All unused variables are demanded, because they are the
+    /// results of the program. Going backwards from the last
+    /// statement, variables referenced by demanded arguments of
+    /// demanded variables' funcs are also demanded.
+    fn compute_demanded_vars_up_until(
+        &self,
+        index: usize,
+        unused_vars: &HashSet<ast::VarIdent>,
+    ) -> HashSet<ast::VarIdent> {
+        let mut demanded_vars = unused_vars.clone();
+
+        for stmt in self.prog.stmts()[0..=index].iter().rev() {
+            match stmt {
+                ast::Stmt::VarDecl(var_decl) => {
+                    if !demanded_vars.contains(&var_decl.ident()) {
+                        continue;
+                    }
+
+                    // Held values don't need their args
+                    if self.manual_update_vars.contains(&var_decl.ident())
+                        && self.env.contains_key(&var_decl.ident())
+                    {
+                        continue;
+                    }
+
+                    let init_expr = var_decl.init_expr();
+                    let func = &self.funcs[&init_expr.ident()];
+                    for (arg_index, arg) in init_expr.args().iter().enumerate() {
+                        if let ast::Expr::Var(var) = arg {
+                            if func.arg_demanded(arg_index, init_expr.args()) {
+                                demanded_vars.insert(var.ident());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        demanded_vars
+    }
+
     /// Invalidates variables in the environment.
     ///
     /// Verify all variables we have computed already, invalidating
@@ -621,6 +931,8 @@ impl Interpreter {
         // incremental computation model with fact verification a-lá
         // salsa. https://github.com/salsa-rs/salsa
 
+        self.stale_vars.clear();
+
         for stmt in self.prog.stmts() {
             match stmt {
                 ast::Stmt::VarDecl(var_decl) => {
@@ -628,6 +940,26 @@ impl Interpreter {
                     let init_expr = var_decl.init_expr();
                     let func_ident = init_expr.ident();
 
+                    // Manually updated vars hold their values until
+                    // explicitly invalidated, but we remember whether
+                    // they are out of date.
+                    if self.manual_update_vars.contains(&var_ident) {
+                        if let Some(var_info) = self.env.get(&var_ident) {
+                            let stale = &var_info.created_call != init_expr
+                                || init_expr.args().iter().any(|expr| match expr {
+                                    ast::Expr::Var(var) => !self.env.contains_key(&var.ident()),
+                                    ast::Expr::Lit(_) => false,
+                                });
+
+                            if stale {
+                                log::debug!("Holding stale manually updated {}", var_ident);
+                                self.stale_vars.insert(var_ident);
+                            }
+
+                            continue;
+                        }
+                    }
+
                     // Perform 1) Impurity invalidation
 
                     if !self.funcs[&func_ident].flags().contains(FuncFlags::PURE) {
@@ -653,9 +985,14 @@ impl Interpreter {
 
                     // Perform 3) Dependency invalidation
 
-                    for expr in var_decl.init_expr().args() {
+                    // Args that are not demanded are not evaluated and
+                    // their vars need not have a value.
+                    let func = &self.funcs[&func_ident];
+                    for (arg_index, expr) in init_expr.args().iter().enumerate() {
                         if let ast::Expr::Var(var) = expr {
-                            if !self.env.contains_key(&var.ident()) {
+                            if func.arg_demanded(arg_index, init_expr.args())
+                                && !self.env.contains_key(&var.ident())
+                            {
                                 log::debug!("Performing dependency invalidation of {}", var_ident);
                                 self.env.remove(&var_ident);
 
@@ -669,22 +1006,110 @@ impl Interpreter {
     }
 }
 
+// Only the editor edits the program in place and controls the
+// interpretation
+#[cfg(any(test, feature = "gui"))]
+impl Interpreter {
+    /// Sets the token, which can cancel the following interpretations
+    /// from another thread.
+    pub fn set_cancellation_token(&mut self, cancellation_token: CancellationToken) {
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Sets the store, to which mesh values of variables not demanded
+    /// recently are spilled after each following interpretation,
+    /// should they exceed its memory budget.
+    pub fn set_spill_store(&mut self, spill_store: SpillStore) {
+        self.spill_store = Some(spill_store);
+    }
+
+    /// Sets whether the variable is only re-computed when explicitly
+    /// requested via `invalidate_var`.
+    pub fn set_var_manual_update(&mut self, var_ident: VarIdent, manual_update: bool) {
+        if manual_update {
+            self.manual_update_vars.insert(var_ident);
+        } else {
+            self.manual_update_vars.remove(&var_ident);
+        }
+    }
+
+    /// Removes the variable's value, so that it is re-computed by the
+    /// next interpretation. Used to update manually updated
+    /// variables.
+    pub fn invalidate_var(&mut self, var_ident: VarIdent) {
+        self.env.remove(&var_ident);
+    }
+
+    pub fn set_prog_stmt_at(&mut self, index: usize, stmt: ast::Stmt) {
+        self.prog.set_stmt_at(index, stmt);
+        self.log_messages[index].clear();
+        self.epoch += 1;
+    }
+}
+
+// Only the editor builds the program statement by statement
+#[cfg(feature = "gui")]
+impl Interpreter {
+    pub fn prog(&self) -> &ast::Prog {
+        &self.prog
+    }
+
+    pub fn clear_prog(&mut self) {
+        self.prog = ast::Prog::default();
+
+        self.env.clear();
+        self.manual_update_vars.clear();
+        self.log_messages.clear();
+
+        self.epoch += 1;
+    }
+
+    pub fn push_prog_stmt(&mut self, stmt: ast::Stmt) {
+        self.prog.push_stmt(stmt);
+        self.log_messages.push(Vec::new());
+        self.epoch += 1;
+    }
+
+    pub fn pop_prog_stmt(&mut self) {
+        assert!(
+            !self.prog.stmts().is_empty(),
+            "Program must not be empty when popping"
+        );
+
+        if let Some(ast::Stmt::VarDecl(var_decl)) = self.prog.stmts().last() {
+            self.manual_update_vars.remove(&var_decl.ident());
+        }
+
+        self.prog.pop_stmt();
+        self.log_messages.pop();
+        self.epoch += 1;
+    }
+}
+
 fn eval_stmt(
     stmt_index: usize,
     stmt: &ast::Stmt,
     funcs: &mut BTreeMap<FuncIdent, Box<dyn Func>>,
     env: &mut HashMap<VarIdent, VarInfo>,
     log_messages: &mut [Vec<LogMessage>],
+    cancellation_token: &CancellationToken,
+    report_progress: &mut dyn FnMut(usize, f32),
 ) -> Result<(), RuntimeError> {
     let time_start = Instant::now();
     log::debug!("Evaluating stmt {}: {}", stmt_index, stmt);
 
     let result = match stmt {
-        ast::Stmt::VarDecl(var_decl) => {
-            eval_var_decl_stmt(stmt_index, var_decl, funcs, env, &mut |message| {
+        ast::Stmt::VarDecl(var_decl) => eval_var_decl_stmt(
+            stmt_index,
+            var_decl,
+            funcs,
+            env,
+            cancellation_token,
+            &mut |func_progress| report_progress(stmt_index, func_progress),
+            &mut |message| {
                 log_messages[stmt_index].push(message);
-            })
-        }
+            },
+        ),
     };
 
     let elapsed_ms = time_start.elapsed().as_secs_f32() * 1000.0;
@@ -715,40 +1140,200 @@ fn eval_stmt(
     }
 }
 
-fn eval_var_decl_stmt(
+/// Evaluates the section of the program repeated by the func called
+/// in `var_decl`, feeding the value of the section's end variable
+/// back as the value of its start variable for each subsequent
+/// iteration. The first iteration must have already been evaluated.
+///
+/// Only the statements depending on the start variable are
+/// re-evaluated. Returns the variables of the first iteration, which
+/// have been overwritten by subsequent iterations and should be
+/// restored once the func has been called.
+#[allow(clippy::too_many_arguments)]
+fn eval_repeat(
     stmt_index: usize,
     var_decl: &ast::VarDeclStmt,
+    repeat: Repeat,
+    stmts: &[ast::Stmt],
     funcs: &mut BTreeMap<FuncIdent, Box<dyn Func>>,
     env: &mut HashMap<VarIdent, VarInfo>,
-    log: &mut dyn FnMut(LogMessage),
-) -> Result<bool, RuntimeError> {
-    let var_ident = var_decl.ident();
-
-    // This is a false positive. Bad Clippy, bad! Rewriting the code
-    // to use the entry API would fail borrowchecking (and cause
-    // pointer invalidation if it didn't!). The entry would create a
-    // long-lived mutable borrow which simply can't be live when we
-    // call into `eval_call_expr`, which borrows again. Note that
-    // having `eval_call_expr` guarded by the map access is the whole
-    // point here.
+    log_messages: &mut [Vec<LogMessage>],
+    cancellation_token: &CancellationToken,
+    report_progress: &mut dyn FnMut(usize, f32),
+) -> Result<Vec<(VarIdent, VarInfo)>, RuntimeError> {
+    let call = var_decl.init_expr();
+    let (start_var, end_var) = match (&call.args()[repeat.start_arg], &call.args()[repeat.end_arg])
+    {
+        (ast::Expr::Var(start_var), ast::Expr::Var(end_var)) => {
+            (start_var.ident(), end_var.ident())
+        }
+        // Invalid args will be reported when calling the func
+        _ => return Ok(Vec::new()),
+    };
 
-    // FIXME: remove allow after
-    // https://github.com/rust-lang/rust-clippy/issues/4674 is
-    // resolved
+    let stmt_index_for_var = |var_ident| {
+        stmts[0..stmt_index]
+            .iter()
+            .position(|stmt| match stmt {
+                ast::Stmt::VarDecl(var_decl) => var_decl.ident() == var_ident,
+            })
+            .expect("Name resolution must have verified the var exists")
+    };
+    let start_index = stmt_index_for_var(start_var);
+    let end_index = stmt_index_for_var(end_var);
 
-    #[allow(clippy::map_entry)]
+    if end_index < start_index {
+        return Err(RuntimeError::RepeatEndBeforeStart {
+            stmt_index,
+            call: call.clone(),
+        });
+    }
+
+    // Vars without a value were not demanded in the first iteration,
+    // or were blocked. They are not part of the section.
+    if !env.contains_key(&start_var) || !env.contains_key(&end_var) {
+        return Ok(Vec::new());
+    }
+
+    let mut section_vars = HashSet::new();
+    section_vars.insert(start_var);
+
+    // Vars holding the iteration index are assigned directly instead
+    // of being re-evaluated
+    let mut index_vars = Vec::new();
+    let mut section_stmt_indices = Vec::new();
+    for (index, stmt) in stmts
+        .iter()
+        .enumerate()
+        .take(end_index + 1)
+        .skip(start_index + 1)
+    {
+        match stmt {
+            ast::Stmt::VarDecl(var_decl) => {
+                let init_expr = var_decl.init_expr();
+                let depends_on_section = init_expr.args().iter().any(|arg| match arg {
+                    ast::Expr::Var(var) => section_vars.contains(&var.ident()),
+                    ast::Expr::Lit(_) => false,
+                });
+
+                if depends_on_section && env.contains_key(&var_decl.ident()) {
+                    let is_index = match funcs[&init_expr.ident()].repeat_index_arg() {
+                        Some(arg_index) => match init_expr.args().get(arg_index) {
+                            Some(ast::Expr::Var(var)) => var.ident() == start_var,
+                            _ => false,
+                        },
+                        None => false,
+                    };
+
+                    section_vars.insert(var_decl.ident());
+                    if is_index {
+                        index_vars.push(var_decl.ident());
+                    } else {
+                        section_stmt_indices.push(index);
+                    }
+                }
+            }
+        }
+    }
+
+    let first_iteration: Vec<_> = section_vars
+        .iter()
+        .map(|var_ident| (*var_ident, env[var_ident].clone()))
+        .collect();
+
+    for iteration in 1..repeat.iterations {
+        let end_value = match eval_var_expr(stmt_index, end_var, env) {
+            Ok(end_value) => end_value,
+            Err(err) => {
+                env.extend(first_iteration);
+                return Err(err);
+            }
+        };
+        env.get_mut(&start_var)
+            .expect("Start var must have a value")
+            .value = VarValue::Resident(end_value);
+        for index_var in &index_vars {
+            env.get_mut(index_var)
+                .expect("Index var must have a value")
+                .value = VarValue::Resident(Value::Uint(iteration));
+        }
+
+        for index in &section_stmt_indices {
+            match &stmts[*index] {
+                ast::Stmt::VarDecl(var_decl) => env.remove(&var_decl.ident()),
+            };
+        }
+
+        for index in &section_stmt_indices {
+            if let Err(err) = eval_stmt(
+                *index,
+                &stmts[*index],
+                funcs,
+                env,
+                log_messages,
+                cancellation_token,
+                report_progress,
+            ) {
+                env.extend(first_iteration);
+                return Err(err);
+            }
+        }
+    }
+
+    log_messages[stmt_index].push(LogMessage::info(format!(
+        "Repeated {} statement(s) {} times",
+        section_stmt_indices.len(),
+        repeat.iterations,
+    )));
+
+    Ok(first_iteration)
+}
+
+fn eval_var_decl_stmt(
+    stmt_index: usize,
+    var_decl: &ast::VarDeclStmt,
+    funcs: &mut BTreeMap<FuncIdent, Box<dyn Func>>,
+    env: &mut HashMap<VarIdent, VarInfo>,
+    cancellation_token: &CancellationToken,
+    report_progress: &mut dyn FnMut(f32),
+    log: &mut dyn FnMut(LogMessage),
+) -> Result<bool, RuntimeError> {
+    let var_ident = var_decl.ident();
+
+    // This is a false positive. Bad Clippy, bad! Rewriting the code
+    // to use the entry API would fail borrowchecking (and cause
+    // pointer invalidation if it didn't!). The entry would create a
+    // long-lived mutable borrow which simply can't be live when we
+    // call into `eval_call_expr`, which borrows again. Note that
+    // having `eval_call_expr` guarded by the map access is the whole
+    // point here.
+
+    // FIXME: remove allow after
+    // https://github.com/rust-lang/rust-clippy/issues/4674 is
+    // resolved
+
+    #[allow(clippy::map_entry)]
     {
         if env.contains_key(&var_ident) {
             Ok(true)
         } else {
             let init_expr = var_decl.init_expr();
-            let value = eval_call_expr(stmt_index, init_expr, funcs, env, log)?;
+            let value = eval_call_expr(
+                stmt_index,
+                init_expr,
+                funcs,
+                env,
+                cancellation_token,
+                report_progress,
+                log,
+            )?;
 
             env.insert(
                 var_ident,
                 VarInfo {
                     created_call: init_expr.clone(),
-                    value,
+                    value: VarValue::Resident(value),
+                    last_demanded_interpretation: 0,
                 },
             );
 
@@ -758,12 +1343,13 @@ fn eval_var_decl_stmt(
 }
 
 fn eval_expr(
+    stmt_index: usize,
     expr: &ast::Expr,
     env: &mut HashMap<VarIdent, VarInfo>,
 ) -> Result<Value, RuntimeError> {
     match expr {
         ast::Expr::Lit(lit) => eval_lit_expr(lit),
-        ast::Expr::Var(var) => eval_var_expr(var, env),
+        ast::Expr::Var(var) => eval_var_expr(stmt_index, var.ident(), env),
     }
 }
 
@@ -776,6 +1362,8 @@ fn eval_lit_expr(lit: &ast::LitExpr) -> Result<Value, RuntimeError> {
         ast::LitExpr::Float2(float2) => Value::Float2(*float2),
         ast::LitExpr::Float3(float3) => Value::Float3(*float3),
         ast::LitExpr::String(string) => Value::String(Arc::clone(&string)),
+        ast::LitExpr::Mesh(mesh) => Value::Mesh(Arc::clone(&mesh)),
+        ast::LitExpr::Curve(curve) => Value::Curve(Arc::clone(&curve)),
         ast::LitExpr::Nil => Value::Nil,
     };
 
@@ -783,13 +1371,26 @@ fn eval_lit_expr(lit: &ast::LitExpr) -> Result<Value, RuntimeError> {
 }
 
 fn eval_var_expr(
-    var: &ast::VarExpr,
+    stmt_index: usize,
+    var_ident: VarIdent,
     env: &mut HashMap<VarIdent, VarInfo>,
 ) -> Result<Value, RuntimeError> {
-    let var_ident = var.ident();
-    let var_info = &env[&var_ident];
+    let var_info = env.get_mut(&var_ident).expect("Var must have a value");
+
+    match var_info.load_value() {
+        Ok(value) => Ok(value),
+        Err(err) => {
+            // Without the var, its value will be recomputed by the
+            // next interpretation
+            env.remove(&var_ident);
 
-    Ok(var_info.value.clone())
+            Err(RuntimeError::SpillReload {
+                stmt_index,
+                var: var_ident,
+                message: err.to_string(),
+            })
+        }
+    }
 }
 
 fn eval_call_expr(
@@ -797,6 +1398,8 @@ fn eval_call_expr(
     call: &ast::CallExpr,
     funcs: &mut BTreeMap<FuncIdent, Box<dyn Func>>,
     env: &mut HashMap<VarIdent, VarInfo>,
+    cancellation_token: &CancellationToken,
+    report_progress: &mut dyn FnMut(f32),
     log: &mut dyn FnMut(LogMessage),
 ) -> Result<Value, RuntimeError> {
     // FIXME: @Diagnostics use the func name and the param names in
@@ -815,21 +1418,48 @@ fn eval_call_expr(
     }
 
     let mut args = Vec::with_capacity(arg_exprs.len());
-    for arg_expr in arg_exprs {
-        let arg = eval_expr(arg_expr, env)?;
+    for (arg_index, arg_expr) in arg_exprs.iter().enumerate() {
+        let arg = if func.arg_demanded(arg_index, arg_exprs) {
+            eval_expr(stmt_index, arg_expr, env)?
+        } else {
+            Value::Nil
+        };
         args.push(arg);
     }
 
-    for (info, value) in func.param_info().iter().zip(args.iter()) {
+    for (arg_index, info) in func.param_info().iter().enumerate() {
         let param_ty = info.refinement.ty();
-        let value_ty = value.ty();
+        let value_ty = args[arg_index].ty();
 
         if param_ty != value_ty {
+            // Args that are not demanded are always Nil
+            if !func.arg_demanded(arg_index, arg_exprs) {
+                continue;
+            }
+
+            // A func upstream did not produce a value (e.g. a closed
+            // gate), so this func can not produce one either
+            if value_ty == Ty::Nil && !info.optional {
+                if let ast::Expr::Var(_) = arg_exprs[arg_index] {
+                    log(LogMessage::info(format!(
+                        "Skipped, \"{}\" has no value",
+                        info.name,
+                    )));
+
+                    return Ok(Value::Nil);
+                }
+            }
+
             // Nil is an acceptable value for parameters marked optional
             if value_ty == Ty::Nil && info.optional {
                 continue;
             }
 
+            if let Some(coerced_value) = args[arg_index].coerce(param_ty) {
+                args[arg_index] = coerced_value;
+                continue;
+            }
+
             return Err(RuntimeError::ArgTyMismatch {
                 stmt_index,
                 call: call.clone(),
@@ -840,12 +1470,25 @@ fn eval_call_expr(
         }
     }
 
-    match func.call(&args, log) {
+    if !func.flags().contains(FuncFlags::ACCEPTS_QUADS) {
+        for arg in &mut args {
+            if let Some(triangulated_value) = arg.triangulated() {
+                *arg = triangulated_value;
+            }
+        }
+    }
+
+    if cancellation_token.is_cancelled() {
+        return Err(RuntimeError::Cancelled { stmt_index });
+    }
+
+    match func.call(&args, cancellation_token, report_progress, log) {
         Ok(value) => {
             let return_ty = func.return_ty();
             let value_ty = value.ty();
 
-            if return_ty != value_ty {
+            // Funcs are allowed to produce no value
+            if return_ty != value_ty && value_ty != Ty::Nil {
                 return Err(RuntimeError::ReturnTyMismatch {
                     stmt_index,
                     call: call.clone(),
@@ -856,6 +1499,9 @@ fn eval_call_expr(
 
             Ok(value)
         }
+        // Funcs stopping early because of the cancellation report it
+        // as an error, but it is not the func's fault
+        Err(_) if cancellation_token.is_cancelled() => Err(RuntimeError::Cancelled { stmt_index }),
         Err(func_error) => Err(RuntimeError::Func {
             stmt_index,
             call: call.clone(),
@@ -866,9 +1512,16 @@ fn eval_call_expr(
 
 #[cfg(test)]
 mod tests {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
+    use std::env;
+    use std::process;
     use std::rc::Rc;
 
+    use nalgebra::{Point3, Rotation3, Vector3};
+
+    use crate::mesh::{primitive, smoothing, NormalStrategy};
+
+    use super::func::IntParamRefinement;
     use super::*;
 
     fn param_info(ty: Ty, optional: bool) -> ParamInfo {
@@ -885,6 +1538,9 @@ mod tests {
                 Ty::String => ParamRefinement::String(StringParamRefinement::default()),
                 Ty::Mesh => ParamRefinement::Mesh,
                 Ty::MeshArray => ParamRefinement::MeshArray,
+                Ty::Locator => ParamRefinement::Locator,
+                Ty::Curve => ParamRefinement::Curve,
+                Ty::Points => panic!("No test function takes points"),
             },
             optional,
         }
@@ -933,6 +1589,8 @@ mod tests {
         fn call(
             &mut self,
             values: &[Value],
+            _cancellation_token: &CancellationToken,
+            _report_progress: &mut dyn FnMut(f32),
             _log: &mut dyn FnMut(LogMessage),
         ) -> Result<Value, FuncError> {
             (self.func)(values)
@@ -960,6 +1618,144 @@ mod tests {
         }
     }
 
+    /// Returns the value of the argument selected by the first
+    /// argument. Only the selected argument is demanded.
+    struct TestSelectFunc {
+        param_info: Vec<ParamInfo>,
+    }
+
+    impl TestSelectFunc {
+        pub fn new(ty: Ty, option_count: usize) -> Self {
+            let mut param_info = vec![param_info(Ty::Uint, false)];
+            for _ in 0..option_count {
+                param_info.push(self::param_info(ty, true));
+            }
+
+            Self { param_info }
+        }
+    }
+
+    impl Func for TestSelectFunc {
+        fn flags(&self) -> FuncFlags {
+            FuncFlags::PURE
+        }
+
+        fn param_info(&self) -> &[ParamInfo] {
+            &self.param_info
+        }
+
+        fn return_ty(&self) -> Ty {
+            self.param_info[1].refinement.ty()
+        }
+
+        fn arg_demanded(&self, arg_index: usize, args: &[ast::Expr]) -> bool {
+            let selected = args[0].unwrap_literal().unwrap_uint();
+            arg_index == 0 || arg_index == selected as usize
+        }
+
+        fn call(
+            &mut self,
+            values: &[Value],
+            _cancellation_token: &CancellationToken,
+            _report_progress: &mut dyn FnMut(f32),
+            _log: &mut dyn FnMut(LogMessage),
+        ) -> Result<Value, FuncError> {
+            Ok(values[values[0].unwrap_uint() as usize].clone())
+        }
+    }
+
+    /// Repeats the section between its first two arguments as many
+    /// times as the third argument says.
+    struct TestRepeatFunc {
+        param_info: Vec<ParamInfo>,
+    }
+
+    impl TestRepeatFunc {
+        pub fn new(ty: Ty) -> Self {
+            Self {
+                param_info: vec![
+                    param_info(ty, false),
+                    param_info(ty, false),
+                    param_info(Ty::Uint, false),
+                ],
+            }
+        }
+    }
+
+    impl Func for TestRepeatFunc {
+        fn flags(&self) -> FuncFlags {
+            FuncFlags::PURE
+        }
+
+        fn param_info(&self) -> &[ParamInfo] {
+            &self.param_info
+        }
+
+        fn return_ty(&self) -> Ty {
+            self.param_info[0].refinement.ty()
+        }
+
+        fn repeat(&self, args: &[ast::Expr]) -> Option<Repeat> {
+            Some(Repeat {
+                start_arg: 0,
+                end_arg: 1,
+                iterations: args[2].unwrap_literal().unwrap_uint(),
+            })
+        }
+
+        fn call(
+            &mut self,
+            values: &[Value],
+            _cancellation_token: &CancellationToken,
+            _report_progress: &mut dyn FnMut(f32),
+            _log: &mut dyn FnMut(LogMessage),
+        ) -> Result<Value, FuncError> {
+            Ok(values[1].clone())
+        }
+    }
+
+    /// Returns the index of the current iteration of the section
+    /// starting with its argument.
+    struct TestRepeatIndexFunc {
+        param_info: Vec<ParamInfo>,
+    }
+
+    impl TestRepeatIndexFunc {
+        pub fn new(ty: Ty) -> Self {
+            Self {
+                param_info: vec![param_info(ty, false)],
+            }
+        }
+    }
+
+    impl Func for TestRepeatIndexFunc {
+        fn flags(&self) -> FuncFlags {
+            FuncFlags::PURE
+        }
+
+        fn param_info(&self) -> &[ParamInfo] {
+            &self.param_info
+        }
+
+        fn return_ty(&self) -> Ty {
+            Ty::Uint
+        }
+
+        fn repeat_index_arg(&self) -> Option<usize> {
+            Some(0)
+        }
+
+        fn call(
+            &mut self,
+            _values: &[Value],
+            _cancellation_token: &CancellationToken,
+            _report_progress: &mut dyn FnMut(f32),
+            _log: &mut dyn FnMut(LogMessage),
+        ) -> Result<Value, FuncError> {
+            Ok(Value::Uint(0))
+        }
+    }
+
     // Basic tests a.k.a. does it even run?
 
     #[test]
@@ -1574,6 +2370,81 @@ mod tests {
         assert_eq!(n_calls2.get(), 2);
     }
 
+    #[test]
+    fn test_interpreter_interpret_manual_update_holds_value_until_invalidated() {
+        let n_calls1 = Rc::new(CallCount::new());
+        let n_calls2 = Rc::new(CallCount::new());
+        let c1 = Rc::clone(&n_calls1);
+        let c2 = Rc::clone(&n_calls2);
+
+        let (func_id1, func1) = (
+            FuncIdent(0),
+            TestFunc::new(
+                move |_| {
+                    c1.inc();
+                    Ok(Value::Boolean(true))
+                },
+                FuncFlags::empty(),
+                vec![],
+                Ty::Boolean,
+            ),
+        );
+
+        let (func_id2, func2) = (
+            FuncIdent(1),
+            TestFunc::new(
+                move |values| {
+                    c2.inc();
+                    Ok(Value::Boolean(values[0].unwrap_boolean()))
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Boolean, false)],
+                Ty::Boolean,
+            ),
+        );
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id1, vec![]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    func_id2,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id1, Box::new(func1));
+        funcs.insert(func_id2, Box::new(func2));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+        interpreter.set_var_manual_update(VarIdent(1), true);
+
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Boolean(true)));
+
+        // The held value doesn't need its impure dependency to be
+        // re-computed
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Boolean(true)));
+
+        assert_eq!(n_calls1.get(), 1);
+        assert_eq!(n_calls2.get(), 1);
+
+        interpreter.invalidate_var(VarIdent(1));
+
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Boolean(true)));
+
+        assert_eq!(n_calls1.get(), 2);
+        assert_eq!(n_calls2.get(), 2);
+    }
+
     // FIXME: Prog manipulation tests
 
     // Name resolution tests
@@ -1814,7 +2685,7 @@ mod tests {
             ),
         );
 
-        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Int(1))]);
+        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Boolean(true))]);
         let prog = ast::Prog::new(vec![ast::Stmt::VarDecl(ast::VarDeclStmt::new(
             VarIdent(0),
             call.clone(),
@@ -1834,27 +2705,33 @@ mod tests {
                 call,
                 optional: false,
                 ty_expected: Ty::Float,
-                ty_provided: Ty::Int,
+                ty_provided: Ty::Boolean,
             }),
         );
     }
 
     #[test]
-    fn test_interpreter_interpret_single_func_dynamic_optional_arg_ty() {
+    fn test_interpreter_interpret_single_func_coerces_args() {
         let (func_id, func) = (
             FuncIdent(0),
             TestFunc::new(
-                |values| match &values[0] {
-                    Value::Float(float_value) => Ok(Value::Float(*float_value)),
-                    _ => Ok(Value::Float(1.0)),
+                |values| {
+                    let float3 = values[1].unwrap_float3();
+                    Ok(Value::Float(values[0].unwrap_float() + float3[2]))
                 },
                 FuncFlags::PURE,
-                vec![param_info(Ty::Float, true)],
+                vec![param_info(Ty::Float, false), param_info(Ty::Float3, false)],
                 Ty::Float,
             ),
         );
 
-        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Nil)]);
+        let call = ast::CallExpr::new(
+            func_id,
+            vec![
+                ast::Expr::Lit(ast::LitExpr::Int(1)),
+                ast::Expr::Lit(ast::LitExpr::Float(2.0)),
+            ],
+        );
         let prog = ast::Prog::new(vec![ast::Stmt::VarDecl(ast::VarDeclStmt::new(
             VarIdent(0),
             call,
@@ -1867,11 +2744,109 @@ mod tests {
         interpreter.set_prog(prog);
 
         let value = interpreter.interpret().result.unwrap();
-        assert_eq!(value.last_value, Some(Value::Float(1.0)));
+        assert_eq!(value.last_value, Some(Value::Float(3.0)));
     }
 
     #[test]
-    fn test_interpreter_run_single_func_dynamic_optional_arg_ty_error() {
+    fn test_interpreter_interpret_triangulates_mesh_args_of_funcs_not_accepting_quads() {
+        let box_mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let mesh = Arc::new(smoothing::catmull_clark_subdivision(
+            &box_mesh,
+            1,
+            NormalStrategy::Smooth,
+        ));
+        assert!(!mesh.is_triangulated());
+
+        for &(flags, expected_triangulated) in &[
+            (FuncFlags::PURE, true),
+            (FuncFlags::PURE | FuncFlags::ACCEPTS_QUADS, false),
+        ] {
+            let m = Arc::clone(&mesh);
+            let (func_id_create, func_create) = (
+                FuncIdent(0),
+                TestFunc::new(
+                    move |_| Ok(Value::Mesh(Arc::clone(&m))),
+                    FuncFlags::PURE,
+                    vec![],
+                    Ty::Mesh,
+                ),
+            );
+            let (func_id_check, func_check) = (
+                FuncIdent(1),
+                TestFunc::new(
+                    |values| Ok(Value::Boolean(values[0].unwrap_mesh().is_triangulated())),
+                    flags,
+                    vec![param_info(Ty::Mesh, false)],
+                    Ty::Boolean,
+                ),
+            );
+
+            let prog = ast::Prog::new(vec![
+                ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                    VarIdent(0),
+                    ast::CallExpr::new(func_id_create, vec![]),
+                )),
+                ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                    VarIdent(1),
+                    ast::CallExpr::new(
+                        func_id_check,
+                        vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                    ),
+                )),
+            ]);
+
+            let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+            funcs.insert(func_id_create, Box::new(func_create));
+            funcs.insert(func_id_check, Box::new(func_check));
+
+            let mut interpreter = Interpreter::new(funcs);
+            interpreter.set_prog(prog);
+
+            let value = interpreter.interpret().result.unwrap();
+            assert_eq!(
+                value.last_value,
+                Some(Value::Boolean(expected_triangulated))
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpreter_interpret_single_func_dynamic_optional_arg_ty() {
+        let (func_id, func) = (
+            FuncIdent(0),
+            TestFunc::new(
+                |values| match &values[0] {
+                    Value::Float(float_value) => Ok(Value::Float(*float_value)),
+                    _ => Ok(Value::Float(1.0)),
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Float, true)],
+                Ty::Float,
+            ),
+        );
+
+        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Nil)]);
+        let prog = ast::Prog::new(vec![ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+            VarIdent(0),
+            call,
+        ))]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id, Box::new(func));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Float(1.0)));
+    }
+
+    #[test]
+    fn test_interpreter_run_single_func_dynamic_optional_arg_ty_error() {
         let (func_id, func) = (
             FuncIdent(0),
             TestFunc::new(
@@ -1885,7 +2860,7 @@ mod tests {
             ),
         );
 
-        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Int(1))]);
+        let call = ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Boolean(true))]);
         let prog = ast::Prog::new(vec![ast::Stmt::VarDecl(ast::VarDeclStmt::new(
             VarIdent(0),
             call.clone(),
@@ -1905,7 +2880,7 @@ mod tests {
                 call,
                 optional: true,
                 ty_expected: Ty::Float,
-                ty_provided: Ty::Int,
+                ty_provided: Ty::Boolean,
             }),
         );
     }
@@ -2065,4 +3040,465 @@ mod tests {
         assert_eq!(interpret_outcome.pc, 4);
         assert_eq!(interpret_outcome.log_messages.len(), 4);
     }
+
+    #[test]
+    fn test_interpreter_interpret_skips_stmts_not_demanded_by_lazy_func() {
+        let n_calls = Rc::new(CallCount::new());
+        let c = Rc::clone(&n_calls);
+
+        let (func_id, func) = (
+            FuncIdent(0),
+            TestFunc::new(
+                move |values| {
+                    c.inc();
+                    Ok(Value::Boolean(values[0].unwrap_boolean()))
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Boolean, false)],
+                Ty::Boolean,
+            ),
+        );
+        let (select_func_id, select_func) = (FuncIdent(1), TestSelectFunc::new(Ty::Boolean, 2));
+
+        let select_stmt = |selected| {
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(2),
+                ast::CallExpr::new(
+                    select_func_id,
+                    vec![
+                        ast::Expr::Lit(ast::LitExpr::Uint(selected)),
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(0))),
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(1))),
+                    ],
+                ),
+            ))
+        };
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Boolean(true))]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Boolean(false))]),
+            )),
+            select_stmt(1),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id, Box::new(func));
+        funcs.insert(select_func_id, Box::new(select_func));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+
+        let interpret_outcome = interpreter.interpret();
+        assert_eq!(
+            interpret_outcome.result,
+            Ok(InterpretValue {
+                last_value: Some(Value::Boolean(true)),
+                used_values: vec![(VarIdent(0), Value::Boolean(true))],
+                unused_values: vec![(VarIdent(2), Value::Boolean(true))],
+            }),
+        );
+        assert_eq!(n_calls.get(), 1);
+
+        interpreter.set_prog_stmt_at(2, select_stmt(2));
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Boolean(false)));
+        assert_eq!(n_calls.get(), 2);
+
+        // Both branches are cached now
+        interpreter.set_prog_stmt_at(2, select_stmt(1));
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Boolean(true)));
+        assert_eq!(n_calls.get(), 2);
+    }
+
+    #[test]
+    fn test_interpreter_interpret_skips_func_with_nil_var_arg() {
+        let n_calls = Rc::new(CallCount::new());
+        let c = Rc::clone(&n_calls);
+
+        let (nil_func_id, nil_func) = (
+            FuncIdent(0),
+            TestFunc::new(|_| Ok(Value::Nil), FuncFlags::PURE, vec![], Ty::Boolean),
+        );
+        let (func_id, func) = (
+            FuncIdent(1),
+            TestFunc::new(
+                move |values| {
+                    c.inc();
+                    Ok(Value::Boolean(values[0].unwrap_boolean()))
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Boolean, false)],
+                Ty::Boolean,
+            ),
+        );
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(nil_func_id, vec![]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    func_id,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(nil_func_id, Box::new(nil_func));
+        funcs.insert(func_id, Box::new(func));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Nil));
+        assert_eq!(n_calls.get(), 0);
+    }
+
+    #[test]
+    fn test_interpreter_interpret_repeat_feeds_back_section_values() {
+        let n_calls = Rc::new(CallCount::new());
+        let c = Rc::clone(&n_calls);
+
+        let (func_id, func) = (
+            FuncIdent(0),
+            TestFunc::new(
+                move |values| {
+                    c.inc();
+                    Ok(Value::Float(values[0].unwrap_float() * 2.0))
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Float, false)],
+                Ty::Float,
+            ),
+        );
+        let (repeat_func_id, repeat_func) = (FuncIdent(1), TestRepeatFunc::new(Ty::Float));
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Float(0.5))]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    func_id,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(2),
+                ast::CallExpr::new(
+                    repeat_func_id,
+                    vec![
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(0))),
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(1))),
+                        ast::Expr::Lit(ast::LitExpr::Uint(3)),
+                    ],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id, Box::new(func));
+        funcs.insert(repeat_func_id, Box::new(repeat_func));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+
+        // The first iteration's values are restored after repeating
+        let interpret_outcome = interpreter.interpret();
+        assert_eq!(
+            interpret_outcome.result,
+            Ok(InterpretValue {
+                last_value: Some(Value::Float(8.0)),
+                used_values: vec![
+                    (VarIdent(0), Value::Float(1.0)),
+                    (VarIdent(1), Value::Float(2.0)),
+                ],
+                unused_values: vec![(VarIdent(2), Value::Float(8.0))],
+            }),
+        );
+        assert_eq!(n_calls.get(), 4);
+
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Float(8.0)));
+        assert_eq!(n_calls.get(), 4);
+    }
+
+    #[test]
+    fn test_interpreter_interpret_repeat_assigns_iteration_index() {
+        let (func_id, func) = (
+            FuncIdent(0),
+            TestFunc::new(
+                |values| Ok(Value::Float(values[0].unwrap_float())),
+                FuncFlags::PURE,
+                vec![param_info(Ty::Float, false)],
+                Ty::Float,
+            ),
+        );
+        let (add_func_id, add_func) = (
+            FuncIdent(1),
+            TestFunc::new(
+                |values| {
+                    Ok(Value::Float(
+                        values[0].unwrap_float() + values[1].unwrap_float(),
+                    ))
+                },
+                FuncFlags::PURE,
+                vec![param_info(Ty::Float, false), param_info(Ty::Float, false)],
+                Ty::Float,
+            ),
+        );
+        let (index_func_id, index_func) = (FuncIdent(2), TestRepeatIndexFunc::new(Ty::Float));
+        let (repeat_func_id, repeat_func) = (FuncIdent(3), TestRepeatFunc::new(Ty::Float));
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id, vec![ast::Expr::Lit(ast::LitExpr::Float(0.5))]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    index_func_id,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(2),
+                ast::CallExpr::new(
+                    add_func_id,
+                    vec![
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(0))),
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(1))),
+                    ],
+                ),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(3),
+                ast::CallExpr::new(
+                    repeat_func_id,
+                    vec![
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(0))),
+                        ast::Expr::Var(ast::VarExpr::new(VarIdent(2))),
+                        ast::Expr::Lit(ast::LitExpr::Uint(3)),
+                    ],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id, Box::new(func));
+        funcs.insert(add_func_id, Box::new(add_func));
+        funcs.insert(index_func_id, Box::new(index_func));
+        funcs.insert(repeat_func_id, Box::new(repeat_func));
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_prog(prog);
+
+        // 0.5 + 0, then 0.5 + 1, then 1.5 + 2
+        let interpret_outcome = interpreter.interpret();
+        assert_eq!(
+            interpret_outcome.result,
+            Ok(InterpretValue {
+                last_value: Some(Value::Float(3.5)),
+                used_values: vec![
+                    (VarIdent(0), Value::Float(0.5)),
+                    (VarIdent(1), Value::Uint(0)),
+                    (VarIdent(2), Value::Float(0.5)),
+                ],
+                unused_values: vec![(VarIdent(3), Value::Float(3.5))],
+            }),
+        );
+    }
+
+    #[test]
+    fn test_interpreter_interpret_reports_progress_of_computed_stmts() {
+        let (func_id1, func1) = (
+            FuncIdent(0),
+            TestFunc::new(
+                |_| Ok(Value::Boolean(true)),
+                FuncFlags::PURE,
+                vec![],
+                Ty::Boolean,
+            ),
+        );
+        let (func_id2, func2) = (
+            FuncIdent(1),
+            TestFunc::new(
+                |values| Ok(Value::Boolean(values[0].unwrap_boolean())),
+                FuncFlags::PURE,
+                vec![param_info(Ty::Boolean, false)],
+                Ty::Boolean,
+            ),
+        );
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id1, vec![]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    func_id2,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id1, Box::new(func1));
+        funcs.insert(func_id2, Box::new(func2));
+
+        let progress = Rc::new(RefCell::new(Vec::new()));
+        let progress_clone = Rc::clone(&progress);
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_progress_callback(move |p| progress_clone.borrow_mut().push(p));
+        interpreter.set_prog(prog);
+
+        interpreter.interpret().result.unwrap();
+        assert_eq!(
+            *progress.borrow(),
+            vec![
+                InterpretProgress {
+                    stmt_index: 0,
+                    stmt_count: 2,
+                    func_progress: 0.0,
+                },
+                InterpretProgress {
+                    stmt_index: 1,
+                    stmt_count: 2,
+                    func_progress: 0.0,
+                },
+            ],
+        );
+
+        // Pure funcs are cached, so there is nothing to compute
+        progress.borrow_mut().clear();
+        interpreter.interpret().result.unwrap();
+        assert!(progress.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_interpreter_interpret_stops_when_cancelled() {
+        let (func_id, func) = (
+            FuncIdent(0),
+            TestFunc::new(
+                |_| Ok(Value::Boolean(true)),
+                FuncFlags::PURE,
+                vec![],
+                Ty::Boolean,
+            ),
+        );
+
+        let prog = ast::Prog::new(vec![ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+            VarIdent(0),
+            ast::CallExpr::new(func_id, vec![]),
+        ))]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id, Box::new(func));
+
+        let cancellation_token = CancellationToken::new();
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_cancellation_token(cancellation_token.clone());
+        interpreter.set_prog(prog);
+
+        cancellation_token.cancel();
+        match interpreter.interpret().result.unwrap_err() {
+            InterpretError::Runtime(RuntimeError::Cancelled { stmt_index }) => {
+                assert_eq!(stmt_index, 0);
+            }
+            _ => panic!("Interpretation must stop with cancellation"),
+        }
+
+        // Cancelled values are not cached, so the next run computes them
+        cancellation_token.reset();
+        interpreter.interpret().result.unwrap();
+    }
+
+    #[test]
+    fn test_interpreter_interpret_spills_and_reloads_used_values() {
+        let n_calls = Rc::new(CallCount::new());
+        let c = Rc::clone(&n_calls);
+
+        let mesh = Arc::new(primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        ));
+        let m = Arc::clone(&mesh);
+
+        let (func_id_create, func_create) = (
+            FuncIdent(0),
+            TestFunc::new(
+                move |_| {
+                    c.inc();
+                    Ok(Value::Mesh(Arc::clone(&m)))
+                },
+                FuncFlags::PURE,
+                vec![],
+                Ty::Mesh,
+            ),
+        );
+        let (func_id_pass, func_pass) = (
+            FuncIdent(1),
+            TestFunc::new(
+                |values| Ok(values[0].clone()),
+                FuncFlags::PURE,
+                vec![param_info(Ty::Mesh, false)],
+                Ty::Mesh,
+            ),
+        );
+
+        let prog = ast::Prog::new(vec![
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(0),
+                ast::CallExpr::new(func_id_create, vec![]),
+            )),
+            ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                VarIdent(1),
+                ast::CallExpr::new(
+                    func_id_pass,
+                    vec![ast::Expr::Var(ast::VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+        ]);
+
+        let mut funcs: BTreeMap<FuncIdent, Box<dyn Func>> = BTreeMap::new();
+        funcs.insert(func_id_create, Box::new(func_create));
+        funcs.insert(func_id_pass, Box::new(func_pass));
+
+        let dir = env::temp_dir().join(format!(
+            "hurban_selector_test_interpreter_spill_{}",
+            process::id(),
+        ));
+        let spill_store = SpillStore::new(dir, 0).expect("Failed to create spill store");
+
+        let mut interpreter = Interpreter::new(funcs);
+        interpreter.set_spill_store(spill_store);
+        interpreter.set_prog(prog);
+
+        // The used value of the first var doesn't fit the budget
+        let value = interpreter.interpret().result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Mesh(Arc::clone(&mesh))));
+        assert!(value.used_values.is_empty());
+
+        // Displaying the first var reloads it instead of recomputing
+        let value = interpreter.interpret_up_until(0).result.unwrap();
+        assert_eq!(value.last_value, Some(Value::Mesh(Arc::clone(&mesh))));
+        assert_eq!(n_calls.get(), 1);
+    }
 }
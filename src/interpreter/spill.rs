@@ -0,0 +1,221 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::convert::{cast_u32, cast_usize};
+use crate::mesh::{Face, Mesh};
+use crate::mesh_codec::{self, Precision};
+
+use super::{MeshArrayValue, Value};
+
+/// Where and when the interpreter spills values of variables to disk.
+///
+/// Once the mesh values held by the interpreter exceed the memory
+/// budget, the least recently used ones are written to compressed
+/// files in the spill directory and reloaded when they are needed
+/// again.
+#[derive(Debug)]
+pub struct SpillStore {
+    dir: PathBuf,
+    memory_budget_bytes: usize,
+    next_file_id: u64,
+}
+
+impl SpillStore {
+    /// Creates a spill store writing to `dir`, which is created if it
+    /// doesn't exist.
+    #[cfg(any(test, feature = "gui"))]
+    pub fn new(dir: PathBuf, memory_budget_bytes: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            memory_budget_bytes,
+            next_file_id: 0,
+        })
+    }
+
+    pub fn memory_budget_bytes(&self) -> usize {
+        self.memory_budget_bytes
+    }
+
+    /// Writes the value to a new compressed file in the spill
+    /// directory. Only mesh and mesh array values can be spilled.
+    ///
+    /// # Panics
+    /// Panics if the value is neither a mesh, nor a mesh array.
+    pub fn spill(&mut self, value: &Value) -> io::Result<SpilledValue> {
+        let (meshes, is_array): (Vec<&Mesh>, bool) = match value {
+            Value::Mesh(mesh) => (vec![&**mesh], false),
+            Value::MeshArray(mesh_array) => (mesh_array.iter().collect(), true),
+            _ => panic!("Only mesh and mesh array values can be spilled"),
+        };
+
+        let path = self.dir.join(format!("{}.hsmc", self.next_file_id));
+        self.next_file_id += 1;
+
+        // Created before the file is written, so that the file is
+        // removed by the drop if writing fails halfway
+        let spilled_value = SpilledValue {
+            path,
+            is_array,
+            #[cfg(test)]
+            size_bytes: estimate_value_size(value),
+        };
+
+        // Spilled values are reloaded transparently, so they must not
+        // lose any precision
+        let mut file = BufWriter::new(File::create(&spilled_value.path)?);
+        file.write_all(&cast_u32(meshes.len()).to_le_bytes())?;
+        for mesh in meshes {
+            let encoded = mesh_codec::encode(mesh, Precision::Lossless);
+            file.write_all(&cast_u32(encoded.len()).to_le_bytes())?;
+            file.write_all(&encoded)?;
+        }
+        file.flush()?;
+
+        Ok(spilled_value)
+    }
+}
+
+/// A value written to disk by the spill store. The file is removed
+/// once the spilled value is dropped.
+#[derive(Debug)]
+pub struct SpilledValue {
+    path: PathBuf,
+    is_array: bool,
+    #[cfg(test)]
+    size_bytes: usize,
+}
+
+impl SpilledValue {
+    /// The estimated memory footprint of the value, once reloaded.
+    #[cfg(test)]
+    pub fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
+    /// Reads the value back from its file.
+    pub fn load(&self) -> io::Result<Value> {
+        let mut file = BufReader::new(File::open(&self.path)?);
+
+        let mesh_count = read_u32(&mut file)?;
+        let mut meshes = Vec::new();
+        for _ in 0..mesh_count {
+            let mut encoded = vec![0; cast_usize(read_u32(&mut file)?)];
+            file.read_exact(&mut encoded)?;
+
+            let mesh = mesh_codec::decode(&encoded)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            meshes.push(mesh);
+        }
+
+        if self.is_array {
+            let meshes = meshes.into_iter().map(Arc::new).collect();
+            Ok(Value::MeshArray(Arc::new(MeshArrayValue::new(meshes))))
+        } else {
+            let mesh = meshes.into_iter().next().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Spilled mesh file is empty")
+            })?;
+            Ok(Value::Mesh(Arc::new(mesh)))
+        }
+    }
+}
+
+impl Drop for SpilledValue {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            log::warn!(
+                "Failed to remove spilled value file {}: {}",
+                self.path.display(),
+                err,
+            );
+        }
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buffer = [0; 4];
+    reader.read_exact(&mut buffer)?;
+
+    Ok(u32::from_le_bytes(buffer))
+}
+
+/// Estimates the memory footprint of the meshes in the value. Other
+/// values are considered too small to count against the memory
+/// budget.
+pub fn estimate_value_size(value: &Value) -> usize {
+    match value {
+        Value::Mesh(mesh) => estimate_mesh_size(mesh),
+        Value::MeshArray(mesh_array) => mesh_array.iter().map(estimate_mesh_size).sum(),
+        _ => 0,
+    }
+}
+
+/// Estimates the memory footprint of the mesh geometry and its vertex
+/// data.
+pub fn estimate_mesh_size(mesh: &Mesh) -> usize {
+    let attribute_values_count: usize = mesh
+        .vertex_attributes()
+        .iter()
+        .map(|attribute| attribute.values().len())
+        .sum();
+    let colors_count = mesh.vertex_colors().map_or(0, <[_]>::len);
+    let materials_count = mesh.face_materials().map_or(0, <[_]>::len);
+
+    mesh.faces().len() * mem::size_of::<Face>()
+        + mesh.vertices().len() * mem::size_of::<nalgebra::Point3<f32>>()
+        + mesh.vertex_normals().stored_normal_count() * mem::size_of::<nalgebra::Vector3<f32>>()
+        + attribute_values_count * mem::size_of::<f32>()
+        + colors_count * mem::size_of::<[f32; 3]>()
+        + materials_count * mem::size_of::<u32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use nalgebra::{Point3, Rotation3, Vector3};
+
+    use crate::mesh::{primitive, NormalStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_spill_store_spill_and_load_round_trip() {
+        let dir = env::temp_dir().join(format!("hurban_selector_test_spill_{}", process::id()));
+        let mut spill_store = SpillStore::new(dir, 0).expect("Failed to create spill store");
+
+        let mesh = Arc::new(primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 2.0, 3.0),
+        ));
+        let mesh_array = Arc::new(MeshArrayValue::new(vec![
+            Arc::clone(&mesh),
+            Arc::new(primitive::create_uv_sphere(
+                Point3::origin(),
+                Rotation3::identity(),
+                Vector3::new(1.0, 1.0, 1.0),
+                8,
+                8,
+                NormalStrategy::Sharp,
+            )),
+        ]));
+
+        for value in &[Value::Mesh(mesh), Value::MeshArray(mesh_array)] {
+            let spilled_value = spill_store.spill(value).expect("Failed to spill value");
+            assert_eq!(spilled_value.size_bytes(), estimate_value_size(value));
+
+            let loaded_value = spilled_value.load().expect("Failed to load spilled value");
+            assert_eq!(&loaded_value, value);
+
+            let path = spilled_value.path.clone();
+            drop(spilled_value);
+            assert!(!path.exists());
+        }
+    }
+}
@@ -3,13 +3,16 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use crate::convert::{cast_u32, cast_usize};
+use crate::curve::Curve;
+use crate::locator::Locator;
 use crate::mesh::Mesh;
+use crate::point_cloud::PointCloud;
 
 /// A type of a value.
 ///
 /// Declared as inputs and outputs in functions. Checked dynamically
 /// (and one day maybe also statically) by the interpreter.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Ty {
     Nil,
     Boolean,
@@ -21,6 +24,9 @@ pub enum Ty {
     String,
     Mesh,
     MeshArray,
+    Locator,
+    Curve,
+    Points,
 }
 
 impl fmt::Display for Ty {
@@ -36,6 +42,9 @@ impl fmt::Display for Ty {
             Ty::String => f.write_str("String"),
             Ty::Mesh => f.write_str("Mesh"),
             Ty::MeshArray => f.write_str("MeshArray"),
+            Ty::Locator => f.write_str("Locator"),
+            Ty::Curve => f.write_str("Curve"),
+            Ty::Points => f.write_str("Points"),
         }
     }
 }
@@ -53,6 +62,9 @@ pub enum Value {
     String(Arc<String>),
     Mesh(Arc<Mesh>),
     MeshArray(Arc<MeshArrayValue>),
+    Locator(Arc<Locator>),
+    Curve(Arc<Curve>),
+    Points(Arc<PointCloud>),
 }
 
 impl Value {
@@ -69,6 +81,52 @@ impl Value {
             Value::String(_) => Ty::String,
             Value::Mesh(_) => Ty::Mesh,
             Value::MeshArray(_) => Ty::MeshArray,
+            Value::Locator(_) => Ty::Locator,
+            Value::Curve(_) => Ty::Curve,
+            Value::Points(_) => Ty::Points,
+        }
+    }
+
+    /// Converts the value to the type `ty`, if there is an implicit
+    /// conversion between them: integers widen to floats and floats
+    /// are splatted into all components of vectors.
+    ///
+    /// Returns `None`, if there is no implicit conversion.
+    pub fn coerce(&self, ty: Ty) -> Option<Value> {
+        match (self, ty) {
+            (Value::Int(int), Ty::Float) => Some(Value::Float(*int as f32)),
+            (Value::Uint(uint), Ty::Float) => Some(Value::Float(*uint as f32)),
+            (Value::Float(float), Ty::Float2) => Some(Value::Float2([*float; 2])),
+            (Value::Float(float), Ty::Float3) => Some(Value::Float3([*float; 3])),
+            _ => None,
+        }
+    }
+
+    /// Splits the quads of mesh and mesh array values into triangles,
+    /// see `Mesh::triangulated`.
+    ///
+    /// Returns `None`, if the value has no quads.
+    pub fn triangulated(&self) -> Option<Value> {
+        match self {
+            Value::Mesh(mesh) if !mesh.is_triangulated() => {
+                Some(Value::Mesh(Arc::new(mesh.triangulated())))
+            }
+            Value::MeshArray(mesh_array)
+                if mesh_array.iter().any(|mesh| !mesh.is_triangulated()) =>
+            {
+                let meshes = mesh_array
+                    .iter_refcounted()
+                    .map(|mesh| {
+                        if mesh.is_triangulated() {
+                            mesh
+                        } else {
+                            Arc::new(mesh.triangulated())
+                        }
+                    })
+                    .collect();
+                Some(Value::MeshArray(Arc::new(MeshArrayValue::new(meshes))))
+            }
+            _ => None,
         }
     }
 
@@ -76,7 +134,6 @@ impl Value {
     ///
     /// # Panics
     /// This function panics when value is not a boolean.
-    #[allow(dead_code)]
     pub fn unwrap_boolean(&self) -> bool {
         match self {
             Value::Boolean(boolean) => *boolean,
@@ -183,6 +240,28 @@ impl Value {
             _ => panic!("Value not mesh array"),
         }
     }
+
+    /// Get the value if locator, otherwise panic.
+    ///
+    /// # Panics
+    /// This function panics when value is not a locator.
+    pub fn unwrap_locator(&self) -> &Locator {
+        match self {
+            Value::Locator(locator_ptr) => locator_ptr,
+            _ => panic!("Value not locator"),
+        }
+    }
+
+    /// Get the value if curve, otherwise panic.
+    ///
+    /// # Panics
+    /// This function panics when value is not a curve.
+    pub fn unwrap_curve(&self) -> &Curve {
+        match self {
+            Value::Curve(curve_ptr) => curve_ptr,
+            _ => panic!("Value not curve"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -240,6 +319,24 @@ impl fmt::Display for Value {
                 )
             }
             Value::MeshArray(mesh_array) => write!(f, "<mesh-array (size: {})>", mesh_array.len()),
+            Value::Locator(locator) => {
+                let origin = locator.origin();
+                write!(
+                    f,
+                    "<locator {} [{}, {}, {}]>",
+                    locator.name(),
+                    origin.x,
+                    origin.y,
+                    origin.z,
+                )
+            }
+            Value::Curve(curve) => write!(
+                f,
+                "<curve (polylines: {}, vertices: {})>",
+                curve.polylines().len(),
+                curve.vertex_count(),
+            ),
+            Value::Points(points) => write!(f, "<points (size: {})>", points.points().len()),
         }
     }
 }
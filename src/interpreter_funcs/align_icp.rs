@@ -0,0 +1,118 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::{analysis, tools, Mesh};
+
+pub const MAX_ITERATIONS: u32 = 100;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncAlignIcpError {
+    EmptyMesh,
+    EmptyTargetMesh,
+}
+
+impl fmt::Display for FuncAlignIcpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncAlignIcpError::EmptyMesh => write!(f, "The mesh has no vertices"),
+            FuncAlignIcpError::EmptyTargetMesh => write!(f, "The target mesh has no faces"),
+        }
+    }
+}
+
+impl error::Error for FuncAlignIcpError {}
+
+/// Registers the mesh onto the target mesh, reporting why it can't be
+/// registered and logging how well it fits.
+pub fn register(
+    mesh: &Mesh,
+    target_mesh: &Mesh,
+    iterations: u32,
+    log: &mut dyn FnMut(LogMessage),
+) -> Result<analysis::RigidRegistration, FuncError> {
+    if mesh.vertices().is_empty() {
+        return Err(FuncError::new(FuncAlignIcpError::EmptyMesh));
+    }
+    if target_mesh.faces().is_empty() {
+        return Err(FuncError::new(FuncAlignIcpError::EmptyTargetMesh));
+    }
+
+    let registration = analysis::register_rigid(mesh, target_mesh, iterations)
+        .expect("Both meshes must be checked to not be empty");
+    log(LogMessage::info(format!(
+        "Alignment deviation: {}",
+        registration.deviation,
+    )));
+
+    Ok(registration)
+}
+
+/// Rigidly moves the mesh onto the target mesh. The transformation
+/// itself is output by the Alignment Locator (ICP) func.
+pub struct FuncAlignIcp;
+
+impl Func for FuncAlignIcp {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Align (ICP)",
+            return_value_name: "Aligned Mesh",
+            category: FuncCategory::Transform,
+            tags: &["register", "match", "icp"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Target Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Iterations",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(30),
+                    min_value: Some(1),
+                    max_value: Some(MAX_ITERATIONS),
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let target_mesh = args[1].unwrap_mesh();
+        let iterations = args[2].unwrap_uint();
+
+        let registration = register(mesh, target_mesh, iterations, log)?;
+
+        let value = tools::transform_mesh(mesh, &registration.transform);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use nalgebra::Point3;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, StringParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::locator::Locator;
+
+use super::align_icp;
+
+/// Registers the mesh onto the target mesh like Align (ICP), but
+/// outputs the found transformation as a locator: the world origin and
+/// axes moved by it. Moving other meshes from a locator at the world
+/// origin to this one applies the same alignment to them, e.g. to
+/// bring the other parts of a scan along.
+pub struct FuncAlignIcpLocator;
+
+impl Func for FuncAlignIcpLocator {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Alignment Locator (ICP)",
+            return_value_name: "Alignment",
+            category: FuncCategory::Analyze,
+            tags: &["register", "match", "icp", "transform", "anchor"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Target Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Iterations",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(30),
+                    min_value: Some(1),
+                    max_value: Some(align_icp::MAX_ITERATIONS),
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Name",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "Alignment",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Locator
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let target_mesh = args[1].unwrap_mesh();
+        let iterations = args[2].unwrap_uint();
+        let name = args[3].unwrap_string();
+
+        let registration = align_icp::register(mesh, target_mesh, iterations, log)?;
+
+        Ok(Value::Locator(Arc::new(Locator::new(
+            name,
+            Point3::from(registration.transform.translation.vector),
+            registration.transform.rotation,
+        ))))
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, OrientedEdge, UnorientedEdge};
+
+/// Logs the topological characteristics of a mesh and passes it
+/// through unchanged, so that it can be inserted anywhere in the
+/// pipeline as a checkpoint.
+pub struct FuncAnalyzeMesh;
+
+impl Func for FuncAnalyzeMesh {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Analyze Mesh",
+            return_value_name: "Analyzed Mesh",
+            category: FuncCategory::Analyze,
+            tags: &[
+                "validate",
+                "manifold",
+                "watertight",
+                "holes",
+                "genus",
+                "topology",
+            ],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        let topology_info = analysis::topology_info(mesh);
+
+        let border_edges: Vec<UnorientedEdge> = analysis::border_edges(&edge_sharing)
+            .map(UnorientedEdge)
+            .collect();
+        // Non-manifold edges are reported once for each face sharing
+        // them, so they need to be deduplicated
+        let non_manifold_edge_count = analysis::non_manifold_edges(&edge_sharing)
+            .map(UnorientedEdge)
+            .collect::<HashSet<_>>()
+            .len();
+        let border_loop_count = analysis::edge_chains(border_edges)
+            .iter()
+            .filter(|chain| chain.first() == chain.last())
+            .count();
+        let isolated_vertex_count = mesh.vertices().len() - topology_info.vertex_count;
+
+        log(LogMessage::info(format!(
+            "Triangles: {}, vertices: {}, edges: {}, components: {}",
+            topology_info.face_count,
+            topology_info.vertex_count,
+            topology_info.edge_count,
+            topology_info.component_count,
+        )));
+
+        if analysis::is_mesh_watertight(&edge_sharing) {
+            log(LogMessage::info("Mesh is watertight"));
+        } else {
+            log(LogMessage::warn(format!(
+                "Mesh is not watertight, found {} border loops",
+                border_loop_count,
+            )));
+        }
+
+        if non_manifold_edge_count > 0 {
+            log(LogMessage::warn(format!(
+                "Found {} non-manifold edges",
+                non_manifold_edge_count,
+            )));
+        }
+
+        let duplicate_faces = analysis::find_duplicate_faces(mesh);
+        if !duplicate_faces.duplicate_face_indices.is_empty() {
+            log(LogMessage::warn(format!(
+                "Found {} duplicate faces",
+                duplicate_faces.duplicate_face_indices.len(),
+            )));
+        }
+        if !duplicate_faces.internal_face_indices.is_empty() {
+            log(LogMessage::warn(format!(
+                "Found {} internal faces",
+                duplicate_faces.internal_face_indices.len(),
+            )));
+        }
+
+        if isolated_vertex_count > 0 {
+            log(LogMessage::warn(format!(
+                "Found {} isolated vertices",
+                isolated_vertex_count,
+            )));
+        }
+
+        match topology_info.genus {
+            Some(genus) => log(LogMessage::info(format!("Genus: {}", genus))),
+            None => log(LogMessage::info(
+                "Genus is only known for watertight meshes",
+            )),
+        }
+
+        Ok(Value::Mesh(args[0].unwrap_refcounted_mesh()))
+    }
+}
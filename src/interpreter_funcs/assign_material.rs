@@ -0,0 +1,123 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::convert::cast_usize;
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::tools;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncAssignMaterialError {
+    FaceOutOfBounds { face: u32, face_count: usize },
+}
+
+impl fmt::Display for FuncAssignMaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FaceOutOfBounds { face, face_count } => write!(
+                f,
+                "Face {} does not exist in mesh with {} faces",
+                face, face_count,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncAssignMaterialError {}
+
+/// Assigns a material index to all faces of the mesh, or to a face
+/// and optionally its connected patch. Exporters write faces of each
+/// material as separate submeshes.
+pub struct FuncAssignMaterial;
+
+impl Func for FuncAssignMaterial {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Assign Material",
+            return_value_name: "Mesh With Material",
+            category: FuncCategory::Transform,
+            tags: &["material", "face", "submesh", "export"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Material",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(1),
+                    min_value: Some(0),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "All Faces",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Face",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(0),
+                    min_value: Some(0),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Connected Patch",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let material = args[1].unwrap_uint();
+        let all_faces = args[2].unwrap_boolean();
+        let face = args[3].unwrap_uint();
+        let connected_patch = args[4].unwrap_boolean();
+
+        let face_index = if all_faces {
+            None
+        } else {
+            Some(cast_usize(face))
+        };
+
+        match tools::assign_face_material(mesh, material, face_index, connected_patch) {
+            Some(value) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(FuncError::new(FuncAssignMaterialError::FaceOutOfBounds {
+                face,
+                face_count: mesh.faces().len(),
+            })),
+        }
+    }
+}
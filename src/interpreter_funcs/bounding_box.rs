@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use nalgebra::Rotation3;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::primitive;
+
+pub struct FuncBoundingBox;
+
+impl Func for FuncBoundingBox {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Bounding Box",
+            return_value_name: "Bounding Box",
+            category: FuncCategory::Analyze,
+            tags: &["bounds", "box", "extents"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        let bounding_box = mesh.bounding_box();
+        let dimensions = bounding_box.diagonal();
+
+        log(LogMessage::info(format!(
+            "Bounding box dimensions: {} x {} x {}, volume: {}",
+            dimensions.x,
+            dimensions.y,
+            dimensions.z,
+            dimensions.x * dimensions.y * dimensions.z,
+        )));
+
+        let value = primitive::create_box(bounding_box.center(), Rotation3::identity(), dimensions);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,71 @@
+use std::cmp;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::{smoothing, NormalStrategy};
+
+pub struct FuncCatmullClark;
+
+impl FuncCatmullClark {
+    const MAX_ITERATIONS: u32 = 3;
+}
+
+impl Func for FuncCatmullClark {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Catmull-Clark Subdivision",
+            return_value_name: "Subdivided Mesh",
+            category: FuncCategory::Transform,
+            tags: &["subdivide", "smooth", "quad"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE | FuncFlags::ACCEPTS_QUADS
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Iterations",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(1),
+                    min_value: Some(0),
+                    max_value: Some(Self::MAX_ITERATIONS),
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_refcounted_mesh();
+        let iterations = cmp::min(args[1].unwrap_uint(), Self::MAX_ITERATIONS);
+
+        if iterations == 0 {
+            return Ok(Value::Mesh(mesh));
+        }
+
+        let value = smoothing::catmull_clark_subdivision(&mesh, iterations, NormalStrategy::Smooth);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
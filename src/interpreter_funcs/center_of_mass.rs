@@ -0,0 +1,118 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Rotation3, Vector3};
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, primitive, NormalStrategy};
+
+/// Size of the center of mass marker relative to the mesh bounding box
+/// diagonal.
+const MARKER_RELATIVE_SIZE: f32 = 0.02;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCenterOfMassError {
+    NotWatertight,
+    NoVolume,
+}
+
+impl fmt::Display for FuncCenterOfMassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotWatertight => write!(f, "The mesh is not watertight"),
+            Self::NoVolume => write!(
+                f,
+                "The mesh encloses no volume, its faces may be inside out"
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncCenterOfMassError {}
+
+pub struct FuncCenterOfMass;
+
+impl Func for FuncCenterOfMass {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Center of Mass",
+            return_value_name: "Center of Mass",
+            category: FuncCategory::Analyze,
+            tags: &["mass", "centroid", "volume"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        if analysis::topology_info(mesh).genus.is_none() {
+            return Err(FuncError::new(FuncCenterOfMassError::NotWatertight));
+        }
+        let mass_properties = match analysis::compute_mass_properties(mesh) {
+            Some(mass_properties) => mass_properties,
+            None => return Err(FuncError::new(FuncCenterOfMassError::NoVolume)),
+        };
+
+        let center = mass_properties.center_of_mass;
+        let inertia = mass_properties.inertia_tensor;
+        let moments = mass_properties.principal_moments_of_inertia();
+        log(LogMessage::info(format!(
+            "Volume: {}, center of mass: [{}, {}, {}]",
+            mass_properties.volume, center.x, center.y, center.z,
+        )));
+        log(LogMessage::info(format!(
+            "Inertia tensor (unit density): [[{}, {}, {}], [{}, {}, {}], [{}, {}, {}]]",
+            inertia[(0, 0)],
+            inertia[(0, 1)],
+            inertia[(0, 2)],
+            inertia[(1, 0)],
+            inertia[(1, 1)],
+            inertia[(1, 2)],
+            inertia[(2, 0)],
+            inertia[(2, 1)],
+            inertia[(2, 2)],
+        )));
+        log(LogMessage::info(format!(
+            "Principal moments of inertia: [{}, {}, {}]",
+            moments.x, moments.y, moments.z,
+        )));
+
+        let marker_size = mesh.bounding_box().diagonal().norm() * MARKER_RELATIVE_SIZE;
+        let value = primitive::create_uv_sphere(
+            center,
+            Rotation3::identity(),
+            Vector3::repeat(marker_size),
+            8,
+            8,
+            NormalStrategy::Smooth,
+        );
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,112 @@
+use std::f32;
+use std::sync::Arc;
+
+use crate::color_ramp::{self, ColorRamp};
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, EnumParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::analysis;
+
+/// The vertex attribute holding the distances from the reference mesh.
+pub const DISTANCE_ATTRIBUTE_NAME: &str = "Distance";
+
+/// Measures how far the vertices of a mesh are from the surface of a
+/// reference mesh and colors them by the distance, e.g. to see how
+/// much smoothing or decimation deviates from the original scan.
+pub struct FuncCompareMeshes;
+
+impl Func for FuncCompareMeshes {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Compare Meshes",
+            return_value_name: "Deviation Mesh",
+            category: FuncCategory::Analyze,
+            tags: &["distance", "deviation", "difference", "color", "visualize"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Reference Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Signed",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Color Ramp",
+                refinement: ParamRefinement::Enum(EnumParamRefinement {
+                    default_value: 1,
+                    options: &ColorRamp::NAMES,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let reference = args[1].unwrap_mesh();
+        let signed = args[2].unwrap_boolean();
+        let ramp = ColorRamp::from_index(args[3].unwrap_uint()).unwrap_or(ColorRamp::Heat);
+
+        let distances = analysis::vertex_distances_to_mesh(mesh, reference, signed);
+
+        let max_deviation = distances
+            .iter()
+            .fold(0.0, |max, distance| f32::max(max, distance.abs()));
+        let mean_deviation =
+            distances.iter().map(|distance| distance.abs()).sum::<f32>() / distances.len() as f32;
+        let rms_deviation = (distances
+            .iter()
+            .map(|distance| distance * distance)
+            .sum::<f32>()
+            / distances.len() as f32)
+            .sqrt();
+
+        log(LogMessage::info(format!(
+            "Deviation max: {}, mean: {}, RMS: {}",
+            max_deviation, mean_deviation, rms_deviation,
+        )));
+
+        // Signed distances are centered around zero, so that the
+        // middle of diverging ramps marks no deviation
+        let (min, max) = if signed {
+            (-max_deviation, max_deviation)
+        } else {
+            (0.0, max_deviation)
+        };
+
+        let mut value = mesh.clone();
+        value.set_vertex_colors(color_ramp::remap_to_colors(&distances, ramp, min, max));
+        value.set_vertex_attribute(DISTANCE_ATTRIBUTE_NAME, distances);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
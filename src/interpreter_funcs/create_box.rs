@@ -3,8 +3,8 @@ use std::sync::Arc;
 use nalgebra::{Point3, Rotation3, Vector3};
 
 use crate::interpreter::{
-    Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
-    ParamRefinement, Ty, Value,
+    CancellationToken, Float3ParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, Value,
 };
 use crate::mesh::primitive;
 
@@ -15,6 +15,8 @@ impl Func for FuncCreateBox {
         &FuncInfo {
             name: "Create Box",
             return_value_name: "Box",
+            category: FuncCategory::Generate,
+            tags: &["primitive", "cube"],
         }
     }
 
@@ -36,6 +38,7 @@ impl Func for FuncCreateBox {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -51,6 +54,7 @@ impl Func for FuncCreateBox {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -66,6 +70,7 @@ impl Func for FuncCreateBox {
                     default_value_z: Some(1.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -79,6 +84,8 @@ impl Func for FuncCreateBox {
     fn call(
         &mut self,
         values: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let center = values[0].unwrap_float3();
@@ -0,0 +1,166 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Point3, Rotation3};
+
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, FloatParamRefinement, Func,
+    FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
+    UintParamRefinement, Value,
+};
+use crate::mesh::primitive;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCreateCylinderError {
+    TooFewSegments { segments_provided: u32 },
+}
+
+impl fmt::Display for FuncCreateCylinderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncCreateCylinderError::TooFewSegments { segments_provided } => write!(
+                f,
+                "Create Cylinder requires at least 3 segments, but only {} provided",
+                segments_provided,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncCreateCylinderError {}
+
+/// Creates a cylinder standing on the Z axis, optionally without caps,
+/// e.g. as a tube.
+pub struct FuncCreateCylinder;
+
+impl FuncCreateCylinder {
+    const MIN_SEGMENTS: u32 = 3;
+}
+
+impl Func for FuncCreateCylinder {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Create Cylinder",
+            return_value_name: "Cylinder",
+            tags: &["primitive", "tube", "column", "pipe"],
+            category: FuncCategory::Generate,
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Center",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Rotate (deg)",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.5),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Height",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(1.0),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Segments",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(16),
+                    min_value: Some(Self::MIN_SEGMENTS),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Caps",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let center = args[0].unwrap_float3();
+        let rotate = args[1].unwrap_float3();
+        let radius = args[2].unwrap_float();
+        let height = args[3].unwrap_float();
+        let n_segments = args[4].unwrap_uint();
+        let caps = args[5].unwrap_boolean();
+
+        if n_segments < Self::MIN_SEGMENTS {
+            return Err(FuncError::new(FuncCreateCylinderError::TooFewSegments {
+                segments_provided: n_segments,
+            }));
+        }
+
+        let value = primitive::create_cylinder(
+            Point3::from(center),
+            Rotation3::from_euler_angles(
+                rotate[0].to_radians(),
+                rotate[1].to_radians(),
+                rotate[2].to_radians(),
+            ),
+            radius,
+            height,
+            n_segments,
+            caps,
+        );
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
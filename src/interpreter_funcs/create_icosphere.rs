@@ -0,0 +1,152 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Point3, Rotation3};
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::{primitive, NormalStrategy};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCreateIcosphereError {
+    TooManySubdivisions { subdivisions_provided: u32 },
+}
+
+impl fmt::Display for FuncCreateIcosphereError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncCreateIcosphereError::TooManySubdivisions {
+                subdivisions_provided,
+            } => write!(
+                f,
+                "Create Icosphere allows at most {} subdivisions, but {} provided",
+                FuncCreateIcosphere::MAX_SUBDIVISIONS,
+                subdivisions_provided,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncCreateIcosphereError {}
+
+/// Creates a sphere from a subdivided icosahedron, whose triangles are
+/// more even than the ones of the UV sphere, e.g. for smoothing or
+/// voxelizing.
+pub struct FuncCreateIcosphere;
+
+impl FuncCreateIcosphere {
+    // Each subdivision quadruples the number of faces
+    const MAX_SUBDIVISIONS: u32 = 7;
+}
+
+impl Func for FuncCreateIcosphere {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Create Icosphere",
+            return_value_name: "Sphere",
+            tags: &["primitive", "sphere", "ball", "geodesic"],
+            category: FuncCategory::Generate,
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Center",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Rotate (deg)",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.5),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Subdivisions",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(2),
+                    min_value: Some(0),
+                    max_value: Some(Self::MAX_SUBDIVISIONS),
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let center = args[0].unwrap_float3();
+        let rotate = args[1].unwrap_float3();
+        let radius = args[2].unwrap_float();
+        let subdivisions = args[3].unwrap_uint();
+
+        if subdivisions > Self::MAX_SUBDIVISIONS {
+            return Err(FuncError::new(
+                FuncCreateIcosphereError::TooManySubdivisions {
+                    subdivisions_provided: subdivisions,
+                },
+            ));
+        }
+
+        let value = primitive::create_icosphere(
+            Point3::from(center),
+            Rotation3::from_euler_angles(
+                rotate[0].to_radians(),
+                rotate[1].to_radians(),
+                rotate[2].to_radians(),
+            ),
+            radius,
+            subdivisions,
+            NormalStrategy::Smooth,
+        );
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
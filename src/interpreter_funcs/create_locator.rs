@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use nalgebra::{Point3, UnitQuaternion};
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, StringParamRefinement, Ty, Value,
+};
+use crate::locator::Locator;
+
+/// Creates a named locator at a position, rotated by Euler angles
+/// applied in the X, Y, Z order like in Transform. Clicking a mesh in
+/// the viewport while placing locators adds this operation.
+pub struct FuncCreateLocator;
+
+impl Func for FuncCreateLocator {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Create Locator",
+            return_value_name: "Locator",
+            category: FuncCategory::Generate,
+            tags: &["anchor", "point", "marker", "reference"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Name",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "Locator",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Position",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Rotate (deg)",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Locator
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let name = args[0].unwrap_string();
+        let position = Point3::from(args[1].unwrap_float3());
+        let rotate = args[2].unwrap_float3();
+
+        let rotation = UnitQuaternion::from_euler_angles(
+            rotate[0].to_radians(),
+            rotate[1].to_radians(),
+            rotate[2].to_radians(),
+        );
+
+        Ok(Value::Locator(Arc::new(Locator::new(
+            name, position, rotation,
+        ))))
+    }
+}
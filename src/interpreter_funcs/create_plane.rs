@@ -3,8 +3,8 @@ use std::sync::Arc;
 use nalgebra::{Point3, Rotation3, Vector2, Vector3};
 
 use crate::interpreter::{
-    Float2ParamRefinement, Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo, LogMessage,
-    ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Float2ParamRefinement, Float3ParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
 };
 use crate::mesh::primitive;
 use crate::plane::Plane;
@@ -16,6 +16,8 @@ impl Func for FuncCreatePlane {
         &FuncInfo {
             name: "Create Plane",
             return_value_name: "Plane",
+            category: FuncCategory::Generate,
+            tags: &["primitive", "plane", "ground"],
         }
     }
 
@@ -37,6 +39,7 @@ impl Func for FuncCreatePlane {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -52,6 +55,7 @@ impl Func for FuncCreatePlane {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -64,6 +68,7 @@ impl Func for FuncCreatePlane {
                     default_value_y: Some(1.0),
                     min_value_y: Some(0.0),
                     max_value_y: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -77,6 +82,8 @@ impl Func for FuncCreatePlane {
     fn call(
         &mut self,
         values: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let center = values[0].unwrap_float3();
@@ -0,0 +1,179 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Point3, Rotation3};
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::{primitive, NormalStrategy};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCreateTorusError {
+    TooFewMajorSegments { segments_provided: u32 },
+    TooFewMinorSegments { segments_provided: u32 },
+}
+
+impl fmt::Display for FuncCreateTorusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncCreateTorusError::TooFewMajorSegments { segments_provided } => write!(
+                f,
+                "Create Torus requires at least 3 major segments, but only {} provided",
+                segments_provided,
+            ),
+            FuncCreateTorusError::TooFewMinorSegments { segments_provided } => write!(
+                f,
+                "Create Torus requires at least 3 minor segments, but only {} provided",
+                segments_provided,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncCreateTorusError {}
+
+/// Creates a torus lying in the XY plane. The major radius is measured
+/// to the middle of the tube, the minor radius is the one of the tube.
+pub struct FuncCreateTorus;
+
+impl FuncCreateTorus {
+    const MIN_SEGMENTS: u32 = 3;
+}
+
+impl Func for FuncCreateTorus {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Create Torus",
+            return_value_name: "Torus",
+            tags: &["primitive", "ring", "donut"],
+            category: FuncCategory::Generate,
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Center",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Rotate (deg)",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Major Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.5),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Minor Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.2),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Major Segments",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(24),
+                    min_value: Some(Self::MIN_SEGMENTS),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Minor Segments",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(12),
+                    min_value: Some(Self::MIN_SEGMENTS),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let center = args[0].unwrap_float3();
+        let rotate = args[1].unwrap_float3();
+        let major_radius = args[2].unwrap_float();
+        let minor_radius = args[3].unwrap_float();
+        let n_major_segments = args[4].unwrap_uint();
+        let n_minor_segments = args[5].unwrap_uint();
+
+        if n_major_segments < Self::MIN_SEGMENTS {
+            return Err(FuncError::new(FuncCreateTorusError::TooFewMajorSegments {
+                segments_provided: n_major_segments,
+            }));
+        }
+        if n_minor_segments < Self::MIN_SEGMENTS {
+            return Err(FuncError::new(FuncCreateTorusError::TooFewMinorSegments {
+                segments_provided: n_minor_segments,
+            }));
+        }
+
+        let value = primitive::create_torus(
+            Point3::from(center),
+            Rotation3::from_euler_angles(
+                rotate[0].to_radians(),
+                rotate[1].to_radians(),
+                rotate[2].to_radians(),
+            ),
+            major_radius,
+            minor_radius,
+            n_major_segments,
+            n_minor_segments,
+            NormalStrategy::Smooth,
+        );
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
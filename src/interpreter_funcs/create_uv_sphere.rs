@@ -5,8 +5,8 @@ use std::sync::Arc;
 use nalgebra::{Point3, Rotation3, Vector3};
 
 use crate::interpreter::{
-    Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
-    ParamRefinement, Ty, UintParamRefinement, Value,
+    CancellationToken, Float3ParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
 };
 use crate::mesh::{primitive, NormalStrategy};
 
@@ -47,6 +47,8 @@ impl Func for FuncCreateUvSphere {
         &FuncInfo {
             name: "Create UV Sphere",
             return_value_name: "Sphere",
+            category: FuncCategory::Generate,
+            tags: &["primitive", "sphere", "ball"],
         }
     }
 
@@ -68,6 +70,7 @@ impl Func for FuncCreateUvSphere {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -83,6 +86,7 @@ impl Func for FuncCreateUvSphere {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -98,6 +102,7 @@ impl Func for FuncCreateUvSphere {
                     default_value_z: Some(1.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -129,6 +134,8 @@ impl Func for FuncCreateUvSphere {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let center = args[0].unwrap_float3();
@@ -0,0 +1,116 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::bounding_box::BoundingBox;
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, Func, FuncCategory,
+    FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCropToBoxError {
+    EmptyMesh,
+}
+
+impl fmt::Display for FuncCropToBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncCropToBoxError::EmptyMesh => write!(f, "No part of the mesh is inside the box"),
+        }
+    }
+}
+
+impl error::Error for FuncCropToBoxError {}
+
+pub struct FuncCropToBox;
+
+impl Func for FuncCropToBox {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Crop to Box",
+            return_value_name: "Cropped Mesh",
+            category: FuncCategory::Transform,
+            tags: &["trim", "clip", "cut", "bounding box"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Center",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Size",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(1.0),
+                    min_value_x: Some(0.0),
+                    max_value_x: None,
+                    default_value_y: Some(1.0),
+                    min_value_y: Some(0.0),
+                    max_value_y: None,
+                    default_value_z: Some(1.0),
+                    min_value_z: Some(0.0),
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Cap",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let center = Point3::from(args[1].unwrap_float3());
+        let half_size = Vector3::from(args[2].unwrap_float3()) / 2.0;
+        let cap = args[3].unwrap_boolean();
+
+        let bounding_box = BoundingBox::new(&(center - half_size), &(center + half_size));
+        match tools::crop_to_box(mesh, &bounding_box, cap) {
+            Some(cropped) => Ok(Value::Mesh(Arc::new(cropped))),
+            None => Err(FuncError::new(FuncCropToBoxError::EmptyMesh)),
+        }
+    }
+}
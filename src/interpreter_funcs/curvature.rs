@@ -0,0 +1,109 @@
+use std::f32;
+use std::sync::Arc;
+
+use crate::color_ramp::{self, ColorRamp};
+use crate::interpreter::{
+    CancellationToken, EnumParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::curvature;
+
+/// The vertex attribute holding the estimated curvatures.
+pub const CURVATURE_ATTRIBUTE_NAME: &str = "Curvature";
+
+const CURVATURE_KIND_MEAN: u32 = 0;
+const CURVATURE_KIND_GAUSSIAN: u32 = 1;
+
+/// Estimates the curvature of the mesh at each vertex and colors the
+/// vertices by it, e.g. to see where the mesh needs smoothing or
+/// subdivision.
+pub struct FuncCurvature;
+
+impl Func for FuncCurvature {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Curvature",
+            return_value_name: "Curvature Mesh",
+            category: FuncCategory::Analyze,
+            tags: &["mean", "gaussian", "bend", "color", "visualize"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Kind",
+                refinement: ParamRefinement::Enum(EnumParamRefinement {
+                    default_value: CURVATURE_KIND_MEAN,
+                    options: &["Mean", "Gaussian"],
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Color Ramp",
+                refinement: ParamRefinement::Enum(EnumParamRefinement {
+                    default_value: 4,
+                    options: &ColorRamp::NAMES,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let kind = args[1].unwrap_uint();
+        let ramp = ColorRamp::from_index(args[2].unwrap_uint()).unwrap_or(ColorRamp::Diverging);
+
+        let vertex_curvatures = curvature::compute_vertex_curvatures(mesh);
+        let curvatures = if kind == CURVATURE_KIND_GAUSSIAN {
+            vertex_curvatures.gaussian
+        } else {
+            vertex_curvatures.mean
+        };
+
+        if let Some((min, max)) = color_ramp::compute_range(&curvatures) {
+            log(LogMessage::info(format!(
+                "Curvature min: {}, max: {}",
+                min, max
+            )));
+        }
+
+        // Curvatures are signed, so the range is centered around zero
+        // for the middle of diverging ramps to mark flat surface
+        let max_curvature = curvatures
+            .iter()
+            .filter(|curvature| curvature.is_finite())
+            .fold(0.0, |max, curvature| f32::max(max, curvature.abs()));
+
+        let mut value = mesh.clone();
+        value.set_vertex_colors(color_ramp::remap_to_colors(
+            &curvatures,
+            ramp,
+            -max_curvature,
+            max_curvature,
+        ));
+        value.set_vertex_attribute(CURVATURE_ATTRIBUTE_NAME, curvatures);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
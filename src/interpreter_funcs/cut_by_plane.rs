@@ -0,0 +1,122 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, Func, FuncCategory,
+    FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncCutByPlaneError {
+    ZeroNormal,
+    EmptyMesh,
+}
+
+impl fmt::Display for FuncCutByPlaneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncCutByPlaneError::ZeroNormal => write!(f, "The plane normal can't be zero"),
+            FuncCutByPlaneError::EmptyMesh => write!(f, "The whole mesh was cut away"),
+        }
+    }
+}
+
+impl error::Error for FuncCutByPlaneError {}
+
+/// Cuts the mesh by a plane and keeps the part behind it, i.e. the
+/// normal points towards the part cut away.
+pub struct FuncCutByPlane;
+
+impl Func for FuncCutByPlane {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Cut by Plane",
+            return_value_name: "Cut Mesh",
+            category: FuncCategory::Transform,
+            tags: &["trim", "clip", "slice", "section"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Origin",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(0.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Normal",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.0),
+                    min_value_x: None,
+                    max_value_x: None,
+                    default_value_y: Some(0.0),
+                    min_value_y: None,
+                    max_value_y: None,
+                    default_value_z: Some(1.0),
+                    min_value_z: None,
+                    max_value_z: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Cap",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let origin = Point3::from(args[1].unwrap_float3());
+        let normal = Vector3::from(args[2].unwrap_float3());
+        let cap = args[3].unwrap_boolean();
+
+        if normal == Vector3::zeros() {
+            return Err(FuncError::new(FuncCutByPlaneError::ZeroNormal));
+        }
+
+        match tools::cut_by_plane(mesh, &origin, &normal, cap) {
+            Some(cut) => Ok(Value::Mesh(Arc::new(cut))),
+            None => Err(FuncError::new(FuncCutByPlaneError::EmptyMesh)),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use nalgebra::Vector2;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, primitive};
+
+/// Deviation, relative to the bounding box diagonal, above which the
+/// mesh is reported as not symmetric.
+const SYMMETRY_TOLERANCE: f32 = 0.01;
+
+pub struct FuncDetectSymmetry;
+
+impl Func for FuncDetectSymmetry {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Detect Symmetry",
+            return_value_name: "Symmetry Plane",
+            category: FuncCategory::Analyze,
+            tags: &["mirror", "plane", "symmetry"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        // FIXME: Output the plane itself once there is a plane value
+        // type, so that it can be consumed by other funcs
+        let symmetry =
+            analysis::detect_mirror_symmetry(mesh).expect("Mesh must have at least 3 vertices");
+        let diagonal = mesh.bounding_box().diagonal().norm();
+        let origin = symmetry.plane.origin();
+        let normal = symmetry.plane.normal();
+
+        log(LogMessage::info(format!(
+            "Symmetry plane origin: [{}, {}, {}], normal: [{}, {}, {}], deviation: {}",
+            origin.x, origin.y, origin.z, normal.x, normal.y, normal.z, symmetry.deviation,
+        )));
+        if symmetry.deviation > diagonal * SYMMETRY_TOLERANCE {
+            log(LogMessage::warn(
+                "Mesh is not symmetric, the plane is only the best approximation",
+            ));
+        }
+
+        let value = primitive::create_mesh_plane(symmetry.plane, Vector2::new(diagonal, diagonal));
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, MeshArrayValue, ParamInfo, ParamRefinement,
-    Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage,
+    MeshArrayValue, ParamInfo, ParamRefinement, Ty, Value,
 };
 use crate::mesh::tools;
 
@@ -13,6 +13,8 @@ impl Func for FuncDisjointMesh {
         &FuncInfo {
             name: "Disjoint Mesh",
             return_value_name: "Disjoint Group",
+            category: FuncCategory::Transform,
+            tags: &["split", "pieces", "group"],
         }
     }
 
@@ -35,6 +37,8 @@ impl Func for FuncDisjointMesh {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
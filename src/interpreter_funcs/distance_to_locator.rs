@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use crate::color_ramp;
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+
+/// Measures the distance of each mesh vertex from the locator and
+/// stores it in a vertex attribute named after the locator, e.g. to
+/// color the mesh by how far it is from an entrance.
+pub struct FuncDistanceToLocator;
+
+impl Func for FuncDistanceToLocator {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Distance to Locator",
+            return_value_name: "Measured Mesh",
+            category: FuncCategory::Analyze,
+            tags: &["measure", "distance", "anchor", "attribute"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Locator",
+                refinement: ParamRefinement::Locator,
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let locator = args[1].unwrap_locator();
+
+        let origin = locator.origin();
+        let distances: Vec<f32> = mesh
+            .vertices()
+            .iter()
+            .map(|vertex| nalgebra::distance(vertex, &origin))
+            .collect();
+
+        if let Some((min, max)) = color_ramp::compute_range(&distances) {
+            log(LogMessage::info(format!(
+                "Distance to {} min: {}, max: {}",
+                locator.name(),
+                min,
+                max,
+            )));
+        }
+
+        let mut value = mesh.clone();
+        value.set_vertex_attribute(format!("Distance to {}", locator.name()), distances);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,122 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, rigid_body, tools};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncDropSimulationError {
+    NotWatertight,
+    NoVolume,
+}
+
+impl fmt::Display for FuncDropSimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotWatertight => write!(f, "The mesh is not watertight"),
+            Self::NoVolume => write!(
+                f,
+                "The mesh encloses no volume, its faces may be inside out"
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncDropSimulationError {}
+
+pub struct FuncDropSimulation;
+
+impl Func for FuncDropSimulation {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Drop Simulation",
+            return_value_name: "Dropped Mesh",
+            category: FuncCategory::Transform,
+            tags: &["physics", "gravity", "ground"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Drop Height",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.0),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Apply Resting Pose",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let drop_height = args[1].unwrap_float();
+        let apply_resting_pose = args[2].unwrap_boolean();
+
+        if analysis::topology_info(mesh).genus.is_none() {
+            return Err(FuncError::new(FuncDropSimulationError::NotWatertight));
+        }
+        let simulation = match rigid_body::simulate_drop(mesh, drop_height) {
+            Some(simulation) => simulation,
+            None => return Err(FuncError::new(FuncDropSimulationError::NoVolume)),
+        };
+
+        let rotation = simulation.transform.rotation;
+        let translation = simulation.transform.translation.vector;
+        if simulation.settled {
+            log(LogMessage::info(format!(
+                "Mesh came to rest after {:.2} s, rotated by {:.1}° and moved by [{}, {}, {}]",
+                simulation.time,
+                rotation.angle().to_degrees(),
+                translation.x,
+                translation.y,
+                translation.z,
+            )));
+        } else {
+            log(LogMessage::warn(format!(
+                "Mesh did not come to rest within {:.0} s, the pose may be unstable",
+                simulation.time,
+            )));
+        }
+
+        if apply_resting_pose {
+            let value = tools::transform_mesh(mesh, &simulation.transform);
+            Ok(Value::Mesh(Arc::new(value)))
+        } else {
+            Ok(Value::Mesh(args[0].unwrap_refcounted_mesh()))
+        }
+    }
+}
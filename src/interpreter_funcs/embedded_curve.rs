@@ -0,0 +1,66 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncEmbeddedCurveError {
+    Empty,
+}
+
+impl fmt::Display for FuncEmbeddedCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "No curve data embedded"),
+        }
+    }
+}
+
+impl error::Error for FuncEmbeddedCurveError {}
+
+pub struct FuncEmbeddedCurve;
+
+impl Func for FuncEmbeddedCurve {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Embedded Curve",
+            return_value_name: "Embedded Curve",
+            category: FuncCategory::Generate,
+            tags: &["embed", "curve", "polyline", "static"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Curve Data",
+            refinement: ParamRefinement::EmbeddedCurve,
+            // The data is missing until the user embeds something
+            optional: true,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Curve
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        match &args[0] {
+            Value::Curve(curve) if !curve.is_empty() => Ok(Value::Curve(Arc::clone(curve))),
+            _ => Err(FuncError::new(FuncEmbeddedCurveError::Empty)),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncEmbeddedMeshError {
+    Empty,
+}
+
+impl fmt::Display for FuncEmbeddedMeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "No mesh data embedded"),
+        }
+    }
+}
+
+impl error::Error for FuncEmbeddedMeshError {}
+
+pub struct FuncEmbeddedMesh;
+
+impl Func for FuncEmbeddedMesh {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Embedded Mesh",
+            return_value_name: "Embedded Mesh",
+            category: FuncCategory::Generate,
+            tags: &["embed", "mesh", "static"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh Data",
+            refinement: ParamRefinement::EmbeddedMesh,
+            // The data is missing until the user embeds something
+            optional: true,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        match &args[0] {
+            Value::Mesh(mesh) => Ok(Value::Mesh(Arc::clone(mesh))),
+            _ => Err(FuncError::new(FuncEmbeddedMeshError::Empty)),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::error;
+use std::fmt;
+
+use crate::exporter::{self, ExporterError};
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, StringParamRefinement, Ty, Value,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncExportAttributeCsvError {
+    AttributeNotFound(String),
+    Exporter(ExporterError),
+}
+
+impl fmt::Display for FuncExportAttributeCsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AttributeNotFound(name) => {
+                write!(f, "Mesh has no vertex attribute named \"{}\"", name)
+            }
+            Self::Exporter(exporter_error) => f.write_str(&exporter_error.to_string()),
+        }
+    }
+}
+
+impl error::Error for FuncExportAttributeCsvError {}
+
+pub struct FuncExportAttributeCsv;
+
+impl Func for FuncExportAttributeCsv {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Export Attribute CSV",
+            return_value_name: "Exported Mesh",
+            category: FuncCategory::Export,
+            tags: &["attribute", "csv", "table"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::empty()
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Path",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "",
+                    file_path: true,
+                    file_save: true,
+                    file_ext_filter: Some((&["*.csv", "*.CSV"], "Comma-separated values (.csv)")),
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Attributes",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_refcounted_mesh();
+        let path = args[1].unwrap_string();
+        let attributes = args[2].unwrap_string();
+
+        // The attributes are given as a comma-separated list of
+        // names. If none are given, all attributes are exported.
+        let attribute_names: Vec<&str> = if attributes.trim().is_empty() {
+            mesh.vertex_attributes()
+                .iter()
+                .map(|attribute| attribute.name())
+                .collect()
+        } else {
+            attributes
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect()
+        };
+
+        if let Some(missing_name) = attribute_names
+            .iter()
+            .find(|name| mesh.vertex_attribute(name).is_none())
+        {
+            return Err(FuncError::new(
+                FuncExportAttributeCsvError::AttributeNotFound(missing_name.to_string()),
+            ));
+        }
+
+        exporter::export_attributes_csv(path, &mesh, &attribute_names)
+            .map_err(|err| FuncError::new(FuncExportAttributeCsvError::Exporter(err)))?;
+
+        log(LogMessage::info(format!(
+            "Exported {} vertices with {} attribute(s) to {}",
+            mesh.vertices().len(),
+            attribute_names.len(),
+            path,
+        )));
+
+        Ok(Value::Mesh(mesh))
+    }
+}
@@ -0,0 +1,128 @@
+use std::error;
+use std::fmt;
+
+use crate::exporter::{self, ExporterError};
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, StringParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+/// The fractions of the original face count of the exported levels
+/// of detail, from the most to the least detailed.
+const LOD_FACE_RATIOS: [f32; 4] = [1.0, 0.5, 0.25, 0.1];
+
+#[derive(Debug, PartialEq)]
+pub enum FuncExportLodGltfError {
+    Exporter(ExporterError),
+}
+
+impl fmt::Display for FuncExportLodGltfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Exporter(exporter_error) => f.write_str(&exporter_error.to_string()),
+        }
+    }
+}
+
+impl error::Error for FuncExportLodGltfError {}
+
+pub struct FuncExportLodGltf;
+
+impl Func for FuncExportLodGltf {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Export LODs glTF",
+            return_value_name: "Exported Mesh",
+            category: FuncCategory::Export,
+            tags: &["gltf", "lod", "level of detail"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::empty()
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Path",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "",
+                    file_path: true,
+                    file_save: true,
+                    file_ext_filter: Some((&["*.glb", "*.GLB"], "Binary glTF (.glb)")),
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Name",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "Mesh",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_refcounted_mesh();
+        let path = args[1].unwrap_string();
+        let name = args[2].unwrap_string().trim();
+        let name = if name.is_empty() { "Mesh" } else { name };
+
+        // Levels of detail are named with the suffix recognized by
+        // common real-time engines
+        let mut lods = Vec::with_capacity(LOD_FACE_RATIOS.len());
+        for (lod_index, face_ratio) in LOD_FACE_RATIOS.iter().enumerate() {
+            match tools::decimate(&mesh, *face_ratio) {
+                Some(lod_mesh) => {
+                    lods.push((format!("{}_LOD{}", name, lod_index), lod_mesh));
+                }
+                None => log(LogMessage::warn(format!(
+                    "Level of detail {} ({}%) has no faces and was skipped",
+                    lod_index,
+                    face_ratio * 100.0,
+                ))),
+            }
+        }
+
+        let named_lods: Vec<_> = lods
+            .iter()
+            .map(|(lod_name, lod_mesh)| (lod_name.clone(), lod_mesh))
+            .collect();
+        exporter::export_glb(path, &named_lods)
+            .map_err(|err| FuncError::new(FuncExportLodGltfError::Exporter(err)))?;
+
+        let face_counts: Vec<_> = lods
+            .iter()
+            .map(|(_, lod_mesh)| lod_mesh.faces().len().to_string())
+            .collect();
+        log(LogMessage::info(format!(
+            "Exported {} levels of detail with {} faces to {}",
+            lods.len(),
+            face_counts.join(", "),
+            path,
+        )));
+
+        Ok(Value::Mesh(mesh))
+    }
+}
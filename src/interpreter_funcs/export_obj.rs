@@ -0,0 +1,87 @@
+use std::error;
+use std::fmt;
+
+use crate::exporter::{self, ExporterError};
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, StringParamRefinement, Ty, Value,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncExportObjError {
+    Exporter(ExporterError),
+}
+
+impl fmt::Display for FuncExportObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Exporter(exporter_error) => f.write_str(&exporter_error.to_string()),
+        }
+    }
+}
+
+impl error::Error for FuncExportObjError {}
+
+pub struct FuncExportObj;
+
+impl Func for FuncExportObj {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Export OBJ",
+            return_value_name: "Exported Mesh",
+            category: FuncCategory::Export,
+            tags: &["obj", "file", "save"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::ACCEPTS_QUADS
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Path",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "",
+                    file_path: true,
+                    file_save: true,
+                    file_ext_filter: Some((&["*.obj", "*.OBJ"], "Wavefront (.obj)")),
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_refcounted_mesh();
+        let path = args[1].unwrap_string();
+
+        exporter::export_obj(path, &mesh)
+            .map_err(|err| FuncError::new(FuncExportObjError::Exporter(err)))?;
+
+        log(LogMessage::info(format!(
+            "Exported {} vertices and {} faces to {}",
+            mesh.vertices().len(),
+            mesh.faces().len(),
+            path,
+        )));
+
+        Ok(Value::Mesh(mesh))
+    }
+}
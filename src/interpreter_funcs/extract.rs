@@ -2,8 +2,8 @@ use std::error;
 use std::fmt;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
-    UintParamRefinement, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, UintParamRefinement, Value,
 };
 
 #[derive(Debug, PartialEq)]
@@ -28,6 +28,8 @@ impl Func for FuncExtract {
         &FuncInfo {
             name: "Extract from Group",
             return_value_name: "Extracted Mesh",
+            category: FuncCategory::Transform,
+            tags: &["group", "select", "pick"],
         }
     }
 
@@ -61,6 +63,8 @@ impl Func for FuncExtract {
     fn call(
         &mut self,
         values: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh_array = values[0].unwrap_mesh_array();
@@ -2,7 +2,8 @@ use std::error;
 use std::fmt;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
 };
 
 #[derive(Debug, PartialEq)]
@@ -27,6 +28,8 @@ impl Func for FuncExtractLargest {
         &FuncInfo {
             name: "Extract Largest",
             return_value_name: "Extracted Mesh",
+            category: FuncCategory::Transform,
+            tags: &["group", "pieces", "select"],
         }
     }
 
@@ -49,6 +52,8 @@ impl Func for FuncExtractLargest {
     fn call(
         &mut self,
         values: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh_array = values[0].unwrap_mesh_array();
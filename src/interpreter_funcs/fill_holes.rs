@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use crate::convert::cast_usize;
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::tools;
+
+pub struct FuncFillHoles;
+
+impl Func for FuncFillHoles {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Fill Holes",
+            return_value_name: "Filled Mesh",
+            category: FuncCategory::Transform,
+            tags: &["holes", "watertight", "repair", "close", "scan"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Max Hole Edges",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(100),
+                    min_value: Some(3),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Smooth Fill",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let max_hole_edges = args[1].unwrap_uint();
+        let smooth = args[2].unwrap_boolean();
+
+        let (filled, filled_hole_count) =
+            tools::fill_holes(mesh, cast_usize(max_hole_edges), smooth);
+        log(LogMessage::info(format!(
+            "Filled {} holes",
+            filled_hole_count
+        )));
+
+        Ok(Value::Mesh(Arc::new(filled)))
+    }
+}
@@ -0,0 +1,64 @@
+use crate::interpreter::{
+    ast, BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags,
+    FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+
+pub struct FuncGate;
+
+impl Func for FuncGate {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Gate",
+            return_value_name: "Gated Mesh",
+            category: FuncCategory::Flow,
+            tags: &["enable", "disable", "bypass"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Open",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn arg_demanded(&self, arg_index: usize, args: &[ast::Expr]) -> bool {
+        match &args[1] {
+            ast::Expr::Lit(ast::LitExpr::Boolean(open)) => arg_index == 1 || *open,
+            _ => true,
+        }
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        if args[1].unwrap_boolean() {
+            Ok(args[0].clone())
+        } else {
+            log(LogMessage::info("Gate is closed"));
+            Ok(Value::Nil)
+        }
+    }
+}
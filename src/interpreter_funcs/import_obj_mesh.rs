@@ -4,8 +4,8 @@ use std::sync::Arc;
 
 use crate::importer::{Importer, ImporterError, ObjCache};
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, MeshArrayValue, ParamInfo, ParamRefinement,
-    StringParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage,
+    MeshArrayValue, ParamInfo, ParamRefinement, StringParamRefinement, Ty, Value,
 };
 
 #[derive(Debug, PartialEq)]
@@ -40,6 +40,8 @@ impl<C: ObjCache> Func for FuncImportObjMesh<C> {
         &FuncInfo {
             name: "Import OBJ as Group",
             return_value_name: "Imported Group",
+            category: FuncCategory::Generate,
+            tags: &["import", "obj", "file", "scan"],
         }
     }
 
@@ -53,6 +55,7 @@ impl<C: ObjCache> Func for FuncImportObjMesh<C> {
             refinement: ParamRefinement::String(StringParamRefinement {
                 default_value: "",
                 file_path: true,
+                file_save: false,
                 file_ext_filter: Some((&["*.obj", "*.OBJ"], "Wavefront (.obj)")),
             }),
             optional: false,
@@ -66,6 +69,8 @@ impl<C: ObjCache> Func for FuncImportObjMesh<C> {
     fn call(
         &mut self,
         values: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let path = values[0].unwrap_string();
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
 };
 use crate::mesh::tools;
 
@@ -12,6 +13,8 @@ impl Func for FuncJoinGroup {
         &FuncInfo {
             name: "Join Group",
             return_value_name: "Joined Mesh",
+            category: FuncCategory::Transform,
+            tags: &["group", "merge", "combine"],
         }
     }
 
@@ -34,6 +37,8 @@ impl Func for FuncJoinGroup {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh_arc_array = args[0].unwrap_mesh_array();
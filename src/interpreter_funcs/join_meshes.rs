@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
 };
 use crate::mesh::tools;
 
@@ -12,11 +13,13 @@ impl Func for FuncJoinMeshes {
         &FuncInfo {
             name: "Join Meshes",
             return_value_name: "Joined Mesh",
+            category: FuncCategory::Transform,
+            tags: &["merge", "combine"],
         }
     }
 
     fn flags(&self) -> FuncFlags {
-        FuncFlags::PURE
+        FuncFlags::PURE | FuncFlags::ACCEPTS_QUADS
     }
 
     fn param_info(&self) -> &[ParamInfo] {
@@ -41,6 +44,8 @@ impl Func for FuncJoinMeshes {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let meshes = args.iter().map(|a| a.unwrap_mesh());
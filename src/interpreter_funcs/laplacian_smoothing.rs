@@ -2,10 +2,10 @@ use std::cmp;
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
-    UintParamRefinement, Value,
+    BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
 };
-use crate::mesh::{smoothing, topology, NormalStrategy};
+use crate::mesh::{analysis, smoothing, topology, NormalStrategy, OrientedEdge};
 
 pub struct FuncLaplacianSmoothing;
 
@@ -14,6 +14,8 @@ impl Func for FuncLaplacianSmoothing {
         &FuncInfo {
             name: "Relax",
             return_value_name: "Relaxed Mesh",
+            category: FuncCategory::Transform,
+            tags: &["smooth", "laplacian", "fair"],
         }
     }
 
@@ -37,6 +39,13 @@ impl Func for FuncLaplacianSmoothing {
                 }),
                 optional: false,
             },
+            ParamInfo {
+                name: "Keep Borders Fixed",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
         ]
     }
 
@@ -47,21 +56,47 @@ impl Func for FuncLaplacianSmoothing {
     fn call(
         &mut self,
         args: &[Value],
+        cancellation_token: &CancellationToken,
+        report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
-        let iterations = args[1].unwrap_uint();
+        let iterations = cmp::min(255, args[1].unwrap_uint());
+        let keep_borders_fixed = args[2].unwrap_boolean();
 
         let v2v = topology::compute_vertex_to_vertex_topology(mesh);
 
-        let (value, _, _) = smoothing::laplacian_smoothing(
+        // Anchoring the borders keeps open meshes, e.g. scans, from
+        // shrinking away from their open edges
+        let fixed_vertex_indices: Vec<u32> = if keep_borders_fixed {
+            let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+            let edge_sharing = analysis::edge_sharing(&oriented_edges);
+            let mut border_vertex_indices: Vec<u32> =
+                analysis::border_vertex_indices(&edge_sharing)
+                    .into_iter()
+                    .collect();
+            border_vertex_indices.sort();
+            border_vertex_indices
+        } else {
+            Vec::new()
+        };
+
+        match smoothing::laplacian_smoothing_cancellable(
             mesh,
             &v2v,
-            cmp::min(255, iterations),
-            &[],
+            iterations,
+            &fixed_vertex_indices,
             false,
             NormalStrategy::Smooth,
-        );
-        Ok(Value::Mesh(Arc::new(value)))
+            |iteration| {
+                report_progress(iteration as f32 / iterations as f32);
+                cancellation_token.is_cancelled()
+            },
+        ) {
+            Some((value, _, _)) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(cancellation_token
+                .check()
+                .expect_err("Smoothing must only stop early when cancelled")),
+        }
     }
 }
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use nalgebra::{Point3, UnitQuaternion};
+
+use crate::interpreter::{
+    CancellationToken, EnumParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, StringParamRefinement, Ty, Value,
+};
+use crate::locator::Locator;
+
+const POINT_CENTROID: u32 = 0;
+const POINT_BOUNDING_BOX_CENTER: u32 = 1;
+const POINT_BOUNDING_BOX_BOTTOM: u32 = 2;
+const POINT_BOUNDING_BOX_MIN: u32 = 3;
+const POINT_BOUNDING_BOX_MAX: u32 = 4;
+
+/// Creates a named locator at a point derived from the mesh, aligned
+/// with the world axes, e.g. to move the mesh by it or to measure from
+/// it.
+pub struct FuncLocatorFromMesh;
+
+impl Func for FuncLocatorFromMesh {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Locator from Mesh",
+            return_value_name: "Locator",
+            category: FuncCategory::Analyze,
+            tags: &["anchor", "centroid", "bounding box", "corner", "reference"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Point",
+                refinement: ParamRefinement::Enum(EnumParamRefinement {
+                    default_value: POINT_CENTROID,
+                    options: &[
+                        "Centroid",
+                        "Bounding Box Center",
+                        "Bounding Box Bottom",
+                        "Bounding Box Min Corner",
+                        "Bounding Box Max Corner",
+                    ],
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Name",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "Locator",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Locator
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let point = args[1].unwrap_uint();
+        let name = args[2].unwrap_string();
+
+        let bounding_box = mesh.bounding_box();
+        let origin = match point {
+            POINT_BOUNDING_BOX_CENTER => bounding_box.center(),
+            POINT_BOUNDING_BOX_BOTTOM => {
+                let center = bounding_box.center();
+                Point3::new(center.x, center.y, bounding_box.minimum_point().z)
+            }
+            POINT_BOUNDING_BOX_MIN => bounding_box.minimum_point(),
+            POINT_BOUNDING_BOX_MAX => bounding_box.maximum_point(),
+            _ => {
+                let vertices = mesh.vertices();
+                let sum = vertices
+                    .iter()
+                    .fold(Point3::origin(), |sum, vertex| sum + vertex.coords);
+                sum / vertices.len() as f32
+            }
+        };
+
+        Ok(Value::Locator(Arc::new(Locator::new(
+            name,
+            origin,
+            UnitQuaternion::identity(),
+        ))))
+    }
+}
@@ -4,8 +4,8 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
-    UintParamRefinement, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, UintParamRefinement, Value,
 };
 use crate::mesh::{smoothing, topology, NormalStrategy};
 
@@ -38,6 +38,8 @@ impl Func for FuncLoopSubdivision {
         &FuncInfo {
             name: "Loop Subdivision",
             return_value_name: "Subdivided Mesh",
+            category: FuncCategory::Transform,
+            tags: &["subdivide", "smooth", "triangle"],
         }
     }
 
@@ -71,6 +73,8 @@ impl Func for FuncLoopSubdivision {
     fn call(
         &mut self,
         args: &[Value],
+        cancellation_token: &CancellationToken,
+        report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_refcounted_mesh();
@@ -80,16 +84,25 @@ impl Func for FuncLoopSubdivision {
             return Ok(Value::Mesh(mesh));
         }
 
+        // Each iteration quadruples the face count, and so roughly the
+        // work of the next iteration
+        let total_work = 4f32.powi(iterations as i32) - 1.0;
+        let mut report_iteration = |iteration: u32| {
+            report_progress((4f32.powi(iteration as i32) - 1.0) / total_work);
+        };
+
         let mut v2v = topology::compute_vertex_to_vertex_topology(&mesh);
-        let mut v2f = topology::compute_vertex_to_face_topology(&mesh);
-        let mut f2f = topology::compute_face_to_face_topology(&mesh, &v2f);
+        let mut e2f = topology::compute_edge_to_face_topology(&mesh);
+        let mut f2f = topology::compute_face_to_face_topology(&mesh, &e2f);
         if let Some(mut current_mesh) =
             smoothing::loop_subdivision(&mesh, &v2v, &f2f, NormalStrategy::Smooth)
         {
-            for _ in 1..iterations {
+            report_iteration(1);
+            for iteration in 1..iterations {
+                cancellation_token.check()?;
                 v2v = topology::compute_vertex_to_vertex_topology(&current_mesh);
-                v2f = topology::compute_vertex_to_face_topology(&current_mesh);
-                f2f = topology::compute_face_to_face_topology(&current_mesh, &v2f);
+                e2f = topology::compute_edge_to_face_topology(&current_mesh);
+                f2f = topology::compute_face_to_face_topology(&current_mesh, &e2f);
                 current_mesh = match smoothing::loop_subdivision(
                     &current_mesh,
                     &v2v,
@@ -98,7 +111,8 @@ impl Func for FuncLoopSubdivision {
                 ) {
                     Some(m) => m,
                     None => return Err(FuncError::new(FuncLoopSubdivisionError::InvalidMesh)),
-                }
+                };
+                report_iteration(iteration + 1);
             }
             Ok(Value::Mesh(Arc::new(current_mesh)))
         } else {
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::point_cloud::PointCloud;
+
+/// Converts a mesh to the points of its vertices, dropping its faces.
+pub struct FuncMeshToPoints;
+
+impl Func for FuncMeshToPoints {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Mesh to Points",
+            return_value_name: "Points",
+            category: FuncCategory::Transform,
+            tags: &["convert", "vertices", "point cloud"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Points
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let points = PointCloud::new(mesh.vertices().to_vec());
+
+        Ok(Value::Points(Arc::new(points)))
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+/// Mirrors the mesh by the plane spanned by the X and Y axes of the
+/// locator, optionally joined with the original to complete symmetric
+/// designs.
+pub struct FuncMirrorByLocator;
+
+impl Func for FuncMirrorByLocator {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Mirror by Locator",
+            return_value_name: "Mirrored Mesh",
+            category: FuncCategory::Transform,
+            tags: &["reflect", "symmetry", "plane", "anchor"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Mirror Plane",
+                refinement: ParamRefinement::Locator,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Keep Original",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let locator = args[1].unwrap_locator();
+        let keep_original = args[2].unwrap_boolean();
+
+        let mirrored = tools::mirror_mesh(mesh, &locator.to_plane());
+        let value = if keep_original {
+            tools::join_multiple_meshes(&[mesh.clone(), mirrored])
+        } else {
+            mirrored
+        };
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
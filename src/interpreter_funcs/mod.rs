@@ -3,20 +3,66 @@ use std::collections::BTreeMap;
 use crate::importer::{EndlessCache, Importer};
 use crate::interpreter::{Func, FuncIdent};
 
+use self::align_icp::FuncAlignIcp;
+use self::align_icp_locator::FuncAlignIcpLocator;
+use self::analyze_mesh::FuncAnalyzeMesh;
+use self::assign_material::FuncAssignMaterial;
+use self::bounding_box::FuncBoundingBox;
+use self::catmull_clark::FuncCatmullClark;
+use self::center_of_mass::FuncCenterOfMass;
+use self::compare_meshes::FuncCompareMeshes;
 use self::create_box::FuncCreateBox;
+use self::create_cone::FuncCreateCone;
+use self::create_cylinder::FuncCreateCylinder;
+use self::create_icosphere::FuncCreateIcosphere;
+use self::create_locator::FuncCreateLocator;
 use self::create_plane::FuncCreatePlane;
+use self::create_torus::FuncCreateTorus;
 use self::create_uv_sphere::FuncCreateUvSphere;
+use self::crop_to_box::FuncCropToBox;
+use self::curvature::FuncCurvature;
+use self::cut_by_plane::FuncCutByPlane;
+use self::detect_symmetry::FuncDetectSymmetry;
 use self::disjoint_mesh::FuncDisjointMesh;
+use self::distance_to_locator::FuncDistanceToLocator;
+use self::drop_simulation::FuncDropSimulation;
+use self::embedded_curve::FuncEmbeddedCurve;
+use self::embedded_mesh::FuncEmbeddedMesh;
+use self::export_attribute_csv::FuncExportAttributeCsv;
+use self::export_lod_gltf::FuncExportLodGltf;
+use self::export_obj::FuncExportObj;
 use self::extract::FuncExtract;
 use self::extract_largest::FuncExtractLargest;
+use self::fill_holes::FuncFillHoles;
+use self::gate::FuncGate;
 use self::import_obj_mesh::FuncImportObjMesh;
 use self::join_group::FuncJoinGroup;
 use self::join_meshes::FuncJoinMeshes;
 use self::laplacian_smoothing::FuncLaplacianSmoothing;
+use self::locator_from_mesh::FuncLocatorFromMesh;
 use self::loop_subdivision::FuncLoopSubdivision;
+use self::mesh_to_points::FuncMeshToPoints;
+use self::mirror_by_locator::FuncMirrorByLocator;
+use self::move_to_locator::FuncMoveToLocator;
+use self::offset_mesh::FuncOffsetMesh;
+use self::orient_to_ground::FuncOrientToGround;
+use self::oriented_bounding_box::FuncOrientedBoundingBox;
+use self::pipe_curve::FuncPipeCurve;
+use self::recompute_normals::FuncRecomputeNormals;
+use self::relax_on_surface::FuncRelaxOnSurface;
+use self::remap_attribute_to_color::FuncRemapAttributeToColor;
+use self::repair_mesh::FuncRepairMesh;
+use self::repeat::FuncRepeat;
+use self::repeat_index::FuncRepeatIndex;
+use self::repeat_start::FuncRepeatStart;
+use self::report_open_edges::FuncReportOpenEdges;
+use self::revert_face::FuncRevertFace;
 use self::revert_mesh_faces::FuncRevertMeshFaces;
 use self::shrink_wrap::FuncShrinkWrap;
+use self::smooth_blend::FuncSmoothBlend;
+use self::switch::FuncSwitch;
 use self::synchronize_mesh_faces::FuncSynchronizeMeshFaces;
+use self::transfer_attributes::FuncTransferAttributes;
 use self::transform::FuncTransform;
 use self::voxel_boolean_difference::FuncBooleanDifference;
 use self::voxel_boolean_intersection::FuncBooleanIntersection;
@@ -24,20 +70,66 @@ use self::voxel_boolean_union::FuncBooleanUnion;
 use self::voxelize::FuncVoxelize;
 use self::weld::FuncWeld;
 
+mod align_icp;
+mod align_icp_locator;
+mod analyze_mesh;
+mod assign_material;
+mod bounding_box;
+mod catmull_clark;
+mod center_of_mass;
+mod compare_meshes;
 mod create_box;
+mod create_cone;
+mod create_cylinder;
+mod create_icosphere;
+mod create_locator;
 mod create_plane;
+mod create_torus;
 mod create_uv_sphere;
+mod crop_to_box;
+mod curvature;
+mod cut_by_plane;
+mod detect_symmetry;
 mod disjoint_mesh;
+mod distance_to_locator;
+mod drop_simulation;
+mod embedded_curve;
+mod embedded_mesh;
+mod export_attribute_csv;
+mod export_lod_gltf;
+mod export_obj;
 mod extract;
 mod extract_largest;
+mod fill_holes;
+mod gate;
 mod import_obj_mesh;
 mod join_group;
 mod join_meshes;
 mod laplacian_smoothing;
+mod locator_from_mesh;
 mod loop_subdivision;
+mod mesh_to_points;
+mod mirror_by_locator;
+mod move_to_locator;
+mod offset_mesh;
+mod orient_to_ground;
+mod oriented_bounding_box;
+mod pipe_curve;
+mod recompute_normals;
+mod relax_on_surface;
+mod remap_attribute_to_color;
+mod repair_mesh;
+mod repeat;
+mod repeat_index;
+mod repeat_start;
+mod report_open_edges;
+mod revert_face;
 mod revert_mesh_faces;
 mod shrink_wrap;
+mod smooth_blend;
+mod switch;
 mod synchronize_mesh_faces;
+mod transfer_attributes;
 mod transform;
 mod voxel_boolean_difference;
 mod voxel_boolean_intersection;
@@ -59,13 +151,30 @@ pub const FUNC_ID_EXTRACT_LARGEST: FuncIdent = FuncIdent(2);
 pub const FUNC_ID_CREATE_UV_SPHERE: FuncIdent = FuncIdent(1000);
 pub const FUNC_ID_CREATE_PLANE: FuncIdent = FuncIdent(1001);
 pub const FUNC_ID_CREATE_BOX: FuncIdent = FuncIdent(1002);
+pub const FUNC_ID_EMBEDDED_MESH: FuncIdent = FuncIdent(1003);
+pub const FUNC_ID_CREATE_ICOSPHERE: FuncIdent = FuncIdent(1004);
+pub const FUNC_ID_CREATE_CYLINDER: FuncIdent = FuncIdent(1005);
+pub const FUNC_ID_CREATE_CONE: FuncIdent = FuncIdent(1006);
+pub const FUNC_ID_CREATE_TORUS: FuncIdent = FuncIdent(1007);
+pub const FUNC_ID_EMBEDDED_CURVE: FuncIdent = FuncIdent(1008);
 
 // Import/Export funcs
 pub const FUNC_ID_IMPORT_OBJ_MESH: FuncIdent = FuncIdent(2000);
+pub const FUNC_ID_EXPORT_ATTRIBUTE_CSV: FuncIdent = FuncIdent(2001);
+pub const FUNC_ID_EXPORT_LOD_GLTF: FuncIdent = FuncIdent(2002);
+pub const FUNC_ID_EXPORT_OBJ: FuncIdent = FuncIdent(2003);
 
 // Smoothing funcs
 pub const FUNC_ID_LAPLACIAN_SMOOTHING: FuncIdent = FuncIdent(3000);
 pub const FUNC_ID_LOOP_SUBDIVISION: FuncIdent = FuncIdent(3001);
+pub const FUNC_ID_CATMULL_CLARK: FuncIdent = FuncIdent(3002);
+
+// Flow funcs
+pub const FUNC_ID_SWITCH: FuncIdent = FuncIdent(4000);
+pub const FUNC_ID_GATE: FuncIdent = FuncIdent(4001);
+pub const FUNC_ID_REPEAT_START: FuncIdent = FuncIdent(4002);
+pub const FUNC_ID_REPEAT: FuncIdent = FuncIdent(4003);
+pub const FUNC_ID_REPEAT_INDEX: FuncIdent = FuncIdent(4004);
 
 // Tool funcs
 pub const FUNC_ID_SHRINK_WRAP: FuncIdent = FuncIdent(9000);
@@ -79,6 +188,37 @@ pub const FUNC_ID_VOXELIZE: FuncIdent = FuncIdent(9007);
 pub const FUNC_ID_BOOLEAN_INTERSECTION: FuncIdent = FuncIdent(9008);
 pub const FUNC_ID_BOOLEAN_DIFFERENCE: FuncIdent = FuncIdent(9009);
 pub const FUNC_ID_BOOLEAN_UNION: FuncIdent = FuncIdent(9010);
+pub const FUNC_ID_TRANSFER_ATTRIBUTES: FuncIdent = FuncIdent(9011);
+pub const FUNC_ID_REMAP_ATTRIBUTE_TO_COLOR: FuncIdent = FuncIdent(9012);
+pub const FUNC_ID_REVERT_FACE: FuncIdent = FuncIdent(9013);
+pub const FUNC_ID_REPORT_OPEN_EDGES: FuncIdent = FuncIdent(9014);
+pub const FUNC_ID_DETECT_SYMMETRY: FuncIdent = FuncIdent(9015);
+pub const FUNC_ID_ALIGN_ICP: FuncIdent = FuncIdent(9016);
+pub const FUNC_ID_OFFSET_MESH: FuncIdent = FuncIdent(9017);
+pub const FUNC_ID_SMOOTH_BLEND: FuncIdent = FuncIdent(9018);
+pub const FUNC_ID_ORIENT_TO_GROUND: FuncIdent = FuncIdent(9019);
+pub const FUNC_ID_BOUNDING_BOX: FuncIdent = FuncIdent(9020);
+pub const FUNC_ID_ORIENTED_BOUNDING_BOX: FuncIdent = FuncIdent(9021);
+pub const FUNC_ID_CENTER_OF_MASS: FuncIdent = FuncIdent(9022);
+pub const FUNC_ID_DROP_SIMULATION: FuncIdent = FuncIdent(9023);
+pub const FUNC_ID_ANALYZE_MESH: FuncIdent = FuncIdent(9024);
+pub const FUNC_ID_FILL_HOLES: FuncIdent = FuncIdent(9025);
+pub const FUNC_ID_CUT_BY_PLANE: FuncIdent = FuncIdent(9026);
+pub const FUNC_ID_CROP_TO_BOX: FuncIdent = FuncIdent(9027);
+pub const FUNC_ID_REPAIR_MESH: FuncIdent = FuncIdent(9028);
+pub const FUNC_ID_RECOMPUTE_NORMALS: FuncIdent = FuncIdent(9029);
+pub const FUNC_ID_RELAX_ON_SURFACE: FuncIdent = FuncIdent(9030);
+pub const FUNC_ID_COMPARE_MESHES: FuncIdent = FuncIdent(9031);
+pub const FUNC_ID_CURVATURE: FuncIdent = FuncIdent(9032);
+pub const FUNC_ID_ASSIGN_MATERIAL: FuncIdent = FuncIdent(9033);
+pub const FUNC_ID_CREATE_LOCATOR: FuncIdent = FuncIdent(9034);
+pub const FUNC_ID_LOCATOR_FROM_MESH: FuncIdent = FuncIdent(9035);
+pub const FUNC_ID_MOVE_TO_LOCATOR: FuncIdent = FuncIdent(9036);
+pub const FUNC_ID_MIRROR_BY_LOCATOR: FuncIdent = FuncIdent(9037);
+pub const FUNC_ID_DISTANCE_TO_LOCATOR: FuncIdent = FuncIdent(9038);
+pub const FUNC_ID_ALIGN_ICP_LOCATOR: FuncIdent = FuncIdent(9039);
+pub const FUNC_ID_MESH_TO_POINTS: FuncIdent = FuncIdent(9040);
+pub const FUNC_ID_PIPE_CURVE: FuncIdent = FuncIdent(9041);
 
 /// Returns the global set of function definitions available to the
 /// editor.
@@ -98,6 +238,12 @@ pub fn create_function_table() -> BTreeMap<FuncIdent, Box<dyn Func>> {
     funcs.insert(FUNC_ID_CREATE_UV_SPHERE, Box::new(FuncCreateUvSphere));
     funcs.insert(FUNC_ID_CREATE_PLANE, Box::new(FuncCreatePlane));
     funcs.insert(FUNC_ID_CREATE_BOX, Box::new(FuncCreateBox));
+    funcs.insert(FUNC_ID_EMBEDDED_MESH, Box::new(FuncEmbeddedMesh));
+    funcs.insert(FUNC_ID_CREATE_ICOSPHERE, Box::new(FuncCreateIcosphere));
+    funcs.insert(FUNC_ID_CREATE_CYLINDER, Box::new(FuncCreateCylinder));
+    funcs.insert(FUNC_ID_CREATE_CONE, Box::new(FuncCreateCone));
+    funcs.insert(FUNC_ID_CREATE_TORUS, Box::new(FuncCreateTorus));
+    funcs.insert(FUNC_ID_EMBEDDED_CURVE, Box::new(FuncEmbeddedCurve));
 
     // Import/Export funcs
     funcs.insert(
@@ -106,6 +252,12 @@ pub fn create_function_table() -> BTreeMap<FuncIdent, Box<dyn Func>> {
             EndlessCache::default(),
         ))),
     );
+    funcs.insert(
+        FUNC_ID_EXPORT_ATTRIBUTE_CSV,
+        Box::new(FuncExportAttributeCsv),
+    );
+    funcs.insert(FUNC_ID_EXPORT_LOD_GLTF, Box::new(FuncExportLodGltf));
+    funcs.insert(FUNC_ID_EXPORT_OBJ, Box::new(FuncExportObj));
 
     // Smoothing funcs
     funcs.insert(
@@ -113,6 +265,14 @@ pub fn create_function_table() -> BTreeMap<FuncIdent, Box<dyn Func>> {
         Box::new(FuncLaplacianSmoothing),
     );
     funcs.insert(FUNC_ID_LOOP_SUBDIVISION, Box::new(FuncLoopSubdivision));
+    funcs.insert(FUNC_ID_CATMULL_CLARK, Box::new(FuncCatmullClark));
+
+    // Flow funcs
+    funcs.insert(FUNC_ID_SWITCH, Box::new(FuncSwitch));
+    funcs.insert(FUNC_ID_GATE, Box::new(FuncGate));
+    funcs.insert(FUNC_ID_REPEAT_START, Box::new(FuncRepeatStart));
+    funcs.insert(FUNC_ID_REPEAT, Box::new(FuncRepeat));
+    funcs.insert(FUNC_ID_REPEAT_INDEX, Box::new(FuncRepeatIndex));
 
     // Tool funcs
     funcs.insert(FUNC_ID_SHRINK_WRAP, Box::new(FuncShrinkWrap));
@@ -132,6 +292,46 @@ pub fn create_function_table() -> BTreeMap<FuncIdent, Box<dyn Func>> {
     );
     funcs.insert(FUNC_ID_BOOLEAN_DIFFERENCE, Box::new(FuncBooleanDifference));
     funcs.insert(FUNC_ID_BOOLEAN_UNION, Box::new(FuncBooleanUnion));
+    funcs.insert(
+        FUNC_ID_TRANSFER_ATTRIBUTES,
+        Box::new(FuncTransferAttributes),
+    );
+    funcs.insert(
+        FUNC_ID_REMAP_ATTRIBUTE_TO_COLOR,
+        Box::new(FuncRemapAttributeToColor),
+    );
+    funcs.insert(FUNC_ID_REVERT_FACE, Box::new(FuncRevertFace));
+    funcs.insert(FUNC_ID_REPORT_OPEN_EDGES, Box::new(FuncReportOpenEdges));
+    funcs.insert(FUNC_ID_DETECT_SYMMETRY, Box::new(FuncDetectSymmetry));
+    funcs.insert(FUNC_ID_ALIGN_ICP, Box::new(FuncAlignIcp));
+    funcs.insert(FUNC_ID_OFFSET_MESH, Box::new(FuncOffsetMesh));
+    funcs.insert(FUNC_ID_SMOOTH_BLEND, Box::new(FuncSmoothBlend));
+    funcs.insert(FUNC_ID_ORIENT_TO_GROUND, Box::new(FuncOrientToGround));
+    funcs.insert(FUNC_ID_BOUNDING_BOX, Box::new(FuncBoundingBox));
+    funcs.insert(
+        FUNC_ID_ORIENTED_BOUNDING_BOX,
+        Box::new(FuncOrientedBoundingBox),
+    );
+    funcs.insert(FUNC_ID_CENTER_OF_MASS, Box::new(FuncCenterOfMass));
+    funcs.insert(FUNC_ID_DROP_SIMULATION, Box::new(FuncDropSimulation));
+    funcs.insert(FUNC_ID_ANALYZE_MESH, Box::new(FuncAnalyzeMesh));
+    funcs.insert(FUNC_ID_FILL_HOLES, Box::new(FuncFillHoles));
+    funcs.insert(FUNC_ID_CUT_BY_PLANE, Box::new(FuncCutByPlane));
+    funcs.insert(FUNC_ID_CROP_TO_BOX, Box::new(FuncCropToBox));
+    funcs.insert(FUNC_ID_REPAIR_MESH, Box::new(FuncRepairMesh));
+    funcs.insert(FUNC_ID_RECOMPUTE_NORMALS, Box::new(FuncRecomputeNormals));
+    funcs.insert(FUNC_ID_RELAX_ON_SURFACE, Box::new(FuncRelaxOnSurface));
+    funcs.insert(FUNC_ID_COMPARE_MESHES, Box::new(FuncCompareMeshes));
+    funcs.insert(FUNC_ID_CURVATURE, Box::new(FuncCurvature));
+    funcs.insert(FUNC_ID_ASSIGN_MATERIAL, Box::new(FuncAssignMaterial));
+    funcs.insert(FUNC_ID_CREATE_LOCATOR, Box::new(FuncCreateLocator));
+    funcs.insert(FUNC_ID_LOCATOR_FROM_MESH, Box::new(FuncLocatorFromMesh));
+    funcs.insert(FUNC_ID_MOVE_TO_LOCATOR, Box::new(FuncMoveToLocator));
+    funcs.insert(FUNC_ID_MIRROR_BY_LOCATOR, Box::new(FuncMirrorByLocator));
+    funcs.insert(FUNC_ID_DISTANCE_TO_LOCATOR, Box::new(FuncDistanceToLocator));
+    funcs.insert(FUNC_ID_ALIGN_ICP_LOCATOR, Box::new(FuncAlignIcpLocator));
+    funcs.insert(FUNC_ID_MESH_TO_POINTS, Box::new(FuncMeshToPoints));
+    funcs.insert(FUNC_ID_PIPE_CURVE, Box::new(FuncPipeCurve));
 
     funcs
 }
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+/// Moves and rotates the mesh, so that the place marked by the From
+/// locator ends up at the To locator, e.g. to put a mesh standing on
+/// its bounding box bottom onto a clicked point.
+pub struct FuncMoveToLocator;
+
+impl Func for FuncMoveToLocator {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Move to Locator",
+            return_value_name: "Moved Mesh",
+            category: FuncCategory::Transform,
+            tags: &["place", "align", "anchor", "snap"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "From",
+                refinement: ParamRefinement::Locator,
+                optional: false,
+            },
+            ParamInfo {
+                name: "To",
+                refinement: ParamRefinement::Locator,
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let from = args[1].unwrap_locator();
+        let to = args[2].unwrap_locator();
+
+        let value = tools::transform_mesh_by_matrix(mesh, &from.transformation_to(to));
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,105 @@
+use std::error;
+use std::f32;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::distance_field::DistanceField;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncOffsetMeshError {
+    EmptyVolume,
+}
+
+impl fmt::Display for FuncOffsetMeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncOffsetMeshError::EmptyVolume => write!(f, "The offset volume is empty"),
+        }
+    }
+}
+
+impl error::Error for FuncOffsetMeshError {}
+
+pub struct FuncOffsetMesh;
+
+impl Func for FuncOffsetMesh {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Offset Mesh",
+            return_value_name: "Offset Mesh",
+            category: FuncCategory::Voxel,
+            tags: &["thicken", "grow", "shell"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Voxel Size",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.1),
+                    min_value_x: Some(f32::MIN_POSITIVE),
+                    max_value_x: None,
+                    default_value_y: Some(0.1),
+                    min_value_y: Some(f32::MIN_POSITIVE),
+                    max_value_y: None,
+                    default_value_z: Some(0.1),
+                    min_value_z: Some(f32::MIN_POSITIVE),
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Distance",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.1),
+                    min_value: None,
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let voxel_dimensions = args[1].unwrap_float3();
+        let distance = args[2].unwrap_float();
+
+        let mut distance_field =
+            DistanceField::from_mesh(mesh, &Vector3::from(voxel_dimensions), distance.max(0.0));
+        distance_field.offset(distance);
+
+        match distance_field.to_mesh() {
+            Some(value) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(FuncError::new(FuncOffsetMeshError::EmptyVolume)),
+        }
+    }
+}
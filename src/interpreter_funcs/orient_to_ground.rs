@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+pub struct FuncOrientToGround;
+
+impl Func for FuncOrientToGround {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Orient to Ground",
+            return_value_name: "Oriented Mesh",
+            category: FuncCategory::Transform,
+            tags: &["rotate", "ground", "align"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        let value = tools::orient_mesh_to_ground(mesh);
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
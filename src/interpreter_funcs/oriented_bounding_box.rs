@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, primitive};
+
+pub struct FuncOrientedBoundingBox;
+
+impl Func for FuncOrientedBoundingBox {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Oriented Bounding Box",
+            return_value_name: "Oriented Bounding Box",
+            category: FuncCategory::Analyze,
+            tags: &["bounds", "box", "orientation"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        let oriented_bounding_box =
+            analysis::compute_minimal_oriented_bounding_box(mesh.vertices())
+                .expect("Mesh must have vertices");
+        let dimensions = oriented_bounding_box.dimensions;
+
+        log(LogMessage::info(format!(
+            "Oriented bounding box dimensions: {} x {} x {}, volume: {}",
+            dimensions.x,
+            dimensions.y,
+            dimensions.z,
+            oriented_bounding_box.volume(),
+        )));
+
+        let value = primitive::create_box(
+            oriented_bounding_box.center,
+            oriented_bounding_box.rotation,
+            dimensions,
+        );
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -0,0 +1,109 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, FloatParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::primitive;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncPipeCurveError {
+    TooFewSegments { segments_provided: u32 },
+    EmptyCurve,
+}
+
+impl fmt::Display for FuncPipeCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncPipeCurveError::TooFewSegments { segments_provided } => write!(
+                f,
+                "Pipe Curve requires at least 3 segments, but only {} provided",
+                segments_provided,
+            ),
+            FuncPipeCurveError::EmptyCurve => write!(f, "The curve has no length to pipe"),
+        }
+    }
+}
+
+impl error::Error for FuncPipeCurveError {}
+
+/// Converts a curve to a mesh of pipes with circular cross-section
+/// around its polylines.
+pub struct FuncPipeCurve;
+
+impl FuncPipeCurve {
+    const MIN_SEGMENTS: u32 = 3;
+}
+
+impl Func for FuncPipeCurve {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Pipe Curve",
+            return_value_name: "Pipe",
+            category: FuncCategory::Generate,
+            tags: &["convert", "tube", "sweep", "polyline"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Curve",
+                refinement: ParamRefinement::Curve,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.05),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Segments",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(8),
+                    min_value: Some(Self::MIN_SEGMENTS),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let curve = args[0].unwrap_curve();
+        let radius = args[1].unwrap_float();
+        let n_segments = args[2].unwrap_uint();
+
+        if n_segments < Self::MIN_SEGMENTS {
+            return Err(FuncError::new(FuncPipeCurveError::TooFewSegments {
+                segments_provided: n_segments,
+            }));
+        }
+
+        match primitive::create_pipe(curve, radius, n_segments) {
+            Some(pipe) => Ok(Value::Mesh(Arc::new(pipe))),
+            None => Err(FuncError::new(FuncPipeCurveError::EmptyCurve)),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, FloatParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+pub struct FuncRecomputeNormals;
+
+impl Func for FuncRecomputeNormals {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Recompute Normals",
+            return_value_name: "Mesh",
+            category: FuncCategory::Transform,
+            tags: &["normals", "smooth", "sharp", "crease", "shading", "repair"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Crease Angle",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(30.0),
+                    min_value: Some(0.0),
+                    max_value: Some(180.0),
+                    length: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let crease_angle = args[1].unwrap_float();
+
+        let recomputed = tools::recompute_normals(mesh, crease_angle.to_radians());
+
+        Ok(Value::Mesh(Arc::new(recomputed)))
+    }
+}
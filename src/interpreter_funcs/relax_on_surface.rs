@@ -0,0 +1,103 @@
+use std::cmp;
+use std::f32;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::distance_field::DistanceField;
+use crate::mesh::{smoothing, topology, NormalStrategy};
+
+pub struct FuncRelaxOnSurface;
+
+impl Func for FuncRelaxOnSurface {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Relax On Surface",
+            return_value_name: "Relaxed Mesh",
+            category: FuncCategory::Voxel,
+            tags: &["smooth", "staircase", "voxel", "fair"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Voxel Size",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(1.0),
+                    min_value_x: Some(f32::MIN_POSITIVE),
+                    max_value_x: None,
+                    default_value_y: Some(1.0),
+                    min_value_y: Some(f32::MIN_POSITIVE),
+                    max_value_y: None,
+                    default_value_z: Some(1.0),
+                    min_value_z: Some(f32::MIN_POSITIVE),
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Iterations",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(10),
+                    min_value: Some(0),
+                    max_value: Some(255),
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        cancellation_token: &CancellationToken,
+        report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let voxel_dimensions = Vector3::from(args[1].unwrap_float3());
+        let iterations = cmp::min(255, args[2].unwrap_uint());
+
+        // The surface the vertices are kept on is the surface of the
+        // input mesh, sampled at the voxel resolution, which rounds the
+        // voxel steps
+        let distance_field = DistanceField::from_mesh(mesh, &voxel_dimensions, 0.0);
+        let v2v = topology::compute_vertex_to_vertex_topology(mesh);
+
+        match smoothing::relax_on_surface(
+            mesh,
+            &v2v,
+            &distance_field,
+            iterations,
+            NormalStrategy::Smooth,
+            |iteration| {
+                report_progress(iteration as f32 / iterations as f32);
+                cancellation_token.is_cancelled()
+            },
+        ) {
+            Some(value) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(cancellation_token
+                .check()
+                .expect_err("Relaxing must only stop early when cancelled")),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::color_ramp::{self, ColorRamp};
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, EnumParamRefinement, FloatParamRefinement, Func,
+    FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement,
+    StringParamRefinement, Ty, Value,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncRemapAttributeToColorError {
+    AttributeNotFound(String),
+    NoFiniteValues(String),
+}
+
+impl fmt::Display for FuncRemapAttributeToColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncRemapAttributeToColorError::AttributeNotFound(name) => {
+                write!(f, "Mesh has no vertex attribute named \"{}\"", name)
+            }
+            FuncRemapAttributeToColorError::NoFiniteValues(name) => write!(
+                f,
+                "Vertex attribute \"{}\" has no finite values to compute range from",
+                name,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncRemapAttributeToColorError {}
+
+pub struct FuncRemapAttributeToColor;
+
+impl Func for FuncRemapAttributeToColor {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Remap Attribute To Color",
+            return_value_name: "Colored Mesh",
+            category: FuncCategory::Analyze,
+            tags: &["attribute", "color", "visualize"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Attribute",
+                refinement: ParamRefinement::String(StringParamRefinement {
+                    default_value: "",
+                    file_path: false,
+                    file_save: false,
+                    file_ext_filter: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Color Ramp",
+                refinement: ParamRefinement::Enum(EnumParamRefinement {
+                    default_value: 0,
+                    options: &ColorRamp::NAMES,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Auto Range",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Min",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.0),
+                    min_value: None,
+                    max_value: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Max",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(1.0),
+                    min_value: None,
+                    max_value: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let attribute_name = args[1].unwrap_string();
+        let ramp = ColorRamp::from_index(args[2].unwrap_uint()).unwrap_or(ColorRamp::Grayscale);
+        let auto_range = args[3].unwrap_boolean();
+
+        let attribute = mesh.vertex_attribute(attribute_name).ok_or_else(|| {
+            FuncError::new(FuncRemapAttributeToColorError::AttributeNotFound(
+                attribute_name.to_string(),
+            ))
+        })?;
+
+        let (min, max) = if auto_range {
+            color_ramp::compute_range(attribute.values()).ok_or_else(|| {
+                FuncError::new(FuncRemapAttributeToColorError::NoFiniteValues(
+                    attribute_name.to_string(),
+                ))
+            })?
+        } else {
+            (args[4].unwrap_float(), args[5].unwrap_float())
+        };
+
+        log(LogMessage::info(format!(
+            "Remapping \"{}\" from range {} to {}",
+            attribute_name, min, max,
+        )));
+
+        let mut value = mesh.clone();
+        value.set_vertex_colors(color_ramp::remap_to_colors(
+            attribute.values(),
+            ramp,
+            min,
+            max,
+        ));
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
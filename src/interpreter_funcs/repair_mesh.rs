@@ -0,0 +1,146 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, tools};
+
+#[derive(Debug, PartialEq)]
+pub enum FuncRepairMeshError {
+    EmptyMesh,
+}
+
+impl fmt::Display for FuncRepairMeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncRepairMeshError::EmptyMesh => write!(
+                f,
+                "All faces of the mesh are duplicate, internal or degenerate"
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncRepairMeshError {}
+
+pub struct FuncRepairMesh;
+
+impl Func for FuncRepairMesh {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Repair Mesh",
+            return_value_name: "Repaired Mesh",
+            category: FuncCategory::Transform,
+            tags: &[
+                "repair",
+                "degenerate",
+                "sliver",
+                "needle",
+                "duplicate",
+                "internal",
+                "clean",
+                "fix",
+            ],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Min Face Area",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.000_001),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Max Aspect Ratio",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(20.0),
+                    min_value: Some(1.0),
+                    max_value: None,
+                    length: false,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Remove Duplicate Faces",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let area_epsilon = args[1].unwrap_float();
+        let aspect_limit = args[2].unwrap_float();
+        let remove_duplicate_faces = args[3].unwrap_boolean();
+
+        let deduplicated = if remove_duplicate_faces {
+            let duplicate_faces = analysis::find_duplicate_faces(mesh);
+            log(LogMessage::info(format!(
+                "Removed {} duplicate and {} internal faces",
+                duplicate_faces.duplicate_face_indices.len(),
+                duplicate_faces.internal_face_indices.len(),
+            )));
+
+            if duplicate_faces.is_empty() {
+                None
+            } else {
+                match tools::remove_duplicate_faces(mesh, &duplicate_faces) {
+                    Some(deduplicated) => Some(deduplicated),
+                    None => return Err(FuncError::new(FuncRepairMeshError::EmptyMesh)),
+                }
+            }
+        } else {
+            None
+        };
+        let mesh = deduplicated.as_ref().unwrap_or(mesh);
+
+        let (repaired, remaining_count) =
+            match tools::remove_degenerate_faces(mesh, area_epsilon, aspect_limit) {
+                Some(repaired) => repaired,
+                None => return Err(FuncError::new(FuncRepairMeshError::EmptyMesh)),
+            };
+        log(LogMessage::info(format!(
+            "Removed {} faces while repairing degenerate faces",
+            mesh.faces().len() - repaired.faces().len()
+        )));
+        if remaining_count > 0 {
+            log(LogMessage::warn(format!(
+                "{} degenerate faces could not be removed",
+                remaining_count
+            )));
+        }
+
+        Ok(Value::Mesh(Arc::new(repaired)))
+    }
+}
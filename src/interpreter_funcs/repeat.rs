@@ -0,0 +1,72 @@
+use crate::interpreter::{
+    ast, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage,
+    ParamInfo, ParamRefinement, Repeat, Ty, UintParamRefinement, Value,
+};
+
+const ITERATIONS_REFINEMENT: UintParamRefinement = UintParamRefinement {
+    default_value: Some(2),
+    min_value: Some(1),
+    max_value: None,
+};
+
+pub struct FuncRepeat;
+
+impl Func for FuncRepeat {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Repeat",
+            return_value_name: "Repeated Mesh",
+            category: FuncCategory::Flow,
+            tags: &["loop", "iterate"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Loop Start",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Loop End",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Iterations",
+                refinement: ParamRefinement::Uint(ITERATIONS_REFINEMENT),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn repeat(&self, args: &[ast::Expr]) -> Option<Repeat> {
+        match &args[2] {
+            ast::Expr::Lit(ast::LitExpr::Uint(iterations)) => Some(Repeat {
+                start_arg: 0,
+                end_arg: 1,
+                iterations: ITERATIONS_REFINEMENT.clamp(*iterations),
+            }),
+            _ => None,
+        }
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        Ok(args[1].clone())
+    }
+}
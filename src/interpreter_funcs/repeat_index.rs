@@ -0,0 +1,48 @@
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+
+pub struct FuncRepeatIndex;
+
+impl Func for FuncRepeatIndex {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Repeat Index",
+            return_value_name: "Iteration Index",
+            category: FuncCategory::Flow,
+            tags: &["loop", "iterate", "counter"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Loop Start",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Uint
+    }
+
+    fn repeat_index_arg(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn call(
+        &mut self,
+        _args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        // Subsequent iterations are assigned by the interpreter
+        Ok(Value::Uint(0))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+
+pub struct FuncRepeatStart;
+
+impl Func for FuncRepeatStart {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Repeat Start",
+            return_value_name: "Loop Start",
+            category: FuncCategory::Flow,
+            tags: &["loop", "iterate"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        Ok(args[0].clone())
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::convert::cast_usize;
+use crate::curve::{Curve, Polyline};
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::{analysis, Mesh, OrientedEdge, UnorientedEdge};
+
+pub struct FuncReportOpenEdges;
+
+impl Func for FuncReportOpenEdges {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Report Open Edges",
+            return_value_name: "Open Edges",
+            category: FuncCategory::Analyze,
+            tags: &["holes", "watertight", "edges"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[ParamInfo {
+            name: "Mesh",
+            refinement: ParamRefinement::Mesh,
+            optional: false,
+        }]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Curve
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+
+        // Non-manifold edges are reported once for each face sharing
+        // them, so they need to be deduplicated
+        let border_edges: Vec<UnorientedEdge> = analysis::border_edges(&edge_sharing)
+            .map(UnorientedEdge)
+            .collect();
+        let non_manifold_edges: HashSet<UnorientedEdge> =
+            analysis::non_manifold_edges(&edge_sharing)
+                .map(UnorientedEdge)
+                .collect();
+
+        let border_edge_count = border_edges.len();
+        let non_manifold_edge_count = non_manifold_edges.len();
+
+        let border_chains = analysis::edge_chains(border_edges);
+        let non_manifold_chains = analysis::edge_chains(non_manifold_edges);
+
+        let border_loop_count = border_chains
+            .iter()
+            .filter(|chain| chain.first() == chain.last())
+            .count();
+
+        log(LogMessage::info(format!(
+            "Found {} border edges in {} loops and {} open chains",
+            border_edge_count,
+            border_loop_count,
+            border_chains.len() - border_loop_count,
+        )));
+        if non_manifold_edge_count > 0 {
+            log(LogMessage::warn(format!(
+                "Found {} non-manifold edges in {} chains",
+                non_manifold_edge_count,
+                non_manifold_chains.len(),
+            )));
+        }
+        if border_edge_count == 0 && non_manifold_edge_count == 0 {
+            log(LogMessage::info("Mesh is watertight"));
+        }
+
+        let polylines = border_chains
+            .iter()
+            .chain(non_manifold_chains.iter())
+            .map(|chain| chain_to_polyline(mesh, chain))
+            .collect();
+
+        Ok(Value::Curve(Arc::new(Curve::new(polylines))))
+    }
+}
+
+/// Closed chains end with the vertex they start with, which closed
+/// polylines don't repeat.
+fn chain_to_polyline(mesh: &Mesh, chain: &[u32]) -> Polyline {
+    let closed = chain.len() > 2 && chain.first() == chain.last();
+    let open_chain = if closed {
+        &chain[..chain.len() - 1]
+    } else {
+        chain
+    };
+
+    let vertices = mesh.vertices();
+    let points = open_chain
+        .iter()
+        .map(|vertex_index| vertices[cast_usize(*vertex_index)])
+        .collect();
+
+    Polyline::new(points, closed)
+}
@@ -0,0 +1,96 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::convert::cast_usize;
+use crate::interpreter::{
+    BooleanParamRefinement, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo,
+    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+use crate::mesh::tools;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncRevertFaceError {
+    FaceOutOfBounds { face: u32, face_count: usize },
+}
+
+impl fmt::Display for FuncRevertFaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FaceOutOfBounds { face, face_count } => write!(
+                f,
+                "Face {} does not exist in mesh with {} faces",
+                face, face_count,
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncRevertFaceError {}
+
+pub struct FuncRevertFace;
+
+impl Func for FuncRevertFace {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Revert Face",
+            return_value_name: "Reverted Mesh",
+            category: FuncCategory::Transform,
+            tags: &["flip", "normals", "face"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Face",
+                refinement: ParamRefinement::Uint(UintParamRefinement {
+                    default_value: Some(0),
+                    min_value: Some(0),
+                    max_value: None,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Connected Patch",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let face = args[1].unwrap_uint();
+        let connected_patch = args[2].unwrap_boolean();
+
+        match tools::revert_mesh_faces_at(mesh, cast_usize(face), connected_patch) {
+            Some(value) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(FuncError::new(FuncRevertFaceError::FaceOutOfBounds {
+                face,
+                face_count: mesh.faces().len(),
+            })),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
 };
 use crate::mesh::tools;
 
@@ -12,11 +13,13 @@ impl Func for FuncRevertMeshFaces {
         &FuncInfo {
             name: "Revert Faces",
             return_value_name: "Reverted Mesh",
+            category: FuncCategory::Transform,
+            tags: &["flip", "normals", "orientation"],
         }
     }
 
     fn flags(&self) -> FuncFlags {
-        FuncFlags::PURE
+        FuncFlags::PURE | FuncFlags::ACCEPTS_QUADS
     }
 
     fn param_info(&self) -> &[ParamInfo] {
@@ -34,6 +37,8 @@ impl Func for FuncRevertMeshFaces {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use nalgebra::Rotation3;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
-    UintParamRefinement, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, UintParamRefinement, Value,
 };
 use crate::mesh::{analysis, primitive, NormalStrategy};
 
@@ -15,6 +15,8 @@ impl Func for FuncShrinkWrap {
         &FuncInfo {
             name: "Shrinkwrap",
             return_value_name: "Shrinkwrapped Mesh",
+            category: FuncCategory::Transform,
+            tags: &["wrap", "envelope", "hull"],
         }
     }
 
@@ -48,6 +50,8 @@ impl Func for FuncShrinkWrap {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
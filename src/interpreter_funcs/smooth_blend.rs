@@ -0,0 +1,115 @@
+use std::error;
+use std::f32;
+use std::fmt;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+use crate::interpreter::{
+    CancellationToken, Float3ParamRefinement, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+};
+use crate::mesh::distance_field::DistanceField;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncSmoothBlendError {
+    EmptyVolume,
+}
+
+impl fmt::Display for FuncSmoothBlendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncSmoothBlendError::EmptyVolume => write!(f, "The blended volume is empty"),
+        }
+    }
+}
+
+impl error::Error for FuncSmoothBlendError {}
+
+pub struct FuncSmoothBlend;
+
+impl Func for FuncSmoothBlend {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Smooth Blend",
+            return_value_name: "Blended Mesh",
+            category: FuncCategory::Voxel,
+            tags: &["boolean", "blend", "fillet"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Other Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Voxel Size",
+                refinement: ParamRefinement::Float3(Float3ParamRefinement {
+                    default_value_x: Some(0.1),
+                    min_value_x: Some(f32::MIN_POSITIVE),
+                    max_value_x: None,
+                    default_value_y: Some(0.1),
+                    min_value_y: Some(f32::MIN_POSITIVE),
+                    max_value_y: None,
+                    default_value_z: Some(0.1),
+                    min_value_z: Some(f32::MIN_POSITIVE),
+                    max_value_z: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Blend Radius",
+                refinement: ParamRefinement::Float(FloatParamRefinement {
+                    default_value: Some(0.5),
+                    min_value: Some(0.0),
+                    max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        _log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let mesh = args[0].unwrap_mesh();
+        let other_mesh = args[1].unwrap_mesh();
+        let voxel_dimensions = Vector3::from(args[2].unwrap_float3());
+        let blend_radius = args[3].unwrap_float();
+
+        // The blend may bulge out of the meshes by a quarter of the radius
+        let margin = blend_radius / 4.0;
+        let distance_field = DistanceField::from_mesh(mesh, &voxel_dimensions, margin);
+        let other_distance_field = DistanceField::from_mesh(other_mesh, &voxel_dimensions, margin);
+
+        match distance_field
+            .smooth_union(&other_distance_field, blend_radius)
+            .to_mesh()
+        {
+            Some(value) => Ok(Value::Mesh(Arc::new(value))),
+            None => Err(FuncError::new(FuncSmoothBlendError::EmptyVolume)),
+        }
+    }
+}
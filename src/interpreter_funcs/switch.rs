@@ -0,0 +1,88 @@
+use crate::convert::cast_usize;
+use crate::interpreter::{
+    ast, CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage,
+    ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+};
+
+const INDEX_REFINEMENT: UintParamRefinement = UintParamRefinement {
+    default_value: Some(1),
+    min_value: Some(1),
+    max_value: Some(4),
+};
+
+pub struct FuncSwitch;
+
+impl Func for FuncSwitch {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Switch",
+            return_value_name: "Selected Mesh",
+            category: FuncCategory::Flow,
+            tags: &["choose", "select", "branch"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Selected Input",
+                refinement: ParamRefinement::Uint(INDEX_REFINEMENT),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Input 1",
+                refinement: ParamRefinement::Mesh,
+                optional: true,
+            },
+            ParamInfo {
+                name: "Input 2",
+                refinement: ParamRefinement::Mesh,
+                optional: true,
+            },
+            ParamInfo {
+                name: "Input 3",
+                refinement: ParamRefinement::Mesh,
+                optional: true,
+            },
+            ParamInfo {
+                name: "Input 4",
+                refinement: ParamRefinement::Mesh,
+                optional: true,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn arg_demanded(&self, arg_index: usize, args: &[ast::Expr]) -> bool {
+        match &args[0] {
+            ast::Expr::Lit(ast::LitExpr::Uint(index)) => {
+                arg_index == 0 || arg_index == cast_usize(INDEX_REFINEMENT.clamp(*index))
+            }
+            _ => true,
+        }
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let index = INDEX_REFINEMENT.clamp(args[0].unwrap_uint());
+        let value = args[cast_usize(index)].clone();
+
+        if value == Value::Nil {
+            log(LogMessage::warn(format!("Input {} has no value", index)));
+        }
+
+        Ok(value)
+    }
+}
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::interpreter::{
-    Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
 };
 use crate::mesh::{analysis, tools, topology};
 
@@ -12,6 +13,8 @@ impl Func for FuncSynchronizeMeshFaces {
         &FuncInfo {
             name: "Synchronize Faces",
             return_value_name: "Synchronized Mesh",
+            category: FuncCategory::Transform,
+            tags: &["normals", "orientation", "consistent"],
         }
     }
 
@@ -34,6 +37,8 @@ impl Func for FuncSynchronizeMeshFaces {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_refcounted_mesh();
@@ -44,8 +49,8 @@ impl Func for FuncSynchronizeMeshFaces {
         if !analysis::is_mesh_orientable(&edge_sharing_map)
             && analysis::is_mesh_manifold(&edge_sharing_map)
         {
-            let vertex_to_face = topology::compute_vertex_to_face_topology(&mesh);
-            let face_to_face = topology::compute_face_to_face_topology(&mesh, &vertex_to_face);
+            let edge_to_face = topology::compute_edge_to_face_topology(&mesh);
+            let face_to_face = topology::compute_face_to_face_topology(&mesh, &edge_to_face);
 
             let value = Arc::new(tools::synchronize_mesh_winding(&mesh, &face_to_face));
 
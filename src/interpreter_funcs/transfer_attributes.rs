@@ -0,0 +1,91 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::interpreter::{
+    CancellationToken, Func, FuncCategory, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
+    ParamRefinement, Ty, Value,
+};
+use crate::mesh::tools;
+
+#[derive(Debug, PartialEq)]
+pub enum FuncTransferAttributesError {
+    EmptySourceMesh,
+}
+
+impl fmt::Display for FuncTransferAttributesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncTransferAttributesError::EmptySourceMesh => write!(
+                f,
+                "Source mesh has no faces to transfer the attributes from"
+            ),
+        }
+    }
+}
+
+impl error::Error for FuncTransferAttributesError {}
+
+pub struct FuncTransferAttributes;
+
+impl Func for FuncTransferAttributes {
+    fn info(&self) -> &FuncInfo {
+        &FuncInfo {
+            name: "Transfer Attributes",
+            return_value_name: "Mesh With Attributes",
+            category: FuncCategory::Transform,
+            tags: &["attribute", "copy", "project"],
+        }
+    }
+
+    fn flags(&self) -> FuncFlags {
+        FuncFlags::PURE
+    }
+
+    fn param_info(&self) -> &[ParamInfo] {
+        &[
+            ParamInfo {
+                name: "Source Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+            ParamInfo {
+                name: "Target Mesh",
+                refinement: ParamRefinement::Mesh,
+                optional: false,
+            },
+        ]
+    }
+
+    fn return_ty(&self) -> Ty {
+        Ty::Mesh
+    }
+
+    fn call(
+        &mut self,
+        args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
+    ) -> Result<Value, FuncError> {
+        let source = args[0].unwrap_mesh();
+        let target = args[1].unwrap_refcounted_mesh();
+
+        if source.vertex_attributes().is_empty() {
+            log(LogMessage::warn(
+                "Source mesh has no vertex attributes to transfer",
+            ));
+            return Ok(Value::Mesh(target));
+        }
+
+        let value = tools::transfer_vertex_attributes(source, &target)
+            .ok_or_else(|| FuncError::new(FuncTransferAttributesError::EmptySourceMesh))?;
+
+        log(LogMessage::info(format!(
+            "Transferred {} vertex attribute(s)",
+            source.vertex_attributes().len(),
+        )));
+
+        Ok(Value::Mesh(Arc::new(value)))
+    }
+}
@@ -1,13 +1,35 @@
+use std::error;
+use std::fmt;
 use std::sync::Arc;
 
 use nalgebra::{Matrix4, Rotation, Vector3};
 
 use crate::interpreter::{
-    BooleanParamRefinement, Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo,
-    LogMessage, ParamInfo, ParamRefinement, Ty, Value,
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, Func, FuncCategory,
+    FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
 };
-use crate::mesh::Mesh;
+use crate::mesh::tools;
 
+#[derive(Debug, PartialEq)]
+pub enum FuncTransformError {
+    ZeroScale,
+}
+
+impl fmt::Display for FuncTransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuncTransformError::ZeroScale => write!(f, "Scale can't be zero"),
+        }
+    }
+}
+
+impl error::Error for FuncTransformError {}
+
+/// Moves, rotates and scales a mesh. The rotation is given as Euler
+/// angles applied in the X, Y, Z order.
+///
+/// The parameters of the selected Transform can also be edited with
+/// the viewport gizmo.
 pub struct FuncTransform;
 
 impl Func for FuncTransform {
@@ -15,11 +37,13 @@ impl Func for FuncTransform {
         &FuncInfo {
             name: "Transform",
             return_value_name: "Transformed Mesh",
+            category: FuncCategory::Transform,
+            tags: &["move", "rotate", "scale"],
         }
     }
 
     fn flags(&self) -> FuncFlags {
-        FuncFlags::PURE
+        FuncFlags::PURE | FuncFlags::ACCEPTS_QUADS
     }
 
     fn param_info(&self) -> &[ParamInfo] {
@@ -41,6 +65,7 @@ impl Func for FuncTransform {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -56,6 +81,7 @@ impl Func for FuncTransform {
                     default_value_z: Some(0.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -71,6 +97,7 @@ impl Func for FuncTransform {
                     default_value_z: Some(1.0),
                     min_value_z: None,
                     max_value_z: None,
+                    length: false,
                 }),
                 optional: false,
             },
@@ -91,6 +118,8 @@ impl Func for FuncTransform {
     fn call(
         &mut self,
         args: &[Value],
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
@@ -100,6 +129,12 @@ impl Func for FuncTransform {
         let scale = Vector3::from(args[3].unwrap_float3());
         let transform_around_local_center = args[4].unwrap_boolean();
 
+        // Scaling by zero would flatten the mesh and its normals could
+        // not be transformed
+        if scale.iter().any(|component| *component == 0.0) {
+            return Err(FuncError::new(FuncTransformError::ZeroScale));
+        }
+
         let user_rotation = Rotation::from_euler_angles(
             rotate[0].to_radians(),
             rotate[1].to_radians(),
@@ -107,48 +142,21 @@ impl Func for FuncTransform {
         );
         let user_scaling = Matrix4::new_nonuniform_scaling(&scale);
         let user_translation = Matrix4::new_translation(&translate);
+        let user_transformation = user_translation * Matrix4::from(user_rotation) * user_scaling;
 
-        let value = if transform_around_local_center {
+        let transformation = if transform_around_local_center {
             // Move to the origin, scale and rotate, then move back and finally
             // move according to the user translation.
-            let b_box = mesh.bounding_box();
-            let center = b_box.center();
-            let vector_to_origin = Vector3::zeros() - center.coords;
-
-            let translation_to_origin = Matrix4::new_translation(&vector_to_origin);
-            let user_transformation = Matrix4::from(user_rotation) * user_scaling;
-            let translation_from_origin = Matrix4::new_translation(&(-1.0 * vector_to_origin));
-            let final_translation = translation_from_origin * user_translation;
-
-            let vertices_iter = mesh.vertices().iter().map(|v| {
-                let v1 = translation_to_origin.transform_point(v);
-                let v2 = user_transformation.transform_point(&v1);
-                final_translation.transform_point(&v2)
-            });
-            let normals_iter = mesh.normals().iter().map(|n| {
-                let n1 = translation_to_origin.transform_vector(n);
-                let n2 = user_transformation.transform_vector(&n1);
-                final_translation.transform_vector(&n2)
-            });
-
-            Mesh::from_faces_with_vertices_and_normals(
-                mesh.faces().iter().copied(),
-                vertices_iter,
-                normals_iter,
-            )
+            let center = mesh.bounding_box().center().coords;
+            Matrix4::new_translation(&center)
+                * user_transformation
+                * Matrix4::new_translation(&-center)
         } else {
-            let t = user_translation * Matrix4::from(user_rotation) * user_scaling;
-
-            let vertices_iter = mesh.vertices().iter().map(|v| t.transform_point(v));
-            let normals_iter = mesh.normals().iter().map(|n| t.transform_vector(n));
-
-            Mesh::from_faces_with_vertices_and_normals(
-                mesh.faces().iter().copied(),
-                vertices_iter,
-                normals_iter,
-            )
+            user_transformation
         };
 
+        let value = tools::transform_mesh_by_matrix(mesh, &transformation);
+
         Ok(Value::Mesh(Arc::new(value)))
     }
 }
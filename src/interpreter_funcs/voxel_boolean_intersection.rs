@@ -6,8 +6,9 @@ use std::sync::Arc;
 use nalgebra::Vector3;
 
 use crate::interpreter::{
-    BooleanParamRefinement, Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo,
-    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, Func, FuncCategory,
+    FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
+    UintParamRefinement, Value,
 };
 use crate::mesh::voxel_cloud::VoxelCloud;
 
@@ -40,6 +41,8 @@ impl Func for FuncBooleanIntersection {
         &FuncInfo {
             name: "Intersection",
             return_value_name: "Intersection Mesh",
+            category: FuncCategory::Voxel,
+            tags: &["boolean", "common", "intersect"],
         }
     }
 
@@ -71,6 +74,7 @@ impl Func for FuncBooleanIntersection {
                     default_value_z: Some(1.0),
                     min_value_z: Some(f32::MIN_POSITIVE),
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -100,6 +104,8 @@ impl Func for FuncBooleanIntersection {
     fn call(
         &mut self,
         args: &[Value],
+        cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh1 = args[0].unwrap_mesh();
@@ -112,11 +118,13 @@ impl Func for FuncBooleanIntersection {
         let mut voxel_cloud2 = VoxelCloud::from_mesh(mesh2, &Vector3::from(voxel_dimensions));
 
         for _ in 0..growth_iterations {
+            cancellation_token.check()?;
             voxel_cloud1.grow_volume();
             voxel_cloud2.grow_volume();
         }
 
         if fill {
+            cancellation_token.check()?;
             voxel_cloud1.fill_volumes();
             voxel_cloud2.fill_volumes();
         }
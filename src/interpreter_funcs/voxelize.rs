@@ -6,8 +6,9 @@ use std::sync::Arc;
 use nalgebra::Vector3;
 
 use crate::interpreter::{
-    BooleanParamRefinement, Float3ParamRefinement, Func, FuncError, FuncFlags, FuncInfo,
-    LogMessage, ParamInfo, ParamRefinement, Ty, UintParamRefinement, Value,
+    BooleanParamRefinement, CancellationToken, Float3ParamRefinement, Func, FuncCategory,
+    FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty,
+    UintParamRefinement, Value,
 };
 use crate::mesh::voxel_cloud::VoxelCloud;
 
@@ -38,6 +39,8 @@ impl Func for FuncVoxelize {
         &FuncInfo {
             name: "Voxelize Mesh",
             return_value_name: "Voxelized mesh",
+            category: FuncCategory::Voxel,
+            tags: &["voxelize", "remesh", "watertight"],
         }
     }
 
@@ -64,6 +67,7 @@ impl Func for FuncVoxelize {
                     default_value_z: Some(1.0),
                     min_value_z: Some(f32::MIN_POSITIVE),
                     max_value_z: None,
+                    length: true,
                 }),
                 optional: false,
             },
@@ -83,6 +87,13 @@ impl Func for FuncVoxelize {
                 }),
                 optional: false,
             },
+            ParamInfo {
+                name: "Smooth",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
+                }),
+                optional: false,
+            },
         ]
     }
 
@@ -93,27 +104,47 @@ impl Func for FuncVoxelize {
     fn call(
         &mut self,
         args: &[Value],
+        cancellation_token: &CancellationToken,
+        report_progress: &mut dyn FnMut(f32),
         _log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
         let voxel_dimensions = args[1].unwrap_float3();
         let growth_iterations = args[2].unwrap_uint();
         let fill = args[3].unwrap_boolean();
+        let smooth = args[4].unwrap_boolean();
+
+        // Voxelizing, each growth iteration, filling and meshing are
+        // counted as equal steps of the progress
+        let step_count = 2 + growth_iterations + u32::from(fill);
+        let mut report_step = |step: u32| report_progress(step as f32 / step_count as f32);
 
         let mut voxel_cloud = VoxelCloud::from_mesh(mesh, &Vector3::from(voxel_dimensions));
-        for _ in 0..growth_iterations {
+        report_step(1);
+
+        for iteration in 0..growth_iterations {
+            cancellation_token.check()?;
             voxel_cloud.grow_volume();
+            report_step(2 + iteration);
         }
 
         if fill {
+            cancellation_token.check()?;
             voxel_cloud.fill_volumes();
+            report_step(step_count - 1);
         }
 
         if !voxel_cloud.contains_voxels() {
             return Err(FuncError::new(FuncVoxelizeError::EmptyVoxelCloud));
         }
 
-        match voxel_cloud.to_mesh() {
+        let value = if smooth {
+            voxel_cloud.to_smooth_mesh()
+        } else {
+            voxel_cloud.to_mesh()
+        };
+
+        match value {
             Some(value) => Ok(Value::Mesh(Arc::new(value))),
             None => Err(FuncError::new(FuncVoxelizeError::WeldFailed)),
         }
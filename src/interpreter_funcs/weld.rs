@@ -3,14 +3,15 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::interpreter::{
-    FloatParamRefinement, Func, FuncError, FuncFlags, FuncInfo, LogMessage, ParamInfo,
-    ParamRefinement, Ty, Value,
+    BooleanParamRefinement, CancellationToken, FloatParamRefinement, Func, FuncCategory, FuncError,
+    FuncFlags, FuncInfo, LogMessage, ParamInfo, ParamRefinement, Ty, Value,
 };
 use crate::mesh::tools;
 
 #[derive(Debug, PartialEq)]
 pub enum FuncWeldError {
     AllFacesDegenerate,
+    EmptyMesh,
 }
 
 impl fmt::Display for FuncWeldError {
@@ -19,6 +20,7 @@ impl fmt::Display for FuncWeldError {
             FuncWeldError::AllFacesDegenerate => {
                 write!(f, "All faces remained degenerate after welding")
             }
+            FuncWeldError::EmptyMesh => write!(f, "Mesh has no faces"),
         }
     }
 }
@@ -32,6 +34,8 @@ impl Func for FuncWeld {
         &FuncInfo {
             name: "Weld",
             return_value_name: "Welded Mesh",
+            category: FuncCategory::Transform,
+            tags: &["merge", "vertices", "cleanup"],
         }
     }
 
@@ -52,6 +56,14 @@ impl Func for FuncWeld {
                     default_value: Some(0.001),
                     min_value: Some(0.0),
                     max_value: None,
+                    length: true,
+                }),
+                optional: false,
+            },
+            ParamInfo {
+                name: "Preserve Topology",
+                refinement: ParamRefinement::Boolean(BooleanParamRefinement {
+                    default_value: false,
                 }),
                 optional: false,
             },
@@ -65,10 +77,30 @@ impl Func for FuncWeld {
     fn call(
         &mut self,
         args: &[Value],
-        _log: &mut dyn FnMut(LogMessage),
+        _cancellation_token: &CancellationToken,
+        _report_progress: &mut dyn FnMut(f32),
+        log: &mut dyn FnMut(LogMessage),
     ) -> Result<Value, FuncError> {
         let mesh = args[0].unwrap_mesh();
         let tolerance = args[1].unwrap_float();
+        let preserve_topology = args[2].unwrap_boolean();
+
+        // Only welds open borders and skips merges, which would make
+        // the mesh non-manifold
+        if preserve_topology {
+            return match tools::weld_borders(&mesh, tolerance) {
+                Some((welded, rejected_count)) => {
+                    if rejected_count > 0 {
+                        log(LogMessage::info(format!(
+                            "Skipped {} merges, which would create non-manifold edges",
+                            rejected_count
+                        )));
+                    }
+                    Ok(Value::Mesh(Arc::new(welded)))
+                }
+                None => Err(FuncError::new(FuncWeldError::EmptyMesh)),
+            };
+        }
 
         if let Some(welded) = tools::weld(&mesh, tolerance) {
             Ok(Value::Mesh(Arc::new(welded)))
@@ -1,11 +1,18 @@
+use std::cell::Cell;
+use std::env;
 use std::fmt;
+use std::process;
+use std::rc::Rc;
 use std::thread;
 
 use crossbeam_channel as channel;
 
-use crate::interpreter::ast::{Prog, Stmt};
-use crate::interpreter::{InterpretOutcome, Interpreter};
+use crate::interpreter::ast::{Prog, Stmt, VarIdent};
+use crate::interpreter::{
+    CancellationToken, InterpretOutcome, InterpretProgress, Interpreter, SpillStore,
+};
 use crate::interpreter_funcs;
+use crate::optimizer::{self, OptimizationRequest, ParetoFront, ParetoRequest, Plot, PlotRequest};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RequestId(u64);
@@ -28,26 +35,46 @@ pub enum PollResponseError {
 /// the interpreter.
 #[derive(Debug)]
 pub enum InterpreterRequest {
-    #[allow(dead_code)]
     SetProg(Prog),
     #[allow(dead_code)]
     ClearProg,
     PushProgStmt(Stmt),
     PopProgStmt,
     SetProgStmtAt(usize, Stmt),
+    SetVarManualUpdate(VarIdent, bool),
+    InvalidateVar(VarIdent),
     Interpret,
     #[allow(dead_code)]
     InterpretUpUntil(usize),
+    Optimize(OptimizationRequest),
+    ExploreParetoFront(ParetoRequest),
+    RecordPlot(PlotRequest),
 }
 
 /// An interpreter response.
 #[derive(Debug)]
 pub enum InterpreterResponse {
+    /// Interpreter started computing a statement while working on an
+    /// interpret, optimize, Pareto front exploration or plot
+    /// recording request. The request is not completed yet.
+    Progress(InterpretProgress),
+
     /// Interpreter completed program edit request.
     CompletedEditProg,
 
     /// Interpreter completed interpret request.
     CompletedInterpret(InterpretOutcome),
+
+    /// Interpreter completed optimize request. Contains the programs
+    /// with the best parameter values and their scores, the best
+    /// first.
+    CompletedOptimize(Vec<(Prog, f32)>),
+
+    /// Interpreter completed Pareto front exploration request.
+    CompletedExploreParetoFront(ParetoFront),
+
+    /// Interpreter completed plot recording request.
+    CompletedRecordPlot(Plot),
 }
 
 enum Request {
@@ -73,17 +100,50 @@ pub struct InterpreterServer {
     thread: Option<thread::JoinHandle<()>>,
     request_sender: channel::Sender<Request>,
     response_receiver: channel::Receiver<Response>,
+    cancellation_token: CancellationToken,
 }
 
 impl InterpreterServer {
-    pub fn new() -> Self {
+    /// Starts the interpreter thread. If `spill_memory_budget_bytes`
+    /// is set, the interpreter spills intermediate meshes exceeding
+    /// the budget to a temporary directory.
+    pub fn new(spill_memory_budget_bytes: Option<usize>) -> Self {
         let (request_sender, request_receiver) = channel::unbounded();
         let (response_sender, response_receiver) = channel::unbounded();
+        let cancellation_token = CancellationToken::new();
+        let interpreter_cancellation_token = cancellation_token.clone();
 
         let thread = thread::spawn(move || {
             log::info!("Interpreter server starting up");
 
             let mut interpreter = Interpreter::new(interpreter_funcs::create_function_table());
+            interpreter.set_cancellation_token(interpreter_cancellation_token.clone());
+
+            if let Some(memory_budget_bytes) = spill_memory_budget_bytes {
+                let spill_dir =
+                    env::temp_dir().join(format!("hurban_selector_spill_{}", process::id(),));
+                match SpillStore::new(spill_dir, memory_budget_bytes) {
+                    Ok(spill_store) => interpreter.set_spill_store(spill_store),
+                    Err(err) => log::warn!(
+                        "Failed to create spill directory, keeping all values in memory: {}",
+                        err,
+                    ),
+                }
+            }
+
+            // Progress is reported while the request is being worked
+            // on, so the callback needs to know which request it is
+            let current_request_id = Rc::new(Cell::new(RequestId(0)));
+            let progress_request_id = Rc::clone(&current_request_id);
+            let progress_sender = response_sender.clone();
+            interpreter.set_progress_callback(move |progress| {
+                progress_sender
+                    .send(Response {
+                        request_id: progress_request_id.get(),
+                        data: InterpreterResponse::Progress(progress),
+                    })
+                    .expect("Interpreter server failed to send progress");
+            });
 
             loop {
                 let request: Request = request_receiver
@@ -94,6 +154,7 @@ impl InterpreterServer {
                     Request::Command { request_id, data } => (request_id, data),
                     Request::Shutdown => break,
                 };
+                current_request_id.set(request_id);
 
                 // FIXME: handle potential interpreter panic?
 
@@ -148,6 +209,29 @@ impl InterpreterServer {
                             data: InterpreterResponse::CompletedEditProg,
                         }
                     }
+                    InterpreterRequest::SetVarManualUpdate(var_ident, manual_update) => {
+                        log::info!(
+                            "Interpreter server received request 'SetVarManualUpdate({}, {})'",
+                            var_ident,
+                            manual_update,
+                        );
+                        interpreter.set_var_manual_update(var_ident, manual_update);
+                        Response {
+                            request_id,
+                            data: InterpreterResponse::CompletedEditProg,
+                        }
+                    }
+                    InterpreterRequest::InvalidateVar(var_ident) => {
+                        log::info!(
+                            "Interpreter server received request 'InvalidateVar({})'",
+                            var_ident,
+                        );
+                        interpreter.invalidate_var(var_ident);
+                        Response {
+                            request_id,
+                            data: InterpreterResponse::CompletedEditProg,
+                        }
+                    }
                     InterpreterRequest::Interpret => {
                         log::info!("Interpreter server received request 'Interpret'");
                         let interpret_outcome = interpreter.interpret();
@@ -167,8 +251,48 @@ impl InterpreterServer {
                             data: InterpreterResponse::CompletedInterpret(interpret_outcome),
                         }
                     }
+                    InterpreterRequest::Optimize(optimization_request) => {
+                        log::info!(
+                            "Interpreter server received request 'Optimize' with {} iterations",
+                            optimization_request.options.iterations,
+                        );
+                        let best =
+                            optimizer::optimize_prog(&mut interpreter, &optimization_request);
+                        Response {
+                            request_id,
+                            data: InterpreterResponse::CompletedOptimize(best),
+                        }
+                    }
+                    InterpreterRequest::ExploreParetoFront(pareto_request) => {
+                        log::info!(
+                            "Interpreter server received request 'ExploreParetoFront' with {} objectives and {} iterations",
+                            pareto_request.objectives.len(),
+                            pareto_request.iterations,
+                        );
+                        let front =
+                            optimizer::explore_pareto_front_prog(&mut interpreter, &pareto_request);
+                        Response {
+                            request_id,
+                            data: InterpreterResponse::CompletedExploreParetoFront(front),
+                        }
+                    }
+                    InterpreterRequest::RecordPlot(plot_request) => {
+                        log::info!(
+                            "Interpreter server received request 'RecordPlot' with {} steps",
+                            plot_request.steps,
+                        );
+                        let plot = optimizer::plot_prog(&mut interpreter, &plot_request);
+                        Response {
+                            request_id,
+                            data: InterpreterResponse::CompletedRecordPlot(plot),
+                        }
+                    }
                 };
 
+                // The cancellation only applies to the request, which
+                // was running when it was issued
+                interpreter_cancellation_token.reset();
+
                 response_sender
                     .send(response)
                     .expect("Interpreter server failed to send response");
@@ -182,9 +306,20 @@ impl InterpreterServer {
             thread: Some(thread),
             request_sender,
             response_receiver,
+            cancellation_token,
         }
     }
 
+    /// Asks the interpreter to stop working on the current request as
+    /// soon as possible.
+    ///
+    /// A cancelled interpretation completes with a runtime error.
+    /// Optimization requests complete with what they found so far.
+    pub fn cancel(&self) {
+        log::info!("Interpreter client cancelling current request");
+        self.cancellation_token.cancel();
+    }
+
     /// Submit a new request for the interpreter to work on.
     ///
     /// The corresponding response can be paired with based on the
@@ -0,0 +1,672 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel as channel;
+use serde::{Deserialize, Serialize};
+
+use crate::headless;
+use crate::interpreter::ast::{Expr, LitExpr, Stmt, VarIdent};
+use crate::interpreter::ParamRefinement;
+use crate::mesh::{Face, Mesh};
+use crate::session::Session;
+
+/// The longest line accepted from a client, in bytes. Commands only
+/// carry parameter values and paths, so this is plenty, but keeps a
+/// misbehaving client from exhausting the memory.
+const MAX_LINE_LEN: u64 = 64 * 1024;
+
+/// Methods starting the request line of HTTP requests.
+const HTTP_METHODS: [&str; 9] = [
+    "CONNECT", "DELETE", "GET", "HEAD", "OPTIONS", "PATCH", "POST", "PUT", "TRACE",
+];
+
+/// The first line sent by a client, authenticating it with the token
+/// the server was started with, e.g. `{"token": "secret"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct IpcHandshake {
+    token: String,
+}
+
+/// A command sent by an external tool. Commands are sent as JSON
+/// objects, one per line, with the command name in the `command`
+/// field, e.g. `{"command": "set_param", "stmt": 0, "arg": 1,
+/// "value": [1.0, 2.0, 3.0]}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum IpcCommand {
+    /// Returns whether the interpreter is busy.
+    Status,
+    /// Returns the operations of the pipeline and their literal
+    /// parameter values.
+    ListParams,
+    /// Sets a literal parameter of an operation.
+    SetParam {
+        stmt: usize,
+        arg: usize,
+        value: serde_json::Value,
+    },
+    /// Starts evaluating the pipeline.
+    Interpret,
+    /// Returns the geometry of the evaluated results, optionally only
+    /// of a single variable.
+    GetMeshes { var: Option<u64> },
+    /// Exports the evaluated results, optionally only of a single
+    /// variable, to an OBJ, STL or glTF file at `path`, the same way
+    /// headless runs do.
+    Export { path: String, var: Option<u64> },
+}
+
+/// A reply to a command. Replies are sent as JSON objects, one per
+/// line, with the reply kind in the `result` field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum IpcReply {
+    Ok,
+    Status { busy: bool },
+    Params { stmts: Vec<IpcStmt> },
+    Meshes { meshes: Vec<IpcMesh> },
+    Error { message: String },
+}
+
+impl IpcReply {
+    fn error<S: Into<String>>(message: S) -> Self {
+        IpcReply::Error {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IpcStmt {
+    pub var: u64,
+    pub func: String,
+    pub params: Vec<IpcParam>,
+}
+
+/// A parameter of an operation. The value is `null` for parameters
+/// referencing other variables and for embedded meshes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IpcParam {
+    pub name: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IpcMesh {
+    pub var: u64,
+    pub vertices: Vec<[f32; 3]>,
+    pub faces: Vec<[u32; 3]>,
+}
+
+/// A local server accepting commands from external tools over TCP,
+/// so that they can drive the application as a compute engine.
+///
+/// Clients authenticate by sending the server's token on the first
+/// line. Web pages can make browsers send requests to local ports, so
+/// connections starting with anything resembling an HTTP request are
+/// dropped.
+///
+/// Connections are served on background threads, but the commands
+/// are executed on the main thread, when it polls the server.
+pub struct IpcServer {
+    command_receiver: channel::Receiver<(IpcCommand, channel::Sender<IpcReply>)>,
+}
+
+impl IpcServer {
+    /// Starts listening on the `port` of the local loopback interface,
+    /// accepting clients authenticating with the `token`.
+    pub fn new(port: u16, token: String) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let (command_sender, command_receiver) = channel::unbounded();
+        let token = Arc::new(token);
+
+        log::info!("IPC server listening on {}", listener.local_addr()?);
+
+        thread::Builder::new()
+            .name("ipc_server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let command_sender = command_sender.clone();
+                            let token = Arc::clone(&token);
+                            let spawn_result = thread::Builder::new()
+                                .name("ipc_connection".to_string())
+                                .spawn(move || {
+                                    if let Err(err) =
+                                        serve_connection(stream, &token, &command_sender)
+                                    {
+                                        log::warn!("IPC connection failed: {}", err);
+                                    }
+                                });
+
+                            if let Err(err) = spawn_result {
+                                log::error!("Failed to spawn IPC connection thread: {}", err);
+                            }
+                        }
+                        Err(err) => log::warn!("IPC server failed to accept connection: {}", err),
+                    }
+                }
+            })?;
+
+        Ok(Self { command_receiver })
+    }
+
+    /// Executes the commands received since the last poll. Does not
+    /// block, if there are no commands.
+    ///
+    /// The `var_meshes` callback provides the meshes of the evaluated
+    /// results for a variable, or for all variables.
+    pub fn poll<F>(&self, session: &mut Session, var_meshes: F)
+    where
+        F: Fn(Option<VarIdent>) -> Vec<(VarIdent, Arc<Mesh>)>,
+    {
+        while let Ok((command, reply_sender)) = self.command_receiver.try_recv() {
+            log::debug!("IPC server executing command {:?}", command);
+            let reply = execute_command(session, command, &var_meshes);
+
+            // The connection may have been closed in the meantime,
+            // in which case there is nobody to reply to.
+            let _ = reply_sender.send(reply);
+        }
+    }
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    token: &str,
+    command_sender: &channel::Sender<(IpcCommand, channel::Sender<IpcReply>)>,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let handshake = match read_line(&mut reader)? {
+        Some(handshake) => handshake,
+        None => return Ok(()),
+    };
+    if looks_like_http_request(&handshake) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Refused a connection starting with an HTTP request",
+        ));
+    }
+    match serde_json::from_str::<IpcHandshake>(&handshake) {
+        Ok(handshake) if tokens_match(&handshake.token, token) => {
+            write_reply(&mut writer, &IpcReply::Ok)?;
+        }
+        _ => {
+            write_reply(&mut writer, &IpcReply::error("Invalid token"))?;
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Refused a client with an invalid token",
+            ));
+        }
+    }
+
+    while let Some(line) = read_line(&mut reader)? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str(&line) {
+            Ok(command) => {
+                let (reply_sender, reply_receiver) = channel::bounded(1);
+                if command_sender.send((command, reply_sender)).is_err() {
+                    // The application is shutting down
+                    return Ok(());
+                }
+
+                reply_receiver
+                    .recv()
+                    .unwrap_or_else(|_| IpcReply::error("Command was not executed"))
+            }
+            Err(err) => IpcReply::error(format!("Invalid command: {}", err)),
+        };
+
+        write_reply(&mut writer, &reply)?;
+    }
+
+    Ok(())
+}
+
+fn write_reply<W: Write>(writer: &mut W, reply: &IpcReply) -> io::Result<()> {
+    let reply_json = serde_json::to_string(reply).expect("Failed to serialize IPC reply to JSON");
+    writeln!(writer, "{}", reply_json)
+}
+
+/// Reads a single line of at most `MAX_LINE_LEN` bytes. Returns `None`
+/// if the connection was closed.
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let len = reader.by_ref().take(MAX_LINE_LEN).read_line(&mut line)?;
+    if len == 0 {
+        return Ok(None);
+    }
+    if len as u64 == MAX_LINE_LEN && !line.ends_with('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Line exceeds {} bytes", MAX_LINE_LEN),
+        ));
+    }
+
+    Ok(Some(line))
+}
+
+/// Returns whether the line looks like the request line of an HTTP
+/// request, e.g. `POST / HTTP/1.1`.
+fn looks_like_http_request(line: &str) -> bool {
+    let line = line.trim();
+    let starts_with_method = HTTP_METHODS.iter().any(|method| {
+        line.starts_with(method) && line[method.len()..].starts_with(char::is_whitespace)
+    });
+
+    let ends_with_version = line
+        .rsplit(char::is_whitespace)
+        .next()
+        .filter(|word| word.starts_with("HTTP/"))
+        .map_or(false, |word| {
+            word["HTTP/".len()..]
+                .chars()
+                .all(|character| character.is_ascii_digit() || character == '.')
+        });
+
+    starts_with_method || ends_with_version
+}
+
+/// Compares the tokens in time independent of where they differ, so
+/// that the token can not be guessed byte by byte.
+fn tokens_match(token: &str, expected_token: &str) -> bool {
+    token.len() == expected_token.len()
+        && token
+            .bytes()
+            .zip(expected_token.bytes())
+            .fold(0, |difference, (byte, expected_byte)| {
+                difference | (byte ^ expected_byte)
+            })
+            == 0
+}
+
+fn execute_command<F>(session: &mut Session, command: IpcCommand, var_meshes: F) -> IpcReply
+where
+    F: Fn(Option<VarIdent>) -> Vec<(VarIdent, Arc<Mesh>)>,
+{
+    match command {
+        IpcCommand::Status => IpcReply::Status {
+            busy: session.interpreter_busy(),
+        },
+        IpcCommand::ListParams => IpcReply::Params {
+            stmts: list_params(session),
+        },
+        IpcCommand::SetParam { stmt, arg, value } => {
+            if session.interpreter_busy() {
+                return IpcReply::error("Interpreter is busy");
+            }
+
+            let Stmt::VarDecl(var_decl) = match session.stmts().get(stmt) {
+                Some(stmt) => stmt,
+                None => return IpcReply::error(format!("No operation at index {}", stmt)),
+            };
+            let init_expr = var_decl.init_expr();
+            let lit = match init_expr.args().get(arg) {
+                Some(Expr::Lit(lit)) => lit,
+                Some(Expr::Var(_)) => {
+                    return IpcReply::error("Parameters referencing variables can not be set")
+                }
+                None => return IpcReply::error(format!("No parameter at index {}", arg)),
+            };
+            let param_info = &session.function_table()[&init_expr.ident()].param_info()[arg];
+
+            // Values are kept in the parameter's range, just like the
+            // values entered in the editor
+            match lit_from_json(lit, &value)
+                .map(|new_lit| clamp_lit(new_lit, &param_info.refinement))
+            {
+                Some(new_lit) => {
+                    let new_var_decl = var_decl
+                        .clone_with_init_expr(init_expr.clone_with_arg_at(arg, Expr::Lit(new_lit)));
+                    session.set_prog_stmt_at(stmt, Stmt::VarDecl(new_var_decl));
+
+                    IpcReply::Ok
+                }
+                None => {
+                    IpcReply::error(format!("Value {} does not match the parameter type", value,))
+                }
+            }
+        }
+        IpcCommand::Interpret => {
+            if session.interpreter_busy() {
+                IpcReply::error("Interpreter is busy")
+            } else {
+                session.interpret();
+                IpcReply::Ok
+            }
+        }
+        IpcCommand::GetMeshes { var } => {
+            let meshes = var_meshes(var.map(VarIdent))
+                .into_iter()
+                .map(|(var_ident, mesh)| IpcMesh {
+                    var: var_ident.0,
+                    vertices: mesh
+                        .vertices()
+                        .iter()
+                        .map(|vertex| [vertex.x, vertex.y, vertex.z])
+                        .collect(),
+                    // Clients get triangles, quads are split
+                    faces: mesh
+                        .faces()
+                        .iter()
+                        .flat_map(Face::to_triangles)
+                        .map(|triangle_face| {
+                            let (v1, v2, v3) = triangle_face.vertices;
+                            [v1, v2, v3]
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            IpcReply::Meshes { meshes }
+        }
+        IpcCommand::Export { path, var } => {
+            let meshes: Vec<_> = var_meshes(var.map(VarIdent))
+                .into_iter()
+                .map(|(_, mesh)| mesh)
+                .collect();
+
+            match headless::export_meshes(&path, &meshes, session.unit()) {
+                Ok(()) => IpcReply::Ok,
+                Err(err) => IpcReply::error(err.to_string()),
+            }
+        }
+    }
+}
+
+fn list_params(session: &Session) -> Vec<IpcStmt> {
+    let function_table = session.function_table();
+
+    session
+        .stmts()
+        .iter()
+        .map(|stmt| {
+            let Stmt::VarDecl(var_decl) = stmt;
+            let init_expr = var_decl.init_expr();
+            let func = &function_table[&init_expr.ident()];
+
+            let params = func
+                .param_info()
+                .iter()
+                .zip(init_expr.args())
+                .map(|(param_info, arg)| IpcParam {
+                    name: param_info.name.to_string(),
+                    value: match arg {
+                        Expr::Lit(lit) => lit_to_json(lit),
+                        Expr::Var(_) => serde_json::Value::Null,
+                    },
+                })
+                .collect();
+
+            IpcStmt {
+                var: var_decl.ident().0,
+                func: func.info().name.to_string(),
+                params,
+            }
+        })
+        .collect()
+}
+
+/// Clamps the literal into the range allowed by the parameter's
+/// refinement. Literals of other types are kept as they are.
+fn clamp_lit(lit: LitExpr, refinement: &ParamRefinement) -> LitExpr {
+    match (lit, refinement) {
+        (LitExpr::Int(int), ParamRefinement::Int(refinement)) => {
+            LitExpr::Int(refinement.clamp(int))
+        }
+        (LitExpr::Uint(uint), ParamRefinement::Uint(refinement)) => {
+            LitExpr::Uint(refinement.clamp(uint))
+        }
+        (LitExpr::Uint(uint), ParamRefinement::Enum(refinement)) => {
+            LitExpr::Uint(refinement.clamp(uint))
+        }
+        (LitExpr::Float(float), ParamRefinement::Float(refinement)) => {
+            LitExpr::Float(refinement.clamp(float))
+        }
+        (LitExpr::Float2(float2), ParamRefinement::Float2(refinement)) => {
+            LitExpr::Float2(refinement.clamp(float2))
+        }
+        (LitExpr::Float3(float3), ParamRefinement::Float3(refinement)) => {
+            LitExpr::Float3(refinement.clamp(float3))
+        }
+        (lit, _) => lit,
+    }
+}
+
+fn lit_to_json(lit: &LitExpr) -> serde_json::Value {
+    match lit {
+        LitExpr::Nil | LitExpr::Mesh(_) | LitExpr::Curve(_) => serde_json::Value::Null,
+        LitExpr::Boolean(boolean) => serde_json::json!(boolean),
+        LitExpr::Int(int) => serde_json::json!(int),
+        LitExpr::Uint(uint) => serde_json::json!(uint),
+        LitExpr::Float(float) => serde_json::json!(float),
+        LitExpr::Float2(float2) => serde_json::json!(float2),
+        LitExpr::Float3(float3) => serde_json::json!(float3),
+        LitExpr::String(string) => serde_json::json!(string.as_str()),
+    }
+}
+
+/// Converts the JSON value to a literal of the same type as the
+/// `current` literal. Returns `None` if the JSON value does not match
+/// the type.
+fn lit_from_json(current: &LitExpr, value: &serde_json::Value) -> Option<LitExpr> {
+    let float_array = |len: usize| -> Option<Vec<f32>> {
+        let array = value.as_array()?;
+        if array.len() != len {
+            return None;
+        }
+
+        array
+            .iter()
+            .map(|element| element.as_f64().map(|float| float as f32))
+            .collect()
+    };
+
+    match current {
+        LitExpr::Boolean(_) => value.as_bool().map(LitExpr::Boolean),
+        LitExpr::Int(_) => value
+            .as_i64()
+            .filter(|int| {
+                *int >= i64::from(i32::min_value()) && *int <= i64::from(i32::max_value())
+            })
+            .map(|int| LitExpr::Int(int as i32)),
+        LitExpr::Uint(_) => value
+            .as_u64()
+            .filter(|uint| *uint <= u64::from(u32::max_value()))
+            .map(|uint| LitExpr::Uint(uint as u32)),
+        LitExpr::Float(_) => value.as_f64().map(|float| LitExpr::Float(float as f32)),
+        LitExpr::Float2(_) => float_array(2).map(|float2| LitExpr::Float2([float2[0], float2[1]])),
+        LitExpr::Float3(_) => {
+            float_array(3).map(|float3| LitExpr::Float3([float3[0], float3[1], float3[2]]))
+        }
+        LitExpr::String(_) => value
+            .as_str()
+            .map(|string| LitExpr::String(Arc::new(string.to_string()))),
+        LitExpr::Nil | LitExpr::Mesh(_) | LitExpr::Curve(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::interpreter::{EnumParamRefinement, FloatParamRefinement, UintParamRefinement};
+
+    use super::*;
+
+    #[test]
+    fn test_ipc_command_from_json() {
+        let command: IpcCommand = serde_json::from_str(
+            r#"{"command": "set_param", "stmt": 1, "arg": 2, "value": [1.0, 2.5, 3.0]}"#,
+        )
+        .expect("Failed to parse command");
+        assert_eq!(
+            command,
+            IpcCommand::SetParam {
+                stmt: 1,
+                arg: 2,
+                value: serde_json::json!([1.0, 2.5, 3.0]),
+            },
+        );
+
+        let command: IpcCommand =
+            serde_json::from_str(r#"{"command": "get_meshes"}"#).expect("Failed to parse command");
+        assert_eq!(command, IpcCommand::GetMeshes { var: None });
+
+        let command: IpcCommand =
+            serde_json::from_str(r#"{"command": "export", "path": "out.obj", "var": 2}"#)
+                .expect("Failed to parse command");
+        assert_eq!(
+            command,
+            IpcCommand::Export {
+                path: "out.obj".to_string(),
+                var: Some(2),
+            },
+        );
+
+        assert!(serde_json::from_str::<IpcCommand>(r#"{"command": "explode"}"#).is_err());
+    }
+
+    #[test]
+    fn test_ipc_reply_to_json() {
+        let reply = IpcReply::Status { busy: true };
+        assert_eq!(
+            serde_json::to_string(&reply).expect("Failed to serialize reply"),
+            r#"{"result":"status","busy":true}"#,
+        );
+
+        let reply = IpcReply::error("Interpreter is busy");
+        assert_eq!(
+            serde_json::to_string(&reply).expect("Failed to serialize reply"),
+            r#"{"result":"error","message":"Interpreter is busy"}"#,
+        );
+    }
+
+    #[test]
+    fn test_looks_like_http_request() {
+        assert!(looks_like_http_request("POST / HTTP/1.1\r\n"));
+        assert!(looks_like_http_request("GET /index.html HTTP/1.0\n"));
+        assert!(looks_like_http_request("OPTIONS *"));
+        assert!(looks_like_http_request("PATCH /"));
+        assert!(looks_like_http_request("FOO /path HTTP/2"));
+
+        assert!(!looks_like_http_request(r#"{"token": "GET / HTTP/1.1"}"#));
+        assert!(!looks_like_http_request(r#"{"command": "status"}"#));
+        assert!(!looks_like_http_request("GETAWAY"));
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret", "secrets"));
+        assert!(!tokens_match("", "secret"));
+    }
+
+    #[test]
+    fn test_read_line_rejects_line_over_limit() {
+        let mut reader = Cursor::new(b"{\"token\": \"secret\"}\n".to_vec());
+        assert_eq!(
+            read_line(&mut reader).expect("Failed to read line"),
+            Some("{\"token\": \"secret\"}\n".to_string()),
+        );
+        assert_eq!(read_line(&mut reader).expect("Failed to read end"), None);
+
+        let mut reader = Cursor::new(vec![b'a'; MAX_LINE_LEN as usize + 1]);
+        assert!(read_line(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_clamp_lit_keeps_values_in_refinement_range() {
+        let uint_refinement = ParamRefinement::Uint(UintParamRefinement {
+            default_value: Some(2),
+            min_value: Some(1),
+            max_value: Some(10),
+        });
+        assert_eq!(
+            clamp_lit(LitExpr::Uint(0), &uint_refinement),
+            LitExpr::Uint(1),
+        );
+        assert_eq!(
+            clamp_lit(LitExpr::Uint(5), &uint_refinement),
+            LitExpr::Uint(5),
+        );
+
+        let float_refinement = ParamRefinement::Float(FloatParamRefinement {
+            default_value: Some(1.0),
+            min_value: Some(0.0),
+            max_value: Some(2.0),
+            length: false,
+        });
+        assert_eq!(
+            clamp_lit(LitExpr::Float(5.0), &float_refinement),
+            LitExpr::Float(2.0),
+        );
+
+        let enum_refinement = ParamRefinement::Enum(EnumParamRefinement {
+            default_value: 0,
+            options: &["A", "B"],
+        });
+        assert_eq!(
+            clamp_lit(LitExpr::Uint(7), &enum_refinement),
+            LitExpr::Uint(1),
+        );
+    }
+
+    #[test]
+    fn test_lit_from_json_matches_current_type() {
+        assert_eq!(
+            lit_from_json(
+                &LitExpr::Float3([0.0; 3]),
+                &serde_json::json!([1.0, 2.0, 3.0])
+            ),
+            Some(LitExpr::Float3([1.0, 2.0, 3.0])),
+        );
+        assert_eq!(
+            lit_from_json(&LitExpr::Uint(1), &serde_json::json!(5)),
+            Some(LitExpr::Uint(5)),
+        );
+        assert_eq!(
+            lit_from_json(&LitExpr::Float(0.0), &serde_json::json!(2)),
+            Some(LitExpr::Float(2.0)),
+        );
+
+        assert_eq!(
+            lit_from_json(&LitExpr::Float3([0.0; 3]), &serde_json::json!([1.0, 2.0])),
+            None,
+        );
+        assert_eq!(
+            lit_from_json(&LitExpr::Uint(1), &serde_json::json!(-5)),
+            None,
+        );
+        assert_eq!(
+            lit_from_json(&LitExpr::Boolean(false), &serde_json::json!("true")),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_lit_to_json_round_trips() {
+        let lits = [
+            LitExpr::Boolean(true),
+            LitExpr::Int(-3),
+            LitExpr::Uint(3),
+            LitExpr::Float(0.5),
+            LitExpr::Float2([0.5, 1.5]),
+            LitExpr::Float3([0.5, 1.5, 2.5]),
+            LitExpr::String(Arc::new("hello".to_string())),
+        ];
+
+        for lit in &lits {
+            assert_eq!(lit_from_json(lit, &lit_to_json(lit)).as_ref(), Some(lit));
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use std::io::{self, BufReader, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel as channel;
+use serde::Deserialize;
+
+use crate::renderer::CapturedFrame;
+use crate::websocket::{self, Opcode};
+
+/// The quality of the streamed JPEG frames, between 1 and 100.
+const JPEG_QUALITY: u8 = 80;
+
+/// A page served to browsers, which connects to the live view
+/// WebSocket, displays the streamed frames and sends camera commands
+/// when dragging or scrolling over them.
+const VIEWER_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>H.U.R.B.A.N. Selector Live View</title>
+<style>
+html, body { margin: 0; height: 100%; background: #1a1a1a; overflow: hidden; }
+img { width: 100%; height: 100%; object-fit: contain; touch-action: none; }
+</style>
+</head>
+<body>
+<img id="frame" draggable="false">
+<script>
+const frame = document.getElementById("frame");
+const socket = new WebSocket("ws://" + location.host + "/live");
+socket.binaryType = "blob";
+socket.onmessage = (event) => {
+    const url = URL.createObjectURL(event.data);
+    frame.onload = () => URL.revokeObjectURL(url);
+    frame.src = url;
+};
+const send = (command) => socket.send(JSON.stringify(command));
+let last = null;
+frame.onpointerdown = (event) => {
+    last = [event.clientX, event.clientY, event.button];
+    frame.setPointerCapture(event.pointerId);
+};
+frame.onpointerup = () => { last = null; };
+frame.onpointermove = (event) => {
+    if (last === null) { return; }
+    const x = event.clientX - last[0];
+    const y = event.clientY - last[1];
+    const command = last[2] === 0 && !event.shiftKey ? "rotate" : "pan";
+    send({ command: command, x: x, y: y });
+    last = [event.clientX, event.clientY, last[2]];
+};
+frame.onwheel = (event) => {
+    event.preventDefault();
+    send({ command: "zoom", delta: event.deltaY });
+};
+frame.ondblclick = () => send({ command: "reset_viewport" });
+frame.oncontextmenu = (event) => event.preventDefault();
+</script>
+</body>
+</html>
+"#;
+
+/// A camera manipulation requested by a live view client. Amounts are
+/// in logical pixels of the client's pointer movement.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CameraCommand {
+    Rotate { x: f32, y: f32 },
+    Pan { x: f32, y: f32 },
+    Zoom { delta: f32 },
+    ResetViewport,
+}
+
+type ClientSenders = Arc<Mutex<Vec<(SocketAddr, channel::Sender<Arc<Vec<u8>>>)>>>;
+
+/// A local HTTP server streaming the rendered viewport to browsers
+/// over WebSocket, so that results can be reviewed live on another
+/// device. Clients can control the camera.
+///
+/// Frames are sent as Motion JPEG, one JPEG image per binary
+/// WebSocket message, which browsers display without a video decoder.
+/// Frames are only captured when at
+/// least one client is connected, and at most once per frame
+/// interval. If encoding or sending can not keep up, frames are
+/// dropped.
+///
+/// Browsers let any page open WebSockets to any address, so WebSocket
+/// connections are only accepted from pages served by this server,
+/// pages on localhost and explicitly allowed origins. Otherwise any
+/// website open in the browser could watch and control the viewport.
+pub struct LiveViewServer {
+    frame_sender: channel::Sender<CapturedFrame>,
+    camera_command_receiver: channel::Receiver<CameraCommand>,
+    client_count: Arc<AtomicUsize>,
+    frame_interval: Duration,
+    last_frame_time: Option<Instant>,
+}
+
+impl LiveViewServer {
+    /// Starts listening on the `port` of the network interface with
+    /// `address`. Other devices can only connect if the address is not
+    /// a loopback address, e.g. `0.0.0.0`. Pages from the
+    /// `allowed_origins`, e.g. `https://example.com`, may connect in
+    /// addition to the pages served by this server and localhost.
+    pub fn new(
+        address: IpAddr,
+        port: u16,
+        allowed_origins: Vec<String>,
+        frame_interval: Duration,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind((address, port))?;
+        let (frame_sender, frame_receiver) = channel::bounded::<CapturedFrame>(1);
+        let (camera_command_sender, camera_command_receiver) = channel::unbounded();
+        let client_senders: ClientSenders = Arc::new(Mutex::new(Vec::new()));
+        let client_count = Arc::new(AtomicUsize::new(0));
+
+        log::info!("Live view server listening on {}", listener.local_addr()?);
+
+        let encoder_client_senders = Arc::clone(&client_senders);
+        thread::Builder::new()
+            .name("live_view_encoder".to_string())
+            .spawn(move || {
+                for frame in frame_receiver {
+                    let jpeg = match frame.encode_jpeg(JPEG_QUALITY) {
+                        Ok(jpeg) => Arc::new(jpeg),
+                        Err(err) => {
+                            log::error!("Failed to encode live view frame: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let client_senders = encoder_client_senders
+                        .lock()
+                        .expect("Failed to lock live view clients");
+                    for (_, client_sender) in client_senders.iter() {
+                        // Slow clients miss frames
+                        let _ = client_sender.try_send(Arc::clone(&jpeg));
+                    }
+                }
+            })?;
+
+        let accept_client_count = Arc::clone(&client_count);
+        let allowed_origins = Arc::new(allowed_origins);
+        thread::Builder::new()
+            .name("live_view_server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            log::warn!("Live view server failed to accept connection: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let client_senders = Arc::clone(&client_senders);
+                    let client_count = Arc::clone(&accept_client_count);
+                    let camera_command_sender = camera_command_sender.clone();
+                    let allowed_origins = Arc::clone(&allowed_origins);
+                    let spawn_result = thread::Builder::new()
+                        .name("live_view_connection".to_string())
+                        .spawn(move || {
+                            let result = serve_connection(
+                                stream,
+                                &client_senders,
+                                &client_count,
+                                &camera_command_sender,
+                                &allowed_origins,
+                            );
+                            if let Err(err) = result {
+                                log::debug!("Live view connection closed: {}", err);
+                            }
+                        });
+
+                    if let Err(err) = spawn_result {
+                        log::error!("Failed to spawn live view connection thread: {}", err);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            frame_sender,
+            camera_command_receiver,
+            client_count,
+            frame_interval,
+            last_frame_time: None,
+        })
+    }
+
+    /// Returns whether a frame should be captured and sent now.
+    pub fn wants_frame(&self, time: Instant) -> bool {
+        self.client_count.load(Ordering::SeqCst) > 0
+            && self.last_frame_time.map_or(true, |last| {
+                time.duration_since(last) >= self.frame_interval
+            })
+    }
+
+    /// Sends the frame to all connected clients. The frame is dropped,
+    /// if the previous one is still being encoded.
+    pub fn send_frame(&mut self, frame: CapturedFrame, time: Instant) {
+        self.last_frame_time = Some(time);
+        let _ = self.frame_sender.try_send(frame);
+    }
+
+    /// Returns the camera commands received since the last poll.
+    pub fn poll_camera_commands(&self) -> Vec<CameraCommand> {
+        self.camera_command_receiver.try_iter().collect()
+    }
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    client_senders: &ClientSenders,
+    client_count: &AtomicUsize,
+    camera_command_sender: &channel::Sender<CameraCommand>,
+    allowed_origins: &[String],
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let request = websocket::read_http_request(&mut reader)?;
+    let websocket_key = match request.websocket_key {
+        Some(websocket_key) => websocket_key,
+        None => {
+            // Plain HTTP requests get the viewer page
+            write!(
+                writer,
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/html; charset=utf-8\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                VIEWER_HTML.len(),
+                VIEWER_HTML,
+            )?;
+            return writer.flush();
+        }
+    };
+
+    let peer_addr = writer.peer_addr()?;
+    if let Some(origin) = &request.origin {
+        if !is_origin_allowed(
+            origin,
+            request.host.as_ref().map(String::as_str),
+            allowed_origins,
+        ) {
+            log::warn!(
+                "Live view refused connection from {} opened by a page from {}",
+                peer_addr,
+                origin,
+            );
+            write!(
+                writer,
+                "HTTP/1.1 403 Forbidden\r\n\
+                 Content-Length: 0\r\n\
+                 Connection: close\r\n\r\n",
+            )?;
+            return writer.flush();
+        }
+    }
+
+    websocket::write_handshake_response(&mut writer, &websocket_key)?;
+    log::info!("Live view client connected from {}", peer_addr);
+
+    let (frame_sender, frame_receiver) = channel::bounded::<Arc<Vec<u8>>>(1);
+    client_senders
+        .lock()
+        .expect("Failed to lock live view clients")
+        .push((peer_addr, frame_sender));
+    client_count.fetch_add(1, Ordering::SeqCst);
+
+    // Frames are written on a separate thread, so that reading
+    // commands is not blocked by slow writes
+    let frame_writer = thread::Builder::new()
+        .name("live_view_writer".to_string())
+        .spawn(move || -> io::Result<()> {
+            for jpeg in frame_receiver {
+                websocket::write_message(&mut writer, Opcode::Binary, &jpeg)?;
+            }
+
+            Ok(())
+        })?;
+
+    let result = read_camera_commands(&mut reader, camera_command_sender);
+
+    // Dropping the client's sender ends its writer thread
+    client_senders
+        .lock()
+        .expect("Failed to lock live view clients")
+        .retain(|(addr, _)| *addr != peer_addr);
+    client_count.fetch_sub(1, Ordering::SeqCst);
+
+    let _ = frame_writer.join();
+    log::info!("Live view client {} disconnected", peer_addr);
+
+    result
+}
+
+/// Checks whether a page from the `origin` may connect to the live view
+/// WebSocket. Allowed are pages on localhost, pages from the
+/// `allowed_origins` and pages served by this server at an IP address,
+/// i.e. with the origin matching the `host` the request was sent to.
+///
+/// Hosts given by name are not trusted even if they match, because any
+/// website can rebind its name to the address of this server.
+fn is_origin_allowed(origin: &str, host: Option<&str>, allowed_origins: &[String]) -> bool {
+    let origin = origin.trim_end_matches('/');
+    let authority = match origin.find("://") {
+        Some(scheme_end) => &origin[scheme_end + 3..],
+        None => return false,
+    };
+    let hostname = authority_hostname(authority);
+
+    let is_loopback = hostname.eq_ignore_ascii_case("localhost")
+        || hostname
+            .parse::<IpAddr>()
+            .map_or(false, |address| address.is_loopback());
+    let is_allowed = allowed_origins.iter().any(|allowed_origin| {
+        allowed_origin
+            .trim_end_matches('/')
+            .eq_ignore_ascii_case(origin)
+    });
+    let is_served_by_server = host.map_or(false, |host| {
+        host.eq_ignore_ascii_case(authority) && hostname.parse::<IpAddr>().is_ok()
+    });
+
+    is_loopback || is_allowed || is_served_by_server
+}
+
+/// Returns the hostname of the `host[:port]` authority, without the
+/// brackets around IPv6 addresses.
+fn authority_hostname(authority: &str) -> &str {
+    if authority.starts_with('[') {
+        match authority.find(']') {
+            Some(bracket_index) => &authority[1..bracket_index],
+            None => authority,
+        }
+    } else {
+        match authority.rfind(':') {
+            Some(colon_index) => &authority[..colon_index],
+            None => authority,
+        }
+    }
+}
+
+fn read_camera_commands(
+    reader: &mut BufReader<TcpStream>,
+    camera_command_sender: &channel::Sender<CameraCommand>,
+) -> io::Result<()> {
+    loop {
+        let (opcode, payload) = websocket::read_frame(reader)?;
+        match opcode {
+            Opcode::Text => match serde_json::from_slice(&payload) {
+                Ok(command) => {
+                    if camera_command_sender.send(command).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(err) => log::warn!("Invalid live view command: {}", err),
+            },
+            Opcode::Close => return Ok(()),
+            _ => (/* Ignore other messages */),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camera_command_from_json() {
+        let command: CameraCommand =
+            serde_json::from_str(r#"{"command": "rotate", "x": 2, "y": -3.5}"#)
+                .expect("Failed to parse command");
+        assert_eq!(command, CameraCommand::Rotate { x: 2.0, y: -3.5 });
+
+        let command: CameraCommand = serde_json::from_str(r#"{"command": "reset_viewport"}"#)
+            .expect("Failed to parse command");
+        assert_eq!(command, CameraCommand::ResetViewport);
+    }
+
+    #[test]
+    fn test_is_origin_allowed_accepts_localhost_and_own_pages() {
+        assert!(is_origin_allowed(
+            "http://localhost:8300",
+            Some("localhost:8300"),
+            &[],
+        ));
+        assert!(is_origin_allowed(
+            "http://127.0.0.1:3000",
+            Some("127.0.0.1:8300"),
+            &[],
+        ));
+        assert!(is_origin_allowed(
+            "http://[::1]:8300",
+            Some("[::1]:8300"),
+            &[]
+        ));
+        assert!(is_origin_allowed(
+            "http://192.168.1.10:8300",
+            Some("192.168.1.10:8300"),
+            &[],
+        ));
+        assert!(is_origin_allowed(
+            "https://review.example.com",
+            Some("192.168.1.10:8300"),
+            &["https://review.example.com/".to_string()],
+        ));
+    }
+
+    #[test]
+    fn test_is_origin_allowed_refuses_other_pages() {
+        assert!(!is_origin_allowed(
+            "https://example.com",
+            Some("localhost:8300"),
+            &[],
+        ));
+        assert!(!is_origin_allowed(
+            "http://192.168.1.20:8300",
+            Some("192.168.1.10:8300"),
+            &[],
+        ));
+        // A website rebinding its name to this server
+        assert!(!is_origin_allowed(
+            "http://example.com:8300",
+            Some("example.com:8300"),
+            &[],
+        ));
+        assert!(!is_origin_allowed("null", Some("localhost:8300"), &[]));
+    }
+
+    #[test]
+    fn test_encode_jpeg() {
+        let frame = CapturedFrame {
+            width: 16,
+            height: 8,
+            data: [255, 0, 0, 255]
+                .iter()
+                .cycle()
+                .take(16 * 8 * 4)
+                .copied()
+                .collect(),
+        };
+
+        let jpeg_data = frame
+            .encode_jpeg(JPEG_QUALITY)
+            .expect("Failed to encode JPEG");
+        let decoded = image::load_from_memory_with_format(&jpeg_data, image::ImageFormat::JPEG)
+            .expect("Failed to decode JPEG")
+            .to_rgba();
+
+        assert_eq!(decoded.dimensions(), (16, 8));
+        for pixel in decoded.pixels() {
+            assert!(pixel[0] > 240 && pixel[1] < 16 && pixel[2] < 16);
+        }
+    }
+}
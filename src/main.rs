@@ -1,10 +1,122 @@
-#![windows_subsystem = "windows"]
+// Headless builds stay console applications on Windows
+#![cfg_attr(feature = "gui", windows_subsystem = "windows")]
 
 use std::env;
 
 use hurban_selector as hs;
 
 fn main() {
+    let app_log_level = env::var("HS_APP_LOG_LEVEL")
+        .ok()
+        .map(|app_log_level| match app_log_level.as_str() {
+            "error" => hs::LogLevel::Error,
+            "warning" => hs::LogLevel::Warning,
+            "info" => hs::LogLevel::Info,
+            "debug" => hs::LogLevel::Debug,
+            _ => panic!("Unknown library log level requested"),
+        });
+
+    let lib_log_level = env::var("HS_LIB_LOG_LEVEL")
+        .ok()
+        .map(|lib_log_level| match lib_log_level.as_str() {
+            "error" => hs::LogLevel::Error,
+            "warning" => hs::LogLevel::Warning,
+            "info" => hs::LogLevel::Info,
+            "debug" => hs::LogLevel::Debug,
+            _ => panic!("Unknown library log level requested"),
+        });
+
+    // Applies to all modes, so that sweep workers and headless runs
+    // can reproduce each other's results
+    let deterministic = env::var("HS_DETERMINISTIC")
+        .ok()
+        .map(|deterministic| match deterministic.as_str() {
+            "0" => false,
+            "1" => true,
+            unsupported_deterministic => panic!(
+                "Unsupported deterministic value requested: {}",
+                unsupported_deterministic,
+            ),
+        })
+        .unwrap_or(false);
+    if deterministic {
+        hs::enable_deterministic_mode()
+            .unwrap_or_else(|err| panic!("Failed to enable deterministic mode: {}", err));
+    }
+
+    // Workers run headless, without opening a window
+    if let Ok(sweep_worker) = env::var("HS_SWEEP_WORKER") {
+        if let Err(err) = hs::run_sweep_worker(&sweep_worker, app_log_level, lib_log_level) {
+            panic!("Sweep worker connected to {} failed: {}", sweep_worker, err);
+        }
+        return;
+    }
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Conversions process a single mesh file without opening a window,
+    // e.g. `hurban_selector --convert in.obj out.stl --weld 0.01 --recompute-normals`
+    if let Some(input_path) = arg_value(&args, "--convert") {
+        let output_path = arg_value_at(&args, "--convert", 2).unwrap_or_else(|| {
+            panic!("Convert mode requires an output path: --convert <in> <out>")
+        });
+        let weld_tolerance = arg_value(&args, "--weld").map(|weld_tolerance| {
+            weld_tolerance
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid weld tolerance requested: {}", weld_tolerance))
+        });
+        let crease_angle = arg_value(&args, "--crease-angle").map(|crease_angle| {
+            crease_angle
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid crease angle requested: {}", crease_angle))
+        });
+        let recompute_normals = args.iter().any(|arg| arg == "--recompute-normals");
+
+        let options = hs::ConvertOptions {
+            weld_tolerance,
+            crease_angle: if recompute_normals {
+                Some(crease_angle.unwrap_or(30.0))
+            } else {
+                None
+            },
+        };
+        if let Err(err) = hs::run_convert(
+            input_path,
+            output_path,
+            options,
+            app_log_level,
+            lib_log_level,
+        ) {
+            panic!("Conversion of {} failed: {}", input_path, err);
+        }
+        return;
+    }
+
+    // Lists the operations that headless projects can be built of,
+    // e.g. `hurban_selector --list-operations`
+    if args.iter().any(|arg| arg == "--list-operations") {
+        print!("{}", hs::operation_catalog());
+        return;
+    }
+
+    // Headless runs process a saved project without opening a window,
+    // e.g. `hurban_selector --headless project.json --out result.obj`
+    if let Some(project_path) = arg_value(&args, "--headless") {
+        let output_path = arg_value(&args, "--out")
+            .unwrap_or_else(|| panic!("Headless mode requires an output path: --out <path>"));
+        if let Err(err) = hs::run_headless(project_path, output_path, app_log_level, lib_log_level)
+        {
+            panic!("Headless run of {} failed: {}", project_path, err);
+        }
+        return;
+    }
+
+    run_gui(app_log_level, lib_log_level);
+}
+
+/// Opens the editor window configured by the environment variables.
+#[cfg(feature = "gui")]
+fn run_gui(app_log_level: Option<hs::LogLevel>, lib_log_level: Option<hs::LogLevel>) {
     let theme = env::var("HS_THEME")
         .ok()
         .map(|theme| match theme.as_str() {
@@ -60,25 +172,75 @@ fn main() {
             _ => panic!("Unknown gpu backend requested"),
         });
 
-    let app_log_level = env::var("HS_APP_LOG_LEVEL")
+    let ipc_port = env::var("HS_IPC_PORT").ok().map(|ipc_port| {
+        ipc_port
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid IPC port requested: {}", ipc_port))
+    });
+
+    // Any local process, including browsers, can connect to the port,
+    // so clients have to know the token
+    let ipc_token = env::var("HS_IPC_TOKEN").ok();
+    if ipc_port.is_some() && ipc_token.as_ref().map_or(true, String::is_empty) {
+        panic!("IPC server requires a token: HS_IPC_TOKEN");
+    }
+
+    let live_view_port = env::var("HS_LIVE_VIEW_PORT").ok().map(|live_view_port| {
+        live_view_port
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid live view port requested: {}", live_view_port))
+    });
+
+    let live_view_address = env::var("HS_LIVE_VIEW_ADDRESS")
         .ok()
-        .map(|app_log_level| match app_log_level.as_str() {
-            "error" => hs::LogLevel::Error,
-            "warning" => hs::LogLevel::Warning,
-            "info" => hs::LogLevel::Info,
-            "debug" => hs::LogLevel::Debug,
-            _ => panic!("Unknown library log level requested"),
-        });
+        .map(|live_view_address| {
+            live_view_address.parse().unwrap_or_else(|_| {
+                panic!("Invalid live view address requested: {}", live_view_address)
+            })
+        })
+        .unwrap_or_else(|| std::net::Ipv4Addr::LOCALHOST.into());
 
-    let lib_log_level = env::var("HS_LIB_LOG_LEVEL")
+    let live_view_allowed_origins = env::var("HS_LIVE_VIEW_ALLOWED_ORIGINS")
+        .map(|live_view_allowed_origins| {
+            live_view_allowed_origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let sweep_coordinator_port =
+        env::var("HS_SWEEP_COORDINATOR_PORT")
+            .ok()
+            .map(|sweep_coordinator_port| {
+                sweep_coordinator_port.parse().unwrap_or_else(|_| {
+                    panic!(
+                        "Invalid sweep coordinator port requested: {}",
+                        sweep_coordinator_port,
+                    )
+                })
+            });
+
+    let sweep_coordinator_address = env::var("HS_SWEEP_COORDINATOR_ADDRESS")
         .ok()
-        .map(|lib_log_level| match lib_log_level.as_str() {
-            "error" => hs::LogLevel::Error,
-            "warning" => hs::LogLevel::Warning,
-            "info" => hs::LogLevel::Info,
-            "debug" => hs::LogLevel::Debug,
-            _ => panic!("Unknown library log level requested"),
+        .map(|sweep_coordinator_address| {
+            sweep_coordinator_address.parse().unwrap_or_else(|_| {
+                panic!(
+                    "Invalid sweep coordinator address requested: {}",
+                    sweep_coordinator_address,
+                )
+            })
+        })
+        .unwrap_or_else(|| std::net::Ipv4Addr::LOCALHOST.into());
+
+    let spill_memory_budget_bytes = env::var("HS_SPILL_BUDGET_MB").ok().map(|spill_budget_mb| {
+        let spill_budget_mb: usize = spill_budget_mb.parse().unwrap_or_else(|_| {
+            panic!("Invalid spill memory budget requested: {}", spill_budget_mb)
         });
+        spill_budget_mb * 1024 * 1024
+    });
 
     hs::init_and_run(hs::Options {
         theme,
@@ -88,5 +250,38 @@ fn main() {
         gpu_backend,
         app_log_level,
         lib_log_level,
+        ipc_port,
+        ipc_token,
+        live_view_port,
+        live_view_address,
+        live_view_allowed_origins,
+        sweep_coordinator_port,
+        sweep_coordinator_address,
+        spill_memory_budget_bytes,
     });
 }
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_app_log_level: Option<hs::LogLevel>, _lib_log_level: Option<hs::LogLevel>) {
+    eprintln!("Built without the editor window (the gui feature). Usage:");
+    eprintln!("  hurban_selector --headless <project.json> --out <path>");
+    eprintln!("  hurban_selector --convert <in> <out> [--weld <tolerance>] [--recompute-normals]");
+    eprintln!("  hurban_selector --list-operations");
+    eprintln!("  HS_SWEEP_WORKER=<address> hurban_selector");
+    std::process::exit(2);
+}
+
+/// Returns the value following the `flag` in the command line
+/// arguments.
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    arg_value_at(args, flag, 1)
+}
+
+/// Returns the value at the `offset` after the `flag` in the command
+/// line arguments, for flags taking more values.
+fn arg_value_at<'a>(args: &'a [String], flag: &str, offset: usize) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + offset))
+        .map(String::as_str)
+}
@@ -35,11 +35,13 @@ pub fn lerp(source: f32, target: f32, weight: f32) -> f32 {
 /// Port and simplification of https://github.com/gre/bezier-easing
 /// and
 /// http://greweb.me/2012/02/bezier-curve-based-easing-functions-from-concept-to-implementation/
+#[cfg(feature = "gui")]
 pub struct CubicBezierEasing {
     p1: [f32; 2],
     p2: [f32; 2],
 }
 
+#[cfg(feature = "gui")]
 impl CubicBezierEasing {
     /// Create a new cubic bezier curve with control points `p1` and `p2`.
     ///
@@ -0,0 +1,1126 @@
+//! Compact binary encoding of meshes for storage.
+//!
+//! Meshes embedded in project files and meshes spilled to disk by the
+//! interpreter are much smaller encoded than serialized as JSON:
+//! vertex positions are optionally quantized to a grid spanning the
+//! mesh bounding box and delta-encoded, normals are optionally stored
+//! octahedrally encoded, face indices are delta-encoded as variable
+//! length integers and the whole payload is deflated. Dense voxel
+//! meshes, whose vertices lie on a regular grid, shrink 5-10 times
+//! compared to JSON.
+
+use std::error;
+use std::fmt;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::convert::{cast_u32, cast_usize};
+use crate::mesh::normals::VertexNormals;
+use crate::mesh::{Face, Mesh, QuadFace, TriangleFace};
+
+const MAGIC: &[u8; 4] = b"HSMC";
+const VERSION: u8 = 4;
+
+/// The first version with face materials. Older encoded meshes, e.g.
+/// embedded in saved projects, are still decoded.
+const FACE_MATERIALS_VERSION: u8 = 2;
+
+/// The first version with a normal for each vertex and overrides of
+/// face corners. Older versions have indexed normals of face corners,
+/// which are converted when decoded.
+const VERTEX_NORMALS_VERSION: u8 = 3;
+
+/// The first version with quad faces. Faces of older versions are all
+/// triangles.
+const QUADS_VERSION: u8 = 4;
+
+/// The number of bits per axis, to which positions of meshes embedded
+/// in project files are quantized. A 500m large site is quantized to
+/// half a millimeter.
+pub const EMBEDDED_POSITION_BITS: u32 = 20;
+
+/// The largest number of bits per axis positions can be quantized to.
+/// More bits than the precision of `f32` would not help.
+pub const MAX_POSITION_BITS: u32 = 24;
+
+/// How precisely the mesh data is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// The decoded mesh equals the encoded one.
+    Lossless,
+    /// Positions are quantized to `position_bits` bits per axis,
+    /// normals to 16 bits per component of their octahedral encoding
+    /// and vertex colors to 8 bits per channel. Vertex attributes and
+    /// face materials are always lossless.
+    Quantized { position_bits: u32 },
+}
+
+/// An error decoding an encoded mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    Inflate(String),
+    InvalidHeader,
+    UnexpectedEnd,
+    InvalidData(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Inflate(message) => write!(f, "Failed to decompress mesh: {}", message),
+            DecodeError::InvalidHeader => write!(f, "Not an encoded mesh or unsupported version"),
+            DecodeError::UnexpectedEnd => write!(f, "Encoded mesh ends unexpectedly"),
+            DecodeError::InvalidData(message) => write!(f, "Invalid encoded mesh: {}", message),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Encodes the mesh with the given precision and compresses it.
+///
+/// # Panics
+/// Panics if quantized with 0 or more than `MAX_POSITION_BITS` bits.
+pub fn encode(mesh: &Mesh, precision: Precision) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.bytes.extend_from_slice(MAGIC);
+    writer.write_u8(VERSION);
+
+    match precision {
+        Precision::Lossless => {
+            writer.write_u8(0);
+        }
+        Precision::Quantized { position_bits } => {
+            assert!(
+                position_bits > 0 && position_bits <= MAX_POSITION_BITS,
+                "Positions must be quantized to 1 to {} bits",
+                MAX_POSITION_BITS,
+            );
+            writer.write_u8(position_bits as u8);
+        }
+    }
+
+    let vertex_normals = mesh.vertex_normals();
+    let face_overrides = vertex_normals.face_overrides();
+    let corner_overrides = vertex_normals.corner_overrides();
+
+    writer.write_u32(cast_u32(mesh.vertices().len()));
+    writer.write_u32(cast_u32(mesh.faces().len()));
+    writer.write_u32(cast_u32(face_overrides.len()));
+    writer.write_u32(cast_u32(corner_overrides.len()));
+
+    let quad_face_indices: Vec<u32> = mesh
+        .faces()
+        .iter()
+        .enumerate()
+        .filter(|(_, face)| face.vertices().len() == 4)
+        .map(|(face_index, _)| cast_u32(face_index))
+        .collect();
+    writer.write_u32(cast_u32(quad_face_indices.len()));
+
+    // The vertex normals are followed by the normals of the face and
+    // corner overrides, whose faces and vertices are written after the
+    // faces
+    let stored_normals = vertex_normals
+        .vertex_normals()
+        .iter()
+        .chain(face_overrides.iter().map(|(_, normal)| normal))
+        .chain(corner_overrides.iter().map(|(_, _, normal)| normal));
+
+    match precision {
+        Precision::Lossless => {
+            for vertex in mesh.vertices() {
+                writer.write_f32(vertex.x);
+                writer.write_f32(vertex.y);
+                writer.write_f32(vertex.z);
+            }
+            for normal in stored_normals {
+                writer.write_f32(normal.x);
+                writer.write_f32(normal.y);
+                writer.write_f32(normal.z);
+            }
+        }
+        Precision::Quantized { position_bits } => {
+            let bounding_box = mesh.bounding_box();
+            let (min, max) = (bounding_box.minimum_point(), bounding_box.maximum_point());
+            for coord in min.coords.iter().chain(max.coords.iter()) {
+                writer.write_f32(*coord);
+            }
+
+            let max_quantized = (1u32 << position_bits) - 1;
+            let mut previous = [0u32; 3];
+            for vertex in mesh.vertices() {
+                for axis in 0..3 {
+                    let quantized = quantize(vertex[axis], min[axis], max[axis], max_quantized);
+                    writer.write_delta(previous[axis], quantized);
+                    previous[axis] = quantized;
+                }
+            }
+
+            for normal in stored_normals {
+                let [u, v] = encode_octahedral(normal);
+                writer.write_u16(u);
+                writer.write_u16(v);
+            }
+        }
+    }
+
+    // The indices of quad faces tell the decoder how many vertex
+    // indices each face has
+    let mut previous_face_index = 0;
+    for face_index in quad_face_indices {
+        writer.write_delta(previous_face_index, face_index);
+        previous_face_index = face_index;
+    }
+
+    let mut previous_vertex_index = 0;
+    for face in mesh.faces() {
+        for vertex_index in &face.vertices() {
+            writer.write_delta(previous_vertex_index, *vertex_index);
+            previous_vertex_index = *vertex_index;
+        }
+    }
+
+    let mut previous_face_index = 0;
+    for (face_index, _) in face_overrides {
+        writer.write_delta(previous_face_index, *face_index);
+        previous_face_index = *face_index;
+    }
+
+    let mut previous_face_index = 0;
+    let mut previous_vertex_index = 0;
+    for (face_index, vertex_index, _) in corner_overrides {
+        writer.write_delta(previous_face_index, *face_index);
+        writer.write_delta(previous_vertex_index, *vertex_index);
+        previous_face_index = *face_index;
+        previous_vertex_index = *vertex_index;
+    }
+
+    writer.write_u32(cast_u32(mesh.vertex_attributes().len()));
+    for attribute in mesh.vertex_attributes() {
+        writer.write_u32(cast_u32(attribute.name().len()));
+        writer.bytes.extend_from_slice(attribute.name().as_bytes());
+        for value in attribute.values() {
+            writer.write_f32(*value);
+        }
+    }
+
+    match mesh.vertex_colors() {
+        Some(colors) => {
+            writer.write_u8(1);
+            for color in colors {
+                for channel in color {
+                    match precision {
+                        Precision::Lossless => writer.write_f32(*channel),
+                        Precision::Quantized { .. } => {
+                            writer.write_u8((channel.max(0.0).min(1.0) * 255.0).round() as u8)
+                        }
+                    }
+                }
+            }
+        }
+        None => writer.write_u8(0),
+    }
+
+    match mesh.face_materials() {
+        Some(materials) => {
+            writer.write_u8(1);
+            let mut previous_material = 0;
+            for material in materials {
+                writer.write_delta(previous_material, *material);
+                previous_material = *material;
+            }
+        }
+        None => writer.write_u8(0),
+    }
+
+    deflate::deflate_bytes(&writer.bytes)
+}
+
+/// Decompresses and decodes a mesh encoded by `encode`.
+pub fn decode(bytes: &[u8]) -> Result<Mesh, DecodeError> {
+    let bytes = inflate::inflate_bytes(bytes).map_err(DecodeError::Inflate)?;
+    let mut reader = Reader { bytes: &bytes };
+
+    if reader.read_bytes(MAGIC.len())? != MAGIC {
+        return Err(DecodeError::InvalidHeader);
+    }
+    let version = reader.read_u8()?;
+    if version == 0 || version > VERSION {
+        return Err(DecodeError::InvalidHeader);
+    }
+
+    let position_bits = u32::from(reader.read_u8()?);
+    if position_bits > MAX_POSITION_BITS {
+        return Err(DecodeError::InvalidData("Too many position bits"));
+    }
+
+    let vertex_count = reader.read_u32()?;
+    let (normal_count, face_count, face_override_count, corner_override_count) =
+        if version >= VERTEX_NORMALS_VERSION {
+            let face_count = reader.read_u32()?;
+            let face_override_count = reader.read_u32()?;
+            let corner_override_count = reader.read_u32()?;
+            let normal_count = vertex_count
+                .checked_add(face_override_count)
+                .and_then(|count| count.checked_add(corner_override_count))
+                .ok_or(DecodeError::InvalidData("Too many normals"))?;
+
+            (
+                normal_count,
+                face_count,
+                face_override_count,
+                corner_override_count,
+            )
+        } else {
+            let normal_count = reader.read_u32()?;
+            let face_count = reader.read_u32()?;
+
+            (normal_count, face_count, 0, 0)
+        };
+    let quad_face_count = if version >= QUADS_VERSION {
+        reader.read_u32()?
+    } else {
+        0
+    };
+    if vertex_count == 0 || normal_count == 0 || face_count == 0 {
+        return Err(DecodeError::InvalidData(
+            "Mesh has no faces, vertices or normals",
+        ));
+    }
+
+    // Counts are not trusted for preallocation, since corrupted data
+    // could request huge amounts of memory
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+
+    if position_bits == 0 {
+        for _ in 0..vertex_count {
+            let (x, y, z) = (reader.read_f32()?, reader.read_f32()?, reader.read_f32()?);
+            vertices.push(Point3::new(x, y, z));
+        }
+        for _ in 0..normal_count {
+            let (x, y, z) = (reader.read_f32()?, reader.read_f32()?, reader.read_f32()?);
+            normals.push(Vector3::new(x, y, z));
+        }
+    } else {
+        let mut min = [0.0; 3];
+        let mut max = [0.0; 3];
+        for coord in min.iter_mut().chain(max.iter_mut()) {
+            *coord = reader.read_f32()?;
+        }
+
+        let max_quantized = (1u32 << position_bits) - 1;
+        let mut previous = [0u32; 3];
+        for _ in 0..vertex_count {
+            let mut vertex = Point3::origin();
+            for axis in 0..3 {
+                let quantized = reader.read_delta(previous[axis], max_quantized)?;
+                vertex[axis] = dequantize(quantized, min[axis], max[axis], max_quantized);
+                previous[axis] = quantized;
+            }
+            vertices.push(vertex);
+        }
+
+        for _ in 0..normal_count {
+            let (u, v) = (reader.read_u16()?, reader.read_u16()?);
+            normals.push(decode_octahedral([u, v]));
+        }
+    }
+
+    let mut quad_face_indices = Vec::new();
+    let mut previous_face_index = 0;
+    for _ in 0..quad_face_count {
+        let face_index = reader.read_delta(previous_face_index, face_count - 1)?;
+        if !quad_face_indices.is_empty() && face_index <= previous_face_index {
+            return Err(DecodeError::InvalidData(
+                "Quad face indices are not ascending",
+            ));
+        }
+        quad_face_indices.push(face_index);
+        previous_face_index = face_index;
+    }
+    let mut quad_face_indices = quad_face_indices.into_iter().peekable();
+
+    let mut faces = Vec::new();
+    // Normal indices of the face corners of older versions
+    let mut face_normal_indices = Vec::new();
+    let mut previous_vertex_index = 0;
+    let mut previous_normal_index = 0;
+    for face_index in 0..face_count {
+        let is_quad = quad_face_indices.peek() == Some(&face_index);
+        if is_quad {
+            quad_face_indices.next();
+        }
+
+        let mut vertex_indices = [0; 4];
+        let vertex_index_count = if is_quad { 4 } else { 3 };
+        for vertex_index in &mut vertex_indices[..vertex_index_count] {
+            *vertex_index = reader.read_delta(previous_vertex_index, vertex_count - 1)?;
+            previous_vertex_index = *vertex_index;
+        }
+
+        if version < VERTEX_NORMALS_VERSION {
+            let mut normal_indices = [0; 3];
+            for normal_index in &mut normal_indices {
+                *normal_index = reader.read_delta(previous_normal_index, normal_count - 1)?;
+                previous_normal_index = *normal_index;
+            }
+
+            let [n1, n2, n3] = normal_indices;
+            face_normal_indices.push((n1, n2, n3));
+        }
+
+        let [v1, v2, v3, v4] = vertex_indices;
+        if is_quad {
+            faces.push(Face::from(QuadFace {
+                vertices: (v1, v2, v3, v4),
+            }));
+        } else {
+            faces.push(Face::from(TriangleFace {
+                vertices: (v1, v2, v3),
+            }));
+        }
+    }
+
+    let vertex_normals = if version >= VERTEX_NORMALS_VERSION {
+        let mut normals = normals.into_iter();
+        let vertex_normals = normals.by_ref().take(cast_usize(vertex_count)).collect();
+
+        let mut face_overrides = Vec::new();
+        let mut previous_face_index = 0;
+        for normal in normals.by_ref().take(cast_usize(face_override_count)) {
+            let face_index = reader.read_delta(previous_face_index, face_count - 1)?;
+            face_overrides.push((face_index, normal));
+            previous_face_index = face_index;
+        }
+
+        let mut corner_overrides = Vec::new();
+        let mut previous_face_index = 0;
+        let mut previous_vertex_index = 0;
+        for normal in normals.take(cast_usize(corner_override_count)) {
+            let face_index = reader.read_delta(previous_face_index, face_count - 1)?;
+            let vertex_index = reader.read_delta(previous_vertex_index, vertex_count - 1)?;
+            corner_overrides.push((face_index, vertex_index, normal));
+            previous_face_index = face_index;
+            previous_vertex_index = vertex_index;
+        }
+
+        // Unlike renormalizing constructors, this keeps lossless
+        // normals bit for bit
+        VertexNormals::from_parts(vertex_normals, face_overrides, corner_overrides)
+    } else {
+        VertexNormals::from_indexed_normals(
+            faces
+                .iter()
+                .zip(face_normal_indices)
+                .map(|(face, normal_indices)| {
+                    let vertices = face.vertices();
+                    ((vertices[0], vertices[1], vertices[2]), normal_indices)
+                }),
+            &normals,
+            cast_usize(vertex_count),
+        )
+    };
+    if !vertex_normals.is_consistent_with(&faces, cast_usize(vertex_count)) {
+        return Err(DecodeError::InvalidData(
+            "Normal overrides don't refer to face corners",
+        ));
+    }
+
+    // Face indices are checked by the delta reads above
+    let mut mesh =
+        Mesh::from_faces_with_vertices_and_vertex_normals(faces, vertices, vertex_normals);
+
+    let attribute_count = reader.read_u32()?;
+    for _ in 0..attribute_count {
+        let name_len = cast_usize(reader.read_u32()?);
+        let name = String::from_utf8(reader.read_bytes(name_len)?.to_vec())
+            .map_err(|_| DecodeError::InvalidData("Attribute name is not UTF-8"))?;
+
+        let mut values = Vec::new();
+        for _ in 0..vertex_count {
+            values.push(reader.read_f32()?);
+        }
+        mesh.set_vertex_attribute(name, values);
+    }
+
+    if reader.read_u8()? == 1 {
+        let mut colors = Vec::new();
+        for _ in 0..vertex_count {
+            let mut color = [0.0; 3];
+            for channel in &mut color {
+                *channel = if position_bits == 0 {
+                    reader.read_f32()?
+                } else {
+                    f32::from(reader.read_u8()?) / 255.0
+                };
+            }
+            colors.push(color);
+        }
+        mesh.set_vertex_colors(colors);
+    }
+
+    if version >= FACE_MATERIALS_VERSION && reader.read_u8()? == 1 {
+        let mut materials = Vec::new();
+        let mut previous_material = 0;
+        for _ in 0..face_count {
+            let material = reader.read_delta(previous_material, u32::max_value())?;
+            materials.push(material);
+            previous_material = material;
+        }
+        mesh.set_face_materials(materials);
+    }
+
+    Ok(mesh)
+}
+
+/// Serializes meshes embedded in the program as quantized, encoded
+/// and base64 wrapped strings. Meshes serialized as plain JSON by
+/// older versions are still deserialized.
+pub mod embedded {
+    use std::sync::Arc;
+
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::mesh::VertexAttribute;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(mesh: &Arc<Mesh>, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = encode(
+            mesh,
+            Precision::Quantized {
+                position_bits: EMBEDDED_POSITION_BITS,
+            },
+        );
+
+        serializer.serialize_str(&encode_base64(&bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<Mesh>, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum EmbeddedMesh {
+            Encoded(String),
+            Json(Mesh),
+            LegacyJson(LegacyMesh),
+        }
+
+        match EmbeddedMesh::deserialize(deserializer)? {
+            EmbeddedMesh::Encoded(encoded) => {
+                let bytes = decode_base64(&encoded)
+                    .ok_or_else(|| D::Error::custom("Embedded mesh is not valid base64"))?;
+                let mesh = decode(&bytes).map_err(D::Error::custom)?;
+
+                Ok(Arc::new(mesh))
+            }
+            // Unlike decoding, plain deserialization doesn't check
+            // that the faces and per-vertex data fit together
+            EmbeddedMesh::Json(mesh) => {
+                if !mesh.is_consistent() {
+                    return Err(D::Error::custom(
+                        "Embedded mesh refers to missing vertices or normals or has \
+                         per-vertex or per-face data of wrong length",
+                    ));
+                }
+
+                Ok(Arc::new(mesh))
+            }
+            EmbeddedMesh::LegacyJson(legacy_mesh) => {
+                let mesh = legacy_mesh.into_mesh().ok_or_else(|| {
+                    D::Error::custom(
+                        "Embedded mesh refers to missing vertices or normals or has \
+                         per-vertex or per-face data of wrong length",
+                    )
+                })?;
+
+                Ok(Arc::new(mesh))
+            }
+        }
+    }
+
+    /// A mesh serialized as plain JSON by older versions, whose faces
+    /// refer to indexed normals of their corners.
+    #[derive(serde::Deserialize)]
+    struct LegacyMesh {
+        faces: Vec<LegacyFace>,
+        vertices: Vec<Point3<f32>>,
+        normals: Vec<Vector3<f32>>,
+        #[serde(default)]
+        vertex_attributes: Vec<VertexAttribute>,
+        #[serde(default)]
+        vertex_colors: Option<Vec<[f32; 3]>>,
+        #[serde(default)]
+        face_materials: Option<Vec<u32>>,
+    }
+
+    #[derive(serde::Deserialize)]
+    enum LegacyFace {
+        Triangle {
+            vertices: (u32, u32, u32),
+            normals: (u32, u32, u32),
+        },
+    }
+
+    impl LegacyMesh {
+        /// Converts the mesh to the current representation, or returns
+        /// `None` if its faces and per-vertex data don't fit together.
+        fn into_mesh(self) -> Option<Mesh> {
+            let vertex_count = self.vertices.len();
+            let normal_count = self.normals.len();
+            let faces: Vec<_> = self
+                .faces
+                .iter()
+                .map(|LegacyFace::Triangle { vertices, normals }| (*vertices, *normals))
+                .collect();
+
+            let faces_valid = !faces.is_empty()
+                && faces.iter().all(|((v1, v2, v3), (n1, n2, n3))| {
+                    [v1, v2, v3]
+                        .iter()
+                        .all(|vertex_index| cast_usize(**vertex_index) < vertex_count)
+                        && [n1, n2, n3]
+                            .iter()
+                            .all(|normal_index| cast_usize(**normal_index) < normal_count)
+                });
+            let attributes_valid = self
+                .vertex_attributes
+                .iter()
+                .all(|attribute| attribute.values().len() == vertex_count);
+            let colors_valid = self
+                .vertex_colors
+                .as_ref()
+                .map_or(true, |colors| colors.len() == vertex_count);
+            let materials_valid = self
+                .face_materials
+                .as_ref()
+                .map_or(true, |materials| materials.len() == faces.len());
+            if !faces_valid || !attributes_valid || !colors_valid || !materials_valid {
+                return None;
+            }
+
+            let vertex_normals = VertexNormals::from_indexed_normals(
+                faces.iter().copied(),
+                &self.normals,
+                vertex_count,
+            );
+            let mut mesh = Mesh::from_faces_with_vertices_and_vertex_normals(
+                faces.iter().map(|(vertices, _)| {
+                    Face::from(TriangleFace {
+                        vertices: *vertices,
+                    })
+                }),
+                self.vertices,
+                vertex_normals,
+            );
+            for attribute in &self.vertex_attributes {
+                mesh.set_vertex_attribute(attribute.name(), attribute.values().to_vec());
+            }
+            if let Some(colors) = self.vertex_colors {
+                mesh.set_vertex_colors(colors);
+            }
+            if let Some(materials) = self.face_materials {
+                mesh.set_face_materials(materials);
+            }
+
+            Some(mesh)
+        }
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    /// Writes the difference from the previous value as a zigzag
+    /// encoded variable length integer. Differences between nearby
+    /// values take up just a byte or two.
+    fn write_delta(&mut self, previous: u32, value: u32) {
+        let delta = i64::from(value) - i64::from(previous);
+        let mut zigzag = ((delta << 1) ^ (delta >> 63)) as u64;
+
+        while zigzag >= 0x80 {
+            self.bytes.push((zigzag as u8 & 0x7f) | 0x80);
+            zigzag >>= 7;
+        }
+        self.bytes.push(zigzag as u8);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let (read, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(read)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        let mut buffer = [0; 2];
+        buffer.copy_from_slice(self.read_bytes(2)?);
+
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(self.read_bytes(4)?);
+
+        Ok(u32::from_le_bytes(buffer))
+    }
+
+    fn read_f32(&mut self) -> Result<f32, DecodeError> {
+        Ok(f32::from_bits(self.read_u32()?))
+    }
+
+    /// Reads a value written by `Writer::write_delta`, which must not
+    /// exceed `max`.
+    fn read_delta(&mut self, previous: u32, max: u32) -> Result<u32, DecodeError> {
+        let mut zigzag = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift > 63 {
+                return Err(DecodeError::InvalidData(
+                    "Variable length integer is too long",
+                ));
+            }
+
+            zigzag |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        match i64::from(previous).checked_add(delta) {
+            Some(value) if value >= 0 && value <= i64::from(max) => Ok(value as u32),
+            _ => Err(DecodeError::InvalidData("Value out of bounds")),
+        }
+    }
+}
+
+fn quantize(value: f32, min: f32, max: f32, max_quantized: u32) -> u32 {
+    let extent = max - min;
+    if extent <= 0.0 {
+        return 0;
+    }
+
+    let normalized = ((value - min) / extent).max(0.0).min(1.0);
+    (normalized * max_quantized as f32).round() as u32
+}
+
+fn dequantize(quantized: u32, min: f32, max: f32, max_quantized: u32) -> f32 {
+    let normalized = quantized as f32 / max_quantized as f32;
+    min + normalized * (max - min)
+}
+
+/// Maps the unit vector on an octahedron unfolded into a square,
+/// which spreads the quantization error evenly across directions.
+fn encode_octahedral(normal: &Vector3<f32>) -> [u16; 2] {
+    let l1_norm = normal.x.abs() + normal.y.abs() + normal.z.abs();
+    if l1_norm == 0.0 {
+        return [0, 0];
+    }
+
+    let (u, v) = (normal.x / l1_norm, normal.y / l1_norm);
+    let (u, v) = if normal.z < 0.0 {
+        (
+            (1.0 - v.abs()) * sign_not_zero(u),
+            (1.0 - u.abs()) * sign_not_zero(v),
+        )
+    } else {
+        (u, v)
+    };
+
+    let quantize_unit =
+        |value: f32| (((value + 1.0) * 0.5) * f32::from(u16::max_value())).round() as u16;
+    [quantize_unit(u), quantize_unit(v)]
+}
+
+fn decode_octahedral([u, v]: [u16; 2]) -> Vector3<f32> {
+    let dequantize_unit = |value: u16| f32::from(value) / f32::from(u16::max_value()) * 2.0 - 1.0;
+    let (u, v) = (dequantize_unit(u), dequantize_unit(v));
+
+    let z = 1.0 - u.abs() - v.abs();
+    let normal = if z < 0.0 {
+        Vector3::new(
+            (1.0 - v.abs()) * sign_not_zero(u),
+            (1.0 - u.abs()) * sign_not_zero(v),
+            z,
+        )
+    } else {
+        Vector3::new(u, v, z)
+    };
+
+    normal.normalize()
+}
+
+fn sign_not_zero(value: f32) -> f32 {
+    if value < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let indices = [
+            b[0] >> 2,
+            ((b[0] & 0x03) << 4) | (b[1] >> 4),
+            ((b[1] & 0x0f) << 2) | (b[2] >> 6),
+            b[2] & 0x3f,
+        ];
+
+        for (i, index) in indices.iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(char::from(BASE64_ALPHABET[usize::from(*index)]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if encoded.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+    for chunk in encoded.chunks(4) {
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+
+        let mut indices = [0u8; 4];
+        for (index, c) in indices.iter_mut().zip(&chunk[..4 - padding]) {
+            *index = BASE64_ALPHABET.iter().position(|a| a == c)? as u8;
+        }
+
+        let decoded = [
+            (indices[0] << 2) | (indices[1] >> 4),
+            (indices[1] << 4) | (indices[2] >> 2),
+            (indices[2] << 6) | indices[3],
+        ];
+        bytes.extend_from_slice(&decoded[..3 - padding]);
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32;
+    use std::sync::Arc;
+
+    use nalgebra::Rotation3;
+
+    use crate::mesh::{primitive, NormalStrategy};
+
+    use super::*;
+
+    fn sphere() -> Mesh {
+        let mut mesh = primitive::create_uv_sphere(
+            Point3::new(10.0, -5.0, 2.0),
+            Rotation3::identity(),
+            Vector3::new(3.0, 3.0, 3.0),
+            32,
+            32,
+            NormalStrategy::Smooth,
+        );
+
+        let heights: Vec<_> = mesh.vertices().iter().map(|vertex| vertex.z).collect();
+        let colors = heights
+            .iter()
+            .map(|height| [height / 5.0, 0.5, 0.0])
+            .collect();
+        let materials = (0..mesh.faces().len()).map(|i| (i % 3) as u32).collect();
+        mesh.set_vertex_attribute("Height", heights);
+        mesh.set_vertex_colors(colors);
+        mesh.set_face_materials(materials);
+
+        mesh
+    }
+
+    #[test]
+    fn test_mesh_codec_lossless_round_trip() {
+        let mesh = sphere();
+        let decoded = decode(&encode(&mesh, Precision::Lossless)).expect("Failed to decode");
+
+        assert_eq!(decoded, mesh);
+    }
+
+    #[test]
+    fn test_mesh_codec_lossless_round_trip_keeps_normal_overrides() {
+        let box_mesh = primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let cone =
+            primitive::create_cone(Point3::origin(), Rotation3::identity(), 1.0, 2.0, 8, true);
+
+        for mesh in &[box_mesh, cone] {
+            assert!(mesh.vertex_normals().has_overrides());
+
+            let decoded = decode(&encode(mesh, Precision::Lossless)).expect("Failed to decode");
+            assert_eq!(&decoded, mesh);
+        }
+    }
+
+    #[test]
+    fn test_mesh_codec_lossless_round_trip_keeps_quads() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(2.0, 1.0, 1.0),
+        ];
+        let faces = vec![
+            Face::from(TriangleFace::new(1, 4, 5)),
+            Face::from(QuadFace::new(0, 1, 2, 3)),
+            Face::from(TriangleFace::new(1, 5, 2)),
+        ];
+        let mesh = Mesh::from_faces_with_vertices_and_computed_normals(
+            faces,
+            vertices,
+            NormalStrategy::Sharp,
+        );
+
+        let decoded = decode(&encode(&mesh, Precision::Lossless)).expect("Failed to decode");
+
+        assert_eq!(decoded, mesh);
+    }
+
+    #[test]
+    fn test_mesh_codec_decodes_indexed_normals_of_older_versions() {
+        let mut writer = Writer::default();
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.write_u8(FACE_MATERIALS_VERSION);
+        writer.write_u8(0);
+
+        // 3 vertices, 1 normal, 1 face
+        writer.write_u32(3);
+        writer.write_u32(1);
+        writer.write_u32(1);
+        for vertex in &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+            for coord in vertex {
+                writer.write_f32(*coord);
+            }
+        }
+        for coord in &[0.0, 0.0, 1.0] {
+            writer.write_f32(*coord);
+        }
+
+        // Vertex indices 0, 1, 2 and normal indices 0, 0, 0
+        writer.write_delta(0, 0);
+        writer.write_delta(0, 1);
+        writer.write_delta(1, 2);
+        for _ in 0..3 {
+            writer.write_delta(0, 0);
+        }
+
+        // No attributes, colors or materials
+        writer.write_u32(0);
+        writer.write_u8(0);
+        writer.write_u8(0);
+
+        let decoded =
+            decode(&deflate::deflate_bytes(&writer.bytes)).expect("Failed to decode version 2");
+
+        assert_eq!(
+            decoded.faces(),
+            &[Face::from(TriangleFace::new(0, 1, 2))][..]
+        );
+        assert_eq!(decoded.corner_normals(0), [Vector3::z(); 3]);
+    }
+
+    #[test]
+    fn test_mesh_codec_quantized_round_trip_is_within_tolerance() {
+        let mesh = sphere();
+        let decoded = decode(&encode(&mesh, Precision::Quantized { position_bits: 16 }))
+            .expect("Failed to decode");
+
+        assert_eq!(decoded.faces(), mesh.faces());
+        assert_eq!(decoded.vertex_attributes(), mesh.vertex_attributes());
+        assert_eq!(decoded.face_materials(), mesh.face_materials());
+
+        // The sphere is 6 units large, quantized to 2^16 steps
+        for (decoded_vertex, vertex) in decoded.vertices().iter().zip(mesh.vertices()) {
+            assert!(nalgebra::distance(decoded_vertex, vertex) < 0.0001);
+        }
+        let decoded_normals = decoded.vertex_normals().vertex_normals();
+        for (decoded_normal, normal) in decoded_normals
+            .iter()
+            .zip(mesh.vertex_normals().vertex_normals())
+        {
+            assert!(decoded_normal.dot(normal) > 0.9999);
+        }
+
+        let decoded_colors = decoded.vertex_colors().expect("Colors must be decoded");
+        let colors = mesh.vertex_colors().expect("Colors must be set");
+        for (decoded_color, color) in decoded_colors.iter().zip(colors) {
+            for (decoded_channel, channel) in decoded_color.iter().zip(color) {
+                // Channels halfway between two steps are off by exactly
+                // half a step, which rounding may push slightly over
+                assert!(
+                    (decoded_channel - channel.max(0.0).min(1.0)).abs()
+                        <= 0.5 / 255.0 + f32::EPSILON
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mesh_codec_quantized_is_much_smaller_than_json() {
+        let mesh = sphere();
+        let json = serde_json::to_vec(&mesh).expect("Failed to serialize mesh");
+        let encoded = encode(
+            &mesh,
+            Precision::Quantized {
+                position_bits: EMBEDDED_POSITION_BITS,
+            },
+        );
+
+        assert!(encoded.len() * 4 < json.len());
+    }
+
+    #[test]
+    fn test_mesh_codec_decode_rejects_corrupted_data() {
+        let mut encoded = encode(&sphere(), Precision::Lossless);
+        encoded.truncate(encoded.len() / 2);
+
+        assert!(decode(&encoded).is_err());
+        assert!(decode(&deflate::deflate_bytes(b"not a mesh")).is_err());
+    }
+
+    #[test]
+    fn test_mesh_codec_base64_round_trip() {
+        for len in 0..8 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 97 + 251) as u8).collect();
+            let encoded = encode_base64(&bytes);
+
+            assert_eq!(encoded.len() % 4, 0);
+            assert_eq!(decode_base64(&encoded), Some(bytes));
+        }
+
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(decode_base64("TQ=="), Some(b"M".to_vec()));
+        assert_eq!(decode_base64("T!=="), None);
+    }
+
+    #[test]
+    fn test_mesh_codec_embedded_reads_encoded_and_json_meshes() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Embedding(#[serde(with = "embedded")] Arc<Mesh>);
+
+        let mesh = Arc::new(sphere());
+        let encoded_json = serde_json::to_string(&Embedding(Arc::clone(&mesh)))
+            .expect("Failed to serialize embedded mesh");
+        assert!(encoded_json.starts_with('"'));
+
+        let Embedding(decoded) =
+            serde_json::from_str(&encoded_json).expect("Failed to deserialize embedded mesh");
+        assert_eq!(decoded.faces(), mesh.faces());
+
+        let plain_json = serde_json::to_string(&*mesh).expect("Failed to serialize mesh");
+        let Embedding(decoded) =
+            serde_json::from_str(&plain_json).expect("Failed to deserialize plain mesh");
+        assert_eq!(decoded, mesh);
+    }
+
+    #[test]
+    fn test_mesh_codec_embedded_reads_json_mesh_with_indexed_normals() {
+        #[derive(serde::Deserialize)]
+        struct Embedding(#[serde(with = "embedded")] Arc<Mesh>);
+
+        let legacy_json = serde_json::json!({
+            "faces": [
+                { "Triangle": { "vertices": [0, 1, 2], "normals": [0, 0, 1] } },
+                { "Triangle": { "vertices": [0, 2, 3], "normals": [0, 1, 1] } },
+            ],
+            "vertices": [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            "normals": [[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]],
+            "vertex_colors": null,
+            "face_materials": [0, 1],
+        });
+
+        let Embedding(decoded) =
+            serde_json::from_value(legacy_json).expect("Failed to deserialize legacy mesh");
+
+        assert_eq!(decoded.vertices().len(), 4);
+        assert_eq!(decoded.face_materials(), Some(&[0, 1][..]));
+        assert_eq!(
+            decoded.corner_normals(0),
+            [Vector3::z(), Vector3::z(), -Vector3::z()],
+        );
+        assert_eq!(
+            decoded.corner_normals(1),
+            [Vector3::z(), -Vector3::z(), -Vector3::z()]
+        );
+    }
+
+    #[test]
+    fn test_mesh_codec_embedded_rejects_inconsistent_json_mesh() {
+        #[derive(serde::Deserialize)]
+        struct Embedding(#[serde(with = "embedded")] Arc<Mesh>);
+
+        let mut plain_json = serde_json::to_value(&sphere()).expect("Failed to serialize mesh");
+        plain_json["face_materials"] = serde_json::json!([0, 1]);
+
+        assert!(serde_json::from_value::<Embedding>(plain_json).is_err());
+    }
+}
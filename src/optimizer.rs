@@ -0,0 +1,812 @@
+use std::cmp::Ordering;
+use std::f32;
+
+use crate::convert::clamp_cast_i32_to_u32;
+use crate::interpreter::ast::{self, Prog, VarIdent};
+use crate::interpreter::{Interpreter, ParamRefinement, Value};
+use crate::mesh::{analysis, Mesh};
+
+/// Whether the optimizer should look for the smallest or the largest
+/// score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Minimize,
+    Maximize,
+}
+
+/// The strategy used to explore the parameter space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Samples the parameter space uniformly.
+    RandomSearch,
+    /// Walks the parameter space, taking smaller steps and accepting
+    /// worse candidates less often as the optimization progresses.
+    SimulatedAnnealing,
+}
+
+impl Method {
+    pub const ALL: [Method; 2] = [Method::RandomSearch, Method::SimulatedAnnealing];
+    pub const NAMES: [&'static str; 2] = ["Random Search", "Simulated Annealing"];
+}
+
+/// A numeric property of a mesh value to be optimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Volume,
+    SurfaceArea,
+    VertexCount,
+    BoundingBoxDiagonal,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [
+        Metric::Volume,
+        Metric::SurfaceArea,
+        Metric::VertexCount,
+        Metric::BoundingBoxDiagonal,
+    ];
+    pub const NAMES: [&'static str; 4] = [
+        "Volume",
+        "Surface Area",
+        "Vertex Count",
+        "Bounding Box Diagonal",
+    ];
+
+    /// Measures the metric of a mesh.
+    pub fn measure(self, mesh: &Mesh) -> f32 {
+        match self {
+            Metric::Volume => analysis::signed_volume(mesh).abs(),
+            Metric::SurfaceArea => analysis::surface_area(mesh),
+            Metric::VertexCount => mesh.vertices().len() as f32,
+            Metric::BoundingBoxDiagonal => mesh.bounding_box().diagonal().norm(),
+        }
+    }
+
+    /// Measures the metric of a mesh or the sum of metrics of all
+    /// meshes in a mesh array. Returns `None` for other values.
+    pub fn measure_value(self, value: &Value) -> Option<f32> {
+        match value {
+            Value::Mesh(mesh) => Some(self.measure(mesh)),
+            Value::MeshArray(mesh_array) => {
+                Some(mesh_array.iter().map(|mesh| self.measure(mesh)).sum())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The inclusive range a parameter is varied within.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub goal: Goal,
+    pub method: Method,
+    /// The number of evaluated candidates.
+    pub iterations: u32,
+    /// The number of best candidates to return.
+    pub best_count: usize,
+    pub seed: u64,
+}
+
+/// A set of parameter values and the score they achieved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub values: Vec<f32>,
+    pub score: f32,
+}
+
+/// Searches for parameter values within the `ranges` that achieve the
+/// best score. The score for a set of parameter values is computed by
+/// `evaluate`, which can return `None` if the values could not be
+/// scored.
+///
+/// Returns up to `options.best_count` best distinct candidates, the
+/// best first.
+pub fn optimize<F>(ranges: &[ParamRange], options: Options, mut evaluate: F) -> Vec<Candidate>
+where
+    F: FnMut(&[f32]) -> Option<f32>,
+{
+    let mut rng = Rng::new(options.seed);
+    let mut best: Vec<Candidate> = Vec::with_capacity(options.best_count + 1);
+
+    // Whether score `a` is better than score `b`
+    let is_better = |a: f32, b: f32| match options.goal {
+        Goal::Minimize => a < b,
+        Goal::Maximize => a > b,
+    };
+
+    let mut current: Option<Candidate> = None;
+
+    for iteration in 0..options.iterations {
+        let values: Vec<f32> = match (options.method, &current) {
+            (Method::SimulatedAnnealing, Some(current)) => {
+                let temperature = 1.0 - iteration as f32 / options.iterations as f32;
+                ranges
+                    .iter()
+                    .zip(&current.values)
+                    .map(|(range, value)| {
+                        let step = (range.max - range.min) * 0.25 * temperature;
+                        let offset = (rng.next_f32() * 2.0 - 1.0) * step;
+                        clamp_to_range(value + offset, *range)
+                    })
+                    .collect()
+            }
+            _ => ranges
+                .iter()
+                .map(|range| range.min + rng.next_f32() * (range.max - range.min))
+                .collect(),
+        };
+
+        let score = match evaluate(&values) {
+            Some(score) if score.is_finite() => score,
+            _ => continue,
+        };
+
+        let candidate = Candidate { values, score };
+
+        if options.method == Method::SimulatedAnnealing {
+            let accept = match &current {
+                Some(current) if !is_better(score, current.score) => {
+                    let temperature = 1.0 - iteration as f32 / options.iterations as f32;
+                    let scale = f32::max(current.score.abs(), f32::EPSILON);
+                    let delta = (score - current.score).abs() / scale;
+                    rng.next_f32() < (-delta / f32::max(temperature, 0.001)).exp()
+                }
+                _ => true,
+            };
+
+            if accept {
+                current = Some(candidate.clone());
+            }
+        }
+
+        if !best.iter().any(|other| other.values == candidate.values) {
+            best.push(candidate);
+            best.sort_by(|a, b| {
+                if is_better(a.score, b.score) {
+                    Ordering::Less
+                } else if is_better(b.score, a.score) {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            });
+            best.truncate(options.best_count);
+        }
+    }
+
+    best
+}
+
+/// A set of parameter values and the scores they achieved in each
+/// objective of a multi-objective study.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiCandidate {
+    pub values: Vec<f32>,
+    pub scores: Vec<f32>,
+}
+
+/// Returns whether scores `a` dominate scores `b`, i.e. `a` is not
+/// worse than `b` in any objective and better in at least one.
+pub fn dominates(a: &[f32], b: &[f32], goals: &[Goal]) -> bool {
+    let mut better_in_any = false;
+    for ((a, b), goal) in a.iter().zip(b).zip(goals) {
+        let (better, worse) = match goal {
+            Goal::Minimize => (a < b, a > b),
+            Goal::Maximize => (a > b, a < b),
+        };
+
+        if worse {
+            return false;
+        }
+        better_in_any |= better;
+    }
+
+    better_in_any
+}
+
+/// Samples `iterations` sets of parameter values within the `ranges`
+/// and returns the candidates not dominated by any other candidate,
+/// i.e. the Pareto front of the sampled candidates. The scores for a
+/// set of parameter values are computed by `evaluate`, one for each
+/// of the `goals`, which can return `None` if the values could not be
+/// scored.
+///
+/// The returned candidates are sorted by their first score.
+pub fn explore_pareto_front<F>(
+    ranges: &[ParamRange],
+    goals: &[Goal],
+    iterations: u32,
+    seed: u64,
+    mut evaluate: F,
+) -> Vec<MultiCandidate>
+where
+    F: FnMut(&[f32]) -> Option<Vec<f32>>,
+{
+    let mut rng = Rng::new(seed);
+    let mut front: Vec<MultiCandidate> = Vec::new();
+
+    for _ in 0..iterations {
+        let values: Vec<f32> = ranges
+            .iter()
+            .map(|range| range.min + rng.next_f32() * (range.max - range.min))
+            .collect();
+
+        let scores = match evaluate(&values) {
+            Some(scores) if scores.iter().all(|score| score.is_finite()) => scores,
+            _ => continue,
+        };
+        assert_eq!(
+            scores.len(),
+            goals.len(),
+            "There must be a score for each goal",
+        );
+
+        let dominated_or_duplicate = front
+            .iter()
+            .any(|other| other.scores == scores || dominates(&other.scores, &scores, goals));
+        if !dominated_or_duplicate {
+            front.retain(|other| !dominates(&scores, &other.scores, goals));
+            front.push(MultiCandidate { values, scores });
+        }
+    }
+
+    front.sort_by(|a, b| {
+        a.scores[0]
+            .partial_cmp(&b.scores[0])
+            .unwrap_or(Ordering::Equal)
+    });
+
+    front
+}
+
+/// Evaluates `evaluate` for `steps` values spread evenly over the
+/// range, including its bounds. Returns the values and their results,
+/// skipping values that `evaluate` could not measure.
+pub fn sweep<F>(range: ParamRange, steps: u32, mut evaluate: F) -> Vec<[f32; 2]>
+where
+    F: FnMut(f32) -> Option<f32>,
+{
+    (0..steps)
+        .filter_map(|step| {
+            let value = if steps == 1 {
+                range.min
+            } else {
+                let t = step as f32 / (steps - 1) as f32;
+                range.min + t * (range.max - range.min)
+            };
+
+            evaluate(value).map(|result| [value, result])
+        })
+        .collect()
+}
+
+/// A parameter of a program statement to be varied by the optimizer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizedParam {
+    pub stmt_index: usize,
+    pub arg_index: usize,
+    /// The component of a vector parameter, 0 for scalars.
+    pub component: usize,
+    pub range: ParamRange,
+}
+
+/// A request to optimize the parameters of the interpreter's program.
+#[derive(Debug, Clone)]
+pub struct OptimizationRequest {
+    pub params: Vec<OptimizedParam>,
+    pub target_var: VarIdent,
+    pub metric: Metric,
+    pub options: Options,
+}
+
+/// A measured property of a variable, which is to be minimized or
+/// maximized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Objective {
+    pub target_var: VarIdent,
+    pub metric: Metric,
+    pub goal: Goal,
+}
+
+/// A request to explore the trade-offs between multiple objectives by
+/// varying the parameters of the interpreter's program.
+#[derive(Debug, Clone)]
+pub struct ParetoRequest {
+    pub params: Vec<OptimizedParam>,
+    pub objectives: Vec<Objective>,
+    pub iterations: u32,
+    pub seed: u64,
+}
+
+/// A request to record a metric of a variable while sweeping a
+/// parameter of the interpreter's program.
+#[derive(Debug, Clone)]
+pub struct PlotRequest {
+    pub param: OptimizedParam,
+    pub target_var: VarIdent,
+    pub metric: Metric,
+    pub steps: u32,
+}
+
+/// The metric of a variable recorded for the values of a swept
+/// parameter.
+#[derive(Debug, Clone)]
+pub struct Plot {
+    pub param: OptimizedParam,
+    pub target_var: VarIdent,
+    pub metric: Metric,
+    /// The parameter values and the measured metrics, ordered by the
+    /// parameter value.
+    pub points: Vec<[f32; 2]>,
+}
+
+/// A program on the Pareto front and its scores, one for each
+/// objective.
+#[derive(Debug, Clone)]
+pub struct ParetoPoint {
+    pub prog: Prog,
+    pub scores: Vec<f32>,
+}
+
+/// The result of a multi-objective study.
+#[derive(Debug, Clone)]
+pub struct ParetoFront {
+    pub objectives: Vec<Objective>,
+    pub points: Vec<ParetoPoint>,
+}
+
+/// Optimizes the literal parameters of the interpreter's current
+/// program by repeatedly interpreting it with different values.
+///
+/// Returns the programs with the best parameter values and their
+/// scores, the best first. The interpreter's program is restored
+/// afterwards.
+///
+/// # Panics
+/// Panics if an optimized parameter is not a numeric literal.
+pub fn optimize_prog(
+    interpreter: &mut Interpreter,
+    request: &OptimizationRequest,
+) -> Vec<(Prog, f32)> {
+    let original_prog = interpreter.prog().clone();
+    let ranges: Vec<_> = request.params.iter().map(|param| param.range).collect();
+    let measurements = [(request.target_var, request.metric)];
+
+    let best = optimize(&ranges, request.options, |values| {
+        let scores = measure_prog_with_values(
+            interpreter,
+            &original_prog,
+            &request.params,
+            values,
+            &measurements,
+        )?;
+
+        Some(scores[0])
+    });
+
+    restore_prog_stmts(interpreter, &original_prog, &request.params);
+
+    best.into_iter()
+        .map(|candidate| {
+            let prog = prog_with_values(&original_prog, &request.params, &candidate.values);
+            (prog, candidate.score)
+        })
+        .collect()
+}
+
+/// Explores the Pareto front of the objectives by repeatedly
+/// interpreting the interpreter's current program with different
+/// literal parameter values. The interpreter's program is restored
+/// afterwards.
+///
+/// # Panics
+/// Panics if an optimized parameter is not a numeric literal.
+pub fn explore_pareto_front_prog(
+    interpreter: &mut Interpreter,
+    request: &ParetoRequest,
+) -> ParetoFront {
+    let original_prog = interpreter.prog().clone();
+    let ranges: Vec<_> = request.params.iter().map(|param| param.range).collect();
+    let goals: Vec<_> = request
+        .objectives
+        .iter()
+        .map(|objective| objective.goal)
+        .collect();
+    let measurements: Vec<_> = request
+        .objectives
+        .iter()
+        .map(|objective| (objective.target_var, objective.metric))
+        .collect();
+
+    let front = explore_pareto_front(
+        &ranges,
+        &goals,
+        request.iterations,
+        request.seed,
+        |values| {
+            measure_prog_with_values(
+                interpreter,
+                &original_prog,
+                &request.params,
+                values,
+                &measurements,
+            )
+        },
+    );
+
+    restore_prog_stmts(interpreter, &original_prog, &request.params);
+
+    let points = front
+        .into_iter()
+        .map(|candidate| ParetoPoint {
+            prog: prog_with_values(&original_prog, &request.params, &candidate.values),
+            scores: candidate.scores,
+        })
+        .collect();
+
+    ParetoFront {
+        objectives: request.objectives.clone(),
+        points,
+    }
+}
+
+/// Records the metric of the target variable for evenly spread values
+/// of the parameter by repeatedly interpreting the interpreter's
+/// current program. Values for which the program fails are skipped.
+/// The interpreter's program is restored afterwards.
+///
+/// # Panics
+/// Panics if the parameter is not a numeric literal.
+pub fn plot_prog(interpreter: &mut Interpreter, request: &PlotRequest) -> Plot {
+    let original_prog = interpreter.prog().clone();
+    let params = [request.param];
+    let measurements = [(request.target_var, request.metric)];
+
+    let points = sweep(request.param.range, request.steps, |value| {
+        let scores = measure_prog_with_values(
+            interpreter,
+            &original_prog,
+            &params,
+            &[value],
+            &measurements,
+        )?;
+
+        Some(scores[0])
+    });
+
+    restore_prog_stmts(interpreter, &original_prog, &params);
+
+    Plot {
+        param: request.param,
+        target_var: request.target_var,
+        metric: request.metric,
+        points,
+    }
+}
+
+/// Interprets the program with the parameter values and measures the
+/// metrics of the variables. Returns `None` if the program fails or a
+/// variable can not be measured.
+///
+/// Only the statements with optimized parameters are replaced in the
+/// interpreter, so that it can reuse cached values of the others.
+fn measure_prog_with_values(
+    interpreter: &mut Interpreter,
+    original_prog: &Prog,
+    params: &[OptimizedParam],
+    values: &[f32],
+    measurements: &[(VarIdent, Metric)],
+) -> Option<Vec<f32>> {
+    let prog = prog_with_values(original_prog, params, values);
+    for stmt_index in optimized_stmt_indices(params) {
+        interpreter.set_prog_stmt_at(stmt_index, prog.stmts()[stmt_index].clone());
+    }
+
+    let interpret_value = interpreter.interpret().result.ok()?;
+    measurements
+        .iter()
+        .map(|(target_var, metric)| {
+            let (_, value) = interpret_value
+                .used_values
+                .iter()
+                .chain(interpret_value.unused_values.iter())
+                .find(|(var_ident, _)| var_ident == target_var)?;
+
+            metric.measure_value(value)
+        })
+        .collect()
+}
+
+fn restore_prog_stmts(
+    interpreter: &mut Interpreter,
+    original_prog: &Prog,
+    params: &[OptimizedParam],
+) {
+    for stmt_index in optimized_stmt_indices(params) {
+        interpreter.set_prog_stmt_at(stmt_index, original_prog.stmts()[stmt_index].clone());
+    }
+}
+
+fn optimized_stmt_indices(params: &[OptimizedParam]) -> Vec<usize> {
+    let mut stmt_indices: Vec<_> = params.iter().map(|param| param.stmt_index).collect();
+    stmt_indices.sort();
+    stmt_indices.dedup();
+
+    stmt_indices
+}
+
+/// A numeric component of a literal parameter, which can be varied by
+/// the optimizer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericComponent {
+    /// The component of a vector parameter, 0 for scalars.
+    pub component: usize,
+    pub value: f32,
+    pub min_value: Option<f32>,
+    pub max_value: Option<f32>,
+}
+
+impl NumericComponent {
+    /// Returns the range given by the parameter refinement. Unbounded
+    /// sides extend from the current value by its magnitude, but at
+    /// least by one.
+    pub fn default_range(&self) -> ParamRange {
+        let extent = f32::max(self.value.abs(), 1.0);
+        ParamRange {
+            min: self.min_value.unwrap_or(self.value - extent),
+            max: self.max_value.unwrap_or(self.value + extent),
+        }
+    }
+}
+
+/// Returns the numeric components of a literal parameter that can be
+/// optimized. Returns an empty vector for non-numeric parameters.
+pub fn numeric_components(
+    refinement: &ParamRefinement,
+    lit: &ast::LitExpr,
+) -> Vec<NumericComponent> {
+    let component = |component, value, min_value, max_value| NumericComponent {
+        component,
+        value,
+        min_value,
+        max_value,
+    };
+
+    match (refinement, lit) {
+        (ParamRefinement::Int(r), ast::LitExpr::Int(value)) => vec![component(
+            0,
+            *value as f32,
+            r.min_value.map(|min| min as f32),
+            r.max_value.map(|max| max as f32),
+        )],
+        (ParamRefinement::Uint(r), ast::LitExpr::Uint(value)) => vec![component(
+            0,
+            *value as f32,
+            r.min_value.map(|min| min as f32),
+            r.max_value.map(|max| max as f32),
+        )],
+        (ParamRefinement::Float(r), ast::LitExpr::Float(value)) => {
+            vec![component(0, *value, r.min_value, r.max_value)]
+        }
+        (ParamRefinement::Float2(r), ast::LitExpr::Float2(value)) => vec![
+            component(0, value[0], r.min_value_x, r.max_value_x),
+            component(1, value[1], r.min_value_y, r.max_value_y),
+        ],
+        (ParamRefinement::Float3(r), ast::LitExpr::Float3(value)) => vec![
+            component(0, value[0], r.min_value_x, r.max_value_x),
+            component(1, value[1], r.min_value_y, r.max_value_y),
+            component(2, value[2], r.min_value_z, r.max_value_z),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+pub fn prog_with_values(prog: &Prog, params: &[OptimizedParam], values: &[f32]) -> Prog {
+    let mut prog = prog.clone();
+
+    for (param, value) in params.iter().zip(values) {
+        let stmt = prog.stmts()[param.stmt_index].clone();
+        match stmt {
+            ast::Stmt::VarDecl(var_decl) => {
+                let init_expr = var_decl.init_expr();
+                let lit = match &init_expr.args()[param.arg_index] {
+                    ast::Expr::Lit(lit) => with_component(lit, param.component, *value),
+                    ast::Expr::Var(_) => panic!("Only literal parameters can be optimized"),
+                };
+
+                let new_var_decl = var_decl.clone_with_init_expr(
+                    init_expr.clone_with_arg_at(param.arg_index, ast::Expr::Lit(lit)),
+                );
+                prog.set_stmt_at(param.stmt_index, ast::Stmt::VarDecl(new_var_decl));
+            }
+        }
+    }
+
+    prog
+}
+
+fn with_component(lit: &ast::LitExpr, component: usize, value: f32) -> ast::LitExpr {
+    match lit {
+        ast::LitExpr::Int(_) => ast::LitExpr::Int(value.round() as i32),
+        ast::LitExpr::Uint(_) => ast::LitExpr::Uint(clamp_cast_i32_to_u32(value.round() as i32)),
+        ast::LitExpr::Float(_) => ast::LitExpr::Float(value),
+        ast::LitExpr::Float2(float2) => {
+            let mut float2 = *float2;
+            float2[component] = value;
+            ast::LitExpr::Float2(float2)
+        }
+        ast::LitExpr::Float3(float3) => {
+            let mut float3 = *float3;
+            float3[component] = value;
+            ast::LitExpr::Float3(float3)
+        }
+        _ => panic!("Only numeric literal parameters can be optimized"),
+    }
+}
+
+fn clamp_to_range(value: f32, range: ParamRange) -> f32 {
+    f32::min(f32::max(value, range.min), range.max)
+}
+
+/// A small deterministic xorshift pseudo-random number generator, so
+/// that optimizations are reproducible with the same seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Xorshift must not be seeded with zero
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+
+        x
+    }
+
+    /// Returns a number in the `0..1` range.
+    fn next_f32(&mut self) -> f32 {
+        const PRECISION: u64 = 1 << 24;
+        (self.next_u64() >> 40) as f32 / PRECISION as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(goal: Goal, method: Method) -> Options {
+        Options {
+            goal,
+            method,
+            iterations: 500,
+            best_count: 3,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn test_optimize_random_search_minimizes() {
+        let ranges = [ParamRange {
+            min: -10.0,
+            max: 10.0,
+        }];
+
+        let best = optimize(
+            &ranges,
+            options(Goal::Minimize, Method::RandomSearch),
+            |values| Some((values[0] - 3.0).powi(2)),
+        );
+
+        assert_eq!(best.len(), 3);
+        assert!((best[0].values[0] - 3.0).abs() < 0.2);
+        assert!(best[0].score <= best[1].score);
+        assert!(best[1].score <= best[2].score);
+    }
+
+    #[test]
+    fn test_optimize_simulated_annealing_maximizes_within_ranges() {
+        let ranges = [
+            ParamRange { min: 0.0, max: 2.0 },
+            ParamRange { min: 0.0, max: 5.0 },
+        ];
+
+        let best = optimize(
+            &ranges,
+            options(Goal::Maximize, Method::SimulatedAnnealing),
+            |values| Some(values[0] * values[1]),
+        );
+
+        assert!(best[0].score > 9.0);
+        for candidate in &best {
+            assert!(candidate.values[0] >= 0.0 && candidate.values[0] <= 2.0);
+            assert!(candidate.values[1] >= 0.0 && candidate.values[1] <= 5.0);
+        }
+    }
+
+    #[test]
+    fn test_optimize_skips_unscored_candidates() {
+        let ranges = [ParamRange { min: 0.0, max: 1.0 }];
+
+        let best = optimize(
+            &ranges,
+            options(Goal::Minimize, Method::RandomSearch),
+            |_| None,
+        );
+
+        assert!(best.is_empty());
+    }
+
+    #[test]
+    fn test_sweep_spreads_values_over_range_and_skips_unmeasured() {
+        let range = ParamRange {
+            min: -1.0,
+            max: 1.0,
+        };
+
+        let points = sweep(
+            range,
+            5,
+            |value| {
+                if value < 0.0 {
+                    None
+                } else {
+                    Some(value * 2.0)
+                }
+            },
+        );
+
+        assert_eq!(points, vec![[0.0, 0.0], [0.5, 1.0], [1.0, 2.0]]);
+        assert_eq!(sweep(range, 1, Some), vec![[-1.0, -1.0]]);
+    }
+
+    #[test]
+    fn test_dominates() {
+        let goals = [Goal::Minimize, Goal::Maximize];
+
+        assert!(dominates(&[1.0, 2.0], &[2.0, 2.0], &goals));
+        assert!(dominates(&[1.0, 3.0], &[2.0, 2.0], &goals));
+        assert!(!dominates(&[1.0, 1.0], &[2.0, 2.0], &goals));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0], &goals));
+    }
+
+    #[test]
+    fn test_explore_pareto_front_contains_only_non_dominated_candidates() {
+        let ranges = [ParamRange { min: 0.0, max: 1.0 }];
+        let goals = [Goal::Minimize, Goal::Minimize];
+
+        // The objectives conflict, so every sampled value lies on the
+        // front
+        let front = explore_pareto_front(&ranges, &goals, 50, 7, |values| {
+            Some(vec![values[0], 1.0 - values[0]])
+        });
+
+        assert!(front.len() > 1);
+        for candidate in &front {
+            for other in &front {
+                assert!(!dominates(&other.scores, &candidate.scores, &goals));
+            }
+        }
+        for pair in front.windows(2) {
+            assert!(pair[0].scores[0] <= pair[1].scores[0]);
+        }
+
+        // The objectives agree, so only the best value remains
+        let front = explore_pareto_front(&ranges, &goals, 50, 7, |values| {
+            Some(vec![values[0], 2.0 * values[0]])
+        });
+
+        assert_eq!(front.len(), 1);
+        assert!(front[0].values[0] < 0.1);
+    }
+}
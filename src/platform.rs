@@ -1,3 +1,138 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::io::{self, Write};
+use std::ops::Deref;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Access to files of the platform.
+///
+/// Abstracted, so that platforms without a native file system, such as
+/// browsers, can provide files from elsewhere or refuse to.
+#[cfg_attr(test, automock)]
+pub trait FileSystem {
+    /// Returns the contents of the file at `path`.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Returns the contents of the file at `path` mapped into memory,
+    /// if the platform supports it, so that large files are paged in
+    /// as they are read instead of being copied up front.
+    ///
+    /// The file must not be modified while the contents are alive.
+    fn read_mapped(&self, path: &Path) -> io::Result<FileContents>;
+
+    /// Returns the timestamp of the last modification of the file at
+    /// `path`.
+    fn modified(&self, path: &Path) -> io::Result<SystemTime>;
+
+    /// Creates or truncates the file at `path` and writes the
+    /// `contents` to it.
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// Creates or truncates the file at `path` and returns a buffered
+    /// writer to it, so that large contents can be written as they
+    /// are produced, without building them in memory first.
+    ///
+    /// The writer must be flushed to find out whether the last writes
+    /// succeeded.
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write>>;
+}
+
+/// The contents of a file returned by `FileSystem::read_mapped`.
+#[derive(Debug)]
+pub enum FileContents {
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(memmap::Mmap),
+    Buffered(Vec<u8>),
+}
+
+impl Deref for FileContents {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            FileContents::Mapped(mmap) => mmap,
+            FileContents::Buffered(buffer) => buffer,
+        }
+    }
+}
+
+/// The file system of the platform the application was built for.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlatformFileSystem;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSystem for PlatformFileSystem {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn read_mapped(&self, path: &Path) -> io::Result<FileContents> {
+        let file = fs::File::open(path)?;
+        // Empty files can't be mapped
+        if file.metadata()?.len() == 0 {
+            return Ok(FileContents::Buffered(Vec::new()));
+        }
+
+        // Safety: Reading the mapping is undefined behavior, if
+        // another process truncates or modifies the file meanwhile.
+        // We can't prevent that, but the contents are only kept for
+        // the duration of a single import.
+        let mmap = unsafe { memmap::Mmap::map(&file)? };
+        Ok(FileContents::Mapped(mmap))
+    }
+
+    fn modified(&self, path: &Path) -> io::Result<SystemTime> {
+        fs::metadata(path)?.modified()
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+        let file = fs::File::create(path)?;
+        Ok(Box::new(io::BufWriter::new(file)))
+    }
+}
+
+// Browsers don't provide access to files by path. Programs embedding
+// their meshes still work.
+#[cfg(target_arch = "wasm32")]
+impl FileSystem for PlatformFileSystem {
+    fn read(&self, _path: &Path) -> io::Result<Vec<u8>> {
+        Err(unsupported())
+    }
+
+    fn read_mapped(&self, _path: &Path) -> io::Result<FileContents> {
+        Err(unsupported())
+    }
+
+    fn modified(&self, _path: &Path) -> io::Result<SystemTime> {
+        Err(unsupported())
+    }
+
+    fn write(&self, _path: &Path, _contents: &[u8]) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    fn create(&self, _path: &Path) -> io::Result<Box<dyn Write>> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "Files are not available on this platform",
+    )
+}
+
 #[cfg(target_os = "windows")]
 pub mod windows {
     use std::io;
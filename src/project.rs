@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::constants::{self, Constant, ConstantBinding};
+use crate::interpreter::ast::{Expr, FuncIdent, Prog, Stmt, VarIdent};
+use crate::interpreter::Func;
+use crate::platform::{FileSystem, PlatformFileSystem};
+use crate::recorder::Macro;
+use crate::units::LengthUnit;
+
+/// Version of the project file format. Bumped on incompatible changes.
+pub const PROJECT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    UnknownFunc(usize, FuncIdent),
+    ArgCountMismatch(usize),
+    InvalidVarIdent(usize),
+    InvalidVarReference(usize, VarIdent),
+    InvalidConstantBinding(usize),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectError::Io(err) => write!(f, "Failed to access the project file: {}", err),
+            ProjectError::Parse(err) => write!(f, "Failed to parse the project file: {}", err),
+            ProjectError::UnsupportedVersion(version) => write!(
+                f,
+                "Project file version {} is not supported (expected {})",
+                version, PROJECT_VERSION,
+            ),
+            ProjectError::UnknownFunc(index, func_ident) => write!(
+                f,
+                "Operation {} uses an unknown function {}",
+                index + 1,
+                func_ident,
+            ),
+            ProjectError::ArgCountMismatch(index) => write!(
+                f,
+                "Operation {} has a wrong number of parameters",
+                index + 1,
+            ),
+            ProjectError::InvalidVarIdent(index) => {
+                write!(f, "Operation {} has an invalid identifier", index + 1)
+            }
+            ProjectError::InvalidVarReference(index, var_ident) => write!(
+                f,
+                "Operation {} refers to {}, which is not a preceding operation",
+                index + 1,
+                var_ident,
+            ),
+            ProjectError::InvalidConstantBinding(index) => {
+                write!(f, "Operation {} has an invalid constant binding", index + 1,)
+            }
+        }
+    }
+}
+
+impl error::Error for ProjectError {}
+
+impl From<io::Error> for ProjectError {
+    fn from(err: io::Error) -> Self {
+        ProjectError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ProjectError {
+    fn from(err: serde_json::Error) -> Self {
+        ProjectError::Parse(err)
+    }
+}
+
+/// A saved program variant.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProjectVariant {
+    pub name: String,
+    pub prog: Prog,
+}
+
+/// Everything needed to rebuild the interpreter state of a session:
+/// the pipeline with its parameter values (including referenced file
+/// paths), the saved variants and the pipeline settings.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub prog: Prog,
+    pub variants: Vec<ProjectVariant>,
+    pub manual_update_vars: Vec<VarIdent>,
+    pub auto_interpret: bool,
+    // Projects saved before macros existed don't contain any
+    #[serde(default)]
+    pub macros: Vec<Macro>,
+    #[serde(default)]
+    pub constants: Vec<Constant>,
+    #[serde(default)]
+    pub constant_bindings: Vec<ConstantBinding>,
+    #[serde(default)]
+    pub unit: LengthUnit,
+}
+
+/// Writes the project to a JSON file at `path`.
+#[cfg(any(test, feature = "gui"))]
+pub fn save_project<P: AsRef<Path>>(path: P, project: &Project) -> Result<(), ProjectError> {
+    let contents = serde_json::to_vec_pretty(project)?;
+    PlatformFileSystem.write(path.as_ref(), &contents)?;
+
+    Ok(())
+}
+
+/// Reads a project from a JSON file at `path` and checks that its
+/// programs can be run with the functions in `function_table`.
+pub fn load_project<P: AsRef<Path>>(
+    path: P,
+    function_table: &BTreeMap<FuncIdent, Box<dyn Func>>,
+) -> Result<Project, ProjectError> {
+    let contents = PlatformFileSystem.read(path.as_ref())?;
+    let project: Project = serde_json::from_slice(&contents)?;
+
+    if project.version != PROJECT_VERSION {
+        return Err(ProjectError::UnsupportedVersion(project.version));
+    }
+
+    validate_stmts(project.prog.stmts(), 0, function_table)?;
+    for variant in &project.variants {
+        validate_stmts(variant.prog.stmts(), 0, function_table)?;
+    }
+    for recorded_macro in &project.macros {
+        validate_stmts(
+            &recorded_macro.stmts,
+            recorded_macro.inputs.len(),
+            function_table,
+        )?;
+    }
+
+    for binding in &project.constant_bindings {
+        let constant_exists = project
+            .constants
+            .iter()
+            .any(|constant| constant.name == binding.constant_name);
+        if !constant_exists
+            || !constants::binding_fits_stmts(binding, project.prog.stmts(), function_table)
+        {
+            return Err(ProjectError::InvalidConstantBinding(binding.stmt_index));
+        }
+    }
+
+    Ok(project)
+}
+
+/// Checks that the statements are built the way the session builds
+/// them: variables are numbered by their statement, calls refer to
+/// existing funcs with the right number of arguments and variable
+/// references only point back to preceding statements.
+///
+/// Variables below `first_index` are declared outside of the
+/// statements, e.g. macro inputs, and statement variables are numbered
+/// from it.
+fn validate_stmts(
+    stmts: &[Stmt],
+    first_index: usize,
+    function_table: &BTreeMap<FuncIdent, Box<dyn Func>>,
+) -> Result<(), ProjectError> {
+    for (index, stmt) in stmts.iter().enumerate() {
+        let index = first_index + index;
+        match stmt {
+            Stmt::VarDecl(var_decl) => {
+                if var_decl.ident() != VarIdent(index as u64) {
+                    return Err(ProjectError::InvalidVarIdent(index));
+                }
+
+                let init_expr = var_decl.init_expr();
+                let func = function_table
+                    .get(&init_expr.ident())
+                    .ok_or_else(|| ProjectError::UnknownFunc(index, init_expr.ident()))?;
+                if func.param_info().len() != init_expr.args().len() {
+                    return Err(ProjectError::ArgCountMismatch(index));
+                }
+
+                for arg in init_expr.args() {
+                    if let Expr::Var(var_expr) = arg {
+                        if var_expr.ident().0 >= index as u64 {
+                            return Err(ProjectError::InvalidVarReference(index, var_expr.ident()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::sync::Arc;
+
+    use nalgebra::Point3;
+
+    use crate::curve::{Curve, Polyline};
+    use crate::interpreter::ast::{CallExpr, LitExpr, VarDeclStmt, VarExpr};
+    use crate::interpreter::Ty;
+    use crate::interpreter_funcs;
+
+    use super::*;
+
+    fn create_project() -> Project {
+        let prog = Prog::new(vec![
+            Stmt::VarDecl(VarDeclStmt::new(
+                VarIdent(0),
+                CallExpr::new(
+                    interpreter_funcs::FUNC_ID_IMPORT_OBJ_MESH,
+                    vec![Expr::Lit(LitExpr::String(Arc::new(
+                        "/tmp/model.obj".to_string(),
+                    )))],
+                ),
+            )),
+            Stmt::VarDecl(VarDeclStmt::new(
+                VarIdent(1),
+                CallExpr::new(
+                    interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                    vec![Expr::Var(VarExpr::new(VarIdent(0)))],
+                ),
+            )),
+        ]);
+
+        Project {
+            version: PROJECT_VERSION,
+            prog: prog.clone(),
+            variants: vec![ProjectVariant {
+                name: "Variant 1".to_string(),
+                prog,
+            }],
+            manual_update_vars: vec![VarIdent(1)],
+            auto_interpret: true,
+            macros: vec![Macro {
+                name: "Macro 1".to_string(),
+                inputs: vec![Ty::Mesh],
+                stmts: vec![Stmt::VarDecl(VarDeclStmt::new(
+                    VarIdent(1),
+                    CallExpr::new(
+                        interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                        vec![Expr::Var(VarExpr::new(VarIdent(0)))],
+                    ),
+                ))],
+            }],
+            constants: vec![Constant {
+                name: "voxel_size".to_string(),
+                value: 0.5,
+            }],
+            constant_bindings: Vec::new(),
+            unit: LengthUnit::Millimeter,
+        }
+    }
+
+    #[test]
+    fn test_project_save_load_roundtrip() {
+        let project = create_project();
+        let path = env::temp_dir().join("hurban_selector_test_project_roundtrip.json");
+        let function_table = interpreter_funcs::create_function_table();
+
+        save_project(&path, &project).expect("Failed to save project");
+        let loaded_project = load_project(&path, &function_table);
+        fs::remove_file(&path).expect("Failed to remove project file");
+
+        assert_eq!(loaded_project.expect("Failed to load project"), project);
+    }
+
+    #[test]
+    fn test_project_save_load_roundtrip_with_embedded_curve() {
+        let curve = Curve::new(vec![Polyline::new(
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+            ],
+            true,
+        )]);
+        let mut project = create_project();
+        project.prog = Prog::new(vec![Stmt::VarDecl(VarDeclStmt::new(
+            VarIdent(0),
+            CallExpr::new(
+                interpreter_funcs::FUNC_ID_EMBEDDED_CURVE,
+                vec![Expr::Lit(LitExpr::Curve(Arc::new(curve)))],
+            ),
+        ))]);
+        let path = env::temp_dir().join("hurban_selector_test_project_embedded_curve.json");
+        let function_table = interpreter_funcs::create_function_table();
+
+        save_project(&path, &project).expect("Failed to save project");
+        let loaded_project = load_project(&path, &function_table);
+        fs::remove_file(&path).expect("Failed to remove project file");
+
+        assert_eq!(loaded_project.expect("Failed to load project"), project);
+    }
+
+    #[test]
+    fn test_project_validate_prog_rejects_forward_var_reference() {
+        let prog = Prog::new(vec![Stmt::VarDecl(VarDeclStmt::new(
+            VarIdent(0),
+            CallExpr::new(
+                interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                vec![Expr::Var(VarExpr::new(VarIdent(0)))],
+            ),
+        ))]);
+        let function_table = interpreter_funcs::create_function_table();
+
+        let result = validate_stmts(prog.stmts(), 0, &function_table);
+
+        assert!(match result {
+            Err(ProjectError::InvalidVarReference(0, VarIdent(0))) => true,
+            _ => false,
+        });
+    }
+}
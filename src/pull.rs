@@ -90,7 +90,7 @@ pub fn pull_point_to_mesh(
     unoriented_edges: &[UnorientedEdge],
 ) -> PulledPointWithDistance {
     let vertices = mesh.vertices();
-    let all_mesh_faces_with_normals = mesh.faces().iter().map(|Face::Triangle(t_f)| {
+    let all_mesh_faces_with_normals = mesh.faces().iter().flat_map(Face::to_triangles).map(|t_f| {
         let face_vertices = (
             &vertices[cast_usize(t_f.vertices.0)],
             &vertices[cast_usize(t_f.vertices.1)],
@@ -0,0 +1,243 @@
+#[cfg(any(test, feature = "gui"))]
+use std::collections::BTreeMap;
+
+use crate::interpreter::ast::Stmt;
+#[cfg(any(test, feature = "gui"))]
+use crate::interpreter::ast::{CallExpr, Expr, FuncIdent, Prog, VarDeclStmt, VarExpr, VarIdent};
+#[cfg(any(test, feature = "gui"))]
+use crate::interpreter::Func;
+use crate::interpreter::Ty;
+
+/// A recorded sequence of pipeline operations, which can be replayed
+/// on top of any program as a reusable subgraph.
+///
+/// The variables the recorded operations refer to, but which existed
+/// before the recording started, become the macro's inputs. Inside the
+/// macro, the inputs are numbered from 0 and the variables of the
+/// recorded statements are numbered after them.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub inputs: Vec<Ty>,
+    pub stmts: Vec<Stmt>,
+}
+
+#[cfg(any(test, feature = "gui"))]
+impl Macro {
+    /// Creates a macro from the statements of the program starting at
+    /// the `start` index.
+    ///
+    /// # Panics
+    /// Panics if the statements call funcs missing from the
+    /// `function_table`.
+    pub fn from_prog(
+        name: String,
+        prog: &Prog,
+        start: usize,
+        function_table: &BTreeMap<FuncIdent, Box<dyn Func>>,
+    ) -> Self {
+        let recorded_stmts = &prog.stmts()[start..];
+
+        let mut input_vars: Vec<VarIdent> = Vec::new();
+        for stmt in recorded_stmts {
+            let Stmt::VarDecl(var_decl) = stmt;
+            for arg in var_decl.init_expr().args() {
+                if let Expr::Var(var_expr) = arg {
+                    let var_ident = var_expr.ident();
+                    if var_ident.0 < start as u64 && !input_vars.contains(&var_ident) {
+                        input_vars.push(var_ident);
+                    }
+                }
+            }
+        }
+
+        let inputs = input_vars
+            .iter()
+            .map(|var_ident| {
+                let Stmt::VarDecl(var_decl) = &prog.stmts()[var_ident.0 as usize];
+                function_table[&var_decl.init_expr().ident()].return_ty()
+            })
+            .collect();
+
+        let input_count = input_vars.len() as u64;
+        let map_var_ident = |var_ident: VarIdent| match input_vars
+            .iter()
+            .position(|input_var| *input_var == var_ident)
+        {
+            Some(input_index) => VarIdent(input_index as u64),
+            None => VarIdent(input_count + var_ident.0 - start as u64),
+        };
+
+        let stmts = recorded_stmts
+            .iter()
+            .map(|stmt| {
+                let Stmt::VarDecl(var_decl) = stmt;
+                let init_expr = var_decl.init_expr();
+                let args = init_expr
+                    .args()
+                    .iter()
+                    .map(|arg| match arg {
+                        Expr::Var(var_expr) => {
+                            Expr::Var(VarExpr::new(map_var_ident(var_expr.ident())))
+                        }
+                        Expr::Lit(_) => arg.clone(),
+                    })
+                    .collect();
+
+                Stmt::VarDecl(VarDeclStmt::new(
+                    map_var_ident(var_decl.ident()),
+                    CallExpr::new(init_expr.ident(), args),
+                ))
+            })
+            .collect();
+
+        Self {
+            name,
+            inputs,
+            stmts,
+        }
+    }
+
+    /// Creates the statements replaying the macro on top of a program,
+    /// where `first_var_ident` is the next free variable of the
+    /// program. The macro inputs are replaced by the `input_args`.
+    ///
+    /// # Panics
+    /// Panics if the number of `input_args` doesn't match the number
+    /// of macro inputs.
+    pub fn instantiate(&self, first_var_ident: VarIdent, input_args: &[Expr]) -> Vec<Stmt> {
+        assert_eq!(
+            input_args.len(),
+            self.inputs.len(),
+            "Each macro input must have an argument",
+        );
+
+        let input_count = self.inputs.len() as u64;
+        let map_var_ident =
+            |var_ident: VarIdent| VarIdent(first_var_ident.0 + var_ident.0 - input_count);
+
+        self.stmts
+            .iter()
+            .map(|stmt| {
+                let Stmt::VarDecl(var_decl) = stmt;
+                let init_expr = var_decl.init_expr();
+                let args = init_expr
+                    .args()
+                    .iter()
+                    .map(|arg| match arg {
+                        Expr::Var(var_expr) if var_expr.ident().0 < input_count => {
+                            input_args[var_expr.ident().0 as usize].clone()
+                        }
+                        Expr::Var(var_expr) => {
+                            Expr::Var(VarExpr::new(map_var_ident(var_expr.ident())))
+                        }
+                        Expr::Lit(_) => arg.clone(),
+                    })
+                    .collect();
+
+                Stmt::VarDecl(VarDeclStmt::new(
+                    map_var_ident(var_decl.ident()),
+                    CallExpr::new(init_expr.ident(), args),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::ast::LitExpr;
+    use crate::interpreter_funcs;
+
+    use super::*;
+
+    fn var_decl(index: u64, func_ident: FuncIdent, args: Vec<Expr>) -> Stmt {
+        Stmt::VarDecl(VarDeclStmt::new(
+            VarIdent(index),
+            CallExpr::new(func_ident, args),
+        ))
+    }
+
+    fn var(index: u64) -> Expr {
+        Expr::Var(VarExpr::new(VarIdent(index)))
+    }
+
+    fn create_prog() -> Prog {
+        Prog::new(vec![
+            var_decl(
+                0,
+                interpreter_funcs::FUNC_ID_CREATE_BOX,
+                vec![
+                    Expr::Lit(LitExpr::Float3([0.0, 0.0, 0.0])),
+                    Expr::Lit(LitExpr::Float3([0.0, 0.0, 0.0])),
+                    Expr::Lit(LitExpr::Float3([1.0, 1.0, 1.0])),
+                ],
+            ),
+            var_decl(
+                1,
+                interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                vec![var(0)],
+            ),
+            var_decl(
+                2,
+                interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                vec![var(0)],
+            ),
+            var_decl(
+                3,
+                interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                vec![var(2)],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_macro_from_prog_turns_preceding_vars_into_inputs() {
+        let prog = create_prog();
+        let function_table = interpreter_funcs::create_function_table();
+
+        let recorded_macro = Macro::from_prog("Macro".to_string(), &prog, 2, &function_table);
+
+        assert_eq!(recorded_macro.inputs, vec![Ty::Mesh]);
+        assert_eq!(
+            recorded_macro.stmts,
+            vec![
+                var_decl(
+                    1,
+                    interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                    vec![var(0)]
+                ),
+                var_decl(
+                    2,
+                    interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                    vec![var(1)]
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_macro_instantiate_appends_after_first_var_ident() {
+        let prog = create_prog();
+        let function_table = interpreter_funcs::create_function_table();
+        let recorded_macro = Macro::from_prog("Macro".to_string(), &prog, 2, &function_table);
+
+        let stmts = recorded_macro.instantiate(VarIdent(4), &[var(3)]);
+
+        assert_eq!(
+            stmts,
+            vec![
+                var_decl(
+                    4,
+                    interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                    vec![var(3)]
+                ),
+                var_decl(
+                    5,
+                    interpreter_funcs::FUNC_ID_REVERT_MESH_FACES,
+                    vec![var(4)]
+                ),
+            ],
+        );
+    }
+}
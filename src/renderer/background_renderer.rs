@@ -0,0 +1,371 @@
+use std::io;
+
+use nalgebra::Matrix4;
+use serde::{Deserialize, Serialize};
+
+use crate::include_shader;
+
+use super::common::{
+    apply_wgpu_correction_matrix, upload_texture_rgba8_unorm, wgpu_size_of, UniformBufferRing,
+};
+
+static SHADER_FULLSCREEN_VERT: &[u8] = include_shader!("fullscreen.vert.spv");
+static SHADER_BACKGROUND_FRAG: &[u8] = include_shader!("background.frag.spv");
+
+/// What is drawn behind the scene. Colors are RGB in the `0..1`
+/// range.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Background {
+    /// A single color.
+    Solid { color: [f32; 3] },
+    /// A vertical gradient from the top to the bottom of the screen.
+    Gradient { top: [f32; 3], bottom: [f32; 3] },
+    /// The environment image set with
+    /// `Renderer::set_background_environment`, in the equirectangular
+    /// projection with Z up. The background follows the camera
+    /// rotation.
+    Environment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub sample_count: u32,
+    pub output_color_attachment_format: wgpu::TextureFormat,
+}
+
+/// Draws the viewport background over the whole screen, before
+/// anything else is drawn.
+pub struct BackgroundRenderer {
+    background: Background,
+    inverse_view_projection_matrix: Matrix4<f32>,
+    uniform_buffers: UniformBufferRing,
+    environment_texture_bind_group_layout: wgpu::BindGroupLayout,
+    environment_texture_bind_group: wgpu::BindGroup,
+    environment_sampler: wgpu::Sampler,
+    pipeline_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+    options: Options,
+}
+
+impl BackgroundRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        background: Background,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+        options: Options,
+    ) -> Self {
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let uniform_buffers = UniformBufferRing::new(
+            device,
+            &uniform_bind_group_layout,
+            wgpu_size_of::<BackgroundUniforms>(),
+        );
+
+        let environment_texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                        },
+                    },
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler,
+                    },
+                ],
+            });
+
+        // The environment wraps around horizontally, but not over the
+        // poles
+        let environment_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            compare_function: wgpu::CompareFunction::Always,
+        });
+
+        // Until an environment image is set, a black placeholder is
+        // bound
+        let environment_texture_bind_group = create_environment_texture_bind_group(
+            device,
+            queue,
+            &environment_texture_bind_group_layout,
+            &environment_sampler,
+            1,
+            1,
+            &[0, 0, 0, 255],
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[
+                &uniform_bind_group_layout,
+                &environment_texture_bind_group_layout,
+            ],
+        });
+
+        let render_pipeline = create_render_pipeline(device, &pipeline_layout, options);
+
+        let mut background_renderer = Self {
+            background,
+            inverse_view_projection_matrix: Matrix4::identity(),
+            uniform_buffers,
+            environment_texture_bind_group_layout,
+            environment_texture_bind_group,
+            environment_sampler,
+            pipeline_layout,
+            render_pipeline,
+            options,
+        };
+        background_renderer.set_camera_matrices(device, queue, projection_matrix, view_matrix);
+
+        background_renderer
+    }
+
+    /// Recreates the render pipeline for a new multi-sampling
+    /// setting of the output attachment.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.render_pipeline = create_render_pipeline(device, &self.pipeline_layout, self.options);
+    }
+
+    pub fn set_background(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        background: Background,
+    ) {
+        self.background = background;
+        self.upload_uniforms(device, queue);
+    }
+
+    /// Replaces the environment image with RGBA8 `data` of the given
+    /// size.
+    pub fn set_environment(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) {
+        self.environment_texture_bind_group = create_environment_texture_bind_group(
+            device,
+            queue,
+            &self.environment_texture_bind_group_layout,
+            &self.environment_sampler,
+            width,
+            height,
+            data,
+        );
+    }
+
+    pub fn set_camera_matrices(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+    ) {
+        let view_projection_matrix = apply_wgpu_correction_matrix(projection_matrix) * view_matrix;
+        self.inverse_view_projection_matrix = view_projection_matrix
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        self.upload_uniforms(device, queue);
+    }
+
+    /// Records drawing the background, clearing the color attachment
+    /// in the process.
+    pub fn draw_background(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+    ) {
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Clear,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: None,
+        });
+
+        rpass.set_pipeline(&self.render_pipeline);
+        rpass.set_bind_group(0, self.uniform_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, &self.environment_texture_bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+
+    fn upload_uniforms(&mut self, device: &wgpu::Device, queue: &mut wgpu::Queue) {
+        let uniforms =
+            BackgroundUniforms::new(self.background, &self.inverse_view_projection_matrix);
+        let transfer_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&[uniforms]);
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+        self.uniform_buffers.write(&mut encoder, &transfer_buffer);
+        queue.submit(&[encoder.finish()]);
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_FULLSCREEN_VERT))
+        .expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_BACKGROUND_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    // The background covers the whole screen with a single
+    // triangle generated in the vertex shader, so there are no
+    // vertex buffers. No blending, no depth testing.
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: None,
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: None,
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+fn create_environment_texture_bind_group(
+    device: &wgpu::Device,
+    queue: &mut wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> wgpu::BindGroup {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        },
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+    });
+
+    upload_texture_rgba8_unorm(device, queue, &texture, width, height, data);
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: bind_group_layout,
+        bindings: &[
+            wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture.create_default_view()),
+            },
+            wgpu::Binding {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+const BACKGROUND_MODE_SOLID: u32 = 0;
+const BACKGROUND_MODE_GRADIENT: u32 = 1;
+const BACKGROUND_MODE_ENVIRONMENT: u32 = 2;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BackgroundUniforms {
+    inverse_view_projection_matrix: [[f32; 4]; 4],
+    top_color: [f32; 4],
+    bottom_color: [f32; 4],
+    mode: u32,
+    _padding: [u32; 3],
+}
+
+impl BackgroundUniforms {
+    fn new(background: Background, inverse_view_projection_matrix: &Matrix4<f32>) -> Self {
+        let (mode, top, bottom) = match background {
+            Background::Solid { color } => (BACKGROUND_MODE_SOLID, color, color),
+            Background::Gradient { top, bottom } => (BACKGROUND_MODE_GRADIENT, top, bottom),
+            Background::Environment => (BACKGROUND_MODE_ENVIRONMENT, [0.0; 3], [0.0; 3]),
+        };
+
+        Self {
+            inverse_view_projection_matrix: (*inverse_view_projection_matrix).into(),
+            top_color: [top[0], top[1], top[2], 1.0],
+            bottom_color: [bottom[0], bottom[1], bottom[2], 1.0],
+            mode,
+            _padding: [0; 3],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_serde_roundtrip() {
+        let background = Background::Gradient {
+            top: [0.1, 0.2, 0.3],
+            bottom: [0.4, 0.5, 0.6],
+        };
+
+        let json = serde_json::to_string(&background).expect("Failed to serialize background");
+        let deserialized: Background =
+            serde_json::from_str(&json).expect("Failed to deserialize background");
+
+        assert_eq!(deserialized, background);
+    }
+}
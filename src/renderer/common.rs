@@ -1,6 +1,8 @@
 use std::convert::TryFrom;
 use std::mem;
 
+use nalgebra::Matrix4;
+
 use crate::convert::cast_u32;
 
 #[macro_export]
@@ -10,6 +12,94 @@ macro_rules! include_shader {
     }};
 }
 
+/// The number of uniform buffers in a `UniformBufferRing`. Up to this
+/// many frames can be in flight on the GPU before uniform updates wait
+/// for it.
+pub const UNIFORM_BUFFER_RING_LEN: usize = 3;
+
+/// A ring of uniform buffers of the same size and layout, each with its
+/// own bind group.
+///
+/// Each update is written to the next buffer in the ring, so that the
+/// CPU does not write a buffer the GPU may still be reading for a
+/// previous frame, and both do not need to serialize.
+pub struct UniformBufferRing {
+    buffers: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+    buffer_size: wgpu::BufferAddress,
+    current: usize,
+}
+
+impl UniformBufferRing {
+    /// Creates the ring of uniform buffers of `buffer_size`, bound at
+    /// binding 0 of the `bind_group_layout`.
+    pub fn new(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        buffer_size: wgpu::BufferAddress,
+    ) -> Self {
+        let buffers = (0..UNIFORM_BUFFER_RING_LEN)
+            .map(|_| {
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    size: buffer_size,
+                    usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: bind_group_layout,
+                    bindings: &[wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: &buffer,
+                            range: 0..buffer_size,
+                        },
+                    }],
+                });
+
+                (buffer, bind_group)
+            })
+            .collect();
+
+        Self {
+            buffers,
+            buffer_size,
+            current: 0,
+        }
+    }
+
+    /// Records copying the `transfer_buffer` to the next buffer of the
+    /// ring with the `encoder`. The next buffer becomes the current.
+    pub fn write(&mut self, encoder: &mut wgpu::CommandEncoder, transfer_buffer: &wgpu::Buffer) {
+        self.current = (self.current + 1) % self.buffers.len();
+        let (buffer, _) = &self.buffers[self.current];
+        encoder.copy_buffer_to_buffer(transfer_buffer, 0, buffer, 0, self.buffer_size);
+    }
+
+    /// Returns the bind group of the most recently written buffer.
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        let (_, bind_group) = &self.buffers[self.current];
+        bind_group
+    }
+}
+
+pub fn apply_wgpu_correction_matrix(projection_matrix: &Matrix4<f32>) -> Matrix4<f32> {
+    // Vulkan (and therefore wgpu) has different NDC and
+    // clip-space semantics than OpenGL: Vulkan is right-handed, Y
+    // grows downwards. The easiest way to keep everything working
+    // as before and use all the libraries that assume OpenGL is
+    // to apply a correction to the projection matrix which
+    // normally changes the right-handed OpenGL world-space to
+    // left-handed OpenGL clip-space.
+    // https://matthewwellings.com/blog/the-new-vulkan-coordinate-system/
+    #[rustfmt::skip]
+    let wgpu_correction_matrix = Matrix4::new(
+        1.0,  0.0,  0.0,  0.0,
+        0.0, -1.0,  0.0,  0.0,
+        0.0,  0.0,  0.5,  0.0,
+        0.0,  0.0,  0.5,  1.0,
+    );
+
+    wgpu_correction_matrix * projection_matrix
+}
+
 pub fn wgpu_size_of<T>() -> wgpu::BufferAddress {
     let size = mem::size_of::<T>();
     wgpu::BufferAddress::try_from(size)
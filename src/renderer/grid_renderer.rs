@@ -0,0 +1,391 @@
+use std::io;
+
+use nalgebra::{Matrix4, Point3, Vector3, Vector4};
+
+use crate::include_shader;
+
+use super::common::{apply_wgpu_correction_matrix, wgpu_size_of, UniformBufferRing};
+
+static SHADER_GRID_VERT: &[u8] = include_shader!("grid.vert.spv");
+static SHADER_GRID_FRAG: &[u8] = include_shader!("grid.frag.spv");
+static SHADER_AXES_VERT: &[u8] = include_shader!("axes.vert.spv");
+static SHADER_AXES_FRAG: &[u8] = include_shader!("axes.frag.spv");
+
+/// How far the grid extends around the eye, in minor cells. The grid
+/// fades out over the outer half of it.
+const GRID_EXTENT_CELLS: f32 = 200.0;
+/// The length of the axes in the corner of the screen, relative to
+/// the screen height.
+const AXES_SIZE: f32 = 0.06;
+/// The distance of the origin of the axes from the left and bottom
+/// edges of the screen, relative to the screen height.
+const AXES_MARGIN: f32 = 0.1;
+
+/// Returns the spacing of minor grid lines for the eye at the
+/// `distance` from the origin. The spacing is a power of ten, so that
+/// grid lines stay at round coordinates, and grows with the distance,
+/// so that there are always about the same number of lines in view.
+fn grid_cell_size(distance: f32) -> f32 {
+    let exponent = distance.max(0.001).log10().floor() - 1.0;
+    10f32.powf(exponent)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub sample_count: u32,
+    pub output_color_attachment_format: wgpu::TextureFormat,
+    pub output_depth_attachment_format: wgpu::TextureFormat,
+}
+
+/// Draws viewport guides for spatial orientation: a grid on the
+/// ground plane with major and minor lines and the X and Y axes
+/// highlighted, and the world axes in the bottom left corner of the
+/// screen, rotated with the camera.
+pub struct GridRenderer {
+    grid_uniform_buffers: UniformBufferRing,
+    axes_uniform_buffers: UniformBufferRing,
+    pipeline_layout: wgpu::PipelineLayout,
+    grid_render_pipeline: wgpu::RenderPipeline,
+    axes_render_pipeline: wgpu::RenderPipeline,
+    options: Options,
+}
+
+impl GridRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+        options: Options,
+    ) -> Self {
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let grid_uniform_buffers = UniformBufferRing::new(
+            device,
+            &uniform_bind_group_layout,
+            wgpu_size_of::<GridUniforms>(),
+        );
+        let axes_uniform_buffers = UniformBufferRing::new(
+            device,
+            &uniform_bind_group_layout,
+            wgpu_size_of::<AxesUniforms>(),
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&uniform_bind_group_layout],
+        });
+
+        let grid_render_pipeline = create_grid_render_pipeline(device, &pipeline_layout, options);
+        let axes_render_pipeline = create_axes_render_pipeline(device, &pipeline_layout, options);
+
+        let mut grid_renderer = Self {
+            grid_uniform_buffers,
+            axes_uniform_buffers,
+            pipeline_layout,
+            grid_render_pipeline,
+            axes_render_pipeline,
+            options,
+        };
+        grid_renderer.set_camera_matrices(device, queue, projection_matrix, view_matrix);
+
+        grid_renderer
+    }
+
+    /// Recreates the render pipelines for a new multi-sampling setting
+    /// of the output attachments.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.grid_render_pipeline =
+            create_grid_render_pipeline(device, &self.pipeline_layout, self.options);
+        self.axes_render_pipeline =
+            create_axes_render_pipeline(device, &self.pipeline_layout, self.options);
+    }
+
+    pub fn set_camera_matrices(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+    ) {
+        let eye = view_matrix
+            .try_inverse()
+            .map(|inverse_view_matrix| inverse_view_matrix.transform_point(&Point3::origin()))
+            .unwrap_or_else(Point3::origin);
+
+        let cell_size = grid_cell_size(eye.coords.norm());
+        let grid_uniforms = GridUniforms {
+            view_projection_matrix: (apply_wgpu_correction_matrix(projection_matrix) * view_matrix)
+                .into(),
+            center_and_extent: [eye.x, eye.y, 0.0, GRID_EXTENT_CELLS * cell_size],
+            cell_size,
+            _padding: [0; 3],
+        };
+        let axes_uniforms = AxesUniforms {
+            axes_matrix: axes_matrix(projection_matrix, view_matrix).into(),
+        };
+
+        let grid_transfer_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&[grid_uniforms]);
+        let axes_transfer_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&[axes_uniforms]);
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+        self.grid_uniform_buffers
+            .write(&mut encoder, &grid_transfer_buffer);
+        self.axes_uniform_buffers
+            .write(&mut encoder, &axes_transfer_buffer);
+        queue.submit(&[encoder.finish()]);
+    }
+
+    /// Records drawing the ground grid over what is already in the
+    /// attachments.
+    pub fn draw_grid(
+        &self,
+        clear_depth: bool,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+        depth_attachment: &wgpu::TextureView,
+    ) {
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let depth_load_op = if clear_depth {
+            wgpu::LoadOp::Clear
+        } else {
+            wgpu::LoadOp::Load
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: depth_attachment,
+                depth_load_op,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 1.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        rpass.set_pipeline(&self.grid_render_pipeline);
+        rpass.set_bind_group(0, self.grid_uniform_buffers.bind_group(), &[]);
+        rpass.draw(0..6, 0..1);
+    }
+
+    /// Records drawing the world axes in the corner of the screen over
+    /// what is already in the color attachment.
+    pub fn draw_axes(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+    ) {
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: None,
+        });
+
+        rpass.set_pipeline(&self.axes_render_pipeline);
+        rpass.set_bind_group(0, self.axes_uniform_buffers.bind_group(), &[]);
+        rpass.draw(0..6, 0..1);
+    }
+}
+
+/// Computes the matrix placing the unit world axes in the bottom left
+/// corner of the screen. Only the rotation of the camera applies to
+/// them, so they keep their size and place as the camera moves.
+fn axes_matrix(projection_matrix: &Matrix4<f32>, view_matrix: &Matrix4<f32>) -> Matrix4<f32> {
+    let mut rotation_matrix = *view_matrix;
+    rotation_matrix.set_column(3, &Vector4::w());
+
+    // Both perspective and orthographic projections scale the x and y
+    // axes by the aspect ratio of the screen
+    let aspect_ratio = projection_matrix[(1, 1)] / projection_matrix[(0, 0)];
+    let origin = Vector3::new(
+        -1.0 + AXES_MARGIN * 2.0 / aspect_ratio,
+        -1.0 + AXES_MARGIN * 2.0,
+        0.0,
+    );
+    let scale = Vector3::new(AXES_SIZE * 2.0 / aspect_ratio, AXES_SIZE * 2.0, 0.0);
+    let screen_matrix = Matrix4::new_translation(&origin) * Matrix4::new_nonuniform_scaling(&scale);
+
+    apply_wgpu_correction_matrix(&screen_matrix) * rotation_matrix
+}
+
+fn create_grid_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_GRID_VERT))
+        .expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_GRID_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    // The grid plane is generated in the vertex shader, so there
+    // are no vertex buffers. Meshes hide the grid, but it doesn't
+    // write depth itself.
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
+            format: options.output_depth_attachment_format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }),
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+fn create_axes_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_AXES_VERT))
+        .expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_AXES_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    // The axes are drawn over everything, in a render pass without
+    // a depth attachment
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        primitive_topology: wgpu::PrimitiveTopology::LineList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: None,
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GridUniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    center_and_extent: [f32; 4],
+    cell_size: f32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxesUniforms {
+    axes_matrix: [[f32; 4]; 4],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_cell_size_is_power_of_ten_below_distance() {
+        assert!((grid_cell_size(5.0) - 0.1).abs() < 0.0001);
+        assert!((grid_cell_size(20.0) - 1.0).abs() < 0.0001);
+        assert!((grid_cell_size(999.0) - 10.0).abs() < 0.001);
+        assert!(grid_cell_size(0.0) > 0.0);
+    }
+}
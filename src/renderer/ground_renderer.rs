@@ -0,0 +1,614 @@
+use std::f32;
+use std::io;
+
+use nalgebra::{Matrix4, Point2, Point3};
+
+use crate::bounding_box::BoundingBox;
+use crate::convert::cast_usize;
+use crate::include_shader;
+use crate::mesh::{Face, Mesh};
+
+use super::common::{
+    apply_wgpu_correction_matrix, upload_texture_rgba8_unorm, wgpu_size_of, UniformBufferRing,
+};
+
+static SHADER_GROUND_VERT: &[u8] = include_shader!("ground.vert.spv");
+static SHADER_GROUND_FRAG: &[u8] = include_shader!("ground.frag.spv");
+
+/// The number of texels along each side of the ground shadow mask.
+const GROUND_SHADOW_RESOLUTION: u32 = 256;
+/// How far the ground plane extends beyond the footprint of the
+/// scene, relative to the scene size.
+const GROUND_SHADOW_MARGIN: f32 = 0.5;
+/// The height above the ground, relative to the scene size, at which
+/// geometry casts a shadow of about a third of the full intensity.
+const GROUND_SHADOW_FALLOFF: f32 = 0.3;
+/// The ground plane is moved slightly below the scene, so that it
+/// doesn't fight with faces lying on the ground.
+const GROUND_OFFSET: f32 = 0.001;
+const GROUND_SHADOW_OPACITY: f32 = 0.6;
+
+/// A soft shadow cast by the scene on a ground plane right below it,
+/// as if lit by an overcast sky. Geometry closer to the ground casts
+/// darker shadows, similarly to ambient occlusion.
+///
+/// The shadow covers a square of the ground plane and is stored as a
+/// mask of shadow intensities, row by row from the minimum corner of
+/// the square.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundShadow {
+    origin: Point3<f32>,
+    size: f32,
+    resolution: u32,
+    intensities: Vec<u8>,
+}
+
+impl GroundShadow {
+    /// Computes the shadow cast by the meshes on the ground plane
+    /// below their bounding box. Returns `None`, if there are no
+    /// meshes, or they are only a single point.
+    pub fn from_meshes<'a, I>(meshes: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Mesh>,
+    {
+        let meshes: Vec<&Mesh> = meshes.into_iter().collect();
+        let bounding_box = BoundingBox::union(meshes.iter().map(|mesh| mesh.bounding_box()))?;
+
+        let center = bounding_box.center();
+        let diagonal = bounding_box.diagonal();
+        let footprint_size = diagonal.x.max(diagonal.y);
+        let scene_size = footprint_size.max(diagonal.z);
+        if scene_size <= f32::EPSILON {
+            return None;
+        }
+
+        let size = footprint_size + 2.0 * GROUND_SHADOW_MARGIN * scene_size;
+        let ground_height = bounding_box.minimum_point().z;
+        let origin = Point3::new(
+            center.x - size / 2.0,
+            center.y - size / 2.0,
+            ground_height - GROUND_OFFSET * scene_size,
+        );
+
+        let resolution = GROUND_SHADOW_RESOLUTION;
+        let texel_size = size / resolution as f32;
+
+        // The lowest height of geometry above each texel
+        let mut heights = vec![f32::INFINITY; cast_usize(resolution * resolution)];
+        for mesh in &meshes {
+            let vertices = mesh.vertices();
+            for triangle_face in mesh.faces().iter().flat_map(Face::to_triangles) {
+                let (v1, v2, v3) = triangle_face.vertices;
+                let triangle = [
+                    vertices[cast_usize(v1)],
+                    vertices[cast_usize(v2)],
+                    vertices[cast_usize(v3)],
+                ];
+                let texel_triangle = [
+                    to_texel_space(&triangle[0], &origin, texel_size),
+                    to_texel_space(&triangle[1], &origin, texel_size),
+                    to_texel_space(&triangle[2], &origin, texel_size),
+                ];
+                let triangle_heights = [
+                    triangle[0].z - ground_height,
+                    triangle[1].z - ground_height,
+                    triangle[2].z - ground_height,
+                ];
+
+                rasterize_min_height(&mut heights, resolution, &texel_triangle, triangle_heights);
+            }
+        }
+
+        let falloff = GROUND_SHADOW_FALLOFF * scene_size;
+        let mut values: Vec<f32> = heights
+            .iter()
+            .map(|height| {
+                if height.is_finite() {
+                    (-height.max(0.0) / falloff).exp()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        // Two box blurs in each direction approximate a gaussian blur
+        let blur_radius = cast_usize(resolution / 64);
+        for _ in 0..2 {
+            box_blur(&mut values, cast_usize(resolution), blur_radius);
+        }
+
+        let intensities = values
+            .iter()
+            .map(|value| (value.max(0.0).min(1.0) * 255.0).round() as u8)
+            .collect();
+
+        Some(Self {
+            origin,
+            size,
+            resolution,
+            intensities,
+        })
+    }
+
+    /// The minimum corner of the shadowed square of the ground plane.
+    pub fn origin(&self) -> Point3<f32> {
+        self.origin
+    }
+
+    /// The length of the sides of the shadowed square.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Returns the shadow intensity at the point of the ground plane
+    /// in the `0..1` range.
+    pub fn intensity_at(&self, point: Point2<f32>) -> f32 {
+        let texel_size = self.size / self.resolution as f32;
+        let x = ((point.x - self.origin.x) / texel_size).floor();
+        let y = ((point.y - self.origin.y) / texel_size).floor();
+        let resolution = self.resolution as f32;
+        if x < 0.0 || y < 0.0 || x >= resolution || y >= resolution {
+            return 0.0;
+        }
+
+        let index = y as usize * cast_usize(self.resolution) + x as usize;
+        f32::from(self.intensities[index]) / 255.0
+    }
+}
+
+fn to_texel_space(point: &Point3<f32>, origin: &Point3<f32>, texel_size: f32) -> Point2<f32> {
+    Point2::new(
+        (point.x - origin.x) / texel_size,
+        (point.y - origin.y) / texel_size,
+    )
+}
+
+/// Rasterizes the triangle projected to the ground plane, keeping the
+/// lowest interpolated height for each texel covered by the triangle.
+fn rasterize_min_height(
+    heights: &mut [f32],
+    resolution: u32,
+    triangle: &[Point2<f32>; 3],
+    triangle_heights: [f32; 3],
+) {
+    let [a, b, c] = *triangle;
+    let area = edge_function(a, b, c);
+    // Triangles perpendicular to the ground don't cover any texels
+    if area.abs() <= f32::EPSILON {
+        return;
+    }
+
+    let max_texel = (resolution - 1) as f32;
+    let x_min = a.x.min(b.x).min(c.x).floor().max(0.0).min(max_texel) as usize;
+    let x_max = a.x.max(b.x).max(c.x).ceil().max(0.0).min(max_texel) as usize;
+    let y_min = a.y.min(b.y).min(c.y).floor().max(0.0).min(max_texel) as usize;
+    let y_max = a.y.max(b.y).max(c.y).ceil().max(0.0).min(max_texel) as usize;
+
+    let resolution = cast_usize(resolution);
+    for y in y_min..=y_max {
+        for x in x_min..=x_max {
+            let texel_center = Point2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w1 = edge_function(b, c, texel_center) / area;
+            let w2 = edge_function(c, a, texel_center) / area;
+            let w3 = edge_function(a, b, texel_center) / area;
+            if w1 < 0.0 || w2 < 0.0 || w3 < 0.0 {
+                continue;
+            }
+
+            let height =
+                w1 * triangle_heights[0] + w2 * triangle_heights[1] + w3 * triangle_heights[2];
+            let texel_height = &mut heights[y * resolution + x];
+            *texel_height = texel_height.min(height);
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `abc`.
+fn edge_function(a: Point2<f32>, b: Point2<f32>, c: Point2<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Blurs the square image horizontally and then vertically with a
+/// box filter. Texels outside the image are considered empty.
+fn box_blur(values: &mut [f32], resolution: usize, radius: usize) {
+    let width = (2 * radius + 1) as f32;
+    let mut blurred = vec![0.0; values.len()];
+
+    for (stride, step) in &[(resolution, 1), (1, resolution)] {
+        for line in 0..resolution {
+            for i in 0..resolution {
+                let from = i.saturating_sub(radius);
+                let to = (i + radius).min(resolution - 1);
+                let sum: f32 = (from..=to).map(|j| values[line * stride + j * step]).sum();
+                blurred[line * stride + i * step] = sum / width;
+            }
+        }
+        values.copy_from_slice(&blurred);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub sample_count: u32,
+    pub output_color_attachment_format: wgpu::TextureFormat,
+    pub output_depth_attachment_format: wgpu::TextureFormat,
+}
+
+/// Draws the ground plane catching the shadow of the scene. The plane
+/// itself is transparent, so that captured frames with transparent
+/// background only contain the shadow and can be composited onto
+/// other images.
+pub struct GroundRenderer {
+    view_projection_matrix: Matrix4<f32>,
+    shadow: Option<(GroundShadowUniforms, wgpu::BindGroup)>,
+    uniform_buffers: UniformBufferRing,
+    shadow_texture_bind_group_layout: wgpu::BindGroupLayout,
+    shadow_sampler: wgpu::Sampler,
+    pipeline_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+    options: Options,
+}
+
+impl GroundRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+        options: Options,
+    ) -> Self {
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let uniform_buffers = UniformBufferRing::new(
+            device,
+            &uniform_bind_group_layout,
+            wgpu_size_of::<GroundUniforms>(),
+        );
+
+        let shadow_texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                        },
+                    },
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler,
+                    },
+                ],
+            });
+
+        let shadow_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            compare_function: wgpu::CompareFunction::Always,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[
+                &uniform_bind_group_layout,
+                &shadow_texture_bind_group_layout,
+            ],
+        });
+
+        let render_pipeline = create_render_pipeline(device, &pipeline_layout, options);
+
+        let mut ground_renderer = Self {
+            view_projection_matrix: Matrix4::identity(),
+            shadow: None,
+            uniform_buffers,
+            shadow_texture_bind_group_layout,
+            shadow_sampler,
+            pipeline_layout,
+            render_pipeline,
+            options,
+        };
+        ground_renderer.set_camera_matrices(device, queue, projection_matrix, view_matrix);
+
+        ground_renderer
+    }
+
+    /// Recreates the render pipeline for a new multi-sampling setting
+    /// of the output attachments.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.render_pipeline = create_render_pipeline(device, &self.pipeline_layout, self.options);
+    }
+
+    /// Replaces the ground shadow, or removes the ground plane, if
+    /// `None`.
+    pub fn set_ground_shadow(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        ground_shadow: Option<&GroundShadow>,
+    ) {
+        self.shadow = ground_shadow.map(|ground_shadow| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                size: wgpu::Extent3d {
+                    width: ground_shadow.resolution,
+                    height: ground_shadow.resolution,
+                    depth: 1,
+                },
+                array_layer_count: 1,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+
+            let data: Vec<u8> = ground_shadow
+                .intensities
+                .iter()
+                .flat_map(|&intensity| vec![intensity; 4])
+                .collect();
+            upload_texture_rgba8_unorm(
+                device,
+                queue,
+                &texture,
+                ground_shadow.resolution,
+                ground_shadow.resolution,
+                &data,
+            );
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.shadow_texture_bind_group_layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(
+                            &texture.create_default_view(),
+                        ),
+                    },
+                    wgpu::Binding {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.shadow_sampler),
+                    },
+                ],
+            });
+
+            let uniforms = GroundShadowUniforms {
+                origin: ground_shadow.origin,
+                size: ground_shadow.size,
+            };
+
+            (uniforms, bind_group)
+        });
+
+        self.upload_uniforms(device, queue);
+    }
+
+    pub fn set_camera_matrices(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        projection_matrix: &Matrix4<f32>,
+        view_matrix: &Matrix4<f32>,
+    ) {
+        self.view_projection_matrix = apply_wgpu_correction_matrix(projection_matrix) * view_matrix;
+        self.upload_uniforms(device, queue);
+    }
+
+    /// Records drawing the ground shadow over what is already in the
+    /// attachments. Does nothing, if there is no ground shadow.
+    pub fn draw_ground_shadow(
+        &self,
+        clear_depth: bool,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+        depth_attachment: &wgpu::TextureView,
+    ) {
+        let bind_group = match &self.shadow {
+            Some((_, bind_group)) => bind_group,
+            None => return,
+        };
+
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let depth_load_op = if clear_depth {
+            wgpu::LoadOp::Clear
+        } else {
+            wgpu::LoadOp::Load
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: depth_attachment,
+                depth_load_op,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 1.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        rpass.set_pipeline(&self.render_pipeline);
+        rpass.set_bind_group(0, self.uniform_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, bind_group, &[]);
+        rpass.draw(0..6, 0..1);
+    }
+
+    fn upload_uniforms(&mut self, device: &wgpu::Device, queue: &mut wgpu::Queue) {
+        let shadow_uniforms = self.shadow.as_ref().map(|(uniforms, _)| *uniforms);
+        let uniforms = GroundUniforms::new(shadow_uniforms, &self.view_projection_matrix);
+        let transfer_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&[uniforms]);
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+        self.uniform_buffers.write(&mut encoder, &transfer_buffer);
+        queue.submit(&[encoder.finish()]);
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_GROUND_VERT))
+        .expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_GROUND_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    // The ground plane is generated in the vertex shader, so
+    // there are no vertex buffers. The shadow is blended over
+    // what is already drawn, including the alpha, so that it
+    // stays in frames with transparent background. Meshes hide
+    // the plane, but it doesn't write depth itself.
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
+            format: options.output_depth_attachment_format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }),
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+/// The placement of the current ground shadow, kept for uploading
+/// uniforms when the camera changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GroundShadowUniforms {
+    origin: Point3<f32>,
+    size: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GroundUniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    origin_and_size: [f32; 4],
+    opacity: f32,
+    _padding: [u32; 3],
+}
+
+impl GroundUniforms {
+    fn new(shadow: Option<GroundShadowUniforms>, view_projection_matrix: &Matrix4<f32>) -> Self {
+        let origin_and_size = match shadow {
+            Some(GroundShadowUniforms { origin, size }) => [origin.x, origin.y, origin.z, size],
+            None => [0.0; 4],
+        };
+
+        Self {
+            view_projection_matrix: (*view_projection_matrix).into(),
+            origin_and_size,
+            opacity: GROUND_SHADOW_OPACITY,
+            _padding: [0; 3],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Rotation3, Vector3};
+
+    use crate::mesh::primitive;
+
+    use super::*;
+
+    #[test]
+    fn test_ground_shadow_from_meshes_is_darkest_below_geometry() {
+        let resting_box = primitive::create_box(
+            Point3::new(0.0, 0.0, 0.5),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let floating_box = primitive::create_box(
+            Point3::new(4.0, 0.0, 2.5),
+            Rotation3::identity(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        let ground_shadow = GroundShadow::from_meshes(vec![&resting_box, &floating_box])
+            .expect("Failed to compute ground shadow");
+
+        let below_resting = ground_shadow.intensity_at(Point2::new(0.0, 0.0));
+        let below_floating = ground_shadow.intensity_at(Point2::new(4.0, 0.0));
+        let beside = ground_shadow.intensity_at(Point2::new(2.0, 3.0));
+
+        assert!(below_resting > 0.9);
+        assert!(below_floating > 0.0 && below_floating < below_resting);
+        assert!(beside < f32::EPSILON);
+        assert!(ground_shadow.origin().z < 0.0);
+        assert!(ground_shadow.size() > 5.0);
+    }
+
+    #[test]
+    fn test_ground_shadow_from_no_meshes_is_none() {
+        assert_eq!(GroundShadow::from_meshes(vec![]), None);
+    }
+}
@@ -5,7 +5,7 @@ use imgui::internal::RawWrapper;
 
 use crate::include_shader;
 
-use super::common::{upload_texture_rgba8_unorm, wgpu_size_of};
+use super::common::{upload_texture_rgba8_unorm, wgpu_size_of, UniformBufferRing};
 
 #[derive(Debug, Clone)]
 pub enum Error {
@@ -22,9 +22,9 @@ pub struct Options {
 pub struct ImguiRenderer {
     texture_resources: imgui::Textures<Texture>,
     sampler: wgpu::Sampler,
-    transform_buffer: wgpu::Buffer,
-    transform_bind_group: wgpu::BindGroup,
+    transform_buffers: UniformBufferRing,
     texture_bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
     render_pipeline: wgpu::RenderPipeline,
 
     options: Options,
@@ -37,24 +37,7 @@ impl ImguiRenderer {
         queue: &mut wgpu::Queue,
         options: Options,
     ) -> Result<ImguiRenderer, Error> {
-        // Link shaders
-
-        let vs_spv: &[u8] = include_shader!("imgui.vert.spv");
-        let fs_spv: &[u8] = include_shader!("imgui.frag.spv");
-        let vs_words =
-            wgpu::read_spirv(io::Cursor::new(vs_spv)).expect("Couldn't read pre-built SPIR-V");
-        let fs_words =
-            wgpu::read_spirv(io::Cursor::new(fs_spv)).expect("Couldn't read pre-built SPIR-V");
-        let vs_module = device.create_shader_module(&vs_words);
-        let fs_module = device.create_shader_module(&fs_words);
-
-        // Create transform uniform buffer bind group
-        let transform_buffer_size = wgpu_size_of::<TransformUniforms>();
-        let transform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            size: transform_buffer_size,
-            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
-        });
-
+        // Create transform uniform buffer bind groups
         let transform_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 bindings: &[wgpu::BindGroupLayoutBinding {
@@ -64,16 +47,11 @@ impl ImguiRenderer {
                 }],
             });
 
-        let transform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &transform_bind_group_layout,
-            bindings: &[wgpu::Binding {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer {
-                    buffer: &transform_buffer,
-                    range: 0..transform_buffer_size,
-                },
-            }],
-        });
+        let transform_buffers = UniformBufferRing::new(
+            device,
+            &transform_bind_group_layout,
+            wgpu_size_of::<TransformUniforms>(),
+        );
 
         // Create texture uniform bind group
         let texture_bind_group_layout =
@@ -101,90 +79,7 @@ impl ImguiRenderer {
             bind_group_layouts: &[&transform_bind_group_layout, &texture_bind_group_layout],
         });
 
-        // Setup render state: alpha-blending enabled, no face
-        // culling, no depth testing
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            layout: &pipeline_layout,
-            vertex_stage: wgpu::ProgrammableStageDescriptor {
-                module: &vs_module,
-                entry_point: "main",
-            },
-            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
-                module: &fs_module,
-                entry_point: "main",
-            }),
-            rasterization_state: None,
-            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
-            color_states: &[wgpu::ColorStateDescriptor {
-                format: options.output_color_attachment_format,
-                // Enable alpha blending
-                color_blend: wgpu::BlendDescriptor {
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
-                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                    operation: wgpu::BlendOperation::Add,
-                },
-                alpha_blend: wgpu::BlendDescriptor {
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
-                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                    operation: wgpu::BlendOperation::Add,
-                },
-                write_mask: wgpu::ColorWrite::ALL,
-            }],
-            // Disabled depth test
-            depth_stencil_state: None,
-            index_format: wgpu::IndexFormat::Uint16, // FIXME(yanchith): may need 32bit indices!
-            vertex_buffers: &[wgpu::VertexBufferDescriptor {
-                stride: wgpu_size_of::<imgui::DrawVert>(),
-                step_mode: wgpu::InputStepMode::Vertex,
-                attributes: &[
-                    wgpu::VertexAttributeDescriptor {
-                        offset: 0,
-                        format: wgpu::VertexFormat::Float2,
-                        shader_location: 0,
-                    },
-                    wgpu::VertexAttributeDescriptor {
-                        offset: 8,
-                        format: wgpu::VertexFormat::Float2,
-                        shader_location: 1,
-                    },
-                    wgpu::VertexAttributeDescriptor {
-                        offset: 16,
-                        format: wgpu::VertexFormat::Uint,
-                        shader_location: 2,
-                    },
-                ],
-            }],
-            sample_count: options.sample_count,
-            sample_mask: !0,
-            alpha_to_coverage_enabled: false,
-        });
-
-        // Create the font texture and add it to the font atlas
-
-        let font_atlas_image = imgui_font_atlas.build_rgba32_texture();
-        let font_atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: wgpu::Extent3d {
-                width: font_atlas_image.width,
-                height: font_atlas_image.height,
-                depth: 1,
-            },
-            array_layer_count: 1,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
-        });
-
-        upload_texture_rgba8_unorm(
-            device,
-            queue,
-            &font_atlas_texture,
-            font_atlas_image.width,
-            font_atlas_image.height,
-            font_atlas_image.data,
-        );
+        let render_pipeline = create_render_pipeline(device, &pipeline_layout, options);
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -198,10 +93,13 @@ impl ImguiRenderer {
             compare_function: wgpu::CompareFunction::Always,
         });
 
-        let font_atlas_texture_resource = Texture::new(
+        // Create the font texture and add it to the font atlas
+
+        let font_atlas_texture_resource = create_font_atlas_texture(
+            &mut imgui_font_atlas,
             device,
+            queue,
             &texture_bind_group_layout,
-            &font_atlas_texture,
             &sampler,
         );
         let mut texture_resources = imgui::Textures::new();
@@ -211,14 +109,42 @@ impl ImguiRenderer {
         Ok(ImguiRenderer {
             texture_resources,
             sampler,
+            pipeline_layout,
             render_pipeline,
-            transform_buffer,
-            transform_bind_group,
+            transform_buffers,
             texture_bind_group_layout,
             options,
         })
     }
 
+    /// Recreates the render pipeline for a new multi-sampling setting
+    /// of the output attachment.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.render_pipeline = create_render_pipeline(device, &self.pipeline_layout, self.options);
+    }
+
+    /// Rebuilds the font atlas texture, e.g. after fonts were
+    /// rasterized at a new scale.
+    pub fn set_font_atlas(
+        &mut self,
+        mut imgui_font_atlas: imgui::FontAtlasRefMut,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+    ) {
+        let font_atlas_texture_resource = create_font_atlas_texture(
+            &mut imgui_font_atlas,
+            device,
+            queue,
+            &self.texture_bind_group_layout,
+            &self.sampler,
+        );
+
+        let font_atlas_texture_id = self.texture_resources.insert(font_atlas_texture_resource);
+        self.texture_resources.remove(imgui_font_atlas.tex_id);
+        imgui_font_atlas.tex_id = font_atlas_texture_id;
+    }
+
     pub fn add_texture_rgba8_unorm(
         &mut self,
         device: &wgpu::Device,
@@ -259,7 +185,7 @@ impl ImguiRenderer {
     }
 
     pub fn draw_ui(
-        &self,
+        &mut self,
         color_needs_clearing: bool,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
@@ -288,18 +214,14 @@ impl ImguiRenderer {
         ];
 
         let transform_uniforms = TransformUniforms { translate, scale };
-        let transform_uniforms_size = wgpu_size_of::<TransformUniforms>();
         let transform_transfer_buffer = device
             .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
             .fill_from_slice(&[transform_uniforms]);
 
-        encoder.copy_buffer_to_buffer(
-            &transform_transfer_buffer,
-            0,
-            &self.transform_buffer,
-            0,
-            transform_uniforms_size,
-        );
+        // Written to the next buffer of the ring, so that the copy does
+        // not wait for the GPU to finish drawing previous frames
+        self.transform_buffers
+            .write(encoder, &transform_transfer_buffer);
 
         // Will project scissor/clipping rectangles into framebuffer space
         let clip_off = draw_data.display_pos; // (0,0) unless using multi-viewports
@@ -360,7 +282,7 @@ impl ImguiRenderer {
         });
 
         rpass.set_pipeline(&self.render_pipeline);
-        rpass.set_bind_group(0, &self.transform_bind_group, &[]);
+        rpass.set_bind_group(0, self.transform_buffers.bind_group(), &[]);
 
         for draw_list in draw_data.draw_lists() {
             let vtx_buffer = draw_list.vtx_buffer();
@@ -470,6 +392,120 @@ impl Texture {
     }
 }
 
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    // Link shaders
+
+    let vs_spv: &[u8] = include_shader!("imgui.vert.spv");
+    let fs_spv: &[u8] = include_shader!("imgui.frag.spv");
+    let vs_words =
+        wgpu::read_spirv(io::Cursor::new(vs_spv)).expect("Couldn't read pre-built SPIR-V");
+    let fs_words =
+        wgpu::read_spirv(io::Cursor::new(fs_spv)).expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    // Setup render state: alpha-blending enabled, no face
+    // culling, no depth testing
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: None,
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            // Enable alpha blending
+            color_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha_blend: wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        // Disabled depth test
+        depth_stencil_state: None,
+        index_format: wgpu::IndexFormat::Uint16, // FIXME(yanchith): may need 32bit indices!
+        vertex_buffers: &[wgpu::VertexBufferDescriptor {
+            stride: wgpu_size_of::<imgui::DrawVert>(),
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttributeDescriptor {
+                    offset: 0,
+                    format: wgpu::VertexFormat::Float2,
+                    shader_location: 0,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: 8,
+                    format: wgpu::VertexFormat::Float2,
+                    shader_location: 1,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: 16,
+                    format: wgpu::VertexFormat::Uint,
+                    shader_location: 2,
+                },
+            ],
+        }],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+fn create_font_atlas_texture(
+    imgui_font_atlas: &mut imgui::FontAtlasRefMut,
+    device: &wgpu::Device,
+    queue: &mut wgpu::Queue,
+    texture_bind_group_layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+) -> Texture {
+    let font_atlas_image = imgui_font_atlas.build_rgba32_texture();
+    let font_atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width: font_atlas_image.width,
+            height: font_atlas_image.height,
+            depth: 1,
+        },
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+    });
+
+    upload_texture_rgba8_unorm(
+        device,
+        queue,
+        &font_atlas_texture,
+        font_atlas_image.width,
+        font_atlas_image.height,
+        font_atlas_image.data,
+    );
+
+    Texture::new(
+        device,
+        texture_bind_group_layout,
+        &font_atlas_texture,
+        sampler,
+    )
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 struct TransformUniforms {
@@ -1,10 +1,29 @@
-pub use self::scene_renderer::{AddMeshError, DrawMeshMode, GpuMesh, GpuMeshId};
+pub use self::background_renderer::Background;
+pub use self::ground_renderer::GroundShadow;
+pub use self::post_renderer::PostProcessing;
+pub use self::scene_renderer::{
+    AddMeshError, DebugView, DrawMeshMode, GpuCurve, GpuCurveId, GpuMesh, GpuMeshId,
+    GpuMeshTopology, GpuPoints, GpuPointsId, Matcap, Material, MeshShading, Section, SectionFill,
+};
 
+use std::error;
 use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use nalgebra::Matrix4;
 
+use crate::convert::cast_usize;
+use crate::math;
+
+use self::background_renderer::{BackgroundRenderer, Options as BackgroundRendererOptions};
+use self::grid_renderer::{GridRenderer, Options as GridRendererOptions};
+use self::ground_renderer::{GroundRenderer, Options as GroundRendererOptions};
 use self::imgui_renderer::{ImguiRenderer, Options as ImguiRendererOptions};
+use self::post_renderer::{Options as PostRendererOptions, PostInput, PostRenderer};
 use self::scene_renderer::{
     ClearFlags as SceneRendererClearFlags, Options as SceneRendererOptions, SceneRenderer,
 };
@@ -12,16 +31,27 @@ use self::scene_renderer::{
 #[macro_use]
 mod common;
 
+mod background_renderer;
+mod grid_renderer;
+mod ground_renderer;
 mod imgui_renderer;
+mod post_renderer;
 mod scene_renderer;
 
 const SWAP_CHAIN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8Unorm;
-const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+// The stencil is used for capping meshes cut by sections
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
+/// The largest width or height of a captured frame. Larger textures
+/// are not supported by all GPUs.
+const CAPTURE_SIZE_MAX: u32 = 8192;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Options {
     /// With what color to clear the screen.
     pub clear_color: [f64; 4],
+    /// What to draw behind the scene, until changed with
+    /// `renderer.set_background()`.
+    pub background: Background,
     /// Which multi-sampling setting to use.
     pub msaa: Msaa,
     /// Whether to run with VSync or not.
@@ -41,6 +71,9 @@ pub enum Msaa {
 }
 
 impl Msaa {
+    pub const ALL: [Msaa; 4] = [Msaa::Disabled, Msaa::X4, Msaa::X8, Msaa::X16];
+    pub const NAMES: [&'static str; 4] = ["Off", "4x", "8x", "16x"];
+
     pub fn enabled(self) -> bool {
         match self {
             Msaa::Disabled => false,
@@ -86,6 +119,87 @@ impl fmt::Display for PresentMode {
     }
 }
 
+/// A rendered frame read back from the GPU. Contains RGBA8 pixels, row
+/// by row from the top left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl CapturedFrame {
+    /// Encodes the frame as a JPEG image with `quality` between 1 and
+    /// 100. The alpha channel is dropped.
+    pub fn encode_jpeg(&self, quality: u8) -> io::Result<Vec<u8>> {
+        let mut jpeg_data = Vec::new();
+        let mut encoder = image::jpeg::JPEGEncoder::new_with_quality(&mut jpeg_data, quality);
+        encoder.encode(&self.data, self.width, self.height, image::RGBA(8))?;
+
+        Ok(jpeg_data)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+        let file = File::create(path)?;
+        self.write_png(BufWriter::new(file))
+    }
+
+    fn write_png<W: io::Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+
+        Ok(())
+    }
+}
+
+/// An error preventing a frame from being captured to an image file.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// The window has zero size, e.g. because it is minimized.
+    Paused,
+    /// The rendered frame could not be read back from the GPU.
+    ReadFailed,
+    Png(png::EncodingError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaptureError::Paused => write!(f, "Rendering is paused"),
+            CaptureError::ReadFailed => write!(f, "Failed to read the frame from the GPU"),
+            CaptureError::Png(err) => write!(f, "Failed to save PNG: {}", err),
+        }
+    }
+}
+
+impl error::Error for CaptureError {}
+
+/// An error preventing a frame from being rendered. The renderer
+/// recovers from it, so rendering can be attempted again in the next
+/// frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// The window has zero size, e.g. because it is minimized.
+    Paused,
+    /// The swap chain texture could not be acquired. The swap chain
+    /// will be recreated for the next frame.
+    SwapChainLost,
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Paused => write!(f, "Rendering is paused"),
+            RenderError::SwapChainLost => write!(f, "Failed to acquire swap chain texture"),
+        }
+    }
+}
+
+impl error::Error for RenderError {}
+
 /// The rendering backend used by `wgpu-rs`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GpuBackend {
@@ -104,6 +218,29 @@ impl fmt::Display for GpuBackend {
     }
 }
 
+/// A window or other platform view the renderer presents to.
+///
+/// Abstracted, so that platforms without native windows, such as
+/// browsers, can provide their own surfaces.
+pub trait SurfaceSource {
+    /// Creates a surface for presenting rendered frames.
+    fn create_surface(&self) -> wgpu::Surface;
+
+    /// Returns the size of the surface in physical pixels.
+    fn physical_size(&self) -> (u32, u32);
+}
+
+impl SurfaceSource for winit::window::Window {
+    fn create_surface(&self) -> wgpu::Surface {
+        wgpu::Surface::create(self)
+    }
+
+    fn physical_size(&self) -> (u32, u32) {
+        let size = self.inner_size().to_physical(self.hidpi_factor());
+        (size.width.round() as u32, size.height.round() as u32)
+    }
+}
+
 /// High level renderer abstraction over wgpu-rs.
 ///
 /// Handles GPU resources (swap chain, msaa buffer, depth buffer) and
@@ -115,6 +252,11 @@ impl fmt::Display for GpuBackend {
 /// draw commands. Use `renderer.begin_render_pass()` to start
 /// recording draw commands and `render_pass.submit()` to execute
 /// them.
+///
+/// Failures to acquire the swap chain texture, e.g. after the surface
+/// becomes outdated or the driver resets, are recovered from by
+/// skipping the frame and recreating the swap chain before the next
+/// one.
 pub struct Renderer {
     device: wgpu::Device,
     queue: wgpu::Queue,
@@ -122,16 +264,22 @@ pub struct Renderer {
     width: u32,
     height: u32,
     swap_chain: wgpu::SwapChain,
+    swap_chain_lost: bool,
     msaa_texture_view: Option<wgpu::TextureView>,
     depth_texture_view: wgpu::TextureView,
+    background_renderer: BackgroundRenderer,
     scene_renderer: SceneRenderer,
+    ground_renderer: GroundRenderer,
+    grid_renderer: GridRenderer,
     imgui_renderer: ImguiRenderer,
+    post_renderer: PostRenderer,
+    capture_target: Option<CaptureTarget>,
     options: Options,
 }
 
 impl Renderer {
-    pub fn new(
-        window: &winit::window::Window,
+    pub fn new<S: SurfaceSource>(
+        surface_source: &S,
         projection_matrix: &Matrix4<f32>,
         view_matrix: &Matrix4<f32>,
         imgui_font_atlas: imgui::FontAtlasRefMut,
@@ -150,7 +298,7 @@ impl Renderer {
             log::info!("No GPU backend selected, will run on default backend");
         }
 
-        let surface = wgpu::Surface::create(window);
+        let surface = surface_source.create_surface();
         let adapter = wgpu::Adapter::request(&wgpu::RequestAdapterOptions {
             power_preference: wgpu::PowerPreference::HighPerformance,
             backends,
@@ -164,29 +312,54 @@ impl Renderer {
             limits: wgpu::Limits::default(),
         });
 
-        let window_size = window.inner_size().to_physical(window.hidpi_factor());
-        let (width, height) = (
-            window_size.width.round() as u32,
-            window_size.height.round() as u32,
-        );
+        let (width, height) = surface_source.physical_size();
+        if width == 0 || height == 0 {
+            log::debug!("Surface has zero size, rendering is paused until resized");
+        }
 
-        let swap_chain = create_swap_chain(&device, &surface, width, height, options.present_mode);
+        // Screen textures can't have zero size. Until the surface is
+        // resized, they are only placeholders.
+        let (texture_width, texture_height) = (width.max(1), height.max(1));
+
+        let swap_chain = create_swap_chain(
+            &device,
+            &surface,
+            texture_width,
+            texture_height,
+            options.present_mode,
+        );
 
         log::info!("Selected multisampling level: {}", options.msaa);
         let msaa_texture = if options.msaa.enabled() {
             Some(create_msaa_texture(
                 &device,
-                width,
-                height,
+                texture_width,
+                texture_height,
                 options.msaa.sample_count(),
             ))
         } else {
             None
         };
-        let depth_texture =
-            create_depth_texture(&device, width, height, options.msaa.sample_count());
+        let depth_texture = create_depth_texture(
+            &device,
+            texture_width,
+            texture_height,
+            options.msaa.sample_count(),
+        );
+
+        let background_renderer = BackgroundRenderer::new(
+            &device,
+            &mut queue,
+            options.background,
+            projection_matrix,
+            view_matrix,
+            BackgroundRendererOptions {
+                sample_count: options.msaa.sample_count(),
+                output_color_attachment_format: SWAP_CHAIN_FORMAT,
+            },
+        );
 
-        let scene_renderer = SceneRenderer::new(
+        let mut scene_renderer = SceneRenderer::new(
             &device,
             &mut queue,
             projection_matrix,
@@ -198,6 +371,31 @@ impl Renderer {
                 output_depth_attachment_format: DEPTH_FORMAT,
             },
         );
+        scene_renderer.set_viewport_size(&device, &mut queue, texture_width, texture_height);
+
+        let ground_renderer = GroundRenderer::new(
+            &device,
+            &mut queue,
+            projection_matrix,
+            view_matrix,
+            GroundRendererOptions {
+                sample_count: options.msaa.sample_count(),
+                output_color_attachment_format: SWAP_CHAIN_FORMAT,
+                output_depth_attachment_format: DEPTH_FORMAT,
+            },
+        );
+
+        let grid_renderer = GridRenderer::new(
+            &device,
+            &mut queue,
+            projection_matrix,
+            view_matrix,
+            GridRendererOptions {
+                sample_count: options.msaa.sample_count(),
+                output_color_attachment_format: SWAP_CHAIN_FORMAT,
+                output_depth_attachment_format: DEPTH_FORMAT,
+            },
+        );
 
         let imgui_renderer = ImguiRenderer::new(
             imgui_font_atlas,
@@ -211,6 +409,17 @@ impl Renderer {
         )
         .expect("Failed to create imgui renderer");
 
+        let post_renderer = PostRenderer::new(
+            &device,
+            projection_matrix,
+            PostRendererOptions {
+                sample_count: options.msaa.sample_count(),
+                scene_color_attachment_format: SWAP_CHAIN_FORMAT,
+                scene_depth_attachment_format: DEPTH_FORMAT,
+                output_color_attachment_format: SWAP_CHAIN_FORMAT,
+            },
+        );
+
         Self {
             device,
             queue,
@@ -218,10 +427,16 @@ impl Renderer {
             width,
             height,
             swap_chain,
+            swap_chain_lost: false,
             msaa_texture_view: msaa_texture.map(|texture| texture.create_default_view()),
             depth_texture_view: depth_texture.create_default_view(),
+            background_renderer,
             scene_renderer,
+            ground_renderer,
+            grid_renderer,
             imgui_renderer,
+            post_renderer,
+            capture_target: None,
             options,
         }
     }
@@ -238,10 +453,70 @@ impl Renderer {
             projection_matrix,
             view_matrix,
         );
+        self.background_renderer.set_camera_matrices(
+            &self.device,
+            &mut self.queue,
+            projection_matrix,
+            view_matrix,
+        );
+        self.ground_renderer.set_camera_matrices(
+            &self.device,
+            &mut self.queue,
+            projection_matrix,
+            view_matrix,
+        );
+        self.grid_renderer.set_camera_matrices(
+            &self.device,
+            &mut self.queue,
+            projection_matrix,
+            view_matrix,
+        );
+        self.post_renderer.set_projection_matrix(projection_matrix);
+    }
+
+    /// Sets the shadow drawn by `render_pass.draw_ground_shadow()`, or
+    /// removes it.
+    pub fn set_ground_shadow(&mut self, ground_shadow: Option<&GroundShadow>) {
+        self.ground_renderer
+            .set_ground_shadow(&self.device, &mut self.queue, ground_shadow);
+    }
+
+    /// Sets the section plane cutting the scene, or removes it.
+    pub fn set_section(&mut self, section: Option<Section>) {
+        self.scene_renderer
+            .set_section(&self.device, &mut self.queue, section);
+    }
+
+    /// Sets what is drawn by `render_pass.draw_background()`.
+    pub fn set_background(&mut self, background: Background) {
+        self.options.background = background;
+        self.background_renderer
+            .set_background(&self.device, &mut self.queue, background);
+    }
+
+    /// Sets the environment image used by `Background::Environment`.
+    /// The image is RGBA8 in the equirectangular projection.
+    pub fn set_background_environment(&mut self, width: u32, height: u32, data: &[u8]) {
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * 4,
+            "Environment image data must be RGBA8 of the given size",
+        );
+        self.background_renderer.set_environment(
+            &self.device,
+            &mut self.queue,
+            width,
+            height,
+            data,
+        );
     }
 
     /// Update window size. Recreate swap chain and all render target
     /// textures.
+    ///
+    /// If the window has zero size, e.g. when minimized, rendering is
+    /// paused and resources are only recreated once the window is
+    /// restored.
     pub fn set_window_size(&mut self, window_size: winit::dpi::PhysicalSize) {
         let (width, height) = (
             window_size.width.round() as u32,
@@ -258,39 +533,166 @@ impl Renderer {
             self.width = width;
             self.height = height;
 
-            self.swap_chain = create_swap_chain(
-                &self.device,
-                &self.surface,
-                width,
-                height,
-                self.options.present_mode,
-            );
+            if self.is_paused() {
+                log::debug!("Window has zero size, pausing rendering");
+            } else {
+                self.recreate_screen_textures();
+                self.scene_renderer
+                    .set_viewport_size(&self.device, &mut self.queue, width, height);
+            }
+        }
+    }
 
-            if self.options.msaa.enabled() {
-                let msaa_texture = create_msaa_texture(
-                    &self.device,
-                    width,
-                    height,
-                    self.options.msaa.sample_count(),
-                );
+    /// Whether rendering is paused, because the window has zero
+    /// size. No render passes can be started while paused.
+    pub fn is_paused(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns the options the renderer currently runs with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Applies new renderer options without recreating the renderer.
+    ///
+    /// The multisampling level, present mode and background take
+    /// effect from the next frame, recreating the pipelines and
+    /// screen textures as needed. The clear color and GPU backend are
+    /// only read when the renderer is created and changes to them are
+    /// ignored.
+    pub fn set_options(&mut self, options: Options) {
+        if options.gpu_backend != self.options.gpu_backend {
+            log::warn!("GPU backend can only be selected at startup, ignoring change");
+        }
+        if options.clear_color != self.options.clear_color {
+            log::warn!("Clear color can only be selected at startup, ignoring change");
+        }
+
+        if options.background != self.options.background {
+            self.set_background(options.background);
+        }
+
+        let msaa_changed = options.msaa != self.options.msaa;
+        let present_mode_changed = options.present_mode != self.options.present_mode;
+
+        self.options.msaa = options.msaa;
+        self.options.present_mode = options.present_mode;
 
-                self.msaa_texture_view = Some(msaa_texture.create_default_view());
+        if msaa_changed {
+            log::info!("Selected multisampling level: {}", options.msaa);
+
+            let sample_count = options.msaa.sample_count();
+            self.background_renderer
+                .set_sample_count(&self.device, sample_count);
+            self.scene_renderer
+                .set_sample_count(&self.device, sample_count);
+            self.ground_renderer
+                .set_sample_count(&self.device, sample_count);
+            self.grid_renderer
+                .set_sample_count(&self.device, sample_count);
+            self.imgui_renderer
+                .set_sample_count(&self.device, sample_count);
+            self.post_renderer
+                .set_sample_count(&self.device, sample_count);
+        }
+
+        if msaa_changed || present_mode_changed {
+            if self.is_paused() {
+                // Screen textures are recreated once the window is
+                // restored, but the capture target must not outlive
+                // the old sample count
+                self.capture_target = None;
+            } else {
+                self.recreate_screen_textures();
             }
+        }
+    }
 
-            let depth_texture = create_depth_texture(
+    /// Recreates the swap chain and all render target textures with
+    /// the current dimensions.
+    fn recreate_screen_textures(&mut self) {
+        self.swap_chain = create_swap_chain(
+            &self.device,
+            &self.surface,
+            self.width,
+            self.height,
+            self.options.present_mode,
+        );
+
+        if self.options.msaa.enabled() {
+            let msaa_texture = create_msaa_texture(
                 &self.device,
-                width,
-                height,
+                self.width,
+                self.height,
                 self.options.msaa.sample_count(),
             );
-            self.depth_texture_view = depth_texture.create_default_view();
+
+            self.msaa_texture_view = Some(msaa_texture.create_default_view());
+        } else {
+            self.msaa_texture_view = None;
         }
+
+        let depth_texture = create_depth_texture(
+            &self.device,
+            self.width,
+            self.height,
+            self.options.msaa.sample_count(),
+        );
+        self.depth_texture_view = depth_texture.create_default_view();
+
+        // The capture target is lazily re-created with the new
+        // dimensions once needed
+        self.capture_target = None;
     }
 
     /// Uploads mesh to the GPU to be used in scene rendering. It
     /// will be available for drawing in subsequent render passes.
     pub fn add_scene_mesh(&mut self, mesh: &GpuMesh) -> Result<GpuMeshId, AddMeshError> {
-        self.scene_renderer.add_mesh(&self.device, mesh)
+        self.scene_renderer
+            .add_mesh(&self.device, &mut self.queue, mesh)
+    }
+
+    /// Places a previously uploaded mesh in the scene with the
+    /// `transform`, without re-uploading its vertex data.
+    pub fn set_scene_mesh_transform(&mut self, id: GpuMeshId, transform: &Matrix4<f32>) {
+        self.scene_renderer
+            .set_mesh_transform(&self.device, &mut self.queue, id, transform);
+    }
+
+    /// Sets the tint blended over the shading color of a scene mesh,
+    /// e.g. to highlight the selected mesh. `None` removes the tint.
+    pub fn set_scene_mesh_tint(&mut self, id: GpuMeshId, tint: Option<[f32; 3]>) {
+        self.scene_renderer
+            .set_mesh_tint(&self.device, &mut self.queue, id, tint);
+    }
+
+    /// Sets the false-color debug view of the scene meshes.
+    pub fn set_debug_view(&mut self, debug_view: DebugView) {
+        self.scene_renderer
+            .set_debug_view(&self.device, &mut self.queue, debug_view);
+    }
+
+    /// Sets whether a scene mesh is shaded smooth or flat and with
+    /// which matcap.
+    pub fn set_scene_mesh_shading(&mut self, id: GpuMeshId, shading: MeshShading) {
+        self.scene_renderer
+            .set_mesh_shading(&self.device, &mut self.queue, id, shading);
+    }
+
+    /// Sets the base color, roughness and opacity of a scene mesh,
+    /// e.g. to tell it apart from other meshes.
+    pub fn set_scene_mesh_material(&mut self, id: GpuMeshId, material: Material) {
+        self.scene_renderer
+            .set_mesh_material(&self.device, &mut self.queue, id, material);
+    }
+
+    /// Uploads the topology of a scene mesh, so that its edges and
+    /// vertices are drawn by `render_pass.draw_topology()`, or removes
+    /// it.
+    pub fn set_scene_mesh_topology(&mut self, id: GpuMeshId, topology: Option<&GpuMeshTopology>) {
+        self.scene_renderer
+            .set_mesh_topology(&self.device, id, topology);
     }
 
     /// Removes mesh from the GPU.
@@ -298,6 +700,37 @@ impl Renderer {
         self.scene_renderer.remove_mesh(id);
     }
 
+    /// Uploads curve to the GPU to be drawn by
+    /// `render_pass.draw_curves_and_points()` in subsequent render
+    /// passes.
+    pub fn add_scene_curve(&mut self, curve: &GpuCurve) -> GpuCurveId {
+        self.scene_renderer.add_curve(&self.device, curve)
+    }
+
+    /// Removes curve from the GPU.
+    pub fn remove_scene_curve(&mut self, id: GpuCurveId) {
+        self.scene_renderer.remove_curve(id);
+    }
+
+    /// Uploads points to the GPU to be drawn by
+    /// `render_pass.draw_curves_and_points()` in subsequent render
+    /// passes.
+    pub fn add_scene_points(&mut self, points: &GpuPoints) -> GpuPointsId {
+        self.scene_renderer.add_points(&self.device, points)
+    }
+
+    /// Removes points from the GPU.
+    pub fn remove_scene_points(&mut self, id: GpuPointsId) {
+        self.scene_renderer.remove_points(id);
+    }
+
+    /// Re-uploads the UI font atlas texture, e.g. after the UI changed
+    /// its scale.
+    pub fn set_ui_font_atlas(&mut self, imgui_font_atlas: imgui::FontAtlasRefMut) {
+        self.imgui_renderer
+            .set_font_atlas(imgui_font_atlas, &self.device, &mut self.queue);
+    }
+
     /// Uploads an RGBA8 texture to the GPU to be used in UI
     /// rendering. It will be available for drawing in the subsequent
     /// render passes.
@@ -324,23 +757,299 @@ impl Renderer {
     }
 
     /// Starts recording draw commands.
-    pub fn begin_render_pass(&mut self) -> RenderPass {
-        let frame = self.swap_chain.get_next_texture();
+    ///
+    /// Fails, if rendering is paused, or if the swap chain texture can
+    /// not be acquired. The swap chain is then recreated when starting
+    /// the next render pass.
+    pub fn begin_render_pass(&mut self) -> Result<RenderPass, RenderError> {
+        if self.is_paused() {
+            return Err(RenderError::Paused);
+        }
+
+        if self.swap_chain_lost {
+            log::info!("Recreating lost swap chain");
+            self.recreate_screen_textures();
+            self.swap_chain_lost = false;
+        }
+
+        // FIXME: The wgpu version in use does not report swap chain
+        // acquisition failures, but panics once it fails to
+        // reconfigure the swap chain, e.g. after a driver reset. We
+        // catch the panic, so that it doesn't bring down the editor,
+        // and try again with a new swap chain in the next frame. This
+        // can go away once wgpu returns errors.
+        let frame = match acquire_swap_chain_texture(&mut self.swap_chain) {
+            Some(frame) => frame,
+            None => {
+                log::error!("Failed to acquire swap chain texture");
+                self.swap_chain_lost = true;
+                return Err(RenderError::SwapChainLost);
+            }
+        };
+
         let encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
 
-        RenderPass {
+        Ok(RenderPass {
             color_needs_clearing: true,
             depth_needs_clearing: true,
+            width: self.width,
+            height: self.height,
             device: &self.device,
             queue: &mut self.queue,
-            frame,
+            target: RenderTarget::SwapChain(frame),
             encoder: Some(encoder),
             msaa_attachment: self.msaa_texture_view.as_ref(),
             depth_attachment: &self.depth_texture_view,
+            background_renderer: &self.background_renderer,
+            scene_renderer: &self.scene_renderer,
+            ground_renderer: &self.ground_renderer,
+            grid_renderer: &self.grid_renderer,
+            imgui_renderer: &mut self.imgui_renderer,
+            post_renderer: &mut self.post_renderer,
+        })
+    }
+
+    /// Starts recording draw commands into an offscreen texture
+    /// instead of the window. Once the render pass is submitted, the
+    /// rendered frame can be read with `read_captured_frame`.
+    ///
+    /// The texture is `scale` times larger than the window, e.g. to
+    /// capture images with higher resolution than the screen. The
+    /// scale is lowered if the texture would be larger than GPUs
+    /// support. If `post_processing` is given, the effects are applied
+    /// to the captured frame when the render pass is submitted.
+    ///
+    /// # Panics
+    /// Panics if rendering is paused.
+    pub fn begin_capture_render_pass(
+        &mut self,
+        post_processing: Option<PostProcessing>,
+        scale: u32,
+    ) -> RenderPass {
+        assert!(
+            !self.is_paused(),
+            "Can't capture frames while rendering is paused",
+        );
+
+        let scale_max = (CAPTURE_SIZE_MAX / self.width.max(self.height)).max(1);
+        let scale = scale.max(1).min(scale_max);
+        let (width, height) = (self.width * scale, self.height * scale);
+        if let Some(capture_target) = &self.capture_target {
+            if (capture_target.width, capture_target.height) != (width, height) {
+                self.capture_target = None;
+            }
+        }
+
+        let device = &self.device;
+        let post_renderer = &self.post_renderer;
+        let sample_count = self.options.msaa.sample_count();
+        let capture_target = self.capture_target.get_or_insert_with(|| {
+            CaptureTarget::new(device, post_renderer, width, height, sample_count)
+        });
+
+        let encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+
+        RenderPass {
+            color_needs_clearing: true,
+            depth_needs_clearing: true,
+            width,
+            height,
+            device: &self.device,
+            queue: &mut self.queue,
+            target: RenderTarget::Capture {
+                capture_target,
+                post_processing,
+            },
+            encoder: Some(encoder),
+            msaa_attachment: capture_target.msaa_texture_view.as_ref(),
+            depth_attachment: capture_target.post_input.depth_attachment(),
+            background_renderer: &self.background_renderer,
             scene_renderer: &self.scene_renderer,
-            imgui_renderer: &self.imgui_renderer,
+            ground_renderer: &self.ground_renderer,
+            grid_renderer: &self.grid_renderer,
+            imgui_renderer: &mut self.imgui_renderer,
+            post_renderer: &mut self.post_renderer,
+        }
+    }
+
+    /// Reads the frame rendered by the last submitted capture render
+    /// pass back from the GPU. Blocks until the GPU finishes
+    /// rendering.
+    ///
+    /// Returns `None` if there is nothing to read, e.g. no capture
+    /// render pass was submitted since the window was resized.
+    pub fn read_captured_frame(&mut self) -> Option<CapturedFrame> {
+        let capture_target = self.capture_target.as_ref()?;
+        let data: Arc<Mutex<Option<Vec<u8>>>> = Arc::new(Mutex::new(None));
+
+        let data_in_callback = Arc::clone(&data);
+        capture_target.buffer.map_read_async(
+            0,
+            capture_target.buffer_size(),
+            move |result: wgpu::BufferMapAsyncResult<&[u8]>| {
+                if let Ok(mapping) = result {
+                    let mut data = data_in_callback
+                        .lock()
+                        .expect("Failed to lock captured frame data");
+                    *data = Some(mapping.data.to_vec());
+                }
+            },
+        );
+        self.device.poll(true);
+
+        let padded_data = data
+            .lock()
+            .expect("Failed to lock captured frame data")
+            .take()?;
+
+        // Rows in the buffer are padded to the required row pitch
+        // and pixels are in the BGRA order of the swap chain
+        let width = cast_usize(capture_target.width);
+        let height = cast_usize(capture_target.height);
+        let row_pitch = cast_usize(capture_target.row_pitch);
+        let mut frame_data = Vec::with_capacity(width * height * 4);
+        for row in padded_data.chunks(row_pitch).take(height) {
+            for bgra in row[..width * 4].chunks(4) {
+                frame_data.extend_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+            }
+        }
+
+        Some(CapturedFrame {
+            width: capture_target.width,
+            height: capture_target.height,
+            data: frame_data,
+        })
+    }
+
+    /// Renders a frame drawn by `draw` into an offscreen texture
+    /// `scale` times larger than the window, reads it back from the
+    /// GPU and saves it to `path` as PNG. See
+    /// `begin_capture_render_pass` for the other parameters.
+    pub fn capture_frame<P, F>(
+        &mut self,
+        path: P,
+        post_processing: Option<PostProcessing>,
+        scale: u32,
+        draw: F,
+    ) -> Result<CapturedFrame, CaptureError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut RenderPass),
+    {
+        if self.is_paused() {
+            return Err(CaptureError::Paused);
+        }
+
+        let mut render_pass = self.begin_capture_render_pass(post_processing, scale);
+        draw(&mut render_pass);
+        render_pass.submit();
+
+        let frame = self.read_captured_frame().ok_or(CaptureError::ReadFailed)?;
+        frame.save_png(path).map_err(CaptureError::Png)?;
+
+        Ok(frame)
+    }
+}
+
+/// An offscreen texture to render frames into, which are then read
+/// back from the GPU, and the buffer they are copied to. With post
+/// processing, the scene is first rendered into the `post_input`.
+///
+/// The target has its own multi-sampling texture, because it may be
+/// larger than the window.
+struct CaptureTarget {
+    width: u32,
+    height: u32,
+    row_pitch: u32,
+    texture: wgpu::Texture,
+    texture_view: wgpu::TextureView,
+    msaa_texture_view: Option<wgpu::TextureView>,
+    buffer: wgpu::Buffer,
+    post_input: PostInput,
+}
+
+impl CaptureTarget {
+    fn new(
+        device: &wgpu::Device,
+        post_renderer: &PostRenderer,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        // Buffer rows must be aligned to 256 bytes when copying from
+        // textures
+        const ROW_PITCH_ALIGNMENT: u32 = 256;
+        let unpadded_row_pitch = width * 4;
+        let row_pitch = (unpadded_row_pitch + ROW_PITCH_ALIGNMENT - 1) / ROW_PITCH_ALIGNMENT
+            * ROW_PITCH_ALIGNMENT;
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SWAP_CHAIN_FORMAT,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        });
+        let texture_view = texture.create_default_view();
+        let msaa_texture_view = if sample_count > 1 {
+            Some(create_msaa_texture(device, width, height, sample_count).create_default_view())
+        } else {
+            None
+        };
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            size: wgpu::BufferAddress::from(row_pitch * height),
+            usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+        });
+
+        Self {
+            width,
+            height,
+            row_pitch,
+            texture,
+            texture_view,
+            msaa_texture_view,
+            buffer,
+            post_input: post_renderer.create_input(device, width, height),
+        }
+    }
+
+    fn buffer_size(&self) -> wgpu::BufferAddress {
+        wgpu::BufferAddress::from(self.row_pitch * self.height)
+    }
+}
+
+/// The destination of a render pass.
+enum RenderTarget<'a> {
+    SwapChain(wgpu::SwapChainOutput<'a>),
+    Capture {
+        capture_target: &'a CaptureTarget,
+        post_processing: Option<PostProcessing>,
+    },
+}
+
+impl RenderTarget<'_> {
+    fn color_attachment(&self) -> &wgpu::TextureView {
+        match self {
+            RenderTarget::SwapChain(frame) => &frame.view,
+            RenderTarget::Capture {
+                capture_target,
+                post_processing: Some(_),
+            } => capture_target.post_input.color_attachment(),
+            RenderTarget::Capture {
+                capture_target,
+                post_processing: None,
+            } => &capture_target.texture_view,
         }
     }
 }
@@ -350,23 +1059,263 @@ impl Renderer {
 pub struct RenderPass<'a> {
     color_needs_clearing: bool,
     depth_needs_clearing: bool,
+    width: u32,
+    height: u32,
     device: &'a wgpu::Device,
     queue: &'a mut wgpu::Queue,
-    frame: wgpu::SwapChainOutput<'a>,
+    target: RenderTarget<'a>,
     encoder: Option<wgpu::CommandEncoder>,
     msaa_attachment: Option<&'a wgpu::TextureView>,
     depth_attachment: &'a wgpu::TextureView,
+    background_renderer: &'a BackgroundRenderer,
     scene_renderer: &'a SceneRenderer,
-    imgui_renderer: &'a ImguiRenderer,
+    ground_renderer: &'a GroundRenderer,
+    grid_renderer: &'a GridRenderer,
+    imgui_renderer: &'a mut ImguiRenderer,
+    post_renderer: &'a mut PostRenderer,
 }
 
 impl RenderPass<'_> {
+    /// Record drawing the background set with
+    /// `renderer.set_background()` to the command buffer. Should be
+    /// recorded first, as it covers the whole screen.
+    pub fn draw_background(&mut self) {
+        self.background_renderer.draw_background(
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+        );
+
+        self.color_needs_clearing = false;
+    }
+
+    /// Record clearing the screen to transparent black instead of
+    /// drawing the background, e.g. so that captured frames can be
+    /// composited onto other images.
+    pub fn clear_transparent(&mut self) {
+        let (attachment, resolve_target) = match self.msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(self.target.color_attachment())),
+            None => (self.target.color_attachment(), None),
+        };
+
+        self.encoder
+            .as_mut()
+            .expect("Need encoder to record drawing")
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment,
+                    resolve_target,
+                    load_op: wgpu::LoadOp::Clear,
+                    store_op: wgpu::StoreOp::Store,
+                    clear_color: wgpu::Color::TRANSPARENT,
+                }],
+                depth_stencil_attachment: None,
+            });
+
+        self.color_needs_clearing = false;
+    }
+
+    /// Record drawing the ground shadow set with
+    /// `renderer.set_ground_shadow()` to the command buffer. Should be
+    /// recorded after the meshes, so that they hide the ground plane.
+    pub fn draw_ground_shadow(&mut self) {
+        self.ground_renderer.draw_ground_shadow(
+            self.depth_needs_clearing,
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+            &self.depth_attachment,
+        );
+
+        self.depth_needs_clearing = false;
+    }
+
+    /// Record drawing the ground grid to the command buffer. Should be
+    /// recorded after the meshes, so that they hide the grid.
+    pub fn draw_grid(&mut self) {
+        self.grid_renderer.draw_grid(
+            self.depth_needs_clearing,
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+            &self.depth_attachment,
+        );
+
+        self.depth_needs_clearing = false;
+    }
+
+    /// Record drawing the world axes in the corner of the screen to
+    /// the command buffer. They are drawn over everything recorded
+    /// before, so should be recorded after the scene.
+    pub fn draw_axes(&mut self) {
+        self.grid_renderer.draw_axes(
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+        );
+    }
+
     /// Record a mesh drawing operation to the command
     /// buffer. Meshes with provided ids must be present in the
     /// renderer.
     pub fn draw_mesh<'a, I>(&mut self, ids: I, mode: DrawMeshMode)
     where
         I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        self.draw_mesh_with_scissor_rect(ids, mode, None);
+    }
+
+    /// Record a mesh drawing operation to the command buffer, only
+    /// drawing within the `clip_rect`. The rect is given as `[x, y,
+    /// width, height]` normalized to the `0..1` range of the screen,
+    /// with origin in the top left corner. Meshes with provided ids
+    /// must be present in the renderer.
+    pub fn draw_mesh_clipped<'a, I>(&mut self, ids: I, mode: DrawMeshMode, clip_rect: [f32; 4])
+    where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        // Empty scissor rects are not allowed, but we can skip
+        // drawing altogether
+        if let Some(scissor_rect) = self.scissor_rect(clip_rect) {
+            self.draw_mesh_with_scissor_rect(ids, mode, Some(scissor_rect));
+        }
+    }
+
+    /// Record drawing the topology of the meshes uploaded with
+    /// `renderer.set_scene_mesh_topology()` to the command
+    /// buffer. Should be recorded after the meshes, so that their
+    /// hidden edges and vertices are not drawn.
+    pub fn draw_topology<'a, I>(&mut self, ids: I)
+    where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        self.draw_topology_with_scissor_rect(ids, None);
+    }
+
+    /// Record drawing the topology of the meshes like
+    /// `draw_topology`, only drawing within the `clip_rect`, like
+    /// `draw_mesh_clipped`.
+    pub fn draw_topology_clipped<'a, I>(&mut self, ids: I, clip_rect: [f32; 4])
+    where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        if let Some(scissor_rect) = self.scissor_rect(clip_rect) {
+            self.draw_topology_with_scissor_rect(ids, Some(scissor_rect));
+        }
+    }
+
+    fn draw_topology_with_scissor_rect<'a, I>(&mut self, ids: I, scissor_rect: Option<[u32; 4]>)
+    where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        self.scene_renderer.draw_topology(
+            self.depth_needs_clearing,
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+            &self.depth_attachment,
+            scissor_rect,
+            ids,
+        );
+
+        self.depth_needs_clearing = false;
+    }
+
+    /// Record drawing the curves and points uploaded with
+    /// `renderer.add_scene_curve()` and `renderer.add_scene_points()`
+    /// to the command buffer. Like the topology, should be recorded
+    /// after the meshes.
+    pub fn draw_curves_and_points<'a, 'b, C, P>(&mut self, curve_ids: C, points_ids: P)
+    where
+        C: Iterator<Item = &'a GpuCurveId>,
+        P: Iterator<Item = &'b GpuPointsId>,
+    {
+        self.draw_curves_and_points_with_scissor_rect(curve_ids, points_ids, None);
+    }
+
+    /// Record drawing the curves and points like
+    /// `draw_curves_and_points`, only drawing within the `clip_rect`,
+    /// like `draw_mesh_clipped`.
+    pub fn draw_curves_and_points_clipped<'a, 'b, C, P>(
+        &mut self,
+        curve_ids: C,
+        points_ids: P,
+        clip_rect: [f32; 4],
+    ) where
+        C: Iterator<Item = &'a GpuCurveId>,
+        P: Iterator<Item = &'b GpuPointsId>,
+    {
+        if let Some(scissor_rect) = self.scissor_rect(clip_rect) {
+            self.draw_curves_and_points_with_scissor_rect(
+                curve_ids,
+                points_ids,
+                Some(scissor_rect),
+            );
+        }
+    }
+
+    fn draw_curves_and_points_with_scissor_rect<'a, 'b, C, P>(
+        &mut self,
+        curve_ids: C,
+        points_ids: P,
+        scissor_rect: Option<[u32; 4]>,
+    ) where
+        C: Iterator<Item = &'a GpuCurveId>,
+        P: Iterator<Item = &'b GpuPointsId>,
+    {
+        self.scene_renderer.draw_curves_and_points(
+            self.depth_needs_clearing,
+            self.encoder
+                .as_mut()
+                .expect("Need encoder to record drawing"),
+            self.target.color_attachment(),
+            self.msaa_attachment,
+            &self.depth_attachment,
+            scissor_rect,
+            curve_ids,
+            points_ids,
+        );
+
+        self.depth_needs_clearing = false;
+    }
+
+    /// Converts the `clip_rect` normalized to the `0..1` range of the
+    /// screen to a scissor rect in pixels. Returns `None`, if the
+    /// scissor rect would be empty.
+    fn scissor_rect(&self, clip_rect: [f32; 4]) -> Option<[u32; 4]> {
+        let [x, y, width, height] = clip_rect;
+        let screen_width = self.width as f32;
+        let screen_height = self.height as f32;
+
+        let x_min = (math::clamp(x, 0.0, 1.0) * screen_width).round() as u32;
+        let y_min = (math::clamp(y, 0.0, 1.0) * screen_height).round() as u32;
+        let x_max = (math::clamp(x + width, 0.0, 1.0) * screen_width).round() as u32;
+        let y_max = (math::clamp(y + height, 0.0, 1.0) * screen_height).round() as u32;
+
+        if x_max <= x_min || y_max <= y_min {
+            None
+        } else {
+            Some([x_min, y_min, x_max - x_min, y_max - y_min])
+        }
+    }
+
+    fn draw_mesh_with_scissor_rect<'a, I>(
+        &mut self,
+        ids: I,
+        mode: DrawMeshMode,
+        scissor_rect: Option<[u32; 4]>,
+    ) where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
     {
         let mut clear_flags = SceneRendererClearFlags::empty();
         if self.color_needs_clearing {
@@ -382,9 +1331,10 @@ impl RenderPass<'_> {
             self.encoder
                 .as_mut()
                 .expect("Need encoder to record drawing"),
-            &self.frame.view,
+            self.target.color_attachment(),
             self.msaa_attachment,
             &self.depth_attachment,
+            scissor_rect,
             ids,
         );
 
@@ -402,7 +1352,7 @@ impl RenderPass<'_> {
                 self.encoder
                     .as_mut()
                     .expect("Need encoder to record drawing"),
-                &self.frame.view,
+                self.target.color_attachment(),
                 self.msaa_attachment,
                 draw_data,
             )
@@ -413,7 +1363,48 @@ impl RenderPass<'_> {
 
     /// Submit the built command buffer for drawing.
     pub fn submit(mut self) {
-        let encoder = self.encoder.take().expect("Can't finish rendering twice");
+        let mut encoder = self.encoder.take().expect("Can't finish rendering twice");
+
+        if let RenderTarget::Capture {
+            capture_target,
+            post_processing,
+        } = &self.target
+        {
+            if let Some(post_processing) = post_processing {
+                self.post_renderer.draw_post(
+                    self.device,
+                    &mut encoder,
+                    &capture_target.post_input,
+                    &capture_target.texture_view,
+                    post_processing,
+                );
+            }
+
+            encoder.copy_texture_to_buffer(
+                wgpu::TextureCopyView {
+                    texture: &capture_target.texture,
+                    mip_level: 0,
+                    array_layer: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                },
+                wgpu::BufferCopyView {
+                    buffer: &capture_target.buffer,
+                    offset: 0,
+                    row_pitch: capture_target.row_pitch,
+                    image_height: capture_target.height,
+                },
+                wgpu::Extent3d {
+                    width: capture_target.width,
+                    height: capture_target.height,
+                    depth: 1,
+                },
+            );
+        }
+
         self.queue.submit(&[encoder.finish()]);
     }
 }
@@ -427,6 +1418,18 @@ impl Drop for RenderPass<'_> {
     }
 }
 
+/// Acquires the next swap chain texture, returning `None` if wgpu
+/// panics while acquiring it.
+fn acquire_swap_chain_texture(swap_chain: &mut wgpu::SwapChain) -> Option<wgpu::SwapChainOutput> {
+    panic::catch_unwind(AssertUnwindSafe(move || {
+        // Moving the reference in makes the closure callable only
+        // once, so that the texture can borrow the swap chain
+        let swap_chain = swap_chain;
+        swap_chain.get_next_texture()
+    }))
+    .ok()
+}
+
 fn create_swap_chain(
     device: &wgpu::Device,
     surface: &wgpu::Surface,
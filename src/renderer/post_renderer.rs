@@ -0,0 +1,505 @@
+use std::io;
+
+use nalgebra::Matrix4;
+use serde::{Deserialize, Serialize};
+
+use crate::include_shader;
+
+use super::common::{apply_wgpu_correction_matrix, wgpu_size_of, UniformBufferRing};
+
+static SHADER_FULLSCREEN_VERT: &[u8] = include_shader!("fullscreen.vert.spv");
+static SHADER_POST_FRAG: &[u8] = include_shader!("post.frag.spv");
+static SHADER_DEPTH_RESOLVE_FRAG: &[u8] = include_shader!("depth_resolve.frag.spv");
+
+const RESOLVED_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+
+const EFFECT_DEPTH_OF_FIELD: u32 = 0x01;
+
+/// Post processing effects applied to captured frames for
+/// presentation renders.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PostProcessing {
+    /// Whether to blur the parts of the scene out of focus.
+    pub depth_of_field: bool,
+    /// The distance from the camera that is in focus, in world units.
+    pub focus_distance: f32,
+    /// The largest blur radius, in pixels.
+    pub blur_radius: f32,
+    /// How much to darken the corners, from 0 (none) to 1 (black).
+    pub vignette: f32,
+    /// The strength of the film grain noise, 0 for none.
+    pub grain: f32,
+}
+
+impl Default for PostProcessing {
+    fn default() -> Self {
+        Self {
+            depth_of_field: false,
+            focus_distance: 10.0,
+            blur_radius: 8.0,
+            vignette: 0.0,
+            grain: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub sample_count: u32,
+    pub scene_color_attachment_format: wgpu::TextureFormat,
+    pub scene_depth_attachment_format: wgpu::TextureFormat,
+    pub output_color_attachment_format: wgpu::TextureFormat,
+}
+
+/// Offscreen textures the scene is rendered into before it is post
+/// processed, and the bind groups reading them.
+pub struct PostInput {
+    width: u32,
+    height: u32,
+    color_texture_view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+    depth_resolve: Option<(wgpu::TextureView, wgpu::BindGroup)>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl PostInput {
+    pub fn color_attachment(&self) -> &wgpu::TextureView {
+        &self.color_texture_view
+    }
+
+    pub fn depth_attachment(&self) -> &wgpu::TextureView {
+        &self.depth_texture_view
+    }
+}
+
+/// Applies post processing effects to a scene rendered into a
+/// `PostInput`.
+///
+/// Depth of field needs to read the scene depth. Multisampled depth
+/// can't be read by the same shader as single-sampled depth, so it is
+/// first resolved to a single-sampled texture by a separate pass.
+pub struct PostRenderer {
+    inverse_projection_matrix: Matrix4<f32>,
+    uniform_buffers: UniformBufferRing,
+    input_bind_group_layout: wgpu::BindGroupLayout,
+    depth_resolve_bind_group_layout: wgpu::BindGroupLayout,
+    depth_resolve_pipeline: Option<wgpu::RenderPipeline>,
+    sampler: wgpu::Sampler,
+    render_pipeline: wgpu::RenderPipeline,
+    seed: u32,
+    options: Options,
+}
+
+impl PostRenderer {
+    pub fn new(device: &wgpu::Device, projection_matrix: &Matrix4<f32>, options: Options) -> Self {
+        let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_FULLSCREEN_VERT))
+            .expect("Couldn't read pre-built SPIR-V");
+        let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_POST_FRAG))
+            .expect("Couldn't read pre-built SPIR-V");
+        let vs_module = device.create_shader_module(&vs_words);
+        let fs_module = device.create_shader_module(&fs_words);
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let uniform_buffers = UniformBufferRing::new(
+            device,
+            &uniform_bind_group_layout,
+            wgpu_size_of::<PostUniforms>(),
+        );
+
+        let input_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                        },
+                    },
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler,
+                    },
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 2,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: false,
+                            dimension: wgpu::TextureViewDimension::D2,
+                        },
+                    },
+                ],
+            });
+
+        let depth_resolve_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::SampledTexture {
+                            multisampled: true,
+                            dimension: wgpu::TextureViewDimension::D2,
+                        },
+                    },
+                    wgpu::BindGroupLayoutBinding {
+                        binding: 1,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler,
+                    },
+                ],
+            });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            compare_function: wgpu::CompareFunction::Always,
+        });
+
+        let render_pipeline = create_fullscreen_pipeline(
+            device,
+            &[&uniform_bind_group_layout, &input_bind_group_layout],
+            &vs_module,
+            &fs_module,
+            options.output_color_attachment_format,
+        );
+
+        let depth_resolve_pipeline =
+            create_depth_resolve_pipeline(device, &depth_resolve_bind_group_layout, options);
+
+        Self {
+            inverse_projection_matrix: inverse_projection_matrix(projection_matrix),
+            uniform_buffers,
+            input_bind_group_layout,
+            depth_resolve_bind_group_layout,
+            depth_resolve_pipeline,
+            sampler,
+            render_pipeline,
+            seed: 0,
+            options,
+        }
+    }
+
+    /// Recreates the depth resolve pipeline for a new multi-sampling
+    /// setting of the scene attachments. Inputs created before must be
+    /// recreated.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.depth_resolve_pipeline = create_depth_resolve_pipeline(
+            device,
+            &self.depth_resolve_bind_group_layout,
+            self.options,
+        );
+    }
+
+    pub fn set_projection_matrix(&mut self, projection_matrix: &Matrix4<f32>) {
+        self.inverse_projection_matrix = inverse_projection_matrix(projection_matrix);
+    }
+
+    /// Creates the textures the scene is rendered into before post
+    /// processing.
+    pub fn create_input(&self, device: &wgpu::Device, width: u32, height: u32) -> PostInput {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+
+        let color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.options.scene_color_attachment_format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+        });
+        let color_texture_view = color_texture.create_default_view();
+
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: self.options.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.options.scene_depth_attachment_format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+        });
+        let depth_texture_view = depth_texture.create_default_view();
+        // Only the depth of the depth-stencil texture can be sampled
+        let sampled_depth_texture_view = depth_texture.create_view(&wgpu::TextureViewDescriptor {
+            format: self.options.scene_depth_attachment_format,
+            dimension: wgpu::TextureViewDimension::D2,
+            aspect: wgpu::TextureAspect::DepthOnly,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            array_layer_count: 1,
+        });
+
+        let depth_resolve = if self.depth_resolve_pipeline.is_some() {
+            let resolved_depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+                size,
+                array_layer_count: 1,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: RESOLVED_DEPTH_FORMAT,
+                usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+            });
+            let resolve_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.depth_resolve_bind_group_layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&sampled_depth_texture_view),
+                    },
+                    wgpu::Binding {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+
+            Some((
+                resolved_depth_texture.create_default_view(),
+                resolve_bind_group,
+            ))
+        } else {
+            None
+        };
+
+        let readable_depth_texture_view = match &depth_resolve {
+            Some((resolved_depth_texture_view, _)) => resolved_depth_texture_view,
+            None => &sampled_depth_texture_view,
+        };
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.input_bind_group_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&color_texture_view),
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::Binding {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(readable_depth_texture_view),
+                },
+            ],
+        });
+
+        PostInput {
+            width,
+            height,
+            color_texture_view,
+            depth_texture_view,
+            depth_resolve,
+            bind_group,
+        }
+    }
+
+    /// Records applying the `post_processing` to the scene in the
+    /// `input` and writing the result to the `color_attachment`.
+    pub fn draw_post(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        input: &PostInput,
+        color_attachment: &wgpu::TextureView,
+        post_processing: &PostProcessing,
+    ) {
+        // Every frame gets different grain
+        self.seed = self.seed.wrapping_add(1);
+
+        let uniforms = PostUniforms::new(
+            post_processing,
+            &self.inverse_projection_matrix,
+            input.width,
+            input.height,
+            self.seed,
+        );
+        let transfer_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&[uniforms]);
+        self.uniform_buffers.write(encoder, &transfer_buffer);
+
+        if let (Some(depth_resolve_pipeline), Some((resolved_depth_texture_view, bind_group))) =
+            (&self.depth_resolve_pipeline, &input.depth_resolve)
+        {
+            let mut rpass = begin_fullscreen_render_pass(encoder, resolved_depth_texture_view);
+            rpass.set_pipeline(depth_resolve_pipeline);
+            rpass.set_bind_group(0, bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        let mut rpass = begin_fullscreen_render_pass(encoder, color_attachment);
+        rpass.set_pipeline(&self.render_pipeline);
+        rpass.set_bind_group(0, self.uniform_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, &input.bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+fn inverse_projection_matrix(projection_matrix: &Matrix4<f32>) -> Matrix4<f32> {
+    apply_wgpu_correction_matrix(projection_matrix)
+        .try_inverse()
+        .unwrap_or_else(Matrix4::identity)
+}
+
+/// Creates the pipeline resolving multisampled scene depth, if the
+/// scene is multisampled.
+fn create_depth_resolve_pipeline(
+    device: &wgpu::Device,
+    depth_resolve_bind_group_layout: &wgpu::BindGroupLayout,
+    options: Options,
+) -> Option<wgpu::RenderPipeline> {
+    if options.sample_count > 1 {
+        let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_FULLSCREEN_VERT))
+            .expect("Couldn't read pre-built SPIR-V");
+        let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_DEPTH_RESOLVE_FRAG))
+            .expect("Couldn't read pre-built SPIR-V");
+        let vs_module = device.create_shader_module(&vs_words);
+        let fs_module = device.create_shader_module(&fs_words);
+
+        Some(create_fullscreen_pipeline(
+            device,
+            &[depth_resolve_bind_group_layout],
+            &vs_module,
+            &fs_module,
+            RESOLVED_DEPTH_FORMAT,
+        ))
+    } else {
+        None
+    }
+}
+
+fn create_fullscreen_pipeline(
+    device: &wgpu::Device,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    vs_module: &wgpu::ShaderModule,
+    fs_module: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout =
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor { bind_group_layouts });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: None,
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: None,
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: 1,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+fn begin_fullscreen_render_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    color_attachment: &'a wgpu::TextureView,
+) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+            attachment: color_attachment,
+            resolve_target: None,
+            load_op: wgpu::LoadOp::Clear,
+            store_op: wgpu::StoreOp::Store,
+            clear_color: wgpu::Color::BLACK,
+        }],
+        depth_stencil_attachment: None,
+    })
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PostUniforms {
+    inverse_projection_matrix: [[f32; 4]; 4],
+    screen_size: [f32; 2],
+    focus_distance: f32,
+    blur_radius: f32,
+    vignette: f32,
+    grain: f32,
+    seed: f32,
+    effects: u32,
+}
+
+impl PostUniforms {
+    fn new(
+        post_processing: &PostProcessing,
+        inverse_projection_matrix: &Matrix4<f32>,
+        width: u32,
+        height: u32,
+        seed: u32,
+    ) -> Self {
+        let mut effects = 0;
+        if post_processing.depth_of_field && post_processing.blur_radius > 0.0 {
+            effects |= EFFECT_DEPTH_OF_FIELD;
+        }
+
+        Self {
+            inverse_projection_matrix: (*inverse_projection_matrix).into(),
+            screen_size: [width as f32, height as f32],
+            focus_distance: post_processing.focus_distance,
+            blur_radius: post_processing.blur_radius,
+            vignette: post_processing.vignette,
+            grain: post_processing.grain,
+            seed: (seed % 1024) as f32,
+            effects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_uniforms_enable_depth_of_field_only_with_blur() {
+        let mut post_processing = PostProcessing {
+            depth_of_field: true,
+            ..PostProcessing::default()
+        };
+
+        let uniforms = PostUniforms::new(&post_processing, &Matrix4::identity(), 4, 2, 0);
+        assert_eq!(uniforms.effects, EFFECT_DEPTH_OF_FIELD);
+        assert_eq!(uniforms.screen_size, [4.0, 2.0]);
+
+        post_processing.blur_radius = 0.0;
+        let uniforms = PostUniforms::new(&post_processing, &Matrix4::identity(), 4, 2, 0);
+        assert_eq!(uniforms.effects, 0);
+    }
+}
@@ -1,22 +1,215 @@
+use std::cmp;
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::error;
+use std::f32;
 use std::fmt;
 use std::io;
 use std::iter;
+use std::ops::Range;
 
 use bitflags::bitflags;
 use nalgebra::{Matrix4, Point3, Vector3};
+use serde::{Deserialize, Serialize};
 
-use crate::convert::cast_usize;
-use crate::mesh::{Face, Mesh};
+use crate::bounding_box::BoundingBox;
+use crate::convert::{cast_u32, cast_usize};
+use crate::curve::Curve;
+use crate::mesh::{analysis, Face, Mesh, OrientedEdge, UnorientedEdge};
+use crate::point_cloud::PointCloud;
 
-use super::common::{upload_texture_rgba8_unorm, wgpu_size_of};
+use super::common::{
+    apply_wgpu_correction_matrix, upload_texture_rgba8_unorm, wgpu_size_of, UniformBufferRing,
+};
 
 static SHADER_VIEWPORT_VERT: &[u8] = include_shader!("viewport.vert.spv");
 static SHADER_VIEWPORT_FRAG: &[u8] = include_shader!("viewport.frag.spv");
+static SHADER_FULLSCREEN_VERT: &[u8] = include_shader!("fullscreen.vert.spv");
+static SHADER_SECTION_CAP_FRAG: &[u8] = include_shader!("section_cap.frag.spv");
+static SHADER_TOPOLOGY_EDGES_VERT: &[u8] = include_shader!("topology_edges.vert.spv");
+static SHADER_TOPOLOGY_VERTICES_VERT: &[u8] = include_shader!("topology_vertices.vert.spv");
+static SHADER_TOPOLOGY_FRAG: &[u8] = include_shader!("topology.frag.spv");
+
+static MATCAP_BLUE_TEXTURE_BYTES: &[u8] = include_bytes!("../../resources/matcap.png");
+static MATCAP_CLAY_TEXTURE_BYTES: &[u8] = include_bytes!("../../resources/matcap_clay.png");
+static MATCAP_CHROME_TEXTURE_BYTES: &[u8] = include_bytes!("../../resources/matcap_chrome.png");
+static MATCAP_ZEBRA_TEXTURE_BYTES: &[u8] = include_bytes!("../../resources/matcap_zebra.png");
+
+/// The color of vertices of meshes without vertex colors. Leaves the
+/// shading color unchanged.
+const DEFAULT_VERTEX_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+
+/// The section plane equation used when there is no section. No point
+/// is in front of it.
+const NO_SECTION_PLANE: [f32; 4] = [0.0, 0.0, 0.0, -1.0];
+
+const TOPOLOGY_EDGE_COLOR: [f32; 3] = [0.239, 0.306, 0.400];
+const TOPOLOGY_BORDER_EDGE_COLOR: [f32; 3] = [1.0, 0.6, 0.1];
+const TOPOLOGY_NON_MANIFOLD_EDGE_COLOR: [f32; 3] = [0.95, 0.1, 0.35];
+const TOPOLOGY_VERTEX_COLOR: [f32; 3] = [0.05, 0.05, 0.05];
+const CURVE_COLOR: [f32; 3] = [0.1, 0.45, 0.95];
+const POINT_COLOR: [f32; 3] = [0.05, 0.3, 0.75];
+/// The side of the squares marking the vertices, in pixels.
+const TOPOLOGY_VERTEX_SIZE: f32 = 4.0;
+
+/// Meshes with at most this many vertices, after unindexing, share
+/// vertex buffers with other small meshes instead of getting their
+/// own.
+const BATCHED_MESH_VERTEX_COUNT_MAX: usize = 4096;
+/// How many vertices fit into a vertex buffer shared by small meshes.
+const MESH_BATCH_VERTEX_CAPACITY: u32 = 65536;
+
+/// How the caps of cut meshes are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionFill {
+    Solid,
+    /// Diagonal lines over a lighter shade of the cap color.
+    Hatched,
+}
+
+/// The bundled matcap textures, which give meshes their lighting and
+/// material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Matcap {
+    Blue,
+    Clay,
+    /// Reflective, with a sharp horizon, showing dents and bumps.
+    Chrome,
+    /// Reflections of parallel stripes, showing whether curvature of
+    /// the surface is continuous.
+    Zebra,
+}
+
+impl Matcap {
+    pub const ALL: [Matcap; 4] = [Matcap::Blue, Matcap::Clay, Matcap::Chrome, Matcap::Zebra];
+
+    fn texture_bytes(self) -> &'static [u8] {
+        match self {
+            Matcap::Blue => MATCAP_BLUE_TEXTURE_BYTES,
+            Matcap::Clay => MATCAP_CLAY_TEXTURE_BYTES,
+            Matcap::Chrome => MATCAP_CHROME_TEXTURE_BYTES,
+            Matcap::Zebra => MATCAP_ZEBRA_TEXTURE_BYTES,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Matcap::Blue => 0,
+            Matcap::Clay => 1,
+            Matcap::Chrome => 2,
+            Matcap::Zebra => 3,
+        }
+    }
+}
+
+impl fmt::Display for Matcap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Matcap::Blue => write!(f, "Blue"),
+            Matcap::Clay => write!(f, "Clay"),
+            Matcap::Chrome => write!(f, "Chrome"),
+            Matcap::Zebra => write!(f, "Zebra"),
+        }
+    }
+}
+
+/// A false-color view of the scene for diagnosing problems of the
+/// geometry, replacing the shading of all meshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugView {
+    Off,
+    /// World-space vertex normals as RGB, e.g. to find flipped or
+    /// unnormalized normals.
+    Normals,
+    /// Distance from the camera, white for the closest and black for
+    /// the farthest point of the uploaded meshes.
+    Depth,
+}
+
+impl DebugView {
+    pub const ALL: [DebugView; 3] = [DebugView::Off, DebugView::Normals, DebugView::Depth];
+    pub const NAMES: [&'static str; 3] = ["Off", "Normals", "Depth"];
+}
+
+/// How the surface of a mesh is shaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshShading {
+    /// Whether the faces are shaded with their own normals instead of
+    /// the interpolated vertex normals, showing the faceting of the
+    /// mesh.
+    pub flat: bool,
+    pub matcap: Matcap,
+}
+
+impl Default for MeshShading {
+    fn default() -> Self {
+        Self {
+            flat: false,
+            matcap: Matcap::Blue,
+        }
+    }
+}
+
+/// The surface of a mesh, e.g. to tell apart meshes by their color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    /// The RGB color in the `0..1` range multiplied with the shading
+    /// color. White keeps the colors of the matcap.
+    pub base_color: [f32; 3],
+    /// How matte the surface looks, in the `0..1` range. Zero shows
+    /// the matcap as is, one flattens its highlights and shadows.
+    pub roughness: f32,
+    /// How opaque the surface is, in the `0..1` range. Meshes that
+    /// aren't fully opaque are blended over the opaque ones.
+    pub opacity: f32,
+}
+
+impl Material {
+    pub fn is_opaque(&self) -> bool {
+        self.opacity >= 1.0
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0],
+            roughness: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// A plane cutting the scene, as in architectural sections. Geometry
+/// in front of the plane, where its normal points, is cut away. Cut
+/// closed meshes are capped, so that they don't look hollow.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Section {
+    /// A point on the plane.
+    pub origin: Point3<f32>,
+    pub normal: Vector3<f32>,
+    pub fill: SectionFill,
+    /// The RGB cap color in the `0..1` range.
+    pub color: [f32; 3],
+    /// The distance between hatch lines in world units.
+    pub hatch_spacing: f32,
+}
 
-static MATCAP_TEXTURE_BYTES: &[u8] = include_bytes!("../../resources/matcap.png");
+impl Section {
+    /// Returns the `[a, b, c, d]` plane equation coefficients, with
+    /// points in front of the plane having positive `ax + by + cz + d`.
+    fn plane_equation(&self) -> [f32; 4] {
+        let normal = self.normal.normalize();
+        [
+            normal.x,
+            normal.y,
+            normal.z,
+            -normal.dot(&self.origin.coords),
+        ]
+    }
+}
 
 /// The mesh containing index and vertex data in same-length
 /// format as will be uploaded on the GPU.
@@ -34,10 +227,14 @@ impl GpuMesh {
     /// times paired with different per-vertex data, e.g. normals.
     pub fn from_mesh(mesh: &Mesh) -> Self {
         let vertices = mesh.vertices();
-        let normals = mesh.normals();
+        let colors = mesh.vertex_colors();
 
-        let faces_len = mesh.faces().len();
-        let indices_len_estimate = faces_len * 3;
+        let triangles_len: usize = mesh
+            .faces()
+            .iter()
+            .map(|face| face.to_triangles().len())
+            .sum();
+        let indices_len_estimate = triangles_len * 3;
 
         let mut indices = Vec::with_capacity(indices_len_estimate);
 
@@ -54,44 +251,67 @@ impl GpuMesh {
 
         // Iterate over all faces, creating or re-using vertices
         // as we go. Vertex data identity is defined by equality
-        // of the index that constructed the vertex.
-        for face in mesh.faces() {
-            match face {
+        // of the vertex index and the bits of the corner normal,
+        // which differs from the vertex normal for overridden
+        // corners.
+        for (face_index, face) in mesh.faces().iter().enumerate() {
+            let n = mesh.face_corner_normals(face_index);
+            let triangle_corners;
+            let quad_corners;
+            let corners: &[(u32, Vector3<f32>, u32)] = match face {
                 Face::Triangle(triangle_face) => {
                     let v = triangle_face.vertices;
-                    let n = triangle_face.normals;
-
-                    for &(vertex_index, normal_index, barycentric) in
-                        &[(v.0, n.0, 0x01), (v.1, n.1, 0x02), (v.2, n.2, 0x04)]
-                    {
-                        match index_map.entry((vertex_index, normal_index, barycentric)) {
-                            Entry::Occupied(occupied) => {
-                                // This concrete vertex/normal combination
-                                // was used before, re-use the vertex it
-                                // created
-                                let renderer_index = *occupied.get();
-
-                                indices.push(renderer_index);
-                            }
-                            Entry::Vacant(vacant) => {
-                                // We didn't see this vertex/normal
-                                // combination before, we need to create a
-                                // new vertex and remember the index we
-                                // assigned
-                                let renderer_index = next_renderer_index;
-                                let position = vertices[cast_usize(vertex_index)];
-                                let normal = normals[cast_usize(normal_index)];
-                                let vertex = Self::vertex(position, normal, barycentric);
-
-                                vacant.insert(renderer_index);
-                                next_renderer_index += 1;
-
-                                vertex_data.push(vertex);
-                                indices.push(renderer_index)
-                            }
-                        };
-                    }
+                    triangle_corners = [(v.0, n[0], 0x01), (v.1, n[1], 0x02), (v.2, n[2], 0x04)];
+                    &triangle_corners
+                }
+                Face::Quad(quad_face) => {
+                    // Quads are drawn as two triangles. The barycentric
+                    // coordinate opposite of the diagonal stays set on
+                    // all corners of both triangles, so that the
+                    // diagonal is not drawn as an edge.
+                    let v = quad_face.vertices;
+                    quad_corners = [
+                        (v.0, n[0], 0x03),
+                        (v.1, n[1], 0x02),
+                        (v.2, n[2], 0x06),
+                        (v.0, n[0], 0x05),
+                        (v.2, n[2], 0x06),
+                        (v.3, n[3], 0x04),
+                    ];
+                    &quad_corners
                 }
+            };
+
+            for &(vertex_index, normal, barycentric) in corners {
+                let normal_bits = [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()];
+                match index_map.entry((vertex_index, normal_bits, barycentric)) {
+                    Entry::Occupied(occupied) => {
+                        // This concrete vertex/normal combination
+                        // was used before, re-use the vertex it
+                        // created
+                        let renderer_index = *occupied.get();
+
+                        indices.push(renderer_index);
+                    }
+                    Entry::Vacant(vacant) => {
+                        // We didn't see this vertex/normal
+                        // combination before, we need to create a
+                        // new vertex and remember the index we
+                        // assigned
+                        let renderer_index = next_renderer_index;
+                        let position = vertices[cast_usize(vertex_index)];
+                        let color = colors.map_or(DEFAULT_VERTEX_COLOR, |colors| {
+                            colors[cast_usize(vertex_index)]
+                        });
+                        let vertex = Self::vertex(position, normal, color, barycentric);
+
+                        vacant.insert(renderer_index);
+                        next_renderer_index += 1;
+
+                        vertex_data.push(vertex);
+                        indices.push(renderer_index)
+                    }
+                };
             }
         }
 
@@ -135,7 +355,9 @@ impl GpuMesh {
             .into_iter()
             .zip(vertex_normals.into_iter())
             .zip(barycentric_sequence_iter())
-            .map(|((position, normal), barycentric)| Self::vertex(position, normal, barycentric))
+            .map(|((position, normal), barycentric)| {
+                Self::vertex(position, normal, DEFAULT_VERTEX_COLOR, barycentric)
+            })
             .collect();
 
         Self {
@@ -172,7 +394,9 @@ impl GpuMesh {
             .into_iter()
             .zip(vertex_normals.into_iter())
             .zip(barycentric_sequence_iter())
-            .map(|((position, normal), barycentric)| Self::vertex(position, normal, barycentric))
+            .map(|((position, normal), barycentric)| {
+                Self::vertex(position, normal, DEFAULT_VERTEX_COLOR, barycentric)
+            })
             .collect();
 
         Self {
@@ -181,19 +405,132 @@ impl GpuMesh {
         }
     }
 
-    fn vertex(position: Point3<f32>, normal: Vector3<f32>, barycentric: u32) -> GpuMeshVertex {
+    fn vertex(
+        position: Point3<f32>,
+        normal: Vector3<f32>,
+        color: [f32; 3],
+        barycentric: u32,
+    ) -> GpuMeshVertex {
         GpuMeshVertex {
             position: [position[0], position[1], position[2], 1.0],
             normal: [normal[0], normal[1], normal[2], 0.0],
+            color: [color[0], color[1], color[2], 1.0],
             barycentric,
         }
     }
 }
 
+/// The edges and vertices of a mesh as uploaded on the GPU for
+/// inspecting its topology. Each edge shared by more faces is only
+/// present once. Border edges of a single face and non-manifold edges
+/// of three or more faces have distinct colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuMeshTopology {
+    /// Pairs of vertices of the edges, in the `LINE_LIST` format.
+    edge_vertex_data: Vec<GpuTopologyVertex>,
+    vertex_data: Vec<GpuTopologyVertex>,
+}
+
+impl GpuMeshTopology {
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let vertices = mesh.vertices();
+        let oriented_edges: Vec<OrientedEdge> = mesh.oriented_edges_iter().collect();
+        let edge_sharing = analysis::edge_sharing(&oriented_edges);
+        let border_edges: HashSet<UnorientedEdge> = analysis::border_edges(&edge_sharing)
+            .map(UnorientedEdge)
+            .collect();
+        let non_manifold_edges: HashSet<UnorientedEdge> =
+            analysis::non_manifold_edges(&edge_sharing)
+                .map(UnorientedEdge)
+                .collect();
+
+        let mut edge_vertex_data = Vec::with_capacity(edge_sharing.len() * 2);
+        for edge in edge_sharing.keys() {
+            let color = if border_edges.contains(edge) {
+                TOPOLOGY_BORDER_EDGE_COLOR
+            } else if non_manifold_edges.contains(edge) {
+                TOPOLOGY_NON_MANIFOLD_EDGE_COLOR
+            } else {
+                TOPOLOGY_EDGE_COLOR
+            };
+
+            let (v1, v2) = edge.0.vertices;
+            edge_vertex_data.push(Self::vertex(vertices[cast_usize(v1)], color));
+            edge_vertex_data.push(Self::vertex(vertices[cast_usize(v2)], color));
+        }
+
+        let vertex_data = vertices
+            .iter()
+            .map(|vertex| Self::vertex(*vertex, TOPOLOGY_VERTEX_COLOR))
+            .collect();
+
+        Self {
+            edge_vertex_data,
+            vertex_data,
+        }
+    }
+
+    fn vertex(position: Point3<f32>, color: [f32; 3]) -> GpuTopologyVertex {
+        GpuTopologyVertex {
+            position: [position[0], position[1], position[2], 1.0],
+            color: [color[0], color[1], color[2], 1.0],
+        }
+    }
+}
+
+/// The line segments of a curve as uploaded on the GPU. Curves are
+/// drawn with the same pipeline as the edges of mesh topology.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuCurve {
+    /// Pairs of vertices of the segments, in the `LINE_LIST` format.
+    segment_vertex_data: Vec<GpuTopologyVertex>,
+}
+
+impl GpuCurve {
+    pub fn from_curve(curve: &Curve) -> Self {
+        let mut segment_vertex_data = Vec::new();
+        for (start, end) in curve.segments_iter() {
+            segment_vertex_data.push(GpuMeshTopology::vertex(start, CURVE_COLOR));
+            segment_vertex_data.push(GpuMeshTopology::vertex(end, CURVE_COLOR));
+        }
+
+        Self {
+            segment_vertex_data,
+        }
+    }
+}
+
+/// The points of a point cloud as uploaded on the GPU. They are drawn
+/// with the same pipeline as the vertices of mesh topology.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuPoints {
+    vertex_data: Vec<GpuTopologyVertex>,
+}
+
+impl GpuPoints {
+    pub fn from_point_cloud(point_cloud: &PointCloud) -> Self {
+        let vertex_data = point_cloud
+            .points()
+            .iter()
+            .map(|point| GpuMeshTopology::vertex(*point, POINT_COLOR))
+            .collect();
+
+        Self { vertex_data }
+    }
+}
+
 /// Opaque handle to mesh stored in scene renderer.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GpuMeshId(u64);
 
+/// Opaque handle to curve stored in scene renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GpuCurveId(u64);
+
+/// Opaque handle to points stored in scene renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GpuPointsId(u64);
+
 #[derive(Debug)]
 pub enum AddMeshError {
     TooManyVertices(usize),
@@ -242,6 +579,12 @@ pub enum DrawMeshMode {
     Edges,
     ShadedEdges,
     ShadedEdgesXray,
+    /// Transparent shading without depth writes, for displaying
+    /// reference geometry behind the scene.
+    Ghosted,
+    /// Shading with front faces and back faces colored differently,
+    /// for finding faces with reverted winding.
+    FaceOrientation,
 }
 
 /// 3D renderer of the editor scene.
@@ -249,17 +592,60 @@ pub enum DrawMeshMode {
 /// Can be used to upload meshes on the GPU and draw it in the
 /// viewport. Currently supports just shaded (matcap) and wireframe
 /// rendering, and their combinations.
+///
+/// Each mesh has its own model matrix, so that it can be moved or
+/// mirrored without re-uploading its vertex data, and its own
+/// shading, smooth or flat with one of the bundled matcaps.
+///
+/// Small meshes are merged into shared vertex buffers and the draws
+/// are grouped by matcap, so that scenes with hundreds of small
+/// meshes don't spend most of the frame rebinding resources.
+///
+/// With a section, the fragments in front of the section plane are
+/// discarded. Opaque meshes are then capped using the stencil buffer:
+/// every pixel of the cut meshes' remaining surfaces inverts the
+/// stencil value, so that it ends up non-zero where the view ray
+/// crosses the surfaces an odd number of times, i.e. where the
+/// section plane is inside a closed mesh. The cap is only drawn on
+/// those pixels.
+///
+/// Meshes can also have their topology uploaded, which is drawn over
+/// them by `draw_topology`: edges as lines and vertices as squares of
+/// constant screen size.
+///
+/// Curves and points are drawn by `draw_curves_and_points` as lines
+/// and squares, just like the topology edges and vertices. They are
+/// not transformed, so they all share a single model bind group with
+/// the identity matrix.
 pub struct SceneRenderer {
     mesh_resources: HashMap<u64, MeshResource>,
     mesh_resources_next_id: u64,
-    matrix_buffer: wgpu::Buffer,
-    matrix_bind_group: wgpu::BindGroup,
+    /// The segment vertex buffers of curves, with the number of
+    /// vertices in each.
+    curve_resources: HashMap<u64, (wgpu::Buffer, u32)>,
+    curve_resources_next_id: u64,
+    /// The vertex buffers of points, with the number of points in each.
+    points_resources: HashMap<u64, (wgpu::Buffer, u32)>,
+    points_resources_next_id: u64,
+    curve_model_bind_group: wgpu::BindGroup,
+    /// Shared vertex buffers of small meshes.
+    mesh_batches: Vec<MeshBatch>,
+    projection_matrix: Matrix4<f32>,
+    view_matrix: Matrix4<f32>,
+    section: Option<Section>,
+    debug_view: DebugView,
+    matrix_buffers: UniformBufferRing,
+    section_cap_buffers: UniformBufferRing,
+    topology_buffers: UniformBufferRing,
+    bind_group_layouts: SceneBindGroupLayouts,
     shading_bind_group_shaded: wgpu::BindGroup,
     shading_bind_group_edges: wgpu::BindGroup,
     shading_bind_group_shaded_edges: wgpu::BindGroup,
-    matcap_texture_bind_group: wgpu::BindGroup,
-    render_pipeline_opaque: wgpu::RenderPipeline,
-    render_pipeline_transparent: wgpu::RenderPipeline,
+    shading_bind_group_ghosted: wgpu::BindGroup,
+    shading_bind_group_face_orientation: wgpu::BindGroup,
+    /// Indexed by `Matcap::index`.
+    matcap_texture_bind_groups: Vec<wgpu::BindGroup>,
+    pipelines: ScenePipelines,
     options: Options,
 }
 
@@ -275,20 +661,47 @@ impl SceneRenderer {
         view_matrix: &Matrix4<f32>,
         options: Options,
     ) -> Self {
-        let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_VIEWPORT_VERT))
-            .expect("Couldn't read pre-built SPIR-V");
-        let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_VIEWPORT_FRAG))
-            .expect("Couldn't read pre-built SPIR-V");
-        let vs_module = device.create_shader_module(&vs_words);
-        let fs_module = device.create_shader_module(&fs_words);
+        // The section plane in the matrix uniforms is also read by
+        // the fragment shader
+        let matrix_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let matrix_buffers = UniformBufferRing::new(
+            device,
+            &matrix_bind_group_layout,
+            wgpu_size_of::<MatrixUniforms>(),
+        );
 
-        let matrix_buffer_size = wgpu_size_of::<MatrixUniforms>();
-        let matrix_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            size: matrix_buffer_size,
-            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
-        });
+        let section_cap_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+        let section_cap_buffers = UniformBufferRing::new(
+            device,
+            &section_cap_bind_group_layout,
+            wgpu_size_of::<SectionCapUniforms>(),
+        );
 
-        let matrix_bind_group_layout =
+        // The fragment shader reads the shading flags of the model
+        let model_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                bindings: &[wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                }],
+            });
+
+        let topology_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 bindings: &[wgpu::BindGroupLayoutBinding {
                     binding: 0,
@@ -296,20 +709,11 @@ impl SceneRenderer {
                     ty: wgpu::BindingType::UniformBuffer { dynamic: false },
                 }],
             });
-        let matrix_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &matrix_bind_group_layout,
-            bindings: &[wgpu::Binding {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer {
-                    buffer: &matrix_buffer,
-                    range: 0..matrix_buffer_size,
-                },
-            }],
-        });
-        let matrix_uniforms = MatrixUniforms {
-            projection_matrix: apply_wgpu_correction_matrix(projection_matrix).into(),
-            view_matrix: view_matrix.clone().into(),
-        };
+        let topology_buffers = UniformBufferRing::new(
+            device,
+            &topology_bind_group_layout,
+            wgpu_size_of::<TopologyUniforms>(),
+        );
 
         let shading_buffer_size = wgpu_size_of::<ShadingUniforms>();
         let shading_buffer_shaded = device.create_buffer(&wgpu::BufferDescriptor {
@@ -324,6 +728,14 @@ impl SceneRenderer {
             size: shading_buffer_size,
             usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
         });
+        let shading_buffer_ghosted = device.create_buffer(&wgpu::BufferDescriptor {
+            size: shading_buffer_size,
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        });
+        let shading_buffer_face_orientation = device.create_buffer(&wgpu::BufferDescriptor {
+            size: shading_buffer_size,
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        });
 
         let shading_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -364,8 +776,28 @@ impl SceneRenderer {
                     },
                 }],
             });
+        let shading_bind_group_ghosted = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &shading_bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &shading_buffer_ghosted,
+                    range: 0..shading_buffer_size,
+                },
+            }],
+        });
+        let shading_bind_group_face_orientation =
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &shading_bind_group_layout,
+                bindings: &[wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &shading_buffer_face_orientation,
+                        range: 0..shading_buffer_size,
+                    },
+                }],
+            });
 
-        upload_matrix_buffer(device, queue, &matrix_buffer, matrix_uniforms);
         upload_shading_buffer(
             device,
             queue,
@@ -393,41 +825,26 @@ impl SceneRenderer {
                 shading_mode: ShadingMode::SHADED | ShadingMode::EDGES,
             },
         );
-
-        let (matcap_texture_width, matcap_texture_height, matcap_texture_data) = {
-            let cursor = io::Cursor::new(MATCAP_TEXTURE_BYTES);
-            let decoder = png::Decoder::new(cursor);
-            let (info, mut reader) = decoder
-                .read_info()
-                .expect("Baked matcap texture decoding must succeed");
-
-            let mut buffer = vec![0; info.buffer_size()];
-            reader
-                .next_frame(&mut buffer)
-                .expect("Baked matcap texture decoding must succeed");
-
-            assert_eq!(
-                info.color_type,
-                png::ColorType::RGBA,
-                "Baked matcap texture must be RGBA",
-            );
-
-            (info.width, info.height, buffer)
-        };
-
-        let matcap_texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: wgpu::Extent3d {
-                width: matcap_texture_width,
-                height: matcap_texture_height,
-                depth: 1,
+        upload_shading_buffer(
+            device,
+            queue,
+            &shading_buffer_ghosted,
+            ShadingUniforms {
+                edge_color_and_face_alpha: [0.0, 0.0, 0.0, 0.2],
+                shading_mode: ShadingMode::SHADED,
             },
-            array_layer_count: 1,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
-        });
+        );
+        upload_shading_buffer(
+            device,
+            queue,
+            &shading_buffer_face_orientation,
+            ShadingUniforms {
+                edge_color_and_face_alpha: [0.239, 0.306, 0.400, 1.0],
+                shading_mode: ShadingMode::SHADED
+                    | ShadingMode::EDGES
+                    | ShadingMode::FACE_ORIENTATION,
+            },
+        );
 
         let matcap_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -459,68 +876,92 @@ impl SceneRenderer {
                     },
                 ],
             });
-        let matcap_texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &matcap_texture_bind_group_layout,
-            bindings: &[
-                wgpu::Binding {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(
-                        &matcap_texture.create_default_view(),
-                    ),
-                },
-                wgpu::Binding {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&matcap_sampler),
+        let matcap_texture_bind_groups = Matcap::ALL
+            .iter()
+            .map(|matcap| {
+                create_matcap_texture_bind_group(
+                    device,
+                    queue,
+                    &matcap_texture_bind_group_layout,
+                    &matcap_sampler,
+                    matcap.texture_bytes(),
+                )
+            })
+            .collect();
+
+        let bind_group_layouts = SceneBindGroupLayouts {
+            matrix: matrix_bind_group_layout,
+            shading: shading_bind_group_layout,
+            matcap_texture: matcap_texture_bind_group_layout,
+            model: model_bind_group_layout,
+            section_cap: section_cap_bind_group_layout,
+            topology: topology_bind_group_layout,
+        };
+        let pipelines = ScenePipelines::new(device, &bind_group_layouts, options);
+
+        let model_buffer_size = wgpu_size_of::<ModelUniforms>();
+        let curve_model_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::UNIFORM)
+            .fill_from_slice(&[ModelUniforms::new(
+                &Matrix4::identity(),
+                None,
+                MeshShading::default(),
+                Material::default(),
+            )]);
+        let curve_model_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layouts.model,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &curve_model_buffer,
+                    range: 0..model_buffer_size,
                 },
-            ],
+            }],
         });
 
-        upload_texture_rgba8_unorm(
-            device,
-            queue,
-            &matcap_texture,
-            matcap_texture_width,
-            matcap_texture_height,
-            &matcap_texture_data,
-        );
-
-        let render_pipeline_opaque = create_pipeline(
-            device,
-            &vs_module,
-            &fs_module,
-            &matrix_bind_group_layout,
-            &shading_bind_group_layout,
-            &matcap_texture_bind_group_layout,
-            false,
-            options,
-        );
-        let render_pipeline_transparent = create_pipeline(
-            device,
-            &vs_module,
-            &fs_module,
-            &matrix_bind_group_layout,
-            &shading_bind_group_layout,
-            &matcap_texture_bind_group_layout,
-            true,
-            options,
-        );
-
-        Self {
+        let mut scene_renderer = Self {
             mesh_resources: HashMap::new(),
             mesh_resources_next_id: 0,
-            matrix_buffer,
-            matrix_bind_group,
+            curve_resources: HashMap::new(),
+            curve_resources_next_id: 0,
+            points_resources: HashMap::new(),
+            points_resources_next_id: 0,
+            curve_model_bind_group,
+            mesh_batches: Vec::new(),
+            projection_matrix: *projection_matrix,
+            view_matrix: *view_matrix,
+            section: None,
+            debug_view: DebugView::Off,
+            matrix_buffers,
+            section_cap_buffers,
+            topology_buffers,
+            bind_group_layouts,
             shading_bind_group_shaded,
             shading_bind_group_edges,
             shading_bind_group_shaded_edges,
-            matcap_texture_bind_group,
-            render_pipeline_opaque,
-            render_pipeline_transparent,
+            shading_bind_group_ghosted,
+            shading_bind_group_face_orientation,
+            matcap_texture_bind_groups,
+            pipelines,
             options,
-        }
+        };
+        scene_renderer.upload_camera_and_section_buffers(device, queue);
+
+        scene_renderer
+    }
+
+    /// Recreates the render pipelines for a new multi-sampling setting
+    /// of the output attachments. Uploaded meshes are kept.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.options.sample_count = sample_count;
+        self.pipelines = ScenePipelines::new(device, &self.bind_group_layouts, self.options);
     }
 
     /// Update camera matrices (projection matrix and view matrix).
+    ///
+    /// The matrices are written to the next buffer of a ring, so that
+    /// the update does not wait for the GPU to finish reading the
+    /// matrices of previous frames.
     pub fn set_camera_matrices(
         &mut self,
         device: &wgpu::Device,
@@ -528,36 +969,193 @@ impl SceneRenderer {
         projection_matrix: &Matrix4<f32>,
         view_matrix: &Matrix4<f32>,
     ) {
-        let matrix_uniforms = MatrixUniforms {
-            projection_matrix: apply_wgpu_correction_matrix(projection_matrix).into(),
-            view_matrix: view_matrix.clone().into(),
-        };
-        upload_matrix_buffer(device, queue, &self.matrix_buffer, matrix_uniforms);
+        self.projection_matrix = *projection_matrix;
+        self.view_matrix = *view_matrix;
+        self.upload_camera_and_section_buffers(device, queue);
     }
 
-    /// Upload mesh on the GPU.
-    ///
-    /// Whether indexed or not, the data must be in the
-    /// `TRIANGLE_LIST` format. The returned id can be used to draw
-    /// the mesh, or remove it.
-    pub fn add_mesh(
+    /// Sets the section plane cutting all drawn meshes, or removes it.
+    pub fn set_section(
         &mut self,
         device: &wgpu::Device,
-        mesh: &GpuMesh,
-    ) -> Result<GpuMeshId, AddMeshError> {
-        let id = GpuMeshId(self.mesh_resources_next_id);
+        queue: &mut wgpu::Queue,
+        section: Option<Section>,
+    ) {
+        self.section = section;
+        self.upload_camera_and_section_buffers(device, queue);
+    }
 
-        let vertex_data = &mesh.vertex_data[..];
-        let vertex_data_count = u32::try_from(vertex_data.len())
-            .map_err(|_| AddMeshError::TooManyVertices(vertex_data.len()))?;
+    /// Sets the false-color debug view replacing the shading of all
+    /// meshes. `DebugView::Off` shades the meshes normally.
+    pub fn set_debug_view(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        debug_view: DebugView,
+    ) {
+        self.debug_view = debug_view;
+        self.upload_camera_and_section_buffers(device, queue);
+    }
 
-        let mesh_descriptor = if let Some(indices) = &mesh.indices {
-            let index_count = u32::try_from(indices.len())
-                .map_err(|_| AddMeshError::TooManyIndices(indices.len()))?;
+    /// Sets the size of the viewport in pixels, so that the vertices
+    /// of mesh topologies are drawn with the same size on the screen.
+    pub fn set_viewport_size(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        width: u32,
+        height: u32,
+    ) {
+        // The normalized device coordinates span 2 units across the
+        // viewport
+        let topology_uniforms = TopologyUniforms {
+            vertex_half_size: [
+                TOPOLOGY_VERTEX_SIZE / width.max(1) as f32,
+                TOPOLOGY_VERTEX_SIZE / height.max(1) as f32,
+            ],
+            _padding: [0; 2],
+        };
+        upload_uniform_buffers(device, queue, &mut self.topology_buffers, topology_uniforms);
+    }
 
-            log::debug!(
-                "Adding mesh with ID {}, {} vertices and {} indices",
-                id.0,
+    fn upload_camera_and_section_buffers(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+    ) {
+        let projection_matrix = apply_wgpu_correction_matrix(&self.projection_matrix);
+        let section_plane = self
+            .section
+            .map_or(NO_SECTION_PLANE, |section| section.plane_equation());
+
+        let matrix_uniforms = MatrixUniforms {
+            projection_matrix: projection_matrix.into(),
+            view_matrix: self.view_matrix.into(),
+            section_plane,
+            depth_range: self.depth_range(),
+            debug_view: match self.debug_view {
+                DebugView::Off => DEBUG_VIEW_OFF,
+                DebugView::Normals => DEBUG_VIEW_NORMALS,
+                DebugView::Depth => DEBUG_VIEW_DEPTH,
+            },
+            _padding: 0,
+        };
+        upload_uniform_buffers(device, queue, &mut self.matrix_buffers, matrix_uniforms);
+
+        if let Some(section) = self.section {
+            let section_cap_uniforms =
+                SectionCapUniforms::new(&section, &(projection_matrix * self.view_matrix));
+            upload_uniform_buffers(
+                device,
+                queue,
+                &mut self.section_cap_buffers,
+                section_cap_uniforms,
+            );
+        }
+    }
+
+    /// Returns the `[closest, farthest]` view-space depth of the
+    /// bounding boxes of all uploaded meshes, for normalizing the depth
+    /// debug view. Only computed when the view is enabled.
+    fn depth_range(&self) -> [f32; 2] {
+        if self.debug_view != DebugView::Depth {
+            return [0.0, 1.0];
+        }
+
+        let mut closest = f32::MAX;
+        let mut farthest = f32::MIN;
+        for mesh in self.mesh_resources.values() {
+            if let Some(bounding_box) = &mesh.bounding_box {
+                let model_view_matrix = self.view_matrix * mesh.transform;
+                for corner in &bounding_box.corners() {
+                    // The camera looks in the direction of negative Z
+                    let depth = -model_view_matrix.transform_point(corner).z;
+                    closest = closest.min(depth);
+                    farthest = farthest.max(depth);
+                }
+            }
+        }
+
+        if closest < farthest {
+            [closest.max(0.0), farthest]
+        } else {
+            [0.0, 1.0]
+        }
+    }
+
+    /// Upload mesh on the GPU.
+    ///
+    /// Whether indexed or not, the data must be in the
+    /// `TRIANGLE_LIST` format. The returned id can be used to draw
+    /// the mesh, set its transform, tint, shading or material, or
+    /// remove it. The mesh starts with the identity transform, no
+    /// tint and the default shading and material.
+    pub fn add_mesh(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        mesh: &GpuMesh,
+    ) -> Result<GpuMeshId, AddMeshError> {
+        let id = GpuMeshId(self.mesh_resources_next_id);
+
+        let vertex_data = &mesh.vertex_data[..];
+        let vertex_data_count = u32::try_from(vertex_data.len())
+            .map_err(|_| AddMeshError::TooManyVertices(vertex_data.len()))?;
+
+        let bounding_box =
+            BoundingBox::from_points(vertex_data.iter().map(|vertex| {
+                Point3::new(vertex.position[0], vertex.position[1], vertex.position[2])
+            }));
+
+        let model_buffer_size = wgpu_size_of::<ModelUniforms>();
+        let model_buffer = device
+            .create_buffer_mapped(1, wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST)
+            .fill_from_slice(&[ModelUniforms::new(
+                &Matrix4::identity(),
+                None,
+                MeshShading::default(),
+                Material::default(),
+            )]);
+        let model_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layouts.model,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &model_buffer,
+                    range: 0..model_buffer_size,
+                },
+            }],
+        });
+
+        // Small meshes are drawn from shared buffers, unindexed, so
+        // that they can be merged without rebasing their indices
+        let batched_vertex_count = mesh
+            .indices
+            .as_ref()
+            .map_or(vertex_data.len(), |indices| indices.len());
+        let geometry = if batched_vertex_count <= BATCHED_MESH_VERTEX_COUNT_MAX {
+            let batched_vertex_data: Vec<GpuMeshVertex> = match &mesh.indices {
+                Some(indices) => indices
+                    .iter()
+                    .map(|index| vertex_data[cast_usize(*index)])
+                    .collect(),
+                None => vertex_data.to_vec(),
+            };
+
+            log::debug!(
+                "Adding mesh with ID {} and {} vertices to a shared buffer",
+                id.0,
+                batched_vertex_data.len(),
+            );
+
+            self.add_batched_geometry(device, queue, &batched_vertex_data)
+        } else if let Some(indices) = &mesh.indices {
+            let index_count = u32::try_from(indices.len())
+                .map_err(|_| AddMeshError::TooManyIndices(indices.len()))?;
+
+            log::debug!(
+                "Adding mesh with ID {}, {} vertices and {} indices",
+                id.0,
                 vertex_data_count,
                 index_count,
             );
@@ -570,7 +1168,7 @@ impl SceneRenderer {
                 .create_buffer_mapped(indices.len(), wgpu::BufferUsage::INDEX)
                 .fill_from_slice(indices);
 
-            MeshResource {
+            MeshGeometry::Dedicated {
                 vertices: (vertex_buffer, vertex_data_count),
                 indices: Some((index_buffer, index_count)),
             }
@@ -585,27 +1183,229 @@ impl SceneRenderer {
                 .create_buffer_mapped(vertex_data.len(), wgpu::BufferUsage::VERTEX)
                 .fill_from_slice(vertex_data);
 
-            MeshResource {
+            MeshGeometry::Dedicated {
                 vertices: (vertex_buffer, vertex_data_count),
                 indices: None,
             }
         };
 
+        let mesh_descriptor = MeshResource {
+            geometry,
+            model_buffer,
+            model_bind_group,
+            transform: Matrix4::identity(),
+            tint: None,
+            shading: MeshShading::default(),
+            material: Material::default(),
+            bounding_box,
+            topology: None,
+        };
+
         self.mesh_resources.insert(id.0, mesh_descriptor);
         self.mesh_resources_next_id += 1;
         Ok(id)
     }
 
+    /// Set the model matrix of a previously uploaded mesh, placing the
+    /// mesh in the world-space.
+    pub fn set_mesh_transform(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        id: GpuMeshId,
+        transform: &Matrix4<f32>,
+    ) {
+        if let Some(mesh) = self.mesh_resources.get_mut(&id.0) {
+            mesh.transform = *transform;
+            upload_model_buffer(
+                device,
+                queue,
+                &mesh.model_buffer,
+                ModelUniforms::new(&mesh.transform, mesh.tint, mesh.shading, mesh.material),
+            );
+        } else {
+            log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
+        }
+    }
+
+    /// Set the tint of a previously uploaded mesh, e.g. to highlight
+    /// it. The RGB tint in the `0..1` range is blended over the
+    /// shading color. `None` removes the tint.
+    pub fn set_mesh_tint(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        id: GpuMeshId,
+        tint: Option<[f32; 3]>,
+    ) {
+        if let Some(mesh) = self.mesh_resources.get_mut(&id.0) {
+            mesh.tint = tint;
+            upload_model_buffer(
+                device,
+                queue,
+                &mesh.model_buffer,
+                ModelUniforms::new(&mesh.transform, mesh.tint, mesh.shading, mesh.material),
+            );
+        } else {
+            log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
+        }
+    }
+
+    /// Set the shading of a previously uploaded mesh.
+    pub fn set_mesh_shading(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        id: GpuMeshId,
+        shading: MeshShading,
+    ) {
+        if let Some(mesh) = self.mesh_resources.get_mut(&id.0) {
+            mesh.shading = shading;
+            upload_model_buffer(
+                device,
+                queue,
+                &mesh.model_buffer,
+                ModelUniforms::new(&mesh.transform, mesh.tint, mesh.shading, mesh.material),
+            );
+        } else {
+            log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
+        }
+    }
+
+    /// Set the material of a previously uploaded mesh.
+    pub fn set_mesh_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        id: GpuMeshId,
+        material: Material,
+    ) {
+        if let Some(mesh) = self.mesh_resources.get_mut(&id.0) {
+            mesh.material = material;
+            upload_model_buffer(
+                device,
+                queue,
+                &mesh.model_buffer,
+                ModelUniforms::new(&mesh.transform, mesh.tint, mesh.shading, mesh.material),
+            );
+        } else {
+            log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
+        }
+    }
+
+    /// Uploads the topology of a previously uploaded mesh, so that it
+    /// is drawn by `draw_topology`, or removes it.
+    pub fn set_mesh_topology(
+        &mut self,
+        device: &wgpu::Device,
+        id: GpuMeshId,
+        topology: Option<&GpuMeshTopology>,
+    ) {
+        if let Some(mesh) = self.mesh_resources.get_mut(&id.0) {
+            mesh.topology = topology
+                .filter(|topology| !topology.vertex_data.is_empty())
+                .map(|topology| {
+                    let edge_vertex_buffer = device
+                        .create_buffer_mapped(
+                            topology.edge_vertex_data.len(),
+                            wgpu::BufferUsage::VERTEX,
+                        )
+                        .fill_from_slice(&topology.edge_vertex_data);
+                    let vertex_buffer = device
+                        .create_buffer_mapped(topology.vertex_data.len(), wgpu::BufferUsage::VERTEX)
+                        .fill_from_slice(&topology.vertex_data);
+
+                    TopologyResource {
+                        edge_vertices: (
+                            edge_vertex_buffer,
+                            cast_u32(topology.edge_vertex_data.len()),
+                        ),
+                        vertices: (vertex_buffer, cast_u32(topology.vertex_data.len())),
+                    }
+                });
+        } else {
+            log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
+        }
+    }
+
     /// Remove a previously uploaded mesh from the GPU.
     pub fn remove_mesh(&mut self, id: GpuMeshId) {
         log::debug!("Removing mesh with ID {}", id.0);
         // Dropping the mesh descriptor here unstreams the buffers from device memory
-        self.mesh_resources.remove(&id.0);
+        if let Some(mesh) = self.mesh_resources.remove(&id.0) {
+            if let MeshGeometry::Batched { batch_index, .. } = mesh.geometry {
+                self.mesh_batches[batch_index].remove_mesh();
+            }
+        }
+    }
+
+    /// Upload curve on the GPU. Returns a handle to draw or remove
+    /// it.
+    pub fn add_curve(&mut self, device: &wgpu::Device, curve: &GpuCurve) -> GpuCurveId {
+        let id = GpuCurveId(self.curve_resources_next_id);
+        log::debug!(
+            "Adding curve with ID {} and {} segments",
+            id.0,
+            curve.segment_vertex_data.len() / 2,
+        );
+
+        // Empty buffers can't be created, so empty curves are not
+        // uploaded, but they still get an ID
+        if !curve.segment_vertex_data.is_empty() {
+            let vertex_buffer = device
+                .create_buffer_mapped(curve.segment_vertex_data.len(), wgpu::BufferUsage::VERTEX)
+                .fill_from_slice(&curve.segment_vertex_data);
+            self.curve_resources.insert(
+                id.0,
+                (vertex_buffer, cast_u32(curve.segment_vertex_data.len())),
+            );
+        }
+
+        self.curve_resources_next_id += 1;
+
+        id
+    }
+
+    /// Remove a previously uploaded curve from the GPU.
+    pub fn remove_curve(&mut self, id: GpuCurveId) {
+        log::debug!("Removing curve with ID {}", id.0);
+        self.curve_resources.remove(&id.0);
+    }
+
+    /// Upload points on the GPU. Returns a handle to draw or remove
+    /// them.
+    pub fn add_points(&mut self, device: &wgpu::Device, points: &GpuPoints) -> GpuPointsId {
+        let id = GpuPointsId(self.points_resources_next_id);
+        log::debug!(
+            "Adding points with ID {} and {} points",
+            id.0,
+            points.vertex_data.len(),
+        );
+
+        // Like with curves, empty buffers are not uploaded
+        if !points.vertex_data.is_empty() {
+            let vertex_buffer = device
+                .create_buffer_mapped(points.vertex_data.len(), wgpu::BufferUsage::VERTEX)
+                .fill_from_slice(&points.vertex_data);
+            self.points_resources
+                .insert(id.0, (vertex_buffer, cast_u32(points.vertex_data.len())));
+        }
+
+        self.points_resources_next_id += 1;
+
+        id
+    }
+
+    /// Remove previously uploaded points from the GPU.
+    pub fn remove_points(&mut self, id: GpuPointsId) {
+        log::debug!("Removing points with ID {}", id.0);
+        self.points_resources.remove(&id.0);
     }
 
     /// Optionally clear color and depth and draw previously uploaded
     /// meshes as one of the commands executed with the `encoder`
-    /// to the `color_attachment`.
+    /// to the `color_attachment`. If `scissor_rect` is provided, only
+    /// pixels within the `[x, y, width, height]` rect are drawn.
     #[allow(clippy::too_many_arguments)]
     pub fn draw_mesh<'a, I>(
         &self,
@@ -615,6 +1415,7 @@ impl SceneRenderer {
         color_attachment: &wgpu::TextureView,
         msaa_attachment: Option<&wgpu::TextureView>,
         depth_attachment: &wgpu::TextureView,
+        scissor_rect: Option<[u32; 4]>,
         ids: I,
     ) where
         I: Iterator<Item = &'a GpuMeshId> + Clone,
@@ -677,16 +1478,22 @@ impl SceneRenderer {
         // set... Not sure if this is a bug or not.
         rpass.set_stencil_reference(0);
 
+        if let Some([x, y, width, height]) = scissor_rect {
+            rpass.set_scissor_rect(x, y, width, height);
+        }
+
         // FIXME: The current renderer architecture is enough for our
         // current needs, but has some serious downsides.
         //
         // - We should be doing object sorting. We currently live
-        //   without it as the only transparent objects we have are
-        //   the edges and those do not show mis-blending artifacts,
-        //   because the area that of their fragments that is neither
-        //   fully transparent nor fully opaque is very small. If we
-        //   ever want to support transparency, we need to sort
-        //   objects.
+        //   without it as the transparent edges do not show
+        //   mis-blending artifacts, because the area that of their
+        //   fragments that is neither fully transparent nor fully
+        //   opaque is very small. Meshes with translucent materials
+        //   are drawn after the opaque ones, back to front by the
+        //   centers of their bounding boxes. Intersecting or nested
+        //   translucent meshes can still mis-blend, as can the
+        //   faces of a single mesh.
         //
         // - We don't mitigate self-transparency issues (because we
         //   don't experience them much). The simplest mitigation
@@ -703,69 +1510,611 @@ impl SceneRenderer {
 
         match mode {
             DrawMeshMode::Shaded => {
-                rpass.set_pipeline(&self.render_pipeline_opaque);
-                rpass.set_bind_group(0, &self.matrix_bind_group, &[]);
-                rpass.set_bind_group(1, &self.shading_bind_group_shaded, &[]);
-                rpass.set_bind_group(2, &self.matcap_texture_bind_group, &[]);
-
-                self.record(&mut rpass, ids);
+                self.record_shaded(&mut rpass, &self.shading_bind_group_shaded, ids);
             }
             DrawMeshMode::Edges => {
-                rpass.set_pipeline(&self.render_pipeline_transparent);
-                rpass.set_bind_group(0, &self.matrix_bind_group, &[]);
+                rpass.set_pipeline(&self.pipelines.transparent);
+                rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
                 rpass.set_bind_group(1, &self.shading_bind_group_edges, &[]);
-                rpass.set_bind_group(2, &self.matcap_texture_bind_group, &[]);
 
                 self.record(&mut rpass, ids);
             }
             DrawMeshMode::ShadedEdges => {
-                rpass.set_pipeline(&self.render_pipeline_opaque);
-                rpass.set_bind_group(0, &self.matrix_bind_group, &[]);
-                rpass.set_bind_group(1, &self.shading_bind_group_shaded_edges, &[]);
-                rpass.set_bind_group(2, &self.matcap_texture_bind_group, &[]);
-
-                self.record(&mut rpass, ids);
+                self.record_shaded(&mut rpass, &self.shading_bind_group_shaded_edges, ids);
             }
             DrawMeshMode::ShadedEdgesXray => {
-                rpass.set_pipeline(&self.render_pipeline_opaque);
-                rpass.set_bind_group(0, &self.matrix_bind_group, &[]);
-                rpass.set_bind_group(1, &self.shading_bind_group_shaded, &[]);
-                rpass.set_bind_group(2, &self.matcap_texture_bind_group, &[]);
-
-                self.record(&mut rpass, ids.clone());
+                self.record_shaded(&mut rpass, &self.shading_bind_group_shaded, ids.clone());
 
-                rpass.set_pipeline(&self.render_pipeline_transparent);
+                rpass.set_pipeline(&self.pipelines.transparent);
+                rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
                 rpass.set_bind_group(1, &self.shading_bind_group_edges, &[]);
 
                 self.record(&mut rpass, ids);
             }
+            DrawMeshMode::Ghosted => {
+                rpass.set_pipeline(&self.pipelines.transparent);
+                rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
+                rpass.set_bind_group(1, &self.shading_bind_group_ghosted, &[]);
+
+                self.record(&mut rpass, ids);
+            }
+            DrawMeshMode::FaceOrientation => {
+                self.record_shaded(&mut rpass, &self.shading_bind_group_face_orientation, ids);
+            }
         }
     }
 
+    /// Draw the topology of previously uploaded meshes over what is
+    /// already in the attachments, as one of the commands executed
+    /// with the `encoder`. Meshes without uploaded topology are
+    /// skipped. The edges and vertices are depth tested against the
+    /// meshes, so should be drawn after them. If `scissor_rect` is
+    /// provided, only pixels within the `[x, y, width, height]` rect
+    /// are drawn.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_topology<'a, I>(
+        &self,
+        clear_depth: bool,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+        depth_attachment: &wgpu::TextureView,
+        scissor_rect: Option<[u32; 4]>,
+        ids: I,
+    ) where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let depth_load_op = if clear_depth {
+            wgpu::LoadOp::Clear
+        } else {
+            wgpu::LoadOp::Load
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: depth_attachment,
+                depth_load_op,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 1.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        if let Some([x, y, width, height]) = scissor_rect {
+            rpass.set_scissor_rect(x, y, width, height);
+        }
+
+        // Both pipelines have the same layout, so the bind groups stay
+        // bound
+        rpass.set_pipeline(&self.pipelines.topology_edges);
+        rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
+        rpass.set_bind_group(2, self.topology_buffers.bind_group(), &[]);
+        for id in ids.clone() {
+            if let Some((model_bind_group, topology)) = self.mesh_topology(*id) {
+                let (edge_vertex_buffer, edge_vertex_count) = &topology.edge_vertices;
+                rpass.set_bind_group(1, model_bind_group, &[]);
+                rpass.set_vertex_buffers(0, &[(edge_vertex_buffer, 0)]);
+                rpass.draw(0..*edge_vertex_count, 0..1);
+            }
+        }
+
+        // Each vertex is an instance of a square made of 6 vertices
+        // generated in the vertex shader
+        rpass.set_pipeline(&self.pipelines.topology_vertices);
+        for id in ids {
+            if let Some((model_bind_group, topology)) = self.mesh_topology(*id) {
+                let (vertex_buffer, vertex_count) = &topology.vertices;
+                rpass.set_bind_group(1, model_bind_group, &[]);
+                rpass.set_vertex_buffers(0, &[(vertex_buffer, 0)]);
+                rpass.draw(0..6, 0..*vertex_count);
+            }
+        }
+    }
+
+    /// Optionally clear depth and draw previously uploaded curves and
+    /// points as one of the commands executed with the `encoder`. They
+    /// are depth tested against the meshes, so should be drawn after
+    /// them. If `scissor_rect` is provided, only pixels within the
+    /// `[x, y, width, height]` rect are drawn.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_curves_and_points<'a, 'b, C, P>(
+        &self,
+        clear_depth: bool,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: &wgpu::TextureView,
+        msaa_attachment: Option<&wgpu::TextureView>,
+        depth_attachment: &wgpu::TextureView,
+        scissor_rect: Option<[u32; 4]>,
+        curve_ids: C,
+        points_ids: P,
+    ) where
+        C: Iterator<Item = &'a GpuCurveId>,
+        P: Iterator<Item = &'b GpuPointsId>,
+    {
+        let (attachment, resolve_target) = match msaa_attachment {
+            Some(msaa_attachment) => (msaa_attachment, Some(color_attachment)),
+            None => (color_attachment, None),
+        };
+
+        let depth_load_op = if clear_depth {
+            wgpu::LoadOp::Clear
+        } else {
+            wgpu::LoadOp::Load
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment,
+                resolve_target,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::BLACK,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: depth_attachment,
+                depth_load_op,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 1.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        if let Some([x, y, width, height]) = scissor_rect {
+            rpass.set_scissor_rect(x, y, width, height);
+        }
+
+        rpass.set_pipeline(&self.pipelines.topology_edges);
+        rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, &self.curve_model_bind_group, &[]);
+        rpass.set_bind_group(2, self.topology_buffers.bind_group(), &[]);
+        for id in curve_ids {
+            if let Some((vertex_buffer, vertex_count)) = self.curve_resources.get(&id.0) {
+                rpass.set_vertex_buffers(0, &[(vertex_buffer, 0)]);
+                rpass.draw(0..*vertex_count, 0..1);
+            }
+        }
+
+        // Each point is an instance of a square, like the topology
+        // vertices
+        rpass.set_pipeline(&self.pipelines.topology_vertices);
+        for id in points_ids {
+            if let Some((vertex_buffer, point_count)) = self.points_resources.get(&id.0) {
+                rpass.set_vertex_buffers(0, &[(vertex_buffer, 0)]);
+                rpass.draw(0..6, 0..*point_count);
+            }
+        }
+    }
+
+    /// Returns the model bind group and the uploaded topology of the
+    /// mesh, if it has any.
+    fn mesh_topology(&self, id: GpuMeshId) -> Option<(&wgpu::BindGroup, &TopologyResource)> {
+        let mesh = self.mesh_resources.get(&id.0)?;
+        let topology = mesh.topology.as_ref()?;
+
+        Some((&mesh.model_bind_group, topology))
+    }
+
+    /// Records capping the meshes, if they are cut by the section.
+    /// The meshes must already be drawn, so that the caps are depth
+    /// tested against them.
+    /// Uploads the unindexed `vertex_data` to the first shared buffer
+    /// with enough space left, creating a new one if there is none.
+    fn add_batched_geometry(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        vertex_data: &[GpuMeshVertex],
+    ) -> MeshGeometry {
+        let vertex_count = cast_u32(vertex_data.len());
+        let batch_index = match self
+            .mesh_batches
+            .iter()
+            .position(|batch| batch.remaining_capacity() >= vertex_count)
+        {
+            Some(batch_index) => batch_index,
+            None => {
+                self.mesh_batches.push(MeshBatch::new(device));
+                self.mesh_batches.len() - 1
+            }
+        };
+
+        let vertices = self.mesh_batches[batch_index].add_mesh(device, queue, vertex_data);
+        MeshGeometry::Batched {
+            batch_index,
+            vertices,
+        }
+    }
+
+    /// Records the meshes with the shading of the
+    /// `shading_bind_group`. Opaque meshes are recorded first, then
+    /// the section caps and finally the meshes with translucent
+    /// materials, blended over the rest from back to front.
+    fn record_shaded<'a, I>(
+        &self,
+        rpass: &mut wgpu::RenderPass,
+        shading_bind_group: &wgpu::BindGroup,
+        ids: I,
+    ) where
+        I: Iterator<Item = &'a GpuMeshId> + Clone,
+    {
+        let is_opaque = |id: &&GpuMeshId| {
+            self.mesh_resources
+                .get(&id.0)
+                .map_or(true, |mesh| mesh.material.is_opaque())
+        };
+
+        rpass.set_pipeline(&self.pipelines.opaque);
+        rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, shading_bind_group, &[]);
+
+        self.record(rpass, ids.clone().filter(is_opaque));
+        self.record_section_caps(rpass, ids.clone());
+
+        let mut translucent_meshes: Vec<&MeshResource> = ids
+            .filter(|id| !is_opaque(id))
+            .filter_map(|id| self.mesh_resources.get(&id.0))
+            .collect();
+        if translucent_meshes.is_empty() {
+            return;
+        }
+        translucent_meshes.sort_by(|a, b| {
+            self.view_distance(b)
+                .partial_cmp(&self.view_distance(a))
+                .unwrap_or(cmp::Ordering::Equal)
+        });
+
+        rpass.set_pipeline(&self.pipelines.translucent);
+        rpass.set_bind_group(0, self.matrix_buffers.bind_group(), &[]);
+        rpass.set_bind_group(1, shading_bind_group, &[]);
+
+        self.record_meshes(rpass, &translucent_meshes);
+    }
+
+    /// The distance of the center of the mesh's bounding box from the
+    /// camera plane, for sorting translucent meshes.
+    fn view_distance(&self, mesh: &MeshResource) -> f32 {
+        let center = mesh
+            .bounding_box
+            .as_ref()
+            .map_or_else(Point3::origin, |bounding_box| bounding_box.center());
+        let view_position = (self.view_matrix * mesh.transform).transform_point(&center);
+
+        // The camera looks in the direction of the negative Z axis
+        -view_position.z
+    }
+
+    fn record_section_caps<'a, I>(&self, rpass: &mut wgpu::RenderPass, ids: I)
+    where
+        I: IntoIterator<Item = &'a GpuMeshId>,
+    {
+        if self.section.is_none() {
+            return;
+        }
+
+        // The stencil pipeline has the same layout as the one used to
+        // draw the meshes, so the bind groups stay bound
+        rpass.set_pipeline(&self.pipelines.section_stencil);
+        self.record(rpass, ids);
+
+        rpass.set_pipeline(&self.pipelines.section_cap);
+        rpass.set_bind_group(0, self.section_cap_buffers.bind_group(), &[]);
+        rpass.draw(0..3, 0..1);
+    }
+
     fn record<'a, I>(&self, rpass: &mut wgpu::RenderPass, ids: I)
     where
         I: IntoIterator<Item = &'a GpuMeshId>,
     {
+        let mut meshes: Vec<&MeshResource> = Vec::new();
         for id in ids {
-            if let Some(mesh) = &self.mesh_resources.get(&id.0) {
-                let (vertex_buffer, vertex_count) = &mesh.vertices;
-                rpass.set_vertex_buffers(0, &[(vertex_buffer, 0)]);
-                if let Some((index_buffer, index_count)) = &mesh.indices {
-                    rpass.set_index_buffer(&index_buffer, 0);
-                    rpass.draw_indexed(0..*index_count, 0, 0..1);
-                } else {
-                    rpass.draw(0..*vertex_count, 0..1);
-                }
+            if let Some(mesh) = self.mesh_resources.get(&id.0) {
+                meshes.push(mesh);
             } else {
                 log::warn!("Mesh with id {} does not exist in this renderer.", id.0);
             }
         }
+
+        // Meshes are grouped by matcap and shared vertex buffer, so
+        // that these are only rebound when they change. Only the
+        // model bind group is set for each mesh.
+        meshes.sort_by_key(|mesh| (mesh.shading.matcap.index(), mesh.geometry.batch_index()));
+
+        self.record_meshes(rpass, &meshes);
+    }
+
+    /// Records the `meshes` in the given order. The matcap and the
+    /// vertex buffer are only rebound when they differ from those of
+    /// the previous mesh.
+    fn record_meshes(&self, rpass: &mut wgpu::RenderPass, meshes: &[&MeshResource]) {
+        let mut bound_matcap_index = None;
+        let mut bound_batch_index = None;
+        for mesh in meshes {
+            let matcap_index = mesh.shading.matcap.index();
+            if bound_matcap_index != Some(matcap_index) {
+                rpass.set_bind_group(2, &self.matcap_texture_bind_groups[matcap_index], &[]);
+                bound_matcap_index = Some(matcap_index);
+            }
+            rpass.set_bind_group(3, &mesh.model_bind_group, &[]);
+
+            match &mesh.geometry {
+                MeshGeometry::Dedicated { vertices, indices } => {
+                    let (vertex_buffer, vertex_count) = vertices;
+                    rpass.set_vertex_buffers(0, &[(vertex_buffer, 0)]);
+                    bound_batch_index = None;
+                    if let Some((index_buffer, index_count)) = indices {
+                        rpass.set_index_buffer(&index_buffer, 0);
+                        rpass.draw_indexed(0..*index_count, 0, 0..1);
+                    } else {
+                        rpass.draw(0..*vertex_count, 0..1);
+                    }
+                }
+                MeshGeometry::Batched {
+                    batch_index,
+                    vertices,
+                } => {
+                    if bound_batch_index != Some(*batch_index) {
+                        let batch = &self.mesh_batches[*batch_index];
+                        rpass.set_vertex_buffers(0, &[(&batch.vertex_buffer, 0)]);
+                        bound_batch_index = Some(*batch_index);
+                    }
+                    rpass.draw(vertices.clone(), 0..1);
+                }
+            }
+        }
+    }
+}
+
+/// The layouts of the bind groups used by the scene renderer,
+/// kept for recreating its pipelines.
+struct SceneBindGroupLayouts {
+    matrix: wgpu::BindGroupLayout,
+    shading: wgpu::BindGroupLayout,
+    matcap_texture: wgpu::BindGroupLayout,
+    model: wgpu::BindGroupLayout,
+    section_cap: wgpu::BindGroupLayout,
+    topology: wgpu::BindGroupLayout,
+}
+
+/// The render pipelines of the scene renderer. They depend on the
+/// multi-sampling setting and are recreated together when it changes.
+struct ScenePipelines {
+    opaque: wgpu::RenderPipeline,
+    transparent: wgpu::RenderPipeline,
+    translucent: wgpu::RenderPipeline,
+    section_stencil: wgpu::RenderPipeline,
+    section_cap: wgpu::RenderPipeline,
+    topology_edges: wgpu::RenderPipeline,
+    topology_vertices: wgpu::RenderPipeline,
+}
+
+impl ScenePipelines {
+    fn new(
+        device: &wgpu::Device,
+        bind_group_layouts: &SceneBindGroupLayouts,
+        options: Options,
+    ) -> Self {
+        let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_VIEWPORT_VERT))
+            .expect("Couldn't read pre-built SPIR-V");
+        let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_VIEWPORT_FRAG))
+            .expect("Couldn't read pre-built SPIR-V");
+        let vs_module = device.create_shader_module(&vs_words);
+        let fs_module = device.create_shader_module(&fs_words);
+
+        let opaque = create_pipeline(
+            device,
+            &vs_module,
+            &fs_module,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.shading,
+            &bind_group_layouts.matcap_texture,
+            &bind_group_layouts.model,
+            PipelineKind::Opaque,
+            options,
+        );
+        let transparent = create_pipeline(
+            device,
+            &vs_module,
+            &fs_module,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.shading,
+            &bind_group_layouts.matcap_texture,
+            &bind_group_layouts.model,
+            PipelineKind::Transparent,
+            options,
+        );
+        let translucent = create_pipeline(
+            device,
+            &vs_module,
+            &fs_module,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.shading,
+            &bind_group_layouts.matcap_texture,
+            &bind_group_layouts.model,
+            PipelineKind::Translucent,
+            options,
+        );
+        let section_stencil = create_pipeline(
+            device,
+            &vs_module,
+            &fs_module,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.shading,
+            &bind_group_layouts.matcap_texture,
+            &bind_group_layouts.model,
+            PipelineKind::SectionStencil,
+            options,
+        );
+        let section_cap =
+            create_section_cap_pipeline(device, &bind_group_layouts.section_cap, options);
+        let topology_edges = create_topology_pipeline(
+            device,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.model,
+            &bind_group_layouts.topology,
+            TopologyPipelineKind::Edges,
+            options,
+        );
+        let topology_vertices = create_topology_pipeline(
+            device,
+            &bind_group_layouts.matrix,
+            &bind_group_layouts.model,
+            &bind_group_layouts.topology,
+            TopologyPipelineKind::Vertices,
+            options,
+        );
+
+        Self {
+            opaque,
+            transparent,
+            translucent,
+            section_stencil,
+            section_cap,
+            topology_edges,
+            topology_vertices,
+        }
+    }
+}
+
+struct MeshResource {
+    geometry: MeshGeometry,
+    model_buffer: wgpu::Buffer,
+    model_bind_group: wgpu::BindGroup,
+    transform: Matrix4<f32>,
+    tint: Option<[f32; 3]>,
+    shading: MeshShading,
+    material: Material,
+    /// The bounding box of the vertices before the transform.
+    bounding_box: Option<BoundingBox<f32>>,
+    topology: Option<TopologyResource>,
+}
+
+/// Where the vertex data of an uploaded mesh is stored.
+enum MeshGeometry {
+    /// In buffers of its own, with the number of vertices and indices.
+    Dedicated {
+        vertices: (wgpu::Buffer, u32),
+        indices: Option<(wgpu::Buffer, u32)>,
+    },
+    /// Unindexed, in a range of the vertices of the `MeshBatch` at
+    /// `batch_index`.
+    Batched {
+        batch_index: usize,
+        vertices: Range<u32>,
+    },
+}
+
+impl MeshGeometry {
+    fn batch_index(&self) -> Option<usize> {
+        match self {
+            MeshGeometry::Dedicated { .. } => None,
+            MeshGeometry::Batched { batch_index, .. } => Some(*batch_index),
+        }
+    }
+}
+
+/// A vertex buffer shared by small meshes, so that drawing many of
+/// them doesn't rebind the vertex buffer for each.
+///
+/// Meshes are appended after the last one and their space is only
+/// reclaimed once all meshes of the batch are removed. This suits the
+/// scene, whose meshes are usually all replaced at once when the
+/// pipeline is evaluated again.
+struct MeshBatch {
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+    mesh_count: usize,
+}
+
+impl MeshBatch {
+    fn new(device: &wgpu::Device) -> Self {
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            size: wgpu_size_of::<GpuMeshVertex>()
+                * wgpu::BufferAddress::from(MESH_BATCH_VERTEX_CAPACITY),
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+        });
+
+        Self {
+            vertex_buffer,
+            vertex_count: 0,
+            mesh_count: 0,
+        }
+    }
+
+    fn remaining_capacity(&self) -> u32 {
+        MESH_BATCH_VERTEX_CAPACITY - self.vertex_count
+    }
+
+    /// Copies the vertex data after the last mesh of the batch and
+    /// returns the range of its vertices.
+    fn add_mesh(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &mut wgpu::Queue,
+        vertex_data: &[GpuMeshVertex],
+    ) -> Range<u32> {
+        let vertex_count = cast_u32(vertex_data.len());
+        assert!(
+            vertex_count <= self.remaining_capacity(),
+            "Mesh doesn't fit into the batch",
+        );
+
+        if !vertex_data.is_empty() {
+            let vertex_size = wgpu_size_of::<GpuMeshVertex>();
+            let transfer_buffer = device
+                .create_buffer_mapped(vertex_data.len(), wgpu::BufferUsage::COPY_SRC)
+                .fill_from_slice(vertex_data);
+
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+            encoder.copy_buffer_to_buffer(
+                &transfer_buffer,
+                0,
+                &self.vertex_buffer,
+                vertex_size * wgpu::BufferAddress::from(self.vertex_count),
+                vertex_size * wgpu::BufferAddress::from(vertex_count),
+            );
+
+            queue.submit(&[encoder.finish()]);
+        }
+
+        let first_vertex = self.vertex_count;
+        self.vertex_count += vertex_count;
+        self.mesh_count += 1;
+
+        first_vertex..self.vertex_count
+    }
+
+    /// Forgets a removed mesh. Once all meshes are removed, the
+    /// buffer is filled from the start again.
+    fn remove_mesh(&mut self) {
+        self.mesh_count -= 1;
+        if self.mesh_count == 0 {
+            self.vertex_count = 0;
+        }
     }
 }
 
-struct MeshResource {
+/// The edge and vertex buffers of `GpuMeshTopology`, with the number
+/// of vertices in each.
+struct TopologyResource {
+    edge_vertices: (wgpu::Buffer, u32),
     vertices: (wgpu::Buffer, u32),
-    indices: Option<(wgpu::Buffer, u32)>,
 }
 
 /// The mesh vertex data as uploaded on the GPU.
@@ -782,12 +2131,28 @@ struct GpuMeshVertex {
     /// The normal of the vertex in world-space. Last component is 0.
     pub normal: [f32; 4],
 
+    /// The linear RGB color of the vertex multiplying the shading
+    /// color. Last component is 1.
+    pub color: [f32; 4],
+
     /// Barycentric coordinates of the current vertex within the
     /// triangle primitive. First bit means `(1, 0, 0)`, second `(0,
     /// 1, 0)`, and the third `(0, 0, 1)`. The rest of the bits are 0.
     pub barycentric: u32,
 }
 
+/// The vertex of an edge of `GpuMeshTopology`, or the vertex of the
+/// topology itself, as uploaded on the GPU.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GpuTopologyVertex {
+    /// The position of the vertex in world-space. Last component is 1.
+    pub position: [f32; 4],
+
+    /// The linear RGB color of the vertex. Last component is 1.
+    pub color: [f32; 4],
+}
+
 // FIXME: @Optimization Determine u16/u32 dynamically per mesh to
 // save memory
 type GpuMeshIndex = u32;
@@ -797,6 +2162,119 @@ type GpuMeshIndex = u32;
 struct MatrixUniforms {
     projection_matrix: [[f32; 4]; 4],
     view_matrix: [[f32; 4]; 4],
+    section_plane: [f32; 4],
+    depth_range: [f32; 2],
+    debug_view: u32,
+    _padding: u32,
+}
+
+const DEBUG_VIEW_OFF: u32 = 0;
+const DEBUG_VIEW_NORMALS: u32 = 1;
+const DEBUG_VIEW_DEPTH: u32 = 2;
+
+const SECTION_FILL_SOLID: u32 = 0;
+const SECTION_FILL_HATCHED: u32 = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SectionCapUniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    inverse_view_projection_matrix: [[f32; 4]; 4],
+    section_plane: [f32; 4],
+    color: [f32; 4],
+    hatch_spacing: f32,
+    fill: u32,
+    _padding: [u32; 2],
+}
+
+impl SectionCapUniforms {
+    fn new(section: &Section, view_projection_matrix: &Matrix4<f32>) -> Self {
+        let inverse_view_projection_matrix = view_projection_matrix
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        let [r, g, b] = section.color;
+
+        Self {
+            view_projection_matrix: (*view_projection_matrix).into(),
+            inverse_view_projection_matrix: inverse_view_projection_matrix.into(),
+            section_plane: section.plane_equation(),
+            color: [r, g, b, 1.0],
+            hatch_spacing: section.hatch_spacing.max(f32::EPSILON),
+            fill: match section.fill {
+                SectionFill::Solid => SECTION_FILL_SOLID,
+                SectionFill::Hatched => SECTION_FILL_HATCHED,
+            },
+            _padding: [0; 2],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TopologyUniforms {
+    /// Half of the size of the squares marking the vertices, in
+    /// normalized device coordinates.
+    vertex_half_size: [f32; 2],
+    _padding: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ModelUniforms {
+    model_matrix: [[f32; 4]; 4],
+    /// The inverse transpose of the model matrix, which keeps normals
+    /// perpendicular to surfaces under non-uniform scaling and
+    /// mirroring.
+    normal_matrix: [[f32; 4]; 4],
+    /// The RGB tint blended over the shading color. Last component is
+    /// the blend factor, 0 for untinted meshes.
+    tint: [f32; 4],
+    /// The RGB base color of the `Material` and its opacity.
+    base_color_and_opacity: [f32; 4],
+    shading_flags: ModelShadingFlags,
+    roughness: f32,
+    _padding: [u32; 2],
+}
+
+impl ModelUniforms {
+    /// How much of the shading color is replaced by the tint.
+    const TINT_FACTOR: f32 = 0.5;
+
+    fn new(
+        transform: &Matrix4<f32>,
+        tint: Option<[f32; 3]>,
+        shading: MeshShading,
+        material: Material,
+    ) -> Self {
+        let mut shading_flags = ModelShadingFlags::empty();
+        shading_flags.set(ModelShadingFlags::FLAT, shading.flat);
+
+        let [r, g, b] = material.base_color;
+        Self {
+            tint: tint.map_or([0.0; 4], |[r, g, b]| [r, g, b, Self::TINT_FACTOR]),
+            base_color_and_opacity: [r, g, b, material.opacity],
+            shading_flags,
+            roughness: material.roughness,
+            ..Self::from_transform(transform)
+        }
+    }
+
+    fn from_transform(transform: &Matrix4<f32>) -> Self {
+        let normal_matrix = transform
+            .try_inverse()
+            .map(|inverse| inverse.transpose())
+            .unwrap_or_else(Matrix4::identity);
+
+        Self {
+            model_matrix: transform.clone().into(),
+            normal_matrix: normal_matrix.into(),
+            tint: [0.0; 4],
+            base_color_and_opacity: [1.0; 4],
+            shading_flags: ModelShadingFlags::empty(),
+            roughness: 0.0,
+            _padding: [0; 2],
+        }
+    }
 }
 
 #[repr(C)]
@@ -810,23 +2288,116 @@ bitflags! {
     pub struct ShadingMode: u32 {
         const SHADED = 0x01;
         const EDGES = 0x02;
+        const FACE_ORIENTATION = 0x04;
+    }
+}
+
+bitflags! {
+    struct ModelShadingFlags: u32 {
+        const FLAT = 0x01;
     }
 }
 
-fn upload_matrix_buffer(
+/// Decodes a baked matcap texture, uploads it on the GPU and creates a
+/// bind group for sampling it.
+fn create_matcap_texture_bind_group(
+    device: &wgpu::Device,
+    queue: &mut wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    texture_bytes: &[u8],
+) -> wgpu::BindGroup {
+    let (texture_width, texture_height, texture_data) = {
+        let cursor = io::Cursor::new(texture_bytes);
+        let decoder = png::Decoder::new(cursor);
+        let (info, mut reader) = decoder
+            .read_info()
+            .expect("Baked matcap texture decoding must succeed");
+
+        let mut buffer = vec![0; info.buffer_size()];
+        reader
+            .next_frame(&mut buffer)
+            .expect("Baked matcap texture decoding must succeed");
+
+        assert_eq!(
+            info.color_type,
+            png::ColorType::RGBA,
+            "Baked matcap texture must be RGBA",
+        );
+
+        (info.width, info.height, buffer)
+    };
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width: texture_width,
+            height: texture_height,
+            depth: 1,
+        },
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        bindings: &[
+            wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture.create_default_view()),
+            },
+            wgpu::Binding {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    });
+
+    upload_texture_rgba8_unorm(
+        device,
+        queue,
+        &texture,
+        texture_width,
+        texture_height,
+        &texture_data,
+    );
+
+    bind_group
+}
+
+fn upload_uniform_buffers<T: Copy + 'static>(
+    device: &wgpu::Device,
+    queue: &mut wgpu::Queue,
+    uniform_buffers: &mut UniformBufferRing,
+    uniforms: T,
+) {
+    let transfer_buffer = device
+        .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+        .fill_from_slice(&[uniforms]);
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+    uniform_buffers.write(&mut encoder, &transfer_buffer);
+
+    queue.submit(&[encoder.finish()]);
+}
+
+fn upload_model_buffer(
     device: &wgpu::Device,
     queue: &mut wgpu::Queue,
-    matrix_buffer: &wgpu::Buffer,
-    matrix_uniforms: MatrixUniforms,
+    model_buffer: &wgpu::Buffer,
+    model_uniforms: ModelUniforms,
 ) {
-    let matrix_uniforms_size = wgpu_size_of::<MatrixUniforms>();
+    let model_uniforms_size = wgpu_size_of::<ModelUniforms>();
 
     let transfer_buffer = device
         .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
-        .fill_from_slice(&[matrix_uniforms]);
+        .fill_from_slice(&[model_uniforms]);
 
     let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
-    encoder.copy_buffer_to_buffer(&transfer_buffer, 0, matrix_buffer, 0, matrix_uniforms_size);
+    encoder.copy_buffer_to_buffer(&transfer_buffer, 0, model_buffer, 0, model_uniforms_size);
 
     queue.submit(&[encoder.finish()]);
 }
@@ -856,26 +2427,6 @@ fn upload_shading_buffer(
 }
 
 /// Applies vulkan/wgpu correction matrix to the projection matrix.
-fn apply_wgpu_correction_matrix(projection_matrix: &Matrix4<f32>) -> Matrix4<f32> {
-    // Vulkan (and therefore wgpu) has different NDC and
-    // clip-space semantics than OpenGL: Vulkan is right-handed, Y
-    // grows downwards. The easiest way to keep everything working
-    // as before and use all the libraries that assume OpenGL is
-    // to apply a correction to the projection matrix which
-    // normally changes the right-handed OpenGL world-space to
-    // left-handed OpenGL clip-space.
-    // https://matthewwellings.com/blog/the-new-vulkan-coordinate-system/
-    #[rustfmt::skip]
-    let wgpu_correction_matrix = Matrix4::new(
-        1.0,  0.0,  0.0,  0.0,
-        0.0, -1.0,  0.0,  0.0,
-        0.0,  0.0,  0.5,  0.0,
-        0.0,  0.0,  0.5,  1.0,
-    );
-
-    wgpu_correction_matrix * projection_matrix
-}
-
 /// Produces an infinite iterator over bit-packed barycentric
 /// coordinates of triangle vertices.
 ///
@@ -893,6 +2444,21 @@ fn barycentric_sequence_iter() -> impl Iterator<Item = u32> {
     })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PipelineKind {
+    Opaque,
+    /// Blends over what is already drawn, regardless of depth, e.g.
+    /// for edges seen through the meshes.
+    Transparent,
+    /// Blends over what is already drawn and is hidden behind it, but
+    /// doesn't hide what is drawn later. For meshes whose material
+    /// isn't opaque.
+    Translucent,
+    /// Writes neither color nor depth, only inverts the stencil of
+    /// each drawn pixel.
+    SectionStencil,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn create_pipeline(
     device: &wgpu::Device,
@@ -901,14 +2467,30 @@ fn create_pipeline(
     matrix_bind_group_layout: &wgpu::BindGroupLayout,
     shading_bind_group_layout: &wgpu::BindGroupLayout,
     matcap_texture_bind_group_layout: &wgpu::BindGroupLayout,
-    support_transparency: bool,
+    model_bind_group_layout: &wgpu::BindGroupLayout,
+    kind: PipelineKind,
     options: Options,
 ) -> wgpu::RenderPipeline {
+    let support_transparency =
+        kind == PipelineKind::Transparent || kind == PipelineKind::Translucent;
+    let section_stencil = kind == PipelineKind::SectionStencil;
+    let stencil_face = if section_stencil {
+        wgpu::StencilStateFaceDescriptor {
+            compare: wgpu::CompareFunction::Always,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op: wgpu::StencilOperation::Invert,
+        }
+    } else {
+        wgpu::StencilStateFaceDescriptor::IGNORE
+    };
+
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         bind_group_layouts: &[
             &matrix_bind_group_layout,
             &shading_bind_group_layout,
             &matcap_texture_bind_group_layout,
+            &model_bind_group_layout,
         ],
     });
 
@@ -926,8 +2508,9 @@ fn create_pipeline(
         //
         // Default rasterization state means CullMode::None. We don't
         // cull faces yet, because we work with potentially non-CCW
-        // meshes. We might implement special rendering for CW
-        // faces one day.
+        // meshes. CW faces are instead colored differently in the
+        // face orientation shading mode, which relies on the default
+        // CCW front face.
         rasterization_state: None,
         primitive_topology: wgpu::PrimitiveTopology::TriangleList,
         color_states: &[wgpu::ColorStateDescriptor {
@@ -950,20 +2533,24 @@ fn create_pipeline(
             } else {
                 wgpu::BlendDescriptor::REPLACE
             },
-            write_mask: wgpu::ColorWrite::ALL,
+            write_mask: if section_stencil {
+                wgpu::ColorWrite::empty()
+            } else {
+                wgpu::ColorWrite::ALL
+            },
         }],
         depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
             format: options.output_depth_attachment_format,
-            depth_write_enabled: !support_transparency,
-            depth_compare: if support_transparency {
-                wgpu::CompareFunction::Always
-            } else {
+            depth_write_enabled: kind == PipelineKind::Opaque,
+            depth_compare: if kind == PipelineKind::Opaque || kind == PipelineKind::Translucent {
                 wgpu::CompareFunction::Less
+            } else {
+                wgpu::CompareFunction::Always
             },
-            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
-            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_front: stencil_face.clone(),
+            stencil_back: stencil_face,
             stencil_read_mask: 0,
-            stencil_write_mask: 0,
+            stencil_write_mask: if section_stencil { !0 } else { 0 },
         }),
         index_format: wgpu::IndexFormat::Uint32,
         vertex_buffers: &[wgpu::VertexBufferDescriptor {
@@ -982,6 +2569,11 @@ fn create_pipeline(
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: wgpu_size_of::<[f32; 4]>() * 2, // 4 bytes * 4 components * 2 attribs
+                    format: wgpu::VertexFormat::Float4,
+                    shader_location: 3,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: wgpu_size_of::<[f32; 4]>() * 3, // 4 bytes * 4 components * 3 attribs
                     format: wgpu::VertexFormat::Uint,
                     shader_location: 2,
                 },
@@ -993,9 +2585,171 @@ fn create_pipeline(
     })
 }
 
+/// Creates the pipeline drawing section caps over the whole screen
+/// where the stencil is non-zero, resetting the stencil in the
+/// process. The cap depth is computed in the fragment shader.
+fn create_section_cap_pipeline(
+    device: &wgpu::Device,
+    section_cap_bind_group_layout: &wgpu::BindGroupLayout,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_words = wgpu::read_spirv(io::Cursor::new(SHADER_FULLSCREEN_VERT))
+        .expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_SECTION_CAP_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[section_cap_bind_group_layout],
+    });
+
+    let stencil_face = wgpu::StencilStateFaceDescriptor {
+        compare: wgpu::CompareFunction::NotEqual,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Zero,
+        pass_op: wgpu::StencilOperation::Zero,
+    };
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: None,
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
+            format: options.output_depth_attachment_format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil_front: stencil_face.clone(),
+            stencil_back: stencil_face,
+            stencil_read_mask: !0,
+            stencil_write_mask: !0,
+        }),
+        index_format: wgpu::IndexFormat::Uint16,
+        vertex_buffers: &[],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TopologyPipelineKind {
+    /// Draws the edges as lines.
+    Edges,
+    /// Draws each vertex as an instance of a square facing the screen.
+    Vertices,
+}
+
+/// Creates the pipeline drawing the edges or the vertices of mesh
+/// topologies. They are depth tested against the meshes drawn before,
+/// but don't write depth themselves.
+fn create_topology_pipeline(
+    device: &wgpu::Device,
+    matrix_bind_group_layout: &wgpu::BindGroupLayout,
+    model_bind_group_layout: &wgpu::BindGroupLayout,
+    topology_bind_group_layout: &wgpu::BindGroupLayout,
+    kind: TopologyPipelineKind,
+    options: Options,
+) -> wgpu::RenderPipeline {
+    let vs_bytes = match kind {
+        TopologyPipelineKind::Edges => SHADER_TOPOLOGY_EDGES_VERT,
+        TopologyPipelineKind::Vertices => SHADER_TOPOLOGY_VERTICES_VERT,
+    };
+    let vs_words =
+        wgpu::read_spirv(io::Cursor::new(vs_bytes)).expect("Couldn't read pre-built SPIR-V");
+    let fs_words = wgpu::read_spirv(io::Cursor::new(SHADER_TOPOLOGY_FRAG))
+        .expect("Couldn't read pre-built SPIR-V");
+    let vs_module = device.create_shader_module(&vs_words);
+    let fs_module = device.create_shader_module(&fs_words);
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[
+            matrix_bind_group_layout,
+            model_bind_group_layout,
+            topology_bind_group_layout,
+        ],
+    });
+
+    let (primitive_topology, step_mode) = match kind {
+        TopologyPipelineKind::Edges => (
+            wgpu::PrimitiveTopology::LineList,
+            wgpu::InputStepMode::Vertex,
+        ),
+        TopologyPipelineKind::Vertices => (
+            wgpu::PrimitiveTopology::TriangleList,
+            wgpu::InputStepMode::Instance,
+        ),
+    };
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &vs_module,
+            entry_point: "main",
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &fs_module,
+            entry_point: "main",
+        }),
+        rasterization_state: None,
+        primitive_topology,
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: options.output_color_attachment_format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        depth_stencil_state: Some(wgpu::DepthStencilStateDescriptor {
+            format: options.output_depth_attachment_format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }),
+        index_format: wgpu::IndexFormat::Uint32,
+        vertex_buffers: &[wgpu::VertexBufferDescriptor {
+            stride: wgpu_size_of::<GpuTopologyVertex>(),
+            step_mode,
+            attributes: &[
+                wgpu::VertexAttributeDescriptor {
+                    offset: 0,
+                    format: wgpu::VertexFormat::Float4,
+                    shader_location: 0,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: wgpu_size_of::<[f32; 4]>(),
+                    format: wgpu::VertexFormat::Float4,
+                    shader_location: 1,
+                },
+            ],
+        }],
+        sample_count: options.sample_count,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::mesh::TriangleFace;
+    use crate::curve::Polyline;
+    use crate::mesh::normals::VertexNormals;
+    use crate::mesh::NormalStrategy;
 
     use super::*;
 
@@ -1039,12 +2793,11 @@ mod tests {
             Vector3::new(0.0, 0.0, 1.0),
         ];
 
-        #[rustfmt::skip]
-        let faces = vec![
-            TriangleFace::from_same_vertex_and_normal_index(0, 1, 2)
-        ];
-
-        Mesh::from_triangle_faces_with_vertices_and_normals(faces, positions, normals)
+        Mesh::from_triangle_faces_with_vertices_and_vertex_normals(
+            vec![(0, 1, 2)],
+            positions,
+            VertexNormals::from_vertex_normals(normals),
+        )
     }
 
     fn triangle_mesh_var_len() -> Mesh {
@@ -1055,17 +2808,10 @@ mod tests {
             Point3::new( 0.0,  0.5,  0.0),
         ];
 
-        #[rustfmt::skip]
-        let normals = vec![
-            Vector3::new(0.0, 0.0, 1.0),
-        ];
-
-        #[rustfmt::skip]
-        let faces = vec![
-            TriangleFace::new(0, 1, 2, 0, 0, 0)
-        ];
+        // The face corners have normals of their own
+        let faces = vec![((0, 1, 2), [Vector3::new(0.0, 0.0, 1.0); 3])];
 
-        Mesh::from_triangle_faces_with_vertices_and_normals(faces, positions, normals)
+        Mesh::from_triangle_faces_with_vertices_and_corner_normals(faces, positions)
     }
 
     #[test]
@@ -1079,16 +2825,19 @@ mod tests {
                 GpuMeshVertex {
                     position: [-0.3, -0.5, 0.0, 1.0],
                     normal: [0.0, 0.0, 1.0, 0.0],
+                    color: [1.0, 1.0, 1.0, 1.0],
                     barycentric: 0x01,
                 },
                 GpuMeshVertex {
                     position: [0.3, -0.5, 0.0, 1.0],
                     normal: [0.0, 0.0, 1.0, 0.0],
+                    color: [1.0, 1.0, 1.0, 1.0],
                     barycentric: 0x02,
                 },
                 GpuMeshVertex {
                     position: [0.0, 0.5, 0.0, 1.0],
                     normal: [0.0, 0.0, 1.0, 0.0],
+                    color: [1.0, 1.0, 1.0, 1.0],
                     barycentric: 0x04,
                 },
             ]
@@ -1105,16 +2854,19 @@ mod tests {
             GpuMeshVertex {
                 position: [-0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x01,
             },
             GpuMeshVertex {
                 position: [0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x02,
             },
             GpuMeshVertex {
                 position: [0.0, 0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x04,
             },
         ];
@@ -1184,16 +2936,19 @@ mod tests {
             GpuMeshVertex {
                 position: [-0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x01,
             },
             GpuMeshVertex {
                 position: [0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x02,
             },
             GpuMeshVertex {
                 position: [0.0, 0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x04,
             },
         ];
@@ -1210,16 +2965,19 @@ mod tests {
             GpuMeshVertex {
                 position: [-0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x01,
             },
             GpuMeshVertex {
                 position: [0.3, -0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x02,
             },
             GpuMeshVertex {
                 position: [0.0, 0.5, 0.0, 1.0],
                 normal: [0.0, 0.0, 1.0, 0.0],
+                color: [1.0, 1.0, 1.0, 1.0],
                 barycentric: 0x04,
             },
         ];
@@ -1227,4 +2985,151 @@ mod tests {
         assert_eq!(mesh.vertex_data, expected_vertex_data);
         assert_eq!(mesh.indices, Some(vec![0, 1, 2]));
     }
+
+    #[test]
+    fn test_model_uniforms_from_transform_flips_normals_of_mirrored_mesh() {
+        let mirror = Matrix4::new_nonuniform_scaling(&Vector3::new(-1.0, 1.0, 1.0));
+        let uniforms = ModelUniforms::from_transform(&mirror);
+
+        let normal_matrix = Matrix4::from(uniforms.normal_matrix);
+        let normal = normal_matrix * nalgebra::Vector4::new(1.0, 0.0, 0.0, 0.0);
+
+        let expected_model_matrix: [[f32; 4]; 4] = mirror.into();
+        assert_eq!(uniforms.model_matrix, expected_model_matrix);
+        assert_eq!(normal, nalgebra::Vector4::new(-1.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_gpu_mesh_topology_from_mesh_highlights_border_and_non_manifold_edges() {
+        // Three triangles sharing the edge between vertices 0 and 1
+        let mesh = Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+            vec![(0, 1, 2), (1, 0, 3), (0, 1, 4)],
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.5, 1.0, 0.0),
+                Point3::new(0.5, -1.0, 0.0),
+                Point3::new(0.5, 0.0, 1.0),
+            ],
+            NormalStrategy::Sharp,
+        );
+
+        let topology = GpuMeshTopology::from_mesh(&mesh);
+
+        let edge_count_with_color = |color: [f32; 3]| {
+            topology
+                .edge_vertex_data
+                .chunks(2)
+                .filter(|edge| edge.iter().all(|vertex| vertex.color[..3] == color))
+                .count()
+        };
+        assert_eq!(topology.edge_vertex_data.len(), 14);
+        assert_eq!(edge_count_with_color(TOPOLOGY_NON_MANIFOLD_EDGE_COLOR), 1);
+        assert_eq!(edge_count_with_color(TOPOLOGY_BORDER_EDGE_COLOR), 6);
+        assert_eq!(edge_count_with_color(TOPOLOGY_EDGE_COLOR), 0);
+        assert_eq!(topology.vertex_data.len(), 5);
+    }
+
+    #[test]
+    fn test_gpu_curve_from_curve_closes_closed_polylines() {
+        let curve = Curve::new(vec![
+            Polyline::new(
+                vec![
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(1.0, 0.0, 0.0),
+                    Point3::new(1.0, 1.0, 0.0),
+                ],
+                true,
+            ),
+            Polyline::new(
+                vec![Point3::new(0.0, 0.0, 1.0), Point3::new(1.0, 0.0, 1.0)],
+                false,
+            ),
+        ]);
+
+        let gpu_curve = GpuCurve::from_curve(&curve);
+
+        assert_eq!(gpu_curve.segment_vertex_data.len(), 8);
+        assert_eq!(
+            gpu_curve.segment_vertex_data[5].position,
+            [0.0, 0.0, 0.0, 1.0]
+        );
+        assert!(gpu_curve
+            .segment_vertex_data
+            .iter()
+            .all(|vertex| vertex.color[..3] == CURVE_COLOR));
+    }
+
+    #[test]
+    fn test_gpu_points_from_point_cloud_has_vertex_per_point() {
+        let point_cloud =
+            PointCloud::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0)]);
+
+        let gpu_points = GpuPoints::from_point_cloud(&point_cloud);
+
+        assert_eq!(gpu_points.vertex_data.len(), 2);
+        assert_eq!(gpu_points.vertex_data[1].position, [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(gpu_points.vertex_data[1].color[..3], POINT_COLOR);
+    }
+
+    #[test]
+    fn test_model_uniforms_new_sets_flat_shading_flag() {
+        let shading = MeshShading {
+            flat: true,
+            matcap: Matcap::Zebra,
+        };
+
+        let flat_uniforms =
+            ModelUniforms::new(&Matrix4::identity(), None, shading, Material::default());
+        let smooth_uniforms = ModelUniforms::new(
+            &Matrix4::identity(),
+            None,
+            MeshShading::default(),
+            Material::default(),
+        );
+
+        assert_eq!(flat_uniforms.shading_flags, ModelShadingFlags::FLAT);
+        assert_eq!(smooth_uniforms.shading_flags, ModelShadingFlags::empty());
+    }
+
+    #[test]
+    fn test_model_uniforms_new_packs_material() {
+        let material = Material {
+            base_color: [0.2, 0.4, 0.6],
+            roughness: 0.5,
+            opacity: 0.25,
+        };
+
+        let uniforms =
+            ModelUniforms::new(&Matrix4::identity(), None, MeshShading::default(), material);
+
+        assert_eq!(uniforms.base_color_and_opacity, [0.2, 0.4, 0.6, 0.25]);
+        assert_eq!(uniforms.roughness, 0.5);
+        assert!(!material.is_opaque());
+    }
+
+    #[test]
+    fn test_matcap_index_matches_order_of_all_matcaps() {
+        for (index, matcap) in Matcap::ALL.iter().enumerate() {
+            assert_eq!(matcap.index(), index);
+        }
+    }
+
+    #[test]
+    fn test_section_plane_equation_has_positive_front() {
+        let section = Section {
+            origin: Point3::new(0.0, 0.0, 2.0),
+            normal: Vector3::new(0.0, 0.0, 3.0),
+            fill: SectionFill::Solid,
+            color: [1.0, 0.0, 0.0],
+            hatch_spacing: 0.1,
+        };
+
+        let [a, b, c, d] = section.plane_equation();
+        let signed_distance = |point: Point3<f32>| a * point.x + b * point.y + c * point.z + d;
+
+        assert!(signed_distance(Point3::new(5.0, 5.0, 3.0)) > 0.0);
+        assert!(signed_distance(Point3::new(5.0, 5.0, 1.0)) < 0.0);
+        assert!((signed_distance(Point3::new(1.0, -1.0, 2.0))).abs() < f32::EPSILON);
+    }
 }
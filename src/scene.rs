@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::mesh::Mesh;
+use crate::renderer::{GpuMesh, GpuMeshId, Renderer};
+
+/// The changes turning one set of displayed meshes into another.
+///
+/// Meshes are compared by identity, not by value, as values produced
+/// by the interpreter are shared and never mutated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneDiff<K> {
+    /// Keys not displayed yet.
+    pub added: Vec<K>,
+    /// Keys no longer displayed.
+    pub removed: Vec<K>,
+    /// Keys displayed with a different mesh.
+    pub updated: Vec<K>,
+}
+
+impl<K> SceneDiff<K> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Computes the changes needed to display the `desired` meshes instead
+/// of the `displayed` ones.
+pub fn diff<K: Copy + Eq + Hash>(
+    displayed: &HashMap<K, Arc<Mesh>>,
+    desired: &HashMap<K, Arc<Mesh>>,
+) -> SceneDiff<K> {
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for (key, mesh) in desired {
+        match displayed.get(key) {
+            Some(displayed_mesh) => {
+                if !Arc::ptr_eq(displayed_mesh, mesh) {
+                    updated.push(*key);
+                }
+            }
+            None => added.push(*key),
+        }
+    }
+
+    let removed = displayed
+        .keys()
+        .filter(|key| !desired.contains_key(key))
+        .copied()
+        .collect();
+
+    SceneDiff {
+        added,
+        removed,
+        updated,
+    }
+}
+
+/// A set of meshes displayed by the renderer, each identified by a
+/// key.
+///
+/// Instead of uploading and removing GPU meshes by hand, the app
+/// describes which meshes it wants displayed each frame, and the scene
+/// issues only the renderer calls needed to get there.
+#[derive(Debug)]
+pub struct Scene<K> {
+    meshes: HashMap<K, Arc<Mesh>>,
+    gpu_mesh_ids: HashMap<K, GpuMeshId>,
+}
+
+impl<K> Default for Scene<K> {
+    fn default() -> Self {
+        Self {
+            meshes: HashMap::new(),
+            gpu_mesh_ids: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> Scene<K> {
+    /// The GPU meshes of the currently displayed meshes.
+    pub fn gpu_mesh_ids(&self) -> &HashMap<K, GpuMeshId> {
+        &self.gpu_mesh_ids
+    }
+
+    /// Makes the scene display the `desired` meshes, uploading only
+    /// the meshes that changed since the last update. Returns whether
+    /// anything changed.
+    ///
+    /// Meshes re-uploaded because they changed get new GPU mesh IDs,
+    /// with the default transform and no tint.
+    pub fn update(&mut self, renderer: &mut Renderer, desired: &HashMap<K, Arc<Mesh>>) -> bool {
+        let scene_diff = diff(&self.meshes, desired);
+
+        for key in scene_diff.removed.iter().chain(&scene_diff.updated) {
+            self.meshes.remove(key);
+            let gpu_mesh_id = self
+                .gpu_mesh_ids
+                .remove(key)
+                .expect("Gpu mesh ID was not tracked");
+
+            renderer.remove_scene_mesh(gpu_mesh_id);
+        }
+
+        for key in scene_diff.added.iter().chain(&scene_diff.updated) {
+            let mesh = &desired[key];
+            let gpu_mesh_id = renderer
+                .add_scene_mesh(&GpuMesh::from_mesh(mesh))
+                .expect("Failed to upload scene mesh");
+
+            self.meshes.insert(*key, Arc::clone(mesh));
+            self.gpu_mesh_ids.insert(*key, gpu_mesh_id);
+        }
+
+        !scene_diff.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Point3, Rotation3, Vector3};
+
+    use crate::mesh::primitive;
+
+    use super::*;
+
+    fn cube() -> Arc<Mesh> {
+        Arc::new(primitive::create_box(
+            Point3::origin(),
+            Rotation3::identity(),
+            Vector3::repeat(1.0),
+        ))
+    }
+
+    #[test]
+    fn test_scene_diff_finds_added_removed_and_updated_meshes() {
+        let kept = cube();
+        let displayed: HashMap<u32, Arc<Mesh>> =
+            vec![(0, Arc::clone(&kept)), (1, cube()), (2, cube())]
+                .into_iter()
+                .collect();
+        let desired: HashMap<u32, Arc<Mesh>> = vec![(0, kept), (1, cube()), (3, cube())]
+            .into_iter()
+            .collect();
+
+        let scene_diff = diff(&displayed, &desired);
+
+        assert_eq!(scene_diff.added, vec![3]);
+        assert_eq!(scene_diff.removed, vec![2]);
+        assert_eq!(scene_diff.updated, vec![1]);
+    }
+
+    #[test]
+    fn test_scene_diff_is_empty_for_same_meshes() {
+        let meshes: HashMap<u32, Arc<Mesh>> = vec![(0, cube()), (1, cube())].into_iter().collect();
+
+        assert!(diff(&meshes, &meshes.clone()).is_empty());
+    }
+}
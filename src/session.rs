@@ -1,12 +1,19 @@
 use std::collections::hash_map::{Entry, HashMap};
 use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 
-use crate::interpreter::ast::{FuncIdent, Prog, Stmt, VarIdent};
-use crate::interpreter::{Func, LogMessage, Ty, Value};
+use crate::constants::{self, Constant, ConstantBinding};
+use crate::interpreter::ast::{Expr, FuncIdent, Prog, Stmt, VarIdent};
+use crate::interpreter::{Func, InterpretProgress, LogMessage, Ty, Value};
 use crate::interpreter_funcs;
 use crate::interpreter_server::{
     InterpreterRequest, InterpreterResponse, InterpreterServer, PollResponseError, RequestId,
 };
+use crate::mesh::Mesh;
+use crate::optimizer::{OptimizationRequest, ParetoFront, ParetoRequest, Plot, PlotRequest};
+use crate::project::{Project, ProjectVariant, PROJECT_VERSION};
+use crate::recorder::Macro;
+use crate::units::LengthUnit;
 
 /// A notification from the session to the surrounding environment
 /// about what values have been added since the last poll, and what
@@ -16,6 +23,31 @@ pub enum PollInterpreterResponseNotification {
     Remove(VarIdent, Value),
 }
 
+/// A named snapshot of the whole pipeline program, including all
+/// parameter values.
+#[derive(Debug, Clone)]
+pub struct Variant {
+    name: String,
+    prog: Prog,
+    results: Vec<Arc<Mesh>>,
+}
+
+impl Variant {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn prog(&self) -> &Prog {
+        &self.prog
+    }
+
+    /// The meshes evaluated for the variant by a sweep worker. Empty
+    /// for variants evaluated locally.
+    pub fn results(&self) -> &[Arc<Mesh>] {
+        &self.results
+    }
+}
+
 /// An editing session.
 ///
 /// Contains the current definition of the pipeline program and can
@@ -28,39 +60,87 @@ pub enum PollInterpreterResponseNotification {
 pub struct Session {
     interpreter_server: InterpreterServer,
     interpreter_interpret_request_in_flight: Option<RequestId>,
+    interpreter_optimize_request_in_flight: Option<RequestId>,
     interpreter_edit_prog_requests_in_flight: HashSet<RequestId>,
+    interpret_progress: Option<InterpretProgress>,
 
     prog: Prog,
     log_messages: Vec<Vec<LogMessage>>,
+    variants: Vec<Variant>,
+    pareto_front: Option<ParetoFront>,
+    plot: Option<Plot>,
+    macros: Vec<Macro>,
+    constants: Vec<Constant>,
+    constant_bindings: Vec<ConstantBinding>,
+    unit: LengthUnit,
+
+    // The program length when the macro recording started, if a macro
+    // is being recorded. Everything pushed after it gets recorded.
+    macro_recording_start: Option<usize>,
 
     unused_values: HashMap<VarIdent, Value>,
 
-    // Auxiliary side-arrays for prog. Determine mesh and mesh-array
-    // vars visible from a stmt. The value is read by producing a
+    // The values used by other operations in the last successful
+    // interpretation, i.e. the results of the earlier stages of the
+    // pipeline.
+    used_values: Vec<(VarIdent, Value)>,
+
+    // Variables, which are only re-computed when the user explicitly
+    // asks for it.
+    manual_update_vars: HashSet<VarIdent>,
+
+    // Whether to start the interpreter after each program edit.
+    auto_interpret: bool,
+
+    // Auxiliary side-arrays for prog. Determine mesh, mesh-array,
+    // locator, curve and points vars visible from a stmt. The value is read by producing a
     // slice from the begining of the array to the current stmt's
     // index (exclusive), and filtering only `Some` values. E.g. 0th
     // stmt can not see any vars, 1st stmt can see vars produced by
     // the 0th stmt (if it is `Some`), etc.
     var_visibility_mesh: Vec<Option<VarIdent>>,
     var_visibility_mesh_array: Vec<Option<VarIdent>>,
+    var_visibility_locator: Vec<Option<VarIdent>>,
+    var_visibility_curve: Vec<Option<VarIdent>>,
+    var_visibility_points: Vec<Option<VarIdent>>,
+    var_visibility_uint: Vec<Option<VarIdent>>,
 
     function_table: BTreeMap<FuncIdent, Box<dyn Func>>,
 }
 
 impl Session {
-    pub fn new() -> Self {
+    pub fn new(spill_memory_budget_bytes: Option<usize>) -> Self {
         Self {
-            interpreter_server: InterpreterServer::new(),
+            interpreter_server: InterpreterServer::new(spill_memory_budget_bytes),
             interpreter_interpret_request_in_flight: None,
+            interpreter_optimize_request_in_flight: None,
             interpreter_edit_prog_requests_in_flight: HashSet::new(),
+            interpret_progress: None,
 
             prog: Prog::new(Vec::new()),
             log_messages: Vec::new(),
+            variants: Vec::new(),
+            pareto_front: None,
+            plot: None,
+            macros: Vec::new(),
+            constants: Vec::new(),
+            constant_bindings: Vec::new(),
+            unit: LengthUnit::default(),
+
+            macro_recording_start: None,
 
             unused_values: HashMap::new(),
+            used_values: Vec::new(),
+
+            manual_update_vars: HashSet::new(),
+            auto_interpret: false,
 
             var_visibility_mesh: Vec::new(),
             var_visibility_mesh_array: Vec::new(),
+            var_visibility_locator: Vec::new(),
+            var_visibility_curve: Vec::new(),
+            var_visibility_points: Vec::new(),
+            var_visibility_uint: Vec::new(),
 
             // FIXME: @Correctness this is a hack that is currently
             // harmless, but should eventually be cleaned up. Some
@@ -94,21 +174,9 @@ impl Session {
             "Can't submit a request while the interpreter is already interpreting",
         );
 
-        self.prog.push_stmt(stmt.clone());
-        self.log_messages.push(Vec::new());
-
-        let request_id = self
-            .interpreter_server
-            .submit_request(InterpreterRequest::PushProgStmt(stmt));
-        let tracked = self
-            .interpreter_edit_prog_requests_in_flight
-            .insert(request_id);
-        assert!(
-            tracked,
-            "Interpreter server must provide unique request ids"
-        );
-
+        self.push_prog_stmt_without_interpreting(stmt);
         self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
     }
 
     /// Pops a statement from the program.
@@ -123,21 +191,25 @@ impl Session {
             "Can't submit a request while the interpreter is already interpreting",
         );
 
+        if let Some(Stmt::VarDecl(var_decl)) = self.prog.stmts().last() {
+            self.manual_update_vars.remove(&var_decl.ident());
+        }
+
         self.prog.pop_stmt();
+        let stmt_count = self.prog.stmts().len();
+        self.constant_bindings
+            .retain(|binding| binding.stmt_index < stmt_count);
         self.log_messages.pop();
 
-        let request_id = self
-            .interpreter_server
-            .submit_request(InterpreterRequest::PopProgStmt);
-        let tracked = self
-            .interpreter_edit_prog_requests_in_flight
-            .insert(request_id);
-        assert!(
-            tracked,
-            "Interpreter server must provide unique request ids"
-        );
+        // Popping past the start of the recording removes the popped
+        // operation from the recording too
+        if let Some(start) = &mut self.macro_recording_start {
+            *start = (*start).min(self.prog.stmts().len());
+        }
 
+        self.submit_edit_prog_request(InterpreterRequest::PopProgStmt);
         self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
     }
 
     /// Edits a program statement at the index.
@@ -160,24 +232,466 @@ impl Session {
             (Stmt::VarDecl(current_var_decl), Stmt::VarDecl(new_var_decl)) => {
                 if current_var_decl.init_expr().ident() != new_var_decl.init_expr().ident() {
                     self.log_messages[index].clear();
+                    self.constant_bindings
+                        .retain(|binding| binding.stmt_index != index);
                 }
             }
         }
 
         self.prog.set_stmt_at(index, stmt.clone());
 
-        let request_id = self
-            .interpreter_server
-            .submit_request(InterpreterRequest::SetProgStmtAt(index, stmt));
-        let tracked = self
-            .interpreter_edit_prog_requests_in_flight
-            .insert(request_id);
+        self.submit_edit_prog_request(InterpreterRequest::SetProgStmtAt(index, stmt));
+        self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
+    }
+
+    /// Replaces the whole program.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn set_prog(&mut self, prog: Prog) {
         assert!(
-            tracked,
-            "Interpreter server must provide unique request ids"
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
         );
 
+        if self.macro_recording_start.take().is_some() {
+            log::warn!("Replacing the program cancelled the macro recording");
+        }
+
+        self.log_messages.clear();
+        self.log_messages.resize_with(prog.stmts().len(), Vec::new);
+        self.manual_update_vars.clear();
+
+        // Bindings survive as long as the bound params still exist,
+        // but the new program keeps its own param values
+        let function_table = &self.function_table;
+        self.constant_bindings
+            .retain(|binding| constants::binding_fits_stmts(binding, prog.stmts(), function_table));
+        self.prog = prog.clone();
+
+        self.submit_edit_prog_request(InterpreterRequest::SetProg(prog));
         self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
+    }
+
+    /// Returns whether a macro is being recorded.
+    pub fn recording_macro(&self) -> bool {
+        self.macro_recording_start.is_some()
+    }
+
+    /// Starts recording the operations added to the program and their
+    /// parameters into a macro.
+    pub fn start_macro_recording(&mut self) {
+        self.macro_recording_start = Some(self.prog.stmts().len());
+    }
+
+    /// Stops the recording and saves the operations added since it
+    /// started as a named macro, replacing the macro with the same
+    /// name, if it exists. Returns whether a macro was saved, which
+    /// doesn't happen if no operations were recorded.
+    ///
+    /// # Panics
+    /// Panics if no macro is being recorded.
+    pub fn stop_macro_recording(&mut self, name: String) -> bool {
+        let start = self
+            .macro_recording_start
+            .take()
+            .expect("A macro must be recorded to stop the recording");
+        if start == self.prog.stmts().len() {
+            return false;
+        }
+
+        let recorded_macro = Macro::from_prog(name, &self.prog, start, &self.function_table);
+        self.macros
+            .retain(|existing_macro| existing_macro.name != recorded_macro.name);
+        self.macros.push(recorded_macro);
+
+        true
+    }
+
+    /// Returns the recorded macros.
+    pub fn macros(&self) -> &[Macro] {
+        &self.macros
+    }
+
+    /// Appends the operations of the macro at the index to the
+    /// program. The macro inputs are replaced by the `input_args`.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy, the index is out of bounds
+    /// or the number of `input_args` doesn't match the macro inputs.
+    pub fn replay_macro(&mut self, index: usize, input_args: &[Expr]) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        let stmts = self.macros[index].instantiate(self.next_free_var_ident(), input_args);
+        for stmt in stmts {
+            self.push_prog_stmt_without_interpreting(stmt);
+        }
+
+        self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
+    }
+
+    /// Removes the macro at the index.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn remove_macro(&mut self, index: usize) {
+        self.macros.remove(index);
+    }
+
+    /// Returns the project constants.
+    pub fn constants(&self) -> &[Constant] {
+        &self.constants
+    }
+
+    /// Sets the value of the named constant, adding the constant if it
+    /// doesn't exist yet. All params bound to the constant are updated.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn set_constant(&mut self, name: String, value: f32) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        match self
+            .constants
+            .iter_mut()
+            .find(|constant| constant.name == name)
+        {
+            Some(constant) => constant.value = value,
+            None => self.constants.push(Constant {
+                name: name.clone(),
+                value,
+            }),
+        }
+
+        let bound_args: Vec<(usize, usize)> = self
+            .constant_bindings
+            .iter()
+            .filter(|binding| binding.constant_name == name)
+            .map(|binding| (binding.stmt_index, binding.arg_index))
+            .collect();
+        if bound_args.is_empty() {
+            return;
+        }
+
+        for (stmt_index, arg_index) in bound_args {
+            let stmt = constants::bind_stmt_arg(
+                &self.prog.stmts()[stmt_index],
+                arg_index,
+                value,
+                &self.function_table,
+            );
+            self.prog.set_stmt_at(stmt_index, stmt.clone());
+            self.submit_edit_prog_request(InterpreterRequest::SetProgStmtAt(stmt_index, stmt));
+        }
+
+        self.recompute_var_visibility();
+        self.auto_interpret_if_enabled();
+    }
+
+    /// Removes the constant at the index. The params bound to it keep
+    /// their current values.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn remove_constant(&mut self, index: usize) {
+        let constant = self.constants.remove(index);
+        self.constant_bindings
+            .retain(|binding| binding.constant_name != constant.name);
+    }
+
+    /// Returns the name of the constant the argument at `arg_index` of
+    /// the statement at `stmt_index` is bound to, if any.
+    pub fn param_constant(&self, stmt_index: usize, arg_index: usize) -> Option<&str> {
+        self.constant_bindings
+            .iter()
+            .find(|binding| binding.stmt_index == stmt_index && binding.arg_index == arg_index)
+            .map(|binding| binding.constant_name.as_str())
+    }
+
+    /// Binds the argument at `arg_index` of the statement at
+    /// `stmt_index` to the constant at `constant_index` and sets the
+    /// argument to the constant's value.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy, the indices are out of
+    /// bounds or the argument is not a numeric literal.
+    pub fn bind_param_to_constant(
+        &mut self,
+        stmt_index: usize,
+        arg_index: usize,
+        constant_index: usize,
+    ) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        let constant = &self.constants[constant_index];
+        let binding = ConstantBinding {
+            stmt_index,
+            arg_index,
+            constant_name: constant.name.clone(),
+        };
+        assert!(
+            constants::binding_fits_stmts(&binding, self.prog.stmts(), &self.function_table),
+            "Only literal numeric params can be bound to constants",
+        );
+
+        let stmt = constants::bind_stmt_arg(
+            &self.prog.stmts()[stmt_index],
+            arg_index,
+            constant.value,
+            &self.function_table,
+        );
+
+        self.unbind_param(stmt_index, arg_index);
+        self.constant_bindings.push(binding);
+        self.set_prog_stmt_at(stmt_index, stmt);
+    }
+
+    /// Unbinds the argument at `arg_index` of the statement at
+    /// `stmt_index` from its constant. The argument keeps its current
+    /// value.
+    pub fn unbind_param(&mut self, stmt_index: usize, arg_index: usize) {
+        self.constant_bindings
+            .retain(|binding| binding.stmt_index != stmt_index || binding.arg_index != arg_index);
+    }
+
+    /// Returns the unit lengths are displayed and entered in.
+    pub fn unit(&self) -> LengthUnit {
+        self.unit
+    }
+
+    /// Sets the unit lengths are displayed and entered in. The program
+    /// keeps computing in meters, so nothing is re-computed.
+    pub fn set_unit(&mut self, unit: LengthUnit) {
+        self.unit = unit;
+    }
+
+    /// Returns the saved program variants.
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// Saves the current program as a named variant. Replaces the
+    /// variant with the same name, if it exists.
+    pub fn save_variant(&mut self, name: String) {
+        let prog = self.prog.clone();
+        match self
+            .variants
+            .iter_mut()
+            .find(|variant| variant.name == name)
+        {
+            Some(variant) => {
+                variant.prog = prog;
+                variant.results.clear();
+            }
+            None => self.variants.push(Variant {
+                name,
+                prog,
+                results: Vec::new(),
+            }),
+        }
+    }
+
+    /// Adds a program evaluated elsewhere, e.g. by a sweep worker, as a
+    /// named variant along with its resulting meshes. Replaces the
+    /// variant with the same name, if it exists.
+    pub fn add_variant_with_results(&mut self, name: String, prog: Prog, results: Vec<Arc<Mesh>>) {
+        self.variants.retain(|variant| variant.name != name);
+        self.variants.push(Variant {
+            name,
+            prog,
+            results,
+        });
+    }
+
+    /// Replaces the current program with the saved variant at the
+    /// index.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy or the index is out of
+    /// bounds.
+    pub fn recall_variant(&mut self, index: usize) {
+        let prog = self.variants[index].prog.clone();
+        self.set_prog(prog);
+    }
+
+    /// Returns the Pareto front of the last completed multi-objective
+    /// study, if any.
+    pub fn pareto_front(&self) -> Option<&ParetoFront> {
+        self.pareto_front.as_ref()
+    }
+
+    /// Returns the last completed plot recording, if any.
+    pub fn plot(&self) -> Option<&Plot> {
+        self.plot.as_ref()
+    }
+
+    /// Replaces the current program with the program of the point on
+    /// the Pareto front at the index.
+    ///
+    /// # Panics
+    /// Panics if there is no Pareto front, the index is out of bounds
+    /// or if the interpreter is busy.
+    pub fn recall_pareto_point(&mut self, index: usize) {
+        let prog = self
+            .pareto_front
+            .as_ref()
+            .expect("There must be a Pareto front to recall from")
+            .points[index]
+            .prog
+            .clone();
+        self.set_prog(prog);
+    }
+
+    /// Captures the current program, saved variants and pipeline
+    /// settings as a project, which can be saved to a file.
+    pub fn project(&self) -> Project {
+        let mut manual_update_vars: Vec<VarIdent> =
+            self.manual_update_vars.iter().copied().collect();
+        manual_update_vars.sort_by_key(|var_ident| var_ident.0);
+
+        Project {
+            version: PROJECT_VERSION,
+            prog: self.prog.clone(),
+            variants: self
+                .variants
+                .iter()
+                .map(|variant| ProjectVariant {
+                    name: variant.name.clone(),
+                    prog: variant.prog.clone(),
+                })
+                .collect(),
+            manual_update_vars,
+            auto_interpret: self.auto_interpret,
+            macros: self.macros.clone(),
+            constants: self.constants.clone(),
+            constant_bindings: self.constant_bindings.clone(),
+            unit: self.unit,
+        }
+    }
+
+    /// Replaces the current program, saved variants and pipeline
+    /// settings with the project and rebuilds the interpreter state
+    /// from it.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn open_project(&mut self, project: Project) {
+        // Don't start interpreting before the manual update vars are
+        // set, otherwise their values would be computed right away
+        self.auto_interpret = false;
+        self.constant_bindings = project.constant_bindings;
+        self.set_prog(project.prog);
+
+        self.variants = project
+            .variants
+            .into_iter()
+            .map(|variant| Variant {
+                name: variant.name,
+                prog: variant.prog,
+                results: Vec::new(),
+            })
+            .collect();
+        self.pareto_front = None;
+        self.plot = None;
+        self.macros = project.macros;
+        self.constants = project.constants;
+        self.unit = project.unit;
+
+        let stmt_count = self.prog.stmts().len() as u64;
+        for var_ident in project.manual_update_vars {
+            if var_ident.0 < stmt_count {
+                self.set_var_manual_update(var_ident, true);
+            }
+        }
+
+        self.set_auto_interpret(project.auto_interpret);
+    }
+
+    /// Removes the saved variant at the index.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn remove_variant(&mut self, index: usize) {
+        self.variants.remove(index);
+    }
+
+    /// Returns whether the variable is only re-computed when
+    /// explicitly requested via `update_var`.
+    pub fn var_manual_update(&self, var_ident: VarIdent) -> bool {
+        self.manual_update_vars.contains(&var_ident)
+    }
+
+    /// Sets whether the variable is only re-computed when explicitly
+    /// requested via `update_var`. Until then, the interpreter holds
+    /// its last value, even if the variable's definition or its
+    /// dependencies change.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn set_var_manual_update(&mut self, var_ident: VarIdent, manual_update: bool) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        if manual_update {
+            self.manual_update_vars.insert(var_ident);
+        } else {
+            self.manual_update_vars.remove(&var_ident);
+        }
+
+        self.submit_edit_prog_request(InterpreterRequest::SetVarManualUpdate(
+            var_ident,
+            manual_update,
+        ));
+
+        // Turning the manual update off releases the held value
+        if !manual_update {
+            self.auto_interpret_if_enabled();
+        }
+    }
+
+    /// Discards the held value of a manually updated variable and
+    /// starts the interpreter to re-compute it.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn update_var(&mut self, var_ident: VarIdent) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        self.submit_edit_prog_request(InterpreterRequest::InvalidateVar(var_ident));
+        self.interpret();
+    }
+
+    /// Returns whether the interpreter is started after each program
+    /// edit.
+    pub fn auto_interpret(&self) -> bool {
+        self.auto_interpret
+    }
+
+    /// Sets whether the interpreter is started after each program
+    /// edit. Turning the automatic interpretation off pauses the whole
+    /// pipeline until the interpreter is started manually.
+    pub fn set_auto_interpret(&mut self, auto_interpret: bool) {
+        self.auto_interpret = auto_interpret;
+        if auto_interpret && !self.interpreter_busy() {
+            self.interpret();
+        }
     }
 
     /// Returns the statements currently contained in the current pipeline's
@@ -186,6 +700,11 @@ impl Session {
         self.prog.stmts()
     }
 
+    /// Returns the current pipeline's program.
+    pub fn prog(&self) -> &Prog {
+        &self.prog
+    }
+
     /// Returns the definitions of all known functions.
     pub fn function_table(&self) -> &BTreeMap<FuncIdent, Box<dyn Func>> {
         &self.function_table
@@ -197,6 +716,13 @@ impl Session {
         VarIdent(self.prog.stmts().len() as u64)
     }
 
+    /// Returns the values used by other operations, i.e. the results
+    /// of the earlier stages of the pipeline, as of the last
+    /// successful interpretation.
+    pub fn used_values(&self) -> &[(VarIdent, Value)] {
+        &self.used_values
+    }
+
     /// Returns human readable variable name for a variable identifier
     /// or `None` if the variable identifier does not exist in the
     /// current program.
@@ -231,6 +757,10 @@ impl Session {
         let var_visibility = match ty {
             Ty::Mesh => &self.var_visibility_mesh,
             Ty::MeshArray => &self.var_visibility_mesh_array,
+            Ty::Locator => &self.var_visibility_locator,
+            Ty::Curve => &self.var_visibility_curve,
+            Ty::Points => &self.var_visibility_points,
+            Ty::Uint => &self.var_visibility_uint,
             _ => &EMPTY,
         };
 
@@ -249,6 +779,88 @@ impl Session {
     /// disallowed in this state.
     pub fn interpreter_busy(&self) -> bool {
         self.interpreter_interpret_request_in_flight.is_some()
+            || self.interpreter_optimize_request_in_flight.is_some()
+    }
+
+    /// Returns the statement the interpreter is currently computing,
+    /// if it is busy and already started computing any.
+    pub fn interpret_progress(&self) -> Option<InterpretProgress> {
+        self.interpret_progress
+    }
+
+    /// Asks the interpreter to stop the running interpretation or
+    /// optimization. Does nothing, if the interpreter isn't busy.
+    ///
+    /// The interpretation completes with an error once the interpreter
+    /// stops.
+    pub fn cancel_interpretation(&mut self) {
+        if self.interpreter_busy() {
+            self.interpreter_server.cancel();
+        }
+    }
+
+    /// Returns whether the interpreter is currently optimizing
+    /// program parameters.
+    pub fn optimizing(&self) -> bool {
+        self.interpreter_optimize_request_in_flight.is_some()
+    }
+
+    /// Starts optimizing the parameters of the current program. The
+    /// best found parameter values are saved as variants once the
+    /// optimization completes.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn optimize(&mut self, request: OptimizationRequest) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        let request_id = self
+            .interpreter_server
+            .submit_request(InterpreterRequest::Optimize(request));
+        self.interpreter_optimize_request_in_flight
+            .replace(request_id);
+    }
+
+    /// Starts exploring the trade-offs between multiple objectives by
+    /// varying the parameters of the current program. The Pareto
+    /// front is available via `pareto_front` once the exploration
+    /// completes.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn explore_pareto_front(&mut self, request: ParetoRequest) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        let request_id = self
+            .interpreter_server
+            .submit_request(InterpreterRequest::ExploreParetoFront(request));
+        self.interpreter_optimize_request_in_flight
+            .replace(request_id);
+    }
+
+    /// Starts recording a metric of a variable while sweeping a
+    /// parameter of the current program. The plot is available via
+    /// `plot` once the recording completes.
+    ///
+    /// # Panics
+    /// Panics if the interpreter is busy.
+    pub fn record_plot(&mut self, request: PlotRequest) {
+        assert!(
+            !self.interpreter_busy(),
+            "Can't submit a request while the interpreter is already interpreting",
+        );
+
+        let request_id = self
+            .interpreter_server
+            .submit_request(InterpreterRequest::RecordPlot(request));
+        self.interpreter_optimize_request_in_flight
+            .replace(request_id);
     }
 
     /// Starts the interpreter on the current program.
@@ -267,6 +879,30 @@ impl Session {
             .replace(request_id);
     }
 
+    fn push_prog_stmt_without_interpreting(&mut self, stmt: Stmt) {
+        self.prog.push_stmt(stmt.clone());
+        self.log_messages.push(Vec::new());
+
+        self.submit_edit_prog_request(InterpreterRequest::PushProgStmt(stmt));
+    }
+
+    fn submit_edit_prog_request(&mut self, request: InterpreterRequest) {
+        let request_id = self.interpreter_server.submit_request(request);
+        let tracked = self
+            .interpreter_edit_prog_requests_in_flight
+            .insert(request_id);
+        assert!(
+            tracked,
+            "Interpreter server must provide unique request ids"
+        );
+    }
+
+    fn auto_interpret_if_enabled(&mut self) {
+        if self.auto_interpret {
+            self.interpret();
+        }
+    }
+
     /// Poll the interpreter for responses and call the callback for
     /// each notification generated this way.
     ///
@@ -290,6 +926,9 @@ impl Session {
             match self.interpreter_server.poll_response() {
                 Ok((request_id, response)) => {
                     match response {
+                        InterpreterResponse::Progress(progress) => {
+                            self.interpret_progress = Some(progress);
+                        }
                         InterpreterResponse::CompletedEditProg => {
                             let tracked = self
                                 .interpreter_edit_prog_requests_in_flight
@@ -304,6 +943,7 @@ impl Session {
                                 .take()
                                 .is_some();
                             assert!(tracked, "The interpret request must have been tracked");
+                            self.interpret_progress = None;
 
                             log::info!("Interpreter completed interpret request {}", request_id);
 
@@ -381,6 +1021,8 @@ impl Session {
                                         ))
                                     }
 
+                                    self.used_values = interpret_value.used_values;
+
                                     for (var_ident, value) in interpret_value.unused_values {
                                         // Only add and emit events for values that we
                                         // didn't have before. We handled re-insertions
@@ -415,6 +1057,47 @@ impl Session {
                                 self.log_messages[i].extend(log_messages_at_stmt);
                             }
                         }
+                        InterpreterResponse::CompletedOptimize(best) => {
+                            self.untrack_optimize_request();
+
+                            log::info!(
+                                "Interpreter completed optimize request {} with {} results",
+                                request_id,
+                                best.len(),
+                            );
+
+                            for (index, (prog, score)) in best.into_iter().enumerate() {
+                                let name = format!("Optimized {} ({:.4})", index + 1, score);
+                                self.variants.retain(|variant| variant.name != name);
+                                self.variants.push(Variant {
+                                    name,
+                                    prog,
+                                    results: Vec::new(),
+                                });
+                            }
+                        }
+                        InterpreterResponse::CompletedExploreParetoFront(front) => {
+                            self.untrack_optimize_request();
+
+                            log::info!(
+                                "Interpreter completed Pareto front exploration request {} with {} points",
+                                request_id,
+                                front.points.len(),
+                            );
+
+                            self.pareto_front = Some(front);
+                        }
+                        InterpreterResponse::CompletedRecordPlot(plot) => {
+                            self.untrack_optimize_request();
+
+                            log::info!(
+                                "Interpreter completed plot recording request {} with {} points",
+                                request_id,
+                                plot.points.len(),
+                            );
+
+                            self.plot = Some(plot);
+                        }
                     }
 
                     self.recompute_var_visibility();
@@ -427,14 +1110,33 @@ impl Session {
         }
     }
 
+    /// Forgets the completed optimize, Pareto front exploration or
+    /// plot recording request.
+    ///
+    /// # Panics
+    /// Panics if no such request was in flight.
+    fn untrack_optimize_request(&mut self) {
+        let tracked = self.interpreter_optimize_request_in_flight.take().is_some();
+        assert!(tracked, "The optimize request must have been tracked");
+        self.interpret_progress = None;
+    }
+
     fn recompute_var_visibility(&mut self) {
         // FIXME: Get variable visibility analysis from interpreter
 
         self.var_visibility_mesh.clear();
         self.var_visibility_mesh_array.clear();
+        self.var_visibility_locator.clear();
+        self.var_visibility_curve.clear();
+        self.var_visibility_points.clear();
+        self.var_visibility_uint.clear();
 
         let mut n_mesh = 0;
         let mut n_mesh_array = 0;
+        let mut n_locator = 0;
+        let mut n_curve = 0;
+        let mut n_points = 0;
+        let mut n_uint = 0;
 
         for stmt in self.prog.stmts() {
             let Stmt::VarDecl(var_decl) = stmt;
@@ -444,21 +1146,69 @@ impl Session {
                 Ty::Mesh => {
                     self.var_visibility_mesh.push(Some(var_decl.ident()));
                     self.var_visibility_mesh_array.push(None);
+                    self.var_visibility_locator.push(None);
+                    self.var_visibility_curve.push(None);
+                    self.var_visibility_points.push(None);
+                    self.var_visibility_uint.push(None);
 
                     n_mesh += 1;
                 }
                 Ty::MeshArray => {
                     self.var_visibility_mesh.push(None);
                     self.var_visibility_mesh_array.push(Some(var_decl.ident()));
+                    self.var_visibility_locator.push(None);
+                    self.var_visibility_curve.push(None);
+                    self.var_visibility_points.push(None);
+                    self.var_visibility_uint.push(None);
 
                     n_mesh_array += 1;
                 }
+                Ty::Locator => {
+                    self.var_visibility_mesh.push(None);
+                    self.var_visibility_mesh_array.push(None);
+                    self.var_visibility_locator.push(Some(var_decl.ident()));
+                    self.var_visibility_curve.push(None);
+                    self.var_visibility_points.push(None);
+                    self.var_visibility_uint.push(None);
+
+                    n_locator += 1;
+                }
+                Ty::Curve => {
+                    self.var_visibility_mesh.push(None);
+                    self.var_visibility_mesh_array.push(None);
+                    self.var_visibility_locator.push(None);
+                    self.var_visibility_curve.push(Some(var_decl.ident()));
+                    self.var_visibility_points.push(None);
+                    self.var_visibility_uint.push(None);
+
+                    n_curve += 1;
+                }
+                Ty::Points => {
+                    self.var_visibility_mesh.push(None);
+                    self.var_visibility_mesh_array.push(None);
+                    self.var_visibility_locator.push(None);
+                    self.var_visibility_curve.push(None);
+                    self.var_visibility_points.push(Some(var_decl.ident()));
+                    self.var_visibility_uint.push(None);
+
+                    n_points += 1;
+                }
+                Ty::Uint => {
+                    self.var_visibility_mesh.push(None);
+                    self.var_visibility_mesh_array.push(None);
+                    self.var_visibility_locator.push(None);
+                    self.var_visibility_curve.push(None);
+                    self.var_visibility_points.push(None);
+                    self.var_visibility_uint.push(Some(var_decl.ident()));
+
+                    n_uint += 1;
+                }
                 _ => panic!("Unsupported variable type"),
             }
         }
 
         assert_eq!(
-            n_mesh + n_mesh_array,
+            n_mesh + n_mesh_array + n_locator + n_curve + n_points + n_uint,
             self.prog.stmts().len(),
             "Each stmt is a var decl and must produce a variable",
         );
@@ -0,0 +1,28 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform Axes {
+    mat4 u_axes_matrix;
+};
+
+layout(location = 0) out vec3 v_color;
+
+// Lines from the origin along the X, Y and Z axes
+const vec3 POSITIONS[6] = vec3[6](
+    vec3(0.0, 0.0, 0.0),
+    vec3(1.0, 0.0, 0.0),
+    vec3(0.0, 0.0, 0.0),
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, 0.0, 0.0),
+    vec3(0.0, 0.0, 1.0)
+);
+
+const vec3 COLORS[3] = vec3[3](
+    vec3(0.9, 0.25, 0.25),
+    vec3(0.35, 0.8, 0.25),
+    vec3(0.3, 0.5, 0.95)
+);
+
+void main() {
+    v_color = COLORS[gl_VertexIndex / 2];
+    gl_Position = u_axes_matrix * vec4(POSITIONS[gl_VertexIndex], 1.0);
+}
@@ -0,0 +1,43 @@
+#version 450
+
+const uint MODE_SOLID = 0;
+const uint MODE_GRADIENT = 1;
+const uint MODE_ENVIRONMENT = 2;
+
+const float PI = 3.14159265359;
+
+layout(set = 0, binding = 0, std140) uniform Background {
+    mat4 u_inverse_view_projection_matrix;
+    vec4 u_top_color;
+    vec4 u_bottom_color;
+    uint u_mode;
+};
+
+layout(set = 1, binding = 0) uniform texture2D t_environment;
+layout(set = 1, binding = 1) uniform sampler s_environment;
+
+layout(location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    if (u_mode == MODE_ENVIRONMENT) {
+        // Unproject the pixel to get the view direction in world-space
+        vec2 ndc = v_tex_coords * 2.0 - 1.0;
+        vec4 near = u_inverse_view_projection_matrix * vec4(ndc, 0.0, 1.0);
+        vec4 far = u_inverse_view_projection_matrix * vec4(ndc, 1.0, 1.0);
+        vec3 direction = normalize(far.xyz / far.w - near.xyz / near.w);
+
+        // Equirectangular mapping with Z up
+        vec2 environment_tex_coords = vec2(
+            atan(direction.y, direction.x) / (2.0 * PI) + 0.5,
+            acos(clamp(direction.z, -1.0, 1.0)) / PI);
+
+        vec3 color = texture(sampler2D(t_environment, s_environment), environment_tex_coords).rgb;
+        f_color = vec4(color, 1.0);
+    } else if (u_mode == MODE_GRADIENT) {
+        f_color = vec4(mix(u_top_color.rgb, u_bottom_color.rgb, v_tex_coords.y), 1.0);
+    } else {
+        f_color = vec4(u_top_color.rgb, 1.0);
+    }
+}
@@ -0,0 +1,16 @@
+#version 450
+
+// Copies the first sample of a multisampled depth texture to a
+// single-sampled color texture, so that it can be read by the post
+// processing shader regardless of multisampling.
+
+layout(set = 0, binding = 0) uniform texture2DMS t_depth;
+layout(set = 0, binding = 1) uniform sampler s_depth;
+
+layout(location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out float f_depth;
+
+void main() {
+    f_depth = texelFetch(sampler2DMS(t_depth, s_depth), ivec2(gl_FragCoord.xy), 0).r;
+}
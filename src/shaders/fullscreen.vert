@@ -0,0 +1,12 @@
+#version 450
+
+layout(location = 0) out vec2 v_tex_coords;
+
+void main() {
+    // A single triangle covering the whole screen. Texture
+    // coordinates have their origin in the top left corner.
+    vec2 position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    v_tex_coords = position;
+
+    gl_Position = vec4(position * 2.0 - 1.0, 1.0, 1.0);
+}
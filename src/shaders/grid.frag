@@ -0,0 +1,49 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform Grid {
+    mat4 u_view_projection_matrix;
+    vec4 u_center_and_extent;
+    float u_cell_size;
+};
+
+layout(location = 0) in vec2 v_position;
+
+layout(location = 0) out vec4 f_color;
+
+const vec3 LINE_COLOR = vec3(0.5, 0.5, 0.5);
+const vec3 X_AXIS_COLOR = vec3(0.9, 0.25, 0.25);
+const vec3 Y_AXIS_COLOR = vec3(0.35, 0.8, 0.25);
+const float MINOR_LINE_OPACITY = 0.15;
+const float MAJOR_LINE_OPACITY = 0.35;
+// Every tenth line is a major line
+const float MAJOR_LINE_EVERY = 10.0;
+
+// Returns how much the fragment is covered by a one pixel wide line
+// of the grid with unit spacing of the coords.
+float grid_line(vec2 coords) {
+    vec2 pixel_distance = abs(fract(coords - 0.5) - 0.5) / fwidth(coords);
+    return 1.0 - min(min(pixel_distance.x, pixel_distance.y), 1.0);
+}
+
+// Returns how much the fragment is covered by a line two pixels wide
+// where the coord is zero.
+float axis_line(float coord) {
+    return 1.0 - min(abs(coord) / (2.0 * fwidth(coord)), 1.0);
+}
+
+void main() {
+    vec2 coords = v_position / u_cell_size;
+    float minor = grid_line(coords) * MINOR_LINE_OPACITY;
+    float major = grid_line(coords / MAJOR_LINE_EVERY) * MAJOR_LINE_OPACITY;
+    vec4 color = vec4(LINE_COLOR, max(minor, major));
+
+    // The X axis lies along y = 0, the Y axis along x = 0
+    color = mix(color, vec4(X_AXIS_COLOR, 1.0), axis_line(v_position.y));
+    color = mix(color, vec4(Y_AXIS_COLOR, 1.0), axis_line(v_position.x));
+
+    // Fade the grid out towards its edges instead of ending abruptly
+    float distance = length(v_position - u_center_and_extent.xy) / u_center_and_extent.w;
+    float fade = 1.0 - smoothstep(0.5, 1.0, distance);
+
+    f_color = vec4(color.rgb, color.a * fade);
+}
@@ -0,0 +1,27 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform Grid {
+    mat4 u_view_projection_matrix;
+    vec4 u_center_and_extent;
+    float u_cell_size;
+};
+
+layout(location = 0) out vec2 v_position;
+
+// Two triangles covering the square of the ground plane around its
+// center
+const vec2 CORNERS[6] = vec2[6](
+    vec2(-1.0, -1.0),
+    vec2(1.0, -1.0),
+    vec2(1.0, 1.0),
+    vec2(-1.0, -1.0),
+    vec2(1.0, 1.0),
+    vec2(-1.0, 1.0)
+);
+
+void main() {
+    vec2 position = u_center_and_extent.xy + CORNERS[gl_VertexIndex] * u_center_and_extent.w;
+    v_position = position;
+
+    gl_Position = u_view_projection_matrix * vec4(position, u_center_and_extent.z, 1.0);
+}
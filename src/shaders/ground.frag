@@ -0,0 +1,21 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform Ground {
+    mat4 u_view_projection_matrix;
+    vec4 u_origin_and_size;
+    float u_opacity;
+};
+
+layout(set = 1, binding = 0) uniform texture2D t_shadow;
+layout(set = 1, binding = 1) uniform sampler s_shadow;
+
+layout(location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    // The plane itself is invisible, only the shadow darkens what is
+    // behind it
+    float shadow = texture(sampler2D(t_shadow, s_shadow), v_tex_coords).r;
+    f_color = vec4(0.0, 0.0, 0.0, shadow * u_opacity);
+}
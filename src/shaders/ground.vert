@@ -0,0 +1,27 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform Ground {
+    mat4 u_view_projection_matrix;
+    vec4 u_origin_and_size;
+    float u_opacity;
+};
+
+layout(location = 0) out vec2 v_tex_coords;
+
+// Two triangles covering the square of the ground plane
+const vec2 CORNERS[6] = vec2[6](
+    vec2(0.0, 0.0),
+    vec2(1.0, 0.0),
+    vec2(1.0, 1.0),
+    vec2(0.0, 0.0),
+    vec2(1.0, 1.0),
+    vec2(0.0, 1.0)
+);
+
+void main() {
+    vec2 corner = CORNERS[gl_VertexIndex];
+    v_tex_coords = corner;
+
+    vec3 position = u_origin_and_size.xyz + vec3(corner * u_origin_and_size.w, 0.0);
+    gl_Position = u_view_projection_matrix * vec4(position, 1.0);
+}
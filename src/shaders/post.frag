@@ -0,0 +1,80 @@
+#version 450
+
+const uint EFFECT_DEPTH_OF_FIELD = 0x01;
+
+const int DOF_SAMPLE_COUNT = 32;
+const float GOLDEN_ANGLE = 2.39996323;
+
+layout(set = 0, binding = 0, std140) uniform Post {
+    mat4 u_inverse_projection_matrix;
+    vec2 u_screen_size;
+    float u_focus_distance;
+    float u_blur_radius;
+    float u_vignette;
+    float u_grain;
+    float u_seed;
+    uint u_effects;
+};
+
+layout(set = 1, binding = 0) uniform texture2D t_color;
+layout(set = 1, binding = 1) uniform sampler s_color;
+layout(set = 1, binding = 2) uniform texture2D t_depth;
+
+layout(location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+float view_distance(vec2 tex_coords) {
+    ivec2 texel = clamp(ivec2(tex_coords * u_screen_size), ivec2(0), ivec2(u_screen_size) - 1);
+    float depth = texelFetch(sampler2D(t_depth, s_color), texel, 0).r;
+    vec4 position = u_inverse_projection_matrix * vec4(tex_coords * 2.0 - 1.0, depth, 1.0);
+    return -position.z / position.w;
+}
+
+// Radius of the circle of confusion in pixels
+float blur_radius(float distance) {
+    return min(u_blur_radius * abs(1.0 - u_focus_distance / distance), u_blur_radius);
+}
+
+float hash(vec2 p) {
+    return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    vec4 center_color = texture(sampler2D(t_color, s_color), v_tex_coords);
+    vec3 color = center_color.rgb;
+
+    if ((u_effects & EFFECT_DEPTH_OF_FIELD) != 0) {
+        float center_radius = blur_radius(view_distance(v_tex_coords));
+
+        // Gather samples on a golden angle spiral. Each sample only
+        // contributes if it is blurred enough to reach this pixel, so
+        // that sharp foreground doesn't bleed into the background.
+        vec3 color_sum = color;
+        float weight_sum = 1.0;
+        for (int i = 1; i < DOF_SAMPLE_COUNT; i++) {
+            float sample_distance = sqrt(float(i) / float(DOF_SAMPLE_COUNT)) * u_blur_radius;
+            float angle = float(i) * GOLDEN_ANGLE;
+            vec2 offset = vec2(cos(angle), sin(angle)) * sample_distance / u_screen_size;
+            vec2 sample_tex_coords = v_tex_coords + offset;
+
+            float sample_radius = blur_radius(view_distance(sample_tex_coords));
+            float weight = smoothstep(sample_distance - 1.0, sample_distance + 1.0, min(sample_radius, center_radius + 1.0));
+            color_sum += texture(sampler2D(t_color, s_color), sample_tex_coords).rgb * weight;
+            weight_sum += weight;
+        }
+
+        color = color_sum / weight_sum;
+    }
+
+    // Darken the corners
+    float distance_from_center = length(v_tex_coords - 0.5) * 1.41421356;
+    color *= 1.0 - u_vignette * smoothstep(0.4, 1.0, distance_from_center);
+
+    // Film grain, different in each capture
+    float noise = hash(v_tex_coords * u_screen_size + u_seed) - 0.5;
+    color += noise * u_grain;
+
+    // Keep the alpha of frames captured with transparent background
+    f_color = vec4(clamp(color, 0.0, 1.0), center_color.a);
+}
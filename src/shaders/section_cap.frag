@@ -0,0 +1,61 @@
+#version 450
+
+const uint FILL_SOLID = 0;
+const uint FILL_HATCHED = 1;
+
+const float HATCH_LINE_WIDTH = 0.15;
+
+layout(set = 0, binding = 0, std140) uniform SectionCap {
+    mat4 u_view_projection_matrix;
+    mat4 u_inverse_view_projection_matrix;
+    vec4 u_section_plane;
+    vec4 u_color;
+    float u_hatch_spacing;
+    uint u_fill;
+};
+
+layout(location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    // Intersect the view ray of this pixel with the section plane
+    vec2 ndc = v_tex_coords * 2.0 - 1.0;
+    vec4 near_h = u_inverse_view_projection_matrix * vec4(ndc, 0.0, 1.0);
+    vec4 far_h = u_inverse_view_projection_matrix * vec4(ndc, 1.0, 1.0);
+    vec3 near = near_h.xyz / near_h.w;
+    vec3 far = far_h.xyz / far_h.w;
+
+    vec3 normal = u_section_plane.xyz;
+    float denominator = dot(normal, far - near);
+    if (abs(denominator) < 1e-6) {
+        discard;
+    }
+
+    float t = -(dot(normal, near) + u_section_plane.w) / denominator;
+    if (t < 0.0 || t > 1.0) {
+        discard;
+    }
+
+    vec3 position = mix(near, far, t);
+    vec4 clip_position = u_view_projection_matrix * vec4(position, 1.0);
+    gl_FragDepth = clip_position.z / clip_position.w;
+
+    vec3 color = u_color.rgb;
+    if (u_fill == FILL_HATCHED) {
+        // Diagonal lines in the plane, anchored in world-space so that
+        // they don't swim when the camera moves
+        vec3 tangent_reference = abs(normal.z) < 0.9 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+        vec3 tangent = normalize(cross(normal, tangent_reference));
+        vec3 bitangent = cross(normal, tangent);
+
+        float coord = (dot(position, tangent) + dot(position, bitangent)) / u_hatch_spacing;
+        float distance_to_line = abs(fract(coord) - 0.5);
+        float d_coord = fwidth(coord);
+        float line = 1.0 - smoothstep(HATCH_LINE_WIDTH - d_coord, HATCH_LINE_WIDTH + d_coord, 0.5 - distance_to_line);
+
+        color = mix(mix(color, vec3(1.0), 0.75), color, line);
+    }
+
+    f_color = vec4(color, 1.0);
+}
@@ -0,0 +1,24 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform GlobalMatrix {
+    mat4 u_projection_matrix;
+    mat4 u_view_matrix;
+    vec4 u_section_plane;
+    vec2 u_depth_range;
+    uint u_debug_view;
+};
+
+layout(location = 0) in vec3 v_color;
+layout(location = 1) in vec3 v_world_position;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    // Topology in front of the section plane is cut away with the
+    // faces
+    if (dot(u_section_plane.xyz, v_world_position) + u_section_plane.w > 0.0) {
+        discard;
+    }
+
+    f_color = vec4(v_color, 1.0);
+}
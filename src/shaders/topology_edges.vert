@@ -0,0 +1,51 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform GlobalMatrix {
+    mat4 u_projection_matrix;
+    mat4 u_view_matrix;
+    vec4 u_section_plane;
+    vec2 u_depth_range;
+    uint u_debug_view;
+};
+
+layout(set = 1, binding = 0, std140) uniform ModelMatrix {
+    mat4 u_model_matrix;
+    mat4 u_normal_matrix;
+    vec4 u_tint;
+    vec4 u_base_color_and_opacity;
+    uint u_shading_flags;
+    float u_roughness;
+};
+
+layout(location = 0) in vec4 a_position;
+layout(location = 1) in vec4 a_color;
+
+layout(location = 0) out vec3 v_color;
+layout(location = 1) out vec3 v_world_position;
+
+// How far the edges are moved towards the eye, relative to their depth
+const float DEPTH_OFFSET = 0.001;
+// How far the edges are moved towards the eye in the orthographic
+// projection, in normalized device coordinates
+const float DEPTH_OFFSET_ORTHOGRAPHIC = 0.00001;
+
+void main() {
+    vec4 world_position = u_model_matrix * a_position;
+    v_world_position = world_position.xyz / world_position.w;
+    v_color = a_color.rgb;
+
+    // The edges lie on the faces, so they are moved slightly towards
+    // the eye to win the depth test against them. In perspective
+    // projection, scaling the view-space position keeps its place on
+    // the screen.
+    vec4 view_position = u_view_matrix * world_position;
+    bool perspective = u_projection_matrix[3][3] == 0.0;
+    if (perspective) {
+        view_position.xyz *= 1.0 - DEPTH_OFFSET;
+    }
+
+    gl_Position = u_projection_matrix * view_position;
+    if (!perspective) {
+        gl_Position.z -= DEPTH_OFFSET_ORTHOGRAPHIC * gl_Position.w;
+    }
+}
@@ -0,0 +1,69 @@
+#version 450
+
+layout(set = 0, binding = 0, std140) uniform GlobalMatrix {
+    mat4 u_projection_matrix;
+    mat4 u_view_matrix;
+    vec4 u_section_plane;
+    vec2 u_depth_range;
+    uint u_debug_view;
+};
+
+layout(set = 1, binding = 0, std140) uniform ModelMatrix {
+    mat4 u_model_matrix;
+    mat4 u_normal_matrix;
+    vec4 u_tint;
+    vec4 u_base_color_and_opacity;
+    uint u_shading_flags;
+    float u_roughness;
+};
+
+layout(set = 2, binding = 0, std140) uniform Topology {
+    vec2 u_vertex_half_size;
+};
+
+// Per instance
+layout(location = 0) in vec4 a_position;
+layout(location = 1) in vec4 a_color;
+
+layout(location = 0) out vec3 v_color;
+layout(location = 1) out vec3 v_world_position;
+
+// How far the vertices are moved towards the eye, relative to their
+// depth
+const float DEPTH_OFFSET = 0.002;
+// How far the vertices are moved towards the eye in the orthographic
+// projection, in normalized device coordinates
+const float DEPTH_OFFSET_ORTHOGRAPHIC = 0.00002;
+
+// Two triangles of a square around the vertex, facing the screen
+const vec2 CORNERS[6] = vec2[6](
+    vec2(-1.0, -1.0),
+    vec2(1.0, -1.0),
+    vec2(1.0, 1.0),
+    vec2(-1.0, -1.0),
+    vec2(1.0, 1.0),
+    vec2(-1.0, 1.0)
+);
+
+void main() {
+    vec4 world_position = u_model_matrix * a_position;
+    v_world_position = world_position.xyz / world_position.w;
+    v_color = a_color.rgb;
+
+    // Moved towards the eye, so that the vertices win the depth test
+    // against the faces and edges they lie on, like the edges
+    vec4 view_position = u_view_matrix * world_position;
+    bool perspective = u_projection_matrix[3][3] == 0.0;
+    if (perspective) {
+        view_position.xyz *= 1.0 - DEPTH_OFFSET;
+    }
+
+    vec4 clip_position = u_projection_matrix * view_position;
+    if (!perspective) {
+        clip_position.z -= DEPTH_OFFSET_ORTHOGRAPHIC * clip_position.w;
+    }
+
+    // The square has the same size on the screen at any depth
+    clip_position.xy += CORNERS[gl_VertexIndex] * u_vertex_half_size * clip_position.w;
+    gl_Position = clip_position;
+}
@@ -1,5 +1,13 @@
 #version 450
 
+layout(set = 0, binding = 0, std140) uniform GlobalMatrix {
+    mat4 u_projection_matrix;
+    mat4 u_view_matrix;
+    vec4 u_section_plane;
+    vec2 u_depth_range;
+    uint u_debug_view;
+};
+
 layout(set = 1, binding = 0, std140) uniform Shading {
     vec4 u_edge_color_and_face_alpha;
     uint u_shading_mode;
@@ -8,20 +16,58 @@ layout(set = 1, binding = 0, std140) uniform Shading {
 layout(set = 2, binding = 0) uniform texture2D u_matcap_texture;
 layout(set = 2, binding = 1) uniform sampler u_matcap_sampler;
 
+layout(set = 3, binding = 0, std140) uniform ModelMatrix {
+    mat4 u_model_matrix;
+    mat4 u_normal_matrix;
+    vec4 u_tint;
+    vec4 u_base_color_and_opacity;
+    uint u_shading_flags;
+    float u_roughness;
+};
+
 layout(location = 0) in vec2 v_matcap_tex_coords;
 layout(location = 1) in vec3 v_barycentric;
+layout(location = 2) in vec3 v_color;
+layout(location = 3) in vec3 v_world_position;
+layout(location = 4) in vec4 v_tint;
+layout(location = 5) in vec3 v_view_position;
+layout(location = 6) in vec3 v_world_normal;
 
 layout(location = 0) out vec4 f_color;
 
 const uint SHADING_MODE_SHADED = 0x01;
 const uint SHADING_MODE_EDGES = 0x02;
+const uint SHADING_MODE_FACE_ORIENTATION = 0x04;
+
+const uint MODEL_SHADING_FLAT = 0x01;
+
+const uint DEBUG_VIEW_OFF = 0;
+const uint DEBUG_VIEW_NORMALS = 1;
+const uint DEBUG_VIEW_DEPTH = 2;
+
+const vec3 FRONT_FACE_COLOR = vec3(0.30, 0.50, 0.90);
+const vec3 BACK_FACE_COLOR = vec3(0.90, 0.25, 0.25);
 
 const float EDGE_THICKNESS_MIN = 0.75;
 const float EDGE_THICKNESS_MAX = 1.00;
 
+// The gray the matcap is flattened towards by the material roughness
+const vec3 MATTE_COLOR = vec3(0.6);
+// How much of the matcap contrast is removed at full roughness
+const float ROUGHNESS_FLATTENING = 0.7;
+
+float remap(float value, vec2 from, vec2 to) {
+    return (value - from.x) / (from.y - from.x) * (to.y - to.x) + to.x;
+}
+
 void main() {
+    // Geometry in front of the section plane is cut away
+    if (dot(u_section_plane.xyz, v_world_position) + u_section_plane.w > 0.0) {
+        discard;
+    }
+
     vec3 edge_color = u_edge_color_and_face_alpha.rgb;
-    float face_alpha = u_edge_color_and_face_alpha.a;
+    float face_alpha = u_edge_color_and_face_alpha.a * u_base_color_and_opacity.a;
 
     // Find which edge this pixel is the closest to by finding the
     // barycentric coordinate of the farthest away vertex within this
@@ -38,7 +84,43 @@ void main() {
     // antialiasing.
     float edge_alpha = 1.0 - smoothstep(EDGE_THICKNESS_MIN, EDGE_THICKNESS_MAX, thickness);
 
-    vec4 matcap_color = texture(sampler2D(u_matcap_texture, u_matcap_sampler), v_matcap_tex_coords);
+    // Flat shading uses the normal of the face, which is perpendicular
+    // to the screen space derivatives of the position. The framebuffer
+    // Y axis points down, hence the order of the cross product.
+    vec2 matcap_tex_coords = v_matcap_tex_coords;
+    if (bool(u_shading_flags & MODEL_SHADING_FLAT)) {
+        vec3 face_normal = normalize(cross(dFdy(v_view_position), dFdx(v_view_position)));
+        matcap_tex_coords = vec2(remap(face_normal.x, vec2(-1, 1), vec2(0, 1)),
+                                 remap(face_normal.y, vec2(-1, 1), vec2(0, 1)));
+    }
+
+    vec4 matcap_color = texture(sampler2D(u_matcap_texture, u_matcap_sampler), matcap_tex_coords);
+    matcap_color.rgb = mix(matcap_color.rgb, MATTE_COLOR, u_roughness * ROUGHNESS_FLATTENING);
+    matcap_color.rgb *= v_color * u_base_color_and_opacity.rgb;
+
+    // Replace the color with a tint showing which side of the face
+    // we see, keeping the matcap lighting for readability of shape.
+    if (bool(u_shading_mode & SHADING_MODE_FACE_ORIENTATION)) {
+        float luminance = dot(matcap_color.rgb, vec3(0.2126, 0.7152, 0.0722));
+        vec3 face_color = gl_FrontFacing ? FRONT_FACE_COLOR : BACK_FACE_COLOR;
+        matcap_color.rgb = face_color * (0.4 + 0.6 * luminance);
+    }
+
+    // False colors replace the shading, but not the highlight
+    if (u_debug_view == DEBUG_VIEW_NORMALS) {
+        matcap_color.rgb = normalize(v_world_normal) * 0.5 + 0.5;
+    } else if (u_debug_view == DEBUG_VIEW_DEPTH) {
+        float depth = -v_view_position.z;
+        float normalized_depth = clamp(
+            (depth - u_depth_range.x) / max(u_depth_range.y - u_depth_range.x, 0.0001),
+            0.0,
+            1.0
+        );
+        matcap_color.rgb = vec3(1.0 - normalized_depth);
+    }
+
+    // Highlight, e.g. of the selected mesh
+    matcap_color.rgb = mix(matcap_color.rgb, v_tint.rgb, v_tint.a);
 
     bool shaded_mode_enabled = bool(u_shading_mode & SHADING_MODE_SHADED);
     bool edges_mode_enabled = bool(u_shading_mode & SHADING_MODE_EDGES);
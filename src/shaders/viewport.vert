@@ -3,14 +3,32 @@
 layout(set = 0, binding = 0, std140) uniform GlobalMatrix {
     mat4 u_projection_matrix;
     mat4 u_view_matrix;
+    vec4 u_section_plane;
+    vec2 u_depth_range;
+    uint u_debug_view;
+};
+
+layout(set = 3, binding = 0, std140) uniform ModelMatrix {
+    mat4 u_model_matrix;
+    mat4 u_normal_matrix;
+    vec4 u_tint;
+    vec4 u_base_color_and_opacity;
+    uint u_shading_flags;
+    float u_roughness;
 };
 
 layout(location = 0) in vec4 a_position;
 layout(location = 1) in vec4 a_normal;
 layout(location = 2) in uint a_barycentric;
+layout(location = 3) in vec4 a_color;
 
 layout(location = 0) out vec2 v_matcap_tex_coords;
 layout(location = 1) out vec3 v_barycentric;
+layout(location = 2) out vec3 v_color;
+layout(location = 3) out vec3 v_world_position;
+layout(location = 4) out vec4 v_tint;
+layout(location = 5) out vec3 v_view_position;
+layout(location = 6) out vec3 v_world_normal;
 
 float remap(float value, vec2 from, vec2 to) {
     return (value - from.x) / (from.y - from.x) * (to.y - to.x) + to.x;
@@ -28,11 +46,21 @@ vec3 get_barycentric_coord(uint barycentric) {
 
 void main() {
     // FIXME: @Optimization Should we assume it is normalized already?
-    vec4 viewspace_normal = u_view_matrix * normalize(a_normal);
+    vec4 world_normal = normalize(u_normal_matrix * a_normal);
+    vec4 viewspace_normal = u_view_matrix * world_normal;
 
     v_matcap_tex_coords = vec2(remap(viewspace_normal.x, vec2(-1, 1), vec2(0, 1)),
                                remap(viewspace_normal.y, vec2(-1, 1), vec2(0, 1)));
     v_barycentric = get_barycentric_coord(a_barycentric);
+    v_color = a_color.rgb;
+    v_tint = u_tint;
+    v_world_normal = world_normal.xyz;
+
+    vec4 world_position = u_model_matrix * a_position;
+    v_world_position = world_position.xyz / world_position.w;
+
+    vec4 view_position = u_view_matrix * world_position;
+    v_view_position = view_position.xyz / view_position.w;
 
-    gl_Position = u_projection_matrix * u_view_matrix * a_position;
+    gl_Position = u_projection_matrix * view_position;
 }
@@ -0,0 +1,133 @@
+use std::f32;
+
+use crate::convert::cast_usize;
+
+/// Descriptive statistics of a set of scalar values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    /// The number of finite values the statistics were computed from.
+    pub count: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub standard_deviation: f32,
+}
+
+impl Statistics {
+    /// Computes statistics of all finite `values`. Returns `None` if
+    /// there are no finite values.
+    pub fn from_values(values: &[f32]) -> Option<Self> {
+        let mut count = 0;
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+
+        // Accumulate in f64 to keep precision for large meshes
+        let mut sum = 0.0f64;
+        let mut sum_squared = 0.0f64;
+
+        for value in values.iter().copied().filter(|value| value.is_finite()) {
+            count += 1;
+            min = f32::min(min, value);
+            max = f32::max(max, value);
+            sum += f64::from(value);
+            sum_squared += f64::from(value) * f64::from(value);
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        let count_f64 = count as f64;
+        let mean = sum / count_f64;
+        let variance = f64::max(0.0, sum_squared / count_f64 - mean * mean);
+
+        Some(Self {
+            count,
+            min,
+            max,
+            mean: mean as f32,
+            standard_deviation: variance.sqrt() as f32,
+        })
+    }
+}
+
+/// Sorts finite `values` into `bin_count` equally wide bins spanning
+/// `min..=max` and returns the number of values in each bin. Values
+/// outside of the range are not counted.
+///
+/// The counts are returned as floats for convenient plotting.
+///
+/// # Panics
+/// Panics if `bin_count` is zero.
+pub fn compute_histogram(values: &[f32], min: f32, max: f32, bin_count: u32) -> Vec<f32> {
+    assert!(bin_count > 0, "Histogram must have at least one bin");
+
+    let bin_count = cast_usize(bin_count);
+    let mut bins = vec![0.0; bin_count];
+    let range = max - min;
+
+    for value in values.iter().copied().filter(|value| value.is_finite()) {
+        if value < min || value > max {
+            continue;
+        }
+
+        let bin = if range > 0.0 {
+            let position = (value - min) / range * bin_count as f32;
+            usize::min(position as usize, bin_count - 1)
+        } else {
+            0
+        };
+
+        bins[bin] += 1.0;
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics_from_values() {
+        let statistics = Statistics::from_values(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0])
+            .expect("Failed to compute statistics");
+
+        assert_eq!(statistics.count, 8);
+        assert_eq!(statistics.min, 2.0);
+        assert_eq!(statistics.max, 9.0);
+        assert_eq!(statistics.mean, 5.0);
+        assert_eq!(statistics.standard_deviation, 2.0);
+    }
+
+    #[test]
+    fn test_statistics_from_values_skips_non_finite_values() {
+        let statistics = Statistics::from_values(&[f32::NAN, 1.0, f32::NEG_INFINITY, 3.0])
+            .expect("Failed to compute statistics");
+
+        assert_eq!(statistics.count, 2);
+        assert_eq!(statistics.min, 1.0);
+        assert_eq!(statistics.max, 3.0);
+        assert_eq!(statistics.mean, 2.0);
+    }
+
+    #[test]
+    fn test_statistics_from_values_returns_none_for_no_finite_values() {
+        assert_eq!(Statistics::from_values(&[]), None);
+        assert_eq!(Statistics::from_values(&[f32::NAN]), None);
+    }
+
+    #[test]
+    fn test_compute_histogram() {
+        let histogram = compute_histogram(&[0.0, 0.1, 0.5, 0.9, 1.0, 2.0], 0.0, 1.0, 4);
+
+        assert_eq!(histogram, vec![2.0, 0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_compute_histogram_with_empty_range() {
+        let histogram = compute_histogram(&[1.0, 1.0, 1.0], 1.0, 1.0, 3);
+
+        assert_eq!(histogram, vec![3.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,615 @@
+#[cfg(feature = "gui")]
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+#[cfg(feature = "gui")]
+use std::net::{IpAddr, TcpListener};
+use std::sync::Arc;
+#[cfg(feature = "gui")]
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "gui")]
+use crossbeam_channel as channel;
+use serde::{Deserialize, Serialize};
+
+use crate::interpreter::ast::{FuncIdent, Prog, Stmt};
+use crate::interpreter::{Interpreter, Value, VarIdent};
+use crate::interpreter_funcs;
+use crate::mesh::Mesh;
+#[cfg(feature = "gui")]
+use crate::optimizer::{self, OptimizedParam};
+
+/// Workers and coordinators of different versions could disagree on
+/// the function identifiers, so they refuse to work with each other.
+const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How long an idle worker waits before asking for a job again.
+const WORKER_IDLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The largest message accepted from the other side of the connection,
+/// in bytes. Meshes of about half a million faces fit when serialized
+/// as JSON, larger results have to be reduced before being returned.
+const MAX_MESSAGE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Funcs reading or writing files, which workers refuse to run.
+/// Otherwise anyone able to run a coordinator could read or write
+/// arbitrary paths on the worker machines.
+const WORKER_FORBIDDEN_FUNCS: [FuncIdent; 4] = [
+    interpreter_funcs::FUNC_ID_IMPORT_OBJ_MESH,
+    interpreter_funcs::FUNC_ID_EXPORT_ATTRIBUTE_CSV,
+    interpreter_funcs::FUNC_ID_EXPORT_LOD_GLTF,
+    interpreter_funcs::FUNC_ID_EXPORT_OBJ,
+];
+
+/// A message sent by a worker to the coordinator. Messages are sent as
+/// JSON objects, one per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "message", rename_all = "snake_case")]
+enum WorkerMessage {
+    RequestJob {
+        version: String,
+    },
+    JobCompleted {
+        job_id: u64,
+        result: Result<Vec<Arc<Mesh>>, String>,
+    },
+}
+
+/// A reply of the coordinator to a worker message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "message", rename_all = "snake_case")]
+enum CoordinatorMessage {
+    Job { job_id: u64, prog: Prog },
+    NoJob,
+    Accepted,
+    Refused { reason: String },
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+struct SweepJob {
+    id: u64,
+    name: String,
+    prog: Prog,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Default)]
+struct SharedState {
+    queued: VecDeque<SweepJob>,
+    running: HashMap<u64, SweepJob>,
+    worker_count: usize,
+}
+
+/// A finished job of a parameter sweep.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    pub name: String,
+    pub prog: Prog,
+    /// The meshes of the terminal operations evaluated by the worker,
+    /// or the reason the evaluation failed.
+    pub result: Result<Vec<Arc<Mesh>>, String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SweepStatus {
+    pub queued: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub workers: usize,
+}
+
+/// A server distributing the programs of a parameter sweep to headless
+/// worker instances running on other machines.
+///
+/// Workers pull jobs one at a time and return the evaluated meshes.
+/// Jobs of workers that disconnect before completing them are queued
+/// again.
+#[cfg(feature = "gui")]
+pub struct SweepCoordinator {
+    state: Arc<Mutex<SharedState>>,
+    result_receiver: channel::Receiver<SweepResult>,
+    next_job_id: u64,
+    completed: usize,
+}
+
+#[cfg(feature = "gui")]
+impl SweepCoordinator {
+    /// Starts listening on the `port` of the network interface with
+    /// `address`. Workers on other machines can only connect if the
+    /// address is not a loopback address, e.g. `0.0.0.0`.
+    pub fn new(address: IpAddr, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((address, port))?;
+        let (result_sender, result_receiver) = channel::unbounded();
+        let state = Arc::new(Mutex::new(SharedState::default()));
+
+        log::info!("Sweep coordinator listening on {}", listener.local_addr()?);
+
+        let accept_state = Arc::clone(&state);
+        thread::Builder::new()
+            .name("sweep_coordinator".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            log::warn!("Sweep coordinator failed to accept connection: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let state = Arc::clone(&accept_state);
+                    let result_sender = result_sender.clone();
+                    let spawn_result = thread::Builder::new()
+                        .name("sweep_worker_connection".to_string())
+                        .spawn(move || {
+                            if let Err(err) = serve_worker(stream, &state, &result_sender) {
+                                log::warn!("Sweep worker connection failed: {}", err);
+                            }
+                        });
+
+                    if let Err(err) = spawn_result {
+                        log::error!("Failed to spawn sweep worker connection thread: {}", err);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            state,
+            result_receiver,
+            next_job_id: 0,
+            completed: 0,
+        })
+    }
+
+    /// Queues named programs to be evaluated by the workers.
+    pub fn submit(&mut self, jobs: Vec<(String, Prog)>) {
+        let mut state = self.state.lock().expect("Failed to lock sweep state");
+        for (name, prog) in jobs {
+            state.queued.push_back(SweepJob {
+                id: self.next_job_id,
+                name,
+                prog,
+            });
+            self.next_job_id += 1;
+        }
+    }
+
+    /// Returns the jobs finished since the last poll.
+    pub fn poll_results(&mut self) -> Vec<SweepResult> {
+        let results: Vec<_> = self.result_receiver.try_iter().collect();
+        self.completed += results.len();
+
+        results
+    }
+
+    pub fn status(&self) -> SweepStatus {
+        let state = self.state.lock().expect("Failed to lock sweep state");
+        SweepStatus {
+            queued: state.queued.len(),
+            running: state.running.len(),
+            completed: self.completed,
+            workers: state.worker_count,
+        }
+    }
+}
+
+/// Creates the programs of a sweep over a regular grid of parameter
+/// values, with `steps` values spread evenly over each parameter's
+/// range. Each program is named after its parameter values.
+#[cfg(feature = "gui")]
+pub fn sweep_progs(prog: &Prog, params: &[OptimizedParam], steps: u32) -> Vec<(String, Prog)> {
+    if params.is_empty() || steps == 0 {
+        return Vec::new();
+    }
+
+    let steps = steps as usize;
+    let job_count = steps.pow(params.len() as u32);
+    let mut progs = Vec::with_capacity(job_count);
+
+    for job_index in 0..job_count {
+        let mut step_index = job_index;
+        let values: Vec<f32> = params
+            .iter()
+            .map(|param| {
+                let step = step_index % steps;
+                step_index /= steps;
+
+                if steps == 1 {
+                    param.range.min
+                } else {
+                    let t = step as f32 / (steps - 1) as f32;
+                    param.range.min + t * (param.range.max - param.range.min)
+                }
+            })
+            .collect();
+
+        let value_names: Vec<_> = values.iter().map(|value| format!("{:.3}", value)).collect();
+        let name = format!("Sweep {} ({})", job_index + 1, value_names.join(", "));
+
+        progs.push((name, optimizer::prog_with_values(prog, params, &values)));
+    }
+
+    progs
+}
+
+/// Runs a headless worker, which repeatedly pulls a program from the
+/// coordinator at `coordinator_addr`, evaluates it and sends back the
+/// meshes of its terminal operations. Returns once the coordinator
+/// closes the connection or refuses the worker.
+pub fn run_worker(coordinator_addr: &str) -> io::Result<()> {
+    let stream = TcpStream::connect(coordinator_addr)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut interpreter = Interpreter::new(interpreter_funcs::create_function_table());
+
+    log::info!("Sweep worker connected to {}", coordinator_addr);
+
+    loop {
+        let request_job = WorkerMessage::RequestJob {
+            version: PROTOCOL_VERSION.to_string(),
+        };
+
+        match exchange(&mut writer, &mut reader, &request_job)? {
+            CoordinatorMessage::Job { job_id, prog } => {
+                log::info!("Sweep worker evaluating job {}", job_id);
+
+                let result = check_worker_prog(&prog).and_then(|()| {
+                    interpreter.set_prog(prog);
+                    match interpreter.interpret().result {
+                        Ok(interpret_value) => Ok(terminal_meshes(&interpret_value.unused_values)),
+                        Err(err) => Err(err.to_string()),
+                    }
+                });
+
+                let job_completed = WorkerMessage::JobCompleted { job_id, result };
+                exchange(&mut writer, &mut reader, &job_completed)?;
+            }
+            CoordinatorMessage::NoJob => thread::sleep(WORKER_IDLE_INTERVAL),
+            CoordinatorMessage::Accepted => (/* Only a reply to completed jobs */),
+            CoordinatorMessage::Refused { reason } => {
+                return Err(io::Error::new(io::ErrorKind::Other, reason));
+            }
+        }
+    }
+}
+
+/// Checks that the program received from the coordinator doesn't
+/// call any of the funcs workers refuse to run.
+fn check_worker_prog(prog: &Prog) -> Result<(), String> {
+    for (index, stmt) in prog.stmts().iter().enumerate() {
+        let Stmt::VarDecl(var_decl) = stmt;
+        if WORKER_FORBIDDEN_FUNCS.contains(&var_decl.init_expr().ident()) {
+            return Err(format!(
+                "Operation {} accesses files, which sweep workers refuse to do",
+                index + 1,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the meshes of the values not used by any operation.
+pub fn terminal_meshes(unused_values: &[(VarIdent, Value)]) -> Vec<Arc<Mesh>> {
+    let mut meshes = Vec::new();
+    for (_, value) in unused_values {
+        match value {
+            Value::Mesh(mesh) => meshes.push(Arc::clone(mesh)),
+            Value::MeshArray(mesh_array) => meshes.extend(mesh_array.iter_refcounted()),
+            _ => (/* Only geometry is returned */),
+        }
+    }
+
+    meshes
+}
+
+/// Sends a message to the coordinator and waits for its reply.
+fn exchange(
+    writer: &mut TcpStream,
+    reader: &mut BufReader<TcpStream>,
+    message: &WorkerMessage,
+) -> io::Result<CoordinatorMessage> {
+    write_message(writer, message)?;
+    read_message(reader)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Coordinator closed the connection",
+        )
+    })
+}
+
+#[cfg(feature = "gui")]
+fn serve_worker(
+    stream: TcpStream,
+    state: &Mutex<SharedState>,
+    result_sender: &channel::Sender<SweepResult>,
+) -> io::Result<()> {
+    let peer_addr = stream.peer_addr()?;
+    log::info!("Sweep worker connected from {}", peer_addr);
+
+    state
+        .lock()
+        .expect("Failed to lock sweep state")
+        .worker_count += 1;
+
+    let mut assigned_job_ids = Vec::new();
+    let result = serve_worker_messages(stream, state, result_sender, &mut assigned_job_ids);
+
+    // Jobs of the disconnected worker are given to other workers
+    let mut state = state.lock().expect("Failed to lock sweep state");
+    state.worker_count -= 1;
+    for job_id in assigned_job_ids {
+        if let Some(job) = state.running.remove(&job_id) {
+            state.queued.push_front(job);
+        }
+    }
+
+    log::info!("Sweep worker {} disconnected", peer_addr);
+
+    result
+}
+
+#[cfg(feature = "gui")]
+fn serve_worker_messages(
+    stream: TcpStream,
+    state: &Mutex<SharedState>,
+    result_sender: &channel::Sender<SweepResult>,
+    assigned_job_ids: &mut Vec<u64>,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    while let Some(message) = read_message(&mut reader)? {
+        let reply = match message {
+            WorkerMessage::RequestJob { version } => {
+                if version != PROTOCOL_VERSION {
+                    let reason = format!(
+                        "Worker version {} does not match coordinator version {}",
+                        version, PROTOCOL_VERSION,
+                    );
+                    return write_message(&mut writer, &CoordinatorMessage::Refused { reason });
+                }
+
+                let mut state = state.lock().expect("Failed to lock sweep state");
+                match state.queued.pop_front() {
+                    Some(job) => {
+                        let reply = CoordinatorMessage::Job {
+                            job_id: job.id,
+                            prog: job.prog.clone(),
+                        };
+                        assigned_job_ids.push(job.id);
+                        state.running.insert(job.id, job);
+
+                        reply
+                    }
+                    None => CoordinatorMessage::NoJob,
+                }
+            }
+            WorkerMessage::JobCompleted { job_id, result } => {
+                assigned_job_ids.retain(|assigned_job_id| *assigned_job_id != job_id);
+                let job = state
+                    .lock()
+                    .expect("Failed to lock sweep state")
+                    .running
+                    .remove(&job_id);
+
+                match job {
+                    Some(job) => {
+                        let result = result.and_then(|meshes| {
+                            if meshes.iter().all(|mesh| mesh.is_consistent()) {
+                                Ok(meshes)
+                            } else {
+                                Err("Worker returned invalid geometry".to_string())
+                            }
+                        });
+
+                        let sweep_result = SweepResult {
+                            name: job.name,
+                            prog: job.prog,
+                            result,
+                        };
+                        if result_sender.send(sweep_result).is_err() {
+                            // The application is shutting down
+                            return Ok(());
+                        }
+                    }
+                    None => log::warn!("Sweep worker completed unknown job {}", job_id),
+                }
+
+                CoordinatorMessage::Accepted
+            }
+        };
+
+        write_message(&mut writer, &reply)?;
+    }
+
+    Ok(())
+}
+
+fn write_message<W: Write, M: Serialize>(writer: &mut W, message: &M) -> io::Result<()> {
+    let json = serde_json::to_string(message)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    writeln!(writer, "{}", json)?;
+    writer.flush()
+}
+
+/// Reads a single message. Returns `None` if the connection was
+/// closed.
+fn read_message<R: BufRead, M: for<'de> Deserialize<'de>>(reader: &mut R) -> io::Result<Option<M>> {
+    read_message_with_limit(reader, MAX_MESSAGE_LEN)
+}
+
+/// Reads a single message of at most `max_len` bytes. Returns `None`
+/// if the connection was closed.
+fn read_message_with_limit<R: BufRead, M: for<'de> Deserialize<'de>>(
+    reader: &mut R,
+    max_len: u64,
+) -> io::Result<Option<M>> {
+    let mut line = String::new();
+    let len = reader.by_ref().take(max_len).read_line(&mut line)?;
+    if len == 0 {
+        return Ok(None);
+    }
+    if len as u64 == max_len && !line.ends_with('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Message exceeds {} bytes", max_len),
+        ));
+    }
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use nalgebra::Point3;
+
+    use crate::interpreter::ast::{
+        CallExpr, Expr, FuncIdent, LitExpr, Stmt, VarDeclStmt, VarIdent,
+    };
+    use crate::interpreter::ParamRefinement;
+    use crate::mesh::NormalStrategy;
+
+    use super::*;
+
+    fn float_prog(value: f32) -> Prog {
+        Prog::new(vec![Stmt::VarDecl(VarDeclStmt::new(
+            VarIdent(0),
+            CallExpr::new(FuncIdent(0), vec![Expr::Lit(LitExpr::Float(value))]),
+        ))])
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_sweep_progs_covers_grid() {
+        let param = |stmt_index, min, max| OptimizedParam {
+            stmt_index,
+            arg_index: 0,
+            component: 0,
+            range: optimizer::ParamRange { min, max },
+        };
+        let mut prog = float_prog(0.0);
+        prog.push_stmt(float_prog(0.0).stmts()[0].clone());
+
+        let progs = sweep_progs(&prog, &[param(0, 0.0, 1.0), param(1, 2.0, 4.0)], 3);
+        assert_eq!(progs.len(), 9);
+
+        let values: Vec<_> = progs
+            .iter()
+            .map(|(_, prog)| {
+                let value = |stmt_index: usize| {
+                    let Stmt::VarDecl(var_decl) = &prog.stmts()[stmt_index];
+                    var_decl.init_expr().args()[0].unwrap_literal().clone()
+                };
+                (value(0), value(1))
+            })
+            .collect();
+        assert_eq!(values[0], (LitExpr::Float(0.0), LitExpr::Float(2.0)));
+        assert_eq!(values[1], (LitExpr::Float(0.5), LitExpr::Float(2.0)));
+        assert_eq!(values[8], (LitExpr::Float(1.0), LitExpr::Float(4.0)));
+        assert_eq!(progs[4].0, "Sweep 5 (0.500, 3.000)");
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        let mesh = Arc::new(
+            Mesh::from_triangle_faces_with_vertices_and_computed_normals(
+                vec![(0, 1, 2)],
+                vec![
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(1.0, 0.0, 0.0),
+                    Point3::new(0.0, 1.0, 0.0),
+                ],
+                NormalStrategy::Sharp,
+            ),
+        );
+
+        let mut buffer = Vec::new();
+        let job = CoordinatorMessage::Job {
+            job_id: 3,
+            prog: float_prog(1.5),
+        };
+        let job_completed = WorkerMessage::JobCompleted {
+            job_id: 3,
+            result: Ok(vec![Arc::clone(&mesh)]),
+        };
+        write_message(&mut buffer, &job).expect("Failed to write message");
+        write_message(&mut buffer, &job_completed).expect("Failed to write message");
+
+        let mut reader = Cursor::new(buffer);
+        let read_job: Option<CoordinatorMessage> =
+            read_message(&mut reader).expect("Failed to read message");
+        let read_job_completed: Option<WorkerMessage> =
+            read_message(&mut reader).expect("Failed to read message");
+        let end: Option<WorkerMessage> = read_message(&mut reader).expect("Failed to read end");
+
+        assert_eq!(read_job, Some(job));
+        assert_eq!(read_job_completed, Some(job_completed));
+        assert_eq!(end, None);
+    }
+
+    #[test]
+    fn test_read_message_rejects_message_over_limit() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &CoordinatorMessage::NoJob).expect("Failed to write message");
+        let message_len = buffer.len() as u64;
+
+        let mut reader = Cursor::new(buffer.clone());
+        let read_no_job: Option<CoordinatorMessage> =
+            read_message_with_limit(&mut reader, message_len).expect("Failed to read message");
+        assert_eq!(read_no_job, Some(CoordinatorMessage::NoJob));
+
+        let mut reader = Cursor::new(buffer);
+        let result: io::Result<Option<CoordinatorMessage>> =
+            read_message_with_limit(&mut reader, message_len - 1);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_worker_prog_refuses_imports_and_exports() {
+        assert_eq!(check_worker_prog(&float_prog(1.0)), Ok(()));
+
+        for func_ident in &[
+            interpreter_funcs::FUNC_ID_IMPORT_OBJ_MESH,
+            interpreter_funcs::FUNC_ID_EXPORT_OBJ,
+        ] {
+            let file_prog = Prog::new(vec![Stmt::VarDecl(VarDeclStmt::new(
+                VarIdent(0),
+                CallExpr::new(*func_ident, Vec::new()),
+            ))]);
+            assert!(check_worker_prog(&file_prog).is_err());
+        }
+    }
+
+    #[test]
+    fn test_worker_forbidden_funcs_include_all_funcs_with_file_paths() {
+        let function_table = interpreter_funcs::create_function_table();
+
+        for (func_ident, func) in &function_table {
+            let takes_file_path =
+                func.param_info()
+                    .iter()
+                    .any(|param_info| match param_info.refinement {
+                        ParamRefinement::String(refinement) => refinement.file_path,
+                        _ => false,
+                    });
+
+            if takes_file_path {
+                assert!(
+                    WORKER_FORBIDDEN_FUNCS.contains(func_ident),
+                    "Func {} takes a file path, but workers run it",
+                    func.info().name,
+                );
+            }
+        }
+    }
+}
@@ -1,18 +1,85 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::f32;
+use std::f64;
 use std::sync::Arc;
 
 use imgui_winit_support::{HiDpiMode, WinitPlatform};
+use nalgebra::{Matrix4, Point3, Vector3};
 
-use crate::convert::{cast_u8_color_to_f32, clamp_cast_i32_to_u32, clamp_cast_u32_to_i32};
-use crate::interpreter::{ast, LogMessageLevel, ParamRefinement, Ty};
-use crate::renderer::DrawMeshMode;
+use crate::camera::{CameraProjection, CameraRotationMode};
+use crate::camera_path::{self, CameraPath};
+use crate::color_ramp::ColorRamp;
+use crate::constants;
+use crate::convert::{
+    cast_u32, cast_u8_color_to_f32, cast_usize, clamp_cast_i32_to_u32, clamp_cast_u32_to_i32,
+};
+use crate::curve::{Curve, Polyline};
+use crate::exporter;
+use crate::gizmo::{GizmoAxis, GizmoHandle, GizmoMode};
+use crate::history::{EvaluationHistory, HistoryEntryId};
+use crate::importer;
+use crate::interpreter::{
+    ast, Float2ParamRefinement, Float3ParamRefinement, FloatParamRefinement, FuncCategory,
+    LogMessageLevel, ParamRefinement, Ty,
+};
+use crate::math;
+use crate::mesh::analysis::{self, MassProperties, TopologyInfo};
+use crate::mesh::{tools, Mesh};
+use crate::optimizer::{
+    self, Goal, Method, Metric, Objective, OptimizationRequest, OptimizedParam, ParetoFront,
+    ParetoRequest, Plot, PlotRequest,
+};
+use crate::project;
+use crate::renderer::{
+    Background, DebugView, DrawMeshMode, Matcap, Material, MeshShading, Msaa, PostProcessing,
+    Section, SectionFill,
+};
 use crate::session::Session;
+use crate::statistics::{self, Statistics};
+use crate::sweep::{self, SweepStatus};
+use crate::units::LengthUnit;
 
 const OPENSANS_REGULAR_BYTES: &[u8] = include_bytes!("../resources/SpaceMono-Regular.ttf");
 const OPENSANS_BOLD_BYTES: &[u8] = include_bytes!("../resources/SpaceMono-Bold.ttf");
 
 const MARGIN: f32 = 10.0;
+const VIEWPORT_WINDOW_WIDTH: f32 = 150.0;
+const VIEWPORT_WINDOW_HEIGHT: f32 = 690.0;
+
+/// Colors of the X, Y and Z axes of the gizmo and locators.
+const AXIS_COLOR_X: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+const AXIS_COLOR_Y: [f32; 4] = [0.2, 0.8, 0.2, 1.0];
+const AXIS_COLOR_Z: [f32; 4] = [0.2, 0.4, 0.95, 1.0];
+
+/// The color backgrounds switch to from the environment background.
+const DEFAULT_BACKGROUND_COLOR: [f32; 3] = [0.1, 0.1, 0.1];
+const INSPECTOR_WINDOW_HEIGHT: f32 = 400.0;
+
+/// A locator projected to the screen, in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocatorMarker {
+    pub name: String,
+    pub origin: [f32; 2],
+    /// The ends of the X, Y and Z axes of the locator, if they are in
+    /// front of the camera.
+    pub axis_ends: [Option<[f32; 2]>; 3],
+}
+
+/// A request from the camera path settings. Carried out by the caller,
+/// who owns the camera and the locators.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraPathCommand {
+    /// Adds a keyframe at the current view of the camera.
+    AddKeyframe,
+    /// Adds a keyframe looking at the locator with the index in the
+    /// list of locator names.
+    AddKeyframeFromLocator(usize),
+    Preview,
+    /// Renders the path to an image sequence in the directory.
+    Render(String),
+    Stop,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Theme {
@@ -43,14 +110,167 @@ struct ConsoleState {
     message_count: usize,
 }
 
+#[derive(Debug, Default)]
+struct InspectorState {
+    selected_mesh_index: usize,
+    /// The mesh last selected in the viewport, so that the inspector
+    /// only follows changes of the selection.
+    viewport_selection: Option<(ast::VarIdent, usize)>,
+    selected_attribute_index: usize,
+    cache: Option<InspectorCache>,
+    topology_cache: Option<(Arc<Mesh>, TopologyInfo, Option<MassProperties>)>,
+}
+
+#[derive(Debug)]
+struct VariantsState {
+    name_buffer: imgui::ImString,
+    selected_variant_index: usize,
+}
+
+impl Default for VariantsState {
+    fn default() -> Self {
+        Self {
+            name_buffer: imgui::ImString::with_capacity(64),
+            selected_variant_index: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MacrosState {
+    name_buffer: imgui::ImString,
+    selected_macro_index: usize,
+}
+
+impl Default for MacrosState {
+    fn default() -> Self {
+        Self {
+            name_buffer: imgui::ImString::with_capacity(64),
+            selected_macro_index: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ConstantsState {
+    name_buffer: imgui::ImString,
+    value: f32,
+}
+
+impl Default for ConstantsState {
+    fn default() -> Self {
+        Self {
+            name_buffer: imgui::ImString::with_capacity(64),
+            value: 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LibraryState {
+    query_buffer: imgui::ImString,
+}
+
+impl Default for LibraryState {
+    fn default() -> Self {
+        Self {
+            query_buffer: imgui::ImString::with_capacity(64),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct OptimizerState {
+    selected_target_index: usize,
+    selected_metric_index: usize,
+    selected_method_index: usize,
+    maximize: bool,
+    iterations: i32,
+    /// Ranges of the optimized parameters, identified by their
+    /// statement index, argument index and component.
+    param_ranges: HashMap<(usize, usize, usize), [f32; 2]>,
+    pareto_objectives: [ParetoObjectiveState; 3],
+    pareto_third_objective: bool,
+    pareto_iterations: i32,
+    pareto_x_axis: usize,
+    pareto_y_axis: usize,
+    sweep_steps: i32,
+    plot_param_index: usize,
+    plot_target_index: usize,
+    plot_metric_index: usize,
+    plot_steps: i32,
+    plot_lines: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct ParetoObjectiveState {
+    selected_target_index: usize,
+    selected_metric_index: usize,
+    maximize: bool,
+}
+
+impl Default for OptimizerState {
+    fn default() -> Self {
+        Self {
+            selected_target_index: 0,
+            selected_metric_index: 0,
+            selected_method_index: 0,
+            maximize: true,
+            iterations: 100,
+            param_ranges: HashMap::new(),
+            pareto_objectives: [
+                ParetoObjectiveState {
+                    selected_metric_index: 0,
+                    ..ParetoObjectiveState::default()
+                },
+                ParetoObjectiveState {
+                    selected_metric_index: 1,
+                    ..ParetoObjectiveState::default()
+                },
+                ParetoObjectiveState {
+                    selected_metric_index: 2,
+                    ..ParetoObjectiveState::default()
+                },
+            ],
+            pareto_third_objective: false,
+            pareto_iterations: 200,
+            pareto_x_axis: 0,
+            pareto_y_axis: 1,
+            sweep_steps: 3,
+            plot_param_index: 0,
+            plot_target_index: 0,
+            plot_metric_index: 0,
+            plot_steps: 10,
+            plot_lines: true,
+        }
+    }
+}
+
+/// Statistics of the inspected attribute. Recomputed only when the
+/// inspected mesh or attribute changes.
+#[derive(Debug)]
+struct InspectorCache {
+    mesh: Arc<Mesh>,
+    attribute_index: usize,
+    statistics: Option<Statistics>,
+    histogram: Vec<f32>,
+}
+
 /// Thin wrapper around imgui and its winit platform. Its main responsibilty
 /// is to create UI frames which draw the UI itself.
 pub struct Ui {
     imgui_context: imgui::Context,
     imgui_winit_platform: WinitPlatform,
+    hidpi_factor: f64,
     font_ids: FontIds,
     colors: Colors,
     console_state: RefCell<Vec<ConsoleState>>,
+    inspector_state: RefCell<InspectorState>,
+    variants_state: RefCell<VariantsState>,
+    macros_state: RefCell<MacrosState>,
+    constants_state: RefCell<ConstantsState>,
+    library_state: RefCell<LibraryState>,
+    optimizer_state: RefCell<OptimizerState>,
 
     /// A preallocated string buffer used for imgui strings in the
     /// UI. Every user of this buffer has the responsibility to clear
@@ -165,34 +385,21 @@ impl Ui {
         platform.attach_window(imgui_context.io_mut(), window, HiDpiMode::Default);
 
         let hidpi_factor = platform.hidpi_factor();
-        let font_size = (15.0 * hidpi_factor) as f32;
-
-        let regular_font_id = imgui_context
-            .fonts()
-            .add_font(&[imgui::FontSource::TtfData {
-                data: OPENSANS_REGULAR_BYTES,
-                size_pixels: font_size,
-                config: None,
-            }]);
-        let bold_font_id = imgui_context
-            .fonts()
-            .add_font(&[imgui::FontSource::TtfData {
-                data: OPENSANS_BOLD_BYTES,
-                size_pixels: font_size,
-                config: None,
-            }]);
-
-        imgui_context.io_mut().font_global_scale = (1.0 / hidpi_factor) as f32;
+        let font_ids = add_fonts(&mut imgui_context, hidpi_factor);
 
         Ui {
             imgui_context,
             imgui_winit_platform: platform,
-            font_ids: FontIds {
-                regular: regular_font_id,
-                bold: bold_font_id,
-            },
+            hidpi_factor,
+            font_ids,
             colors,
             console_state: RefCell::new(Vec::new()),
+            inspector_state: RefCell::new(InspectorState::default()),
+            variants_state: RefCell::new(VariantsState::default()),
+            macros_state: RefCell::new(MacrosState::default()),
+            constants_state: RefCell::new(ConstantsState::default()),
+            library_state: RefCell::new(LibraryState::default()),
+            optimizer_state: RefCell::new(OptimizerState::default()),
             global_imstring_buffer: RefCell::new(imgui::ImString::with_capacity(1024)),
         }
     }
@@ -201,6 +408,31 @@ impl Ui {
         self.imgui_context.fonts()
     }
 
+    /// Updates the UI to the window's new hidpi factor, e.g. after
+    /// moving it to another monitor. Fonts are rasterized again at the
+    /// new scale, so that they stay sharp. Returns whether the factor
+    /// changed, in which case the font atlas has to be re-uploaded to
+    /// the renderer.
+    ///
+    /// Must not be called while a frame is being prepared.
+    pub fn set_hidpi_factor(&mut self, hidpi_factor: f64) -> bool {
+        if (hidpi_factor - self.hidpi_factor).abs() < f64::EPSILON {
+            return false;
+        }
+
+        log::info!(
+            "Hidpi factor changed from {} to {}, rebuilding fonts",
+            self.hidpi_factor,
+            hidpi_factor,
+        );
+
+        self.imgui_context.fonts().clear();
+        self.font_ids = add_fonts(&mut self.imgui_context, hidpi_factor);
+        self.hidpi_factor = hidpi_factor;
+
+        true
+    }
+
     pub fn handle_event<T>(
         &mut self,
         window: &winit::window::Window,
@@ -221,6 +453,12 @@ impl Ui {
             font_ids: &self.font_ids,
             colors: &self.colors,
             console_state: &self.console_state,
+            inspector_state: &self.inspector_state,
+            variants_state: &self.variants_state,
+            macros_state: &self.macros_state,
+            constants_state: &self.constants_state,
+            library_state: &self.library_state,
+            optimizer_state: &self.optimizer_state,
             global_imstring_buffer: &self.global_imstring_buffer,
         }
     }
@@ -238,6 +476,12 @@ pub struct UiFrame<'a> {
     font_ids: &'a FontIds,
     colors: &'a Colors,
     console_state: &'a RefCell<Vec<ConsoleState>>,
+    inspector_state: &'a RefCell<InspectorState>,
+    variants_state: &'a RefCell<VariantsState>,
+    macros_state: &'a RefCell<MacrosState>,
+    constants_state: &'a RefCell<ConstantsState>,
+    library_state: &'a RefCell<LibraryState>,
+    optimizer_state: &'a RefCell<OptimizerState>,
     global_imstring_buffer: &'a RefCell<imgui::ImString>,
 }
 
@@ -256,11 +500,51 @@ impl<'a> UiFrame<'a> {
         self.imgui_ui.render()
     }
 
-    pub fn draw_viewport_settings_window(&self, draw_mode: &mut DrawMeshMode) -> bool {
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_viewport_settings_window(
+        &self,
+        draw_mode: &mut DrawMeshMode,
+        show_previous_result: &mut bool,
+        show_changes: &mut bool,
+        show_pipeline_stages: &mut bool,
+        compare_with_pinned: &mut bool,
+        auto_orbit: &mut bool,
+        exploded_view_factor: &mut f32,
+        background: &mut Background,
+        environment_path: &mut Option<String>,
+        ground_shadow: &mut bool,
+        ground_grid: &mut bool,
+        axes: &mut bool,
+        topology: &mut bool,
+        post_processing_enabled: &mut bool,
+        post_processing: &mut PostProcessing,
+        section_enabled: &mut bool,
+        section: &mut Section,
+        attribute_colors_enabled: &mut bool,
+        attribute_colors_name: &mut Option<String>,
+        attribute_colors_ramp: &mut ColorRamp,
+        attribute_names: &[String],
+        assign_material_on_click: &mut bool,
+        assigned_material: &mut u32,
+        place_locator_on_click: &mut bool,
+        gizmo_mode: &mut GizmoMode,
+        camera_rotation_mode: &mut CameraRotationMode,
+        camera_projection: &mut CameraProjection,
+        mesh_shading: &mut MeshShading,
+        mesh_material: &mut Material,
+        mesh_selected: bool,
+        debug_view: &mut DebugView,
+        msaa: &mut Msaa,
+        capture_scale: &mut u32,
+        capture_path: &mut Option<String>,
+        camera_path: &mut CameraPath,
+        camera_path_fps: &mut u32,
+        locator_names: &[String],
+        camera_path_playing: bool,
+        camera_path_command: &mut Option<CameraPathCommand>,
+    ) -> bool {
         let ui = &self.imgui_ui;
 
-        const VIEWPORT_WINDOW_WIDTH: f32 = 150.0;
-        const VIEWPORT_WINDOW_HEIGHT: f32 = 150.0;
         let window_logical_size = ui.io().display_size;
         let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
 
@@ -295,8 +579,201 @@ impl<'a> UiFrame<'a> {
                     draw_mode,
                     DrawMeshMode::ShadedEdgesXray,
                 );
+                ui.radio_button(
+                    imgui::im_str!("Face Orientation"),
+                    draw_mode,
+                    DrawMeshMode::FaceOrientation,
+                );
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Show front faces blue and back faces red. \
+                         Click a face to revert it, shift+click to revert \
+                         its consistently wound patch",
+                    );
+                }
+                ui.checkbox(imgui::im_str!("Topology"), topology);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Show mesh edges and vertices. Border edges are \
+                         orange, non-manifold edges red",
+                    );
+                }
+                ui.checkbox(imgui::im_str!("Previous result"), show_previous_result);
+                ui.checkbox(imgui::im_str!("What changed"), show_changes);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Show faces added since the previous result green, \
+                         moved yellow and removed red",
+                    );
+                }
+                ui.checkbox(imgui::im_str!("Pipeline stages"), show_pipeline_stages);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Show the results of earlier operations as \
+                         transparent ghosts behind the final result",
+                    );
+                }
+                ui.checkbox(imgui::im_str!("Compare pinned"), compare_with_pinned);
+                ui.checkbox(imgui::im_str!("Grid"), ground_grid);
+                ui.same_line(0.0);
+                ui.checkbox(imgui::im_str!("Axes"), axes);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Show the world axes in the corner of the viewport");
+                }
+                ui.checkbox(imgui::im_str!("Orbit in F11 mode"), auto_orbit);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Orbit the camera in the presentation mode, \
+                         which is toggled with F11",
+                    );
+                }
+                imgui::Slider::new(imgui::im_str!("Explode"), 0.0..=2.0)
+                    .build(ui, exploded_view_factor);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Move the meshes apart from the center of the scene");
+                }
 
                 reset_viewport_clicked = ui.button(imgui::im_str!("Reset Viewport"), [0.0, 0.0]);
+                if ui.button(imgui::im_str!("Background..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Background"));
+                }
+                ui.popup(imgui::im_str!("Background"), || {
+                    draw_background_settings(ui, background, environment_path, ground_shadow);
+                });
+                if ui.button(imgui::im_str!("Render Effects..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Render Effects"));
+                }
+                ui.popup(imgui::im_str!("Render Effects"), || {
+                    draw_post_processing_settings(ui, post_processing_enabled, post_processing);
+                });
+                if ui.button(imgui::im_str!("Section..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Section"));
+                }
+                ui.popup(imgui::im_str!("Section"), || {
+                    draw_section_settings(ui, section_enabled, section);
+                });
+                if ui.button(imgui::im_str!("Attribute Colors..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Attribute Colors"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Color the meshes by a vertex attribute, e.g. \
+                         curvature or deviation from another mesh",
+                    );
+                }
+                ui.popup(imgui::im_str!("Attribute Colors"), || {
+                    draw_attribute_color_settings(
+                        ui,
+                        attribute_colors_enabled,
+                        attribute_colors_name,
+                        attribute_colors_ramp,
+                        attribute_names,
+                    );
+                });
+                if ui.button(imgui::im_str!("Face Materials..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Face Materials"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Assign material indices to faces, exported as \
+                         separate submeshes to OBJ and glTF",
+                    );
+                }
+                ui.popup(imgui::im_str!("Face Materials"), || {
+                    draw_face_material_settings(ui, assign_material_on_click, assigned_material);
+                });
+                ui.checkbox(imgui::im_str!("Place locators"), place_locator_on_click);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Click a mesh to add a locator on its surface, \
+                         usable by the Locator operations",
+                    );
+                }
+                if ui.button(imgui::im_str!("Gizmo..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Gizmo"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Drag the gizmo handles in the viewport to edit \
+                         the Transform of the selected mesh",
+                    );
+                }
+                ui.popup(imgui::im_str!("Gizmo"), || {
+                    ui.radio_button(imgui::im_str!("Move"), gizmo_mode, GizmoMode::Translate);
+                    ui.radio_button(imgui::im_str!("Rotate"), gizmo_mode, GizmoMode::Rotate);
+                    ui.radio_button(imgui::im_str!("Scale"), gizmo_mode, GizmoMode::Scale);
+                });
+                if ui.button(imgui::im_str!("Camera..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Camera"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Numpad 1, 3 and 7 look from the front, right and top, \
+                         Numpad 5 toggles the orthographic projection",
+                    );
+                }
+                ui.popup(imgui::im_str!("Camera"), || {
+                    ui.radio_button(
+                        imgui::im_str!("Turntable"),
+                        camera_rotation_mode,
+                        CameraRotationMode::Turntable,
+                    );
+                    ui.radio_button(
+                        imgui::im_str!("Arcball"),
+                        camera_rotation_mode,
+                        CameraRotationMode::Arcball,
+                    );
+                    ui.separator();
+                    ui.radio_button(
+                        imgui::im_str!("Perspective"),
+                        camera_projection,
+                        CameraProjection::Perspective,
+                    );
+                    ui.radio_button(
+                        imgui::im_str!("Orthographic"),
+                        camera_projection,
+                        CameraProjection::Orthographic,
+                    );
+                });
+                if ui.button(imgui::im_str!("Shading..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Shading"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Shade all meshes, or only the selected mesh, \
+                         flat or with a different matcap and material",
+                    );
+                }
+                ui.popup(imgui::im_str!("Shading"), || {
+                    draw_shading_settings(ui, mesh_shading, mesh_material, mesh_selected);
+                });
+                draw_debug_view_combo(ui, debug_view);
+                draw_msaa_combo(ui, msaa);
+                if ui.button(imgui::im_str!("Save Image..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Save Image"));
+                }
+                ui.popup(imgui::im_str!("Save Image"), || {
+                    draw_capture_settings(ui, capture_scale, capture_path);
+                });
+                if ui.button(imgui::im_str!("Camera Path..."), [0.0, 0.0]) {
+                    ui.open_popup(imgui::im_str!("Camera Path"));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Fly the camera through keyframed views and locators, \
+                         preview the flight and render it to images",
+                    );
+                }
+                ui.popup(imgui::im_str!("Camera Path"), || {
+                    draw_camera_path_settings(
+                        ui,
+                        camera_path,
+                        camera_path_fps,
+                        locator_names,
+                        camera_path_playing,
+                        camera_path_command,
+                    );
+                });
                 regular_font_token.pop(ui);
             });
         bold_font_token.pop(ui);
@@ -304,189 +781,1558 @@ impl<'a> UiFrame<'a> {
         reset_viewport_clicked
     }
 
-    pub fn draw_pipeline_window(&self, session: &mut Session) {
+    /// Draws a window for saving the current pipeline as a named
+    /// variant and recalling saved variants, either from a dropdown or
+    /// a gallery of all variants.
+    ///
+    /// The selected variant can be exported to USDZ for review in AR,
+    /// if its results are known. These are either the results of a
+    /// sweep, or the current `scene_meshes`, if the variant is the
+    /// current program.
+    pub fn draw_variants_window(&self, session: &mut Session, scene_meshes: &[Arc<Mesh>]) {
         let ui = &self.imgui_ui;
-        self.console_state
-            .borrow_mut()
-            .resize_with(session.stmts().len(), Default::default);
-
-        let function_table = session.function_table();
-
-        const PIPELINE_WINDOW_WIDTH: f32 = 400.0;
-        const PIPELINE_WINDOW_HEIGHT_MULT: f32 = 0.7;
+        let mut variants_state = self.variants_state.borrow_mut();
 
+        const VARIANTS_WINDOW_WIDTH: f32 = 250.0;
         let window_logical_size = ui.io().display_size;
-        let window_inner_height = window_logical_size[1] - 2.0 * MARGIN;
-
-        let pipeline_window_height = window_inner_height * PIPELINE_WINDOW_HEIGHT_MULT;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
 
         let interpreter_busy = session.interpreter_busy();
-        let mut change = None;
+        let mut save_clicked = false;
+        let mut recalled_variant_index = None;
+        let mut removed_variant_index = None;
+        let mut exported_meshes = None;
 
         let bold_font_token = ui.push_font(self.font_ids.bold);
-        imgui::Window::new(imgui::im_str!("Pipeline"))
+        imgui::Window::new(imgui::im_str!("Variants"))
             .movable(false)
             .resizable(false)
             .collapsible(false)
-            .size([PIPELINE_WINDOW_WIDTH, pipeline_window_height], imgui::Condition::Always)
-            .position([MARGIN, MARGIN], imgui::Condition::Always)
+            .size(
+                [VARIANTS_WINDOW_WIDTH, VIEWPORT_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width - VIEWPORT_WINDOW_WIDTH - VARIANTS_WINDOW_WIDTH,
+                    MARGIN,
+                ],
+                imgui::Condition::Always,
+            )
             .build(ui, || {
                 let regular_font_token = ui.push_font(self.font_ids.regular);
-                for (stmt_index, stmt) in session.stmts().iter().enumerate() {
-                    match stmt {
-                        ast::Stmt::VarDecl(var_decl) => {
-                            let call_expr = var_decl.init_expr();
-                            let func_ident = call_expr.ident();
-                            let func = &function_table[&func_ident];
 
-                            if ui
-                                .collapsing_header(&imgui::im_str!(
-                                    "#{} {} ##{}",
-                                    stmt_index + 1,
-                                    func.info().name,
-                                    stmt_index
-                                ))
-                                .default_open(true)
-                                .build()
-                            {
-                                ui.indent();
+                ui.input_text(
+                    imgui::im_str!("##Variant name"),
+                    &mut variants_state.name_buffer,
+                )
+                .build();
+                ui.same_line(0.0);
+                save_clicked = ui.button(imgui::im_str!("Save"), [0.0, 0.0]);
 
-                                assert_eq!(
-                                    call_expr.args().len(),
-                                    func.param_info().len(),
-                                    "Function call must be generated with correct number of arguments",
-                                );
+                let variants = session.variants();
+                if variants.is_empty() {
+                    ui.text(imgui::im_str!("No variants saved"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
 
-                                let operation_arg_style_tokens = if interpreter_busy {
-                                    Some(push_disabled_style(ui))
-                                } else {
-                                    None
-                                };
+                let variant_names: Vec<_> = variants
+                    .iter()
+                    .map(|variant| imgui::ImString::new(variant.name()))
+                    .collect();
+                let variant_name_refs: Vec<_> = variant_names.iter().collect();
 
-                                for (arg_index, (param_info, arg)) in func
-                                    .param_info()
-                                    .iter()
-                                    .zip(call_expr.args().iter())
-                                    .enumerate()
-                                {
-                                    let input_label = imgui::im_str!(
-                                        "{}##{}-{}",
-                                        &param_info.name,
-                                        stmt_index,
-                                        arg_index
-                                    );
+                if variants_state.selected_variant_index >= variants.len() {
+                    variants_state.selected_variant_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("##Variant")).build_simple_string(
+                    ui,
+                    &mut variants_state.selected_variant_index,
+                    &variant_name_refs,
+                );
 
-                                    match param_info.refinement {
-                                        ParamRefinement::Boolean(_) => {
-                                            let mut boolean_lit =
-                                                arg.unwrap_literal().unwrap_boolean();
+                let tokens = if interpreter_busy {
+                    Some(push_disabled_style(ui))
+                } else {
+                    None
+                };
 
-                                            if ui.checkbox(&input_label, &mut boolean_lit) {
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Boolean(
-                                                        boolean_lit,
-                                                    )),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::Int(param_refinement_int) => {
-                                            let mut int_lit = arg.unwrap_literal().unwrap_int();
+                if ui.button(imgui::im_str!("Recall"), [0.0, 0.0]) {
+                    recalled_variant_index = Some(variants_state.selected_variant_index);
+                }
+                ui.same_line(0.0);
+                if ui.button(imgui::im_str!("Delete"), [0.0, 0.0]) {
+                    removed_variant_index = Some(variants_state.selected_variant_index);
+                }
 
-                                            if ui.input_int(&input_label, &mut int_lit)
-                                                .read_only(interpreter_busy)
-                                                .build()
-                                            {
-                                                int_lit = param_refinement_int.clamp(int_lit);
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Int(int_lit)),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::Uint(param_refinement_uint) => {
-                                            let uint_lit = arg.unwrap_literal().unwrap_uint();
-                                            let mut int_value = clamp_cast_u32_to_i32(uint_lit);
+                let selected_variant = &variants[variants_state.selected_variant_index];
+                let selected_variant_meshes = if !selected_variant.results().is_empty() {
+                    Some(selected_variant.results())
+                } else if selected_variant.prog() == session.prog() && !scene_meshes.is_empty() {
+                    Some(scene_meshes)
+                } else {
+                    None
+                };
 
-                                            if ui.input_int(&input_label, &mut int_value)
-                                                .read_only(interpreter_busy)
-                                                .build()
-                                            {
-                                                let uint_value = clamp_cast_i32_to_u32(int_value);
-                                                let uint_value = param_refinement_uint.clamp(uint_value);
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Uint(uint_value)),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::Float(param_refinement_float) => {
-                                            let mut float_lit = arg.unwrap_literal().unwrap_float();
+                ui.same_line(0.0);
+                match selected_variant_meshes {
+                    Some(meshes) => {
+                        if ui.button(imgui::im_str!("Export USDZ"), [0.0, 0.0]) {
+                            exported_meshes =
+                                Some((selected_variant.name().to_string(), meshes.to_vec()));
+                        }
+                    }
+                    None => {
+                        let (color_token, style_token) = push_disabled_style(ui);
+                        ui.button(imgui::im_str!("Export USDZ"), [0.0, 0.0]);
+                        color_token.pop(ui);
+                        style_token.pop(ui);
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Recall the variant to evaluate it first");
+                        }
+                    }
+                }
 
-                                            if ui.input_float(&input_label, &mut float_lit)
-                                                .read_only(interpreter_busy)
-                                                .build()
-                                            {
-                                                float_lit = param_refinement_float.clamp(float_lit);
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Float(float_lit)),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::Float2(param_refinement_float2) => {
-                                            let mut float2_lit =
-                                                arg.unwrap_literal().unwrap_float2();
+                if ui
+                    .collapsing_header(imgui::im_str!("Gallery"))
+                    .default_open(false)
+                    .build()
+                {
+                    ui.columns(2, imgui::im_str!("Gallery columns"), false);
+                    for (variant_index, variant) in variants.iter().enumerate() {
+                        let button_label = if variant.results().is_empty() {
+                            imgui::im_str!(
+                                "{}\n{} operations##gallery-{}",
+                                variant.name(),
+                                variant.prog().stmts().len(),
+                                variant_index,
+                            )
+                        } else {
+                            let face_count: usize = variant
+                                .results()
+                                .iter()
+                                .map(|mesh| mesh.faces().len())
+                                .sum();
+                            imgui::im_str!(
+                                "{}\n{} meshes, {} faces##gallery-{}",
+                                variant.name(),
+                                variant.results().len(),
+                                face_count,
+                                variant_index,
+                            )
+                        };
+                        if ui.button(&button_label, [-f32::MIN_POSITIVE, 40.0]) {
+                            recalled_variant_index = Some(variant_index);
+                        }
+                        ui.next_column();
+                    }
+                    ui.columns(1, imgui::im_str!("Gallery columns end"), false);
+                }
 
-                                            if ui
-                                                .input_float2(&input_label, &mut float2_lit)
-                                                .read_only(interpreter_busy)
-                                                .build()
-                                            {
-                                                float2_lit = param_refinement_float2.clamp(float2_lit);
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Float2(
-                                                        float2_lit,
-                                                    )),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::Float3(param_refinement_float3) => {
-                                            let mut float3_lit =
-                                                arg.unwrap_literal().unwrap_float3();
+                if let Some((color_token, style_token)) = tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
 
-                                            if ui
-                                                .input_float3(&input_label, &mut float3_lit)
-                                                .read_only(interpreter_busy)
-                                                .build()
-                                            {
-                                                float3_lit = param_refinement_float3.clamp(float3_lit);
-                                                change = Some((
-                                                    stmt_index,
-                                                    arg_index,
-                                                    ast::Expr::Lit(ast::LitExpr::Float3(
-                                                        float3_lit,
-                                                    )),
-                                                ));
-                                            }
-                                        }
-                                        ParamRefinement::String(param_refinement_string) => {
-                                            let mut imstring_buffer = self.global_imstring_buffer
-                                                .borrow_mut();
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
 
-                                            let string_lit = arg.unwrap_literal().unwrap_string();
-                                            imstring_buffer.push_str(string_lit);
+        if save_clicked {
+            let name = variants_state.name_buffer.to_str().trim();
+            let name = if name.is_empty() {
+                format!("Variant {}", session.variants().len() + 1)
+            } else {
+                name.to_string()
+            };
 
-                                            if param_refinement_string.file_path {
-                                                if file_input(
-                                                    ui,
+            session.save_variant(name);
+            variants_state.name_buffer.clear();
+        }
+
+        if let Some((name, meshes)) = exported_meshes {
+            export_variant_usdz(&name, &meshes);
+        }
+
+        // Recalling replaces the program, which is not allowed while
+        // the interpreter is running
+        if !interpreter_busy {
+            if let Some(variant_index) = recalled_variant_index {
+                variants_state.selected_variant_index = variant_index;
+                session.recall_variant(variant_index);
+            }
+
+            if let Some(variant_index) = removed_variant_index {
+                session.remove_variant(variant_index);
+            }
+        }
+    }
+
+    /// Draws a window for optimizing numeric operation parameters to
+    /// minimize or maximize a metric of a mesh variable. The best
+    /// results are saved as variants.
+    pub fn draw_optimizer_window(&self, session: &mut Session) {
+        let ui = &self.imgui_ui;
+        let mut optimizer_state = self.optimizer_state.borrow_mut();
+
+        const OPTIMIZER_WINDOW_WIDTH: f32 = 300.0;
+        const OPTIMIZER_BEST_COUNT: usize = 5;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let interpreter_busy = session.interpreter_busy();
+        let stmt_count = session.stmts().len();
+        let targets: Vec<_> = session
+            .visible_vars_at_stmt(stmt_count, Ty::Mesh)
+            .chain(session.visible_vars_at_stmt(stmt_count, Ty::MeshArray))
+            .collect();
+
+        let mut optimize_clicked = false;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Optimizer"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [OPTIMIZER_WINDOW_WIDTH, INSPECTOR_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width - 2.0 * OPTIMIZER_WINDOW_WIDTH,
+                    2.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                if targets.is_empty() {
+                    ui.text(imgui::im_str!("No meshes to optimize"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
+
+                let target_names: Vec<_> = targets
+                    .iter()
+                    .map(|var_ident| {
+                        let name = session
+                            .var_name_for_ident(*var_ident)
+                            .unwrap_or("<Removed>");
+                        format_var_name(name, *var_ident, false)
+                    })
+                    .collect();
+                let target_name_refs: Vec<_> = target_names.iter().collect();
+                if optimizer_state.selected_target_index >= targets.len() {
+                    optimizer_state.selected_target_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("Target")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.selected_target_index,
+                    &target_name_refs,
+                );
+
+                let metric_names: Vec<_> = Metric::NAMES
+                    .iter()
+                    .map(|name| imgui::ImString::new(*name))
+                    .collect();
+                let metric_name_refs: Vec<_> = metric_names.iter().collect();
+                imgui::ComboBox::new(imgui::im_str!("Metric")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.selected_metric_index,
+                    &metric_name_refs,
+                );
+
+                let method_names: Vec<_> = Method::NAMES
+                    .iter()
+                    .map(|name| imgui::ImString::new(*name))
+                    .collect();
+                let method_name_refs: Vec<_> = method_names.iter().collect();
+                imgui::ComboBox::new(imgui::im_str!("Method")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.selected_method_index,
+                    &method_name_refs,
+                );
+
+                ui.checkbox(imgui::im_str!("Maximize"), &mut optimizer_state.maximize);
+                ui.input_int(
+                    imgui::im_str!("Iterations"),
+                    &mut optimizer_state.iterations,
+                )
+                .build();
+                optimizer_state.iterations = optimizer_state.iterations.max(1);
+
+                ui.separator();
+                ui.text(imgui::im_str!("Parameters"));
+                self.draw_optimizer_params(session, &mut optimizer_state.param_ranges);
+                ui.separator();
+
+                let tokens = if interpreter_busy || optimizer_state.param_ranges.is_empty() {
+                    Some(push_disabled_style(ui))
+                } else {
+                    None
+                };
+                let optimize_label = if session.optimizing() {
+                    imgui::im_str!("Optimizing...")
+                } else {
+                    imgui::im_str!("Optimize")
+                };
+                optimize_clicked = ui.button(&optimize_label, [-f32::MIN_POSITIVE, 25.0]);
+                if let Some((color_token, style_token)) = tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
+
+        if optimize_clicked && !interpreter_busy && !optimizer_state.param_ranges.is_empty() {
+            session.optimize(OptimizationRequest {
+                params: optimized_params(&optimizer_state.param_ranges),
+                target_var: targets[optimizer_state.selected_target_index],
+                metric: Metric::ALL[optimizer_state.selected_metric_index],
+                options: optimizer::Options {
+                    goal: if optimizer_state.maximize {
+                        Goal::Maximize
+                    } else {
+                        Goal::Minimize
+                    },
+                    method: Method::ALL[optimizer_state.selected_method_index],
+                    iterations: clamp_cast_i32_to_u32(optimizer_state.iterations),
+                    best_count: OPTIMIZER_BEST_COUNT,
+                    seed: 0,
+                },
+            });
+        }
+    }
+
+    /// Draws a window for distributing a sweep over a grid of the
+    /// parameters selected in the optimizer window to remote workers.
+    /// Returns the named programs of the sweep, if it was started.
+    pub fn draw_sweep_window(
+        &self,
+        session: &Session,
+        status: SweepStatus,
+    ) -> Option<Vec<(String, ast::Prog)>> {
+        let ui = &self.imgui_ui;
+        let mut optimizer_state = self.optimizer_state.borrow_mut();
+
+        const SWEEP_WINDOW_WIDTH: f32 = 300.0;
+        const SWEEP_WINDOW_HEIGHT: f32 = 170.0;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let param_count = optimizer_state.param_ranges.len();
+        let mut start_clicked = false;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Distributed Sweep"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [SWEEP_WINDOW_WIDTH, SWEEP_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width - 3.0 * SWEEP_WINDOW_WIDTH,
+                    2.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                ui.text_wrapped(&imgui::im_str!(
+                    "Sweeps the {} parameters selected in the optimizer.",
+                    param_count,
+                ));
+                ui.input_int(imgui::im_str!("Steps"), &mut optimizer_state.sweep_steps)
+                    .build();
+                optimizer_state.sweep_steps = optimizer_state.sweep_steps.max(1);
+
+                let job_count = sweep_job_count(optimizer_state.sweep_steps, param_count);
+                match job_count {
+                    Some(job_count) => ui.text(&imgui::im_str!("{} jobs", job_count)),
+                    None => ui.text(imgui::im_str!("Too many jobs")),
+                }
+
+                let disabled = param_count == 0 || job_count.is_none();
+                let tokens = if disabled {
+                    Some(push_disabled_style(ui))
+                } else {
+                    None
+                };
+                start_clicked = ui
+                    .button(imgui::im_str!("Start Sweep"), [-f32::MIN_POSITIVE, 25.0])
+                    && !disabled;
+                if let Some((color_token, style_token)) = tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+
+                ui.text(&imgui::im_str!(
+                    "Workers: {}, queued: {}, running: {}, completed: {}",
+                    status.workers,
+                    status.queued,
+                    status.running,
+                    status.completed,
+                ));
+
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
+
+        if start_clicked {
+            Some(sweep::sweep_progs(
+                session.prog(),
+                &optimized_params(&optimizer_state.param_ranges),
+                clamp_cast_i32_to_u32(optimizer_state.sweep_steps),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Draws a window for exploring the trade-offs between two or three
+    /// objectives by varying the parameters selected in the optimizer
+    /// window. The resulting Pareto front is shown in a scatter plot,
+    /// where clicking a point recalls its program.
+    pub fn draw_pareto_window(&self, session: &mut Session) {
+        let ui = &self.imgui_ui;
+        let mut optimizer_state = self.optimizer_state.borrow_mut();
+
+        const PARETO_WINDOW_WIDTH: f32 = 300.0;
+        const PARETO_WINDOW_HEIGHT: f32 = 440.0;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let interpreter_busy = session.interpreter_busy();
+        let stmt_count = session.stmts().len();
+        let targets: Vec<_> = session
+            .visible_vars_at_stmt(stmt_count, Ty::Mesh)
+            .chain(session.visible_vars_at_stmt(stmt_count, Ty::MeshArray))
+            .collect();
+
+        let mut explore_clicked = false;
+        let mut recalled_point_index = None;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Pareto Front"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [PARETO_WINDOW_WIDTH, PARETO_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width - 2.0 * PARETO_WINDOW_WIDTH,
+                    3.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT + INSPECTOR_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                if targets.is_empty() {
+                    ui.text(imgui::im_str!("No meshes to explore"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
+
+                let target_names: Vec<_> = targets
+                    .iter()
+                    .map(|var_ident| {
+                        let name = session
+                            .var_name_for_ident(*var_ident)
+                            .unwrap_or("<Removed>");
+                        format_var_name(name, *var_ident, false)
+                    })
+                    .collect();
+
+                let objective_count = if optimizer_state.pareto_third_objective {
+                    3
+                } else {
+                    2
+                };
+                for (index, objective_state) in optimizer_state.pareto_objectives[..objective_count]
+                    .iter_mut()
+                    .enumerate()
+                {
+                    pareto_objective_input(ui, index, &target_names, objective_state);
+                }
+
+                ui.checkbox(
+                    imgui::im_str!("Third objective"),
+                    &mut optimizer_state.pareto_third_objective,
+                );
+                ui.input_int(
+                    imgui::im_str!("Iterations##pareto"),
+                    &mut optimizer_state.pareto_iterations,
+                )
+                .build();
+                optimizer_state.pareto_iterations = optimizer_state.pareto_iterations.max(1);
+
+                let tokens = if interpreter_busy || optimizer_state.param_ranges.is_empty() {
+                    Some(push_disabled_style(ui))
+                } else {
+                    None
+                };
+                let explore_label = if session.optimizing() {
+                    imgui::im_str!("Exploring...")
+                } else {
+                    imgui::im_str!("Explore")
+                };
+                explore_clicked = ui.button(&explore_label, [-f32::MIN_POSITIVE, 25.0]);
+                if let Some((color_token, style_token)) = tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+
+                ui.separator();
+
+                if let Some(front) = session.pareto_front() {
+                    recalled_point_index =
+                        self.draw_pareto_scatter(front, &mut optimizer_state, session);
+                } else if optimizer_state.param_ranges.is_empty() {
+                    ui.text_wrapped(imgui::im_str!(
+                        "Select parameters to vary in the Optimizer window"
+                    ));
+                }
+
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
+
+        if interpreter_busy {
+            return;
+        }
+
+        if let Some(index) = recalled_point_index {
+            session.recall_pareto_point(index);
+        } else if explore_clicked && !optimizer_state.param_ranges.is_empty() {
+            let objective_count = if optimizer_state.pareto_third_objective {
+                3
+            } else {
+                2
+            };
+            let objectives = optimizer_state.pareto_objectives[..objective_count]
+                .iter()
+                .map(|objective_state| Objective {
+                    target_var: targets[objective_state.selected_target_index],
+                    metric: Metric::ALL[objective_state.selected_metric_index],
+                    goal: if objective_state.maximize {
+                        Goal::Maximize
+                    } else {
+                        Goal::Minimize
+                    },
+                })
+                .collect();
+
+            session.explore_pareto_front(ParetoRequest {
+                params: optimized_params(&optimizer_state.param_ranges),
+                objectives,
+                iterations: clamp_cast_i32_to_u32(optimizer_state.pareto_iterations),
+                seed: 0,
+            });
+        }
+    }
+
+    /// Draws the points of the Pareto front as a scatter plot of two
+    /// selectable objectives. Returns the index of the clicked point,
+    /// if any.
+    fn draw_pareto_scatter(
+        &self,
+        front: &ParetoFront,
+        optimizer_state: &mut OptimizerState,
+        session: &Session,
+    ) -> Option<usize> {
+        const PLOT_HEIGHT: f32 = 160.0;
+        const PLOT_PADDING: f32 = 8.0;
+        const POINT_RADIUS: f32 = 4.0;
+        let ui = &self.imgui_ui;
+
+        let objective_names: Vec<_> = front
+            .objectives
+            .iter()
+            .map(|objective| {
+                let name = session
+                    .var_name_for_ident(objective.target_var)
+                    .unwrap_or("<Removed>");
+                imgui::im_str!(
+                    "{} of {} #{}",
+                    Metric::NAMES[metric_index(objective.metric)],
+                    name,
+                    objective.target_var.0 + 1,
+                )
+            })
+            .collect();
+        let objective_name_refs: Vec<_> = objective_names.iter().collect();
+
+        if optimizer_state.pareto_x_axis >= objective_names.len() {
+            optimizer_state.pareto_x_axis = 0;
+        }
+        if optimizer_state.pareto_y_axis >= objective_names.len() {
+            optimizer_state.pareto_y_axis = 1;
+        }
+        imgui::ComboBox::new(imgui::im_str!("X Axis")).build_simple_string(
+            ui,
+            &mut optimizer_state.pareto_x_axis,
+            &objective_name_refs,
+        );
+        imgui::ComboBox::new(imgui::im_str!("Y Axis")).build_simple_string(
+            ui,
+            &mut optimizer_state.pareto_y_axis,
+            &objective_name_refs,
+        );
+
+        if front.points.is_empty() {
+            ui.text(imgui::im_str!("No valid results found"));
+            return None;
+        }
+
+        let x_axis = optimizer_state.pareto_x_axis;
+        let y_axis = optimizer_state.pareto_y_axis;
+        let bounds = |axis: usize| {
+            front
+                .points
+                .iter()
+                .map(|point| point.scores[axis])
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), score| {
+                    (f32::min(min, score), f32::max(max, score))
+                })
+        };
+        let (x_min, x_max) = bounds(x_axis);
+        let (y_min, y_max) = bounds(y_axis);
+
+        let plot_position = ui.cursor_screen_pos();
+        let plot_width = ui.content_region_avail()[0];
+        ui.invisible_button(
+            imgui::im_str!("##Pareto scatter"),
+            [plot_width, PLOT_HEIGHT],
+        );
+        let plot_hovered = ui.is_item_hovered();
+        let plot_clicked = ui.is_item_clicked(imgui::MouseButton::Left);
+
+        // Degenerate ranges are drawn in the middle of the plot
+        let to_plot = |value: f32, min: f32, max: f32| {
+            if max > min {
+                (value - min) / (max - min)
+            } else {
+                0.5
+            }
+        };
+
+        let draw_list = ui.get_window_draw_list();
+        draw_list
+            .add_rect(
+                plot_position,
+                [
+                    plot_position[0] + plot_width,
+                    plot_position[1] + PLOT_HEIGHT,
+                ],
+                self.colors.special_button,
+            )
+            .build();
+
+        let mouse_position = ui.io().mouse_pos;
+        let mut hovered_point_index = None;
+        for (index, point) in front.points.iter().enumerate() {
+            // The y axis grows upwards, unlike the screen coordinates
+            let x = to_plot(point.scores[x_axis], x_min, x_max);
+            let y = 1.0 - to_plot(point.scores[y_axis], y_min, y_max);
+            let center = [
+                plot_position[0] + PLOT_PADDING + x * (plot_width - 2.0 * PLOT_PADDING),
+                plot_position[1] + PLOT_PADDING + y * (PLOT_HEIGHT - 2.0 * PLOT_PADDING),
+            ];
+
+            let distance = (mouse_position[0] - center[0]).hypot(mouse_position[1] - center[1]);
+            let hovered = plot_hovered && distance <= 2.0 * POINT_RADIUS;
+            if hovered {
+                hovered_point_index = Some(index);
+            }
+
+            let color = if hovered {
+                self.colors.special_button_hovered
+            } else {
+                self.colors.special_button_text
+            };
+            draw_list
+                .add_circle(center, POINT_RADIUS, color)
+                .filled(true)
+                .build();
+        }
+
+        if let Some(index) = hovered_point_index {
+            let mut tooltip = format!("Point {}", index + 1);
+            for (name, score) in objective_names.iter().zip(&front.points[index].scores) {
+                tooltip.push_str(&format!("\n{}: {:.4}", name.to_str(), score));
+            }
+            ui.tooltip_text(tooltip);
+        }
+
+        ui.text(imgui::im_str!(
+            "{} points, click a point to recall it",
+            front.points.len(),
+        ));
+
+        if plot_clicked {
+            hovered_point_index
+        } else {
+            None
+        }
+    }
+
+    /// Draws a window for recording a metric of a variable while
+    /// sweeping one of the parameters selected in the optimizer
+    /// window. The recorded values are shown in a line or scatter
+    /// chart.
+    pub fn draw_plot_window(&self, session: &mut Session) {
+        let ui = &self.imgui_ui;
+        let mut optimizer_state = self.optimizer_state.borrow_mut();
+
+        const PLOT_WINDOW_WIDTH: f32 = 300.0;
+        const PLOT_WINDOW_HEIGHT: f32 = 440.0;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let interpreter_busy = session.interpreter_busy();
+        let stmt_count = session.stmts().len();
+        let targets: Vec<_> = session
+            .visible_vars_at_stmt(stmt_count, Ty::Mesh)
+            .chain(session.visible_vars_at_stmt(stmt_count, Ty::MeshArray))
+            .collect();
+        let mut params = optimized_params(&optimizer_state.param_ranges);
+        params.sort_by_key(|param| (param.stmt_index, param.arg_index, param.component));
+
+        let mut record_clicked = false;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Plot"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [PLOT_WINDOW_WIDTH, PLOT_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width - 3.0 * PLOT_WINDOW_WIDTH,
+                    3.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT + INSPECTOR_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                if targets.is_empty() {
+                    ui.text(imgui::im_str!("No meshes to plot"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
+                if params.is_empty() {
+                    ui.text_wrapped(imgui::im_str!(
+                        "Select parameters to vary in the Optimizer window"
+                    ));
+                    regular_font_token.pop(ui);
+                    return;
+                }
+
+                let param_names: Vec<_> = params
+                    .iter()
+                    .map(|param| {
+                        optimized_param_name(session, param)
+                            .expect("Optimized parameters must exist in the program")
+                    })
+                    .collect();
+                let param_name_refs: Vec<_> = param_names.iter().collect();
+                if optimizer_state.plot_param_index >= params.len() {
+                    optimizer_state.plot_param_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("Parameter##plot")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.plot_param_index,
+                    &param_name_refs,
+                );
+
+                let target_names: Vec<_> = targets
+                    .iter()
+                    .map(|var_ident| {
+                        let name = session
+                            .var_name_for_ident(*var_ident)
+                            .unwrap_or("<Removed>");
+                        format_var_name(name, *var_ident, false)
+                    })
+                    .collect();
+                let target_name_refs: Vec<_> = target_names.iter().collect();
+                if optimizer_state.plot_target_index >= targets.len() {
+                    optimizer_state.plot_target_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("Target##plot")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.plot_target_index,
+                    &target_name_refs,
+                );
+
+                let metric_names: Vec<_> = Metric::NAMES
+                    .iter()
+                    .map(|name| imgui::ImString::new(*name))
+                    .collect();
+                let metric_name_refs: Vec<_> = metric_names.iter().collect();
+                imgui::ComboBox::new(imgui::im_str!("Metric##plot")).build_simple_string(
+                    ui,
+                    &mut optimizer_state.plot_metric_index,
+                    &metric_name_refs,
+                );
+
+                ui.input_int(
+                    imgui::im_str!("Steps##plot"),
+                    &mut optimizer_state.plot_steps,
+                )
+                .build();
+                optimizer_state.plot_steps = optimizer_state.plot_steps.max(1);
+
+                let tokens = if interpreter_busy {
+                    Some(push_disabled_style(ui))
+                } else {
+                    None
+                };
+                let record_label = if session.optimizing() {
+                    imgui::im_str!("Recording...")
+                } else {
+                    imgui::im_str!("Record")
+                };
+                record_clicked = ui.button(&record_label, [-f32::MIN_POSITIVE, 25.0]);
+                if let Some((color_token, style_token)) = tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+
+                ui.separator();
+
+                if let Some(plot) = session.plot() {
+                    ui.checkbox(imgui::im_str!("Lines"), &mut optimizer_state.plot_lines);
+                    self.draw_plot_chart(plot, optimizer_state.plot_lines, session);
+                }
+
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
+
+        if record_clicked && !interpreter_busy && !params.is_empty() && !targets.is_empty() {
+            session.record_plot(PlotRequest {
+                param: params[optimizer_state.plot_param_index],
+                target_var: targets[optimizer_state.plot_target_index],
+                metric: Metric::ALL[optimizer_state.plot_metric_index],
+                steps: clamp_cast_i32_to_u32(optimizer_state.plot_steps),
+            });
+        }
+    }
+
+    /// Draws the recorded points of the plot as a chart with the swept
+    /// parameter on the horizontal axis. Consecutive points are
+    /// connected, if `lines` is set.
+    fn draw_plot_chart(&self, plot: &Plot, lines: bool, session: &Session) {
+        const PLOT_HEIGHT: f32 = 160.0;
+        const PLOT_PADDING: f32 = 8.0;
+        const POINT_RADIUS: f32 = 3.0;
+        let ui = &self.imgui_ui;
+
+        let target_name = session
+            .var_name_for_ident(plot.target_var)
+            .unwrap_or("<Removed>");
+        let metric_name = Metric::NAMES[metric_index(plot.metric)];
+        let param_name = optimized_param_name(session, &plot.param)
+            .unwrap_or_else(|| imgui::ImString::new("<Removed>"));
+
+        if plot.points.is_empty() {
+            ui.text(imgui::im_str!("No valid results found"));
+            return;
+        }
+
+        let bounds = |axis: usize| {
+            plot.points
+                .iter()
+                .map(|point| point[axis])
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                    (f32::min(min, value), f32::max(max, value))
+                })
+        };
+        let (x_min, x_max) = bounds(0);
+        let (y_min, y_max) = bounds(1);
+
+        let plot_position = ui.cursor_screen_pos();
+        let plot_width = ui.content_region_avail()[0];
+        ui.invisible_button(imgui::im_str!("##Plot chart"), [plot_width, PLOT_HEIGHT]);
+        let plot_hovered = ui.is_item_hovered();
+
+        // Degenerate ranges are drawn in the middle of the plot
+        let to_plot = |value: f32, min: f32, max: f32| {
+            if max > min {
+                (value - min) / (max - min)
+            } else {
+                0.5
+            }
+        };
+        // The y axis grows upwards, unlike the screen coordinates
+        let to_screen = |point: &[f32; 2]| {
+            let x = to_plot(point[0], x_min, x_max);
+            let y = 1.0 - to_plot(point[1], y_min, y_max);
+            [
+                plot_position[0] + PLOT_PADDING + x * (plot_width - 2.0 * PLOT_PADDING),
+                plot_position[1] + PLOT_PADDING + y * (PLOT_HEIGHT - 2.0 * PLOT_PADDING),
+            ]
+        };
+
+        let draw_list = ui.get_window_draw_list();
+        draw_list
+            .add_rect(
+                plot_position,
+                [
+                    plot_position[0] + plot_width,
+                    plot_position[1] + PLOT_HEIGHT,
+                ],
+                self.colors.special_button,
+            )
+            .build();
+
+        if lines {
+            for segment in plot.points.windows(2) {
+                draw_list
+                    .add_line(
+                        to_screen(&segment[0]),
+                        to_screen(&segment[1]),
+                        self.colors.special_button_text,
+                    )
+                    .thickness(1.5)
+                    .build();
+            }
+        }
+
+        let mouse_position = ui.io().mouse_pos;
+        let mut hovered_point_index = None;
+        for (index, point) in plot.points.iter().enumerate() {
+            let center = to_screen(point);
+
+            let distance = (mouse_position[0] - center[0]).hypot(mouse_position[1] - center[1]);
+            let hovered = plot_hovered && distance <= 2.0 * POINT_RADIUS;
+            if hovered {
+                hovered_point_index = Some(index);
+            }
+
+            let color = if hovered {
+                self.colors.special_button_hovered
+            } else {
+                self.colors.special_button_text
+            };
+            draw_list
+                .add_circle(center, POINT_RADIUS, color)
+                .filled(true)
+                .build();
+        }
+
+        if let Some(index) = hovered_point_index {
+            let [x, y] = plot.points[index];
+            ui.tooltip_text(format!(
+                "{}: {:.4}\n{} of {}: {:.4}",
+                param_name.to_str(),
+                x,
+                metric_name,
+                target_name,
+                y,
+            ));
+        }
+
+        ui.text(imgui::im_str!(
+            "{} of {} #{}",
+            metric_name,
+            target_name,
+            plot.target_var.0 + 1,
+        ));
+        ui.text(imgui::im_str!("  from {:.4} to {:.4}", y_min, y_max));
+        ui.text(imgui::im_str!("over {}", param_name.to_str()));
+        ui.text(imgui::im_str!("  from {:.4} to {:.4}", x_min, x_max));
+    }
+
+    /// Draws a checkbox for each numeric literal parameter of the
+    /// program and a range input for each checked parameter.
+    fn draw_optimizer_params(
+        &self,
+        session: &Session,
+        param_ranges: &mut HashMap<(usize, usize, usize), [f32; 2]>,
+    ) {
+        const COMPONENT_NAMES: [&str; 3] = ["X", "Y", "Z"];
+        let ui = &self.imgui_ui;
+        let function_table = session.function_table();
+
+        // Forget parameters that no longer exist or changed type
+        let mut existing_params = Vec::new();
+
+        for (stmt_index, stmt) in session.stmts().iter().enumerate() {
+            match stmt {
+                ast::Stmt::VarDecl(var_decl) => {
+                    let call_expr = var_decl.init_expr();
+                    let func = &function_table[&call_expr.ident()];
+
+                    for (arg_index, (param_info, arg)) in func
+                        .param_info()
+                        .iter()
+                        .zip(call_expr.args().iter())
+                        .enumerate()
+                    {
+                        let lit = match arg {
+                            ast::Expr::Lit(lit) => lit,
+                            ast::Expr::Var(_) => continue,
+                        };
+
+                        let components = optimizer::numeric_components(&param_info.refinement, lit);
+                        let component_count = components.len();
+
+                        for numeric_component in components {
+                            let key = (stmt_index, arg_index, numeric_component.component);
+                            existing_params.push(key);
+
+                            let label = if component_count > 1 {
+                                imgui::im_str!(
+                                    "#{} {} {}##optimize-{}-{}-{}",
+                                    stmt_index + 1,
+                                    param_info.name,
+                                    COMPONENT_NAMES[numeric_component.component],
+                                    stmt_index,
+                                    arg_index,
+                                    numeric_component.component,
+                                )
+                            } else {
+                                imgui::im_str!(
+                                    "#{} {}##optimize-{}-{}-{}",
+                                    stmt_index + 1,
+                                    param_info.name,
+                                    stmt_index,
+                                    arg_index,
+                                    numeric_component.component,
+                                )
+                            };
+
+                            let mut selected = param_ranges.contains_key(&key);
+                            if ui.checkbox(&label, &mut selected) {
+                                if selected {
+                                    let range = numeric_component.default_range();
+                                    param_ranges.insert(key, [range.min, range.max]);
+                                } else {
+                                    param_ranges.remove(&key);
+                                }
+                            }
+
+                            if let Some(range) = param_ranges.get_mut(&key) {
+                                ui.input_float2(
+                                    &imgui::im_str!(
+                                        "Range##optimize-range-{}-{}-{}",
+                                        stmt_index,
+                                        arg_index,
+                                        numeric_component.component,
+                                    ),
+                                    range,
+                                )
+                                .build();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        param_ranges.retain(|key, _| existing_params.contains(key));
+    }
+
+    /// Draws a draggable vertical splitter across the whole screen at
+    /// the `position`, normalized to the `0..1` range of the screen
+    /// width.
+    pub fn draw_comparison_splitter(&self, position: &mut f32) {
+        let ui = &self.imgui_ui;
+
+        const SPLITTER_WIDTH: f32 = 8.0;
+        const SPLITTER_POSITION_MIN: f32 = 0.05;
+        const SPLITTER_POSITION_MAX: f32 = 0.95;
+
+        let [window_width, window_height] = ui.io().display_size;
+        if window_width <= 0.0 {
+            return;
+        }
+
+        let splitter_x = *position * window_width;
+
+        let style_token = ui.push_style_vars(&[
+            imgui::StyleVar::WindowPadding([0.0, 0.0]),
+            imgui::StyleVar::WindowBorderSize(0.0),
+        ]);
+        imgui::Window::new(imgui::im_str!("##Comparison splitter"))
+            .no_decoration()
+            .movable(false)
+            .draw_background(false)
+            .bring_to_front_on_focus(false)
+            .size([SPLITTER_WIDTH, window_height], imgui::Condition::Always)
+            .position(
+                [splitter_x - SPLITTER_WIDTH / 2.0, 0.0],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                ui.invisible_button(
+                    imgui::im_str!("##Comparison splitter handle"),
+                    [SPLITTER_WIDTH, window_height],
+                );
+
+                let hovered_or_active = ui.is_item_hovered() || ui.is_item_active();
+                if ui.is_item_active() {
+                    *position = math::clamp(
+                        *position + ui.io().mouse_delta[0] / window_width,
+                        SPLITTER_POSITION_MIN,
+                        SPLITTER_POSITION_MAX,
+                    );
+                }
+
+                let color = if hovered_or_active {
+                    self.colors.special_button_hovered
+                } else {
+                    self.colors.special_button
+                };
+                ui.get_window_draw_list()
+                    .add_line([splitter_x, 0.0], [splitter_x, window_height], color)
+                    .thickness(2.0)
+                    .build();
+            });
+        style_token.pop(ui);
+    }
+
+    /// Draws the gizmo handles over the viewport, behind all other
+    /// windows. Moving and scaling handles are told apart by their
+    /// tips.
+    pub fn draw_gizmo(&self, mode: GizmoMode, handles: &[GizmoHandle]) {
+        let ui = &self.imgui_ui;
+
+        const HANDLE_THICKNESS: f32 = 2.0;
+        const HANDLE_TIP_SIZE: f32 = 5.0;
+        const COLOR_HIGHLIGHTED: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+
+        let style_token = ui.push_style_vars(&[
+            imgui::StyleVar::WindowPadding([0.0, 0.0]),
+            imgui::StyleVar::WindowBorderSize(0.0),
+        ]);
+        imgui::Window::new(imgui::im_str!("##Gizmo"))
+            .no_decoration()
+            .no_inputs()
+            .movable(false)
+            .draw_background(false)
+            .bring_to_front_on_focus(false)
+            .focus_on_appearing(false)
+            .size(ui.io().display_size, imgui::Condition::Always)
+            .position([0.0, 0.0], imgui::Condition::Always)
+            .build(ui, || {
+                let draw_list = ui.get_window_draw_list();
+                for handle in handles {
+                    let color = if handle.highlighted {
+                        COLOR_HIGHLIGHTED
+                    } else {
+                        match handle.axis {
+                            GizmoAxis::X => AXIS_COLOR_X,
+                            GizmoAxis::Y => AXIS_COLOR_Y,
+                            GizmoAxis::Z => AXIS_COLOR_Z,
+                        }
+                    };
+
+                    for segment in handle.points.windows(2) {
+                        draw_list
+                            .add_line(segment[0], segment[1], color)
+                            .thickness(HANDLE_THICKNESS)
+                            .build();
+                    }
+
+                    if let Some([x, y]) = handle.points.last().copied() {
+                        match mode {
+                            GizmoMode::Translate => draw_list
+                                .add_circle([x, y], HANDLE_TIP_SIZE, color)
+                                .filled(true)
+                                .build(),
+                            GizmoMode::Scale => draw_list
+                                .add_rect(
+                                    [x - HANDLE_TIP_SIZE, y - HANDLE_TIP_SIZE],
+                                    [x + HANDLE_TIP_SIZE, y + HANDLE_TIP_SIZE],
+                                    color,
+                                )
+                                .filled(true)
+                                .build(),
+                            GizmoMode::Rotate => (),
+                        }
+                    }
+                }
+            });
+        style_token.pop(ui);
+    }
+
+    /// Draws the locators as their axes with their names, behind the
+    /// panels like the gizmo.
+    pub fn draw_locators(&self, markers: &[LocatorMarker]) {
+        let ui = &self.imgui_ui;
+
+        const AXIS_THICKNESS: f32 = 2.0;
+        const ORIGIN_SIZE: f32 = 3.0;
+        const NAME_OFFSET: f32 = 6.0;
+        const COLOR_NAME: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+        if markers.is_empty() {
+            return;
+        }
+
+        let style_token = ui.push_style_vars(&[
+            imgui::StyleVar::WindowPadding([0.0, 0.0]),
+            imgui::StyleVar::WindowBorderSize(0.0),
+        ]);
+        imgui::Window::new(imgui::im_str!("##Locators"))
+            .no_decoration()
+            .no_inputs()
+            .movable(false)
+            .draw_background(false)
+            .bring_to_front_on_focus(false)
+            .focus_on_appearing(false)
+            .size(ui.io().display_size, imgui::Condition::Always)
+            .position([0.0, 0.0], imgui::Condition::Always)
+            .build(ui, || {
+                let draw_list = ui.get_window_draw_list();
+                for marker in markers {
+                    let colors = [AXIS_COLOR_X, AXIS_COLOR_Y, AXIS_COLOR_Z];
+                    for (axis_end, color) in marker.axis_ends.iter().zip(&colors) {
+                        if let Some(axis_end) = axis_end {
+                            draw_list
+                                .add_line(marker.origin, *axis_end, *color)
+                                .thickness(AXIS_THICKNESS)
+                                .build();
+                        }
+                    }
+
+                    let [x, y] = marker.origin;
+                    draw_list
+                        .add_circle(marker.origin, ORIGIN_SIZE, COLOR_NAME)
+                        .filled(true)
+                        .build();
+                    draw_list.add_text(
+                        [x + NAME_OFFSET, y + NAME_OFFSET],
+                        COLOR_NAME,
+                        &marker.name,
+                    );
+                }
+            });
+        style_token.pop(ui);
+    }
+
+    pub fn draw_pipeline_window(&self, session: &mut Session) {
+        let ui = &self.imgui_ui;
+        self.console_state
+            .borrow_mut()
+            .resize_with(session.stmts().len(), Default::default);
+
+        let function_table = session.function_table();
+
+        const PIPELINE_WINDOW_WIDTH: f32 = 400.0;
+        const PIPELINE_WINDOW_HEIGHT_MULT: f32 = 0.7;
+
+        let window_logical_size = ui.io().display_size;
+        let window_inner_height = window_logical_size[1] - 2.0 * MARGIN;
+
+        let pipeline_window_height = window_inner_height * PIPELINE_WINDOW_HEIGHT_MULT;
+
+        let interpreter_busy = session.interpreter_busy();
+        let mut change = None;
+        let mut manual_update_change = None;
+        let mut update_clicked = None;
+        let mut constant_binding_change = None;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Pipeline"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size([PIPELINE_WINDOW_WIDTH, pipeline_window_height], imgui::Condition::Always)
+            .position([MARGIN, MARGIN], imgui::Condition::Always)
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+                for (stmt_index, stmt) in session.stmts().iter().enumerate() {
+                    match stmt {
+                        ast::Stmt::VarDecl(var_decl) => {
+                            let call_expr = var_decl.init_expr();
+                            let func_ident = call_expr.ident();
+                            let func = &function_table[&func_ident];
+
+                            if ui
+                                .collapsing_header(&imgui::im_str!(
+                                    "#{} {} ##{}",
+                                    stmt_index + 1,
+                                    func.info().name,
+                                    stmt_index
+                                ))
+                                .default_open(true)
+                                .build()
+                            {
+                                ui.indent();
+
+                                assert_eq!(
+                                    call_expr.args().len(),
+                                    func.param_info().len(),
+                                    "Function call must be generated with correct number of arguments",
+                                );
+
+                                // Funcs may report their progress
+                                // while they compute the statement
+                                if let Some(progress) = session.interpret_progress() {
+                                    if progress.stmt_index == stmt_index {
+                                        imgui::ProgressBar::new(progress.func_progress)
+                                            .size([-f32::MIN_POSITIVE, 0.0])
+                                            .build(ui);
+                                    }
+                                }
+
+                                let operation_arg_style_tokens = if interpreter_busy {
+                                    Some(push_disabled_style(ui))
+                                } else {
+                                    None
+                                };
+
+                                for (arg_index, (param_info, arg)) in func
+                                    .param_info()
+                                    .iter()
+                                    .zip(call_expr.args().iter())
+                                    .enumerate()
+                                {
+                                    // Lengths are computed in meters,
+                                    // but shown in the project unit
+                                    let length_unit = match param_info.refinement {
+                                        ParamRefinement::Float(FloatParamRefinement { length: true, .. })
+                                        | ParamRefinement::Float2(Float2ParamRefinement { length: true, .. })
+                                        | ParamRefinement::Float3(Float3ParamRefinement { length: true, .. }) => {
+                                            Some(session.unit())
+                                        }
+                                        _ => None,
+                                    };
+                                    let to_value = |meters: f32| {
+                                        length_unit.map_or(meters, |unit| unit.meters_to_value(meters))
+                                    };
+                                    let to_meters = |value: f32| {
+                                        length_unit.map_or(value, |unit| unit.value_to_meters(value))
+                                    };
+
+                                    let input_label = match length_unit {
+                                        Some(unit) => imgui::im_str!(
+                                            "{} ({})##{}-{}",
+                                            &param_info.name,
+                                            unit.symbol(),
+                                            stmt_index,
+                                            arg_index
+                                        ),
+                                        None => imgui::im_str!(
+                                            "{}##{}-{}",
+                                            &param_info.name,
+                                            stmt_index,
+                                            arg_index
+                                        ),
+                                    };
+
+                                    // Params bound to a constant are
+                                    // edited via the constant
+                                    if let Some(constant_name) =
+                                        session.param_constant(stmt_index, arg_index)
+                                    {
+                                        ui.text(imgui::im_str!(
+                                            "{} = {}",
+                                            param_info.name,
+                                            constant_name,
+                                        ));
+                                        constant_binding_popup(
+                                            ui,
+                                            session,
+                                            stmt_index,
+                                            arg_index,
+                                            true,
+                                            &[],
+                                            &mut constant_binding_change,
+                                            &mut change,
+                                        );
+                                        continue;
+                                    }
+
+                                    // Numeric params bound to a var,
+                                    // e.g. a repeat index, read its value
+                                    if let ast::Expr::Var(var) = arg {
+                                        if accepts_uint_var(&param_info.refinement) {
+                                            let var_name = session
+                                                .var_name_for_ident(var.ident())
+                                                .expect("Failed to find name for ident");
+                                            ui.text(imgui::im_str!(
+                                                "{} = {} #{}",
+                                                param_info.name,
+                                                var_name,
+                                                var.ident().0 + 1,
+                                            ));
+                                            var_binding_popup(
+                                                ui,
+                                                session,
+                                                stmt_index,
+                                                arg_index,
+                                                &param_info.refinement,
+                                                &mut change,
+                                            );
+                                            continue;
+                                        }
+                                    }
+
+                                    match param_info.refinement {
+                                        ParamRefinement::Boolean(_) => {
+                                            let mut boolean_lit =
+                                                arg.unwrap_literal().unwrap_boolean();
+
+                                            if ui.checkbox(&input_label, &mut boolean_lit) {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Boolean(
+                                                        boolean_lit,
+                                                    )),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Int(param_refinement_int) => {
+                                            let mut int_lit = arg.unwrap_literal().unwrap_int();
+
+                                            if ui.input_int(&input_label, &mut int_lit)
+                                                .read_only(interpreter_busy)
+                                                .build()
+                                            {
+                                                int_lit = param_refinement_int.clamp(int_lit);
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Int(int_lit)),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Uint(param_refinement_uint) => {
+                                            let uint_lit = arg.unwrap_literal().unwrap_uint();
+                                            let mut int_value = clamp_cast_u32_to_i32(uint_lit);
+
+                                            if ui.input_int(&input_label, &mut int_value)
+                                                .read_only(interpreter_busy)
+                                                .build()
+                                            {
+                                                let uint_value = clamp_cast_i32_to_u32(int_value);
+                                                let uint_value = param_refinement_uint.clamp(uint_value);
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Uint(uint_value)),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Enum(param_refinement_enum) => {
+                                            let uint_lit = arg.unwrap_literal().unwrap_uint();
+                                            let mut selected = cast_usize(param_refinement_enum.clamp(uint_lit));
+
+                                            let options: Vec<_> = param_refinement_enum
+                                                .options
+                                                .iter()
+                                                .map(|option| imgui::ImString::new(*option))
+                                                .collect();
+                                            let option_refs: Vec<_> = options.iter().collect();
+
+                                            if imgui::ComboBox::new(&input_label)
+                                                .build_simple_string(ui, &mut selected, &option_refs)
+                                            {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Uint(cast_u32(selected))),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Float(param_refinement_float) => {
+                                            let mut float_value = to_value(arg.unwrap_literal().unwrap_float());
+
+                                            if ui.input_float(&input_label, &mut float_value)
+                                                .read_only(interpreter_busy)
+                                                .build()
+                                            {
+                                                let float_lit = param_refinement_float.clamp(to_meters(float_value));
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Float(float_lit)),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Float2(param_refinement_float2) => {
+                                            let float2_lit = arg.unwrap_literal().unwrap_float2();
+                                            let mut float2_value = [to_value(float2_lit[0]), to_value(float2_lit[1])];
+
+                                            if ui
+                                                .input_float2(&input_label, &mut float2_value)
+                                                .read_only(interpreter_busy)
+                                                .build()
+                                            {
+                                                let float2_lit = param_refinement_float2.clamp([
+                                                    to_meters(float2_value[0]),
+                                                    to_meters(float2_value[1]),
+                                                ]);
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Float2(
+                                                        float2_lit,
+                                                    )),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::Float3(param_refinement_float3) => {
+                                            let float3_lit = arg.unwrap_literal().unwrap_float3();
+                                            let mut float3_value = [
+                                                to_value(float3_lit[0]),
+                                                to_value(float3_lit[1]),
+                                                to_value(float3_lit[2]),
+                                            ];
+
+                                            if ui
+                                                .input_float3(&input_label, &mut float3_value)
+                                                .read_only(interpreter_busy)
+                                                .build()
+                                            {
+                                                let float3_lit = param_refinement_float3.clamp([
+                                                    to_meters(float3_value[0]),
+                                                    to_meters(float3_value[1]),
+                                                    to_meters(float3_value[2]),
+                                                ]);
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Float3(
+                                                        float3_lit,
+                                                    )),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::String(param_refinement_string) => {
+                                            let mut imstring_buffer = self.global_imstring_buffer
+                                                .borrow_mut();
+
+                                            let string_lit = arg.unwrap_literal().unwrap_string();
+                                            imstring_buffer.push_str(string_lit);
+
+                                            if param_refinement_string.file_path {
+                                                if file_input(
+                                                    ui,
                                                     &input_label,
+                                                    param_refinement_string.file_save,
                                                     param_refinement_string.file_ext_filter,
                                                     &mut imstring_buffer,
                                                 ) {
@@ -511,14 +2357,48 @@ impl<'a> UiFrame<'a> {
                                                     ));
                                                 }
 
-                                            imstring_buffer.clear();
+                                            imstring_buffer.clear();
+                                        }
+                                        ParamRefinement::Mesh => {
+                                            let changed_expr = self.draw_var_combo_box(
+                                                session,
+                                                stmt_index,
+                                                arg,
+                                                Ty::Mesh,
+                                                &input_label,
+                                            );
+
+                                            if let Some(changed_expr) = changed_expr {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    changed_expr,
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::MeshArray => {
+                                            let changed_expr = self.draw_var_combo_box(
+                                                session,
+                                                stmt_index,
+                                                arg,
+                                                Ty::MeshArray,
+                                                &input_label,
+                                            );
+
+                                            if let Some(changed_expr) = changed_expr {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    changed_expr,
+                                                ));
+                                            }
                                         }
-                                        ParamRefinement::Mesh => {
+                                        ParamRefinement::Locator => {
                                             let changed_expr = self.draw_var_combo_box(
                                                 session,
                                                 stmt_index,
                                                 arg,
-                                                Ty::Mesh,
+                                                Ty::Locator,
                                                 &input_label,
                                             );
 
@@ -530,12 +2410,12 @@ impl<'a> UiFrame<'a> {
                                                 ));
                                             }
                                         }
-                                        ParamRefinement::MeshArray => {
+                                        ParamRefinement::Curve => {
                                             let changed_expr = self.draw_var_combo_box(
                                                 session,
                                                 stmt_index,
                                                 arg,
-                                                Ty::MeshArray,
+                                                Ty::Curve,
                                                 &input_label,
                                             );
 
@@ -547,6 +2427,58 @@ impl<'a> UiFrame<'a> {
                                                 ));
                                             }
                                         }
+                                        ParamRefinement::EmbeddedMesh => {
+                                            if let Some(mesh) = embedded_mesh_input(
+                                                ui,
+                                                &input_label,
+                                                param_info.name,
+                                                arg.unwrap_literal(),
+                                                session.unit(),
+                                            ) {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Mesh(Arc::new(mesh))),
+                                                ));
+                                            }
+                                        }
+                                        ParamRefinement::EmbeddedCurve => {
+                                            if let Some(curve) = embedded_curve_input(
+                                                ui,
+                                                &input_label,
+                                                param_info.name,
+                                                arg.unwrap_literal(),
+                                                session.unit(),
+                                            ) {
+                                                change = Some((
+                                                    stmt_index,
+                                                    arg_index,
+                                                    ast::Expr::Lit(ast::LitExpr::Curve(Arc::new(curve))),
+                                                ));
+                                            }
+                                        }
+                                    }
+
+                                    if constants::constant_lit(&param_info.refinement, 0.0).is_some() {
+                                        let bindable_vars: Vec<_> =
+                                            if accepts_uint_var(&param_info.refinement) {
+                                                session
+                                                    .visible_vars_at_stmt(stmt_index, Ty::Uint)
+                                                    .collect()
+                                            } else {
+                                                Vec::new()
+                                            };
+
+                                        constant_binding_popup(
+                                            ui,
+                                            session,
+                                            stmt_index,
+                                            arg_index,
+                                            false,
+                                            &bindable_vars,
+                                            &mut constant_binding_change,
+                                            &mut change,
+                                        );
                                     }
                                 }
 
@@ -577,6 +2509,15 @@ impl<'a> UiFrame<'a> {
                                     window_token.end(ui);
                                 }
 
+                                manual_update_input(
+                                    ui,
+                                    stmt_index,
+                                    var_decl.ident(),
+                                    session.var_manual_update(var_decl.ident()),
+                                    &mut manual_update_change,
+                                    &mut update_clicked,
+                                );
+
                                 if let Some((color_token, style_token)) = operation_arg_style_tokens {
                                     color_token.pop(ui);
                                     style_token.pop(ui);
@@ -609,6 +2550,29 @@ impl<'a> UiFrame<'a> {
                     }
                 }
             }
+
+            if let Some((var_ident, manual_update)) = manual_update_change {
+                if !session.interpreter_busy() {
+                    session.set_var_manual_update(var_ident, manual_update);
+                }
+            }
+
+            if let Some(var_ident) = update_clicked {
+                if !session.interpreter_busy() {
+                    session.update_var(var_ident);
+                }
+            }
+
+            if let Some((stmt_index, arg_index, constant_index)) = constant_binding_change {
+                if !session.interpreter_busy() {
+                    match constant_index {
+                        Some(constant_index) => {
+                            session.bind_param_to_constant(stmt_index, arg_index, constant_index)
+                        }
+                        None => session.unbind_param(stmt_index, arg_index),
+                    }
+                }
+            }
         }
     }
 
@@ -629,10 +2593,26 @@ impl<'a> UiFrame<'a> {
         let running_enabled = !session.interpreter_busy();
         let popping_enabled = !session.interpreter_busy() && !session.stmts().is_empty();
         let pushing_enabled = !session.interpreter_busy();
+        let opening_enabled = !session.interpreter_busy();
 
         let mut function_clicked = None;
         let mut interpret_clicked = false;
         let mut pop_stmt_clicked = false;
+        let mut cancel_clicked = false;
+        let mut unit_change = None;
+        let mut auto_interpret_change = None;
+        let mut save_project_clicked = false;
+        let mut open_project_clicked = false;
+        let mut macros_state = self.macros_state.borrow_mut();
+        let mut library_state = self.library_state.borrow_mut();
+        let mut record_macro_clicked = false;
+        let mut stop_recording_clicked = false;
+        let mut replayed_macro_index = None;
+        let mut removed_macro_index = None;
+        let mut constants_state = self.constants_state.borrow_mut();
+        let mut add_constant_clicked = false;
+        let mut changed_constant = None;
+        let mut removed_constant_index = None;
 
         let bold_font_token = ui.push_font(self.font_ids.bold);
         imgui::Window::new(imgui::im_str!("Operations"))
@@ -698,23 +2678,238 @@ impl<'a> UiFrame<'a> {
                     style_token.pop(ui);
                 }
 
+                ui.columns(1, imgui::im_str!("Auto-run columns"), false);
+
+                // The interpreter runs in its own thread and reports
+                // which operation it is computing
+                if let Some(progress) = session.interpret_progress() {
+                    if let Some(ast::Stmt::VarDecl(var_decl)) =
+                        session.stmts().get(progress.stmt_index)
+                    {
+                        ui.text(imgui::im_str!(
+                            "Running {} ({}/{})",
+                            function_table[&var_decl.init_expr().ident()].info().name,
+                            progress.stmt_index + 1,
+                            progress.stmt_count,
+                        ));
+                    }
+                }
+
+                if session.interpreter_busy()
+                    && ui.button(imgui::im_str!("Cancel"), [-f32::MIN_POSITIVE, 20.0])
+                {
+                    cancel_clicked = true;
+                }
+
+                let mut auto_interpret = session.auto_interpret();
+                if ui.checkbox(imgui::im_str!("Auto-run pipeline"), &mut auto_interpret) {
+                    auto_interpret_change = Some(auto_interpret);
+                }
+
+                let unit_names: Vec<_> = LengthUnit::ALL
+                    .iter()
+                    .map(|unit| imgui::ImString::new(unit.name()))
+                    .collect();
+                let unit_name_refs: Vec<_> = unit_names.iter().collect();
+                let mut unit_index = LengthUnit::ALL
+                    .iter()
+                    .position(|unit| *unit == session.unit())
+                    .expect("All units must be listed");
+                if imgui::ComboBox::new(imgui::im_str!("Units")).build_simple_string(
+                    ui,
+                    &mut unit_index,
+                    &unit_name_refs,
+                ) {
+                    unit_change = Some(LengthUnit::ALL[unit_index]);
+                }
+
+                ui.columns(2, imgui::im_str!("Project columns"), false);
+                if ui.button(imgui::im_str!("Save project"), [-f32::MIN_POSITIVE, 20.0]) {
+                    save_project_clicked = true;
+                }
+                ui.next_column();
+
+                let opening_tokens = if opening_enabled {
+                    None
+                } else {
+                    Some(push_disabled_style(ui))
+                };
+                if ui.button(imgui::im_str!("Open project"), [-f32::MIN_POSITIVE, 20.0])
+                    && opening_enabled
+                {
+                    open_project_clicked = true;
+                }
+                if let Some((color_token, style_token)) = opening_tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+                ui.columns(1, imgui::im_str!("Project columns end"), false);
+
                 ui.separator();
 
-                let pushing_tokens = if pushing_enabled {
+                if session.recording_macro() {
+                    ui.input_text(
+                        imgui::im_str!("##Macro name"),
+                        &mut macros_state.name_buffer,
+                    )
+                    .build();
+                    ui.same_line(0.0);
+                    stop_recording_clicked =
+                        ui.button(imgui::im_str!("Stop recording"), [0.0, 0.0]);
+                } else if ui.button(imgui::im_str!("Record macro"), [0.0, 0.0]) {
+                    record_macro_clicked = true;
+                }
+
+                let macros = session.macros();
+                if !macros.is_empty() {
+                    let macro_names: Vec<_> = macros
+                        .iter()
+                        .map(|recorded_macro| {
+                            imgui::im_str!(
+                                "{} ({} operations)",
+                                recorded_macro.name,
+                                recorded_macro.stmts.len(),
+                            )
+                        })
+                        .collect();
+                    let macro_name_refs: Vec<_> = macro_names.iter().collect();
+
+                    if macros_state.selected_macro_index >= macros.len() {
+                        macros_state.selected_macro_index = 0;
+                    }
+                    imgui::ComboBox::new(imgui::im_str!("##Macro")).build_simple_string(
+                        ui,
+                        &mut macros_state.selected_macro_index,
+                        &macro_name_refs,
+                    );
+
+                    let replaying_tokens = if pushing_enabled {
+                        None
+                    } else {
+                        Some(push_disabled_style(ui))
+                    };
+                    ui.same_line(0.0);
+                    if ui.button(imgui::im_str!("Replay"), [0.0, 0.0]) && pushing_enabled {
+                        replayed_macro_index = Some(macros_state.selected_macro_index);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(
+                            "Adds the recorded operations, connected to the last fitting operations",
+                        );
+                    }
+                    if let Some((color_token, style_token)) = replaying_tokens {
+                        color_token.pop(ui);
+                        style_token.pop(ui);
+                    }
+                    ui.same_line(0.0);
+                    if ui.button(imgui::im_str!("Delete##Macro"), [0.0, 0.0]) {
+                        removed_macro_index = Some(macros_state.selected_macro_index);
+                    }
+                }
+
+                ui.separator();
+
+                ui.input_text(
+                    imgui::im_str!("Constant name"),
+                    &mut constants_state.name_buffer,
+                )
+                .build();
+                ui.input_float(imgui::im_str!("Constant value"), &mut constants_state.value)
+                    .build();
+                let constant_tokens = if pushing_enabled {
                     None
                 } else {
                     Some(push_disabled_style(ui))
                 };
-                ui.columns(3, imgui::im_str!("Add operations columns"), false);
-                for (func_ident, func) in function_table {
-                    if ui.button(
-                        &imgui::im_str!("{}", func.info().name),
-                        [-f32::MIN_POSITIVE, 20.0],
-                    ) && pushing_enabled
+                if ui.button(imgui::im_str!("Add constant"), [0.0, 0.0]) && pushing_enabled {
+                    add_constant_clicked = true;
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Right-click a numeric parameter in the pipeline to bind it to a constant",
+                    );
+                }
+                for (constant_index, constant) in session.constants().iter().enumerate() {
+                    let mut value = constant.value;
+                    if ui
+                        .input_float(
+                            &imgui::im_str!("{}##constant-{}", constant.name, constant_index),
+                            &mut value,
+                        )
+                        .read_only(!pushing_enabled)
+                        .build()
                     {
-                        function_clicked = Some(func_ident);
+                        changed_constant = Some((constant.name.clone(), value));
+                    }
+                    ui.same_line(0.0);
+                    if ui.button(
+                        &imgui::im_str!("Delete##constant-{}", constant_index),
+                        [0.0, 0.0],
+                    ) {
+                        removed_constant_index = Some(constant_index);
+                    }
+                }
+                if let Some((color_token, style_token)) = constant_tokens {
+                    color_token.pop(ui);
+                    style_token.pop(ui);
+                }
+
+                ui.separator();
+
+                ui.input_text(
+                    imgui::im_str!("Search operations"),
+                    &mut library_state.query_buffer,
+                )
+                .build();
+                let query = library_state.query_buffer.to_str();
+
+                let pushing_tokens = if pushing_enabled {
+                    None
+                } else {
+                    Some(push_disabled_style(ui))
+                };
+
+                for category in &FuncCategory::ALL {
+                    let category_funcs: Vec<_> = function_table
+                        .iter()
+                        .filter(|(_, func)| {
+                            let info = func.info();
+                            info.category == *category && info.matches_query(query)
+                        })
+                        .collect();
+                    if category_funcs.is_empty() {
+                        continue;
+                    }
+
+                    // While searching, all categories with matches are
+                    // expanded, so that no match stays hidden
+                    let header_open = ui
+                        .collapsing_header(&imgui::im_str!(
+                            "{} ({})###{}",
+                            category.name(),
+                            category_funcs.len(),
+                            category.name(),
+                        ))
+                        .default_open(true)
+                        .build();
+                    if !header_open && query.trim().is_empty() {
+                        continue;
+                    }
+
+                    ui.columns(3, &imgui::im_str!("{} columns", category.name()), false);
+                    for (func_ident, func) in category_funcs {
+                        let info = func.info();
+                        if ui.button(&imgui::im_str!("{}", info.name), [-f32::MIN_POSITIVE, 20.0])
+                            && pushing_enabled
+                        {
+                            function_clicked = Some(func_ident);
+                        }
+                        if ui.is_item_hovered() && !info.tags.is_empty() {
+                            ui.tooltip_text(info.tags.join(", "));
+                        }
+                        ui.next_column();
                     }
-                    ui.next_column();
+                    ui.columns(1, &imgui::im_str!("{} columns end", category.name()), false);
                 }
                 if let Some((color_token, style_token)) = pushing_tokens {
                     color_token.pop(ui);
@@ -729,89 +2924,437 @@ impl<'a> UiFrame<'a> {
             let mut args = Vec::with_capacity(func.param_info().len());
 
             for param_info in func.param_info() {
-                let expr = match param_info.refinement {
-                    ParamRefinement::Boolean(boolean_refinement) => {
-                        ast::Expr::Lit(ast::LitExpr::Boolean(boolean_refinement.default_value))
-                    }
-                    ParamRefinement::Int(int_param_refinement) => ast::Expr::Lit(
-                        ast::LitExpr::Int(int_param_refinement.default_value.unwrap_or_default()),
-                    ),
-                    ParamRefinement::Uint(uint_param_refinement) => ast::Expr::Lit(
-                        ast::LitExpr::Uint(uint_param_refinement.default_value.unwrap_or_default()),
-                    ),
-                    ParamRefinement::Float(float_param_refinement) => {
-                        ast::Expr::Lit(ast::LitExpr::Float(
-                            float_param_refinement.default_value.unwrap_or_default(),
-                        ))
-                    }
-                    ParamRefinement::Float2(float2_param_refinement) => {
-                        ast::Expr::Lit(ast::LitExpr::Float2([
-                            float2_param_refinement.default_value_x.unwrap_or_default(),
-                            float2_param_refinement.default_value_y.unwrap_or_default(),
-                        ]))
-                    }
-                    ParamRefinement::Float3(float3_param_refinement) => {
-                        ast::Expr::Lit(ast::LitExpr::Float3([
-                            float3_param_refinement.default_value_x.unwrap_or_default(),
-                            float3_param_refinement.default_value_y.unwrap_or_default(),
-                            float3_param_refinement.default_value_z.unwrap_or_default(),
-                        ]))
-                    }
-                    ParamRefinement::String(string_param_refinement) => {
-                        let initial_value = String::from(string_param_refinement.default_value);
-                        ast::Expr::Lit(ast::LitExpr::String(Arc::new(initial_value)))
-                    }
-                    ParamRefinement::Mesh => {
-                        let one_past_last_stmt = session.stmts().len();
-                        let visible_vars_iter =
-                            session.visible_vars_at_stmt(one_past_last_stmt, Ty::Mesh);
+                args.push(default_arg(session, &param_info.refinement));
+            }
 
-                        if visible_vars_iter.clone().count() == 0 {
-                            ast::Expr::Lit(ast::LitExpr::Nil)
-                        } else {
-                            let last = visible_vars_iter
-                                .last()
-                                .expect("Need at least one variable to provide default value");
+            let init_expr = ast::CallExpr::new(*func_ident, args);
+            let stmt = ast::Stmt::VarDecl(ast::VarDeclStmt::new(
+                session.next_free_var_ident(),
+                init_expr,
+            ));
+
+            session.push_prog_stmt(stmt);
+        }
+
+        // Editing the program may have already started the
+        // interpreter, if running it automatically is enabled
+        if interpret_clicked && !session.interpreter_busy() {
+            session.interpret();
+        }
+
+        if pop_stmt_clicked && !session.interpreter_busy() {
+            session.pop_prog_stmt();
+        }
+
+        if cancel_clicked {
+            session.cancel_interpretation();
+        }
+
+        if let Some(auto_interpret) = auto_interpret_change {
+            session.set_auto_interpret(auto_interpret);
+        }
+
+        if let Some(unit) = unit_change {
+            session.set_unit(unit);
+        }
+
+        if record_macro_clicked {
+            session.start_macro_recording();
+        }
+
+        if stop_recording_clicked {
+            let name = macros_state.name_buffer.to_str().trim();
+            let name = if name.is_empty() {
+                format!("Macro {}", session.macros().len() + 1)
+            } else {
+                name.to_string()
+            };
+
+            if !session.stop_macro_recording(name) {
+                log::warn!("No operations were recorded, the macro was not saved");
+            }
+            macros_state.name_buffer.clear();
+        }
+
+        if let Some(macro_index) = replayed_macro_index {
+            if !session.interpreter_busy() {
+                let input_args: Vec<_> = session.macros()[macro_index]
+                    .inputs
+                    .iter()
+                    .map(|input_ty| last_visible_var_arg(session, *input_ty))
+                    .collect();
+                session.replay_macro(macro_index, &input_args);
+            }
+        }
+
+        if let Some(macro_index) = removed_macro_index {
+            session.remove_macro(macro_index);
+        }
+
+        // Changing a constant changes the params bound to it, which is
+        // not allowed while the interpreter is running
+        if !session.interpreter_busy() {
+            if add_constant_clicked {
+                let name = constants_state.name_buffer.to_str().trim();
+                let name = if name.is_empty() {
+                    format!("constant_{}", session.constants().len() + 1)
+                } else {
+                    name.to_string()
+                };
+
+                session.set_constant(name, constants_state.value);
+                constants_state.name_buffer.clear();
+            }
+
+            if let Some((name, value)) = changed_constant {
+                session.set_constant(name, value);
+            }
+        }
+
+        if let Some(constant_index) = removed_constant_index {
+            session.remove_constant(constant_index);
+        }
+
+        if save_project_clicked {
+            save_project(session);
+        }
+
+        if open_project_clicked && !session.interpreter_busy() {
+            open_project(session);
+        }
+    }
+
+    /// Draws a window with statistics and a histogram of a vertex
+    /// attribute of one of the `meshes`. Each mesh is identified by
+    /// the variable that holds it and its index within the variable's
+    /// value. When the mesh selected in the viewport changes, the
+    /// window switches to it.
+    pub fn draw_inspector_window(
+        &self,
+        session: &Session,
+        meshes: &[(ast::VarIdent, usize, Arc<Mesh>)],
+        viewport_selection: Option<(ast::VarIdent, usize)>,
+    ) {
+        let ui = &self.imgui_ui;
+        let mut inspector_state = self.inspector_state.borrow_mut();
+
+        const INSPECTOR_WINDOW_WIDTH: f32 = 300.0;
+        const HISTOGRAM_BIN_COUNT: u32 = 32;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("Inspector"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [INSPECTOR_WINDOW_WIDTH, INSPECTOR_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width + MARGIN - INSPECTOR_WINDOW_WIDTH,
+                    2.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                if meshes.is_empty() {
+                    // Don't keep the last inspected mesh alive
+                    inspector_state.cache = None;
+                    inspector_state.topology_cache = None;
+                    ui.text(imgui::im_str!("No meshes to inspect"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
 
-                            ast::Expr::Var(ast::VarExpr::new(last))
+                let mesh_names: Vec<_> = meshes
+                    .iter()
+                    .map(|(var_ident, index, _)| {
+                        // The var can already be removed from the
+                        // program, while its value is still in the
+                        // scene waiting for the interpreter.
+                        let name = session
+                            .var_name_for_ident(*var_ident)
+                            .unwrap_or("<Removed>");
+                        let is_array_element = meshes
+                            .iter()
+                            .filter(|(other_var_ident, _, _)| other_var_ident == var_ident)
+                            .count()
+                            > 1;
+
+                        if is_array_element {
+                            imgui::im_str!("{} #{} [{}]", name, var_ident.0 + 1, index)
+                        } else {
+                            format_var_name(name, *var_ident, false)
                         }
+                    })
+                    .collect();
+                let mesh_name_refs: Vec<_> = mesh_names.iter().collect();
+
+                if viewport_selection != inspector_state.viewport_selection {
+                    inspector_state.viewport_selection = viewport_selection;
+                    if let Some(selected_index) = meshes.iter().position(|(var_ident, index, _)| {
+                        Some((*var_ident, *index)) == viewport_selection
+                    }) {
+                        inspector_state.selected_mesh_index = selected_index;
                     }
-                    ParamRefinement::MeshArray => {
-                        let one_past_last_stmt = session.stmts().len();
-                        let visible_vars_iter =
-                            session.visible_vars_at_stmt(one_past_last_stmt, Ty::MeshArray);
+                }
+                if inspector_state.selected_mesh_index >= meshes.len() {
+                    inspector_state.selected_mesh_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("Mesh")).build_simple_string(
+                    ui,
+                    &mut inspector_state.selected_mesh_index,
+                    &mesh_name_refs,
+                );
 
-                        if visible_vars_iter.clone().count() == 0 {
-                            ast::Expr::Lit(ast::LitExpr::Nil)
-                        } else {
-                            let last = visible_vars_iter
-                                .last()
-                                .expect("Need at least one variable to provide default value");
+                let (_, _, mesh) = &meshes[inspector_state.selected_mesh_index];
+                let topology_cache_valid = match &inspector_state.topology_cache {
+                    Some((cached_mesh, _, _)) => Arc::ptr_eq(cached_mesh, mesh),
+                    None => false,
+                };
+                if !topology_cache_valid {
+                    let topology = analysis::topology_info(mesh);
+                    // Mass properties only make sense for closed meshes
+                    let mass_properties = if topology.genus.is_some() {
+                        analysis::compute_mass_properties(mesh)
+                    } else {
+                        None
+                    };
+                    inspector_state.topology_cache =
+                        Some((Arc::clone(mesh), topology, mass_properties));
+                }
+                let (topology, mass_properties) = inspector_state
+                    .topology_cache
+                    .as_ref()
+                    .map(|(_, topology, mass_properties)| (*topology, *mass_properties))
+                    .expect("Topology cache must be populated");
 
-                            ast::Expr::Var(ast::VarExpr::new(last))
+                ui.text(imgui::im_str!(
+                    "Vertices: {}  Edges: {}  Faces: {}",
+                    mesh.vertices().len(),
+                    topology.edge_count,
+                    mesh.faces().len(),
+                ));
+                ui.text(imgui::im_str!(
+                    "Components: {}  Euler Characteristic: {}",
+                    topology.component_count,
+                    topology.euler_characteristic,
+                ));
+                let unit = session.unit();
+                let size = mesh.bounding_box().diagonal();
+                ui.text(imgui::im_str!(
+                    "Size: {} x {} x {}",
+                    unit.length(size.x),
+                    unit.length(size.y),
+                    unit.length(size.z),
+                ));
+                match topology.genus {
+                    Some(genus) => ui.text(imgui::im_str!("Genus: {}", genus)),
+                    None => ui.text(imgui::im_str!("Genus: - (not watertight)")),
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(
+                        "Total number of holes through the mesh components, \
+                         known only for watertight meshes",
+                    );
+                }
+                match mass_properties {
+                    Some(mass_properties) => {
+                        let center = mass_properties.center_of_mass;
+                        let moments = mass_properties.principal_moments_of_inertia();
+                        ui.text(imgui::im_str!(
+                            "Volume: {}",
+                            unit.volume(mass_properties.volume),
+                        ));
+                        ui.text(imgui::im_str!(
+                            "Center of Mass: {}, {}, {}",
+                            unit.length(center.x),
+                            unit.length(center.y),
+                            unit.length(center.z),
+                        ));
+                        ui.text(imgui::im_str!(
+                            "Principal Moments: {:.3}, {:.3}, {:.3}",
+                            moments.x,
+                            moments.y,
+                            moments.z,
+                        ));
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(
+                                "Moments of inertia around the principal axes \
+                                 through the center of mass, for unit density",
+                            );
                         }
                     }
+                    None => ui.text(imgui::im_str!("Volume: - (not a closed solid)")),
+                }
+                ui.separator();
+
+                let attributes = mesh.vertex_attributes();
+                if attributes.is_empty() {
+                    inspector_state.cache = None;
+                    ui.text(imgui::im_str!("No vertex attributes"));
+                    regular_font_token.pop(ui);
+                    return;
+                }
+
+                let attribute_names: Vec<_> = attributes
+                    .iter()
+                    .map(|attribute| imgui::ImString::new(attribute.name()))
+                    .collect();
+                let attribute_name_refs: Vec<_> = attribute_names.iter().collect();
+
+                if inspector_state.selected_attribute_index >= attributes.len() {
+                    inspector_state.selected_attribute_index = 0;
+                }
+                imgui::ComboBox::new(imgui::im_str!("Attribute")).build_simple_string(
+                    ui,
+                    &mut inspector_state.selected_attribute_index,
+                    &attribute_name_refs,
+                );
+
+                let attribute_index = inspector_state.selected_attribute_index;
+                let cache_valid = match &inspector_state.cache {
+                    Some(cache) => {
+                        Arc::ptr_eq(&cache.mesh, mesh) && cache.attribute_index == attribute_index
+                    }
+                    None => false,
                 };
 
-                args.push(expr);
-            }
+                if !cache_valid {
+                    let values = attributes[attribute_index].values();
+                    let statistics = Statistics::from_values(values);
+                    let histogram = statistics
+                        .map(|statistics| {
+                            statistics::compute_histogram(
+                                values,
+                                statistics.min,
+                                statistics.max,
+                                HISTOGRAM_BIN_COUNT,
+                            )
+                        })
+                        .unwrap_or_default();
 
-            let init_expr = ast::CallExpr::new(*func_ident, args);
-            let stmt = ast::Stmt::VarDecl(ast::VarDeclStmt::new(
-                session.next_free_var_ident(),
-                init_expr,
-            ));
+                    inspector_state.cache = Some(InspectorCache {
+                        mesh: Arc::clone(mesh),
+                        attribute_index,
+                        statistics,
+                        histogram,
+                    });
+                }
+
+                let cache = inspector_state
+                    .cache
+                    .as_ref()
+                    .expect("Inspector cache must be populated");
+
+                if let Some(statistics) = cache.statistics {
+                    ui.text(imgui::im_str!("Count: {}", statistics.count));
+                    ui.text(imgui::im_str!("Min: {:.4}", statistics.min));
+                    ui.text(imgui::im_str!("Max: {:.4}", statistics.max));
+                    ui.text(imgui::im_str!("Mean: {:.4}", statistics.mean));
+                    ui.text(imgui::im_str!(
+                        "Std. Deviation: {:.4}",
+                        statistics.standard_deviation,
+                    ));
+
+                    imgui::PlotHistogram::new(ui, imgui::im_str!("##histogram"), &cache.histogram)
+                        .scale_min(0.0)
+                        .graph_size([0.0, 80.0])
+                        .build();
+                } else {
+                    ui.text(imgui::im_str!("Attribute has no finite values"));
+                }
+
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
+    }
+
+    /// Draws a window listing the remembered results of terminal
+    /// operations. Returns the result whose pinning was toggled, if
+    /// any, and its new pinned state.
+    pub fn draw_history_window(
+        &self,
+        session: &Session,
+        history: &EvaluationHistory,
+    ) -> Option<(HistoryEntryId, bool)> {
+        let ui = &self.imgui_ui;
+
+        const HISTORY_WINDOW_WIDTH: f32 = 300.0;
+        const HISTORY_WINDOW_HEIGHT: f32 = 200.0;
+        let window_logical_size = ui.io().display_size;
+        let window_inner_width = window_logical_size[0] - 2.0 * MARGIN;
+
+        let mut var_idents: Vec<_> = history.var_idents().collect();
+        var_idents.sort_by_key(|var_ident| var_ident.0);
+
+        let mut pin_change = None;
+
+        let bold_font_token = ui.push_font(self.font_ids.bold);
+        imgui::Window::new(imgui::im_str!("History"))
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .size(
+                [HISTORY_WINDOW_WIDTH, HISTORY_WINDOW_HEIGHT],
+                imgui::Condition::Always,
+            )
+            .position(
+                [
+                    window_inner_width + MARGIN - HISTORY_WINDOW_WIDTH,
+                    3.0 * MARGIN + VIEWPORT_WINDOW_HEIGHT + INSPECTOR_WINDOW_HEIGHT,
+                ],
+                imgui::Condition::Always,
+            )
+            .build(ui, || {
+                let regular_font_token = ui.push_font(self.font_ids.regular);
+
+                if var_idents.is_empty() {
+                    ui.text(imgui::im_str!("No results evaluated yet"));
+                }
+
+                for var_ident in &var_idents {
+                    let name = session
+                        .var_name_for_ident(*var_ident)
+                        .unwrap_or("<Removed>");
+                    ui.text(format_var_name(name, *var_ident, false));
+
+                    ui.indent();
+                    for (entry_index, entry) in history.entries(*var_ident).enumerate() {
+                        let vertex_count: usize = entry
+                            .meshes()
+                            .iter()
+                            .map(|mesh| mesh.vertices().len())
+                            .sum();
 
-            session.push_prog_stmt(stmt);
-        }
+                        let mut pinned = entry.pinned();
+                        if ui.checkbox(
+                            &imgui::im_str!(
+                                "Pin result {} ({} vertices, {:.1} MB)##{}-{}",
+                                entry_index + 1,
+                                vertex_count,
+                                entry.size_bytes() as f64 / 1_000_000.0,
+                                var_ident.0,
+                                entry_index,
+                            ),
+                            &mut pinned,
+                        ) {
+                            pin_change = Some((entry.id(), pinned));
+                        }
+                    }
+                    ui.unindent();
+                }
 
-        if interpret_clicked {
-            session.interpret();
-        }
+                regular_font_token.pop(ui);
+            });
+        bold_font_token.pop(ui);
 
-        if pop_stmt_clicked {
-            session.pop_prog_stmt();
-        }
+        pin_change
     }
 
     fn draw_var_combo_box(
@@ -937,6 +3480,727 @@ fn format_var_name(
     }
 }
 
+/// Converts the parameter ranges selected in the optimizer window to
+/// the parameters of an optimization request.
+fn optimized_params(
+    param_ranges: &HashMap<(usize, usize, usize), [f32; 2]>,
+) -> Vec<OptimizedParam> {
+    param_ranges
+        .iter()
+        .map(
+            |(&(stmt_index, arg_index, component), &[min, max])| OptimizedParam {
+                stmt_index,
+                arg_index,
+                component,
+                range: optimizer::ParamRange {
+                    min: f32::min(min, max),
+                    max: f32::max(min, max),
+                },
+            },
+        )
+        .collect()
+}
+
+/// Returns the name of an optimized parameter, including its statement
+/// and the component of vector parameters. Returns `None` if the
+/// parameter no longer exists in the program.
+fn optimized_param_name(session: &Session, param: &OptimizedParam) -> Option<imgui::ImString> {
+    const COMPONENT_NAMES: [&str; 3] = ["X", "Y", "Z"];
+
+    match session.stmts().get(param.stmt_index)? {
+        ast::Stmt::VarDecl(var_decl) => {
+            let call_expr = var_decl.init_expr();
+            let func = &session.function_table()[&call_expr.ident()];
+            let param_name = func.param_info().get(param.arg_index)?.name;
+
+            let name = match call_expr.args().get(param.arg_index)? {
+                ast::Expr::Lit(ast::LitExpr::Float2(_))
+                | ast::Expr::Lit(ast::LitExpr::Float3(_)) => imgui::im_str!(
+                    "#{} {} {}",
+                    param.stmt_index + 1,
+                    param_name,
+                    COMPONENT_NAMES[param.component],
+                ),
+                _ => imgui::im_str!("#{} {}", param.stmt_index + 1, param_name),
+            };
+
+            Some(name)
+        }
+    }
+}
+
+/// Returns the number of jobs of a sweep over a grid of parameters,
+/// or `None` if there would be too many of them.
+fn sweep_job_count(steps: i32, param_count: usize) -> Option<usize> {
+    const SWEEP_MAX_JOB_COUNT: usize = 10_000;
+
+    let mut job_count: usize = 1;
+    for _ in 0..param_count {
+        job_count = job_count.checked_mul(clamp_cast_i32_to_u32(steps) as usize)?;
+        if job_count > SWEEP_MAX_JOB_COUNT {
+            return None;
+        }
+    }
+
+    Some(job_count)
+}
+
+fn metric_index(metric: Metric) -> usize {
+    Metric::ALL
+        .iter()
+        .position(|other| *other == metric)
+        .expect("Metric must be listed in all metrics")
+}
+
+/// Draws the target, metric and goal inputs of a Pareto objective.
+fn pareto_objective_input(
+    ui: &imgui::Ui,
+    index: usize,
+    target_names: &[imgui::ImString],
+    objective_state: &mut ParetoObjectiveState,
+) {
+    let target_name_refs: Vec<_> = target_names.iter().collect();
+    if objective_state.selected_target_index >= target_names.len() {
+        objective_state.selected_target_index = 0;
+    }
+
+    ui.text(imgui::im_str!("Objective {}", index + 1));
+    imgui::ComboBox::new(&imgui::im_str!("Target##pareto-target-{}", index)).build_simple_string(
+        ui,
+        &mut objective_state.selected_target_index,
+        &target_name_refs,
+    );
+
+    let metric_names: Vec<_> = Metric::NAMES
+        .iter()
+        .map(|name| imgui::ImString::new(*name))
+        .collect();
+    let metric_name_refs: Vec<_> = metric_names.iter().collect();
+    imgui::ComboBox::new(&imgui::im_str!("Metric##pareto-metric-{}", index)).build_simple_string(
+        ui,
+        &mut objective_state.selected_metric_index,
+        &metric_name_refs,
+    );
+
+    ui.checkbox(
+        &imgui::im_str!("Maximize##pareto-maximize-{}", index),
+        &mut objective_state.maximize,
+    );
+}
+
+/// Draws controls for choosing the viewport background. Loading an
+/// environment image only sets the `environment_path`, the image is
+/// loaded by the caller.
+fn draw_background_settings(
+    ui: &imgui::Ui,
+    background: &mut Background,
+    environment_path: &mut Option<String>,
+    ground_shadow: &mut bool,
+) {
+    const MODE_SOLID: usize = 0;
+    const MODE_GRADIENT: usize = 1;
+    const MODE_ENVIRONMENT: usize = 2;
+
+    let (mut mode, top, bottom) = match *background {
+        Background::Solid { color } => (MODE_SOLID, color, color),
+        Background::Gradient { top, bottom } => (MODE_GRADIENT, top, bottom),
+        Background::Environment => (
+            MODE_ENVIRONMENT,
+            DEFAULT_BACKGROUND_COLOR,
+            DEFAULT_BACKGROUND_COLOR,
+        ),
+    };
+
+    ui.radio_button(imgui::im_str!("Solid"), &mut mode, MODE_SOLID);
+    ui.radio_button(imgui::im_str!("Gradient"), &mut mode, MODE_GRADIENT);
+    if environment_path.is_some() {
+        ui.radio_button(imgui::im_str!("Environment"), &mut mode, MODE_ENVIRONMENT);
+    }
+
+    *background = match mode {
+        MODE_SOLID => Background::Solid { color: top },
+        MODE_GRADIENT => Background::Gradient { top, bottom },
+        _ => Background::Environment,
+    };
+
+    match background {
+        Background::Solid { color } => {
+            imgui::ColorEdit::new(imgui::im_str!("Color"), color).build(ui);
+        }
+        Background::Gradient { top, bottom } => {
+            imgui::ColorEdit::new(imgui::im_str!("Top"), top).build(ui);
+            imgui::ColorEdit::new(imgui::im_str!("Bottom"), bottom).build(ui);
+        }
+        Background::Environment => {
+            if let Some(path) = environment_path {
+                ui.text(imgui::im_str!("{}", path));
+            }
+        }
+    }
+
+    if ui.button(imgui::im_str!("Load HDRI..."), [0.0, 0.0]) {
+        if let Some(path) =
+            tinyfiledialogs::open_file_dialog("Open", "", Some((&["*.hdr"], "Radiance HDR (.hdr)")))
+        {
+            *environment_path = Some(path);
+            *background = Background::Environment;
+        }
+    }
+    ui.separator();
+    ui.checkbox(imgui::im_str!("Ground shadow"), ground_shadow);
+    if ui.is_item_hovered() {
+        ui.tooltip_text(
+            "Cast a shadow on the ground below the scene. Captured \
+             frames, e.g. the live view, get a transparent background, \
+             so that they can be composited onto other images",
+        );
+    }
+}
+
+/// Draws controls for the post processing effects of captured frames.
+fn draw_post_processing_settings(
+    ui: &imgui::Ui,
+    enabled: &mut bool,
+    post_processing: &mut PostProcessing,
+) {
+    ui.checkbox(imgui::im_str!("Enabled"), enabled);
+    if ui.is_item_hovered() {
+        ui.tooltip_text(
+            "Effects are only applied to captured frames, \
+             e.g. the live view, not to the viewport",
+        );
+    }
+
+    ui.checkbox(
+        imgui::im_str!("Depth of field"),
+        &mut post_processing.depth_of_field,
+    );
+    if ui
+        .input_float(
+            imgui::im_str!("Focus distance"),
+            &mut post_processing.focus_distance,
+        )
+        .build()
+    {
+        post_processing.focus_distance = post_processing.focus_distance.max(0.01);
+    }
+    imgui::Slider::new(imgui::im_str!("Blur radius"), 0.0..=32.0)
+        .build(ui, &mut post_processing.blur_radius);
+    imgui::Slider::new(imgui::im_str!("Vignette"), 0.0..=1.0)
+        .build(ui, &mut post_processing.vignette);
+    imgui::Slider::new(imgui::im_str!("Grain"), 0.0..=0.2).build(ui, &mut post_processing.grain);
+}
+
+/// Draws controls for the section plane. The plane is always
+/// perpendicular to one of the world axes and offset along it.
+fn draw_section_settings(ui: &imgui::Ui, enabled: &mut bool, section: &mut Section) {
+    const AXIS_X: usize = 0;
+    const AXIS_Y: usize = 1;
+    const AXIS_Z: usize = 2;
+
+    ui.checkbox(imgui::im_str!("Enabled"), enabled);
+
+    let mut axis = section.normal.iamax();
+    let mut flip = section.normal[axis] < 0.0;
+    let mut offset = section.origin[axis];
+
+    ui.radio_button(imgui::im_str!("X"), &mut axis, AXIS_X);
+    ui.same_line(0.0);
+    ui.radio_button(imgui::im_str!("Y"), &mut axis, AXIS_Y);
+    ui.same_line(0.0);
+    ui.radio_button(imgui::im_str!("Z"), &mut axis, AXIS_Z);
+    ui.checkbox(imgui::im_str!("Flip"), &mut flip);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Cut away the other side of the plane");
+    }
+    ui.input_float(imgui::im_str!("Offset"), &mut offset)
+        .build();
+
+    let mut normal = Vector3::zeros();
+    normal[axis] = if flip { -1.0 } else { 1.0 };
+    let mut origin = Point3::origin();
+    origin[axis] = offset;
+    section.normal = normal;
+    section.origin = origin;
+
+    ui.radio_button(
+        imgui::im_str!("Solid"),
+        &mut section.fill,
+        SectionFill::Solid,
+    );
+    ui.radio_button(
+        imgui::im_str!("Hatched"),
+        &mut section.fill,
+        SectionFill::Hatched,
+    );
+    imgui::ColorEdit::new(imgui::im_str!("Color"), &mut section.color).build(ui);
+    if section.fill == SectionFill::Hatched
+        && ui
+            .input_float(imgui::im_str!("Hatch spacing"), &mut section.hatch_spacing)
+            .build()
+    {
+        section.hatch_spacing = section.hatch_spacing.max(0.001);
+    }
+}
+
+fn draw_shading_settings(
+    ui: &imgui::Ui,
+    shading: &mut MeshShading,
+    material: &mut Material,
+    mesh_selected: bool,
+) {
+    if mesh_selected {
+        ui.text("Selected mesh");
+    } else {
+        ui.text("All meshes");
+    }
+    ui.checkbox(imgui::im_str!("Flat"), &mut shading.flat);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Shade each face with its own normal, showing the faceting of the mesh");
+    }
+    ui.separator();
+    for matcap in &Matcap::ALL {
+        ui.radio_button(&imgui::im_str!("{}", matcap), &mut shading.matcap, *matcap);
+    }
+    ui.separator();
+    imgui::ColorEdit::new(imgui::im_str!("Color"), &mut material.base_color).build(ui);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Color the mesh to tell it apart from the others");
+    }
+    imgui::Slider::new(imgui::im_str!("Roughness"), 0.0..=1.0).build(ui, &mut material.roughness);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Flatten the highlights and shadows of the matcap for a matte look");
+    }
+    imgui::Slider::new(imgui::im_str!("Opacity"), 0.0..=1.0).build(ui, &mut material.opacity);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("See through the mesh to the meshes behind it");
+    }
+}
+
+fn draw_debug_view_combo(ui: &imgui::Ui, debug_view: &mut DebugView) {
+    let names: Vec<_> = DebugView::NAMES
+        .iter()
+        .map(|name| imgui::ImString::new(*name))
+        .collect();
+    let name_refs: Vec<_> = names.iter().collect();
+    let mut index = DebugView::ALL
+        .iter()
+        .position(|view| view == debug_view)
+        .unwrap_or(0);
+    if imgui::ComboBox::new(imgui::im_str!("Debug View"))
+        .build_simple_string(ui, &mut index, &name_refs)
+    {
+        *debug_view = DebugView::ALL[index];
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Show false colors for diagnosing normals and depth");
+    }
+}
+
+fn draw_attribute_color_settings(
+    ui: &imgui::Ui,
+    enabled: &mut bool,
+    attribute_name: &mut Option<String>,
+    ramp: &mut ColorRamp,
+    attribute_names: &[String],
+) {
+    ui.checkbox(imgui::im_str!("Enabled"), enabled);
+
+    if attribute_names.is_empty() {
+        ui.text(imgui::im_str!("No vertex attributes"));
+    } else {
+        let names: Vec<_> = attribute_names
+            .iter()
+            .map(|name| imgui::ImString::new(name.as_str()))
+            .collect();
+        let name_refs: Vec<_> = names.iter().collect();
+        let mut index = attribute_name
+            .as_ref()
+            .and_then(|selected| attribute_names.iter().position(|name| name == selected))
+            .unwrap_or(0);
+        imgui::ComboBox::new(imgui::im_str!("Attribute"))
+            .build_simple_string(ui, &mut index, &name_refs);
+        *attribute_name = Some(attribute_names[index].clone());
+    }
+
+    let ramp_names: Vec<_> = ColorRamp::NAMES
+        .iter()
+        .map(|name| imgui::ImString::new(*name))
+        .collect();
+    let ramp_name_refs: Vec<_> = ramp_names.iter().collect();
+    let mut ramp_index = ColorRamp::ALL
+        .iter()
+        .position(|other| other == ramp)
+        .unwrap_or(0);
+    if imgui::ComboBox::new(imgui::im_str!("Ramp")).build_simple_string(
+        ui,
+        &mut ramp_index,
+        &ramp_name_refs,
+    ) {
+        *ramp = ColorRamp::ALL[ramp_index];
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text(
+            "The values are mapped to the ramp from the smallest to the \
+             largest value over all the meshes",
+        );
+    }
+}
+
+fn draw_msaa_combo(ui: &imgui::Ui, msaa: &mut Msaa) {
+    let names: Vec<_> = Msaa::NAMES
+        .iter()
+        .map(|name| imgui::ImString::new(*name))
+        .collect();
+    let name_refs: Vec<_> = names.iter().collect();
+    let mut index = Msaa::ALL
+        .iter()
+        .position(|level| level == msaa)
+        .unwrap_or(0);
+    if imgui::ComboBox::new(imgui::im_str!("Antialiasing"))
+        .build_simple_string(ui, &mut index, &name_refs)
+    {
+        *msaa = Msaa::ALL[index];
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text(
+            "Multisampling level of the viewport. Higher levels may not be \
+             supported by all GPUs",
+        );
+    }
+}
+
+fn draw_face_material_settings(ui: &imgui::Ui, assign_on_click: &mut bool, material: &mut u32) {
+    ui.checkbox(imgui::im_str!("Assign on click"), assign_on_click);
+    if ui.is_item_hovered() {
+        ui.tooltip_text(
+            "Click a face to assign it the material, \
+             shift+click to assign it to its connected patch",
+        );
+    }
+
+    let mut material_input = clamp_cast_u32_to_i32(*material);
+    ui.input_int(imgui::im_str!("Material"), &mut material_input)
+        .build();
+    *material = clamp_cast_i32_to_u32(material_input);
+}
+
+fn draw_capture_settings(
+    ui: &imgui::Ui,
+    capture_scale: &mut u32,
+    capture_path: &mut Option<String>,
+) {
+    imgui::Slider::new(imgui::im_str!("Scale"), 1..=4).build(ui, capture_scale);
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Save the image at a multiple of the window resolution");
+    }
+    if ui.button(imgui::im_str!("Save"), [0.0, 0.0]) {
+        if let Some(path) =
+            tinyfiledialogs::save_file_dialog_with_filter("Save", "", &["*.png"], "PNG (.png)")
+        {
+            *capture_path = Some(path);
+        }
+        ui.close_current_popup();
+    }
+}
+
+fn draw_camera_path_settings(
+    ui: &imgui::Ui,
+    camera_path: &mut CameraPath,
+    camera_path_fps: &mut u32,
+    locator_names: &[String],
+    camera_path_playing: bool,
+    camera_path_command: &mut Option<CameraPathCommand>,
+) {
+    if ui.button(imgui::im_str!("Add View"), [0.0, 0.0]) {
+        *camera_path_command = Some(CameraPathCommand::AddKeyframe);
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Add a keyframe at the current camera view");
+    }
+    if !locator_names.is_empty() {
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Add Locator..."), [0.0, 0.0]) {
+            ui.open_popup(imgui::im_str!("Camera Path Locators"));
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Add a keyframe looking at a locator along its Z axis \
+                 from the current camera distance",
+            );
+        }
+        ui.popup(imgui::im_str!("Camera Path Locators"), || {
+            for (index, name) in locator_names.iter().enumerate() {
+                let label = imgui::im_str!("{}##camera-path-locator-{}", name, index);
+                if imgui::Selectable::new(&label).build(ui) {
+                    *camera_path_command = Some(CameraPathCommand::AddKeyframeFromLocator(index));
+                }
+            }
+        });
+    }
+
+    ui.separator();
+    if camera_path.keyframes().is_empty() {
+        ui.text(imgui::im_str!("No keyframes"));
+    }
+
+    let mut removed_index = None;
+    for (index, keyframe) in camera_path.keyframes_mut().iter_mut().enumerate() {
+        ui.text(imgui::im_str!("{}", keyframe.name));
+        ui.input_float(
+            &imgui::im_str!("Time (s)##camera-path-time-{}", index),
+            &mut keyframe.time,
+        )
+        .build();
+        keyframe.time = keyframe.time.max(0.0);
+        ui.same_line(0.0);
+        if ui.button(
+            &imgui::im_str!("Remove##camera-path-remove-{}", index),
+            [0.0, 0.0],
+        ) {
+            removed_index = Some(index);
+        }
+    }
+    if let Some(index) = removed_index {
+        camera_path.keyframes_mut().remove(index);
+    }
+    // Sorted only once the times are not being edited, so that the
+    // edited keyframe doesn't jump away from under the cursor
+    if !ui.is_any_item_active() {
+        camera_path.sort_keyframes();
+    }
+
+    ui.separator();
+    imgui::Slider::new(imgui::im_str!("FPS"), 1..=60).build(ui, camera_path_fps);
+    if camera_path_playing {
+        if ui.button(imgui::im_str!("Stop"), [0.0, 0.0]) {
+            *camera_path_command = Some(CameraPathCommand::Stop);
+        }
+    } else if !camera_path.keyframes().is_empty() {
+        if ui.button(imgui::im_str!("Preview"), [0.0, 0.0]) {
+            *camera_path_command = Some(CameraPathCommand::Preview);
+        }
+        ui.same_line(0.0);
+        if ui.button(imgui::im_str!("Render..."), [0.0, 0.0]) {
+            if let Some(dir) = tinyfiledialogs::select_folder_dialog("Render", "") {
+                *camera_path_command = Some(CameraPathCommand::Render(dir));
+            }
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Save a PNG image of each frame to a directory, \
+                 at the scale of saved images",
+            );
+        }
+    }
+
+    ui.separator();
+    if ui.button(imgui::im_str!("Export..."), [0.0, 0.0]) {
+        export_camera_path(camera_path);
+    }
+    ui.same_line(0.0);
+    if ui.button(imgui::im_str!("Import..."), [0.0, 0.0]) {
+        import_camera_path(camera_path);
+    }
+}
+
+/// Asks for a path and saves the camera path to it, so that it can be
+/// imported to other projects.
+fn export_camera_path(camera_path: &CameraPath) {
+    let path = match tinyfiledialogs::save_file_dialog_with_filter(
+        "Export",
+        "",
+        &["*.json"],
+        "Camera Path (.json)",
+    ) {
+        Some(path) => path,
+        None => return,
+    };
+
+    match camera_path::save_camera_path(&path, camera_path) {
+        Ok(()) => log::info!("Exported camera path to {}", path),
+        Err(err) => log::error!("Failed to export camera path to {}: {}", path, err),
+    }
+}
+
+/// Asks for a path and replaces the camera path with the one loaded
+/// from it.
+fn import_camera_path(camera_path: &mut CameraPath) {
+    let path = match tinyfiledialogs::open_file_dialog(
+        "Import",
+        "",
+        Some((&["*.json"], "Camera Path (.json)")),
+    ) {
+        Some(path) => path,
+        None => return,
+    };
+
+    match camera_path::load_camera_path(&path) {
+        Ok(loaded_camera_path) => {
+            *camera_path = loaded_camera_path;
+            log::info!("Imported camera path {}", path);
+        }
+        Err(err) => log::error!("Failed to import camera path {}: {}", path, err),
+    }
+}
+
+/// Asks for a path and exports the meshes of a variant to USDZ.
+fn export_variant_usdz(name: &str, meshes: &[Arc<Mesh>]) {
+    let path = match tinyfiledialogs::save_file_dialog_with_filter(
+        "Save",
+        "",
+        &["*.usdz"],
+        "USDZ (.usdz)",
+    ) {
+        Some(path) => path,
+        None => return,
+    };
+
+    let usd_meshes: Vec<_> = meshes
+        .iter()
+        .enumerate()
+        .map(|(mesh_index, mesh)| exporter::UsdMesh {
+            name: format!("{} {}", name, mesh_index + 1),
+            mesh,
+            transform: Matrix4::identity(),
+        })
+        .collect();
+
+    match exporter::export_usdz(&path, &usd_meshes) {
+        Ok(()) => log::info!("Exported variant {} to {}", name, path),
+        Err(err) => log::error!("Failed to export variant {} to {}: {}", name, path, err),
+    }
+}
+
+/// Asks for a path and saves the session's pipeline to a project
+/// file.
+fn save_project(session: &Session) {
+    let path = match tinyfiledialogs::save_file_dialog_with_filter(
+        "Save",
+        "",
+        &["*.json"],
+        "Project (.json)",
+    ) {
+        Some(path) => path,
+        None => return,
+    };
+
+    match project::save_project(&path, &session.project()) {
+        Ok(()) => log::info!("Saved project to {}", path),
+        Err(err) => log::error!("Failed to save project to {}: {}", path, err),
+    }
+}
+
+/// Asks for a path and replaces the session's pipeline with the
+/// project loaded from it.
+fn open_project(session: &mut Session) {
+    let path =
+        match tinyfiledialogs::open_file_dialog("Open", "", Some((&["*.json"], "Project (.json)")))
+        {
+            Some(path) => path,
+            None => return,
+        };
+
+    match project::load_project(&path, session.function_table()) {
+        Ok(project) => {
+            session.open_project(project);
+            log::info!("Opened project {}", path);
+        }
+        Err(err) => log::error!("Failed to open project {}: {}", path, err),
+    }
+}
+
+/// Returns the initial argument for the param with the `refinement`
+/// of a newly added statement, or of a param unbound from a var.
+fn default_arg(session: &Session, refinement: &ParamRefinement) -> ast::Expr {
+    match refinement {
+        ParamRefinement::Boolean(boolean_refinement) => {
+            ast::Expr::Lit(ast::LitExpr::Boolean(boolean_refinement.default_value))
+        }
+        ParamRefinement::Int(int_param_refinement) => ast::Expr::Lit(ast::LitExpr::Int(
+            int_param_refinement.default_value.unwrap_or_default(),
+        )),
+        ParamRefinement::Uint(uint_param_refinement) => ast::Expr::Lit(ast::LitExpr::Uint(
+            uint_param_refinement.default_value.unwrap_or_default(),
+        )),
+        ParamRefinement::Enum(enum_param_refinement) => {
+            ast::Expr::Lit(ast::LitExpr::Uint(enum_param_refinement.default_value))
+        }
+        ParamRefinement::Float(float_param_refinement) => ast::Expr::Lit(ast::LitExpr::Float(
+            float_param_refinement.default_value.unwrap_or_default(),
+        )),
+        ParamRefinement::Float2(float2_param_refinement) => ast::Expr::Lit(ast::LitExpr::Float2([
+            float2_param_refinement.default_value_x.unwrap_or_default(),
+            float2_param_refinement.default_value_y.unwrap_or_default(),
+        ])),
+        ParamRefinement::Float3(float3_param_refinement) => ast::Expr::Lit(ast::LitExpr::Float3([
+            float3_param_refinement.default_value_x.unwrap_or_default(),
+            float3_param_refinement.default_value_y.unwrap_or_default(),
+            float3_param_refinement.default_value_z.unwrap_or_default(),
+        ])),
+        ParamRefinement::String(string_param_refinement) => {
+            let initial_value = String::from(string_param_refinement.default_value);
+            ast::Expr::Lit(ast::LitExpr::String(Arc::new(initial_value)))
+        }
+        ParamRefinement::Mesh => last_visible_var_arg(session, Ty::Mesh),
+        ParamRefinement::MeshArray => last_visible_var_arg(session, Ty::MeshArray),
+        ParamRefinement::Locator => last_visible_var_arg(session, Ty::Locator),
+        ParamRefinement::Curve => last_visible_var_arg(session, Ty::Curve),
+        ParamRefinement::EmbeddedMesh | ParamRefinement::EmbeddedCurve => {
+            ast::Expr::Lit(ast::LitExpr::Nil)
+        }
+    }
+}
+
+/// Returns whether params with the `refinement` can read the values
+/// of `Uint` vars, such as the index of a repeated iteration, instead
+/// of literals.
+fn accepts_uint_var(refinement: &ParamRefinement) -> bool {
+    match refinement {
+        ParamRefinement::Uint(_) | ParamRefinement::Float(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns an argument referring to the last variable of the type
+/// visible at the end of the session's program, or nil if there is
+/// none.
+fn last_visible_var_arg(session: &Session, ty: Ty) -> ast::Expr {
+    let one_past_last_stmt = session.stmts().len();
+    match session.visible_vars_at_stmt(one_past_last_stmt, ty).last() {
+        Some(last) => ast::Expr::Var(ast::VarExpr::new(last)),
+        None => ast::Expr::Lit(ast::LitExpr::Nil),
+    }
+}
+
+/// Adds our fonts rasterized for the `hidpi_factor` to the font atlas
+/// and scales them back to logical pixels.
+fn add_fonts(imgui_context: &mut imgui::Context, hidpi_factor: f64) -> FontIds {
+    let font_size = (15.0 * hidpi_factor) as f32;
+
+    let regular_font_id = imgui_context
+        .fonts()
+        .add_font(&[imgui::FontSource::TtfData {
+            data: OPENSANS_REGULAR_BYTES,
+            size_pixels: font_size,
+            config: None,
+        }]);
+    let bold_font_id = imgui_context
+        .fonts()
+        .add_font(&[imgui::FontSource::TtfData {
+            data: OPENSANS_BOLD_BYTES,
+            size_pixels: font_size,
+            config: None,
+        }]);
+
+    imgui_context.io_mut().font_global_scale = (1.0 / hidpi_factor) as f32;
+
+    FontIds {
+        regular: regular_font_id,
+        bold: bold_font_id,
+    }
+}
+
 fn push_disabled_style(ui: &imgui::Ui) -> (imgui::ColorStackToken, imgui::StyleStackToken) {
     let button_color = ui.style_color(imgui::StyleColor::Button);
     let text_color = ui.style_color(imgui::StyleColor::TextDisabled);
@@ -952,27 +4216,154 @@ fn push_disabled_style(ui: &imgui::Ui) -> (imgui::ColorStackToken, imgui::StyleS
     (color_token, style_token)
 }
 
+/// Draws the manual update toggle of an operation and, if the manual
+/// update is enabled, a button to update the operation. Records the
+/// interactions in `manual_update_change` and `update_clicked`.
+fn manual_update_input(
+    ui: &imgui::Ui,
+    stmt_index: usize,
+    var_ident: ast::VarIdent,
+    manual_update: bool,
+    manual_update_change: &mut Option<(ast::VarIdent, bool)>,
+    update_clicked: &mut Option<ast::VarIdent>,
+) {
+    let mut manual_update_value = manual_update;
+    if ui.checkbox(
+        &imgui::im_str!("Manual update##manual-{}", stmt_index),
+        &mut manual_update_value,
+    ) {
+        *manual_update_change = Some((var_ident, manual_update_value));
+    }
+
+    if manual_update {
+        ui.same_line(0.0);
+        if ui.button(&imgui::im_str!("Update##update-{}", stmt_index), [0.0, 0.0]) {
+            *update_clicked = Some(var_ident);
+        }
+    }
+}
+
+/// Opens a popup listing the project constants and the
+/// `bindable_vars`, when the last drawn param input is right-clicked.
+/// Records the param being bound to the picked constant, or unbound if
+/// it is `bound` already, in `constant_binding_change`. Binding to a
+/// picked var is recorded as a `change` of the argument.
+#[allow(clippy::too_many_arguments)]
+fn constant_binding_popup(
+    ui: &imgui::Ui,
+    session: &Session,
+    stmt_index: usize,
+    arg_index: usize,
+    bound: bool,
+    bindable_vars: &[ast::VarIdent],
+    constant_binding_change: &mut Option<(usize, usize, Option<usize>)>,
+    change: &mut Option<(usize, usize, ast::Expr)>,
+) {
+    let popup_id = imgui::im_str!("Constants##constants-{}-{}", stmt_index, arg_index);
+    if ui.is_item_clicked(imgui::MouseButton::Right) {
+        ui.open_popup(&popup_id);
+    }
+
+    ui.popup(&popup_id, || {
+        if session.constants().is_empty() {
+            ui.text(imgui::im_str!("No constants defined"));
+        }
+
+        for (constant_index, constant) in session.constants().iter().enumerate() {
+            let label = imgui::im_str!("Bind to {}##bind-{}", constant.name, constant_index);
+            if imgui::Selectable::new(&label).build(ui) {
+                *constant_binding_change = Some((stmt_index, arg_index, Some(constant_index)));
+            }
+        }
+
+        for var_ident in bindable_vars {
+            let var_name = session
+                .var_name_for_ident(*var_ident)
+                .expect("Failed to find name for ident");
+            let label = imgui::im_str!(
+                "Bind to {} #{}##bind-var-{}",
+                var_name,
+                var_ident.0 + 1,
+                var_ident.0,
+            );
+            if imgui::Selectable::new(&label).build(ui) {
+                *change = Some((
+                    stmt_index,
+                    arg_index,
+                    ast::Expr::Var(ast::VarExpr::new(*var_ident)),
+                ));
+            }
+        }
+
+        if bound && imgui::Selectable::new(imgui::im_str!("Unbind")).build(ui) {
+            *constant_binding_change = Some((stmt_index, arg_index, None));
+        }
+    });
+}
+
+/// Opens a popup for unbinding the param from its var, when the last
+/// drawn param text is right-clicked. Records the param being reset to
+/// its default literal as a `change` of the argument.
+fn var_binding_popup(
+    ui: &imgui::Ui,
+    session: &Session,
+    stmt_index: usize,
+    arg_index: usize,
+    refinement: &ParamRefinement,
+    change: &mut Option<(usize, usize, ast::Expr)>,
+) {
+    let popup_id = imgui::im_str!("Var##var-{}-{}", stmt_index, arg_index);
+    if ui.is_item_clicked(imgui::MouseButton::Right) {
+        ui.open_popup(&popup_id);
+    }
+
+    ui.popup(&popup_id, || {
+        if imgui::Selectable::new(imgui::im_str!("Unbind")).build(ui) {
+            *change = Some((stmt_index, arg_index, default_arg(session, refinement)));
+        }
+    });
+}
+
 fn file_input(
     ui: &imgui::Ui,
     label: &imgui::ImStr,
+    save: bool,
     file_ext_filter: Option<(&[&str], &str)>,
     buffer: &mut imgui::ImString,
 ) -> bool {
     use std::env;
     use std::path::Path;
 
-    let open_button_label = imgui::im_str!("Open##{}", label);
-    let open_button_width = ui.calc_text_size(&open_button_label, true, 50.0)[0] + 8.0;
-    let input_position = open_button_width + 2.0; // Padding
+    let button_label = if save {
+        imgui::im_str!("Save##{}", label)
+    } else {
+        imgui::im_str!("Open##{}", label)
+    };
+    let button_width = ui.calc_text_size(&button_label, true, 50.0)[0] + 8.0;
+    let input_position = button_width + 2.0; // Padding
 
     let mut changed = false;
 
     let group_token = ui.begin_group();
 
-    if ui.button(&open_button_label, [open_button_width, 0.0]) {
-        if let Some(absolute_path_string) =
+    if ui.button(&button_label, [button_width, 0.0]) {
+        let absolute_path_string = if save {
+            match file_ext_filter {
+                Some((filter_patterns, description)) => {
+                    tinyfiledialogs::save_file_dialog_with_filter(
+                        "Save",
+                        "",
+                        filter_patterns,
+                        description,
+                    )
+                }
+                None => tinyfiledialogs::save_file_dialog("Save", ""),
+            }
+        } else {
             tinyfiledialogs::open_file_dialog("Open", "", file_ext_filter)
-        {
+        };
+
+        if let Some(absolute_path_string) = absolute_path_string {
             buffer.clear();
 
             let current_dir = env::current_dir().expect("Couldn't get current dir");
@@ -1000,3 +4391,111 @@ fn file_input(
 
     changed
 }
+
+/// Draws a summary of the embedded mesh `lit` together with a button
+/// for embedding a mesh from an OBJ file. Returns the newly embedded
+/// mesh, if any. The OBJ file is expected to be in the `unit`.
+fn embedded_mesh_input(
+    ui: &imgui::Ui,
+    label: &imgui::ImStr,
+    name: &str,
+    lit: &ast::LitExpr,
+    unit: LengthUnit,
+) -> Option<Mesh> {
+    let embed_button_label = imgui::im_str!("Embed OBJ##{}", label);
+    let embed_button_width = ui.calc_text_size(&embed_button_label, true, 50.0)[0] + 8.0;
+    let text_position = embed_button_width + 8.0; // Padding
+
+    let mut embedded_mesh = None;
+
+    let group_token = ui.begin_group();
+
+    if ui.button(&embed_button_label, [embed_button_width, 0.0]) {
+        if let Some(path) = tinyfiledialogs::open_file_dialog(
+            "Embed",
+            "",
+            Some((&["*.obj", "*.OBJ"], "Wavefront (.obj)")),
+        ) {
+            match importer::import_obj_joined(&path) {
+                Ok(mesh) => {
+                    embedded_mesh = Some(tools::scale_mesh(&mesh, unit.value_to_meters(1.0)))
+                }
+                Err(err) => log::error!("Failed to embed mesh from {}: {}", path, err),
+            }
+        }
+    }
+
+    ui.same_line(text_position);
+    match lit {
+        ast::LitExpr::Mesh(mesh) => ui.text(imgui::im_str!(
+            "{} ({} vertices, {} faces)",
+            name,
+            mesh.vertices().len(),
+            mesh.faces().len(),
+        )),
+        _ => ui.text(imgui::im_str!("{} (empty)", name)),
+    }
+
+    group_token.end(ui);
+
+    embedded_mesh
+}
+
+fn embedded_curve_input(
+    ui: &imgui::Ui,
+    label: &imgui::ImStr,
+    name: &str,
+    lit: &ast::LitExpr,
+    unit: LengthUnit,
+) -> Option<Curve> {
+    let embed_button_label = imgui::im_str!("Embed OBJ##{}", label);
+    let embed_button_width = ui.calc_text_size(&embed_button_label, true, 50.0)[0] + 8.0;
+    let text_position = embed_button_width + 8.0; // Padding
+
+    let mut embedded_curve = None;
+
+    let group_token = ui.begin_group();
+
+    if ui.button(&embed_button_label, [embed_button_width, 0.0]) {
+        if let Some(path) = tinyfiledialogs::open_file_dialog(
+            "Embed",
+            "",
+            Some((&["*.obj", "*.OBJ"], "Wavefront (.obj)")),
+        ) {
+            match importer::import_obj_polylines(&path) {
+                Ok(polylines) => {
+                    let scale = unit.value_to_meters(1.0);
+                    let scaled_polylines = polylines
+                        .iter()
+                        .map(|polyline| {
+                            let vertices = polyline
+                                .vertices()
+                                .iter()
+                                .map(|vertex| vertex * scale)
+                                .collect();
+                            Polyline::new(vertices, polyline.is_closed())
+                        })
+                        .collect();
+
+                    embedded_curve = Some(Curve::new(scaled_polylines));
+                }
+                Err(err) => log::error!("Failed to embed curve from {}: {}", path, err),
+            }
+        }
+    }
+
+    ui.same_line(text_position);
+    match lit {
+        ast::LitExpr::Curve(curve) => ui.text(imgui::im_str!(
+            "{} ({} polylines, {} vertices)",
+            name,
+            curve.polylines().len(),
+            curve.vertex_count(),
+        )),
+        _ => ui.text(imgui::im_str!("{} (empty)", name)),
+    }
+
+    group_token.end(ui);
+
+    embedded_curve
+}
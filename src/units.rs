@@ -0,0 +1,168 @@
+#[cfg(any(test, feature = "gui"))]
+use std::fmt;
+
+const METERS_PER_FOOT: f32 = 0.3048;
+#[cfg(any(test, feature = "gui"))]
+const INCHES_PER_FOOT: f32 = 12.0;
+
+/// A unit of length used to display and enter lengths in the project.
+///
+/// Geometry is always stored and computed in meters, units only affect
+/// what the user sees and types, and how files without their own unit
+/// information are scaled on import and export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LengthUnit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    /// Displayed as feet and inches, entered as decimal feet.
+    FootInch,
+}
+
+impl LengthUnit {
+    #[cfg(feature = "gui")]
+    pub const ALL: [LengthUnit; 4] = [
+        LengthUnit::Millimeter,
+        LengthUnit::Centimeter,
+        LengthUnit::Meter,
+        LengthUnit::FootInch,
+    ];
+
+    #[cfg(feature = "gui")]
+    pub fn name(self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "Millimeters",
+            LengthUnit::Centimeter => "Centimeters",
+            LengthUnit::Meter => "Meters",
+            LengthUnit::FootInch => "Feet and inches",
+        }
+    }
+
+    /// The symbol of the unit values are entered in.
+    #[cfg(any(test, feature = "gui"))]
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::FootInch => "ft",
+        }
+    }
+
+    /// The length of one unit in meters.
+    pub fn meters_per_unit(self) -> f32 {
+        match self {
+            LengthUnit::Millimeter => 0.001,
+            LengthUnit::Centimeter => 0.01,
+            LengthUnit::Meter => 1.0,
+            LengthUnit::FootInch => METERS_PER_FOOT,
+        }
+    }
+
+    /// Converts a length in meters to the value entered in this unit.
+    pub fn meters_to_value(self, meters: f32) -> f32 {
+        meters / self.meters_per_unit()
+    }
+
+    /// Converts a value entered in this unit to a length in meters.
+    #[cfg(any(test, feature = "gui"))]
+    pub fn value_to_meters(self, value: f32) -> f32 {
+        value * self.meters_per_unit()
+    }
+
+    /// Returns a displayable length, e.g. `1.250 m` or `4' 1.2"`.
+    #[cfg(any(test, feature = "gui"))]
+    pub fn length(self, meters: f32) -> Length {
+        Length { unit: self, meters }
+    }
+
+    /// Returns a displayable area, e.g. `1.250 m²`.
+    #[cfg(feature = "gui")]
+    pub fn area(self, square_meters: f32) -> Measure {
+        Measure {
+            value: square_meters / self.meters_per_unit().powi(2),
+            symbol: self.symbol(),
+            exponent: "²",
+        }
+    }
+
+    /// Returns a displayable volume, e.g. `1.250 m³`.
+    #[cfg(any(test, feature = "gui"))]
+    pub fn volume(self, cubic_meters: f32) -> Measure {
+        Measure {
+            value: cubic_meters / self.meters_per_unit().powi(3),
+            symbol: self.symbol(),
+            exponent: "³",
+        }
+    }
+}
+
+impl Default for LengthUnit {
+    fn default() -> Self {
+        LengthUnit::Meter
+    }
+}
+
+/// A length formatted in a unit for display.
+#[cfg(any(test, feature = "gui"))]
+#[derive(Debug, Clone, Copy)]
+pub struct Length {
+    unit: LengthUnit,
+    meters: f32,
+}
+
+#[cfg(any(test, feature = "gui"))]
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.unit {
+            LengthUnit::FootInch => {
+                let sign = if self.meters < 0.0 { "-" } else { "" };
+                let total_inches = self.unit.meters_to_value(self.meters.abs()) * INCHES_PER_FOOT;
+                let feet = (total_inches / INCHES_PER_FOOT).floor();
+                let inches = total_inches - feet * INCHES_PER_FOOT;
+                write!(f, "{}{}' {:.1}\"", sign, feet, inches)
+            }
+            _ => write!(
+                f,
+                "{:.3} {}",
+                self.unit.meters_to_value(self.meters),
+                self.unit.symbol(),
+            ),
+        }
+    }
+}
+
+/// An area or a volume formatted in a unit for display.
+#[cfg(any(test, feature = "gui"))]
+#[derive(Debug, Clone, Copy)]
+pub struct Measure {
+    value: f32,
+    symbol: &'static str,
+    exponent: &'static str,
+}
+
+#[cfg(any(test, feature = "gui"))]
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.3} {}{}", self.value, self.symbol, self.exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_unit_converts_values_and_meters() {
+        assert!((LengthUnit::Millimeter.meters_to_value(1.5) - 1500.0).abs() < 0.001);
+        assert!((LengthUnit::Centimeter.value_to_meters(250.0) - 2.5).abs() < 0.0001);
+        assert!((LengthUnit::FootInch.value_to_meters(10.0) - 3.048).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_length_unit_displays_lengths() {
+        assert_eq!(LengthUnit::Meter.length(1.25).to_string(), "1.250 m");
+        assert_eq!(LengthUnit::FootInch.length(1.2446).to_string(), "4' 1.0\"");
+        assert_eq!(LengthUnit::Meter.volume(2.5).to_string(), "2.500 m³");
+    }
+}
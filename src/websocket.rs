@@ -0,0 +1,319 @@
+use std::io::{self, BufRead, Read, Write};
+
+/// The GUID appended to the client's key when computing the accept
+/// key of the handshake.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Messages larger than this are refused to protect the server from
+/// malicious clients.
+const MAX_MESSAGE_SIZE: u64 = 1024 * 1024;
+
+/// Request lines and headers longer than this are refused, so that
+/// clients can't make the server buffer unbounded amounts of data.
+const MAX_HTTP_LINE_LENGTH: u64 = 8 * 1024;
+
+/// Requests with more headers than this are refused.
+const MAX_HTTP_HEADER_COUNT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl Opcode {
+    fn from_u8(opcode: u8) -> Option<Self> {
+        match opcode {
+            0x0 => Some(Opcode::Continuation),
+            0x1 => Some(Opcode::Text),
+            0x2 => Some(Opcode::Binary),
+            0x8 => Some(Opcode::Close),
+            0x9 => Some(Opcode::Ping),
+            0xa => Some(Opcode::Pong),
+            _ => None,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Opcode::Continuation => 0x0,
+            Opcode::Text => 0x1,
+            Opcode::Binary => 0x2,
+            Opcode::Close => 0x8,
+            Opcode::Ping => 0x9,
+            Opcode::Pong => 0xa,
+        }
+    }
+}
+
+/// An HTTP request header relevant for the WebSocket handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub path: String,
+    /// The value of the `Host` header.
+    pub host: Option<String>,
+    /// The value of the `Origin` header, sent by browsers with the
+    /// origin of the page opening the WebSocket.
+    pub origin: Option<String>,
+    /// The value of the `Sec-WebSocket-Key` header, if the request
+    /// asks to upgrade the connection to a WebSocket.
+    pub websocket_key: Option<String>,
+}
+
+/// Reads the request line and headers of an HTTP request. Refuses
+/// requests with overly long lines or too many headers.
+pub fn read_http_request<R: BufRead>(reader: &mut R) -> io::Result<HttpRequest> {
+    let request_line = read_http_line(reader)?;
+
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid HTTP request"))?
+        .to_string();
+
+    let mut upgrade = false;
+    let mut host = None;
+    let mut origin = None;
+    let mut websocket_key = None;
+    let mut header_count = 0;
+    loop {
+        let header = read_http_line(reader)?;
+        if header.trim().is_empty() {
+            break;
+        }
+
+        header_count += 1;
+        if header_count > MAX_HTTP_HEADER_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Too many HTTP headers",
+            ));
+        }
+
+        if let Some(colon_index) = header.find(':') {
+            let (name, value) = header.split_at(colon_index);
+            let value = value[1..].trim();
+            if name.eq_ignore_ascii_case("upgrade") {
+                upgrade = value.eq_ignore_ascii_case("websocket");
+            } else if name.eq_ignore_ascii_case("host") {
+                host = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("sec-websocket-key") {
+                websocket_key = Some(value.to_string());
+            }
+        }
+    }
+
+    Ok(HttpRequest {
+        path,
+        host,
+        origin,
+        websocket_key: if upgrade { websocket_key } else { None },
+    })
+}
+
+/// Reads a single line of at most `MAX_HTTP_LINE_LENGTH` bytes. An
+/// empty string means the end of the stream.
+fn read_http_line<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    let len = reader
+        .by_ref()
+        .take(MAX_HTTP_LINE_LENGTH)
+        .read_line(&mut line)?;
+
+    if len as u64 == MAX_HTTP_LINE_LENGTH && !line.ends_with('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "HTTP request line or header too long",
+        ));
+    }
+
+    Ok(line)
+}
+
+/// Writes the response accepting the WebSocket handshake for the
+/// client's `websocket_key`.
+pub fn write_handshake_response<W: Write>(writer: &mut W, websocket_key: &str) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(websocket_key),
+    )?;
+    writer.flush()
+}
+
+/// Writes a single unmasked, unfragmented message, as sent by servers.
+pub fn write_message<W: Write>(writer: &mut W, opcode: Opcode, payload: &[u8]) -> io::Result<()> {
+    const FIN: u8 = 0x80;
+
+    writer.write_all(&[FIN | opcode.to_u8()])?;
+
+    let len = payload.len();
+    if len < 126 {
+        writer.write_all(&[len as u8])?;
+    } else if len <= usize::from(u16::max_value()) {
+        writer.write_all(&[126])?;
+        writer.write_all(&(len as u16).to_be_bytes())?;
+    } else {
+        writer.write_all(&[127])?;
+        writer.write_all(&(len as u64).to_be_bytes())?;
+    }
+
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Reads a single frame of a message. Unmasks the payload, if the
+/// frame is masked, as it is always the case for frames sent by
+/// clients.
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<(Opcode, Vec<u8>)> {
+    let mut header = [0; 2];
+    reader.read_exact(&mut header)?;
+
+    let opcode = Opcode::from_u8(header[0] & 0x0f)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Unknown WebSocket opcode"))?;
+    let masked = header[1] & 0x80 != 0;
+
+    let len = match header[1] & 0x7f {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u64::from(u16::from_be_bytes(len))
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => u64::from(len),
+    };
+
+    if len > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "WebSocket message too large",
+        ));
+    }
+
+    let mut mask = [0; 4];
+    if masked {
+        reader.read_exact(&mut mask)?;
+    }
+
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+
+    Ok((opcode, payload))
+}
+
+/// Computes the `Sec-WebSocket-Accept` key for the client's
+/// `Sec-WebSocket-Key`.
+fn accept_key(websocket_key: &str) -> String {
+    let mut key = websocket_key.trim().to_string();
+    key.push_str(HANDSHAKE_GUID);
+
+    base64::encode(&sha1::Sha1::from(key).digest().bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // The example from RFC 6455
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=",
+        );
+    }
+
+    #[test]
+    fn test_read_http_request_websocket_upgrade() {
+        let mut reader = Cursor::new(
+            "GET /live HTTP/1.1\r\n\
+             Host: localhost\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        );
+
+        let request = read_http_request(&mut reader).expect("Failed to read request");
+        assert_eq!(request.path, "/live");
+        assert_eq!(request.host.as_ref().map(String::as_str), Some("localhost"));
+        assert_eq!(request.origin, None);
+        assert_eq!(
+            request.websocket_key.as_ref().map(String::as_str),
+            Some("dGhlIHNhbXBsZSBub25jZQ=="),
+        );
+
+        let mut reader = Cursor::new("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        let request = read_http_request(&mut reader).expect("Failed to read request");
+        assert_eq!(request.path, "/");
+        assert_eq!(request.websocket_key, None);
+    }
+
+    #[test]
+    fn test_read_http_request_refuses_long_lines_and_many_headers() {
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nCookie: {}\r\n\r\n",
+            "a".repeat(MAX_HTTP_LINE_LENGTH as usize),
+        );
+        assert!(read_http_request(&mut Cursor::new(long_header)).is_err());
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Header: value\r\n".repeat(MAX_HTTP_HEADER_COUNT + 1),
+        );
+        assert!(read_http_request(&mut Cursor::new(many_headers)).is_err());
+
+        let enough_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Header: value\r\n".repeat(MAX_HTTP_HEADER_COUNT),
+        );
+        assert!(read_http_request(&mut Cursor::new(enough_headers)).is_ok());
+    }
+
+    #[test]
+    fn test_read_frame_unmasks_client_frame() {
+        // A masked "Hello" text frame from RFC 6455
+        let mut reader = Cursor::new(vec![
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ]);
+
+        let (opcode, payload) = read_frame(&mut reader).expect("Failed to read frame");
+        assert_eq!(opcode, Opcode::Text);
+        assert_eq!(payload, b"Hello");
+    }
+
+    #[test]
+    fn test_write_message_with_extended_length() {
+        let payload = vec![7; 300];
+
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, Opcode::Binary, &payload).expect("Failed to write message");
+
+        assert_eq!(&buffer[..4], &[0x82, 126, 0x01, 0x2c]);
+        assert_eq!(&buffer[4..], &payload[..]);
+
+        let (opcode, read_payload) =
+            read_frame(&mut Cursor::new(buffer)).expect("Failed to read frame");
+        assert_eq!(opcode, Opcode::Binary);
+        assert_eq!(read_payload, payload);
+    }
+}
@@ -7,7 +7,9 @@ fn import_obj(path: &str) -> Vec<importer::Model> {
     let (tobj_models, _) =
         importer::obj_buf_into_tobj(&mut file_contents.as_slice()).expect("Obj should be parsed");
 
-    importer::tobj_to_internal(tobj_models)
+    let face_sizes = importer::decode_obj_face_sizes(&file_contents);
+
+    importer::tobj_to_internal_with_face_sizes(tobj_models, &face_sizes)
 }
 
 #[test]